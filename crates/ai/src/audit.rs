@@ -0,0 +1,174 @@
+//! Append-only audit log of MCP tool calls.
+//!
+//! Every `tools/call` handled by the [`McpServer`](crate::McpServer) is
+//! written as one JSON line: when it ran, which tool, its arguments with
+//! secrets redacted, whether it succeeded and how long it took. Lines are
+//! only ever appended, so the file doubles as a record for compliance
+//! reviews and for debugging what an agent actually did.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+/// File name of the audit log inside the storage directory.
+pub const DEFAULT_AUDIT_FILE: &str = "audit.jsonl";
+
+/// Replacement for redacted argument values.
+const REDACTED: &str = "[REDACTED]";
+
+/// Argument names whose values are never written to the log, matched
+/// case-insensitively as substrings.
+const SECRET_KEYS: &[&str] = &["password", "secret", "token", "api_key", "apikey", "authorization", "credential"];
+
+/// Whether a tool call succeeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditStatus {
+    /// The tool returned a result
+    Success,
+    /// The tool returned an error
+    Error,
+}
+
+/// One logged tool call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// When the call started
+    pub timestamp: devman_core::Time,
+    /// Tool name
+    pub tool: String,
+    /// Arguments, with secret values replaced
+    pub arguments: serde_json::Value,
+    /// Outcome
+    pub status: AuditStatus,
+    /// Error code, for failed calls
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<i64>,
+    /// Time taken, in milliseconds
+    pub duration_ms: u64,
+}
+
+/// Writer appending [`AuditEntry`] lines to a JSONL file.
+pub struct AuditLog {
+    path: PathBuf,
+    // Keeps concurrent calls from interleaving partial lines
+    write_lock: tokio::sync::Mutex<()>,
+}
+
+impl AuditLog {
+    /// Log to `path`; the file is created on the first entry.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), write_lock: tokio::sync::Mutex::new(()) }
+    }
+
+    /// File the log is written to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append `entry` as one line.
+    pub async fn append(&self, entry: &AuditEntry) -> std::io::Result<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+
+        let _guard = self.write_lock.lock().await;
+        if let Some(dir) = self.path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await
+    }
+}
+
+/// The last `limit` entries of the log at `path`, oldest first.
+///
+/// A missing file is an empty log. Lines that do not parse, such as one cut
+/// short by a crash, are skipped.
+pub async fn read_tail(path: &Path, limit: usize) -> std::io::Result<Vec<AuditEntry>> {
+    let content = match tokio::fs::read_to_string(path).await {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let entries: Vec<AuditEntry> = content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    let skip = entries.len().saturating_sub(limit);
+    Ok(entries.into_iter().skip(skip).collect())
+}
+
+/// Copy of `arguments` with the values of secret-looking keys replaced, at
+/// any depth.
+pub fn redact(arguments: &serde_json::Value) -> serde_json::Value {
+    match arguments {
+        serde_json::Value::Object(map) => map
+            .iter()
+            .map(|(key, value)| {
+                let lower = key.to_lowercase();
+                let value = if SECRET_KEYS.iter().any(|secret| lower.contains(secret)) {
+                    serde_json::Value::String(REDACTED.to_string())
+                } else {
+                    redact(value)
+                };
+                (key.clone(), value)
+            })
+            .collect(),
+        serde_json::Value::Array(items) => items.iter().map(redact).collect(),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redact_hides_secrets_at_any_depth() {
+        let arguments = json!({
+            "title": "Deploy",
+            "api_key": "sk-123",
+            "env": [{"name": "DB", "Password": "hunter2"}],
+            "auth": {"Authorization": "Bearer abc", "user": "ci"}
+        });
+        assert_eq!(
+            redact(&arguments),
+            json!({
+                "title": "Deploy",
+                "api_key": REDACTED,
+                "env": [{"name": "DB", "Password": REDACTED}],
+                "auth": {"Authorization": REDACTED, "user": "ci"}
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tail_skips_damaged_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = AuditLog::new(dir.path().join("nested").join(DEFAULT_AUDIT_FILE));
+        assert!(read_tail(log.path(), 10).await.unwrap().is_empty());
+
+        for tool in ["a", "b", "c"] {
+            let entry = AuditEntry {
+                timestamp: chrono::Utc::now(),
+                tool: tool.to_string(),
+                arguments: json!({}),
+                status: AuditStatus::Success,
+                error_code: None,
+                duration_ms: 1,
+            };
+            log.append(&entry).await.unwrap();
+        }
+        let mut file = tokio::fs::OpenOptions::new().append(true).open(log.path()).await.unwrap();
+        file.write_all(b"{\"tool\": \"trunc").await.unwrap();
+
+        let tools: Vec<_> = read_tail(log.path(), 2).await.unwrap().into_iter().map(|e| e.tool).collect();
+        assert_eq!(tools, ["b", "c"]);
+    }
+}
@@ -0,0 +1,274 @@
+//! Failure classification for quality findings.
+//!
+//! After a quality check fails, the findings tell whether the work only
+//! needs fixing (a compile error, a failing test, a style nit) or whether
+//! its approach is wrong and it should be redone. The classifier sorts each
+//! finding into a [`FailureKind`] by configurable rules and recommends a
+//! [`QualityDecision`] from the kinds found.
+
+use std::collections::BTreeMap;
+
+use devman_core::{Finding, QualityCategory, QualityCheckResult};
+use serde::{Deserialize, Serialize};
+
+use crate::interactive::QualityDecision;
+
+/// What kind of problem a finding points at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    /// The code does not build
+    Compile,
+    /// A test or assertion failed
+    Test,
+    /// Lint, formatting or documentation nits
+    Style,
+    /// The approach itself is wrong, such as a design or requirement issue
+    Design,
+    /// Matched no rule
+    Other,
+}
+
+impl FailureKind {
+    /// Name of the kind, as serialized.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FailureKind::Compile => "compile",
+            FailureKind::Test => "test",
+            FailureKind::Style => "style",
+            FailureKind::Design => "design",
+            FailureKind::Other => "other",
+        }
+    }
+}
+
+/// A rule sorting findings into a [`FailureKind`].
+///
+/// A finding matches when its category is one of `categories` (or
+/// `categories` is empty) and its message contains one of `keywords`,
+/// case-insensitively (or `keywords` is empty).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailureRule {
+    /// Kind given to matching findings
+    pub kind: FailureKind,
+    /// Finding categories the rule applies to
+    #[serde(default)]
+    pub categories: Vec<QualityCategory>,
+    /// Message fragments the rule looks for
+    #[serde(default)]
+    pub keywords: Vec<String>,
+}
+
+impl FailureRule {
+    fn new(kind: FailureKind, categories: &[QualityCategory], keywords: &[&str]) -> Self {
+        Self {
+            kind,
+            categories: categories.to_vec(),
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+        }
+    }
+
+    /// Whether `finding` matches this rule.
+    pub fn matches(&self, finding: &Finding) -> bool {
+        let message = finding.message.to_lowercase();
+        (self.categories.is_empty() || self.categories.contains(&finding.category))
+            && (self.keywords.is_empty() || self.keywords.iter().any(|k| message.contains(&k.to_lowercase())))
+    }
+}
+
+/// Sorts findings into failure kinds and recommends what to do next.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailureClassifier {
+    /// Rules tried in order; a finding takes the kind of the first match
+    pub rules: Vec<FailureRule>,
+    /// Kinds that call for redoing the work rather than fixing it
+    pub redo_kinds: Vec<FailureKind>,
+}
+
+impl Default for FailureClassifier {
+    fn default() -> Self {
+        use QualityCategory::*;
+        Self {
+            rules: vec![
+                FailureRule::new(
+                    FailureKind::Compile,
+                    &[],
+                    &["error[e", "failed to compile", "could not compile", "cannot find", "mismatched types", "syntax error", "unresolved import"],
+                ),
+                FailureRule::new(
+                    FailureKind::Design,
+                    &[],
+                    &["design", "architecture", "wrong approach", "requirement", "does not meet"],
+                ),
+                FailureRule::new(FailureKind::Design, &[Business, Compliance], &[]),
+                FailureRule::new(FailureKind::Test, &[Testing], &[]),
+                FailureRule::new(FailureKind::Test, &[], &["test failed", "tests failed", "assertion", "panicked"]),
+                FailureRule::new(FailureKind::Style, &[Maintainability, Documentation], &[]),
+                FailureRule::new(FailureKind::Style, &[], &["clippy", "rustfmt", "format", "lint", "style"]),
+            ],
+            redo_kinds: vec![FailureKind::Design],
+        }
+    }
+}
+
+/// The failure kinds found in a set of check results and the decision they
+/// suggest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailureClassification {
+    /// Number of findings of each kind, from failed checks only
+    pub kinds: BTreeMap<FailureKind, usize>,
+    /// Recommended next step
+    pub decision: QualityDecision,
+    /// Why the decision was recommended
+    pub reason: String,
+}
+
+impl FailureClassifier {
+    /// Kind of a single finding.
+    pub fn classify_finding(&self, finding: &Finding) -> FailureKind {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(finding))
+            .map_or(FailureKind::Other, |rule| rule.kind)
+    }
+
+    /// Classify the findings of failed checks and recommend a decision.
+    ///
+    /// Passing results are accepted whatever they report. Otherwise any
+    /// finding of a redo kind recommends redoing the execution, and
+    /// everything else, compile and test failures included, recommends
+    /// fixing the issues and continuing.
+    pub fn classify(&self, results: &[QualityCheckResult]) -> FailureClassification {
+        let failed: Vec<&QualityCheckResult> = results.iter().filter(|r| !r.passed).collect();
+        if failed.is_empty() {
+            return FailureClassification {
+                kinds: BTreeMap::new(),
+                decision: QualityDecision::AcceptAndComplete,
+                reason: "All quality checks passed".to_string(),
+            };
+        }
+
+        let mut kinds = BTreeMap::new();
+        for check in &failed {
+            if check.findings.is_empty() {
+                *kinds.entry(FailureKind::Other).or_insert(0) += 1;
+            }
+            for finding in &check.findings {
+                *kinds.entry(self.classify_finding(finding)).or_insert(0) += 1;
+            }
+        }
+
+        let (decision, reason) = if !kinds.keys().any(|k| self.redo_kinds.contains(k)) {
+            (
+                QualityDecision::FixIssuesAndContinue,
+                format!("{} failed check(s) with fixable findings: {}", failed.len(), describe(&kinds)),
+            )
+        } else {
+            (
+                QualityDecision::RedoExecution,
+                format!("Findings point at the approach itself: {}", describe(&kinds)),
+            )
+        };
+        FailureClassification { kinds, decision, reason }
+    }
+}
+
+/// "2 compile, 1 style".
+fn describe(kinds: &BTreeMap<FailureKind, usize>) -> String {
+    kinds
+        .iter()
+        .map(|(kind, count)| format!("{} {}", count, kind.as_str()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use devman_core::{CheckDetails, QualityCheckId, Severity};
+
+    fn finding(category: QualityCategory, message: &str) -> Finding {
+        Finding {
+            severity: Severity::Error,
+            category,
+            message: message.to_string(),
+            location: None,
+            suggestion: None,
+        }
+    }
+
+    fn check(passed: bool, findings: Vec<Finding>) -> QualityCheckResult {
+        QualityCheckResult {
+            check_id: QualityCheckId::new(),
+            passed,
+            execution_time: std::time::Duration::ZERO,
+            details: CheckDetails {
+                output: String::new(),
+                exit_code: Some(if passed { 0 } else { 1 }),
+                error: None,
+                timeout: None,
+            },
+            findings,
+            metrics: Vec::new(),
+            human_review: None,
+        }
+    }
+
+    #[test]
+    fn test_compile_and_test_failures_suggest_fix() {
+        let classifier = FailureClassifier::default();
+        let results = vec![
+            check(false, vec![finding(QualityCategory::Correctness, "error[E0425]: cannot find value `x`")]),
+            check(false, vec![finding(QualityCategory::Testing, "test parser::tests::empty ... FAILED")]),
+            check(true, vec![finding(QualityCategory::Business, "advisory only")]),
+        ];
+
+        let classification = classifier.classify(&results);
+        assert_eq!(classification.decision, QualityDecision::FixIssuesAndContinue);
+        assert_eq!(classification.kinds, BTreeMap::from([(FailureKind::Compile, 1), (FailureKind::Test, 1)]));
+        assert_eq!(classification.reason, "2 failed check(s) with fixable findings: 1 compile, 1 test");
+
+        let nits = vec![check(false, vec![finding(QualityCategory::Correctness, "clippy: needless borrow")])];
+        assert_eq!(classifier.classify(&nits).decision, QualityDecision::FixIssuesAndContinue);
+        assert_eq!(classifier.classify(&nits).kinds, BTreeMap::from([(FailureKind::Style, 1)]));
+    }
+
+    #[test]
+    fn test_design_findings_suggest_redo() {
+        let classifier = FailureClassifier::default();
+        let results = vec![
+            check(false, vec![
+                finding(QualityCategory::Maintainability, "unused import"),
+                finding(QualityCategory::Correctness, "Architecture violation: UI layer queries the database directly"),
+            ]),
+        ];
+        let classification = classifier.classify(&results);
+        assert_eq!(classification.decision, QualityDecision::RedoExecution);
+        assert_eq!(classification.kinds, BTreeMap::from([(FailureKind::Style, 1), (FailureKind::Design, 1)]));
+
+        let business = vec![check(false, vec![finding(QualityCategory::Business, "Refunds exceed order total")])];
+        assert_eq!(classifier.classify(&business).decision, QualityDecision::RedoExecution);
+    }
+
+    #[test]
+    fn test_passing_results_are_accepted_and_rules_are_configurable() {
+        let classifier = FailureClassifier::default();
+        let passing = vec![check(true, vec![finding(QualityCategory::Documentation, "missing docs")])];
+        let classification = classifier.classify(&passing);
+        assert_eq!(classification.decision, QualityDecision::AcceptAndComplete);
+        assert!(classification.kinds.is_empty());
+
+        // Failed checks without findings still need fixing
+        let silent = classifier.classify(&[check(false, Vec::new())]);
+        assert_eq!(silent.decision, QualityDecision::FixIssuesAndContinue);
+        assert_eq!(silent.kinds, BTreeMap::from([(FailureKind::Other, 1)]));
+
+        // A project can treat failing tests as a sign to start over
+        let strict = FailureClassifier {
+            redo_kinds: vec![FailureKind::Design, FailureKind::Test],
+            ..FailureClassifier::default()
+        };
+        let tests = vec![check(false, vec![finding(QualityCategory::Testing, "assertion failed")])];
+        assert_eq!(strict.classify(&tests).decision, QualityDecision::RedoExecution);
+    }
+}
@@ -1,6 +1,7 @@
 //! Task guidance system - tells AI what to do next.
 
 use devman_core::{TaskState, TaskId, AbandonReason, QualityCheckType, GenericCheckType, TaskQualityCheckResult, TaskQualityOverallStatus, CheckDetails, Severity};
+use crate::locale::MessageCatalog;
 
 /// Generate guidance for a task in a given state.
 pub struct TaskGuidanceGenerator;
@@ -62,11 +63,9 @@ impl TaskGuidanceGenerator {
 
             TaskState::QualityCompleted { result, .. } => {
                 match result.overall_status {
-                    devman_core::TaskQualityOverallStatus::Passed => NextActionInfo::CompleteTask,
-                    devman_core::TaskQualityOverallStatus::PassedWithWarnings => {
-                        let issues = Self::extract_warnings_from_summary(&result);
-                        NextActionInfo::FixQualityIssues { issues }
-                    }
+                    // Advisory warnings do not hold the task back
+                    devman_core::TaskQualityOverallStatus::Passed
+                    | devman_core::TaskQualityOverallStatus::PassedWithWarnings => NextActionInfo::CompleteTask,
                     devman_core::TaskQualityOverallStatus::Failed => {
                         let issues = Self::extract_failures_from_summary(&result);
                         NextActionInfo::FixQualityIssues { issues }
@@ -164,17 +163,6 @@ impl TaskGuidanceGenerator {
         checks
     }
 
-    fn extract_warnings_from_summary(result: &TaskQualityCheckResult) -> Vec<String> {
-        if result.warnings_count > 0 {
-            vec![
-                format!("质检发现 {} 个警告，请查看详细报告", result.warnings_count),
-                format!("总共有 {} 个问题需要关注", result.findings_count),
-            ]
-        } else {
-            vec!["质检通过但有警告".to_string()]
-        }
-    }
-
     fn extract_failures_from_summary(result: &TaskQualityCheckResult) -> Vec<String> {
         let mut failures = vec![];
 
@@ -289,7 +277,7 @@ impl TaskGuidanceGenerator {
     }
 
     fn build_guidance_message(state: &TaskState, next_action: &NextActionInfo, missing: &[String]) -> String {
-        let base_msg = state.get_guidance();
+        let base_msg = MessageCatalog::default().state_guidance(state);
 
         if !missing.is_empty() {
             format!("{}\n\n缺少前置条件:\n- {}", base_msg, missing.join("\n- "))
@@ -9,9 +9,12 @@ use devman_core::{
     TaskId, WorkRecordId,
 };
 use devman_knowledge::KnowledgeService;
+use devman_progress::{CompletionEstimator, CostEstimation, PriceTable};
 use devman_quality::QualityEngine;
 use devman_tools::ToolExecutor;
+use crate::locale::{Locale, MessageCatalog};
 use std::sync::Arc;
+use tokio::sync::Mutex;
 
 // ==================== Re-exports ====================
 
@@ -162,6 +165,8 @@ pub struct TaskGuidance {
     pub allowed_operations: Vec<String>,
     pub guidance_message: String,
     pub task_health: TaskHealth,
+    /// Estimated tokens and USD to finish the task, `None` once it is closed
+    pub cost_estimate: Option<CostEstimation>,
 }
 
 /// Next action for AI
@@ -187,6 +192,13 @@ pub enum TaskHealth {
     Critical { blockers: Vec<String> },
 }
 
+/// Guidance warnings for success criteria no quality gate enforces.
+pub fn coverage_warnings(gaps: &[String]) -> Vec<String> {
+    gaps.iter()
+        .map(|criterion| format!("Success criterion not enforced by any quality gate: {}", criterion))
+        .collect()
+}
+
 /// Task issue
 #[derive(Debug, Clone)]
 pub struct TaskIssue {
@@ -351,9 +363,11 @@ pub enum ArtifactType {
 }
 
 /// Quality decision
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum QualityDecision {
     AcceptAndComplete,
+    #[serde(rename = "fix_and_continue")]
     FixIssuesAndContinue,
     RedoExecution,
 }
@@ -446,16 +460,20 @@ pub struct TaskCompletionSummary {
 // ==================== Basic Implementation ====================
 
 /// Basic implementation of InteractiveAI
+///
+/// The workflow state of each task is kept on the task itself, so guidance
+/// and allowed operations survive a restart of the process.
 pub struct BasicInteractiveAI {
-    storage: Arc<dyn devman_storage::Storage>,
+    storage: Arc<Mutex<dyn devman_storage::Storage>>,
     knowledge_service: Arc<dyn KnowledgeService>,
     quality_engine: Arc<dyn QualityEngine>,
     tool_executor: Arc<dyn ToolExecutor>,
+    messages: MessageCatalog,
 }
 
 impl BasicInteractiveAI {
     pub fn new(
-        storage: Arc<dyn devman_storage::Storage>,
+        storage: Arc<Mutex<dyn devman_storage::Storage>>,
         knowledge_service: Arc<dyn KnowledgeService>,
         quality_engine: Arc<dyn QualityEngine>,
         tool_executor: Arc<dyn ToolExecutor>,
@@ -465,7 +483,61 @@ impl BasicInteractiveAI {
             knowledge_service,
             quality_engine,
             tool_executor,
+            messages: MessageCatalog::new(Locale::from_env()),
+        }
+    }
+
+    /// Give guidance in `locale` instead of the one `DEVMAN_LOCALE` names.
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.messages = MessageCatalog::new(locale);
+        self
+    }
+
+    async fn load_task(&self, task_id: TaskId) -> Result<Task, anyhow::Error> {
+        self.storage.lock().await.load_task(task_id).await?
+            .ok_or_else(|| anyhow::anyhow!("Task not found"))
+    }
+
+    /// Move a task to the state `next` computes from its current one and
+    /// persist it, refusing operations the current state does not allow.
+    async fn transition(
+        &self,
+        task_id: TaskId,
+        operation: &str,
+        next: impl FnOnce(TaskState) -> TaskState + Send,
+    ) -> Result<Task, anyhow::Error> {
+        let mut task = self.load_task(task_id).await?;
+        let state = task.current_state();
+        if !state.allowed_operations().contains(&operation) {
+            return Err(anyhow::anyhow!(
+                "Operation '{}' is not allowed in the current state: {}",
+                operation,
+                self.messages.state_guidance(&state)
+            ));
+        }
+
+        task.set_state(next(state));
+        task.updated_at = chrono::Utc::now();
+        self.storage.lock().await.save_task(&task).await?;
+        Ok(task)
+    }
+}
+
+/// What the AI should do next in `state`.
+fn next_action(state: &TaskState) -> NextAction {
+    match state {
+        TaskState::Created { .. } => NextAction::ReadContext,
+        TaskState::ContextRead { .. } => NextAction::ReviewKnowledge { suggested_queries: vec![] },
+        TaskState::KnowledgeReviewed { .. } => NextAction::StartExecution { suggested_workflow: None },
+        TaskState::InProgress { .. } => NextAction::ContinueExecution { required_logs: vec![] },
+        TaskState::WorkRecorded { .. } => NextAction::RunQualityCheck { required_checks: vec![] },
+        TaskState::QualityChecking { .. } => NextAction::RunQualityCheck { required_checks: vec![] },
+        TaskState::QualityCompleted { result, .. } if result.overall_status.allows_completion() => {
+            NextAction::CompleteTask
         }
+        TaskState::QualityCompleted { .. } => NextAction::FixQualityIssues { issues: vec![] },
+        TaskState::Paused { .. } => NextAction::ContinueExecution { required_logs: vec![] },
+        TaskState::Abandoned { .. } | TaskState::Completed { .. } => NextAction::TaskFinished,
     }
 }
 
@@ -499,26 +571,34 @@ impl InteractiveAI for BasicInteractiveAI {
     }
 
     async fn get_task_guidance(&self, task_id: TaskId) -> Result<TaskGuidance, anyhow::Error> {
-        let task = self.storage.load_task(task_id).await?
-            .ok_or_else(|| anyhow::anyhow!("Task not found"))?;
-
-        // Convert TaskStatus to TaskState for guidance
-        // For now, use a default state
-        let state = TaskState::Created {
-            created_at: task.created_at,
-            created_by: "system".to_string(),
+        let task = self.load_task(task_id).await?;
+        let state = task.current_state();
+
+        let gaps = task.coverage_gaps();
+        let task_health = if gaps.is_empty() {
+            TaskHealth::Healthy
+        } else {
+            TaskHealth::Warning { warnings: coverage_warnings(&gaps) }
         };
 
-        let guidance_message = state.get_guidance().to_string();
+        let cost_estimate = if task.is_closed() {
+            None
+        } else {
+            let storage = self.storage.lock().await;
+            let history = storage.list_tasks(&devman_core::TaskFilter::default()).await?;
+            let prices = PriceTable::from_storage(&*storage).await;
+            Some(CompletionEstimator::default().with_prices(prices).estimate_cost(&task, &history))
+        };
 
         Ok(TaskGuidance {
-            current_state: state,
-            next_action: NextAction::TaskFinished,
+            next_action: next_action(&state),
             prerequisites_satisfied: true,
             missing_prerequisites: vec![],
-            allowed_operations: vec![],
-            guidance_message,
-            task_health: TaskHealth::Healthy,
+            allowed_operations: state.allowed_operations().into_iter().map(str::to_string).collect(),
+            guidance_message: self.messages.state_guidance(&state).to_string(),
+            task_health,
+            cost_estimate,
+            current_state: state,
         })
     }
 
@@ -527,9 +607,67 @@ impl InteractiveAI for BasicInteractiveAI {
         Ok(vec![])
     }
 
-    async fn read_task_context(&self, _task_id: TaskId) -> Result<TaskContext, anyhow::Error> {
-        // TODO: Implement context reading
-        Err(anyhow::anyhow!("Not implemented"))
+    async fn read_task_context(&self, task_id: TaskId) -> Result<TaskContext, anyhow::Error> {
+        let now = chrono::Utc::now();
+        let task = self
+            .transition(task_id, "read_task_context", |_| TaskState::ContextRead { read_at: now })
+            .await?;
+
+        let storage = self.storage.lock().await;
+        let phase = storage.load_phase(task.phase_id).await?;
+        let project = storage
+            .list_projects()
+            .await?
+            .into_iter()
+            .find(|p| p.phases.contains(&task.phase_id));
+
+        let mut dependencies = Vec::new();
+        for dep_id in &task.depends_on {
+            if let Some(dep) = storage.load_task(*dep_id).await? {
+                dependencies.push(TaskDependency {
+                    task_id: dep.id,
+                    title: dep.title.clone(),
+                    is_blocking: dep.status != devman_core::TaskStatus::Done,
+                    status: dep.current_state(),
+                });
+            }
+        }
+
+        let mut quality_requirements = Vec::new();
+        for gate in &task.quality_gates {
+            for check_id in &gate.checks {
+                if let Some(check) = storage.load_quality_check(*check_id).await? {
+                    quality_requirements.push(QualityRequirement {
+                        check_type: check.check_type,
+                        description: check.description,
+                        required: true,
+                    });
+                }
+            }
+        }
+        drop(storage);
+
+        let current_phase = PhaseInfo {
+            id: task.phase_id,
+            name: phase.as_ref().map(|p| p.name.clone()).unwrap_or_default(),
+            status: phase.map(|p| format!("{:?}", p.status)).unwrap_or_default(),
+        };
+        let project = match project {
+            Some(p) => ProjectContext {
+                name: p.name,
+                description: p.description,
+                tech_stack: p.config.tech_stack,
+                current_phase,
+            },
+            None => ProjectContext {
+                name: String::new(),
+                description: String::new(),
+                tech_stack: vec![],
+                current_phase,
+            },
+        };
+
+        Ok(TaskContext { task, project, dependencies, quality_requirements })
     }
 
     async fn review_knowledge(&self, _task_id: TaskId, _query: &str) -> Result<KnowledgeReviewResult, anyhow::Error> {
@@ -541,16 +679,20 @@ impl InteractiveAI for BasicInteractiveAI {
         })
     }
 
-    async fn confirm_knowledge_reviewed(&self, _task_id: TaskId, _knowledge_ids: Vec<KnowledgeId>) -> Result<(), anyhow::Error> {
-        // TODO: Implement knowledge review confirmation
+    async fn confirm_knowledge_reviewed(&self, task_id: TaskId, knowledge_ids: Vec<KnowledgeId>) -> Result<(), anyhow::Error> {
+        let reviewed_at = chrono::Utc::now();
+        self.transition(task_id, "review_knowledge", |_| TaskState::KnowledgeReviewed { knowledge_ids, reviewed_at })
+            .await?;
         Ok(())
     }
 
-    async fn start_execution(&self, _task_id: TaskId) -> Result<ExecutionSession, anyhow::Error> {
-        // TODO: Implement execution start
+    async fn start_execution(&self, task_id: TaskId) -> Result<ExecutionSession, anyhow::Error> {
+        let started_at = chrono::Utc::now();
+        self.transition(task_id, "start_execution", |_| TaskState::InProgress { started_at, checkpoint: None })
+            .await?;
         Ok(ExecutionSession {
-            session_id: "session_001".to_string(),
-            started_at: chrono::Utc::now(),
+            session_id: format!("{}-{}", task_id, started_at.timestamp()),
+            started_at,
             timeout: None,
         })
     }
@@ -575,18 +717,46 @@ impl InteractiveAI for BasicInteractiveAI {
         Err(anyhow::anyhow!("Not implemented"))
     }
 
-    async fn confirm_quality_result(&self, _task_id: TaskId, _check_id: QualityCheckId, _decision: QualityDecision) -> Result<(), anyhow::Error> {
-        // TODO: Implement quality result confirmation
-        Ok(())
+    async fn confirm_quality_result(&self, task_id: TaskId, _check_id: QualityCheckId, decision: QualityDecision) -> Result<(), anyhow::Error> {
+        let state = self.load_task(task_id).await?.current_state();
+        let TaskState::QualityCompleted { result, .. } = &state else {
+            return Err(anyhow::anyhow!("No quality result to confirm: {}", self.messages.state_guidance(&state)));
+        };
+
+        match decision {
+            // Only blocking checks hold the task back; advisory failures
+            // leave it completable
+            QualityDecision::AcceptAndComplete if result.overall_status.allows_completion() => Ok(()),
+            QualityDecision::AcceptAndComplete => Err(anyhow::anyhow!(
+                "Blocking quality checks did not pass ({:?}); fix them before completing the task",
+                result.overall_status
+            )),
+            QualityDecision::FixIssuesAndContinue | QualityDecision::RedoExecution => {
+                let started_at = chrono::Utc::now();
+                self.transition(task_id, "start_execution", |_| TaskState::InProgress { started_at, checkpoint: None })
+                    .await?;
+                Ok(())
+            }
+        }
     }
 
-    async fn pause_task(&self, _task_id: TaskId, _reason: String) -> Result<(), anyhow::Error> {
-        // TODO: Implement task pause
+    async fn pause_task(&self, task_id: TaskId, reason: String) -> Result<(), anyhow::Error> {
+        let paused_at = chrono::Utc::now();
+        self.transition(task_id, "pause_task", |state| TaskState::Paused {
+            paused_at,
+            reason,
+            previous_state: Box::new(state),
+        })
+        .await?;
         Ok(())
     }
 
-    async fn resume_task(&self, _task_id: TaskId) -> Result<(), anyhow::Error> {
-        // TODO: Implement task resume
+    async fn resume_task(&self, task_id: TaskId) -> Result<(), anyhow::Error> {
+        self.transition(task_id, "resume_task", |state| match state {
+            TaskState::Paused { previous_state, .. } => *previous_state,
+            other => other,
+        })
+        .await?;
         Ok(())
     }
 
@@ -647,6 +817,7 @@ mod tests {
             allowed_operations: vec!["read_task_context".to_string()],
             guidance_message: "Test guidance".to_string(),
             task_health: TaskHealth::Healthy,
+            cost_estimate: None,
         };
 
         assert!(guidance.prerequisites_satisfied);
@@ -968,6 +1139,131 @@ mod tests {
         assert!(summary.created_knowledge.is_some());
     }
 
+    // ==================== State Persistence Tests ====================
+
+    async fn interactive_ai(dir: &std::path::Path) -> BasicInteractiveAI {
+        let open = || devman_storage::JsonStorage::new(dir);
+        let storage: Arc<Mutex<dyn devman_storage::Storage>> = Arc::new(Mutex::new(open().await.unwrap()));
+        let tools: Arc<dyn ToolExecutor> = Arc::new(devman_tools::ToolRegistry::new());
+        BasicInteractiveAI::new(
+            storage,
+            Arc::new(devman_knowledge::BasicKnowledgeService::new(open().await.unwrap())),
+            Arc::new(devman_quality::BasicQualityEngine::new(open().await.unwrap(), tools.clone())),
+            tools,
+        )
+    }
+
+    async fn stored_task(dir: &std::path::Path) -> Task {
+        use devman_work::{BasicWorkManager, TaskSpec, WorkManager};
+
+        let storage = devman_storage::JsonStorage::new(dir).await.unwrap();
+        BasicWorkManager::new(storage)
+            .create_task(TaskSpec {
+                title: "Add retries".to_string(),
+                description: "Retry failed uploads".to_string(),
+                intent: devman_core::TaskIntent {
+                    natural_language: "Retry failed uploads".to_string(),
+                    context: devman_core::TaskContext {
+                        relevant_knowledge: vec![],
+                        similar_tasks: vec![],
+                        affected_files: vec![],
+                    },
+                    success_criteria: vec![],
+                },
+                phase_id: PhaseId::new(),
+                quality_gates: vec![],
+                priority: devman_core::Priority::default(),
+                quality_profile: None,
+                due_at: None,
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_guidance_follows_persisted_state_across_restarts() {
+        let dir = tempfile::tempdir().unwrap();
+        let task = stored_task(dir.path()).await;
+
+        let ai = interactive_ai(dir.path()).await;
+        assert!(ai.start_execution(task.id).await.is_err());
+        ai.read_task_context(task.id).await.unwrap();
+        let reviewed = KnowledgeId::new();
+        ai.confirm_knowledge_reviewed(task.id, vec![reviewed]).await.unwrap();
+        drop(ai);
+
+        // A fresh instance over the same directory stands in for a restart
+        let ai = interactive_ai(dir.path()).await;
+        let guidance = ai.get_task_guidance(task.id).await.unwrap();
+        match &guidance.current_state {
+            TaskState::KnowledgeReviewed { knowledge_ids, .. } => assert_eq!(knowledge_ids, &vec![reviewed]),
+            other => panic!("unexpected state {:?}", other),
+        }
+        assert!(matches!(guidance.next_action, NextAction::StartExecution { .. }));
+        assert_eq!(guidance.allowed_operations, ["start_execution", "abandon_task"]);
+
+        let storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let stored = devman_storage::Storage::load_task(&storage, task.id).await.unwrap().unwrap();
+        assert_eq!(stored.status, devman_core::TaskStatus::Queued);
+
+        ai.start_execution(task.id).await.unwrap();
+        let guidance = ai.get_task_guidance(task.id).await.unwrap();
+        assert!(matches!(guidance.current_state, TaskState::InProgress { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_guidance_message_follows_locale() {
+        let dir = tempfile::tempdir().unwrap();
+        let task = stored_task(dir.path()).await;
+
+        let ai = interactive_ai(dir.path()).await.with_locale(Locale::En);
+        let guidance = ai.get_task_guidance(task.id).await.unwrap();
+        assert_eq!(
+            guidance.guidance_message,
+            "Call read_task_context() first to learn the project, dependencies and quality requirements."
+        );
+        let err = ai.start_execution(task.id).await.unwrap_err();
+        assert!(err.to_string().ends_with("Call read_task_context() first to learn the project, dependencies and quality requirements."));
+
+        let ai = interactive_ai(dir.path()).await.with_locale(Locale::Zh);
+        let guidance = ai.get_task_guidance(task.id).await.unwrap();
+        assert_eq!(guidance.guidance_message, "请先调用 read_task_context() 读取任务上下文，了解项目信息、依赖关系和质检要求。");
+    }
+
+    #[tokio::test]
+    async fn test_only_blocking_failures_prevent_completion() {
+        use devman_core::TaskQualityOverallStatus as Status;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut task = stored_task(dir.path()).await;
+        let ai = interactive_ai(dir.path()).await;
+
+        for (status, completable) in [(Status::PassedWithWarnings, true), (Status::Failed, false)] {
+            task.set_state(TaskState::QualityCompleted {
+                result: devman_core::TaskQualityCheckResult {
+                    overall_status: status,
+                    findings_count: 1,
+                    warnings_count: 1,
+                },
+                completed_at: Utc::now(),
+            });
+            ai.storage.lock().await.save_task(&task).await.unwrap();
+
+            let guidance = ai.get_task_guidance(task.id).await.unwrap();
+            assert_eq!(matches!(guidance.next_action, NextAction::CompleteTask), completable);
+            let confirmed = ai
+                .confirm_quality_result(task.id, QualityCheckId::new(), QualityDecision::AcceptAndComplete)
+                .await;
+            assert_eq!(confirmed.is_ok(), completable);
+        }
+
+        ai.confirm_quality_result(task.id, QualityCheckId::new(), QualityDecision::FixIssuesAndContinue)
+            .await
+            .unwrap();
+        let guidance = ai.get_task_guidance(task.id).await.unwrap();
+        assert!(matches!(guidance.current_state, TaskState::InProgress { .. }));
+    }
+
     // ==================== ID Generation Tests ====================
 
     #[test]
@@ -2,17 +2,25 @@
 
 use async_trait::async_trait;
 use devman_core::{
-    GoalId, GoalProgress, Goal, Knowledge, PhaseId, QualityCheck, QualityCheckId,
-    QualityStatus, SuccessCriterion, Task, TaskId, TaskStatus, VerificationMethod, WorkRecord, WorkResult,
+    AbandonReason, AgentId, BlockedItem, ClaimToken, Blocker, BlockerId, Severity, GoalId, GoalProgress, Goal, Feedback, Knowledge, KnowledgeId, Phase, PhaseId, PhaseOrderError, Project, ProjectId, QualityCheck, QualityCheckId, QualityProfileId,
+    QualityStatus, SuccessCriterion, Task, TaskId, TaskState, TaskStatus, TaskTemplateId, VerificationMethod, WorkEvent,
+    WorkRecord, WorkResult, validate_phase_order,
 };
-use devman_knowledge::KnowledgeService;
-use devman_progress::ProgressTracker;
-use devman_quality::{QualityEngine, engine::WorkContext as QualityWorkContext};
+use devman_knowledge::{KnowledgeHit, KnowledgeService, KnowledgeStats};
+use devman_progress::{BlockerDetector, CompletionEstimator, CostEstimation, PriceTable, ProgressTracker};
+use devman_quality::{GoalQualitySummary, QualityEngine, QualityTrend, quality_trend, engine::{GateDecision, GateResult, WorkContext as QualityWorkContext}};
 use devman_storage::Storage;
 use devman_tools::ToolInput;
-use devman_work::{WorkManager, TaskSpec, WorkManagementContext};
+use devman_work::{ContextBudget, DuplicateWarning, WorkManager, TaskSpec, WorkManagementContext};
+use serde::Serialize;
+use crate::classifier::{FailureClassification, FailureClassifier};
+use std::collections::HashMap;
 use std::sync::Arc;
 
+/// Similar earlier knowledge items linked as see-also to each item a task
+/// creates.
+const SEE_ALSO_LIMIT: usize = 3;
+
 /// High-level interface for AI assistants.
 #[async_trait]
 pub trait AIInterface: Send + Sync {
@@ -21,6 +29,10 @@ pub trait AIInterface: Send + Sync {
     /// Get current work context.
     async fn get_current_context(&self) -> WorkManagementContext;
 
+    /// Context for working on a task: its phase, dependencies, related
+    /// knowledge and work history, trimmed to fit `budget`.
+    async fn task_context(&self, task_id: TaskId, budget: ContextBudget) -> Result<WorkManagementContext, anyhow::Error>;
+
     // === Goal Operations ===
 
     /// Create a new goal.
@@ -32,38 +44,289 @@ pub trait AIInterface: Send + Sync {
     /// List goals with optional filter.
     async fn list_goals(&self, filter: GoalFilter) -> Vec<Goal>;
 
+    /// Change a goal's status. Fails with [`devman_core::TransitionError`]
+    /// if the lifecycle does not allow the change.
+    async fn set_goal_status(&self, goal_id: GoalId, status: devman_core::GoalStatus) -> Result<Goal, anyhow::Error>;
+
+    /// Make a completed goal active again.
+    async fn reopen_goal(&self, goal_id: GoalId) -> Result<Goal, anyhow::Error>;
+
+    /// Run the verification command of each of a goal's success criteria
+    /// and record whether it was met. Criteria without a command are left
+    /// for manual confirmation. Completing a goal runs this first and fails
+    /// while any command-verified criterion is not met.
+    async fn verify_criteria(&self, goal_id: GoalId) -> Result<Vec<CriterionVerification>, anyhow::Error>;
+
+    /// Success criteria of a goal claimed by more than one open task of
+    /// the goal, whose work may be duplicated or contradictory. A task
+    /// claims a criterion when one of its own success criteria names the
+    /// criterion's ID or shares enough keywords with its description.
+    async fn detect_criterion_conflicts(&self, goal_id: GoalId) -> Vec<CriterionConflict>;
+
     // === Knowledge Retrieval ===
 
     /// Search knowledge by semantic query.
     async fn search_knowledge(&self, query: &str) -> Vec<Knowledge>;
 
+    /// Search knowledge, keeping each hit's score and matched excerpt, best
+    /// first.
+    async fn search_knowledge_ranked(&self, query: &str, limit: usize) -> Vec<KnowledgeHit>;
+
     /// Get best practices for a domain.
     async fn get_best_practices(&self, domain: &str) -> Vec<Knowledge>;
 
+    /// Get a knowledge item by ID.
+    async fn get_knowledge(&self, knowledge_id: KnowledgeId) -> Option<Knowledge>;
+
+    /// List the most recently updated knowledge, newest first.
+    async fn recent_knowledge(&self, limit: usize) -> Vec<Knowledge>;
+
+    /// Rate a knowledge item as helpful or not, returning the updated item.
+    async fn rate_knowledge(&self, knowledge_id: KnowledgeId, feedback: Feedback) -> Result<Knowledge, anyhow::Error>;
+
+    /// Summarise the knowledge base by type, tag, embedding coverage and feedback.
+    async fn knowledge_statistics(&self) -> Result<KnowledgeStats, anyhow::Error>;
+
     // === Progress Query ===
 
     /// Get goal progress.
     async fn get_progress(&self, goal_id: GoalId) -> Option<GoalProgress>;
 
-    /// List current blockers.
+    /// Get a goal with its phases in order and each phase's tasks nested
+    /// under their parents, with progress rolled up at every level.
+    async fn get_goal_tree(&self, goal_id: GoalId) -> Option<GoalTree>;
+
+    // === Phase Operations ===
+
+    /// Add a phase to a project, right after `after` or first when `None`.
+    ///
+    /// Fails if `after` or a dependency of the spec is not one of the
+    /// project's phases, or if a dependency would come after the new phase.
+    async fn insert_phase(
+        &self,
+        project_id: ProjectId,
+        after: Option<PhaseId>,
+        spec: PhaseSpec,
+    ) -> Result<Phase, anyhow::Error>;
+
+    /// Replace a project's phase order with `new_order`.
+    ///
+    /// The new order must list each of the project's phases exactly once and
+    /// keep every phase after the phases it depends on.
+    async fn reorder_phases(&self, project_id: ProjectId, new_order: Vec<PhaseId>) -> Result<Project, anyhow::Error>;
+
+    /// Move a goal on to the next phase of its project once every task of
+    /// the current phase is closed and the completed ones have passed their
+    /// quality gates, returning the new current phase. Advancing past the
+    /// last phase completes the goal and returns that last phase. Fails with
+    /// [`PhaseGateError`] while the gate is not met.
+    async fn advance_phase(&self, goal_id: GoalId) -> Result<PhaseId, anyhow::Error>;
+
+    /// Make `phase_id`, a phase of the goal's project, the goal's current
+    /// phase without checking any gate.
+    async fn set_active_phase(&self, goal_id: GoalId, phase_id: PhaseId) -> Result<Goal, anyhow::Error>;
+
+    /// List unresolved blockers: recorded ones and, with a blocker
+    /// detector configured, those derived from task dependencies.
     async fn list_blockers(&self) -> Vec<devman_core::Blocker>;
 
+    /// Record a blocker on something outside the task graph, such as a
+    /// third party, that stays open until resolved by hand.
+    async fn add_blocker(
+        &self,
+        item: BlockedItem,
+        reason: &str,
+        severity: Severity,
+    ) -> Result<Blocker, anyhow::Error>;
+
+    /// Mark a recorded blocker resolved. Fails if it already is.
+    async fn resolve_blocker(&self, blocker_id: BlockerId) -> Result<Blocker, anyhow::Error>;
+
+    // === Idempotency ===
+
+    /// ID of the entity an earlier request with idempotency `key` created,
+    /// if that request was made within `window`.
+    async fn find_idempotent_entity(&self, key: &str, window: chrono::Duration) -> Option<String>;
+
+    /// Remember that the request with idempotency `key` created `entity_id`,
+    /// dropping keys older than `window`.
+    async fn remember_idempotent_entity(
+        &self,
+        key: &str,
+        entity_id: &str,
+        window: chrono::Duration,
+    ) -> Result<(), anyhow::Error>;
+
     // === Task Operations ===
 
     /// Create a new task.
+    ///
+    /// A spec without quality gates gets those of `spec.quality_profile`, or
+    /// of its project's default task profile when that is unset.
     async fn create_task(&self, spec: TaskSpec) -> Result<Task, anyhow::Error>;
 
+    /// Open tasks whose intent overlaps `spec` by at least `threshold`,
+    /// so a caller can avoid creating a near-duplicate.
+    async fn find_duplicate_tasks(
+        &self,
+        spec: &TaskSpec,
+        threshold: f32,
+    ) -> Result<Option<DuplicateWarning>, anyhow::Error>;
+
+    /// Create a sub-task under `parent_id`, linking both ends.
+    ///
+    /// The sub-task is placed in the parent's phase.
+    async fn create_subtask(&self, parent_id: TaskId, spec: TaskSpec) -> Result<Task, anyhow::Error>;
+
+    /// Create a task in `phase_id` from a stored template, filling its
+    /// `{{name}}` placeholders from `params` and parameter defaults.
+    ///
+    /// The task gets the template's steps and quality gates. Fails with
+    /// [`TemplateError::MissingParameters`] when a required parameter is
+    /// not given.
+    async fn create_task_from_template(
+        &self,
+        template_id: TaskTemplateId,
+        phase_id: PhaseId,
+        params: HashMap<String, String>,
+    ) -> Result<Task, anyhow::Error>;
+
     /// Get task by ID.
     async fn get_task(&self, task_id: TaskId) -> Option<Task>;
 
     /// List tasks with optional filter.
     async fn list_tasks(&self, filter: TaskFilter) -> Vec<Task>;
 
+    /// Archive a task, hiding it from default listings while keeping it
+    /// recoverable with [`AIInterface::unarchive_task`].
+    async fn archive_task(&self, task_id: TaskId) -> Result<Task, anyhow::Error>;
+
+    /// Restore an archived task to default listings.
+    async fn unarchive_task(&self, task_id: TaskId) -> Result<Task, anyhow::Error>;
+
+    /// Permanently delete a task. Refused unless `force` is set; prefer
+    /// [`AIInterface::archive_task`].
+    ///
+    /// The task is removed from its parent's sub-tasks and from other
+    /// tasks' dependencies; its own sub-tasks become top-level tasks.
+    async fn delete_task(&self, task_id: TaskId, force: bool) -> Result<(), anyhow::Error>;
+
+    /// Merge the duplicate task `merge` into `keep`.
+    ///
+    /// The survivor takes over the other task's work records and sub-tasks,
+    /// gains the union of both tasks' dependencies, blocked tasks, affected
+    /// files, success criteria and created knowledge, and keeps the longer
+    /// description. Tasks that depended on or were blocked by `merge` point
+    /// at `keep` instead. `merge` is then archived with `merged_into` set to
+    /// the survivor.
+    async fn merge_tasks(&self, keep: TaskId, merge: TaskId) -> Result<(), anyhow::Error>;
+
+    /// Move a done or abandoned task back to `Queued`, keeping its work
+    /// records and logging `reason`. Completed goals that include the task's
+    /// phase are made active again; their IDs are returned.
+    async fn reopen_task(&self, task_id: TaskId, reason: &str) -> Result<Vec<GoalId>, anyhow::Error>;
+
     /// Start executing a task.
     async fn start_task(&self, task_id: TaskId) -> Result<WorkRecord, anyhow::Error>;
 
-    /// Complete a task with result.
-    async fn complete_task(&self, task_id: TaskId, result: WorkResult) -> Result<(), anyhow::Error>;
+    /// Complete a task with result. Fails while the task has open sub-tasks.
+    ///
+    /// Knowledge listed in `result.created_knowledge` is linked to the task,
+    /// to its latest work record and to each other, and similar earlier
+    /// knowledge is added to each item's related knowledge as see-also.
+    /// With the dependency cascade on, blocked dependents left waiting on
+    /// nothing once the task is done are queued again and reported.
+    async fn complete_task(&self, task_id: TaskId, result: WorkResult) -> Result<CascadeReport, anyhow::Error>;
+
+    /// Abandon an open task, recording why in its workflow state.
+    ///
+    /// With the dependency cascade on, a task abandoned for good (see
+    /// [`AbandonReason::is_recoverable`]) releases its blocked dependents
+    /// as completion does, with a warning for each; dependents of a task
+    /// that may be picked up again keep waiting and are flagged instead.
+    async fn abandon_task(&self, task_id: TaskId, reason: AbandonReason) -> Result<CascadeReport, anyhow::Error>;
+
+    /// Record how long a task actually took and how many tokens it used.
+    ///
+    /// Manually recorded actuals replace any captured on completion and are
+    /// kept if the task is completed afterwards.
+    async fn record_actuals(
+        &self,
+        task_id: TaskId,
+        actual_duration: std::time::Duration,
+        actual_tokens: Option<usize>,
+    ) -> Result<Task, anyhow::Error>;
+
+    /// Append an event to the task's running work record, opening a new one
+    /// if it has none or its latest record has finished.
+    async fn record_work_event(&self, task_id: TaskId, event: WorkEvent) -> Result<(), anyhow::Error>;
+
+    /// Get a task's full history, merged from the event log and its work records
+    /// and ordered by time.
+    async fn get_task_timeline(&self, task_id: TaskId) -> Result<Vec<TimelineEntry>, anyhow::Error>;
+
+    /// Pick the best task to work on next from the ready tasks, with the
+    /// reasons for the choice. Blocked tasks are never recommended, nor are
+    /// tasks claimed by an agent other than `agent`.
+    async fn recommend_next_task(&self, agent: Option<&AgentId>) -> Option<NextTaskRecommendation>;
+
+    /// Estimate what completing a task will cost from similar finished
+    /// tasks, priced with the stored price table; `None` if it does not exist.
+    async fn estimate_task_cost(&self, task_id: TaskId) -> Option<CostEstimation>;
+
+    /// Claim a task for `agent` for `ttl`, so other agents sharing the store
+    /// leave it alone until the claim is released or expires.
+    ///
+    /// Claiming a task the same agent already holds renews it. Fails with
+    /// [`ClaimError::Held`] while another agent holds it, and with
+    /// [`devman_storage::StorageError::Conflict`] if another writer changed
+    /// the task between reading and claiming it.
+    async fn claim_task(
+        &self,
+        task_id: TaskId,
+        agent: AgentId,
+        ttl: std::time::Duration,
+    ) -> Result<ClaimToken, anyhow::Error>;
+
+    /// Release a claim taken with [`claim_task`](Self::claim_task). Fails
+    /// with [`ClaimError::TokenMismatch`] unless `token` is the task's
+    /// current claim; releasing an unclaimed task is a no-op.
+    async fn release_task(&self, task_id: TaskId, token: ClaimToken) -> Result<(), anyhow::Error>;
+
+    /// Move every task matching `filter` to `status`.
+    ///
+    /// Tasks already in `status` are skipped and tasks whose lifecycle does
+    /// not allow the change are reported as failed. Closing tasks goes
+    /// through [`complete_task`](Self::complete_task) or
+    /// [`abandon_task`](Self::abandon_task) one task at a time, and a task
+    /// they reject is reported as failed. Other writes are all or nothing:
+    /// if saving any task fails, the tasks already saved are restored and
+    /// the error is returned.
+    async fn bulk_update_status(
+        &self,
+        filter: TaskFilter,
+        status: TaskStatus,
+        reason: &str,
+    ) -> Result<BulkResult, anyhow::Error>;
+
+    /// Report what abandoning a task would break: every open task that
+    /// depends on it directly or transitively, the phases and goals those
+    /// tasks belong to, and suggested mitigations.
+    async fn abandonment_impact(&self, task_id: TaskId) -> Result<ImpactReport, anyhow::Error>;
+
+    /// What moving a task to status `to` would change, without changing
+    /// anything: whether the lifecycle allows it, the tasks a completion
+    /// would make ready or an abandonment would strand, phase and goal
+    /// progress before and after, and whether the task's quality gates
+    /// have passed.
+    async fn preview_transition(&self, task_id: TaskId, to: TaskStatus) -> Result<TransitionPreview, anyhow::Error>;
+
+    /// Active goals, their phases' progress, open blockers, recent
+    /// completions and store-wide counts, bounded in size.
+    async fn project_snapshot(&self) -> Result<ProjectSnapshot, anyhow::Error>;
+
+    /// Tasks that declare or have worked on `path`, excluding archived tasks.
+    async fn tasks_touching_file(&self, path: &str) -> Vec<TaskSummary>;
 
     // === Quality Operations ===
 
@@ -76,10 +339,45 @@ pub trait AIInterface: Send + Sync {
     /// Get quality status for a task.
     async fn get_quality_status(&self, task_id: TaskId) -> QualityStatus;
 
+    /// Assign a quality profile to a phase, so every task in it inherits
+    /// the profile's gates.
+    async fn assign_profile(
+        &self,
+        phase_id: PhaseId,
+        profile_id: QualityProfileId,
+    ) -> Result<Phase, anyhow::Error>;
+
+    /// Run a task's own quality gates and those inherited from its phase's
+    /// profile; firing `cancel` kills the checks still running.
+    async fn run_task_quality_check(
+        &self,
+        task_id: TaskId,
+        cancel: &devman_tools::CancellationToken,
+    ) -> Result<Vec<GateResult>, anyhow::Error>;
+
+    /// Check results of every quality run for a task, oldest first.
+    async fn quality_history(&self, task_id: TaskId) -> Result<Vec<devman_core::QualityCheckResult>, anyhow::Error>;
+
+    /// Whether a task's quality improved or regressed on its latest run.
+    async fn quality_trend(&self, task_id: TaskId) -> Result<QualityTrend, anyhow::Error>;
+
+    /// Classify the findings of a task's latest quality run and recommend
+    /// whether to accept, fix and continue, or redo the work; `None` if the
+    /// task has no quality runs.
+    async fn classify_quality_failures(&self, task_id: TaskId) -> Result<Option<FailureClassification>, anyhow::Error>;
+
+    /// Latest quality results across every task under a goal.
+    async fn goal_quality_summary(&self, goal_id: GoalId) -> Result<GoalQualitySummary, anyhow::Error>;
+
     // === Tool Execution ===
 
-    /// Execute a tool (reduces token usage).
-    async fn execute_tool(&self, tool: String, input: ToolInput) -> devman_tools::ToolOutput;
+    /// Execute a tool (reduces token usage); firing `cancel` kills it.
+    async fn execute_tool(
+        &self,
+        tool: String,
+        input: ToolInput,
+        cancel: &devman_tools::CancellationToken,
+    ) -> devman_tools::ToolOutput;
 
     // === Knowledge Saving ===
 
@@ -100,6 +398,19 @@ pub struct GoalSpec {
     pub project_id: Option<devman_core::ProjectId>,
 }
 
+/// Phase creation specification.
+#[derive(Debug, Clone, Default)]
+pub struct PhaseSpec {
+    /// Phase name
+    pub name: String,
+    /// Description
+    pub description: String,
+    /// Phase objectives
+    pub objectives: Vec<String>,
+    /// Phases of the same project that must come first
+    pub depends_on: Vec<PhaseId>,
+}
+
 /// Goal filter for listing.
 #[derive(Debug, Clone, Default)]
 pub struct GoalFilter {
@@ -122,6 +433,814 @@ pub struct TaskFilter {
     pub limit: Option<usize>,
     /// Include completed tasks
     pub include_completed: bool,
+    /// Include archived tasks
+    pub include_archived: bool,
+    /// Only tasks at or above this priority level
+    pub min_priority: Option<devman_core::Priority>,
+    /// Only tasks created before this time
+    pub created_before: Option<devman_core::Time>,
+    /// Only tasks without a parent
+    pub roots_only: bool,
+    /// With `roots_only`, follow each listed root with its sub-tasks at any
+    /// depth; the other filters and the limit apply to the roots alone
+    pub include_subtasks: bool,
+}
+
+/// A single entry in a task's timeline.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TimelineEntry {
+    /// Task state change from the event log
+    StateTransition {
+        /// When it happened
+        timestamp: devman_core::Time,
+        /// Action taken
+        action: String,
+        /// Resulting state or outcome
+        result: String,
+    },
+    /// Work log entry from a work record
+    WorkLog {
+        /// When it happened
+        timestamp: devman_core::Time,
+        /// Event type
+        event_type: devman_core::WorkEventType,
+        /// Description
+        description: String,
+        /// Associated data
+        data: serde_json::Value,
+    },
+    /// Quality check outcome from a work record
+    QualityCheck {
+        /// When it happened
+        timestamp: devman_core::Time,
+        /// Whether the check passed
+        passed: bool,
+        /// Description
+        description: String,
+        /// Associated data
+        data: serde_json::Value,
+    },
+    /// Issue or resolution noted during work
+    Note {
+        /// When it happened
+        timestamp: devman_core::Time,
+        /// Note text
+        text: String,
+    },
+}
+
+impl TimelineEntry {
+    /// When the entry happened.
+    pub fn timestamp(&self) -> devman_core::Time {
+        match self {
+            TimelineEntry::StateTransition { timestamp, .. }
+            | TimelineEntry::WorkLog { timestamp, .. }
+            | TimelineEntry::QualityCheck { timestamp, .. }
+            | TimelineEntry::Note { timestamp, .. } => *timestamp,
+        }
+    }
+}
+
+/// Recommended next task with the reasoning behind it.
+#[derive(Debug, Clone, Serialize)]
+pub struct NextTaskRecommendation {
+    /// The recommended task
+    pub task: Task,
+    /// Why this task was chosen
+    pub rationale: Vec<String>,
+    /// Knowledge relevant to the task
+    pub relevant_knowledge: Vec<Knowledge>,
+    /// Checks to review before starting
+    pub readiness: Vec<ReadinessItem>,
+    /// Number of ready tasks that were considered
+    pub candidates: usize,
+    /// Estimated tokens and USD to finish the task
+    pub estimated_cost: CostEstimation,
+}
+
+/// One item of a readiness checklist.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadinessItem {
+    /// What is checked
+    pub item: String,
+    /// Whether it holds
+    pub satisfied: bool,
+}
+
+/// A goal with its phases and tasks.
+#[derive(Debug, Clone, Serialize)]
+pub struct GoalTree {
+    /// Goal ID
+    pub id: GoalId,
+    /// Goal title
+    pub title: String,
+    /// Goal status
+    pub status: devman_core::GoalStatus,
+    /// Rolled-up progress over every top-level task of every phase (0-100)
+    pub percentage: f32,
+    /// Phases in project order
+    pub phases: Vec<PhaseNode>,
+}
+
+/// A phase in a [`GoalTree`].
+#[derive(Debug, Clone, Serialize)]
+pub struct PhaseNode {
+    /// Phase ID
+    pub id: PhaseId,
+    /// Phase name
+    pub name: String,
+    /// Phase status
+    pub status: devman_core::PhaseStatus,
+    /// Rolled-up progress of the phase's top-level tasks (0-100)
+    pub percentage: f32,
+    /// Top-level tasks that are closed
+    pub completed_tasks: usize,
+    /// Top-level tasks
+    pub total_tasks: usize,
+    /// Top-level tasks in phase order
+    pub tasks: Vec<TaskNode>,
+}
+
+/// A task in a [`GoalTree`], with its sub-tasks.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskNode {
+    /// Task ID
+    pub id: TaskId,
+    /// Task title
+    pub title: String,
+    /// Task status
+    pub status: TaskStatus,
+    /// Rolled-up progress (0-100): 100 when closed, otherwise the mean of
+    /// the sub-tasks, or the task's own progress if it has none
+    pub percentage: f32,
+    /// Sub-tasks
+    pub children: Vec<TaskNode>,
+}
+
+/// Mean of `values`, or 0 when empty.
+fn mean_percentage(values: impl ExactSizeIterator<Item = f32>) -> f32 {
+    let count = values.len();
+    if count == 0 {
+        0.0
+    } else {
+        values.sum::<f32>() / count as f32
+    }
+}
+
+/// Tasks of a goal: those listed in the phases of its project, followed by
+/// their sub-tasks at any depth.
+async fn goal_tasks(storage: &dyn Storage, goal: &Goal) -> Result<Vec<Task>, devman_storage::StorageError> {
+    let mut pending = Vec::new();
+    if let Some(project) = storage.load_project(goal.project_id).await? {
+        for phase_id in project.phases {
+            if let Some(phase) = storage.load_phase(phase_id).await? {
+                pending.extend(phase.tasks);
+            }
+        }
+    }
+    pending.reverse();
+
+    let mut seen = std::collections::HashSet::new();
+    let mut tasks = Vec::new();
+    while let Some(task_id) = pending.pop() {
+        if !seen.insert(task_id) {
+            continue;
+        }
+        if let Some(task) = storage.load_task(task_id).await? {
+            pending.extend(task.children.iter().rev());
+            tasks.push(task);
+        }
+    }
+    Ok(tasks)
+}
+
+/// Build the node for `task`, loading sub-tasks from `storage`.
+///
+/// `seen` guards against parent/child cycles in corrupt data.
+fn build_task_node<'a>(
+    storage: &'a dyn Storage,
+    task: Task,
+    seen: &'a mut std::collections::HashSet<TaskId>,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = TaskNode> + Send + 'a>> {
+    Box::pin(async move {
+        seen.insert(task.id);
+        let mut children = Vec::new();
+        for child_id in &task.children {
+            if seen.contains(child_id) {
+                continue;
+            }
+            if let Ok(Some(child)) = storage.load_task(*child_id).await {
+                children.push(build_task_node(storage, child, seen).await);
+            }
+        }
+
+        let percentage = if task.is_closed() {
+            100.0
+        } else if children.is_empty() {
+            task.progress.percentage
+        } else {
+            mean_percentage(children.iter().map(|c| c.percentage))
+        };
+
+        TaskNode {
+            id: task.id,
+            title: task.title,
+            status: task.status,
+            percentage,
+            children,
+        }
+    })
+}
+
+/// Outcome of a bulk status update.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BulkResult {
+    /// Number of tasks updated
+    pub updated: usize,
+    /// Number of tasks already in the target status
+    pub skipped: usize,
+    /// Number of tasks whose transition was rejected
+    pub failed: usize,
+    /// Per-task outcomes
+    pub items: Vec<BulkItem>,
+}
+
+impl BulkResult {
+    fn record(&mut self, task_id: TaskId, outcome: BulkOutcome, reason: String) {
+        match outcome {
+            BulkOutcome::Updated => self.updated += 1,
+            BulkOutcome::Skipped => self.skipped += 1,
+            BulkOutcome::Failed => self.failed += 1,
+        }
+        self.items.push(BulkItem { task_id, outcome, reason });
+    }
+}
+
+/// Outcome for one task of a bulk update.
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkItem {
+    /// Task ID
+    pub task_id: TaskId,
+    /// What happened to the task
+    pub outcome: BulkOutcome,
+    /// Why
+    pub reason: String,
+}
+
+/// What happened to a task in a bulk update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkOutcome {
+    /// Status changed
+    Updated,
+    /// Already in the target status
+    Skipped,
+    /// Transition not allowed
+    Failed,
+}
+
+/// Brief view of a task that touches a file.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskSummary {
+    /// Task ID
+    pub id: TaskId,
+    /// Task title
+    pub title: String,
+    /// Current status
+    pub status: TaskStatus,
+    /// Whether the file is listed in the task's affected files
+    pub declared: bool,
+    /// Whether work logged against the task touched the file
+    pub worked_on: bool,
+}
+
+/// Consequences of abandoning a task.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImpactReport {
+    /// Task being considered for abandonment
+    pub task_id: TaskId,
+    /// Open tasks that would be left waiting on it forever, nearest first
+    pub blocked_tasks: Vec<ImpactedTask>,
+    /// Phases that contain the task or a blocked task
+    pub stalled_phases: Vec<StalledPhase>,
+    /// Goals whose project includes a stalled phase
+    pub stalled_goals: Vec<StalledGoal>,
+    /// Suggested ways to avoid the damage
+    pub mitigations: Vec<String>,
+}
+
+/// A task that would be blocked by an abandonment.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImpactedTask {
+    /// Task ID
+    pub id: TaskId,
+    /// Task title
+    pub title: String,
+    /// Current status
+    pub status: TaskStatus,
+    /// Dependency hops from the abandoned task (1 = direct dependent)
+    pub depth: usize,
+}
+
+/// A phase that would stall.
+#[derive(Debug, Clone, Serialize)]
+pub struct StalledPhase {
+    /// Phase ID
+    pub id: PhaseId,
+    /// Phase name
+    pub name: String,
+}
+
+/// A goal that would stall.
+#[derive(Debug, Clone, Serialize)]
+pub struct StalledGoal {
+    /// Goal ID
+    pub id: GoalId,
+    /// Goal title
+    pub title: String,
+}
+
+/// What a status change would do, computed without making it.
+#[derive(Debug, Clone, Serialize)]
+pub struct TransitionPreview {
+    /// Task previewed
+    pub task_id: TaskId,
+    /// Current status
+    pub from: TaskStatus,
+    /// Status previewed
+    pub to: TaskStatus,
+    /// Whether the change would be accepted
+    pub allowed: bool,
+    /// Why it would be refused; empty when allowed
+    pub rejections: Vec<String>,
+    /// Open tasks whose last open dependency is this task, ready once it is done
+    pub unblocked_tasks: Vec<ImpactedTask>,
+    /// Open tasks left waiting forever if it is abandoned, nearest first
+    pub stranded_tasks: Vec<ImpactedTask>,
+    /// Progress of the phases listing the task
+    pub phase_progress: Vec<ProgressDelta<PhaseId>>,
+    /// Progress of the goals whose project includes one of those phases
+    pub goal_progress: Vec<ProgressDelta<GoalId>>,
+    /// Result of the latest quality run against the task's gates
+    pub quality_status: devman_core::TaskQualityOverallStatus,
+    /// Whether the gates allow completion: the task has none, or the latest
+    /// run passed every blocking check
+    pub quality_gates_pass: bool,
+    /// Things worth knowing that would not stop the change
+    pub warnings: Vec<String>,
+}
+
+/// Progress of a phase or goal before and after a status change, in percent.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProgressDelta<I> {
+    /// Phase or goal ID
+    pub id: I,
+    /// Phase name or goal title
+    pub name: String,
+    /// Percentage now
+    pub before: f32,
+    /// Percentage after the change
+    pub after: f32,
+}
+
+/// Most items of each list a [`ProjectSnapshot`] includes.
+pub const SNAPSHOT_LIST_LIMIT: usize = 10;
+
+/// Everything needed to orient at the start of a session, in one read.
+///
+/// Lists are capped at [`SNAPSHOT_LIST_LIMIT`] items; the metrics give the
+/// full counts.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectSnapshot {
+    /// When the snapshot was taken
+    pub generated_at: devman_core::Time,
+    /// Active goals, most recently updated first
+    pub goals: Vec<GoalSnapshot>,
+    /// Phases of the active goals' projects, in project order
+    pub phases: Vec<PhaseSnapshot>,
+    /// Open blockers, most severe first
+    pub blockers: Vec<Blocker>,
+    /// Most recently completed tasks, newest first
+    pub recent_completions: Vec<CompletedTask>,
+    /// Counts across the whole store
+    pub metrics: SnapshotMetrics,
+}
+
+/// An active goal in a [`ProjectSnapshot`].
+#[derive(Debug, Clone, Serialize)]
+pub struct GoalSnapshot {
+    /// Goal ID
+    pub id: GoalId,
+    /// Goal title
+    pub title: String,
+    /// Project the goal belongs to
+    pub project_id: ProjectId,
+    /// Share of the project's phase tasks that are done or abandoned, in percent
+    pub percentage: f32,
+}
+
+/// A phase in a [`ProjectSnapshot`].
+#[derive(Debug, Clone, Serialize)]
+pub struct PhaseSnapshot {
+    /// Phase ID
+    pub id: PhaseId,
+    /// Phase name
+    pub name: String,
+    /// Phase status
+    pub status: devman_core::PhaseStatus,
+    /// Tasks done or abandoned
+    pub completed_tasks: usize,
+    /// Tasks in the phase
+    pub total_tasks: usize,
+    /// Share of tasks completed, in percent
+    pub percentage: f32,
+}
+
+/// A recently completed task in a [`ProjectSnapshot`].
+#[derive(Debug, Clone, Serialize)]
+pub struct CompletedTask {
+    /// Task ID
+    pub id: TaskId,
+    /// Task title
+    pub title: String,
+    /// When the task was last updated, which for a done task is when it
+    /// was completed unless edited since
+    pub completed_at: devman_core::Time,
+}
+
+/// Store-wide counts in a [`ProjectSnapshot`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SnapshotMetrics {
+    /// Unarchived tasks by status
+    pub tasks_by_status: std::collections::BTreeMap<String, usize>,
+    /// Unarchived tasks
+    pub total_tasks: usize,
+    /// Share of unarchived tasks done or abandoned, in percent
+    pub completion_percentage: f32,
+    /// Open blockers
+    pub open_blockers: usize,
+    /// Active goals
+    pub active_goals: usize,
+    /// Knowledge items
+    pub knowledge_items: usize,
+}
+
+/// Outcome of checking one success criterion.
+#[derive(Debug, Clone, Serialize)]
+pub struct CriterionVerification {
+    /// Criterion ID
+    pub criterion_id: devman_core::CriterionId,
+    /// Criterion description
+    pub description: String,
+    /// Whether the criterion was checked by running its command
+    pub automated: bool,
+    /// Status after the check; manual criteria keep their recorded status
+    pub status: devman_core::CriterionStatus,
+    /// Exit code of the verification command
+    pub exit_code: Option<i32>,
+    /// Output of the verification command
+    pub output: Option<String>,
+}
+
+/// Dependents released when a task closed.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CascadeReport {
+    /// Blocked tasks moved back to the queue, their dependencies all settled
+    pub requeued: Vec<TaskId>,
+    /// Dependents released by an abandonment, or still waiting on an
+    /// abandoned task that may come back
+    pub warnings: Vec<String>,
+}
+
+/// A goal success criterion claimed by more than one open task.
+#[derive(Debug, Clone, Serialize)]
+pub struct CriterionConflict {
+    /// Criterion ID
+    pub criterion_id: devman_core::CriterionId,
+    /// Criterion description
+    pub description: String,
+    /// Tasks claiming the criterion, closest match first
+    pub claims: Vec<CriterionClaim>,
+}
+
+/// A task claiming a goal success criterion.
+#[derive(Debug, Clone, Serialize)]
+pub struct CriterionClaim {
+    /// Claiming task
+    pub task_id: TaskId,
+    /// Its title
+    pub title: String,
+    /// The task's success criterion that matched
+    pub task_criterion: String,
+    /// How closely it matched, from 0.0 to 1.0; 1.0 for an explicit link
+    pub score: f32,
+}
+
+/// Why a task claim was refused.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ClaimError {
+    /// Another agent holds an active claim
+    #[error("Task is claimed by {} until {expires_at}", agent.0)]
+    Held {
+        /// Agent holding the claim
+        agent: AgentId,
+        /// When its claim lapses
+        expires_at: devman_core::Time,
+    },
+    /// The token does not belong to the task's current claim
+    #[error("Claim token does not match the task's current claim")]
+    TokenMismatch,
+}
+
+/// Why a task could not be created from a template.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum TemplateError {
+    /// Required parameters without a value
+    #[error("Missing required template parameters: {}", .0.join(", "))]
+    MissingParameters(Vec<String>),
+}
+
+/// Why a goal may not leave its current phase yet.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("Phase {phase_name} has not passed its gate: {}", .unmet.join("; "))]
+pub struct PhaseGateError {
+    /// The goal's current phase
+    pub phase: PhaseId,
+    /// Name of that phase
+    pub phase_name: String,
+    /// What still stands in the way, one entry per task
+    pub unmet: Vec<String>,
+}
+
+/// Give a spec without gates those of its own profile or, failing that,
+/// of the default profile of the project owning its phase. A store with
+/// a single project owns every phase.
+pub async fn apply_default_profile(storage: &dyn Storage, spec: &mut TaskSpec) -> Result<(), anyhow::Error> {
+    if !spec.quality_gates.is_empty() {
+        return Ok(());
+    }
+
+    let profile_id = match spec.quality_profile {
+        Some(profile_id) => Some(profile_id),
+        None => {
+            let projects = storage.list_projects().await?;
+            let owner = match projects.iter().find(|p| p.phases.contains(&spec.phase_id)) {
+                Some(project) => Some(project),
+                None if projects.len() == 1 => projects.first(),
+                None => None,
+            };
+            owner.and_then(|p| p.config.default_task_profile)
+        }
+    };
+    if let Some(profile_id) = profile_id {
+        let profile = storage.require_quality_profile(profile_id).await?;
+        spec.quality_gates.extend(profile.gate_for_phase(spec.phase_id));
+    }
+    Ok(())
+}
+
+/// Recompute a parent task's progress from its sub-tasks and save it.
+pub async fn refresh_parent_progress(storage: &mut dyn Storage, parent_id: TaskId) -> Result<(), anyhow::Error> {
+    let mut parent = storage.require_task(parent_id).await?;
+
+    let mut children = Vec::with_capacity(parent.children.len());
+    for child_id in &parent.children {
+        if let Some(child) = storage.load_task(*child_id).await? {
+            children.push(child);
+        }
+    }
+
+    parent.progress = devman_core::TaskProgress::from_children(&children);
+    parent.updated_at = chrono::Utc::now();
+    storage.save_task(&parent).await?;
+    Ok(())
+}
+
+/// A task's timeline from the event log, its work records and its stored
+/// quality runs, oldest first.
+pub async fn task_timeline(
+    storage: &dyn Storage,
+    task_id: TaskId,
+) -> Result<Vec<TimelineEntry>, devman_storage::StorageError> {
+    use devman_core::WorkEventType;
+
+    let mut entries = Vec::new();
+
+    for event in storage.list_events().await? {
+        if event.related_tasks.contains(&task_id) {
+            entries.push(TimelineEntry::StateTransition {
+                timestamp: event.timestamp,
+                action: event.action,
+                result: event.result,
+            });
+        }
+    }
+
+    for record in storage.list_work_records(task_id).await? {
+        for event in record.events {
+            let entry = match event.event_type {
+                WorkEventType::QualityCheckPassed | WorkEventType::QualityCheckFailed => {
+                    TimelineEntry::QualityCheck {
+                        timestamp: event.timestamp,
+                        passed: event.event_type == WorkEventType::QualityCheckPassed,
+                        description: event.description,
+                        data: event.data,
+                    }
+                }
+                event_type => TimelineEntry::WorkLog {
+                    timestamp: event.timestamp,
+                    event_type,
+                    description: event.description,
+                    data: event.data,
+                },
+            };
+            entries.push(entry);
+        }
+
+        for issue in record.issues {
+            entries.push(TimelineEntry::Note {
+                timestamp: issue.discovered_at,
+                text: issue.description,
+            });
+        }
+
+        for resolution in record.resolutions {
+            entries.push(TimelineEntry::Note {
+                timestamp: resolution.applied_at,
+                text: resolution.description,
+            });
+        }
+    }
+
+    for run in storage.list_quality_runs(task_id).await? {
+        let passed = run.results.iter().filter(|r| r.passed).count();
+        let findings: usize = run.results.iter().map(|r| r.findings.len()).sum();
+        entries.push(TimelineEntry::QualityCheck {
+            timestamp: run.run_at,
+            passed: passed == run.results.len(),
+            description: format!("Quality run: {}/{} checks passed", passed, run.results.len()),
+            data: serde_json::json!({"run_id": run.id, "findings": findings}),
+        });
+    }
+
+    entries.sort_by_key(|e| e.timestamp());
+    Ok(entries)
+}
+
+/// Overall status of a task's latest quality run against its effective
+/// gates, and whether that status allows completing the task.
+async fn task_quality_gate_status(
+    storage: &dyn Storage,
+    task: &Task,
+) -> Result<(devman_core::TaskQualityOverallStatus, bool), devman_storage::StorageError> {
+    let profile = match storage.load_phase(task.phase_id).await?.and_then(|p| p.quality_profile) {
+        Some(profile_id) => storage.load_quality_profile(profile_id).await?,
+        None => None,
+    };
+    if task.effective_quality_gates(profile.as_ref()).is_empty() {
+        return Ok((devman_core::TaskQualityOverallStatus::NotChecked, true));
+    }
+
+    let runs = storage.list_quality_runs(task.id).await?;
+    let status = match runs.iter().max_by_key(|r| r.run_at) {
+        None => devman_core::TaskQualityOverallStatus::NotChecked,
+        Some(run) => {
+            let (mut blocking, mut advisory) = (0, 0);
+            for result in run.results.iter().filter(|r| !r.passed) {
+                match storage.load_quality_check(result.check_id).await? {
+                    Some(check) if !check.blocking => advisory += 1,
+                    _ => blocking += 1,
+                }
+            }
+            if blocking > 0 {
+                devman_core::TaskQualityOverallStatus::Failed
+            } else if advisory > 0 {
+                devman_core::TaskQualityOverallStatus::PassedWithWarnings
+            } else {
+                devman_core::TaskQualityOverallStatus::Passed
+            }
+        }
+    };
+    let pass = status.allows_completion();
+    Ok((status, pass))
+}
+
+/// Normalise a project-relative path for comparison.
+fn normalize_path(path: &str) -> String {
+    path.trim().replace('\\', "/").trim_start_matches("./").to_string()
+}
+
+/// Append the items of `from` that `into` does not already contain.
+fn extend_unique<T: PartialEq>(into: &mut Vec<T>, from: impl IntoIterator<Item = T>) {
+    for item in from {
+        if !into.contains(&item) {
+            into.push(item);
+        }
+    }
+}
+
+/// Successful outcome of work closed by a bulk update, which measured nothing.
+fn bulk_work_result(reason: &str) -> WorkResult {
+    WorkResult {
+        status: devman_core::CompletionStatus::Success,
+        outputs: vec![devman_core::Output { name: "summary".to_string(), value: reason.to_string() }],
+        metrics: devman_core::WorkMetrics {
+            token_used: None,
+            time_spent: std::time::Duration::ZERO,
+            tools_invoked: 0,
+            quality_checks_run: 0,
+            quality_checks_passed: 0,
+        },
+        created_knowledge: Vec::new(),
+    }
+}
+
+/// Whether `task` ends up waiting on itself, given what each task waits on.
+fn waits_on_itself(task: TaskId, waits: &HashMap<TaskId, Vec<TaskId>>) -> bool {
+    let mut seen = std::collections::HashSet::new();
+    let mut pending: Vec<TaskId> = waits.get(&task).cloned().unwrap_or_default();
+    while let Some(id) = pending.pop() {
+        if id == task {
+            return true;
+        }
+        if seen.insert(id) {
+            pending.extend(waits.get(&id).into_iter().flatten().copied());
+        }
+    }
+    false
+}
+
+/// Files named in a work record's logged events and artifacts.
+fn work_record_files(record: &WorkRecord) -> impl Iterator<Item = &str> {
+    let logged = record
+        .events
+        .iter()
+        .filter_map(|e| e.data.get("files").and_then(|f| f.as_array()))
+        .flatten()
+        .filter_map(|f| f.as_str());
+    logged.chain(record.artifacts.iter().map(|a| a.location.as_str()))
+}
+
+/// Choose the next task among `tasks`.
+///
+/// A task is ready when it is an idea or queued, every dependency is closed,
+/// it has no open sub-tasks and no agent other than `agent` holds an active
+/// claim on it at `now`. Ready tasks are ranked by how many open tasks they
+/// unblock, then queued before ideas, then oldest first. Returns the chosen
+/// task, its rationale and the number of ready tasks.
+fn select_next_task<'a>(
+    tasks: &'a [Task],
+    agent: Option<&AgentId>,
+    now: devman_core::Time,
+) -> Option<(&'a Task, Vec<String>, usize)> {
+    let by_id: std::collections::HashMap<TaskId, &Task> = tasks.iter().map(|t| (t.id, t)).collect();
+    let is_closed = |id: &TaskId| by_id.get(id).is_some_and(|t| t.is_closed());
+    let claimed_by_other = |t: &Task| {
+        t.claim.as_ref().is_some_and(|c| c.is_active(now) && Some(&c.agent) != agent)
+    };
+
+    let ready: Vec<&Task> = tasks
+        .iter()
+        .filter(|t| !t.archived && matches!(t.status, TaskStatus::Idea | TaskStatus::Queued))
+        .filter(|t| !claimed_by_other(t))
+        .filter(|t| t.depends_on.iter().all(is_closed))
+        .filter(|t| t.children.iter().all(|c| by_id.get(c).is_none_or(|t| t.is_closed())))
+        .collect();
+
+    let unblocks = |task: &Task| -> Vec<&Task> {
+        tasks
+            .iter()
+            .filter(|t| !t.is_closed())
+            .filter(|t| t.depends_on.contains(&task.id) || task.blocks.contains(&t.id))
+            .collect()
+    };
+
+    let best = ready.iter().copied().max_by(|a, b| {
+        unblocks(a)
+            .len()
+            .cmp(&unblocks(b).len())
+            .then_with(|| (a.status == TaskStatus::Queued).cmp(&(b.status == TaskStatus::Queued)))
+            .then_with(|| b.created_at.cmp(&a.created_at))
+    })?;
+
+    let mut rationale = Vec::new();
+    let dependents = unblocks(best);
+    if !dependents.is_empty() {
+        let titles: Vec<_> = dependents.iter().map(|t| t.title.as_str()).collect();
+        rationale.push(format!("Unblocks {} task(s): {}", dependents.len(), titles.join(", ")));
+    }
+    if best.depends_on.is_empty() {
+        rationale.push("Has no dependencies".to_string());
+    } else {
+        rationale.push(format!("All {} dependencies are complete", best.depends_on.len()));
+    }
+    if best.status == TaskStatus::Queued {
+        rationale.push("Already queued for execution".to_string());
+    }
+    rationale.push(format!("Selected from {} ready task(s)", ready.len()));
+
+    Some((best, rationale, ready.len()))
 }
 
 /// Basic AI interface implementation.
@@ -133,6 +1252,10 @@ pub struct BasicAIInterface {
     knowledge_service: Arc<dyn KnowledgeService>,
     quality_engine: Arc<dyn QualityEngine>,
     tool_executor: Arc<dyn devman_tools::ToolExecutor>,
+    blocker_detector: Option<BlockerDetector>,
+    failure_classifier: FailureClassifier,
+    dependency_cascade: bool,
+    clock: Arc<dyn devman_core::Clock>,
 }
 
 impl BasicAIInterface {
@@ -152,8 +1275,207 @@ impl BasicAIInterface {
             knowledge_service,
             quality_engine,
             tool_executor,
+            blocker_detector: None,
+            failure_classifier: FailureClassifier::default(),
+            dependency_cascade: false,
+            clock: Arc::new(devman_core::SystemClock),
         }
     }
+
+    /// Report blockers found by `detector`, including those derived from
+    /// task dependencies, in the project snapshot instead of only recorded
+    /// ones.
+    pub fn with_blocker_detector(mut self, detector: BlockerDetector) -> Self {
+        self.blocker_detector = Some(detector);
+        self
+    }
+
+    /// Classify quality findings with `classifier` instead of the default
+    /// rules.
+    pub fn with_failure_classifier(mut self, classifier: FailureClassifier) -> Self {
+        self.failure_classifier = classifier;
+        self
+    }
+
+    /// Queue blocked tasks again once the last task they depend on is done
+    /// or abandoned for good. Off by default.
+    pub fn with_dependency_cascade(mut self, enabled: bool) -> Self {
+        self.dependency_cascade = enabled;
+        self
+    }
+
+    /// Set the clock used for claim expiry, the idempotency window and work
+    /// record timestamps.
+    pub fn with_clock(mut self, clock: Arc<dyn devman_core::Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Requeue the blocked dependents of a closed task that no longer wait
+    /// on anything, when the dependency cascade is on.
+    ///
+    /// A dependency is settled once done or abandoned for good. Abandoned
+    /// tasks without a recorded reason are taken to be recoverable.
+    async fn release_dependents(&self, closed_id: TaskId) -> Result<CascadeReport, anyhow::Error> {
+        let mut report = CascadeReport::default();
+        if !self.dependency_cascade {
+            return Ok(report);
+        }
+
+        {
+            let mut storage = self.storage.lock().await;
+            let tasks = storage.list_tasks(&devman_core::TaskFilter::default()).await?;
+            let by_id: HashMap<TaskId, &Task> = tasks.iter().map(|t| (t.id, t)).collect();
+            let Some(closed) = by_id.get(&closed_id).copied() else {
+                return Ok(report);
+            };
+            let gone_for_good = |task: &Task| match &task.state {
+                Some(TaskState::Abandoned { reason, .. }) => !reason.is_recoverable(),
+                _ => false,
+            };
+            let settled = |id: &TaskId| {
+                by_id.get(id).is_none_or(|t| match t.status {
+                    TaskStatus::Done => true,
+                    TaskStatus::Abandoned => gone_for_good(t),
+                    _ => false,
+                })
+            };
+            let abandoned = closed.status == TaskStatus::Abandoned;
+            if closed.status != TaskStatus::Done && !abandoned {
+                return Ok(report);
+            }
+
+            for task in tasks.iter().filter(|t| !t.is_closed()) {
+                if !task.depends_on.contains(&closed_id) && !closed.blocks.contains(&task.id) {
+                    continue;
+                }
+                let waits_on: Vec<&TaskId> = task
+                    .depends_on
+                    .iter()
+                    .chain(tasks.iter().filter(|t| t.blocks.contains(&task.id)).map(|t| &t.id))
+                    .collect();
+                if !waits_on.into_iter().all(settled) {
+                    if abandoned && !gone_for_good(closed) {
+                        report.warnings.push(format!(
+                            "'{}' still waits on abandoned task '{}', which may be picked up again",
+                            task.title, closed.title
+                        ));
+                    }
+                    continue;
+                }
+                if task.status != TaskStatus::Blocked {
+                    continue;
+                }
+
+                let mut released = task.clone();
+                released.set_status(TaskStatus::Queued);
+                released.updated_at = chrono::Utc::now();
+                storage.save_task(&released).await?;
+                report.requeued.push(task.id);
+                if abandoned {
+                    report.warnings.push(format!(
+                        "Requeued '{}' although its dependency '{}' was abandoned",
+                        task.title, closed.title
+                    ));
+                }
+            }
+        }
+
+        for task_id in &report.requeued {
+            self.log_task_event(*task_id, "task_unblocked", format!("Dependency {} closed", closed_id))
+                .await?;
+        }
+        Ok(report)
+    }
+
+    /// Record a task state change in the event log.
+    async fn log_task_event(&self, task_id: TaskId, action: &str, result: String) -> Result<(), anyhow::Error> {
+        let mut event = devman_core::Event::new(devman_core::AgentId::ai(), action, result);
+        event.related_tasks.push(task_id);
+        self.storage.lock().await.save_event(&event).await?;
+        Ok(())
+    }
+
+    /// Give a spec without gates those of its own profile or, failing that,
+    /// of its project's default. See [`apply_default_profile`].
+    async fn apply_default_profile(&self, spec: &mut TaskSpec) -> Result<(), anyhow::Error> {
+        apply_default_profile(&*self.storage.lock().await, spec).await
+    }
+
+    /// Link knowledge created while doing a task to the task, to the work
+    /// record it came from and to each other, and add similar earlier
+    /// knowledge as see-also references.
+    async fn link_created_knowledge(&self, task_id: TaskId, created: &[KnowledgeId]) -> Result<(), anyhow::Error> {
+        let (mut items, record) = {
+            let mut storage = self.storage.lock().await;
+            let mut task = storage.require_task(task_id).await?;
+            for id in created {
+                if !task.created_knowledge.contains(id) {
+                    task.created_knowledge.push(*id);
+                }
+            }
+            storage.save_task(&task).await?;
+
+            let mut items = Vec::with_capacity(created.len());
+            for id in created {
+                items.push(storage.require_knowledge(*id).await?);
+            }
+            (items, task.work_records.last().copied())
+        };
+
+        for item in &mut items {
+            let similar = self
+                .knowledge_service
+                .find_similar_knowledge(item, SEE_ALSO_LIMIT + created.len())
+                .await;
+            let see_also = similar
+                .iter()
+                .map(|k| k.id)
+                .filter(|id| !created.contains(id))
+                .take(SEE_ALSO_LIMIT);
+            let siblings = created.iter().copied().filter(|id| *id != item.id);
+            for id in siblings.chain(see_also).collect::<Vec<_>>() {
+                if !item.related_to.contains(&id) {
+                    item.related_to.push(id);
+                }
+            }
+            if let Some(record) = record {
+                if !item.derived_from.contains(&record) {
+                    item.derived_from.push(record);
+                }
+            }
+            item.updated_at = chrono::Utc::now();
+        }
+
+        let mut storage = self.storage.lock().await;
+        for item in &items {
+            storage.save_knowledge(item).await?;
+        }
+        Ok(())
+    }
+
+    /// Recompute a parent task's progress from its sub-tasks.
+    async fn refresh_parent_progress(&self, parent_id: TaskId) -> Result<(), anyhow::Error> {
+        refresh_parent_progress(&mut *self.storage.lock().await, parent_id).await
+    }
+
+    /// Set a task's archived flag and log the change.
+    async fn set_archived(&self, task_id: TaskId, archived: bool) -> Result<Task, anyhow::Error> {
+        let task = {
+            let mut storage = self.storage.lock().await;
+            let mut task = storage
+                .require_task(task_id)
+                .await?;
+            task.archived = archived;
+            task.updated_at = chrono::Utc::now();
+            storage.save_task(&task).await?;
+            task
+        };
+
+        let action = if archived { "task_archived" } else { "task_unarchived" };
+        self.log_task_event(task_id, action, format!("{:?}", task.status)).await?;
+        Ok(task)
+    }
 }
 
 #[async_trait]
@@ -163,7 +1485,60 @@ impl AIInterface for BasicAIInterface {
         WorkManagementContext::new()
     }
 
+    async fn task_context(&self, task_id: TaskId, budget: ContextBudget) -> Result<WorkManagementContext, anyhow::Error> {
+        let (task, phase, dependencies, history) = {
+            let storage = self.storage.lock().await;
+            let task = storage
+                .load_task(task_id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("Task not found: {}", task_id))?;
+            let phase = storage.load_phase(task.phase_id).await?;
+            let mut dependencies = Vec::new();
+            for id in &task.depends_on {
+                if let Some(dependency) = storage.load_task(*id).await? {
+                    dependencies.push(dependency);
+                }
+            }
+            let history: Vec<WorkEvent> = storage
+                .list_work_records(task_id)
+                .await?
+                .into_iter()
+                .flat_map(|r| r.events)
+                .collect();
+            (task, phase, dependencies, history)
+        };
+
+        let knowledge = self
+            .knowledge_service
+            .search_ranked(&task.title, 20)
+            .await
+            .into_iter()
+            .map(|hit| devman_core::ScoredKnowledge { knowledge: hit.knowledge, score: hit.score })
+            .collect();
+
+        let mut context = WorkManagementContext::new()
+            .with_active_task(task)
+            .with_knowledge(knowledge)
+            .with_dependencies(dependencies)
+            .with_history(history);
+        if let Some(phase) = phase {
+            context = context.with_phase(phase);
+        }
+        Ok(context.build(budget))
+    }
+
     async fn create_goal(&self, spec: GoalSpec) -> Result<Goal, anyhow::Error> {
+        // A goal on an existing project starts at the project's first phase
+        let first_phase = match spec.project_id {
+            Some(project_id) => self
+                .storage
+                .lock()
+                .await
+                .load_project(project_id)
+                .await?
+                .and_then(|project| project.phases.first().copied()),
+            None => None,
+        };
         let goal = Goal {
             id: GoalId::new(),
             title: spec.title,
@@ -189,59 +1564,583 @@ impl AIInterface for BasicAIInterface {
                 blockers: Vec::new(),
             },
             project_id: spec.project_id.unwrap_or_else(devman_core::ProjectId::new),
-            current_phase: PhaseId::new(),
+            current_phase: first_phase.unwrap_or_else(PhaseId::new),
             status: devman_core::GoalStatus::Active,
+            status_history: Vec::new(),
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
 
-        self.storage.lock().await.save_goal(&goal).await?;
-        Ok(goal)
-    }
+        self.storage.lock().await.save_goal(&goal).await?;
+        Ok(goal)
+    }
+
+    async fn get_goal(&self, goal_id: GoalId) -> Option<Goal> {
+        self.storage.lock().await.load_goal(goal_id).await.ok().flatten()
+    }
+
+    async fn list_goals(&self, filter: GoalFilter) -> Vec<Goal> {
+        let mut goals = self.storage.lock().await.list_goals().await.unwrap_or_default();
+
+        // Apply filters
+        if let Some(status) = filter.status {
+            goals.retain(|g| g.status == status);
+        }
+
+        // Apply limit
+        if let Some(limit) = filter.limit {
+            goals.truncate(limit);
+        }
+
+        goals
+    }
+
+    async fn set_goal_status(&self, goal_id: GoalId, status: devman_core::GoalStatus) -> Result<Goal, anyhow::Error> {
+        if status == devman_core::GoalStatus::Completed {
+            let unmet: Vec<String> = self
+                .verify_criteria(goal_id)
+                .await?
+                .into_iter()
+                .filter(|v| v.automated && v.status != devman_core::CriterionStatus::Met)
+                .map(|v| v.description)
+                .collect();
+            if !unmet.is_empty() {
+                anyhow::bail!("Goal has unmet success criteria: {}", unmet.join(", "));
+            }
+        }
+
+        let mut storage = self.storage.lock().await;
+        let mut goal = storage
+            .require_goal(goal_id)
+            .await?;
+        goal.transition(status)?;
+        storage.save_goal(&goal).await?;
+        Ok(goal)
+    }
+
+    async fn reopen_goal(&self, goal_id: GoalId) -> Result<Goal, anyhow::Error> {
+        let mut storage = self.storage.lock().await;
+        let mut goal = storage
+            .require_goal(goal_id)
+            .await?;
+        goal.reopen()?;
+        storage.save_goal(&goal).await?;
+        Ok(goal)
+    }
+
+    async fn verify_criteria(&self, goal_id: GoalId) -> Result<Vec<CriterionVerification>, anyhow::Error> {
+        let goal = self.storage.lock().await.require_goal(goal_id).await?;
+
+        let mut results = Vec::new();
+        for criterion in goal.success_criteria {
+            let VerificationMethod::Command(spec) = criterion.verification else {
+                results.push(CriterionVerification {
+                    criterion_id: criterion.id,
+                    description: criterion.description,
+                    automated: false,
+                    status: criterion.status,
+                    exit_code: None,
+                    output: None,
+                });
+                continue;
+            };
+
+            let input = ToolInput {
+                args: spec.args.clone(),
+                env: Default::default(),
+                stdin: None,
+                timeout: Some(spec.timeout),
+                work_dir: None,
+            };
+            let output = self.execute_tool(spec.command.clone(), input, &devman_tools::CancellationToken::new()).await;
+            // Without an expected code any exit counts, but a command that
+            // failed to start or was killed by a signal did not pass
+            let met = match spec.expected_exit_code {
+                Some(code) => output.exit_code == code,
+                None => output.exit_code >= 0,
+            };
+            let text = if output.stderr.is_empty() {
+                output.stdout
+            } else {
+                format!("{}\n{}", output.stdout, output.stderr)
+            };
+            results.push(CriterionVerification {
+                criterion_id: criterion.id,
+                description: criterion.description,
+                automated: true,
+                status: if met {
+                    devman_core::CriterionStatus::Met
+                } else {
+                    devman_core::CriterionStatus::NotMet
+                },
+                exit_code: Some(output.exit_code),
+                output: Some(devman_core::truncate_output(&text, devman_core::DEFAULT_MAX_OUTPUT_LEN)),
+            });
+        }
+
+        // Reload so edits made while the commands ran are kept
+        let mut storage = self.storage.lock().await;
+        let mut goal = storage.require_goal(goal_id).await?;
+        let mut changed = false;
+        for verification in results.iter().filter(|v| v.automated) {
+            if let Some(criterion) = goal.success_criteria.iter_mut().find(|c| c.id == verification.criterion_id) {
+                changed |= criterion.status != verification.status;
+                criterion.status = verification.status;
+            }
+        }
+        if changed {
+            goal.updated_at = chrono::Utc::now();
+            storage.save_goal(&goal).await?;
+        }
+
+        Ok(results)
+    }
+
+    async fn search_knowledge(&self, query: &str) -> Vec<Knowledge> {
+        self.knowledge_service.search_semantic(query, 10).await
+    }
+
+    async fn search_knowledge_ranked(&self, query: &str, limit: usize) -> Vec<KnowledgeHit> {
+        self.knowledge_service.search_ranked(query, limit).await
+    }
+
+    async fn get_best_practices(&self, domain: &str) -> Vec<Knowledge> {
+        self.knowledge_service.get_best_practices(domain).await
+    }
+
+    async fn get_knowledge(&self, knowledge_id: KnowledgeId) -> Option<Knowledge> {
+        self.storage.lock().await.load_knowledge(knowledge_id).await.ok().flatten()
+    }
+
+    async fn recent_knowledge(&self, limit: usize) -> Vec<Knowledge> {
+        self.knowledge_service.list_recent(limit).await
+    }
+
+    async fn rate_knowledge(&self, knowledge_id: KnowledgeId, feedback: Feedback) -> Result<Knowledge, anyhow::Error> {
+        self.knowledge_service.submit_feedback(knowledge_id, feedback).await
+    }
+
+    async fn knowledge_statistics(&self) -> Result<KnowledgeStats, anyhow::Error> {
+        self.knowledge_service.statistics().await
+    }
+
+    async fn get_progress(&self, goal_id: GoalId) -> Option<GoalProgress> {
+        self.progress_tracker.get_goal_progress(goal_id).await
+    }
+
+    async fn detect_criterion_conflicts(&self, goal_id: GoalId) -> Vec<CriterionConflict> {
+        let storage = self.storage.lock().await;
+        let Ok(Some(goal)) = storage.load_goal(goal_id).await else {
+            return Vec::new();
+        };
+        let tasks = match goal_tasks(&*storage, &goal).await {
+            Ok(tasks) => tasks,
+            Err(e) => {
+                tracing::warn!("Failed to load tasks of goal {}: {}", goal_id, e);
+                return Vec::new();
+            }
+        };
+        let open: Vec<&Task> = tasks.iter().filter(|t| !t.archived && !t.is_closed()).collect();
+
+        let mut conflicts = Vec::new();
+        for criterion in &goal.success_criteria {
+            let mut claims: Vec<CriterionClaim> = open
+                .iter()
+                .filter_map(|task| {
+                    let (task_criterion, score) = task
+                        .intent
+                        .success_criteria
+                        .iter()
+                        .map(|text| (text, criterion.match_score(text)))
+                        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+                    (score >= devman_core::CRITERION_MATCH_THRESHOLD).then(|| CriterionClaim {
+                        task_id: task.id,
+                        title: task.title.clone(),
+                        task_criterion: task_criterion.clone(),
+                        score,
+                    })
+                })
+                .collect();
+            if claims.len() > 1 {
+                claims.sort_by(|a, b| b.score.total_cmp(&a.score));
+                conflicts.push(CriterionConflict {
+                    criterion_id: criterion.id,
+                    description: criterion.description.clone(),
+                    claims,
+                });
+            }
+        }
+        conflicts
+    }
+
+    async fn get_goal_tree(&self, goal_id: GoalId) -> Option<GoalTree> {
+        let storage = self.storage.lock().await;
+        let goal = storage.load_goal(goal_id).await.ok().flatten()?;
+        let phase_ids = match storage.load_project(goal.project_id).await {
+            Ok(Some(project)) => project.phases,
+            _ => Vec::new(),
+        };
+
+        let mut seen = std::collections::HashSet::new();
+        let mut phases = Vec::new();
+        for phase_id in phase_ids {
+            let Ok(Some(phase)) = storage.load_phase(phase_id).await else {
+                continue;
+            };
+
+            let mut tasks = Vec::new();
+            for task_id in &phase.tasks {
+                let Ok(Some(task)) = storage.load_task(*task_id).await else {
+                    continue;
+                };
+                // Sub-tasks listed in the phase are shown under their parent
+                if task.parent.is_some_and(|p| phase.tasks.contains(&p)) || seen.contains(&task.id) {
+                    continue;
+                }
+                tasks.push(build_task_node(&*storage, task, &mut seen).await);
+            }
+
+            phases.push(PhaseNode {
+                id: phase.id,
+                name: phase.name,
+                status: phase.status,
+                percentage: mean_percentage(tasks.iter().map(|t| t.percentage)),
+                completed_tasks: tasks
+                    .iter()
+                    .filter(|t| matches!(t.status, TaskStatus::Done | TaskStatus::Abandoned))
+                    .count(),
+                total_tasks: tasks.len(),
+                tasks,
+            });
+        }
 
-    async fn get_goal(&self, goal_id: GoalId) -> Option<Goal> {
-        self.storage.lock().await.load_goal(goal_id).await.ok().flatten()
+        let percentage = mean_percentage(
+            phases
+                .iter()
+                .flat_map(|p| p.tasks.iter().map(|t| t.percentage))
+                .collect::<Vec<_>>()
+                .into_iter(),
+        );
+
+        Some(GoalTree {
+            id: goal.id,
+            title: goal.title,
+            status: goal.status,
+            percentage,
+            phases,
+        })
     }
 
-    async fn list_goals(&self, filter: GoalFilter) -> Vec<Goal> {
-        let mut goals = self.storage.lock().await.list_goals().await.unwrap_or_default();
+    async fn insert_phase(
+        &self,
+        project_id: ProjectId,
+        after: Option<PhaseId>,
+        spec: PhaseSpec,
+    ) -> Result<Phase, anyhow::Error> {
+        let mut storage = self.storage.lock().await;
+        let mut project = storage.require_project(project_id).await?;
+
+        let index = match after {
+            Some(after) => {
+                project.phases.iter().position(|id| *id == after).ok_or(PhaseOrderError::Unknown(after))? + 1
+            }
+            None => 0,
+        };
+        if let Some(unknown) = spec.depends_on.iter().find(|id| !project.phases.contains(id)) {
+            return Err(PhaseOrderError::Unknown(*unknown).into());
+        }
 
-        // Apply filters
-        if let Some(status) = filter.status {
-            goals.retain(|g| g.status == status);
+        let phase = Phase {
+            id: PhaseId::new(),
+            name: spec.name,
+            description: spec.description,
+            objectives: spec.objectives,
+            acceptance_criteria: Vec::new(),
+            tasks: Vec::new(),
+            depends_on: spec.depends_on,
+            status: devman_core::PhaseStatus::NotStarted,
+            progress: devman_core::PhaseProgress::default(),
+            estimated_duration: None,
+            actual_duration: None,
+            quality_profile: None,
+            created_at: chrono::Utc::now(),
+        };
+
+        let mut phases = Vec::with_capacity(project.phases.len() + 1);
+        for id in &project.phases {
+            phases.push(storage.require_phase(*id).await?);
         }
+        phases.push(phase.clone());
+        let mut order = project.phases.clone();
+        order.insert(index, phase.id);
+        validate_phase_order(&order, &phases)?;
+
+        storage.save_phase(&phase).await?;
+        project.phases = order;
+        storage.save_project(&project).await?;
+        Ok(phase)
+    }
 
-        // Apply limit
-        if let Some(limit) = filter.limit {
-            goals.truncate(limit);
+    async fn reorder_phases(&self, project_id: ProjectId, new_order: Vec<PhaseId>) -> Result<Project, anyhow::Error> {
+        let mut storage = self.storage.lock().await;
+        let mut project = storage.require_project(project_id).await?;
+
+        let mut phases = Vec::with_capacity(project.phases.len());
+        for id in &project.phases {
+            phases.push(storage.require_phase(*id).await?);
         }
+        validate_phase_order(&new_order, &phases)?;
 
-        goals
+        project.phases = new_order;
+        storage.save_project(&project).await?;
+        Ok(project)
     }
 
-    async fn search_knowledge(&self, query: &str) -> Vec<Knowledge> {
-        self.knowledge_service.search_semantic(query, 10).await
-    }
+    async fn advance_phase(&self, goal_id: GoalId) -> Result<PhaseId, anyhow::Error> {
+        let (current, next) = {
+            let storage = self.storage.lock().await;
+            let goal = storage.require_goal(goal_id).await?;
+            if goal.status != devman_core::GoalStatus::Active {
+                anyhow::bail!("Goal {} is {:?}; only active goals advance", goal_id, goal.status);
+            }
+            let project = storage.require_project(goal.project_id).await?;
+            let index = project
+                .phases
+                .iter()
+                .position(|id| *id == goal.current_phase)
+                .ok_or(PhaseOrderError::Unknown(goal.current_phase))?;
+            let current = storage.require_phase(goal.current_phase).await?;
+
+            let mut unmet = Vec::new();
+            for task_id in &current.tasks {
+                let Some(task) = storage.load_task(*task_id).await? else {
+                    continue;
+                };
+                if task.merged_into.is_some() {
+                    continue;
+                }
+                if !task.is_closed() {
+                    unmet.push(format!("Task '{}' is {:?}", task.title, task.status));
+                } else if task.status == TaskStatus::Done {
+                    let (status, pass) = task_quality_gate_status(&*storage, &task).await?;
+                    if !pass {
+                        unmet.push(format!("Task '{}' has not passed its quality gates ({:?})", task.title, status));
+                    }
+                }
+            }
+            if !unmet.is_empty() {
+                return Err(PhaseGateError { phase: current.id, phase_name: current.name, unmet }.into());
+            }
+            (current, project.phases.get(index + 1).copied())
+        };
 
-    async fn get_best_practices(&self, domain: &str) -> Vec<Knowledge> {
-        self.knowledge_service.get_best_practices(domain).await
+        let Some(next) = next else {
+            self.set_goal_status(goal_id, devman_core::GoalStatus::Completed).await?;
+            let mut storage = self.storage.lock().await;
+            let mut phase = current;
+            phase.status = devman_core::PhaseStatus::Completed;
+            storage.save_phase(&phase).await?;
+            return Ok(phase.id);
+        };
+
+        let mut storage = self.storage.lock().await;
+        let mut phase = current;
+        phase.status = devman_core::PhaseStatus::Completed;
+        storage.save_phase(&phase).await?;
+        let mut upcoming = storage.require_phase(next).await?;
+        if upcoming.status == devman_core::PhaseStatus::NotStarted {
+            upcoming.status = devman_core::PhaseStatus::InProgress;
+            storage.save_phase(&upcoming).await?;
+        }
+        let mut goal = storage.require_goal(goal_id).await?;
+        goal.current_phase = next;
+        goal.updated_at = chrono::Utc::now();
+        storage.save_goal(&goal).await?;
+        Ok(next)
     }
 
-    async fn get_progress(&self, goal_id: GoalId) -> Option<GoalProgress> {
-        self.progress_tracker.get_goal_progress(goal_id).await
+    async fn set_active_phase(&self, goal_id: GoalId, phase_id: PhaseId) -> Result<Goal, anyhow::Error> {
+        let mut storage = self.storage.lock().await;
+        let mut goal = storage.require_goal(goal_id).await?;
+        let project = storage.require_project(goal.project_id).await?;
+        if !project.phases.contains(&phase_id) {
+            return Err(PhaseOrderError::Unknown(phase_id).into());
+        }
+
+        let mut phase = storage.require_phase(phase_id).await?;
+        if phase.status == devman_core::PhaseStatus::NotStarted {
+            phase.status = devman_core::PhaseStatus::InProgress;
+            storage.save_phase(&phase).await?;
+        }
+        goal.current_phase = phase_id;
+        goal.updated_at = chrono::Utc::now();
+        storage.save_goal(&goal).await?;
+        Ok(goal)
     }
 
     async fn list_blockers(&self) -> Vec<devman_core::Blocker> {
-        // TODO: Implement blocker detection
-        Vec::new()
+        if let Some(detector) = &self.blocker_detector {
+            return detector.detect_and_analyze().await.blockers;
+        }
+        let mut blockers = self.storage.lock().await.list_blockers().await.unwrap_or_default();
+        blockers.retain(|b| !b.is_resolved());
+        blockers
     }
 
-    async fn create_task(&self, spec: TaskSpec) -> Result<Task, anyhow::Error> {
-        self.work_manager
+    async fn add_blocker(
+        &self,
+        item: BlockedItem,
+        reason: &str,
+        severity: Severity,
+    ) -> Result<Blocker, anyhow::Error> {
+        if reason.trim().is_empty() {
+            anyhow::bail!("Blocker reason must not be empty");
+        }
+        let blocker = Blocker::external(item, reason, severity);
+        {
+            let mut storage = self.storage.lock().await;
+            match blocker.blocked_item {
+                BlockedItem::Task(id) => {
+                    storage.require_task(id).await?;
+                }
+                BlockedItem::Phase(id) => {
+                    storage.require_phase(id).await?;
+                }
+                BlockedItem::Goal(id) => {
+                    storage.require_goal(id).await?;
+                }
+            }
+            storage.save_blocker(&blocker).await?;
+        }
+        if let BlockedItem::Task(task_id) = blocker.blocked_item {
+            self.log_task_event(task_id, "blocker_added", format!("{}: {}", blocker.id, reason)).await?;
+        }
+        Ok(blocker)
+    }
+
+    async fn resolve_blocker(&self, blocker_id: BlockerId) -> Result<Blocker, anyhow::Error> {
+        let blocker = {
+            let mut storage = self.storage.lock().await;
+            let mut blocker = storage.require_blocker(blocker_id).await?;
+            if blocker.is_resolved() {
+                anyhow::bail!("Blocker {} is already resolved", blocker_id);
+            }
+            blocker.resolved_at = Some(chrono::Utc::now());
+            storage.save_blocker(&blocker).await?;
+            blocker
+        };
+        if let BlockedItem::Task(task_id) = blocker.blocked_item {
+            self.log_task_event(task_id, "blocker_resolved", format!("{}: {}", blocker.id, blocker.reason)).await?;
+        }
+        Ok(blocker)
+    }
+
+    async fn create_task(&self, mut spec: TaskSpec) -> Result<Task, anyhow::Error> {
+        self.apply_default_profile(&mut spec).await?;
+        let task = self.work_manager
             .lock()
             .await
             .create_task(spec)
+            .await?;
+        self.log_task_event(task.id, "task_created", format!("{:?}", task.status)).await?;
+        Ok(task)
+    }
+
+    async fn find_duplicate_tasks(
+        &self,
+        spec: &TaskSpec,
+        threshold: f32,
+    ) -> Result<Option<DuplicateWarning>, anyhow::Error> {
+        let tasks = self.storage.lock().await.list_tasks(&Default::default()).await?;
+        Ok(devman_work::find_duplicates(spec, &tasks, threshold))
+    }
+
+    async fn create_subtask(&self, parent_id: TaskId, mut spec: TaskSpec) -> Result<Task, anyhow::Error> {
+        let mut parent = self
+            .storage
+            .lock()
             .await
+            .require_task(parent_id)
+            .await?;
+        if parent.is_closed() {
+            anyhow::bail!("Cannot add a sub-task to task {}; it is {:?}", parent_id, parent.status);
+        }
+
+        spec.phase_id = parent.phase_id;
+        let mut child = self.create_task(spec).await?;
+        parent.add_child(&mut child);
+
+        {
+            let mut storage = self.storage.lock().await;
+            storage.save_task(&child).await?;
+            storage.save_task(&parent).await?;
+        }
+
+        self.refresh_parent_progress(parent_id).await?;
+        Ok(child)
+    }
+
+    async fn create_task_from_template(
+        &self,
+        template_id: TaskTemplateId,
+        phase_id: PhaseId,
+        params: HashMap<String, String>,
+    ) -> Result<Task, anyhow::Error> {
+        let template = self.storage.lock().await.require_task_template(template_id).await?;
+        let parameters: Vec<devman_knowledge::template::TemplateParameter> =
+            template.parameters.iter().cloned().map(Into::into).collect();
+        let values = devman_knowledge::template::resolve_parameters(&parameters, &params)
+            .map_err(|validation| TemplateError::MissingParameters(validation.missing_required))?;
+        let rendered = template.map_text(|text| devman_knowledge::template::fill_placeholders(text, &values));
+
+        let spec = TaskSpec {
+            title: rendered.title,
+            description: rendered.description.clone(),
+            intent: devman_core::TaskIntent {
+                natural_language: rendered.description,
+                context: devman_core::TaskContext {
+                    relevant_knowledge: Vec::new(),
+                    similar_tasks: Vec::new(),
+                    affected_files: Vec::new(),
+                },
+                success_criteria: rendered.success_criteria,
+            },
+            phase_id,
+            quality_gates: rendered.quality_gates,
+            priority: devman_core::Priority::from_value(rendered.priority),
+            quality_profile: None,
+            due_at: None,
+        };
+        let mut task = self.create_task(spec).await?;
+        if !rendered.steps.is_empty() {
+            task.steps = rendered.steps;
+            self.storage.lock().await.save_task(&task).await?;
+        }
+        Ok(task)
+    }
+
+    async fn find_idempotent_entity(&self, key: &str, window: chrono::Duration) -> Option<String> {
+        let record = self.storage.lock().await.load_idempotency_record(key).await.ok().flatten()?;
+        (record.created_at >= self.clock.now() - window).then_some(record.entity_id)
+    }
+
+    async fn remember_idempotent_entity(
+        &self,
+        key: &str,
+        entity_id: &str,
+        window: chrono::Duration,
+    ) -> Result<(), anyhow::Error> {
+        let now = self.clock.now();
+        let mut storage = self.storage.lock().await;
+        storage.purge_idempotency_records(now - window).await?;
+        storage
+            .save_idempotency_record(&devman_storage::IdempotencyRecord {
+                key: key.to_string(),
+                entity_id: entity_id.to_string(),
+                created_at: now,
+            })
+            .await?;
+        Ok(())
     }
 
     async fn get_task(&self, task_id: TaskId) -> Option<Task> {
@@ -249,42 +2148,982 @@ impl AIInterface for BasicAIInterface {
     }
 
     async fn list_tasks(&self, filter: TaskFilter) -> Vec<Task> {
-        let storage_filter = devman_core::TaskFilter::default();
-        let mut tasks = self.storage.lock().await.list_tasks(&storage_filter).await.unwrap_or_default();
+        let storage_filter = devman_core::TaskFilter {
+            roots_only: filter.roots_only,
+            include_subtasks: filter.include_subtasks,
+            ..Default::default()
+        };
+        let tasks = self.storage.lock().await.list_tasks(&storage_filter).await.unwrap_or_default();
+
+        let matches = |t: &Task| {
+            filter.status.is_none_or(|status| t.status == status)
+                && (filter.include_completed || t.status != devman_core::TaskStatus::Done)
+                && (filter.include_archived || !t.archived)
+                && filter.min_priority.is_none_or(|min| t.priority_level().value() >= min.value())
+                && filter.created_before.is_none_or(|before| t.created_at < before)
+        };
+        let limit = filter.limit.unwrap_or(usize::MAX);
 
-        // Apply filters
-        if let Some(status) = filter.status {
-            tasks.retain(|t| t.status == status);
+        if !(filter.roots_only && filter.include_subtasks) {
+            return tasks.into_iter().filter(|t| matches(t)).take(limit).collect();
         }
 
-        if !filter.include_completed {
-            tasks.retain(|t| t.status != devman_core::TaskStatus::Done);
+        // Each root comes right before its subtree, so it decides for the
+        // sub-tasks that follow it
+        let mut roots = 0;
+        let mut keep_subtree = false;
+        tasks
+            .into_iter()
+            .filter(|t| {
+                if t.parent.is_none() {
+                    keep_subtree = roots < limit && matches(t);
+                    roots += usize::from(keep_subtree);
+                }
+                keep_subtree
+            })
+            .collect()
+    }
+
+    async fn archive_task(&self, task_id: TaskId) -> Result<Task, anyhow::Error> {
+        self.set_archived(task_id, true).await
+    }
+
+    async fn unarchive_task(&self, task_id: TaskId) -> Result<Task, anyhow::Error> {
+        self.set_archived(task_id, false).await
+    }
+
+    async fn delete_task(&self, task_id: TaskId, force: bool) -> Result<(), anyhow::Error> {
+        if !force {
+            anyhow::bail!("Refusing to permanently delete task {} without force; archive it instead", task_id);
         }
 
-        // Apply limit
-        if let Some(limit) = filter.limit {
-            tasks.truncate(limit);
+        let parent = {
+            let mut storage = self.storage.lock().await;
+            let task = storage.require_task(task_id).await?;
+            let now = chrono::Utc::now();
+            let mut parent = None;
+
+            // Drop every reference to the task so none is left dangling;
+            // its sub-tasks become top-level tasks
+            for mut other in storage.list_tasks(&Default::default()).await? {
+                if other.id == task_id {
+                    continue;
+                }
+                if task.parent == Some(other.id) {
+                    parent = Some(other.id);
+                }
+                let mut changed = false;
+                for ids in [&mut other.children, &mut other.depends_on, &mut other.blocks] {
+                    if ids.contains(&task_id) {
+                        ids.retain(|id| *id != task_id);
+                        changed = true;
+                    }
+                }
+                if other.parent == Some(task_id) {
+                    other.parent = None;
+                    changed = true;
+                }
+                if other.merged_into == Some(task_id) {
+                    other.merged_into = None;
+                    changed = true;
+                }
+                if changed {
+                    other.updated_at = now;
+                    storage.save_task(&other).await?;
+                }
+            }
+
+            storage.delete_task(task_id).await?;
+            parent
+        };
+
+        if let Some(parent_id) = parent {
+            self.refresh_parent_progress(parent_id).await?;
+        }
+        Ok(())
+    }
+
+    async fn merge_tasks(&self, keep: TaskId, merge: TaskId) -> Result<(), anyhow::Error> {
+        if keep == merge {
+            anyhow::bail!("Cannot merge task {} into itself", keep);
         }
 
-        tasks
+        {
+            let mut storage = self.storage.lock().await;
+            let mut survivor = storage.require_task(keep).await?;
+            let mut merged = storage.require_task(merge).await?;
+            if let Some(target) = merged.merged_into {
+                anyhow::bail!("Task {} was already merged into {}", merge, target);
+            }
+            let now = chrono::Utc::now();
+
+            let not_merged = |id: &TaskId| *id != keep && *id != merge;
+            extend_unique(&mut survivor.depends_on, merged.depends_on.iter().copied().filter(not_merged));
+            survivor.depends_on.retain(not_merged);
+            extend_unique(&mut survivor.blocks, merged.blocks.iter().copied().filter(not_merged));
+            survivor.blocks.retain(not_merged);
+
+            // Other tasks see the survivor wherever they named the merged task
+            let as_kept = |id: TaskId| if id == merge { keep } else { id };
+            let mut waits: HashMap<TaskId, Vec<TaskId>> = HashMap::new();
+            let others = storage.list_tasks(&Default::default()).await?;
+            for task in others.iter().filter(|t| not_merged(&t.id)).chain([&survivor]) {
+                waits.entry(task.id).or_default().extend(task.depends_on.iter().copied().map(as_kept));
+                for blocked in &task.blocks {
+                    waits.entry(as_kept(*blocked)).or_default().push(task.id);
+                }
+            }
+            if waits_on_itself(keep, &waits) {
+                anyhow::bail!("Merging {} into {} would create a dependency cycle", merge, keep);
+            }
+
+            extend_unique(&mut survivor.created_knowledge, merged.created_knowledge.iter().copied());
+            extend_unique(&mut survivor.intent.success_criteria, merged.intent.success_criteria.iter().cloned());
+            let (context, other) = (&mut survivor.intent.context, &merged.intent.context);
+            extend_unique(&mut context.affected_files, other.affected_files.iter().cloned());
+            extend_unique(&mut context.relevant_knowledge, other.relevant_knowledge.iter().copied());
+            extend_unique(&mut context.similar_tasks, other.similar_tasks.iter().copied().filter(not_merged));
+            if merged.description.trim().chars().count() > survivor.description.trim().chars().count() {
+                survivor.description = merged.description.clone();
+            }
+            // Keep the higher base too, or recomputing would undo the raise
+            let base = |task: &Task| task.base_priority.unwrap_or(task.priority);
+            survivor.base_priority = Some(base(&survivor).max(base(&merged)));
+            survivor.priority = survivor.priority.max(merged.priority);
+
+            // The survivor's own records go last so its active one stays current
+            for record_id in &merged.work_records {
+                if let Some(mut record) = storage.load_work_record(*record_id).await? {
+                    record.task_id = keep;
+                    storage.save_work_record(&record).await?;
+                }
+            }
+            let mut records = std::mem::take(&mut merged.work_records);
+            records.retain(|id| !survivor.work_records.contains(id));
+            records.append(&mut survivor.work_records);
+            survivor.work_records = records;
+
+            // Absorbing its own parent or child must not leave the survivor
+            // linked to the archived task
+            if survivor.parent == Some(merge) {
+                survivor.parent = None;
+            }
+            survivor.children.retain(|id| *id != merge);
+            for child_id in std::mem::take(&mut merged.children) {
+                if child_id == keep {
+                    continue;
+                }
+                if let Some(mut child) = storage.load_task(child_id).await? {
+                    child.parent = Some(keep);
+                    child.updated_at = now;
+                    storage.save_task(&child).await?;
+                }
+                extend_unique(&mut survivor.children, [child_id]);
+            }
+            if let Some(parent_id) = merged.parent.filter(|id| *id != keep) {
+                if let Some(mut parent) = storage.load_task(parent_id).await? {
+                    parent.children.retain(|id| *id != merge);
+                    if survivor.parent.is_none() {
+                        survivor.parent = Some(parent_id);
+                        extend_unique(&mut parent.children, [keep]);
+                    }
+                    parent.updated_at = now;
+                    storage.save_task(&parent).await?;
+                }
+            }
+
+            for mut task in storage.list_tasks(&Default::default()).await? {
+                if !not_merged(&task.id) || (!task.depends_on.contains(&merge) && !task.blocks.contains(&merge)) {
+                    continue;
+                }
+                for ids in [&mut task.depends_on, &mut task.blocks] {
+                    if ids.contains(&merge) {
+                        ids.retain(|id| *id != merge);
+                        extend_unique(ids, [keep]);
+                    }
+                }
+                task.updated_at = now;
+                storage.save_task(&task).await?;
+            }
+
+            survivor.updated_at = now;
+            storage.save_task(&survivor).await?;
+            merged.archived = true;
+            merged.merged_into = Some(keep);
+            merged.updated_at = now;
+            storage.save_task(&merged).await?;
+        }
+
+        self.log_task_event(keep, "task_merged", format!("Absorbed {}", merge)).await?;
+        self.log_task_event(merge, "task_merged", format!("Merged into {}", keep)).await?;
+        Ok(())
+    }
+
+    async fn reopen_task(&self, task_id: TaskId, reason: &str) -> Result<Vec<GoalId>, anyhow::Error> {
+        let (task, previous, reopened_goals) = {
+            let mut storage = self.storage.lock().await;
+            let mut task = storage.require_task(task_id).await?;
+            if !task.is_closed() {
+                anyhow::bail!("Only done or abandoned tasks can be reopened; task is {:?}", task.status);
+            }
+
+            let mut children = Vec::with_capacity(task.children.len());
+            for child_id in &task.children {
+                if let Some(child) = storage.load_task(*child_id).await? {
+                    children.push(child);
+                }
+            }
+
+            let previous = task.status;
+            task.set_status(TaskStatus::Queued);
+            task.progress = if children.is_empty() {
+                devman_core::TaskProgress {
+                    total_steps: task.progress.total_steps,
+                    ..Default::default()
+                }
+            } else {
+                devman_core::TaskProgress::from_children(&children)
+            };
+            task.updated_at = chrono::Utc::now();
+            storage.save_task(&task).await?;
+
+            let mut reopened_goals = Vec::new();
+            for mut goal in storage.list_goals().await? {
+                if goal.status != devman_core::GoalStatus::Completed {
+                    continue;
+                }
+                let Ok(Some(project)) = storage.load_project(goal.project_id).await else {
+                    continue;
+                };
+                if project.phases.contains(&task.phase_id) {
+                    goal.reopen()?;
+                    storage.save_goal(&goal).await?;
+                    reopened_goals.push(goal.id);
+                }
+            }
+            (task, previous, reopened_goals)
+        };
+
+        self.log_task_event(task_id, "task_reopened", format!("{:?} -> {:?}: {}", previous, task.status, reason))
+            .await?;
+        for goal_id in &reopened_goals {
+            tracing::warn!("Reopening task {} reactivated completed goal {}", task_id, goal_id);
+        }
+        if let Some(parent_id) = task.parent {
+            self.refresh_parent_progress(parent_id).await?;
+        }
+        Ok(reopened_goals)
     }
 
     async fn start_task(&self, task_id: TaskId) -> Result<WorkRecord, anyhow::Error> {
-        self.work_manager
+        let record = self.work_manager
             .lock()
             .await
             .execute_task(task_id, devman_work::Executor::AI {
                 model: "default".to_string(),
             })
-            .await
+            .await?;
+        self.log_task_event(task_id, "task_started", format!("{:?}", TaskStatus::Active)).await?;
+        Ok(record)
     }
 
-    async fn complete_task(&self, task_id: TaskId, result: WorkResult) -> Result<(), anyhow::Error> {
+    async fn complete_task(&self, task_id: TaskId, result: WorkResult) -> Result<CascadeReport, anyhow::Error> {
+        let task = self
+            .storage
+            .lock()
+            .await
+            .require_task(task_id)
+            .await?;
+
+        {
+            let storage = self.storage.lock().await;
+            let mut open = 0;
+            for child_id in &task.children {
+                match storage.load_task(*child_id).await? {
+                    Some(child) if child.is_closed() => {}
+                    Some(_) => open += 1,
+                    None => {}
+                }
+            }
+            if open > 0 {
+                anyhow::bail!("Task has {} open sub-task(s)", open);
+            }
+        }
+
+        {
+            let storage = self.storage.lock().await;
+            for id in &result.created_knowledge {
+                storage.require_knowledge(*id).await?;
+            }
+        }
+
+        let status = result.status.clone();
+        let metrics = result.metrics.clone();
+        let created_knowledge = result.created_knowledge.clone();
         self.work_manager
             .lock()
             .await
             .complete_task(task_id, result)
+            .await?;
+        match &status {
+            devman_core::CompletionStatus::Interrupted { reason, .. } => {
+                self.log_task_event(task_id, "task_interrupted", reason.clone()).await?
+            }
+            _ => self.log_task_event(task_id, "task_completed", format!("{:?}", status)).await?,
+        }
+
+        // Capture actuals from the work metrics, unless recorded by hand.
+        // Interrupted work only measured part of the task, so it would skew
+        // the estimates calibrated from these actuals
+        let has_metrics = metrics.time_spent > std::time::Duration::ZERO || metrics.token_used.is_some();
+        let manual = matches!(&task.actuals, Some(a) if a.source == devman_core::ActualsSource::Manual);
+        if has_metrics && !manual && !status.is_interrupted() {
+            let mut storage = self.storage.lock().await;
+            let mut completed = storage.require_task(task_id).await?;
+            completed.actuals = Some(devman_core::TaskActuals {
+                duration: metrics.time_spent,
+                tokens: metrics.token_used,
+                source: devman_core::ActualsSource::Metrics,
+                recorded_at: chrono::Utc::now(),
+            });
+            storage.save_task(&completed).await?;
+        }
+
+        if !created_knowledge.is_empty() {
+            self.link_created_knowledge(task_id, &created_knowledge).await?;
+        }
+
+        if let Some(parent_id) = task.parent {
+            self.refresh_parent_progress(parent_id).await?;
+        }
+        self.release_dependents(task_id).await
+    }
+
+    async fn abandon_task(&self, task_id: TaskId, reason: AbandonReason) -> Result<CascadeReport, anyhow::Error> {
+        let task = {
+            let mut storage = self.storage.lock().await;
+            let mut task = storage.require_task(task_id).await?;
+            if !task.status.can_transition_to(TaskStatus::Abandoned) {
+                anyhow::bail!("Cannot change status from {:?} to {:?}", task.status, TaskStatus::Abandoned);
+            }
+            let now = chrono::Utc::now();
+            task.status = TaskStatus::Abandoned;
+            task.state = Some(TaskState::Abandoned { abandoned_at: now, reason: reason.clone() });
+            task.updated_at = now;
+            storage.save_task(&task).await?;
+
+            // Work still running on the task was cut short, not finished
+            if let Some(record_id) = task.work_records.last().copied() {
+                if let Some(mut record) = storage.load_work_record(record_id).await? {
+                    if record.result.status == devman_core::CompletionStatus::Running {
+                        record.completed_at = Some(now);
+                        record.duration = Some(now - record.started_at);
+                        record.result.status = devman_core::CompletionStatus::Interrupted {
+                            at: now,
+                            reason: format!("Task abandoned: {:?}", reason),
+                        };
+                        storage.save_work_record(&record).await?;
+                    }
+                }
+            }
+            task
+        };
+        self.log_task_event(task_id, "task_abandoned", format!("{:?}", reason)).await?;
+
+        if let Some(parent_id) = task.parent {
+            self.refresh_parent_progress(parent_id).await?;
+        }
+        self.release_dependents(task_id).await
+    }
+
+    async fn record_actuals(
+        &self,
+        task_id: TaskId,
+        actual_duration: std::time::Duration,
+        actual_tokens: Option<usize>,
+    ) -> Result<Task, anyhow::Error> {
+        let task = {
+            let mut storage = self.storage.lock().await;
+            let mut task = storage.require_task(task_id).await?;
+            task.actuals = Some(devman_core::TaskActuals {
+                duration: actual_duration,
+                tokens: actual_tokens,
+                source: devman_core::ActualsSource::Manual,
+                recorded_at: chrono::Utc::now(),
+            });
+            storage.save_task(&task).await?;
+            task
+        };
+        self.log_task_event(
+            task_id,
+            "actuals_recorded",
+            format!("{} min", actual_duration.as_secs() / 60),
+        )
+        .await?;
+        Ok(task)
+    }
+
+    async fn claim_task(
+        &self,
+        task_id: TaskId,
+        agent: AgentId,
+        ttl: std::time::Duration,
+    ) -> Result<ClaimToken, anyhow::Error> {
+        let now = self.clock.now();
+        let claim = devman_core::TaskClaim {
+            agent: agent.clone(),
+            token: ClaimToken::new(),
+            claimed_at: now,
+            expires_at: now + chrono::Duration::from_std(ttl)?,
+        };
+        {
+            let mut storage = self.storage.lock().await;
+            // Read the version first: a write landing after it makes the
+            // versioned save below fail instead of being overwritten
+            let version = storage.task_version(task_id).await?;
+            let mut task = storage.require_task(task_id).await?;
+            if let Some(held) = task.claim.as_ref().filter(|c| c.is_active(now) && c.agent != agent) {
+                return Err(ClaimError::Held { agent: held.agent.clone(), expires_at: held.expires_at }.into());
+            }
+            task.claim = Some(claim.clone());
+            task.updated_at = now;
+            storage.save_task_if_version(&task, version).await?;
+        }
+        self.log_task_event(task_id, "task_claimed", format!("{} until {}", agent.0, claim.expires_at))
+            .await?;
+        Ok(claim.token)
+    }
+
+    async fn release_task(&self, task_id: TaskId, token: ClaimToken) -> Result<(), anyhow::Error> {
+        let agent = {
+            let mut storage = self.storage.lock().await;
+            let version = storage.task_version(task_id).await?;
+            let mut task = storage.require_task(task_id).await?;
+            let Some(claim) = task.claim.take() else {
+                return Ok(());
+            };
+            if claim.token != token {
+                return Err(ClaimError::TokenMismatch.into());
+            }
+            task.updated_at = self.clock.now();
+            storage.save_task_if_version(&task, version).await?;
+            claim.agent
+        };
+        self.log_task_event(task_id, "task_released", agent.0).await?;
+        Ok(())
+    }
+
+    async fn record_work_event(&self, task_id: TaskId, event: WorkEvent) -> Result<(), anyhow::Error> {
+        let mut storage = self.storage.lock().await;
+        let mut task = storage
+            .require_task(task_id)
+            .await?;
+
+        // A finished record is history; later events start a new one
+        let existing = match task.work_records.last() {
+            Some(record_id) => storage
+                .load_work_record(*record_id)
+                .await?
+                .filter(|record| record.result.status == devman_core::CompletionStatus::Running),
+            None => None,
+        };
+
+        let mut record = match existing {
+            Some(record) => record,
+            None => {
+                let record = WorkRecord::new(
+                    task_id,
+                    devman_core::Executor::AI {
+                        model: "default".to_string(),
+                    },
+                    self.clock.now(),
+                );
+                task.work_records.push(record.id);
+                task.updated_at = self.clock.now();
+                storage.save_task(&task).await?;
+                record
+            }
+        };
+
+        record.events.push(event);
+        storage.save_work_record(&record).await?;
+        Ok(())
+    }
+
+    async fn estimate_task_cost(&self, task_id: TaskId) -> Option<CostEstimation> {
+        let storage = self.storage.lock().await;
+        let task = storage.load_task(task_id).await.ok().flatten()?;
+        let history = storage.list_tasks(&devman_core::TaskFilter::default()).await.unwrap_or_default();
+        let prices = PriceTable::from_storage(&*storage).await;
+        Some(CompletionEstimator::default().with_prices(prices).estimate_cost(&task, &history))
+    }
+
+    async fn recommend_next_task(&self, agent: Option<&AgentId>) -> Option<NextTaskRecommendation> {
+        let (tasks, prices) = {
+            let storage = self.storage.lock().await;
+            let tasks = storage.list_tasks(&devman_core::TaskFilter::default()).await.unwrap_or_default();
+            (tasks, PriceTable::from_storage(&*storage).await)
+        };
+        let (task, mut rationale, candidates) = select_next_task(&tasks, agent, self.clock.now())?;
+        let task = task.clone();
+
+        let estimated_cost = CompletionEstimator::default().with_prices(prices).estimate_cost(&task, &tasks);
+        rationale.push(format!(
+            "Estimated cost: ~{} tokens (${:.2}, {} similar task(s))",
+            estimated_cost.tokens, estimated_cost.usd, estimated_cost.samples
+        ));
+
+        let mut relevant_knowledge = self.knowledge_service.recommend_knowledge(&task.intent.context).await;
+        if relevant_knowledge.is_empty() {
+            relevant_knowledge = self.knowledge_service.search_semantic(&task.title, 3).await;
+        }
+
+        let readiness = vec![
+            ReadinessItem { item: "Dependencies complete".to_string(), satisfied: true },
+            ReadinessItem { item: "Has a description".to_string(), satisfied: !task.description.is_empty() },
+            ReadinessItem {
+                item: "Has success criteria".to_string(),
+                satisfied: !task.intent.success_criteria.is_empty(),
+            },
+            ReadinessItem { item: "Has quality gates".to_string(), satisfied: !task.quality_gates.is_empty() },
+            ReadinessItem {
+                item: "Relevant knowledge found".to_string(),
+                satisfied: !relevant_knowledge.is_empty(),
+            },
+        ];
+
+        Some(NextTaskRecommendation {
+            task,
+            rationale,
+            relevant_knowledge,
+            readiness,
+            candidates,
+            estimated_cost,
+        })
+    }
+
+    async fn bulk_update_status(
+        &self,
+        filter: TaskFilter,
+        status: TaskStatus,
+        reason: &str,
+    ) -> Result<BulkResult, anyhow::Error> {
+        let mut result = BulkResult::default();
+        let mut changes = Vec::new();
+        for task in self.list_tasks(filter).await {
+            if task.status == status {
+                result.record(task.id, BulkOutcome::Skipped, format!("Already {:?}", status));
+            } else if !task.status.can_transition_to(status) {
+                let reason = format!("Cannot change status from {:?} to {:?}", task.status, status);
+                result.record(task.id, BulkOutcome::Failed, reason);
+            } else {
+                changes.push(task);
+            }
+        }
+
+        if matches!(status, TaskStatus::Done | TaskStatus::Abandoned) {
+            // The single-task flow checks open sub-tasks, captures actuals,
+            // sets the workflow state and releases dependents
+            for task in &changes {
+                let closed = if status == TaskStatus::Done {
+                    self.complete_task(task.id, bulk_work_result(reason)).await
+                } else {
+                    let abandon = AbandonReason::Voluntary { reason: reason.to_string(), can_be_reassigned: true };
+                    self.abandon_task(task.id, abandon).await
+                };
+                match closed {
+                    Ok(report) => {
+                        let change = format!("{:?} -> {:?}: {}", task.status, status, reason);
+                        self.log_task_event(task.id, "task_status_bulk_updated", change).await?;
+                        let mut note = reason.to_string();
+                        for warning in report.warnings {
+                            note.push_str("; ");
+                            note.push_str(&warning);
+                        }
+                        result.record(task.id, BulkOutcome::Updated, note);
+                    }
+                    Err(e) => result.record(task.id, BulkOutcome::Failed, e.to_string()),
+                }
+            }
+            return Ok(result);
+        }
+
+        {
+            let mut storage = self.storage.lock().await;
+            let now = chrono::Utc::now();
+            for (i, original) in changes.iter().enumerate() {
+                let mut task = original.clone();
+                task.set_status(status);
+                task.updated_at = now;
+                if let Err(e) = storage.save_task(&task).await {
+                    for saved in &changes[..i] {
+                        let _ = storage.save_task(saved).await;
+                    }
+                    storage.rollback().await?;
+                    anyhow::bail!("Bulk update failed on task {}, changes reverted: {}", original.id, e);
+                }
+            }
+            storage
+                .commit(&format!("Bulk update {} task(s) to {:?}", changes.len(), status))
+                .await?;
+        }
+
+        let mut parents = std::collections::HashSet::new();
+        for task in &changes {
+            let change = format!("{:?} -> {:?}: {}", task.status, status, reason);
+            self.log_task_event(task.id, "task_status_bulk_updated", change).await?;
+            result.record(task.id, BulkOutcome::Updated, reason.to_string());
+            parents.extend(task.parent);
+        }
+        for parent_id in parents {
+            self.refresh_parent_progress(parent_id).await?;
+        }
+
+        Ok(result)
+    }
+
+    async fn abandonment_impact(&self, task_id: TaskId) -> Result<ImpactReport, anyhow::Error> {
+        let storage = self.storage.lock().await;
+        let root = storage.require_task(task_id).await?;
+        let tasks = storage.list_tasks(&devman_core::TaskFilter::default()).await?;
+
+        // Dependents of each task, from both ends of the link
+        let mut dependents: std::collections::HashMap<TaskId, Vec<TaskId>> = Default::default();
+        for task in &tasks {
+            for dep in &task.depends_on {
+                dependents.entry(*dep).or_default().push(task.id);
+            }
+            dependents.entry(task.id).or_default().extend(task.blocks.iter().copied());
+        }
+        let by_id: std::collections::HashMap<TaskId, &Task> = tasks.iter().map(|t| (t.id, t)).collect();
+
+        let mut seen = std::collections::HashSet::from([task_id]);
+        let mut queue = std::collections::VecDeque::from([(task_id, 0)]);
+        let mut blocked_tasks = Vec::new();
+        while let Some((id, depth)) = queue.pop_front() {
+            for dependent in dependents.get(&id).into_iter().flatten() {
+                if !seen.insert(*dependent) {
+                    continue;
+                }
+                // Closed tasks no longer wait on anything
+                let Some(task) = by_id.get(dependent).filter(|t| !t.is_closed()) else {
+                    continue;
+                };
+                blocked_tasks.push(ImpactedTask {
+                    id: task.id,
+                    title: task.title.clone(),
+                    status: task.status,
+                    depth: depth + 1,
+                });
+                queue.push_back((task.id, depth + 1));
+            }
+        }
+
+        let mut phase_ids = vec![root.phase_id];
+        for impacted in &blocked_tasks {
+            let phase_id = by_id[&impacted.id].phase_id;
+            if !phase_ids.contains(&phase_id) {
+                phase_ids.push(phase_id);
+            }
+        }
+        let mut stalled_phases = Vec::new();
+        for phase_id in &phase_ids {
+            if let Ok(Some(phase)) = storage.load_phase(*phase_id).await {
+                stalled_phases.push(StalledPhase { id: phase.id, name: phase.name });
+            }
+        }
+
+        let mut stalled_goals = Vec::new();
+        for goal in storage.list_goals().await? {
+            let Ok(Some(project)) = storage.load_project(goal.project_id).await else {
+                continue;
+            };
+            if project.phases.iter().any(|p| phase_ids.contains(p)) {
+                stalled_goals.push(StalledGoal { id: goal.id, title: goal.title });
+            }
+        }
+
+        let mut mitigations = Vec::new();
+        if blocked_tasks.is_empty() {
+            mitigations.push("No open task depends on this one; it can be abandoned safely".to_string());
+        } else {
+            mitigations.push(format!(
+                "Reassign \"{}\" instead of abandoning it, so the {} dependent task(s) can still proceed",
+                root.title,
+                blocked_tasks.len()
+            ));
+            let direct: Vec<_> = blocked_tasks
+                .iter()
+                .filter(|t| t.depth == 1)
+                .map(|t| format!("\"{}\"", t.title))
+                .collect();
+            mitigations.push(format!(
+                "Re-scope {} to drop the dependency, or move the work they need into them",
+                direct.join(", ")
+            ));
+        }
+
+        Ok(ImpactReport {
+            task_id,
+            blocked_tasks,
+            stalled_phases,
+            stalled_goals,
+            mitigations,
+        })
+    }
+
+    async fn preview_transition(&self, task_id: TaskId, to: TaskStatus) -> Result<TransitionPreview, anyhow::Error> {
+        let stranded_tasks = if to == TaskStatus::Abandoned {
+            self.abandonment_impact(task_id).await?.blocked_tasks
+        } else {
+            Vec::new()
+        };
+
+        let storage = self.storage.lock().await;
+        let task = storage.require_task(task_id).await?;
+        let tasks = storage.list_tasks(&devman_core::TaskFilter::default()).await?;
+        let by_id: HashMap<TaskId, &Task> = tasks.iter().map(|t| (t.id, t)).collect();
+        let is_closed = |id: &TaskId| by_id.get(id).is_some_and(|t| t.is_closed());
+
+        let mut rejections = Vec::new();
+        if task.status == to {
+            rejections.push(format!("Task is already {:?}", to));
+        } else if !task.status.can_transition_to(to) {
+            rejections.push(format!("Cannot change status from {:?} to {:?}", task.status, to));
+        }
+        let open_children = task.children.iter().filter(|c| by_id.contains_key(c) && !is_closed(c)).count();
+        if to == TaskStatus::Done && open_children > 0 {
+            rejections.push(format!("Task has {} open sub-task(s)", open_children));
+        }
+
+        let unblocked_tasks = if to == TaskStatus::Done {
+            tasks
+                .iter()
+                .filter(|t| !t.is_closed() && t.id != task_id)
+                .filter(|t| t.depends_on.contains(&task_id) || task.blocks.contains(&t.id))
+                .filter(|t| t.depends_on.iter().filter(|d| **d != task_id).all(is_closed))
+                .map(|t| ImpactedTask { id: t.id, title: t.title.clone(), status: t.status, depth: 1 })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        // Progress counts closed tasks, as the progress tracker does
+        let closes = |status: TaskStatus| matches!(status, TaskStatus::Done | TaskStatus::Abandoned);
+        let change = i64::from(closes(to)) - i64::from(task.is_closed());
+        let percentage = |closed: i64, total: usize| {
+            if total == 0 { 0.0 } else { closed as f32 / total as f32 * 100.0 }
+        };
+        let closed_in = |phase: &Phase| phase.tasks.iter().filter(|id| is_closed(id)).count() as i64;
+
+        let projects = storage.list_projects().await?;
+        let mut phase_ids = vec![task.phase_id];
+        extend_unique(&mut phase_ids, projects.iter().flat_map(|p| p.phases.iter().copied()));
+        let mut phase_progress = Vec::new();
+        let mut affected_phases = Vec::new();
+        for phase_id in phase_ids {
+            let Some(phase) = storage.load_phase(phase_id).await? else {
+                continue;
+            };
+            if phase.tasks.contains(&task_id) {
+                let closed = closed_in(&phase);
+                phase_progress.push(ProgressDelta {
+                    id: phase.id,
+                    name: phase.name.clone(),
+                    before: percentage(closed, phase.tasks.len()),
+                    after: percentage(closed + change, phase.tasks.len()),
+                });
+                affected_phases.push(phase.id);
+            }
+        }
+
+        let mut goal_progress = Vec::new();
+        for goal in storage.list_goals().await? {
+            let Some(project) = projects.iter().find(|p| p.id == goal.project_id) else {
+                continue;
+            };
+            if !project.phases.iter().any(|p| affected_phases.contains(p)) {
+                continue;
+            }
+            let (mut closed, mut total) = (0, 0);
+            for phase_id in &project.phases {
+                if let Some(phase) = storage.load_phase(*phase_id).await? {
+                    closed += closed_in(&phase);
+                    total += phase.tasks.len();
+                }
+            }
+            goal_progress.push(ProgressDelta {
+                id: goal.id,
+                name: goal.title,
+                before: percentage(closed, total),
+                after: percentage(closed + change, total),
+            });
+        }
+
+        let (quality_status, quality_gates_pass) = task_quality_gate_status(&*storage, &task).await?;
+
+        let mut warnings = Vec::new();
+        if to == TaskStatus::Done && !quality_gates_pass {
+            warnings.push(format!("Quality gates have not passed ({:?})", quality_status));
+        }
+        if !stranded_tasks.is_empty() {
+            warnings.push(format!("{} open task(s) would be left waiting on this task", stranded_tasks.len()));
+        }
+        if let Some(claim) = task.claim.as_ref().filter(|c| c.is_active(self.clock.now())) {
+            warnings.push(format!("Task is claimed by {}", claim.agent.0));
+        }
+
+        Ok(TransitionPreview {
+            task_id,
+            from: task.status,
+            to,
+            allowed: rejections.is_empty(),
+            rejections,
+            unblocked_tasks,
+            stranded_tasks,
+            phase_progress,
+            goal_progress,
+            quality_status,
+            quality_gates_pass,
+            warnings,
+        })
+    }
+
+
+    async fn project_snapshot(&self) -> Result<ProjectSnapshot, anyhow::Error> {
+        let mut blockers = self.list_blockers().await;
+        blockers.sort_by_key(|b| std::cmp::Reverse(b.severity as u8));
+
+        let storage = self.storage.lock().await;
+        let tasks: Vec<Task> = storage
+            .list_tasks(&Default::default())
+            .await?
+            .into_iter()
+            .filter(|t| !t.archived)
+            .collect();
+        let is_closed = |status: TaskStatus| matches!(status, TaskStatus::Done | TaskStatus::Abandoned);
+        let status_of: HashMap<TaskId, TaskStatus> = tasks.iter().map(|t| (t.id, t.status)).collect();
+        let percentage = |closed: usize, total: usize| {
+            if total == 0 { 0.0 } else { closed as f32 / total as f32 * 100.0 }
+        };
+
+        let mut goals = storage.list_goals().await?;
+        goals.retain(|g| g.status == devman_core::GoalStatus::Active);
+        goals.sort_by_key(|g| std::cmp::Reverse(g.updated_at));
+
+        let mut goal_snapshots = Vec::new();
+        let mut phases: Vec<PhaseSnapshot> = Vec::new();
+        for goal in &goals {
+            let phase_ids = match storage.load_project(goal.project_id).await? {
+                Some(project) => project.phases,
+                None => Vec::new(),
+            };
+            let (mut closed, mut total) = (0, 0);
+            for phase_id in phase_ids {
+                let Some(phase) = storage.load_phase(phase_id).await? else {
+                    continue;
+                };
+                let completed = phase
+                    .tasks
+                    .iter()
+                    .filter(|id| status_of.get(id).is_some_and(|s| is_closed(*s)))
+                    .count();
+                closed += completed;
+                total += phase.tasks.len();
+                if !phases.iter().any(|p| p.id == phase.id) {
+                    phases.push(PhaseSnapshot {
+                        id: phase.id,
+                        name: phase.name,
+                        status: phase.status,
+                        completed_tasks: completed,
+                        total_tasks: phase.tasks.len(),
+                        percentage: percentage(completed, phase.tasks.len()),
+                    });
+                }
+            }
+            goal_snapshots.push(GoalSnapshot {
+                id: goal.id,
+                title: goal.title.clone(),
+                project_id: goal.project_id,
+                percentage: percentage(closed, total),
+            });
+        }
+
+        let mut completed: Vec<&Task> = tasks.iter().filter(|t| t.status == TaskStatus::Done).collect();
+        completed.sort_by_key(|t| std::cmp::Reverse(t.updated_at));
+        let recent_completions = completed
+            .into_iter()
+            .take(SNAPSHOT_LIST_LIMIT)
+            .map(|t| CompletedTask {
+                id: t.id,
+                title: t.title.clone(),
+                completed_at: t.updated_at,
+            })
+            .collect();
+
+        let mut tasks_by_status = std::collections::BTreeMap::new();
+        for task in &tasks {
+            *tasks_by_status.entry(format!("{:?}", task.status)).or_insert(0) += 1;
+        }
+        let metrics = SnapshotMetrics {
+            tasks_by_status,
+            total_tasks: tasks.len(),
+            completion_percentage: percentage(tasks.iter().filter(|t| is_closed(t.status)).count(), tasks.len()),
+            open_blockers: blockers.len(),
+            active_goals: goals.len(),
+            knowledge_items: storage.list_knowledge().await?.len(),
+        };
+
+        goal_snapshots.truncate(SNAPSHOT_LIST_LIMIT);
+        phases.truncate(SNAPSHOT_LIST_LIMIT);
+        blockers.truncate(SNAPSHOT_LIST_LIMIT);
+        Ok(ProjectSnapshot {
+            generated_at: chrono::Utc::now(),
+            goals: goal_snapshots,
+            phases,
+            blockers,
+            recent_completions,
+            metrics,
+        })
+    }
+    async fn tasks_touching_file(&self, path: &str) -> Vec<TaskSummary> {
+        let target = normalize_path(path);
+        let storage = self.storage.lock().await;
+        let tasks = storage
+            .list_tasks(&devman_core::TaskFilter::default())
             .await
+            .unwrap_or_default();
+
+        let mut summaries = Vec::new();
+        for task in tasks.into_iter().filter(|t| !t.archived) {
+            let declared = task
+                .intent
+                .context
+                .affected_files
+                .iter()
+                .any(|f| normalize_path(f) == target);
+            let worked_on = storage
+                .list_work_records(task.id)
+                .await
+                .unwrap_or_default()
+                .iter()
+                .any(|r| work_record_files(r).any(|f| normalize_path(f) == target));
+
+            if declared || worked_on {
+                summaries.push(TaskSummary {
+                    id: task.id,
+                    title: task.title,
+                    status: task.status,
+                    declared,
+                    worked_on,
+                });
+            }
+        }
+        summaries
+    }
+
+    async fn get_task_timeline(&self, task_id: TaskId) -> Result<Vec<TimelineEntry>, anyhow::Error> {
+        Ok(task_timeline(&*self.storage.lock().await, task_id).await?)
     }
 
     async fn run_quality_check(
@@ -311,15 +3150,107 @@ impl AIInterface for BasicAIInterface {
         }
     }
 
-    async fn execute_tool(&self, tool: String, input: ToolInput) -> devman_tools::ToolOutput {
-        self.tool_executor.execute_tool(&tool, input).await.unwrap_or_else(
-            |e| devman_tools::ToolOutput {
-                exit_code: -1,
-                stdout: String::new(),
-                stderr: e.to_string(),
-                duration: std::time::Duration::ZERO,
-            },
-        )
+    async fn assign_profile(
+        &self,
+        phase_id: PhaseId,
+        profile_id: QualityProfileId,
+    ) -> Result<Phase, anyhow::Error> {
+        let mut storage = self.storage.lock().await;
+        storage.require_quality_profile(profile_id).await?;
+        let mut phase = storage.require_phase(phase_id).await?;
+        phase.quality_profile = Some(profile_id);
+        storage.save_phase(&phase).await?;
+        Ok(phase)
+    }
+
+    async fn run_task_quality_check(
+        &self,
+        task_id: TaskId,
+        cancel: &devman_tools::CancellationToken,
+    ) -> Result<Vec<GateResult>, anyhow::Error> {
+        let (gates, affected_files, state) = {
+            let storage = self.storage.lock().await;
+            let task = storage.require_task(task_id).await?;
+            let profile = match storage.load_phase(task.phase_id).await?.and_then(|p| p.quality_profile) {
+                Some(profile_id) => Some(storage.require_quality_profile(profile_id).await?),
+                None => None,
+            };
+            (task.effective_quality_gates(profile.as_ref()), task.intent.context.affected_files, task.state)
+        };
+
+        let mut context = QualityWorkContext::new(task_id);
+        context.affected_files = affected_files;
+        let mut results = Vec::with_capacity(gates.len());
+        for gate in &gates {
+            results.push(self.quality_engine.run_gate(gate, &context, cancel).await);
+        }
+
+        let run = devman_core::QualityRun::new(
+            task_id,
+            results.iter().flat_map(|r| r.check_results.iter().cloned()).collect(),
+            chrono::Utc::now(),
+        );
+        self.storage.lock().await.save_quality_run(&run).await?;
+
+        // A task in the guided flow moves on to its quality result
+        if matches!(state, Some(TaskState::WorkRecorded { .. } | TaskState::QualityChecking { .. })) {
+            let mut storage = self.storage.lock().await;
+            let mut task = storage.require_task(task_id).await?;
+            task.set_state(TaskState::QualityCompleted {
+                result: devman_core::TaskQualityCheckResult {
+                    overall_status: devman_quality::overall_status(&results),
+                    findings_count: results.iter().flat_map(|r| &r.check_results).map(|c| c.findings.len()).sum(),
+                    warnings_count: results.iter().filter(|r| r.decision == GateDecision::PassWithWarnings).count(),
+                },
+                completed_at: chrono::Utc::now(),
+            });
+            storage.save_task(&task).await?;
+        }
+
+        let passed = results.iter().filter(|r| r.passed).count();
+        self.log_task_event(task_id, "quality_checked", format!("{}/{} gates passed", passed, results.len())).await?;
+        Ok(results)
+    }
+
+    async fn quality_history(&self, task_id: TaskId) -> Result<Vec<devman_core::QualityCheckResult>, anyhow::Error> {
+        let storage = self.storage.lock().await;
+        storage.require_task(task_id).await?;
+        let runs = storage.list_quality_runs(task_id).await?;
+        Ok(runs.into_iter().flat_map(|run| run.results).collect())
+    }
+
+    async fn quality_trend(&self, task_id: TaskId) -> Result<QualityTrend, anyhow::Error> {
+        let storage = self.storage.lock().await;
+        storage.require_task(task_id).await?;
+        Ok(quality_trend(&storage.list_quality_runs(task_id).await?))
+    }
+
+    async fn classify_quality_failures(&self, task_id: TaskId) -> Result<Option<FailureClassification>, anyhow::Error> {
+        let storage = self.storage.lock().await;
+        storage.require_task(task_id).await?;
+        let runs = storage.list_quality_runs(task_id).await?;
+        Ok(runs
+            .iter()
+            .max_by_key(|run| run.run_at)
+            .map(|run| self.failure_classifier.classify(&run.results)))
+    }
+
+    async fn goal_quality_summary(&self, goal_id: GoalId) -> Result<GoalQualitySummary, anyhow::Error> {
+        Ok(self.quality_engine.goal_quality_summary(goal_id).await?)
+    }
+
+    async fn execute_tool(
+        &self,
+        tool: String,
+        input: ToolInput,
+        cancel: &devman_tools::CancellationToken,
+    ) -> devman_tools::ToolOutput {
+        self.tool_executor.execute_tool(&tool, input, cancel).await.unwrap_or_else(|e| devman_tools::ToolOutput {
+            exit_code: -1,
+            stdout: String::new(),
+            stderr: e.to_string(),
+            duration: std::time::Duration::ZERO,
+        })
     }
 
     async fn save_knowledge(&self, knowledge: Knowledge) -> Result<(), anyhow::Error> {
@@ -425,6 +3356,7 @@ mod tests {
             },
             stdin: None,
             timeout: Some(std::time::Duration::from_secs(300)),
+            work_dir: None,
         };
         assert_eq!(input.args.len(), 3);
         assert!(input.timeout.is_some());
@@ -438,6 +3370,7 @@ mod tests {
             env: HashMap::new(),
             stdin: Some("input data".to_string()),
             timeout: None,
+            work_dir: None,
         };
         assert!(input.stdin.is_some());
         assert_eq!(input.stdin, Some("input data".to_string()));
@@ -460,6 +3393,31 @@ mod tests {
         };
     }
 
+    // ==================== WorkRecord Tests ====================
+
+    #[test]
+    fn test_work_record_events_sorted() {
+        use devman_core::{Executor, WorkEventType};
+
+        let base = chrono::Utc::now();
+        let mut record = WorkRecord::new(TaskId::new(), Executor::AI { model: "test".to_string() }, base);
+
+        let at = |secs| base + chrono::Duration::seconds(secs);
+        let late = WorkEvent::new(WorkEventType::StepCompleted, "late", serde_json::Value::Null, at(20));
+        let early = WorkEvent::new(WorkEventType::StepStarted, "early", serde_json::Value::Null, at(0));
+        let middle = WorkEvent::new(WorkEventType::IssueDiscovered, "middle", serde_json::Value::Null, at(10));
+
+        record.events.push(late);
+        record.events.push(early);
+        record.events.push(middle);
+
+        let ordered: Vec<_> = record.events_sorted().iter().map(|e| e.description.as_str()).collect();
+        assert_eq!(ordered, vec!["early", "middle", "late"]);
+
+        // Append order is preserved in storage form
+        assert_eq!(record.events[0].description, "late");
+    }
+
     // ==================== QualityCheckId Tests ====================
 
     #[test]
@@ -92,6 +92,9 @@ pub struct Job {
     pub progress: u8,
     /// Progress message
     pub progress_message: String,
+    /// Fired when the job is cancelled; runners pass it to long operations
+    #[serde(skip)]
+    pub cancel_token: devman_tools::CancellationToken,
 }
 
 /// Job type
@@ -196,6 +199,18 @@ pub trait JobManager: Send + Sync {
     /// Cancel a job
     async fn cancel_job(&self, job_id: &JobId) -> Result<(), JobError>;
 
+    /// Token that fires when the job is cancelled, for passing to quality
+    /// gates and tool executions run on the job's behalf
+    async fn cancellation_token(&self, job_id: &JobId) -> Option<devman_tools::CancellationToken>;
+
+    /// Report how far a pending or running job has got, as a percentage
+    /// (capped at 100) and a message; a pending job starts running
+    async fn update_progress(&self, job_id: &JobId, progress: u8, message: String) -> Result<(), JobError>;
+
+    /// Record how a pending or running job ended, with its result or error;
+    /// a job cancelled meanwhile stays cancelled
+    async fn finish_job(&self, job_id: &JobId, outcome: Result<serde_json::Value, JobError>) -> Result<(), JobError>;
+
     /// List jobs with optional filter
     async fn list_jobs(&self, filter: JobFilter) -> Vec<JobStatusResponse>;
 
@@ -273,6 +288,7 @@ impl JobManager for InMemoryJobManager {
             error: None,
             progress: 0,
             progress_message: "Job created".to_string(),
+            cancel_token: devman_tools::CancellationToken::new(),
         };
 
         let mut jobs = self.jobs.lock().await;
@@ -316,18 +332,19 @@ impl JobManager for InMemoryJobManager {
                     job.status = JobStatus::Cancelled;
                     job.completed_at = Some(chrono::Utc::now());
                     job.error = Some(JobError {
-                        code: -32004,
+                        code: error_codes::DevManErrorCode::Cancelled.code(),
                         message: "Job cancelled by user".to_string(),
                         hint: Some("The job was cancelled. You can retry or create a new job.".to_string()),
                         retryable: true,
                         data: None,
                     });
                     job.progress_message = "Job cancelled".to_string();
+                    job.cancel_token.cancel();
                     info!("Job {} cancelled", job_id);
                     Ok(())
                 } else {
                     Err(JobError {
-                        code: -32001,
+                        code: error_codes::DevManErrorCode::StateConflict.code(),
                         message: format!("Cannot cancel job in {} state", job.status),
                         hint: Some("Only pending or running jobs can be cancelled.".to_string()),
                         retryable: false,
@@ -336,7 +353,7 @@ impl JobManager for InMemoryJobManager {
                 }
             }
             None => Err(JobError {
-                code: -32002,
+                code: error_codes::DevManErrorCode::NotFound.code(),
                 message: format!("Job {} not found", job_id),
                 hint: None,
                 retryable: false,
@@ -345,6 +362,84 @@ impl JobManager for InMemoryJobManager {
         }
     }
 
+    async fn cancellation_token(&self, job_id: &JobId) -> Option<devman_tools::CancellationToken> {
+        self.jobs.lock().await.get(job_id).map(|job| job.cancel_token.clone())
+    }
+
+    async fn update_progress(&self, job_id: &JobId, progress: u8, message: String) -> Result<(), JobError> {
+        let mut jobs = self.jobs.lock().await;
+        let Some(job) = jobs.get_mut(job_id) else {
+            return Err(JobError {
+                code: error_codes::DevManErrorCode::NotFound.code(),
+                message: format!("Job {} not found", job_id),
+                hint: None,
+                retryable: false,
+                data: None,
+            });
+        };
+        match job.status {
+            JobStatus::Pending => {
+                job.status = JobStatus::Running;
+                job.started_at = Some(chrono::Utc::now());
+            }
+            JobStatus::Running => {}
+            _ => {
+                return Err(JobError {
+                    code: error_codes::DevManErrorCode::StateConflict.code(),
+                    message: format!("Cannot update progress of job in {} state", job.status),
+                    hint: Some("Only pending or running jobs report progress.".to_string()),
+                    retryable: false,
+                    data: None,
+                });
+            }
+        }
+        job.progress = progress.min(100);
+        job.progress_message = message;
+        Ok(())
+    }
+
+    async fn finish_job(&self, job_id: &JobId, outcome: Result<serde_json::Value, JobError>) -> Result<(), JobError> {
+        let mut jobs = self.jobs.lock().await;
+        let Some(job) = jobs.get_mut(job_id) else {
+            return Err(JobError {
+                code: error_codes::DevManErrorCode::NotFound.code(),
+                message: format!("Job {} not found", job_id),
+                hint: None,
+                retryable: false,
+                data: None,
+            });
+        };
+        match job.status {
+            JobStatus::Pending | JobStatus::Running => {}
+            JobStatus::Cancelled => return Ok(()),
+            _ => {
+                return Err(JobError {
+                    code: error_codes::DevManErrorCode::StateConflict.code(),
+                    message: format!("Cannot finish job in {} state", job.status),
+                    hint: Some("A job finishes only once.".to_string()),
+                    retryable: false,
+                    data: None,
+                });
+            }
+        }
+        job.completed_at = Some(chrono::Utc::now());
+        match outcome {
+            Ok(result) => {
+                job.status = JobStatus::Completed;
+                job.progress = 100;
+                job.progress_message = "Job completed".to_string();
+                job.result = Some(result);
+            }
+            Err(error) => {
+                job.status = JobStatus::Failed;
+                job.progress_message = error.message.clone();
+                job.error = Some(error);
+            }
+        }
+        info!("Job {} finished as {}", job_id, job.status);
+        Ok(())
+    }
+
     async fn list_jobs(&self, filter: JobFilter) -> Vec<JobStatusResponse> {
         let jobs = self.jobs.lock().await;
         let mut results: Vec<_> = jobs
@@ -435,16 +530,85 @@ impl Default for JobSnapshot {
 
 /// Error codes for DevMan MCP Server
 pub mod error_codes {
+    /// Named error codes returned by the MCP server.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum DevManErrorCode {
+        /// Request body is not valid JSON (JSON-RPC)
+        ParseError,
+        /// Unknown JSON-RPC method (JSON-RPC)
+        MethodNotFound,
+        /// Missing or malformed parameters (JSON-RPC)
+        InvalidParams,
+        /// Server misconfiguration or unexpected failure (JSON-RPC)
+        Internal,
+        /// Generic business error
+        Business,
+        /// State conflict (task already completed, goal finished)
+        StateConflict,
+        /// Resource not found
+        NotFound,
+        /// Async job timeout
+        Timeout,
+        /// Async job cancelled
+        Cancelled,
+        /// `tools/call` named a tool the server does not provide
+        ToolNotFound,
+    }
+
+    impl DevManErrorCode {
+        /// Numeric code sent on the wire.
+        pub const fn code(self) -> i32 {
+            match self {
+                Self::ParseError => -32700,
+                Self::MethodNotFound => -32601,
+                Self::InvalidParams => -32602,
+                Self::Internal => -32603,
+                Self::Business => -32000,
+                Self::StateConflict => -32001,
+                Self::NotFound => -32002,
+                Self::Timeout => -32003,
+                Self::Cancelled => -32004,
+                Self::ToolNotFound => -32005,
+            }
+        }
+
+        /// Whether retrying the same request may succeed, unless the caller
+        /// knows better.
+        pub const fn retryable(self) -> bool {
+            matches!(self, Self::Business | Self::Timeout | Self::Cancelled)
+        }
+
+        /// Look up the variant for a numeric code.
+        pub fn from_code(code: i32) -> Option<Self> {
+            [
+                Self::ParseError,
+                Self::MethodNotFound,
+                Self::InvalidParams,
+                Self::Internal,
+                Self::Business,
+                Self::StateConflict,
+                Self::NotFound,
+                Self::Timeout,
+                Self::Cancelled,
+                Self::ToolNotFound,
+            ]
+            .into_iter()
+            .find(|c| c.code() == code)
+        }
+    }
+
     /// Generic business error
-    pub const BUSINESS_ERROR: i32 = -32000;
+    pub const BUSINESS_ERROR: i32 = DevManErrorCode::Business.code();
     /// State conflict (task already completed, goal finished)
-    pub const STATE_CONFLICT: i32 = -32001;
+    pub const STATE_CONFLICT: i32 = DevManErrorCode::StateConflict.code();
     /// Resource not found
-    pub const RESOURCE_NOT_FOUND: i32 = -32002;
+    pub const RESOURCE_NOT_FOUND: i32 = DevManErrorCode::NotFound.code();
     /// Async job timeout
-    pub const JOB_TIMEOUT: i32 = -32003;
+    pub const JOB_TIMEOUT: i32 = DevManErrorCode::Timeout.code();
     /// Async job cancelled
-    pub const JOB_CANCELLED: i32 = -32004;
+    pub const JOB_CANCELLED: i32 = DevManErrorCode::Cancelled.code();
+    /// Unknown tool name in `tools/call`
+    pub const TOOL_NOT_FOUND: i32 = DevManErrorCode::ToolNotFound.code();
 }
 
 use std::collections::HashMap;
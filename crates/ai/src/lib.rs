@@ -10,10 +10,17 @@ pub mod validation;
 pub mod guidance;
 pub mod mcp_server;
 pub mod job_manager;
+pub mod audit;
+pub mod classifier;
+pub mod locale;
+pub mod schema;
 
-pub use r#interface::{AIInterface, GoalSpec, GoalFilter, TaskFilter, BasicAIInterface};
+pub use r#interface::{AIInterface, GoalSpec, PhaseSpec, GoalFilter, TaskFilter, BasicAIInterface, TimelineEntry, NextTaskRecommendation, ReadinessItem, TaskSummary, BulkResult, BulkItem, BulkOutcome, GoalTree, PhaseNode, TaskNode, ImpactReport, ImpactedTask, StalledPhase, StalledGoal, TransitionPreview, ProgressDelta, ProjectSnapshot, GoalSnapshot, PhaseSnapshot, CompletedTask, SnapshotMetrics, SNAPSHOT_LIST_LIMIT, CriterionVerification, CriterionConflict, CriterionClaim, CascadeReport, ClaimError, TemplateError, PhaseGateError, task_timeline, apply_default_profile, refresh_parent_progress};
 pub use interactive::{InteractiveAI, BasicInteractiveAI};
 pub use validation::{TaskStateValidator, TransitionContext, WorkLogStorage, WorkLogEntry, CommandExecutionRecord};
 pub use guidance::{TaskGuidanceGenerator, TaskGuidanceInfo, GuidanceContext};
 pub use job_manager::{JobManager, InMemoryJobManager, JobId, Job, JobStatus, JobType, JobError, JobStatusResponse, CreateJobRequest, JobFilter, error_codes};
+pub use audit::{AuditEntry, AuditLog, AuditStatus};
+pub use classifier::{FailureClassifier, FailureClassification, FailureKind, FailureRule};
+pub use locale::{Locale, MessageCatalog};
 pub use mcp_server::{McpServer, McpServerConfig, McpTool, McpResource};
@@ -0,0 +1,204 @@
+//! Localized guidance messages.
+//!
+//! The MCP server tells the AI what to do next in plain language. Those
+//! messages are looked up by key in a [`MessageCatalog`] for the configured
+//! [`Locale`], so they can be rendered in English or Chinese.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use devman_core::TaskState;
+use serde::{Deserialize, Serialize};
+
+/// Environment variable the default locale is read from.
+pub const LOCALE_ENV: &str = "DEVMAN_LOCALE";
+
+/// Language guidance messages are rendered in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    /// English
+    En,
+    /// Simplified Chinese
+    #[default]
+    Zh,
+}
+
+impl Locale {
+    /// Locale named by `DEVMAN_LOCALE`, or Chinese when it is unset or not
+    /// recognized.
+    pub fn from_env() -> Self {
+        std::env::var(LOCALE_ENV)
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default()
+    }
+
+    /// Language code of the locale.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Zh => "zh",
+        }
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    /// Accepts a bare language code or a full locale name such as
+    /// "en_US.UTF-8" or "zh-CN".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let language = s.split(['_', '-', '.']).next().unwrap_or("").to_lowercase();
+        match language.as_str() {
+            "en" => Ok(Locale::En),
+            "zh" => Ok(Locale::Zh),
+            _ => Err(format!("Unsupported locale: {}", s)),
+        }
+    }
+}
+
+/// Every message as (key, English, Chinese).
+const MESSAGES: &[(&str, &str, &str)] = &[
+    (
+        "guidance.read_context",
+        "Call devman_read_task_context() to read the task context",
+        "请调用 devman_read_task_context() 读取任务上下文",
+    ),
+    ("context.read", "Context read", "上下文已读取"),
+    (
+        "execution.started",
+        "Execution started; use devman_log_work() to record progress",
+        "开始执行，请使用 devman_log_work() 记录工作进展",
+    ),
+    (
+        "quality.running",
+        "Quality check running; use devman_get_quality_result() to get the result",
+        "质检运行中，请使用 devman_get_quality_result() 获取结果",
+    ),
+    ("quality.confirmed", "Quality result confirmed", "质检结果已确认"),
+    ("task.completed", "Task completed", "任务已完成"),
+    (
+        "task.paused",
+        "Task paused; use devman_resume_task() to resume it",
+        "任务已暂停，可使用 devman_resume_task() 恢复",
+    ),
+    ("task.resumed", "Task resumed", "任务已恢复"),
+    ("task.abandoned", "Task abandoned", "任务已放弃"),
+    (
+        "guidance.state.created",
+        "Call read_task_context() first to learn the project, dependencies and quality requirements.",
+        "请先调用 read_task_context() 读取任务上下文，了解项目信息、依赖关系和质检要求。",
+    ),
+    (
+        "guidance.state.context_read",
+        "Call review_knowledge() to look up related knowledge, best practices and similar work.",
+        "请调用 review_knowledge() 查询相关知识，学习最佳实践和类似实现。",
+    ),
+    (
+        "guidance.state.knowledge_reviewed",
+        "Ready to start. Call start_execution() to begin and record progress with log_work().",
+        "现在可以开始执行任务了。调用 start_execution() 开始，并使用 log_work() 记录工作进展。",
+    ),
+    (
+        "guidance.state.in_progress",
+        "Keep working and record it with log_work(). Call finish_work() to submit the work when done.",
+        "继续执行任务，使用 log_work() 记录工作。完成后调用 finish_work() 提交工作记录。",
+    ),
+    (
+        "guidance.state.work_recorded",
+        "Work recorded; call run_quality_check() to run the quality checks.",
+        "工作已记录，请调用 run_quality_check() 运行质检。",
+    ),
+    (
+        "guidance.state.quality_checking",
+        "Quality checks are running; wait for the result...",
+        "质检正在运行，请等待结果...",
+    ),
+    (
+        "guidance.state.quality_passed",
+        "Quality checks passed! Call complete_task() to complete the task.",
+        "质检通过！调用 complete_task() 完成任务。",
+    ),
+    (
+        "guidance.state.quality_passed_with_warnings",
+        "Quality checks passed, but some advisory checks failed. Call complete_task() to complete the task, or fix the warnings and run again.",
+        "质检通过，但有建议性检查未通过。可以调用 complete_task() 完成任务，或修复警告后重新执行。",
+    ),
+    (
+        "guidance.state.quality_failed",
+        "Quality checks failed; fix the issues and call start_execution() to start again.",
+        "质检未通过，请修复问题后调用 start_execution() 重新开始执行。",
+    ),
+    (
+        "guidance.state.paused",
+        "Task paused. Call resume_task() to resume it.",
+        "任务已暂停。调用 resume_task() 恢复执行。",
+    ),
+    ("guidance.state.abandoned", "Task abandoned.", "任务已放弃。"),
+    ("guidance.state.completed", "Task completed.", "任务已完成。"),
+];
+
+/// Guidance messages by key, in one locale.
+#[derive(Debug, Clone)]
+pub struct MessageCatalog {
+    locale: Locale,
+    messages: HashMap<&'static str, &'static str>,
+}
+
+impl MessageCatalog {
+    /// Catalog of the messages in `locale`.
+    pub fn new(locale: Locale) -> Self {
+        let messages = MESSAGES
+            .iter()
+            .map(|&(key, en, zh)| (key, if locale == Locale::En { en } else { zh }))
+            .collect();
+        Self { locale, messages }
+    }
+
+    /// Locale the messages are in.
+    pub fn locale(&self) -> Locale {
+        self.locale
+    }
+
+    /// Message for `key`, or the key itself when there is no such message.
+    pub fn get<'a>(&self, key: &'a str) -> &'a str {
+        self.messages.get(key).copied().unwrap_or(key)
+    }
+
+    /// What to do next in `state`.
+    pub fn state_guidance(&self, state: &TaskState) -> &'static str {
+        self.get(state.guidance_key())
+    }
+}
+
+impl Default for MessageCatalog {
+    fn default() -> Self {
+        Self::new(Locale::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_parses_language_codes() {
+        assert_eq!("en".parse::<Locale>(), Ok(Locale::En));
+        assert_eq!("en_US.UTF-8".parse::<Locale>(), Ok(Locale::En));
+        assert_eq!("zh-CN".parse::<Locale>(), Ok(Locale::Zh));
+        assert_eq!("ZH".parse::<Locale>(), Ok(Locale::Zh));
+        assert!("fr".parse::<Locale>().is_err());
+    }
+
+    #[test]
+    fn test_catalog_has_every_message_in_both_locales() {
+        let en = MessageCatalog::new(Locale::En);
+        let zh = MessageCatalog::new(Locale::Zh);
+        for (key, english, chinese) in MESSAGES {
+            assert_eq!(en.get(key), *english);
+            assert_eq!(zh.get(key), *chinese);
+        }
+        assert_eq!(en.get("no.such.key"), "no.such.key");
+    }
+}
@@ -18,6 +18,14 @@ struct Cli {
     /// Storage path for DevMan data (defaults to .devman in current directory)
     #[arg(short, long)]
     storage: Option<std::path::PathBuf>,
+
+    /// Queue blocked tasks again once everything they depend on is done
+    #[arg(long)]
+    dependency_cascade: bool,
+
+    /// Always run quality checks instead of reusing cached results
+    #[arg(long)]
+    no_cache: bool,
 }
 
 #[derive(Subcommand)]
@@ -29,11 +37,18 @@ enum Commands {
     Socket {
         /// Socket path
         path: std::path::PathBuf,
+        /// Seconds open connections get to finish their current request on
+        /// shutdown
+        #[arg(long, default_value_t = 5)]
+        shutdown_grace: u64,
     },
 
     /// List available tools
     ListTools,
 
+    /// Print the tool catalog as OpenAI function-calling JSON
+    Schema,
+
     /// Print server info
     Info,
 }
@@ -82,21 +97,37 @@ async fn main() -> Result<()> {
             server_name: "devman".to_string(),
             version: env!("CARGO_PKG_VERSION").to_string(),
             socket_path: None,
+            audit_log: Some(storage_path.join(devman_ai::audit::DEFAULT_AUDIT_FILE)),
+            ..Default::default()
         }
     ).await?;
 
     // Initialize AI Interface with real storage-backed implementations
-    let ai_interface = create_ai_interface(&storage_path).await;
+    let ai_interface = create_ai_interface(&storage_path, cli.dependency_cascade, cli.no_cache).await;
     server.set_ai_interface(ai_interface);
 
+    // Long operations such as reindexing knowledge run as jobs
+    server.set_job_manager(Arc::new(devman_ai::InMemoryJobManager::new()));
+    if let Ok(vector_storage) = devman_storage::JsonStorage::new(&storage_path).await {
+        let vector_service = devman_knowledge::VectorKnowledgeServiceImpl::from_storage(
+            Arc::new(Mutex::new(vector_storage)),
+            devman_core::VectorSearchConfig::default(),
+        )
+        .await;
+        server.set_vector_service(Arc::new(vector_service));
+    }
+
     match cli.command {
         Commands::Stdio => {
             init_logging(false);
+            recover_interrupted_work(&storage_path).await?;
             server.start().await?;
         }
 
-        Commands::Socket { path } => {
+        Commands::Socket { path, shutdown_grace } => {
             init_logging(true);
+            recover_interrupted_work(&storage_path).await?;
+            server.config.shutdown_grace = std::time::Duration::from_secs(shutdown_grace);
             server.start_with_socket(&path).await?;
         }
 
@@ -109,6 +140,11 @@ async fn main() -> Result<()> {
             }
         }
 
+        Commands::Schema => {
+            init_logging(false);
+            println!("{}", serde_json::to_string_pretty(&server.export_tool_catalog())?);
+        }
+
         Commands::Info => {
             init_logging(false);
             println!("DevMan MCP Server v{}", env!("CARGO_PKG_VERSION"));
@@ -123,49 +159,23 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-/// A ToolExecutor implementation using builtin tools.
-struct BuiltinToolExecutor {
-    cargo_tool: Arc<dyn devman_tools::Tool>,
-    git_tool: Arc<dyn devman_tools::Tool>,
-    npm_tool: Arc<dyn devman_tools::Tool>,
-    fs_tool: Arc<dyn devman_tools::Tool>,
-}
-
-impl BuiltinToolExecutor {
-    fn new() -> Self {
-        Self {
-            cargo_tool: Arc::new(devman_tools::CargoTool),
-            git_tool: Arc::new(devman_tools::GitTool),
-            npm_tool: Arc::new(devman_tools::NpmTool),
-            fs_tool: Arc::new(devman_tools::FsTool),
-        }
-    }
-
-    fn find_tool(&self, name: &str) -> Option<Arc<dyn devman_tools::Tool>> {
-        match name {
-            "cargo" => Some(self.cargo_tool.clone()),
-            "git" => Some(self.git_tool.clone()),
-            "npm" => Some(self.npm_tool.clone()),
-            "fs" => Some(self.fs_tool.clone()),
-            _ => None,
-        }
-    }
-}
-
-#[async_trait::async_trait]
-impl devman_tools::ToolExecutor for BuiltinToolExecutor {
-    async fn execute_tool(&self, tool: &str, input: devman_tools::ToolInput) -> Result<devman_tools::ToolOutput, anyhow::Error> {
-        if let Some(t) = self.find_tool(tool) {
-            t.execute(&input).await
-        } else {
-            anyhow::bail!("Unknown tool: {}", tool)
-        }
-    }
-}
-
 /// Create a real AI interface with storage-backed implementations.
 /// This provides full functionality for MCP tools.
-async fn create_ai_interface(storage_path: &std::path::Path) -> Arc<dyn devman_ai::AIInterface> {
+/// Requeue tasks whose work was still running when the last server
+/// stopped, marking that work as interrupted.
+async fn recover_interrupted_work(storage_path: &std::path::Path) -> Result<()> {
+    let storage = devman_storage::JsonStorage::new(storage_path).await?;
+    devman_work::BasicWorkManager::new(storage)
+        .recover_interrupted("server restarted")
+        .await?;
+    Ok(())
+}
+
+async fn create_ai_interface(
+    storage_path: &std::path::Path,
+    dependency_cascade: bool,
+    no_cache: bool,
+) -> Arc<dyn devman_ai::AIInterface> {
     use devman_storage::JsonStorage;
 
     // Create shared storage for all components
@@ -190,23 +200,34 @@ async fn create_ai_interface(storage_path: &std::path::Path) -> Arc<dyn devman_a
         storage: storage.clone(),
     };
 
-    // Create quality engine with storage
-    let quality_engine = SimpleQualityEngine {
-        storage: storage.clone(),
-    };
+    // Create tool executor, shared with the quality engine's command checks
+    let tool_executor: Arc<dyn devman_tools::ToolExecutor> = Arc::new(devman_tools::ToolRegistry::new());
 
-    // Create tool executor
-    let tool_executor = Arc::new(BuiltinToolExecutor::new());
+    // Quality checks read the same store through their own handle
+    let quality_storage = JsonStorage::new(storage_path).await.expect("storage directory checked writable at startup");
+    let mut quality_engine = devman_quality::BasicQualityEngine::new(quality_storage, tool_executor.clone());
+    if !no_cache {
+        quality_engine = quality_engine
+            .with_cache(devman_quality::CheckCache::new(storage_path.join(devman_quality::cache::DEFAULT_CACHE_DIR)));
+    }
 
     // Create and return the AI interface
-    Arc::new(devman_ai::BasicAIInterface::new(
+    let mut ai_interface = devman_ai::BasicAIInterface::new(
         storage,
         Arc::new(Mutex::new(work_manager)),
         Arc::new(progress_tracker),
         Arc::new(knowledge_service),
         Arc::new(quality_engine),
         tool_executor,
-    ))
+    )
+    .with_dependency_cascade(dependency_cascade);
+
+    // Blocker detection reads the same store through its own handle
+    if let Ok(detector_storage) = JsonStorage::new(storage_path).await {
+        ai_interface = ai_interface
+            .with_blocker_detector(devman_progress::BlockerDetector::new(Arc::new(detector_storage)));
+    }
+    Arc::new(ai_interface)
 }
 
 /// Simple work manager that delegates to storage.
@@ -232,7 +253,18 @@ impl devman_work::WorkManager for SimpleWorkManager {
             phase_id: spec.phase_id,
             depends_on: Vec::new(),
             blocks: Vec::new(),
+            parent: None,
+            children: Vec::new(),
+            archived: false,
+            priority: spec.priority.value(),
             work_records: Vec::new(),
+            actuals: None,
+            claim: None,
+            state: None,
+            merged_into: None,
+            base_priority: None,
+            due_at: spec.due_at,
+            created_knowledge: Vec::new(),
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
@@ -244,8 +276,7 @@ impl devman_work::WorkManager for SimpleWorkManager {
         let mut storage = self.storage.lock().await;
         let mut task = storage.load_task(task_id).await?
             .ok_or_else(|| anyhow::anyhow!("Task not found"))?;
-        task.status = devman_core::TaskStatus::Active;
-        storage.save_task(&task).await?;
+        task.set_status(devman_core::TaskStatus::Active);
 
         let work_record = devman_core::WorkRecord {
             id: devman_core::WorkRecordId::new(),
@@ -269,12 +300,15 @@ impl devman_work::WorkManager for SimpleWorkManager {
                     quality_checks_run: 0,
                     quality_checks_passed: 0,
                 },
+                created_knowledge: Vec::new(),
             },
             artifacts: Vec::new(),
             issues: Vec::new(),
             resolutions: Vec::new(),
         };
         storage.save_work_record(&work_record).await?;
+        task.work_records.push(work_record.id);
+        storage.save_task(&task).await?;
         Ok(work_record)
     }
 
@@ -306,9 +340,15 @@ impl devman_work::WorkManager for SimpleWorkManager {
         let mut storage = self.storage.lock().await;
         let mut task = storage.load_task(task_id).await?
             .ok_or_else(|| anyhow::anyhow!("Task not found"))?;
-        task.status = devman_core::TaskStatus::Done;
-        task.progress.message = "Completed".to_string();
-        task.progress.percentage = 100.0;
+        if let devman_core::CompletionStatus::Interrupted { reason, .. } = result.status {
+            // Interrupted work goes back to the queue with its progress intact
+            task.set_status(devman_core::TaskStatus::Queued);
+            task.progress.message = format!("Interrupted: {}", reason);
+        } else {
+            task.set_status(devman_core::TaskStatus::Done);
+            task.progress.message = "Completed".to_string();
+            task.progress.percentage = 100.0;
+        }
         task.updated_at = chrono::Utc::now();
         storage.save_task(&task).await?;
         Ok(())
@@ -372,6 +412,7 @@ impl devman_knowledge::KnowledgeService for SimpleKnowledgeService {
         let storage = self.storage.lock().await;
         storage.list_knowledge().await.unwrap_or_default()
             .into_iter()
+            .filter(|k| !k.archived)
             .filter(|k| k.title.to_lowercase().contains(&query.to_lowercase())
                 || k.content.summary.to_lowercase().contains(&query.to_lowercase())
                 || k.content.detail.to_lowercase().contains(&query.to_lowercase()))
@@ -387,6 +428,7 @@ impl devman_knowledge::KnowledgeService for SimpleKnowledgeService {
         let storage = self.storage.lock().await;
         storage.list_knowledge().await.unwrap_or_default()
             .into_iter()
+            .filter(|k| !k.archived)
             .filter(|k| matches!(k.knowledge_type, devman_core::KnowledgeType::BestPractice { .. }))
             .filter(|k| k.title.to_lowercase().contains(&domain.to_lowercase())
                 || k.content.summary.to_lowercase().contains(&domain.to_lowercase())
@@ -404,6 +446,7 @@ impl devman_knowledge::KnowledgeService for SimpleKnowledgeService {
         let storage = self.storage.lock().await;
         storage.list_knowledge().await.unwrap_or_default()
             .into_iter()
+            .filter(|k| !k.archived)
             .filter(|k| tags.iter().any(|t| k.tags.contains(t)))
             .take(limit)
             .collect()
@@ -417,6 +460,7 @@ impl devman_knowledge::KnowledgeService for SimpleKnowledgeService {
         let storage = self.storage.lock().await;
         storage.list_knowledge().await.unwrap_or_default()
             .into_iter()
+            .filter(|k| !k.archived)
             .flat_map(|k| k.tags.into_iter())
             .collect()
     }
@@ -440,56 +484,45 @@ impl devman_knowledge::KnowledgeService for SimpleKnowledgeService {
         let storage = self.storage.lock().await;
         storage.list_knowledge().await.unwrap_or_default()
             .into_iter()
+            .filter(|k| !k.archived)
             .filter(|k| k.knowledge_type == knowledge_type)
             .collect()
     }
 
     async fn suggest_tags(&self, query: &str, limit: usize) -> Vec<String> {
-        let all_tags = self.get_all_tags().await;
-        all_tags.into_iter()
-            .filter(|t| t.to_lowercase().contains(&query.to_lowercase()))
-            .take(limit)
-            .collect()
+        devman_knowledge::rank_tags(query, self.get_all_tags().await, limit)
     }
-}
 
-/// Simple quality engine that delegates to storage.
-struct SimpleQualityEngine {
-    storage: Arc<Mutex<dyn devman_storage::Storage>>,
-}
+    async fn list_recent(&self, limit: usize) -> Vec<devman_core::Knowledge> {
+        let storage = self.storage.lock().await;
+        storage.list_recent_knowledge(limit).await.unwrap_or_default()
+    }
 
-#[async_trait::async_trait]
-impl devman_quality::QualityEngine for SimpleQualityEngine {
-    async fn run_check(&self, check: &devman_core::QualityCheck, _context: &devman_quality::engine::WorkContext) -> devman_core::QualityCheckResult {
-        devman_core::QualityCheckResult {
-            check_id: check.id,
-            passed: true,
-            execution_time: std::time::Duration::ZERO,
-            details: devman_core::CheckDetails {
-                output: String::new(),
-                exit_code: None,
-                error: None,
-            },
-            findings: Vec::new(),
-            metrics: Vec::new(),
-            human_review: None,
-        }
+    async fn submit_feedback(
+        &self,
+        id: devman_core::KnowledgeId,
+        feedback: devman_core::Feedback,
+    ) -> anyhow::Result<devman_core::Knowledge> {
+        let mut storage = self.storage.lock().await;
+        let mut knowledge = storage
+            .load_knowledge(id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Knowledge not found: {}", id))?;
+        knowledge.apply_feedback(feedback);
+        storage.save_knowledge(&knowledge).await?;
+        Ok(knowledge)
     }
 
-    async fn run_checks(&self, checks: &[devman_core::QualityCheck], context: &devman_quality::engine::WorkContext) -> Vec<devman_core::QualityCheckResult> {
-        let mut results = Vec::new();
-        for check in checks {
-            results.push(self.run_check(check, context).await);
-        }
-        results
+    async fn prune(
+        &self,
+        policy: devman_knowledge::PrunePolicy,
+    ) -> anyhow::Result<devman_knowledge::PruneReport> {
+        let mut storage = self.storage.lock().await;
+        devman_knowledge::prune::prune_storage(&mut *storage, &policy, chrono::Utc::now()).await
     }
 
-    async fn run_gate(&self, gate: &devman_core::QualityGate, _context: &devman_quality::engine::WorkContext) -> devman_quality::engine::GateResult {
-        devman_quality::engine::GateResult {
-            gate_name: gate.name.clone(),
-            passed: true,
-            check_results: Vec::new(),
-            decision: devman_quality::engine::GateDecision::Pass,
-        }
+    async fn statistics(&self) -> anyhow::Result<devman_knowledge::KnowledgeStats> {
+        devman_knowledge::stats::compute_statistics(&*self.storage.lock().await).await
     }
 }
+
@@ -9,13 +9,300 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter};
 use tokio::net::UnixStream;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
+use crate::audit::{self, AuditEntry, AuditLog, AuditStatus};
+use crate::interactive::QualityDecision;
 use crate::interface::{GoalSpec, TaskFilter};
+use crate::locale::{Locale, MessageCatalog};
+use crate::schema;
 use crate::job_manager::JobId;
+use crate::job_manager::error_codes::DevManErrorCode;
 use crate::{AIInterface, JobManager};
 use devman_work::TaskSpec;
 
+/// Maximum number of items served by the `devman://knowledge/recent` resource.
+const RECENT_KNOWLEDGE_LIMIT: usize = 20;
+
+/// Token budget for `devman_read_task_context` when `max_tokens` is not given.
+const DEFAULT_CONTEXT_TOKENS: usize = 4000;
+
+/// Create an error response using the code's default retryability.
+fn mcp_error(
+    code: DevManErrorCode,
+    message: &str,
+    data: Option<serde_json::Value>,
+) -> serde_json::Value {
+    create_mcp_error_response(code.code(), message, data, code.retryable())
+}
+
+/// Error response for a failed AI interface call.
+///
+/// Missing entities are reported as `NotFound` with the entity type and id
+/// in `data`, lost races and held claims as `StateConflict`, unmet phase
+/// gates as `StateConflict` with the unmet items in `data`, invalid phase
+/// orders as `InvalidParams`; anything else is a business error prefixed
+/// with `context`.
+fn interface_error(context: &str, error: &anyhow::Error) -> serde_json::Value {
+    if let Some(gate) = error.downcast_ref::<crate::PhaseGateError>() {
+        return mcp_error(
+            DevManErrorCode::StateConflict,
+            &format!("{}: {}", context, error),
+            Some(json!({"phase_id": gate.phase.to_string(), "unmet": gate.unmet})),
+        );
+    }
+    match error.downcast_ref::<devman_storage::StorageError>() {
+        Some(devman_storage::StorageError::NotFound { entity_type, id }) => mcp_error(
+            DevManErrorCode::NotFound,
+            &error.to_string(),
+            Some(json!({"entity_type": entity_type, "id": id})),
+        ),
+        Some(devman_storage::StorageError::Conflict { .. }) => mcp_error(
+            DevManErrorCode::StateConflict,
+            &format!("{}: {}", context, error),
+            None,
+        ),
+        _ if error.downcast_ref::<crate::ClaimError>().is_some() => mcp_error(
+            DevManErrorCode::StateConflict,
+            &format!("{}: {}", context, error),
+            None,
+        ),
+        _ if error.downcast_ref::<crate::TemplateError>().is_some() => mcp_error(
+            DevManErrorCode::InvalidParams,
+            &format!("{}: {}", context, error),
+            None,
+        ),
+        _ if error.downcast_ref::<devman_core::PhaseOrderError>().is_some() => mcp_error(
+            DevManErrorCode::InvalidParams,
+            &format!("{}: {}", context, error),
+            None,
+        ),
+        _ => mcp_error(
+            DevManErrorCode::Business,
+            &format!("{}: {}", context, error),
+            None,
+        ),
+    }
+}
+
+/// Phase IDs from an optional JSON array; an absent value is an empty list,
+/// anything but an array of valid IDs is `None`.
+fn parse_phase_ids(value: Option<&serde_json::Value>) -> Option<Vec<devman_core::PhaseId>> {
+    match value {
+        None | Some(serde_json::Value::Null) => Some(Vec::new()),
+        Some(value) => value
+            .as_array()?
+            .iter()
+            .map(|id| id.as_str()?.parse().ok())
+            .collect(),
+    }
+}
+
+/// JSON view of a blocker for tool responses.
+fn blocker_json(blocker: &devman_core::Blocker) -> serde_json::Value {
+    let (item_type, item_id) = match &blocker.blocked_item {
+        devman_core::BlockedItem::Task(id) => ("task", id.to_string()),
+        devman_core::BlockedItem::Phase(id) => ("phase", id.to_string()),
+        devman_core::BlockedItem::Goal(id) => ("goal", id.to_string()),
+    };
+    json!({
+        "id": blocker.id.to_string(),
+        "item_type": item_type,
+        "item_id": item_id,
+        "reason": blocker.reason,
+        "severity": format!("{:?}", blocker.severity),
+        "category": blocker.category,
+        "created_at": blocker.created_at,
+        "resolved_at": blocker.resolved_at
+    })
+}
+
+/// JSON view of a task for list responses.
+fn task_summary_json(task: &devman_core::Task) -> serde_json::Value {
+    json!({
+        "task_id": task.id.to_string(),
+        "title": task.title,
+        "status": format!("{:?}", task.status),
+        "priority": task.priority_level().as_str(),
+        "priority_value": task.priority,
+        "archived": task.archived,
+        "parent_id": task.parent.map(|id| id.to_string())
+    })
+}
+
+/// Summaries of `tasks` with each listed sub-task nested under its parent's
+/// `subtasks`; tasks whose parent is not listed are top-level.
+fn nested_task_summaries(tasks: &[devman_core::Task]) -> Vec<serde_json::Value> {
+    fn node(
+        task: &devman_core::Task,
+        listed: &HashMap<devman_core::TaskId, &devman_core::Task>,
+    ) -> serde_json::Value {
+        let mut summary = task_summary_json(task);
+        summary["subtasks"] = task
+            .children
+            .iter()
+            .filter_map(|id| listed.get(id))
+            .map(|child| node(child, listed))
+            .collect();
+        summary
+    }
+
+    let listed: HashMap<_, _> = tasks.iter().map(|t| (t.id, t)).collect();
+    tasks
+        .iter()
+        .filter(|t| t.parent.is_none_or(|parent| !listed.contains_key(&parent)))
+        .map(|t| node(t, &listed))
+        .collect()
+}
+
+/// Idempotency key from `arguments`, scoped to `tool` so the same key
+/// sent to different create tools does not collide.
+fn idempotency_key(tool: &str, arguments: &serde_json::Value) -> Option<String> {
+    arguments
+        .get("idempotency_key")
+        .and_then(|v| v.as_str())
+        .filter(|k| !k.is_empty())
+        .map(|k| format!("{}:{}", tool, k))
+}
+
+/// Read an optional priority level name from `arguments[key]`.
+fn priority_arg(
+    arguments: &serde_json::Value,
+    key: &str,
+) -> Result<Option<devman_core::Priority>, serde_json::Value> {
+    let Some(value) = arguments.get(key).filter(|v| !v.is_null()) else {
+        return Ok(None);
+    };
+    value
+        .as_str()
+        .unwrap_or_default()
+        .parse()
+        .map(Some)
+        .map_err(|_: devman_core::ParsePriorityError| {
+            mcp_error(
+                DevManErrorCode::InvalidParams,
+                &format!("Invalid {}: {}, expected one of Critical, High, Medium, Low, Trivial", key, value),
+                None,
+            )
+        })
+}
+
+/// Read an optional RFC 3339 timestamp from `arguments[key]`.
+fn time_arg(arguments: &serde_json::Value, key: &str) -> Result<Option<devman_core::Time>, serde_json::Value> {
+    let Some(value) = arguments.get(key).filter(|v| !v.is_null()) else {
+        return Ok(None);
+    };
+    value.as_str().unwrap_or_default().parse().map(Some).map_err(|_: chrono::ParseError| {
+        mcp_error(
+            DevManErrorCode::InvalidParams,
+            &format!("Invalid {}: {}, expected an RFC 3339 timestamp", key, value),
+            None,
+        )
+    })
+}
+
+/// Re-embed all knowledge on behalf of job `job_id`, reporting progress to
+/// the job and stopping once it is cancelled.
+async fn run_reindex_job(
+    job_manager: Arc<dyn JobManager>,
+    vector_service: Arc<dyn devman_knowledge::VectorKnowledgeService>,
+    job_id: JobId,
+    cancel: devman_tools::CancellationToken,
+) {
+    // The callback runs synchronously, so progress goes to the job from here
+    let (progress_tx, mut progress_rx) = tokio::sync::watch::channel(None::<devman_knowledge::ReindexProgress>);
+    let reporter = {
+        let job_manager = job_manager.clone();
+        let job_id = job_id.clone();
+        tokio::spawn(async move {
+            while progress_rx.changed().await.is_ok() {
+                let Some(progress) = *progress_rx.borrow_and_update() else {
+                    continue;
+                };
+                let message = format!("Embedded {} of {} knowledge items", progress.done, progress.total);
+                if let Err(e) = job_manager.update_progress(&job_id, progress.percentage(), message).await {
+                    debug!("Reindex job {} no longer takes progress: {}", job_id, e.message);
+                }
+            }
+        })
+    };
+    let result = vector_service
+        .reindex_all_cancellable(&cancel, &move |progress| {
+            progress_tx.send_replace(Some(progress));
+        })
+        .await;
+    reporter.await.ok();
+
+    let outcome = result.map(|embedded| json!({"embedded": embedded})).map_err(|e| crate::JobError {
+        code: DevManErrorCode::Business.code(),
+        message: format!("Reindex failed: {:#}", e),
+        hint: Some("Items embedded so far are kept; run devman_reindex_knowledge again to resume.".to_string()),
+        retryable: true,
+        data: None,
+    });
+    if let Err(e) = job_manager.finish_job(&job_id, outcome).await {
+        warn!("Could not finish reindex job {}: {}", job_id, e.message);
+    }
+}
+
+/// Gate results of a task's quality check.
+fn quality_check_json(task_id: devman_core::TaskId, results: &[devman_quality::engine::GateResult]) -> serde_json::Value {
+    let gates: Vec<_> = results
+        .iter()
+        .map(|gate| {
+            json!({
+                "gate": gate.gate_name,
+                "passed": gate.passed,
+                "decision": format!("{:?}", gate.decision),
+                "checks": gate.check_results.iter().map(|check| json!({
+                    "check_id": check.check_id.to_string(),
+                    "passed": check.passed,
+                })).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    json!({
+        "task_id": task_id.to_string(),
+        "passed": results.iter().all(|gate| gate.passed),
+        "gates": gates,
+    })
+}
+
+/// Parse a task status name.
+fn parse_task_status(name: &str) -> Option<devman_core::TaskStatus> {
+    use devman_core::TaskStatus;
+    Some(match name {
+        "Idea" => TaskStatus::Idea,
+        "Queued" => TaskStatus::Queued,
+        "Active" => TaskStatus::Active,
+        "Blocked" => TaskStatus::Blocked,
+        "Review" => TaskStatus::Review,
+        "Done" => TaskStatus::Done,
+        "Abandoned" => TaskStatus::Abandoned,
+        _ => return None,
+    })
+}
+
+/// Abandon reason for a `devman_abandon_task` reason type. Types whose
+/// details the tool does not take are recorded as `Other`.
+fn abandon_reason(reason_type: &str, reason: String) -> devman_core::AbandonReason {
+    use devman_core::AbandonReason;
+    match reason_type {
+        "voluntary" => AbandonReason::Voluntary { reason, can_be_reassigned: true },
+        "project_cancelled" => AbandonReason::ProjectCancelled { reason, cancelled_by: String::new() },
+        "requirement_changed" => AbandonReason::RequirementChanged {
+            old_requirement: String::new(),
+            new_requirement: reason,
+            impact: devman_core::ChangeImpact::NeedsRestart,
+        },
+        "insufficient_info" => AbandonReason::InsufficientInformation { missing_info: vec![reason] },
+        "technical_limitation" => AbandonReason::TechnicalLimitation { limitation: reason, suggested_alternative: None },
+        "resource_unavailable" => AbandonReason::ResourceUnavailable { resource: String::new(), reason },
+        "quality_failed" => AbandonReason::QualityCheckFailed { attempts: 0, remaining_issues: vec![reason] },
+        other => AbandonReason::Other { reason, details: Some(other.to_string()) },
+    }
+}
+
 /// Create an error response with DevMan error codes.
 fn create_mcp_error_response(
     code: i32,
@@ -77,7 +364,9 @@ fn is_mcp_error_response(response: &serde_json::Value) -> bool {
 pub struct JsonRpcRequest {
     #[serde(default)]
     pub jsonrpc: String,
-    #[serde(default)]
+    /// `None` when the member is absent (a notification); an explicit
+    /// `null` id is kept as `Some(Value::Null)`
+    #[serde(default, deserialize_with = "deserialize_present")]
     pub id: Option<serde_json::Value>,
     #[serde(default)]
     pub method: String,
@@ -85,6 +374,14 @@ pub struct JsonRpcRequest {
     pub params: serde_json::Value,
 }
 
+/// Deserialize a member that is present, even as `null`, to `Some`.
+fn deserialize_present<'de, D>(deserializer: D) -> Result<Option<serde_json::Value>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    serde_json::Value::deserialize(deserializer).map(Some)
+}
+
 /// JSON-RPC 2.0 Response wrapper
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JsonRpcResponse {
@@ -108,6 +405,15 @@ pub struct JsonRpcError {
 
 impl JsonRpcResponse {
     fn error(id: Option<serde_json::Value>, code: i32, message: &str) -> Self {
+        Self::error_with_data(id, code, message, None)
+    }
+
+    fn error_with_data(
+        id: Option<serde_json::Value>,
+        code: i32,
+        message: &str,
+        data: Option<serde_json::Value>,
+    ) -> Self {
         Self {
             jsonrpc: "2.0".to_string(),
             id,
@@ -115,7 +421,7 @@ impl JsonRpcResponse {
             error: Some(JsonRpcError {
                 code,
                 message: message.to_string(),
-                data: None,
+                data,
             }),
         }
     }
@@ -146,6 +452,9 @@ fn parse_json_rpc_request(line: &str) -> Result<(Option<serde_json::Value>, Stri
     Ok((request.id, request.method, request.params))
 }
 
+/// Protocol version answered when a client does not ask for one.
+const DEFAULT_PROTOCOL_VERSION: &str = "2024-11-05";
+
 /// DevMan MCP server configuration.
 #[derive(Debug, Clone)]
 pub struct McpServerConfig {
@@ -157,6 +466,22 @@ pub struct McpServerConfig {
     pub version: String,
     /// Unix socket path for stdio transport
     pub socket_path: Option<std::path::PathBuf>,
+    /// How long an `idempotency_key` on a create request is remembered
+    pub idempotency_window: chrono::Duration,
+    /// Fail tool calls that need an AI interface when none is set, instead
+    /// of answering with a placeholder success response
+    pub strict: bool,
+    /// JSONL file every tool call is appended to; no audit log when `None`
+    pub audit_log: Option<std::path::PathBuf>,
+    /// Whether knowledge search is backed by embeddings; only reported to
+    /// clients through `devman/describe`
+    pub vector_search: bool,
+    /// How long socket connections get to finish the request they are
+    /// handling when the server shuts down
+    pub shutdown_grace: std::time::Duration,
+    /// Language of guidance and next-action messages; read from
+    /// `DEVMAN_LOCALE` by default
+    pub locale: Locale,
 }
 
 impl Default for McpServerConfig {
@@ -166,10 +491,36 @@ impl Default for McpServerConfig {
             server_name: "devman".to_string(),
             version: env!("CARGO_PKG_VERSION").to_string(),
             socket_path: None,
+            idempotency_window: chrono::Duration::hours(24),
+            strict: false,
+            audit_log: None,
+            vector_search: false,
+            shutdown_grace: std::time::Duration::from_secs(5),
+            locale: Locale::from_env(),
         }
     }
 }
 
+/// Tools whose handlers never use the AI interface, so strict mode lets
+/// them through without one.
+const TOOLS_WITHOUT_AI_INTERFACE: &[&str] = &[
+    "devman_get_context",
+    "devman_get_task_guidance",
+    "devman_read_task_context",
+    "devman_review_knowledge",
+    "devman_confirm_knowledge_reviewed",
+    "devman_start_execution",
+    "devman_log_work",
+    "devman_finish_work",
+    "devman_get_quality_result",
+    "devman_confirm_quality_result",
+    "devman_complete_task",
+    "devman_pause_task",
+    "devman_resume_task",
+    "devman_abandon_task",
+    "devman_reindex_knowledge",
+];
+
 /// Tool definition for MCP protocol.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McpTool {
@@ -194,6 +545,31 @@ pub struct McpResource {
     pub mime_type: Option<String>,
 }
 
+/// Parameterized resource definition for MCP protocol.
+///
+/// `uri_template` holds a single `{id}` placeholder, e.g. `devman://tasks/{id}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpResourceTemplate {
+    /// URI pattern with an `{id}` placeholder
+    pub uri_template: String,
+    /// Template name
+    pub name: String,
+    /// Template description
+    pub description: String,
+    /// MIME type
+    pub mime_type: Option<String>,
+}
+
+impl McpResourceTemplate {
+    /// Extract the `{id}` part of `uri` if it matches this template.
+    pub fn match_uri<'a>(&self, uri: &'a str) -> Option<&'a str> {
+        let (prefix, suffix) = self.uri_template.split_once("{id}")?;
+        let id = uri.strip_prefix(prefix)?.strip_suffix(suffix)?;
+        (!id.is_empty() && !id.contains('/')).then_some(id)
+    }
+}
+
 /// MCP Request message.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "method")]
@@ -220,6 +596,10 @@ pub enum McpRequest {
     #[serde(rename = "resources/list")]
     ResourcesList,
 
+    /// List resource templates request
+    #[serde(rename = "resources/templates/list")]
+    ResourceTemplatesList,
+
     /// Read resource request
     #[serde(rename = "resources/read")]
     ResourcesRead { uri: String },
@@ -261,12 +641,20 @@ pub struct McpServer {
     pub tools: HashMap<String, McpTool>,
     /// Registered resources
     pub resources: HashMap<String, McpResource>,
+    /// Registered resource templates, matched in order
+    pub resource_templates: Vec<McpResourceTemplate>,
     /// AI interface reference
     pub ai_interface: Option<Arc<dyn AIInterface>>,
     /// Job manager for async tasks
     job_manager: Option<Arc<dyn JobManager>>,
+    /// Vector search, reindexed by `devman_reindex_knowledge`
+    vector_service: Option<Arc<dyn devman_knowledge::VectorKnowledgeService>>,
     /// Storage path for resources
     storage_path: std::path::PathBuf,
+    /// Log of tool calls, when configured
+    audit_log: Option<AuditLog>,
+    /// Guidance messages in the configured locale
+    messages: MessageCatalog,
 }
 
 impl McpServer {
@@ -282,9 +670,13 @@ impl McpServer {
             running: false,
             tools: HashMap::new(),
             resources: HashMap::new(),
+            resource_templates: Vec::new(),
             ai_interface: None,
             job_manager: None,
+            vector_service: None,
             storage_path: config.storage_path.clone(),
+            audit_log: config.audit_log.clone().map(AuditLog::new),
+            messages: MessageCatalog::new(config.locale),
         };
 
         // Register built-in DevMan tools
@@ -301,6 +693,12 @@ impl McpServer {
         self.job_manager = Some(job_manager);
     }
 
+    /// Set the vector search whose embeddings `devman_reindex_knowledge`
+    /// rebuilds.
+    pub fn set_vector_service(&mut self, vector_service: Arc<dyn devman_knowledge::VectorKnowledgeService>) {
+        self.vector_service = Some(vector_service);
+    }
+
     /// Get the server configuration.
     pub fn config(&self) -> &McpServerConfig {
         &self.config
@@ -323,6 +721,25 @@ impl McpServer {
         debug!("Registered tool: {}", name);
     }
 
+    /// Export all registered tools in OpenAI function-calling format.
+    ///
+    /// Returns an array of `{name, description, parameters}` objects sorted by
+    /// name, where `parameters` is the tool's JSON Schema input.
+    pub fn export_tool_catalog(&self) -> serde_json::Value {
+        let mut tools: Vec<_> = self.tools.values().collect();
+        tools.sort_by(|a, b| a.name.cmp(&b.name));
+        serde_json::Value::Array(
+            tools
+                .into_iter()
+                .map(|tool| json!({
+                    "name": tool.name,
+                    "description": tool.description,
+                    "parameters": tool.input_schema
+                }))
+                .collect(),
+        )
+    }
+
     /// Register a resource with the MCP server.
     pub fn register_resource(&mut self, resource: McpResource) {
         let uri = resource.uri.clone();
@@ -330,6 +747,60 @@ impl McpServer {
         debug!("Registered resource: {}", uri);
     }
 
+    /// Run a create request for `tool` at most once per idempotency key.
+    ///
+    /// If `arguments` carry a key an earlier request used inside the
+    /// window, the entity that request made is loaded by id with `load` and
+    /// returned instead of calling `create`. Otherwise the entity `create`
+    /// makes is recorded under the key; failing to record it is logged
+    /// rather than returned, since the entity exists either way. Returns the
+    /// entity and whether it was replayed.
+    async fn create_once<T, L, LF, C, CF>(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        tool: &str,
+        arguments: &serde_json::Value,
+        entity_id: fn(&T) -> String,
+        load: L,
+        create: C,
+    ) -> Result<(T, bool), serde_json::Value>
+    where
+        L: FnOnce(String) -> LF,
+        LF: std::future::Future<Output = Option<T>>,
+        C: FnOnce() -> CF,
+        CF: std::future::Future<Output = Result<T, serde_json::Value>>,
+    {
+        let key = idempotency_key(tool, arguments);
+        if let Some(key) = &key {
+            let earlier = ai_interface
+                .find_idempotent_entity(key, self.config.idempotency_window)
+                .await;
+            if let Some(entity) = match earlier {
+                Some(id) => load(id).await,
+                None => None,
+            } {
+                return Ok((entity, true));
+            }
+        }
+
+        let entity = create().await?;
+        if let Some(key) = &key {
+            if let Err(e) = ai_interface
+                .remember_idempotent_entity(key, &entity_id(&entity), self.config.idempotency_window)
+                .await
+            {
+                error!("Failed to record idempotency key {}: {}", key, e);
+            }
+        }
+        Ok((entity, false))
+    }
+
+    /// Register a resource template with the MCP server.
+    pub fn register_resource_template(&mut self, template: McpResourceTemplate) {
+        debug!("Registered resource template: {}", template.uri_template);
+        self.resource_templates.push(template);
+    }
+
     /// Register built-in DevMan tools.
     fn register_builtin_tools(&mut self) {
         // Goal management tools
@@ -339,6 +810,7 @@ impl McpServer {
             input_schema: json!({
                 "type": "object",
                 "properties": {
+                    "idempotency_key": {"type": "string", "description": "Client-chosen key; repeating a create with the same key returns the entity created the first time"},
                     "title": {"type": "string", "description": "Goal title"},
                     "description": {"type": "string", "description": "Goal description"},
                     "success_criteria": {
@@ -363,6 +835,18 @@ impl McpServer {
             }),
         });
 
+        self.register_tool(McpTool {
+            name: "devman_get_goal_tree".to_string(),
+            description: "Get a goal's phases and tasks as a tree, with sub-tasks nested under their parents and progress rolled up at every level".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "goal_id": {"type": "string", "description": "Goal ID"}
+                },
+                "required": ["goal_id"]
+            }),
+        });
+
         // Task management tools
         self.register_tool(McpTool {
             name: "devman_create_task".to_string(),
@@ -370,15 +854,68 @@ impl McpServer {
             input_schema: json!({
                 "type": "object",
                 "properties": {
+                    "idempotency_key": {"type": "string", "description": "Client-chosen key; repeating a create with the same key returns the entity created the first time"},
                     "title": {"type": "string", "description": "Task title"},
                     "description": {"type": "string", "description": "Task description"},
                     "goal_id": {"type": "string", "description": "Associated goal ID"},
-                    "phase_id": {"type": "string", "description": "Associated phase ID"}
+                    "phase_id": {"type": "string", "description": "Associated phase ID"},
+                    "affected_files": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Files the task is expected to change"
+                    },
+                    "priority": {
+                        "type": "string",
+                        "enum": ["Critical", "High", "Medium", "Low", "Trivial"],
+                        "description": "Priority level (default: Medium)"
+                    },
+                    "due_at": {"type": "string", "description": "Deadline as an RFC 3339 timestamp; the task's priority rises as it nears"},
+                    "allow_duplicate": {"type": "boolean", "description": "Create the task even if a similar open task exists (default: false)"},
+                    "quality_profile": {"type": "string", "description": "Quality profile whose gates the task gets instead of the project's default; a profile without checks opts out"}
                 },
                 "required": ["title"]
             }),
         });
 
+        self.register_tool(McpTool {
+            name: "devman_create_subtask".to_string(),
+            description: "Split a task by creating a sub-task under it".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "idempotency_key": {"type": "string", "description": "Client-chosen key; repeating a create with the same key returns the entity created the first time"},
+                    "parent_id": {"type": "string", "description": "Parent task ID"},
+                    "title": {"type": "string", "description": "Sub-task title"},
+                    "description": {"type": "string", "description": "Sub-task description"},
+                    "priority": {
+                        "type": "string",
+                        "enum": ["Critical", "High", "Medium", "Low", "Trivial"],
+                        "description": "Priority level (default: Medium)"
+                    },
+                    "due_at": {"type": "string", "description": "Deadline as an RFC 3339 timestamp; the task's priority rises as it nears"}
+                },
+                "required": ["parent_id", "title"]
+            }),
+        });
+
+        self.register_tool(McpTool {
+            name: "devman_create_from_template".to_string(),
+            description: "Create a task from a saved task template, filling its {{name}} placeholders. The task gets the template's steps and quality gates".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "template_id": {"type": "string", "description": "Task template ID"},
+                    "phase_id": {"type": "string", "description": "Phase the task belongs to"},
+                    "params": {
+                        "type": "object",
+                        "additionalProperties": {"type": "string"},
+                        "description": "Placeholder values by parameter name; parameters left out use their defaults"
+                    }
+                },
+                "required": ["template_id", "phase_id"]
+            }),
+        });
+
         self.register_tool(McpTool {
             name: "devman_list_tasks".to_string(),
             description: "List tasks with optional filters".to_string(),
@@ -390,11 +927,91 @@ impl McpServer {
                         "enum": ["Created", "InProgress", "Completed", "Abandoned"],
                         "description": "Filter by task state"
                     },
-                    "limit": {"type": "integer", "description": "Maximum results"}
+                    "limit": {"type": "integer", "description": "Maximum results"},
+                    "include_archived": {"type": "boolean", "description": "Include archived tasks (default: false)"},
+                    "min_priority": {
+                        "type": "string",
+                        "enum": ["Critical", "High", "Medium", "Low", "Trivial"],
+                        "description": "Only tasks at this priority level or above"
+                    },
+                    "roots_only": {"type": "boolean", "description": "Only tasks without a parent (default: false)"},
+                    "nested": {"type": "boolean", "description": "List root tasks with their sub-tasks nested under `subtasks`; filters and limit apply to the roots (default: false)"}
                 }
             }),
         });
 
+        self.register_tool(McpTool {
+            name: "devman_archive_task".to_string(),
+            description: "Archive a task: hide it from default listings but keep it recoverable. Use this instead of deleting.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "task_id": {"type": "string", "description": "Task ID"}
+                },
+                "required": ["task_id"]
+            }),
+        });
+
+        self.register_tool(McpTool {
+            name: "devman_unarchive_task".to_string(),
+            description: "Restore an archived task to default listings".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "task_id": {"type": "string", "description": "Task ID"}
+                },
+                "required": ["task_id"]
+            }),
+        });
+
+        self.register_tool(McpTool {
+            name: "devman_merge_tasks".to_string(),
+            description: "Merge a duplicate task into another: the survivor takes over its work records, sub-tasks, dependencies and affected files, dependents are redirected to the survivor, and the duplicate is archived".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "keep_task_id": {"type": "string", "description": "Task that survives the merge"},
+                    "merge_task_id": {"type": "string", "description": "Duplicate task to merge into it and archive"}
+                },
+                "required": ["keep_task_id", "merge_task_id"]
+            }),
+        });
+
+        self.register_tool(McpTool {
+            name: "devman_bulk_update".to_string(),
+            description: "Change the status of every task matching a filter, e.g. abandon all ideas older than 90 days. Each transition is validated; writes are all or nothing.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "status": {
+                        "type": "string",
+                        "enum": ["Idea", "Queued", "Active", "Blocked", "Review", "Done", "Abandoned"],
+                        "description": "New status"
+                    },
+                    "reason": {"type": "string", "description": "Why the tasks are being updated"},
+                    "filter": {
+                        "type": "object",
+                        "properties": {
+                            "state": {
+                                "type": "string",
+                                "enum": ["Idea", "Queued", "Active", "Blocked", "Review", "Done", "Abandoned"],
+                                "description": "Only tasks in this status"
+                            },
+                            "older_than_days": {"type": "integer", "description": "Only tasks created more than this many days ago"},
+                            "min_priority": {
+                                "type": "string",
+                                "enum": ["Critical", "High", "Medium", "Low", "Trivial"],
+                                "description": "Only tasks at this priority level or above"
+                            },
+                            "include_archived": {"type": "boolean", "description": "Include archived tasks (default: false)"},
+                            "limit": {"type": "integer", "description": "Maximum tasks to update"}
+                        }
+                    }
+                },
+                "required": ["status", "reason"]
+            }),
+        });
+
         // Knowledge tools
         self.register_tool(McpTool {
             name: "devman_search_knowledge".to_string(),
@@ -409,6 +1026,15 @@ impl McpServer {
             }),
         });
 
+        self.register_tool(McpTool {
+            name: "devman_reindex_knowledge".to_string(),
+            description: "Re-embed all knowledge with the configured embedding model as an async job; poll it with devman_get_job_status and stop it with devman_cancel_job, a later reindex resumes where it stopped".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {}
+            }),
+        });
+
         self.register_tool(McpTool {
             name: "devman_save_knowledge".to_string(),
             description: "Save new knowledge to the knowledge base".to_string(),
@@ -431,6 +1057,29 @@ impl McpServer {
             }),
         });
 
+        self.register_tool(McpTool {
+            name: "devman_rate_knowledge".to_string(),
+            description: "Rate a knowledge item as helpful or unhelpful. Unhelpful knowledge ranks lower in search.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "knowledge_id": {"type": "string", "description": "Knowledge ID"},
+                    "helpful": {"type": "boolean", "description": "Whether the knowledge was helpful"},
+                    "note": {"type": "string", "description": "Optional note explaining the rating"}
+                },
+                "required": ["knowledge_id", "helpful"]
+            }),
+        });
+
+        self.register_tool(McpTool {
+            name: "devman_knowledge_stats".to_string(),
+            description: "Summarise the knowledge base: counts by type, top tags, embedding coverage and average feedback rating".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {}
+            }),
+        });
+
         // Quality tools
         self.register_tool(McpTool {
             name: "devman_run_quality_check".to_string(),
@@ -443,7 +1092,8 @@ impl McpServer {
                         "enum": ["compile", "test", "lint", "format", "doc"],
                         "description": "Type of quality check"
                     },
-                    "target": {"type": "string", "description": "Optional target"}
+                    "target": {"type": "string", "description": "Optional target"},
+                    "changed_only": {"type": "boolean", "description": "Only check files changed in the git working tree (default: false)"}
                 }
             }),
         });
@@ -482,6 +1132,41 @@ impl McpServer {
             }),
         });
 
+        self.register_tool(McpTool {
+            name: "devman_get_task_timeline".to_string(),
+            description: "Get a task's full history: state transitions, work log, quality checks and notes, ordered by time.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "task_id": {"type": "string", "description": "Task ID"}
+                },
+                "required": ["task_id"]
+            }),
+        });
+
+        self.register_tool(McpTool {
+            name: "devman_recommend_next".to_string(),
+            description: "Recommend the next task to work on, with rationale, relevant knowledge and a readiness checklist. Blocked tasks and tasks claimed by other agents are never recommended.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "agent_id": {"type": "string", "description": "Calling agent; its own claimed tasks stay eligible"}
+                }
+            }),
+        });
+
+        self.register_tool(McpTool {
+            name: "devman_tasks_touching_file".to_string(),
+            description: "List tasks that declare a file in their affected files or have logged work on it. Use before editing a file to spot conflicting tasks.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "path": {"type": "string", "description": "Project-relative file path"}
+                },
+                "required": ["path"]
+            }),
+        });
+
         self.register_tool(McpTool {
             name: "devman_list_blockers".to_string(),
             description: "List current blockers".to_string(),
@@ -491,6 +1176,54 @@ impl McpServer {
             }),
         });
 
+        self.register_tool(McpTool {
+            name: "devman_add_blocker".to_string(),
+            description: "Record a blocker outside the task graph, e.g. waiting on a third party. It stays open until resolved with devman_resolve_blocker".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "item_type": {
+                        "type": "string",
+                        "enum": ["task", "phase", "goal"],
+                        "description": "Kind of item that is blocked"
+                    },
+                    "item_id": {"type": "string", "description": "ID of the blocked task, phase or goal"},
+                    "reason": {"type": "string", "description": "What the item is waiting on"},
+                    "severity": {
+                        "type": "string",
+                        "enum": ["info", "warning", "error", "critical"],
+                        "description": "Severity (default: warning)"
+                    }
+                },
+                "required": ["item_type", "item_id", "reason"]
+            }),
+        });
+
+        self.register_tool(McpTool {
+            name: "devman_resolve_blocker".to_string(),
+            description: "Mark a recorded blocker resolved".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "blocker_id": {"type": "string", "description": "Blocker ID"}
+                },
+                "required": ["blocker_id"]
+            }),
+        });
+
+        self.register_tool(McpTool {
+            name: "devman_assign_quality_profile".to_string(),
+            description: "Assign a quality profile to a phase; every task in the phase inherits the profile's quality gates".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "phase_id": {"type": "string", "description": "Phase ID"},
+                    "profile_id": {"type": "string", "description": "Quality profile ID"}
+                },
+                "required": ["phase_id", "profile_id"]
+            }),
+        });
+
         // ========== Task Guidance Tools (引导性工具) ==========
 
         self.register_tool(McpTool {
@@ -511,7 +1244,8 @@ impl McpServer {
             input_schema: json!({
                 "type": "object",
                 "properties": {
-                    "task_id": {"type": "string", "description": "Task ID"}
+                    "task_id": {"type": "string", "description": "Task ID"},
+                    "max_tokens": {"type": "integer", "description": "Token budget for knowledge, dependencies and history (default 4000)"}
                 },
                 "required": ["task_id"]
             }),
@@ -610,7 +1344,7 @@ impl McpServer {
 
         self.register_tool(McpTool {
             name: "devman_run_task_quality_check".to_string(),
-            description: "Run quality check for a task (WorkRecorded -> QualityChecking).".to_string(),
+            description: "Run quality check for a task (WorkRecorded -> QualityChecking). Runs the task's own gates and those inherited from its phase's quality profile.".to_string(),
             input_schema: json!({
                 "type": "object",
                 "properties": {
@@ -619,19 +1353,48 @@ impl McpServer {
                         "type": "array",
                         "items": {"type": "string"},
                         "description": "Types of checks: compile, test, lint, format, doc"
+                    },
+                    "async": {
+                        "type": "boolean",
+                        "description": "Run as a job and return its job_id at once; devman_cancel_job then kills the running checks"
                     }
                 },
                 "required": ["task_id"]
             }),
         });
 
+        self.register_tool(McpTool {
+            name: "devman_quality_history".to_string(),
+            description: "Get the results of every quality check run for a task, oldest first, and whether the latest run improved or regressed".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "task_id": {"type": "string", "description": "Task ID"}
+                },
+                "required": ["task_id"]
+            }),
+        });
+
+        self.register_tool(McpTool {
+            name: "devman_goal_quality".to_string(),
+            description: "Summarize quality across a goal's tasks from their latest quality runs: findings by severity, average coverage and the tasks with failing gates".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "goal_id": {"type": "string", "description": "Goal ID"}
+                },
+                "required": ["goal_id"]
+            }),
+        });
+
         self.register_tool(McpTool {
             name: "devman_get_quality_result".to_string(),
-            description: "Get quality check result by check ID.".to_string(),
+            description: "Get quality check result by check ID. With task_id, classifies the findings of the task's latest quality run and recommends a decision.".to_string(),
             input_schema: json!({
                 "type": "object",
                 "properties": {
-                    "check_id": {"type": "string", "description": "Quality check ID"}
+                    "check_id": {"type": "string", "description": "Quality check ID"},
+                    "task_id": {"type": "string", "description": "Task whose latest quality run to classify"}
                 },
                 "required": ["check_id"]
             }),
@@ -720,6 +1483,144 @@ impl McpServer {
                 "required": ["task_id", "reason_type", "reason"]
             }),
         });
+
+        self.register_tool(McpTool {
+            name: "devman_reopen_task".to_string(),
+            description: "Reopen a done or abandoned task (back to Queued), keeping its work history. Completed goals containing the task are reactivated".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "task_id": {"type": "string", "description": "Task ID"},
+                    "reason": {"type": "string", "description": "Why the task needs more work"}
+                },
+                "required": ["task_id", "reason"]
+            }),
+        });
+
+        self.register_tool(McpTool {
+            name: "devman_record_actuals".to_string(),
+            description: "Record how long a task actually took and how many tokens it used. Overrides actuals captured from work metrics on completion; used as ground truth for estimates".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "task_id": {"type": "string", "description": "Task ID"},
+                    "duration_minutes": {"type": "integer", "minimum": 0, "description": "Time actually spent, in minutes"},
+                    "tokens": {"type": "integer", "minimum": 0, "description": "Tokens actually used"}
+                },
+                "required": ["task_id", "duration_minutes"]
+            }),
+        });
+
+        self.register_tool(McpTool {
+            name: "devman_claim_task".to_string(),
+            description: "Claim a task so other agents sharing the store do not pick it up. The claim lapses after ttl_minutes unless renewed by claiming again; keep the returned token to release it".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "task_id": {"type": "string", "description": "Task ID"},
+                    "agent_id": {"type": "string", "description": "Identifier of the claiming agent"},
+                    "ttl_minutes": {"type": "integer", "minimum": 1, "description": "Minutes until the claim lapses (default: 30)"}
+                },
+                "required": ["task_id", "agent_id"]
+            }),
+        });
+
+        self.register_tool(McpTool {
+            name: "devman_release_task".to_string(),
+            description: "Release a task claim so other agents can pick the task up".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "task_id": {"type": "string", "description": "Task ID"},
+                    "token": {"type": "string", "description": "Token returned by devman_claim_task"}
+                },
+                "required": ["task_id", "token"]
+            }),
+        });
+
+        self.register_tool(McpTool {
+            name: "devman_insert_phase".to_string(),
+            description: "Add a phase to a project after an existing phase, or first when after_phase_id is omitted. Dependencies must be phases of the project that come before the new one".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "project_id": {"type": "string", "description": "Project ID"},
+                    "after_phase_id": {"type": "string", "description": "Phase to insert after (default: insert first)"},
+                    "name": {"type": "string", "description": "Phase name"},
+                    "description": {"type": "string", "description": "Phase description"},
+                    "objectives": {"type": "array", "items": {"type": "string"}, "description": "Phase objectives"},
+                    "depends_on": {"type": "array", "items": {"type": "string"}, "description": "IDs of phases that must come first"}
+                },
+                "required": ["project_id", "name"]
+            }),
+        });
+
+        self.register_tool(McpTool {
+            name: "devman_reorder_phases".to_string(),
+            description: "Reorder a project's phases. The order must list every phase exactly once and keep each phase after the phases it depends on".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "project_id": {"type": "string", "description": "Project ID"},
+                    "phase_ids": {"type": "array", "items": {"type": "string"}, "description": "All of the project's phase IDs in the new order"}
+                },
+                "required": ["project_id", "phase_ids"]
+            }),
+        });
+
+        self.register_tool(McpTool {
+            name: "devman_advance_phase".to_string(),
+            description: "Move a goal on to the next phase of its project. Every task of the current phase must be closed and the completed ones must have passed their quality gates; otherwise nothing changes and the unmet items are returned. Advancing past the last phase completes the goal".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "goal_id": {"type": "string", "description": "Goal ID"}
+                },
+                "required": ["goal_id"]
+            }),
+        });
+
+        self.register_tool(McpTool {
+            name: "devman_set_active_phase".to_string(),
+            description: "Make a phase of the goal's project the goal's current phase, skipping the gate check. Use to correct or override the goal's position".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "goal_id": {"type": "string", "description": "Goal ID"},
+                    "phase_id": {"type": "string", "description": "Phase to make current"}
+                },
+                "required": ["goal_id", "phase_id"]
+            }),
+        });
+
+        self.register_tool(McpTool {
+            name: "devman_preview_transition".to_string(),
+            description: "Dry-run a status change before committing it: whether it is allowed, which tasks a completion would unblock or an abandonment would strand, phase and goal progress before and after, whether quality gates have passed, and warnings. Changes nothing.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "task_id": {"type": "string", "description": "Task ID"},
+                    "to_state": {
+                        "type": "string",
+                        "enum": ["Idea", "Queued", "Active", "Blocked", "Review", "Done", "Abandoned"],
+                        "description": "Status to preview"
+                    }
+                },
+                "required": ["task_id", "to_state"]
+            }),
+        });
+
+        self.register_tool(McpTool {
+            name: "devman_abandonment_impact".to_string(),
+            description: "Preview what abandoning a task would break: open tasks that depend on it (transitively), phases and goals that would stall, and suggested mitigations".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "task_id": {"type": "string", "description": "Task ID"}
+                },
+                "required": ["task_id"]
+            }),
+        });
     }
 
     /// Register built-in DevMan resources.
@@ -728,7 +1629,7 @@ impl McpServer {
         self.register_resource(McpResource {
             uri: "devman://context/project".to_string(),
             name: "Current Project Context".to_string(),
-            description: "Current project configuration and state".to_string(),
+            description: "Snapshot for orienting at the start of a session: active goals, phase progress, open blockers, recent completions and task counts".to_string(),
             mime_type: Some("application/json".to_string()),
         });
 
@@ -755,6 +1656,21 @@ impl McpServer {
             description: "Recently added or updated knowledge".to_string(),
             mime_type: Some("application/json".to_string()),
         });
+
+        // Single entity templates
+        self.register_resource_template(McpResourceTemplate {
+            uri_template: "devman://tasks/{id}".to_string(),
+            name: "Task".to_string(),
+            description: "A single task by ID".to_string(),
+            mime_type: Some("application/json".to_string()),
+        });
+
+        self.register_resource_template(McpResourceTemplate {
+            uri_template: "devman://knowledge/{id}".to_string(),
+            name: "Knowledge".to_string(),
+            description: "A single knowledge item by ID".to_string(),
+            mime_type: Some("application/json".to_string()),
+        });
     }
 
     /// Handle an MCP request.
@@ -764,7 +1680,7 @@ impl McpServer {
                 // Extract protocol version from params
                 let protocol_version = params.get("protocolVersion")
                     .and_then(|v| v.as_str())
-                    .unwrap_or("2024-11-05");
+                    .unwrap_or(DEFAULT_PROTOCOL_VERSION);
 
                 json!({
                     "protocolVersion": protocol_version,
@@ -795,6 +1711,10 @@ impl McpServer {
                 json!({ "resources": resources })
             }
 
+            "resources/templates/list" => {
+                json!({ "resourceTemplates": self.resource_templates })
+            }
+
             "resources/read" => {
                 let uri = params.get("uri").and_then(|v| v.as_str()).unwrap_or("");
                 self.read_resource(uri).await
@@ -804,25 +1724,115 @@ impl McpServer {
                 json!({ "status": "pong" })
             }
 
+            "devman/describe" => self.describe(),
+
             _ => {
-                create_mcp_error_response(
-                    -32601,
+                mcp_error(
+                    DevManErrorCode::MethodNotFound,
                     &format!("Unknown method: {}", method),
                     None,
-                    false,
                 )
             }
         }
     }
 
-    /// Execute a tool.
+    /// Everything a client would otherwise gather from `initialize`,
+    /// `tools/list` and `resources/list`, plus what this server is wired
+    /// up with. Tools and resources are sorted for stable output.
+    fn describe(&self) -> serde_json::Value {
+        let mut tools: Vec<_> = self.tools.values().collect();
+        tools.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut resources: Vec<_> = self.resources.values().collect();
+        resources.sort_by(|a, b| a.uri.cmp(&b.uri));
+
+        json!({
+            "protocolVersion": DEFAULT_PROTOCOL_VERSION,
+            "serverInfo": {
+                "name": self.config.server_name,
+                "version": self.config.version
+            },
+            "capabilities": {
+                "tools": {},
+                "resources": {}
+            },
+            "tools": tools,
+            "resources": resources,
+            "resourceTemplates": self.resource_templates,
+            "storage": {
+                "path": self.storage_path,
+                "backends": devman_storage::available_backends()
+            },
+            "features": {
+                "aiInterface": self.ai_interface.is_some(),
+                "jobManager": self.job_manager.is_some(),
+                "vectorSearch": self.config.vector_search,
+                "strict": self.config.strict,
+                "auditLog": self.audit_log.is_some()
+            }
+        })
+    }
+
+    /// Execute a tool, recording the call in the audit log if configured.
     async fn execute_tool(
         &self,
         name: &str,
         arguments: serde_json::Value,
     ) -> serde_json::Value {
+        let Some(log) = &self.audit_log else {
+            return self.dispatch_tool(name, arguments).await;
+        };
+
+        let timestamp = chrono::Utc::now();
+        let started = std::time::Instant::now();
+        let redacted = audit::redact(&arguments);
+        let response = self.dispatch_tool(name, arguments).await;
+
+        let failed = is_mcp_error_response(&response);
+        let entry = AuditEntry {
+            timestamp,
+            tool: name.to_string(),
+            arguments: redacted,
+            status: if failed { AuditStatus::Error } else { AuditStatus::Success },
+            error_code: response.get("error").and_then(|e| e.get("code")).and_then(|c| c.as_i64()),
+            duration_ms: started.elapsed().as_millis() as u64,
+        };
+        if let Err(e) = log.append(&entry).await {
+            error!("Failed to write audit log {}: {}", log.path().display(), e);
+        }
+        response
+    }
+
+    /// Run the handler for a tool.
+    async fn dispatch_tool(
+        &self,
+        name: &str,
+        arguments: serde_json::Value,
+    ) -> serde_json::Value {
+        // Reject arguments that do not match the tool's schema before any
+        // handler defaults a missing field
+        if let Some(tool) = self.tools.get(name) {
+            if let Err(violation) = schema::validate(&tool.input_schema, &arguments) {
+                return mcp_error(
+                    DevManErrorCode::InvalidParams,
+                    &format!("Invalid arguments for {}: {}", name, violation),
+                    Some(json!({"tool": name, "path": violation.path, "reason": violation.message})),
+                );
+            }
+        }
+
         // Check if AI interface is available
         let ai_interface = self.ai_interface.as_ref();
+        if self.config.strict
+            && ai_interface.is_none()
+            && self.tools.contains_key(name)
+            && !TOOLS_WITHOUT_AI_INTERFACE.contains(&name)
+        {
+            return mcp_error(
+                DevManErrorCode::Internal,
+                "AI interface not configured (strict mode)",
+                Some(json!({"tool": name})),
+            );
+        }
 
         let result = match name {
             // Goal management - requires AI interface for full functionality
@@ -859,6 +1869,20 @@ impl McpServer {
                     })
                 }
             }
+            "devman_get_goal_tree" => {
+                if let Some(ai) = ai_interface {
+                    self.handle_get_goal_tree(ai, &arguments).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "goal_id": arguments.get("goal_id").and_then(|v| v.as_str()).unwrap_or(""),
+                            "phases": [],
+                            "message": "Goal tree placeholder - AI interface not configured"
+                        }
+                    })
+                }
+            }
 
             // Task management
             "devman_create_task" => {
@@ -876,6 +1900,35 @@ impl McpServer {
                     })
                 }
             }
+            "devman_create_from_template" => {
+                if let Some(ai) = ai_interface {
+                    self.handle_create_from_template(ai, &arguments).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "template_id": arguments.get("template_id").and_then(|v| v.as_str()).unwrap_or(""),
+                            "message": "Create from template placeholder - AI interface not configured"
+                        }
+                    })
+                }
+            }
+            "devman_create_subtask" => {
+                if let Some(ai) = ai_interface {
+                    self.handle_create_subtask(ai, &arguments).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "task_id": format!("task_{}", chrono::Utc::now().timestamp()),
+                            "parent_id": arguments.get("parent_id").and_then(|v| v.as_str()).unwrap_or(""),
+                            "title": arguments.get("title").and_then(|v| v.as_str()).unwrap_or("Untitled"),
+                            "status": "Created",
+                            "message": "Sub-task creation placeholder - AI interface not configured"
+                        }
+                    })
+                }
+            }
             "devman_list_tasks" => {
                 if let Some(ai) = ai_interface {
                     self.handle_list_tasks(ai, &arguments).await
@@ -890,6 +1943,51 @@ impl McpServer {
                     })
                 }
             }
+            "devman_bulk_update" => {
+                if let Some(ai) = ai_interface {
+                    self.handle_bulk_update(ai, &arguments).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "updated": 0,
+                            "skipped": 0,
+                            "failed": 0,
+                            "message": "Bulk update placeholder - AI interface not configured"
+                        }
+                    })
+                }
+            }
+
+            "devman_merge_tasks" => {
+                if let Some(ai) = ai_interface {
+                    self.handle_merge_tasks(ai, &arguments).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "task_id": arguments.get("keep_task_id").and_then(|v| v.as_str()).unwrap_or(""),
+                            "message": "Task merge placeholder - AI interface not configured"
+                        }
+                    })
+                }
+            }
+
+            "devman_archive_task" | "devman_unarchive_task" => {
+                let archived = name == "devman_archive_task";
+                if let Some(ai) = ai_interface {
+                    self.handle_set_task_archived(ai, &arguments, archived).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "task_id": arguments.get("task_id").and_then(|v| v.as_str()).unwrap_or(""),
+                            "archived": archived,
+                            "message": "Task archive placeholder - AI interface not configured"
+                        }
+                    })
+                }
+            }
 
             // Knowledge management
             "devman_search_knowledge" => {
@@ -921,6 +2019,32 @@ impl McpServer {
                 }
             }
 
+            "devman_rate_knowledge" => {
+                if let Some(ai) = ai_interface {
+                    self.handle_rate_knowledge(ai, &arguments).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "knowledge_id": arguments.get("knowledge_id").and_then(|v| v.as_str()).unwrap_or(""),
+                            "message": "Knowledge rating placeholder - AI interface not configured"
+                        }
+                    })
+                }
+            }
+            "devman_knowledge_stats" => {
+                if let Some(ai) = ai_interface {
+                    self.handle_knowledge_stats(ai).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "message": "Knowledge statistics placeholder - AI interface not configured"
+                        }
+                    })
+                }
+            }
+
             // Quality checks
             "devman_run_quality_check" => {
                 if let Some(ai) = ai_interface {
@@ -962,21 +2086,104 @@ impl McpServer {
             "devman_list_blockers" => {
                 self.handle_list_blockers(ai_interface).await
             }
-
-            // Job management - uses job_manager, not AI interface
-            "devman_get_job_status" => {
-                self.handle_get_job_status(&arguments).await
-            }
-            "devman_cancel_job" => {
-                self.handle_cancel_job(&arguments).await
-            }
-
-            // Task guidance tools - these are placeholders, no AI interface needed
-            "devman_get_task_guidance" => {
-                self.handle_get_task_guidance(&arguments).await
+            "devman_add_blocker" => {
+                if let Some(ai) = ai_interface {
+                    self.handle_add_blocker(ai, &arguments).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "message": "Add blocker placeholder - AI interface not configured"
+                        }
+                    })
+                }
             }
-            "devman_read_task_context" => {
-                self.handle_read_task_context(&arguments).await
+            "devman_resolve_blocker" => {
+                if let Some(ai) = ai_interface {
+                    self.handle_resolve_blocker(ai, &arguments).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "blocker_id": arguments.get("blocker_id").and_then(|v| v.as_str()).unwrap_or(""),
+                            "message": "Resolve blocker placeholder - AI interface not configured"
+                        }
+                    })
+                }
+            }
+            "devman_assign_quality_profile" => {
+                if let Some(ai) = ai_interface {
+                    self.handle_assign_quality_profile(ai, &arguments).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "phase_id": arguments.get("phase_id").and_then(|v| v.as_str()).unwrap_or(""),
+                            "message": "Assign quality profile placeholder - AI interface not configured"
+                        }
+                    })
+                }
+            }
+            "devman_get_task_timeline" => {
+                if let Some(ai) = ai_interface {
+                    self.handle_get_task_timeline(ai, &arguments).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "task_id": arguments.get("task_id").and_then(|v| v.as_str()).unwrap_or(""),
+                            "entries": [],
+                            "message": "Task timeline placeholder - AI interface not configured"
+                        }
+                    })
+                }
+            }
+
+            "devman_tasks_touching_file" => {
+                if let Some(ai) = ai_interface {
+                    self.handle_tasks_touching_file(ai, &arguments).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "tasks": [],
+                            "message": "File task lookup placeholder - AI interface not configured"
+                        }
+                    })
+                }
+            }
+
+            "devman_recommend_next" => {
+                if let Some(ai) = ai_interface {
+                    self.handle_recommend_next(ai, &arguments).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "task_id": null,
+                            "message": "Next task recommendation placeholder - AI interface not configured"
+                        }
+                    })
+                }
+            }
+
+            // Job management - uses job_manager, not AI interface
+            "devman_get_job_status" => {
+                self.handle_get_job_status(&arguments).await
+            }
+            "devman_cancel_job" => {
+                self.handle_cancel_job(&arguments).await
+            }
+            "devman_reindex_knowledge" => {
+                self.handle_reindex_knowledge().await
+            }
+
+            // Task guidance tools - these are placeholders, no AI interface needed
+            "devman_get_task_guidance" => {
+                self.handle_get_task_guidance(&arguments).await
+            }
+            "devman_read_task_context" => {
+                self.handle_read_task_context(&arguments).await
             }
             "devman_review_knowledge" => {
                 self.handle_review_knowledge(&arguments).await
@@ -994,7 +2201,46 @@ impl McpServer {
                 self.handle_finish_work(&arguments).await
             }
             "devman_run_task_quality_check" => {
-                self.handle_run_task_quality_check(&arguments).await
+                if let Some(ai) = ai_interface {
+                    self.handle_run_task_quality_check(ai, &arguments).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "state": "QualityChecking",
+                            "check_id": format!("check_{}", chrono::Utc::now().timestamp()),
+                            "message": self.messages.get("quality.running")
+                        }
+                    })
+                }
+            }
+            "devman_goal_quality" => {
+                if let Some(ai) = ai_interface {
+                    self.handle_goal_quality(ai, &arguments).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "goal_id": arguments.get("goal_id").and_then(|v| v.as_str()).unwrap_or(""),
+                            "tasks": [],
+                            "message": "Goal quality placeholder - AI interface not configured"
+                        }
+                    })
+                }
+            }
+            "devman_quality_history" => {
+                if let Some(ai) = ai_interface {
+                    self.handle_quality_history(ai, &arguments).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "task_id": arguments.get("task_id").and_then(|v| v.as_str()).unwrap_or(""),
+                            "history": [],
+                            "message": "Quality history placeholder - AI interface not configured"
+                        }
+                    })
+                }
             }
             "devman_get_quality_result" => {
                 self.handle_get_quality_result(&arguments).await
@@ -1014,14 +2260,148 @@ impl McpServer {
             "devman_abandon_task" => {
                 self.handle_abandon_task(&arguments).await
             }
+            "devman_reopen_task" => {
+                if let Some(ai) = ai_interface {
+                    self.handle_reopen_task(ai, &arguments).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "task_id": arguments.get("task_id").and_then(|v| v.as_str()).unwrap_or(""),
+                            "message": "Task reopen placeholder - AI interface not configured"
+                        }
+                    })
+                }
+            }
+            "devman_claim_task" => {
+                if let Some(ai) = ai_interface {
+                    self.handle_claim_task(ai, &arguments).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "task_id": arguments.get("task_id").and_then(|v| v.as_str()).unwrap_or(""),
+                            "message": "Claim task placeholder - AI interface not configured"
+                        }
+                    })
+                }
+            }
+            "devman_release_task" => {
+                if let Some(ai) = ai_interface {
+                    self.handle_release_task(ai, &arguments).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "task_id": arguments.get("task_id").and_then(|v| v.as_str()).unwrap_or(""),
+                            "message": "Release task placeholder - AI interface not configured"
+                        }
+                    })
+                }
+            }
+            "devman_insert_phase" => {
+                if let Some(ai) = ai_interface {
+                    self.handle_insert_phase(ai, &arguments).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "project_id": arguments.get("project_id").and_then(|v| v.as_str()).unwrap_or(""),
+                            "message": "Insert phase placeholder - AI interface not configured"
+                        }
+                    })
+                }
+            }
+            "devman_reorder_phases" => {
+                if let Some(ai) = ai_interface {
+                    self.handle_reorder_phases(ai, &arguments).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "project_id": arguments.get("project_id").and_then(|v| v.as_str()).unwrap_or(""),
+                            "message": "Reorder phases placeholder - AI interface not configured"
+                        }
+                    })
+                }
+            }
+            "devman_advance_phase" => {
+                if let Some(ai) = ai_interface {
+                    self.handle_advance_phase(ai, &arguments).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "goal_id": arguments.get("goal_id").and_then(|v| v.as_str()).unwrap_or(""),
+                            "message": "Advance phase placeholder - AI interface not configured"
+                        }
+                    })
+                }
+            }
+            "devman_set_active_phase" => {
+                if let Some(ai) = ai_interface {
+                    self.handle_set_active_phase(ai, &arguments).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "goal_id": arguments.get("goal_id").and_then(|v| v.as_str()).unwrap_or(""),
+                            "message": "Set active phase placeholder - AI interface not configured"
+                        }
+                    })
+                }
+            }
+            "devman_record_actuals" => {
+                if let Some(ai) = ai_interface {
+                    self.handle_record_actuals(ai, &arguments).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "task_id": arguments.get("task_id").and_then(|v| v.as_str()).unwrap_or(""),
+                            "message": "Record actuals placeholder - AI interface not configured"
+                        }
+                    })
+                }
+            }
+            "devman_preview_transition" => {
+                if let Some(ai) = ai_interface {
+                    self.handle_preview_transition(ai, &arguments).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "task_id": arguments.get("task_id").and_then(|v| v.as_str()).unwrap_or(""),
+                            "message": "Transition preview placeholder - AI interface not configured"
+                        }
+                    })
+                }
+            }
+
+            "devman_abandonment_impact" => {
+                if let Some(ai) = ai_interface {
+                    self.handle_abandonment_impact(ai, &arguments).await
+                } else {
+                    json!({
+                        "success": true,
+                        "data": {
+                            "blocked_tasks": [],
+                            "message": "Abandonment impact placeholder - AI interface not configured"
+                        }
+                    })
+                }
+            }
 
             // Unknown tool
-            _ => create_mcp_error_response(
-                -32601,
-                &format!("Unknown tool: {}", name),
-                None,
-                false,
-            ),
+            _ => {
+                let mut available: Vec<&str> = self.tools.keys().map(String::as_str).collect();
+                available.sort_unstable();
+                mcp_error(
+                    DevManErrorCode::ToolNotFound,
+                    &format!("Unknown tool: {}", name),
+                    Some(json!({"tool": name, "available_tools": available})),
+                )
+            }
         };
 
         // Wrap non-error responses in MCP content format
@@ -1050,22 +2430,37 @@ impl McpServer {
             project_id: None,
         };
 
-        match ai_interface.create_goal(spec).await {
-            Ok(goal) => json!({
+        let created = self
+            .create_once(
+                ai_interface,
+                "devman_create_goal",
+                arguments,
+                |goal: &devman_core::Goal| goal.id.to_string(),
+                |id| async move { ai_interface.get_goal(id.parse().ok()?).await },
+                || async move {
+                    ai_interface.create_goal(spec).await.map_err(|e| {
+                        mcp_error(
+                            DevManErrorCode::Business,
+                            &format!("Failed to create goal: {}", e),
+                            Some(json!({"hint": "Check the goal title and description are valid."})),
+                        )
+                    })
+                },
+            )
+            .await;
+
+        match created {
+            Ok((goal, is_replay)) => json!({
                 "success": true,
                 "data": {
                     "goal_id": goal.id.to_string(),
                     "title": goal.title,
-                    "status": format!("{:?}", goal.status)
+                    "status": format!("{:?}", goal.status),
+                    "replayed": is_replay
                 },
                 "version": format!("goal_{}@v1", goal.id)
             }),
-            Err(e) => create_mcp_error_response(
-                -32000,
-                &format!("Failed to create goal: {}", e),
-                Some(json!({"hint": "Check the goal title and description are valid."})),
-                true,
-            ),
+            Err(e) => e,
         }
     }
 
@@ -1077,11 +2472,10 @@ impl McpServer {
         let goal_id_str = match arguments.get("goal_id").and_then(|v| v.as_str()) {
             Some(s) => s,
             None => {
-                return create_mcp_error_response(
-                    -32602,
+                return mcp_error(
+                    DevManErrorCode::InvalidParams,
                     "Missing required parameter: goal_id",
                     None,
-                    false,
                 );
             }
         };
@@ -1089,11 +2483,10 @@ impl McpServer {
         let goal_id = match goal_id_str.parse::<devman_core::GoalId>() {
             Ok(id) => id,
             Err(_) => {
-                return create_mcp_error_response(
-                    -32602,
+                return mcp_error(
+                    DevManErrorCode::InvalidParams,
                     "Invalid goal_id format",
                     None,
-                    false,
                 );
             }
         };
@@ -1106,14 +2499,48 @@ impl McpServer {
                     "percentage": progress.percentage,
                     "completed_phases": progress.completed_phases,
                     "active_tasks": progress.active_tasks,
-                    "completed_tasks": progress.completed_tasks
+                    "completed_tasks": progress.completed_tasks,
+                    "criterion_conflicts": ai_interface.detect_criterion_conflicts(goal_id).await
                 }
             }),
-            None => create_mcp_error_response(
-                -32002,
+            None => mcp_error(
+                DevManErrorCode::NotFound,
+                &format!("Goal not found: {}", goal_id_str),
+                None,
+            ),
+        }
+    }
+
+    async fn handle_get_goal_tree(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        arguments: &serde_json::Value,
+    ) -> serde_json::Value {
+        let Some(goal_id_str) = arguments.get("goal_id").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: goal_id",
+                None,
+            );
+        };
+
+        let Ok(goal_id) = goal_id_str.parse::<devman_core::GoalId>() else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Invalid goal_id format",
+                None,
+            );
+        };
+
+        match ai_interface.get_goal_tree(goal_id).await {
+            Some(tree) => json!({
+                "success": true,
+                "data": tree
+            }),
+            None => mcp_error(
+                DevManErrorCode::NotFound,
                 &format!("Goal not found: {}", goal_id_str),
                 None,
-                false,
             ),
         }
     }
@@ -1129,6 +2556,35 @@ impl McpServer {
             .map(|_| devman_core::PhaseId::new())
             .unwrap_or_default();
 
+        let affected_files = arguments
+            .get("affected_files")
+            .and_then(|v| v.as_array())
+            .map(|files| files.iter().filter_map(|f| f.as_str().map(String::from)).collect())
+            .unwrap_or_default();
+
+        let priority = match priority_arg(arguments, "priority") {
+            Ok(priority) => priority.unwrap_or_default(),
+            Err(e) => return e,
+        };
+        let due_at = match time_arg(arguments, "due_at") {
+            Ok(due_at) => due_at,
+            Err(e) => return e,
+        };
+
+        let quality_profile = match arguments.get("quality_profile").and_then(|v| v.as_str()) {
+            Some(id) => match id.parse::<devman_core::QualityProfileId>() {
+                Ok(id) => Some(id),
+                Err(_) => {
+                    return mcp_error(
+                        DevManErrorCode::InvalidParams,
+                        "Invalid quality_profile format",
+                        None,
+                    );
+                }
+            },
+            None => None,
+        };
+
         let spec = TaskSpec {
             title,
             description: description.clone(),
@@ -1137,85 +2593,483 @@ impl McpServer {
                 context: devman_core::TaskContext {
                     relevant_knowledge: Vec::new(),
                     similar_tasks: Vec::new(),
-                    affected_files: Vec::new(),
+                    affected_files,
                 },
                 success_criteria: Vec::new(),
             },
             phase_id,
             quality_gates: Vec::new(),
+            priority,
+            quality_profile,
+            due_at,
         };
 
-        match ai_interface.create_task(spec).await {
-            Ok(task) => json!({
+        let allow_duplicate = arguments.get("allow_duplicate").and_then(|v| v.as_bool()).unwrap_or(false);
+        let created = self
+            .create_once(
+                ai_interface,
+                "devman_create_task",
+                arguments,
+                |task: &devman_core::Task| task.id.to_string(),
+                |id| async move { ai_interface.get_task(id.parse().ok()?).await },
+                || async move {
+                    if !allow_duplicate {
+                        match ai_interface
+                            .find_duplicate_tasks(&spec, devman_work::DEFAULT_DUPLICATE_THRESHOLD)
+                            .await
+                        {
+                            Ok(Some(warning)) => {
+                                return Err(mcp_error(
+                                    DevManErrorCode::StateConflict,
+                                    "Similar open tasks already exist; pass allow_duplicate: true to create anyway",
+                                    Some(json!({"duplicate_warning": warning})),
+                                ));
+                            }
+                            Ok(None) => {}
+                            Err(e) => return Err(interface_error("Failed to check for duplicate tasks", &e)),
+                        }
+                    }
+                    ai_interface.create_task(spec).await.map_err(|e| {
+                        create_mcp_error_response(
+                            DevManErrorCode::Business.code(),
+                            &format!("Failed to create task: {}", e),
+                            None,
+                            false,
+                        )
+                    })
+                },
+            )
+            .await;
+
+        match created {
+            Ok((task, is_replay)) => json!({
                 "success": true,
                 "data": {
                     "task_id": task.id.to_string(),
                     "title": task.title,
                     "status": format!("{:?}", task.status),
+                    "priority": task.priority_level().as_str(),
+                    "replayed": is_replay,
                     "message": "Task created successfully"
                 }
             }),
-            Err(e) => create_mcp_error_response(
-                -32000,
-                &format!("Failed to create task: {}", e),
-                None,
-                false,
-            )
+            Err(e) => e,
         }
     }
 
-    async fn handle_list_tasks(
+    async fn handle_create_from_template(
         &self,
         ai_interface: &Arc<dyn AIInterface>,
         arguments: &serde_json::Value,
     ) -> serde_json::Value {
-        let filter = TaskFilter {
-            status: arguments.get("state").and_then(|v| v.as_str()).map(|s| {
-                match s {
-                    "Created" | "Queued" => devman_core::TaskStatus::Queued,
-                    "InProgress" | "Active" => devman_core::TaskStatus::Active,
-                    "Completed" | "Done" => devman_core::TaskStatus::Done,
-                    "Abandoned" => devman_core::TaskStatus::Abandoned,
-                    _ => devman_core::TaskStatus::Queued,
-                }
+        let Some(template_id) = arguments.get("template_id").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: template_id",
+                None,
+            );
+        };
+        let Ok(template_id) = template_id.parse::<devman_core::TaskTemplateId>() else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Invalid template_id format",
+                None,
+            );
+        };
+        let Some(phase_id) = arguments.get("phase_id").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: phase_id",
+                None,
+            );
+        };
+        let Ok(phase_id) = phase_id.parse::<devman_core::PhaseId>() else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Invalid phase_id format",
+                None,
+            );
+        };
+        let params = match arguments.get("params") {
+            None | Some(serde_json::Value::Null) => Some(std::collections::HashMap::new()),
+            Some(value) => value.as_object().and_then(|map| {
+                map.iter()
+                    .map(|(name, value)| Some((name.clone(), value.as_str()?.to_string())))
+                    .collect()
             }),
-            goal_id: None,
-            phase_id: None,
-            limit: arguments.get("limit").and_then(|v| v.as_u64()).map(|u| u as usize),
-            include_completed: true,
+        };
+        let Some(params) = params else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Invalid params: expected an object of string values",
+                None,
+            );
         };
 
-        let tasks = ai_interface.list_tasks(filter).await;
-        let task_summaries: Vec<serde_json::Value> = tasks.iter().map(|t| json!({
-            "task_id": t.id.to_string(),
-            "title": t.title,
-            "status": format!("{:?}", t.status),
-            "priority": 3 // Default priority
-        })).collect();
-
-        json!({
+        match ai_interface.create_task_from_template(template_id, phase_id, params).await {
+            Ok(task) => json!({
+                "success": true,
+                "data": {
+                    "task_id": task.id.to_string(),
+                    "template_id": template_id.to_string(),
+                    "title": task.title,
+                    "status": format!("{:?}", task.status),
+                    "steps": task.steps.len(),
+                    "quality_gates": task.quality_gates.len(),
+                    "message": "Task created from template"
+                }
+            }),
+            Err(e) => interface_error("Failed to create task from template", &e),
+        }
+    }
+
+    async fn handle_create_subtask(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        arguments: &serde_json::Value,
+    ) -> serde_json::Value {
+        let parent_id = match arguments.get("parent_id").and_then(|v| v.as_str()) {
+            Some(s) => match s.parse::<devman_core::TaskId>() {
+                Ok(id) => id,
+                Err(_) => {
+                    return mcp_error(
+                        DevManErrorCode::InvalidParams,
+                        "Invalid parent_id format",
+                        None,
+                    );
+                }
+            },
+            None => {
+                return mcp_error(
+                    DevManErrorCode::InvalidParams,
+                    "Missing required parameter: parent_id",
+                    None,
+                );
+            }
+        };
+
+        let title = arguments.get("title").and_then(|v| v.as_str()).unwrap_or("Untitled").to_string();
+        let description = arguments.get("description").and_then(|v| v.as_str()).unwrap_or("").to_string();
+        let priority = match priority_arg(arguments, "priority") {
+            Ok(priority) => priority.unwrap_or_default(),
+            Err(e) => return e,
+        };
+        let due_at = match time_arg(arguments, "due_at") {
+            Ok(due_at) => due_at,
+            Err(e) => return e,
+        };
+
+        let spec = TaskSpec {
+            title,
+            description: description.clone(),
+            intent: devman_core::TaskIntent {
+                natural_language: description,
+                context: devman_core::TaskContext {
+                    relevant_knowledge: Vec::new(),
+                    similar_tasks: Vec::new(),
+                    affected_files: Vec::new(),
+                },
+                success_criteria: Vec::new(),
+            },
+            phase_id: devman_core::PhaseId::default(),
+            quality_gates: Vec::new(),
+            priority,
+            quality_profile: None,
+            due_at,
+        };
+
+        let created = self
+            .create_once(
+                ai_interface,
+                "devman_create_subtask",
+                arguments,
+                |task: &devman_core::Task| task.id.to_string(),
+                |id| async move { ai_interface.get_task(id.parse().ok()?).await },
+                || async move {
+                    ai_interface
+                        .create_subtask(parent_id, spec)
+                        .await
+                        .map_err(|e| interface_error("Failed to create sub-task", &e))
+                },
+            )
+            .await;
+
+        match created {
+            Ok((task, is_replay)) => json!({
+                "success": true,
+                "data": {
+                    "task_id": task.id.to_string(),
+                    "parent_id": parent_id.to_string(),
+                    "title": task.title,
+                    "status": format!("{:?}", task.status),
+                    "replayed": is_replay,
+                    "message": "Sub-task created successfully"
+                }
+            }),
+            Err(e) => e,
+        }
+    }
+
+    async fn handle_list_tasks(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        arguments: &serde_json::Value,
+    ) -> serde_json::Value {
+        let min_priority = match priority_arg(arguments, "min_priority") {
+            Ok(priority) => priority,
+            Err(e) => return e,
+        };
+
+        let nested = arguments.get("nested").and_then(|v| v.as_bool()).unwrap_or(false);
+
+        let filter = TaskFilter {
+            status: arguments.get("state").and_then(|v| v.as_str()).map(|s| {
+                match s {
+                    "Created" | "Queued" => devman_core::TaskStatus::Queued,
+                    "InProgress" | "Active" => devman_core::TaskStatus::Active,
+                    "Completed" | "Done" => devman_core::TaskStatus::Done,
+                    "Abandoned" => devman_core::TaskStatus::Abandoned,
+                    _ => devman_core::TaskStatus::Queued,
+                }
+            }),
+            goal_id: None,
+            phase_id: None,
+            limit: arguments.get("limit").and_then(|v| v.as_u64()).map(|u| u as usize),
+            include_completed: true,
+            include_archived: arguments.get("include_archived").and_then(|v| v.as_bool()).unwrap_or(false),
+            min_priority,
+            created_before: None,
+            roots_only: nested || arguments.get("roots_only").and_then(|v| v.as_bool()).unwrap_or(false),
+            include_subtasks: nested,
+        };
+
+        let tasks = ai_interface.list_tasks(filter).await;
+        let task_summaries: Vec<serde_json::Value> = if nested {
+            nested_task_summaries(&tasks)
+        } else {
+            tasks.iter().map(task_summary_json).collect()
+        };
+
+        json!({
             "success": true,
             "data": {
                 "tasks": task_summaries,
-                "total_count": task_summaries.len()
+                "total_count": tasks.len()
             },
-            "version": format!("tasks@v{}", task_summaries.len())
+            "version": format!("tasks@v{}", tasks.len())
         })
     }
 
+    async fn handle_bulk_update(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        arguments: &serde_json::Value,
+    ) -> serde_json::Value {
+        let status = match arguments.get("status").and_then(|v| v.as_str()) {
+            Some(s) => match parse_task_status(s) {
+                Some(status) => status,
+                None => {
+                    return mcp_error(
+                        DevManErrorCode::InvalidParams,
+                        &format!("Invalid status: {}", s),
+                        None,
+                    );
+                }
+            },
+            None => {
+                return mcp_error(
+                    DevManErrorCode::InvalidParams,
+                    "Missing required parameter: status",
+                    None,
+                );
+            }
+        };
+        let Some(reason) = arguments.get("reason").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: reason",
+                None,
+            );
+        };
+
+        let filter_args = arguments.get("filter").cloned().unwrap_or_else(|| json!({}));
+        let state = match filter_args.get("state").and_then(|v| v.as_str()) {
+            Some(s) => match parse_task_status(s) {
+                Some(state) => Some(state),
+                None => {
+                    return mcp_error(
+                        DevManErrorCode::InvalidParams,
+                        &format!("Invalid filter state: {}", s),
+                        None,
+                    );
+                }
+            },
+            None => None,
+        };
+        let min_priority = match priority_arg(&filter_args, "min_priority") {
+            Ok(priority) => priority,
+            Err(e) => return e,
+        };
+        let created_before = match filter_args.get("older_than_days").and_then(|v| v.as_i64()) {
+            Some(days) => {
+                let cutoff = chrono::TimeDelta::try_days(days).and_then(|age| chrono::Utc::now().checked_sub_signed(age));
+                let Some(cutoff) = cutoff else {
+                    return mcp_error(
+                        DevManErrorCode::InvalidParams,
+                        &format!("older_than_days is out of range: {}", days),
+                        None,
+                    );
+                };
+                Some(cutoff)
+            }
+            None => None,
+        };
+
+        let filter = TaskFilter {
+            status: state,
+            limit: filter_args.get("limit").and_then(|v| v.as_u64()).map(|u| u as usize),
+            include_completed: state.is_some(),
+            include_archived: filter_args.get("include_archived").and_then(|v| v.as_bool()).unwrap_or(false),
+            min_priority,
+            created_before,
+            ..Default::default()
+        };
+
+        match ai_interface.bulk_update_status(filter, status, reason).await {
+            Ok(result) => json!({
+                "success": true,
+                "data": {
+                    "status": format!("{:?}", status),
+                    "updated": result.updated,
+                    "skipped": result.skipped,
+                    "failed": result.failed,
+                    "items": result.items.iter().map(|item| json!({
+                        "task_id": item.task_id.to_string(),
+                        "outcome": item.outcome,
+                        "reason": item.reason
+                    })).collect::<Vec<_>>()
+                }
+            }),
+            Err(e) => mcp_error(
+                DevManErrorCode::Business,
+                &format!("Bulk update failed: {}", e),
+                None,
+            ),
+        }
+    }
+
+    async fn handle_set_task_archived(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        arguments: &serde_json::Value,
+        archived: bool,
+    ) -> serde_json::Value {
+        let task_id_str = match arguments.get("task_id").and_then(|v| v.as_str()) {
+            Some(s) => s,
+            None => {
+                return mcp_error(
+                    DevManErrorCode::InvalidParams,
+                    "Missing required parameter: task_id",
+                    None,
+                );
+            }
+        };
+
+        let task_id = match task_id_str.parse::<devman_core::TaskId>() {
+            Ok(id) => id,
+            Err(_) => {
+                return mcp_error(
+                    DevManErrorCode::InvalidParams,
+                    "Invalid task_id format",
+                    None,
+                );
+            }
+        };
+
+        let result = if archived {
+            ai_interface.archive_task(task_id).await
+        } else {
+            ai_interface.unarchive_task(task_id).await
+        };
+
+        match result {
+            Ok(task) => json!({
+                "success": true,
+                "data": {
+                    "task_id": task_id_str,
+                    "title": task.title,
+                    "status": format!("{:?}", task.status),
+                    "archived": task.archived
+                }
+            }),
+            Err(e) => interface_error("Failed to update task archive state", &e),
+        }
+    }
+
+    async fn handle_merge_tasks(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        arguments: &serde_json::Value,
+    ) -> serde_json::Value {
+        let mut ids = Vec::with_capacity(2);
+        for param in ["keep_task_id", "merge_task_id"] {
+            let Some(id_str) = arguments.get(param).and_then(|v| v.as_str()) else {
+                return mcp_error(
+                    DevManErrorCode::InvalidParams,
+                    &format!("Missing required parameter: {}", param),
+                    None,
+                );
+            };
+            let Ok(id) = id_str.parse::<devman_core::TaskId>() else {
+                return mcp_error(
+                    DevManErrorCode::InvalidParams,
+                    &format!("Invalid {} format", param),
+                    None,
+                );
+            };
+            ids.push(id);
+        }
+        let (keep, merge) = (ids[0], ids[1]);
+
+        if let Err(e) = ai_interface.merge_tasks(keep, merge).await {
+            return interface_error("Failed to merge tasks", &e);
+        }
+        match ai_interface.get_task(keep).await {
+            Some(task) => json!({
+                "success": true,
+                "data": {
+                    "task_id": keep.to_string(),
+                    "merged_task_id": merge.to_string(),
+                    "title": task.title,
+                    "depends_on": task.depends_on.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+                    "work_records": task.work_records.len()
+                }
+            }),
+            None => mcp_error(
+                DevManErrorCode::NotFound,
+                &format!("Task {} not found", keep),
+                Some(json!({"entity_type": "task", "id": keep.to_string()})),
+            ),
+        }
+    }
+
     async fn handle_search_knowledge(
         &self,
         ai_interface: &Arc<dyn AIInterface>,
         arguments: &serde_json::Value,
     ) -> serde_json::Value {
         let query = arguments.get("query").and_then(|v| v.as_str()).unwrap_or("");
-        let results = ai_interface.search_knowledge(query).await;
-
-        let summaries: Vec<serde_json::Value> = results.iter().map(|k| json!({
-            "knowledge_id": k.id.to_string(),
-            "title": k.title,
-            "knowledge_type": format!("{:?}", k.knowledge_type),
-            "tags": k.tags
+        let limit = arguments.get("limit").and_then(|v| v.as_u64()).unwrap_or(10) as usize;
+        let hits = ai_interface.search_knowledge_ranked(query, limit).await;
+
+        let summaries: Vec<serde_json::Value> = hits.iter().map(|hit| json!({
+            "knowledge_id": hit.knowledge.id.to_string(),
+            "title": hit.knowledge.title,
+            "knowledge_type": format!("{:?}", hit.knowledge.knowledge_type),
+            "tags": hit.knowledge.tags,
+            "score": hit.score,
+            "snippet": hit.snippet,
+            "search_type": hit.kind
         })).collect();
 
         json!({
@@ -1238,6 +3092,77 @@ impl McpServer {
         })
     }
 
+    async fn handle_rate_knowledge(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        arguments: &serde_json::Value,
+    ) -> serde_json::Value {
+        let knowledge_id = match arguments.get("knowledge_id").and_then(|v| v.as_str()) {
+            Some(s) => match s.parse::<devman_core::KnowledgeId>() {
+                Ok(id) => id,
+                Err(_) => {
+                    return mcp_error(
+                        DevManErrorCode::InvalidParams,
+                        "Invalid knowledge_id format",
+                        None,
+                    );
+                }
+            },
+            None => {
+                return mcp_error(
+                    DevManErrorCode::InvalidParams,
+                    "Missing required parameter: knowledge_id",
+                    None,
+                );
+            }
+        };
+
+        let Some(helpful) = arguments.get("helpful").and_then(|v| v.as_bool()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: helpful",
+                None,
+            );
+        };
+
+        let note = arguments.get("note").and_then(|v| v.as_str()).map(String::from);
+        let feedback = if helpful {
+            devman_core::Feedback::helpful("ai", note)
+        } else {
+            devman_core::Feedback::unhelpful("ai", note)
+        };
+
+        match ai_interface.rate_knowledge(knowledge_id, feedback).await {
+            Ok(knowledge) => json!({
+                "success": true,
+                "data": {
+                    "knowledge_id": knowledge.id.to_string(),
+                    "quality_score": knowledge.metadata.quality_score,
+                    "feedback_count": knowledge.usage_stats.feedback.len()
+                }
+            }),
+            Err(e) => mcp_error(
+                DevManErrorCode::Business,
+                &format!("Failed to rate knowledge: {}", e),
+                None,
+            ),
+        }
+    }
+
+    async fn handle_knowledge_stats(&self, ai_interface: &Arc<dyn AIInterface>) -> serde_json::Value {
+        match ai_interface.knowledge_statistics().await {
+            Ok(stats) => json!({
+                "success": true,
+                "data": stats
+            }),
+            Err(e) => mcp_error(
+                DevManErrorCode::Business,
+                &format!("Failed to compute knowledge statistics: {}", e),
+                None,
+            ),
+        }
+    }
+
     async fn handle_run_quality_check(
         &self,
         ai_interface: &Arc<dyn AIInterface>,
@@ -1256,6 +3181,14 @@ impl McpServer {
             ),
             severity: devman_core::Severity::Error,
             category: devman_core::QualityCategory::Maintainability,
+            timeout: None,
+            scope: if arguments.get("changed_only").and_then(|v| v.as_bool()).unwrap_or(false) {
+                devman_core::CheckScope::ChangedOnly
+            } else {
+                devman_core::CheckScope::Full
+            },
+            working_dir: None,
+            blocking: true,
         };
 
         let result = ai_interface.run_quality_check(check).await;
@@ -1303,79 +3236,173 @@ impl McpServer {
         json!({
             "success": true,
             "data": {
-                "blockers": blockers.iter().map(|b| json!({
-                    "reason": b.reason,
-                    "severity": format!("{:?}", b.severity)
-                })).collect::<Vec<_>>(),
+                "blockers": blockers.iter().map(blocker_json).collect::<Vec<_>>(),
                 "total_count": blockers.len()
             }
         })
     }
 
-    async fn handle_get_job_status(
+    async fn handle_add_blocker(
         &self,
+        ai_interface: &Arc<dyn AIInterface>,
         arguments: &serde_json::Value,
     ) -> serde_json::Value {
-        let job_id_str = match arguments.get("job_id").and_then(|v| v.as_str()) {
-            Some(s) => s,
+        let Some(item_id) = arguments.get("item_id").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: item_id",
+                None,
+            );
+        };
+
+        let item = match arguments.get("item_type").and_then(|v| v.as_str()) {
+            Some("task") => item_id.parse().map(devman_core::BlockedItem::Task).ok(),
+            Some("phase") => item_id.parse().map(devman_core::BlockedItem::Phase).ok(),
+            Some("goal") => item_id.parse().map(devman_core::BlockedItem::Goal).ok(),
+            Some(other) => {
+                return mcp_error(
+                    DevManErrorCode::InvalidParams,
+                    &format!("Invalid item_type: {} (expected task, phase or goal)", other),
+                    None,
+                );
+            }
             None => {
-                return create_mcp_error_response(
-                    -32602,
-                    "Missing required parameter: job_id",
+                return mcp_error(
+                    DevManErrorCode::InvalidParams,
+                    "Missing required parameter: item_type",
                     None,
-                    false,
                 );
             }
         };
+        let Some(item) = item else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Invalid item_id format",
+                None,
+            );
+        };
 
-        let job_manager = match &self.job_manager {
-            Some(jm) => jm,
-            None => {
-                return create_mcp_error_response(
-                    -32603,
-                    "Internal error: Job manager not configured",
+        let Some(reason) = arguments.get("reason").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: reason",
+                None,
+            );
+        };
+
+        let severity = match arguments.get("severity").and_then(|v| v.as_str()) {
+            None | Some("warning") => devman_core::Severity::Warning,
+            Some("info") => devman_core::Severity::Info,
+            Some("error") => devman_core::Severity::Error,
+            Some("critical") => devman_core::Severity::Critical,
+            Some(other) => {
+                return mcp_error(
+                    DevManErrorCode::InvalidParams,
+                    &format!("Invalid severity: {}", other),
                     None,
-                    false,
                 );
             }
         };
 
-        let job_id = JobId(job_id_str.to_string());
-        match job_manager.get_job_status(&job_id).await {
-            Some(status) => json!({
+        match ai_interface.add_blocker(item, reason, severity).await {
+            Ok(blocker) => json!({
                 "success": true,
-                "data": {
-                    "job_id": status.job_id,
-                    "status": status.status,
-                    "progress": status.progress,
-                    "progress_message": status.progress_message,
-                    "created_at": status.created_at,
-                    "completed_at": status.completed_at,
-                    "result": status.result,
-                    "error": status.error
-                }
+                "data": blocker_json(&blocker)
             }),
-            None => create_mcp_error_response(
-                -32002,
-                &format!("Job not found: {}", job_id_str),
-                None,
-                false,
-            ),
+            Err(e) => interface_error("Failed to add blocker", &e),
         }
     }
 
-    async fn handle_cancel_job(
+    async fn handle_resolve_blocker(
         &self,
+        ai_interface: &Arc<dyn AIInterface>,
         arguments: &serde_json::Value,
     ) -> serde_json::Value {
-        let job_id_str = match arguments.get("job_id").and_then(|v| v.as_str()) {
+        let Some(blocker_id) = arguments.get("blocker_id").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: blocker_id",
+                None,
+            );
+        };
+
+        let Ok(blocker_id) = blocker_id.parse::<devman_core::BlockerId>() else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Invalid blocker_id format",
+                None,
+            );
+        };
+
+        match ai_interface.resolve_blocker(blocker_id).await {
+            Ok(blocker) => json!({
+                "success": true,
+                "data": blocker_json(&blocker)
+            }),
+            Err(e) => interface_error("Failed to resolve blocker", &e),
+        }
+    }
+
+    async fn handle_get_job_status(
+        &self,
+        arguments: &serde_json::Value,
+    ) -> serde_json::Value {
+        let job_id_str = match arguments.get("job_id").and_then(|v| v.as_str()) {
+            Some(s) => s,
+            None => {
+                return mcp_error(
+                    DevManErrorCode::InvalidParams,
+                    "Missing required parameter: job_id",
+                    None,
+                );
+            }
+        };
+
+        let job_manager = match &self.job_manager {
+            Some(jm) => jm,
+            None => {
+                return mcp_error(
+                    DevManErrorCode::Internal,
+                    "Internal error: Job manager not configured",
+                    None,
+                );
+            }
+        };
+
+        let job_id = JobId(job_id_str.to_string());
+        match job_manager.get_job_status(&job_id).await {
+            Some(status) => json!({
+                "success": true,
+                "data": {
+                    "job_id": status.job_id,
+                    "status": status.status,
+                    "progress": status.progress,
+                    "progress_message": status.progress_message,
+                    "created_at": status.created_at,
+                    "completed_at": status.completed_at,
+                    "result": status.result,
+                    "error": status.error
+                }
+            }),
+            None => mcp_error(
+                DevManErrorCode::NotFound,
+                &format!("Job not found: {}", job_id_str),
+                None,
+            ),
+        }
+    }
+
+    async fn handle_cancel_job(
+        &self,
+        arguments: &serde_json::Value,
+    ) -> serde_json::Value {
+        let job_id_str = match arguments.get("job_id").and_then(|v| v.as_str()) {
             Some(s) => s,
             None => {
-                return create_mcp_error_response(
-                    -32602,
+                return mcp_error(
+                    DevManErrorCode::InvalidParams,
                     "Missing required parameter: job_id",
                     None,
-                    false,
                 );
             }
         };
@@ -1383,11 +3410,10 @@ impl McpServer {
         let job_manager = match &self.job_manager {
             Some(jm) => jm,
             None => {
-                return create_mcp_error_response(
-                    -32603,
+                return mcp_error(
+                    DevManErrorCode::Internal,
                     "Internal error: Job manager not configured",
                     None,
-                    false,
                 );
             }
         };
@@ -1407,17 +3433,47 @@ impl McpServer {
         }
     }
 
+    async fn handle_reindex_knowledge(&self) -> serde_json::Value {
+        let (Some(job_manager), Some(vector_service)) = (&self.job_manager, &self.vector_service) else {
+            return mcp_error(
+                DevManErrorCode::Internal,
+                "Internal error: Job manager or vector search not configured",
+                None,
+            );
+        };
+
+        let request = crate::CreateJobRequest {
+            job_type: crate::JobType::Custom { name: "reindex_knowledge".to_string(), data: json!({}) },
+            timeout_seconds: Some(3600),
+        };
+        let job_id = match job_manager.create_job(request).await {
+            Ok(job_id) => job_id,
+            Err(e) => {
+                return create_mcp_error_response(e.code, &e.message, e.hint.map(|h| json!({"hint": h})), e.retryable)
+            }
+        };
+        let cancel = job_manager.cancellation_token(&job_id).await.unwrap_or_default();
+        tokio::spawn(run_reindex_job(job_manager.clone(), vector_service.clone(), job_id.clone(), cancel));
+
+        json!({
+            "success": true,
+            "data": {
+                "job_id": job_id.to_string(),
+                "message": "Reindexing started; poll devman_get_job_status for progress"
+            }
+        })
+    }
+
     // ==================== Task Guidance Handlers ====================
 
     async fn handle_get_task_guidance(&self, arguments: &serde_json::Value) -> serde_json::Value {
         let task_id_str = match arguments.get("task_id").and_then(|v| v.as_str()) {
             Some(s) => s,
             None => {
-                return create_mcp_error_response(
-                    -32602,
+                return mcp_error(
+                    DevManErrorCode::InvalidParams,
                     "Missing required parameter: task_id",
                     None,
-                    false,
                 );
             }
         };
@@ -1425,11 +3481,10 @@ impl McpServer {
         let task_id = match task_id_str.parse::<devman_core::TaskId>() {
             Ok(id) => id,
             Err(_) => {
-                return create_mcp_error_response(
-                    -32602,
+                return mcp_error(
+                    DevManErrorCode::InvalidParams,
                     "Invalid task_id format",
                     None,
-                    false,
                 );
             }
         };
@@ -1438,15 +3493,23 @@ impl McpServer {
         let ai_interface = match &self.ai_interface {
             Some(ai) => ai,
             None => {
-                return create_mcp_error_response(
-                    -32603,
+                return mcp_error(
+                    DevManErrorCode::Internal,
                     "Internal error: AI interface not configured",
                     None,
-                    false,
                 );
             }
         };
 
+        let (warnings, estimated_cost) = match ai_interface.get_task(task_id).await {
+            Some(task) => {
+                let cost = ai_interface.estimate_task_cost(task_id).await;
+                (crate::interactive::coverage_warnings(&task.coverage_gaps()), cost)
+            }
+            None => (Vec::new(), None),
+        };
+        let health = if warnings.is_empty() { "healthy" } else { "warning" };
+
         // For now, return placeholder guidance
         // In full implementation, this would call InteractiveAI::get_task_guidance
         json!({
@@ -1455,11 +3518,13 @@ impl McpServer {
                 "task_id": task_id_str,
                 "current_state": "Created",
                 "next_action": "read_context",
-                "guidance_message": "请调用 devman_read_task_context() 读取任务上下文",
+                "guidance_message": self.messages.get("guidance.read_context"),
                 "allowed_operations": ["devman_read_task_context"],
                 "prerequisites_satisfied": true,
                 "missing_prerequisites": [],
-                "health": "healthy"
+                "health": health,
+                "warnings": warnings,
+                "estimated_cost": estimated_cost
             }
         })
     }
@@ -1468,22 +3533,77 @@ impl McpServer {
         let task_id_str = match arguments.get("task_id").and_then(|v| v.as_str()) {
             Some(s) => s,
             None => {
-                return create_mcp_error_response(
-                    -32602,
+                return mcp_error(
+                    DevManErrorCode::InvalidParams,
                     "Missing required parameter: task_id",
                     None,
-                    false,
                 );
             }
         };
 
-        // Placeholder implementation
+        if let Some(ai_interface) = &self.ai_interface {
+            let task_id = match task_id_str.parse::<devman_core::TaskId>() {
+                Ok(id) => id,
+                Err(_) => {
+                    return mcp_error(
+                        DevManErrorCode::InvalidParams,
+                        "Invalid task_id format",
+                        None,
+                    );
+                }
+            };
+            let max_tokens = arguments
+                .get("max_tokens")
+                .and_then(|v| v.as_u64())
+                .map_or(DEFAULT_CONTEXT_TOKENS, |n| n as usize);
+
+            let context = match ai_interface.task_context(task_id, devman_work::ContextBudget::new(max_tokens)).await {
+                Ok(context) => context,
+                Err(e) => return interface_error("Failed to read task context", &e),
+            };
+            let task = context.active_task.as_ref();
+            return json!({
+                "success": true,
+                "data": {
+                    "task_id": task_id_str,
+                    "state": "ContextRead",
+                    "message": self.messages.get("context.read"),
+                    "task_info": {
+                        "title": task.map(|t| t.title.clone()),
+                        "description": task.map(|t| t.description.clone()),
+                        "phase": context.phase.as_ref().map(|p| p.name.clone())
+                    },
+                    "knowledge": context.knowledge.iter().map(|k| json!({
+                        "knowledge_id": k.knowledge.id.to_string(),
+                        "title": k.knowledge.title,
+                        "relevance_score": k.score
+                    })).collect::<Vec<_>>(),
+                    "dependencies": context.dependencies.iter().map(|t| json!({
+                        "task_id": t.id.to_string(),
+                        "title": t.title,
+                        "status": format!("{:?}", t.status)
+                    })).collect::<Vec<_>>(),
+                    "history": context.history.iter().map(|e| json!({
+                        "timestamp": e.timestamp,
+                        "event_type": format!("{:?}", e.event_type),
+                        "description": e.description
+                    })).collect::<Vec<_>>(),
+                    "omitted": {
+                        "knowledge": context.omitted.knowledge,
+                        "dependencies": context.omitted.dependencies,
+                        "history": context.omitted.history
+                    }
+                }
+            });
+        }
+
+        // Placeholder when no AI interface is configured
         json!({
             "success": true,
             "data": {
                 "task_id": task_id_str,
                 "state": "ContextRead",
-                "message": "上下文已读取",
+                "message": self.messages.get("context.read"),
                 "task_info": {
                     "title": "任务标题",
                     "description": "任务描述",
@@ -1504,11 +3624,10 @@ impl McpServer {
         let ai_interface = match &self.ai_interface {
             Some(ai) => ai,
             None => {
-                return create_mcp_error_response(
-                    -32603,
+                return mcp_error(
+                    DevManErrorCode::Internal,
                     "Internal error: AI interface not configured",
                     None,
-                    false,
                 );
             }
         };
@@ -1550,11 +3669,10 @@ impl McpServer {
         let task_id_str = match arguments.get("task_id").and_then(|v| v.as_str()) {
             Some(s) => s,
             None => {
-                return create_mcp_error_response(
-                    -32602,
+                return mcp_error(
+                    DevManErrorCode::InvalidParams,
                     "Missing required parameter: task_id",
                     None,
-                    false,
                 );
             }
         };
@@ -1565,17 +3683,179 @@ impl McpServer {
                 "task_id": task_id_str,
                 "state": "InProgress",
                 "session_id": format!("session_{}", task_id_str),
-                "message": "开始执行，请使用 devman_log_work() 记录工作进展"
+                "message": self.messages.get("execution.started")
+            }
+        })
+    }
+
+    async fn handle_get_task_timeline(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        arguments: &serde_json::Value,
+    ) -> serde_json::Value {
+        let task_id_str = match arguments.get("task_id").and_then(|v| v.as_str()) {
+            Some(s) => s,
+            None => {
+                return mcp_error(
+                    DevManErrorCode::InvalidParams,
+                    "Missing required parameter: task_id",
+                    None,
+                );
+            }
+        };
+
+        let task_id = match task_id_str.parse::<devman_core::TaskId>() {
+            Ok(id) => id,
+            Err(_) => {
+                return mcp_error(
+                    DevManErrorCode::InvalidParams,
+                    "Invalid task_id format",
+                    None,
+                );
+            }
+        };
+
+        match ai_interface.get_task_timeline(task_id).await {
+            Ok(entries) => json!({
+                "success": true,
+                "data": {
+                    "task_id": task_id_str,
+                    "total_count": entries.len(),
+                    "entries": entries
+                }
+            }),
+            Err(e) => interface_error("Failed to get task timeline", &e),
+        }
+    }
+
+    async fn handle_tasks_touching_file(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        arguments: &serde_json::Value,
+    ) -> serde_json::Value {
+        let Some(path) = arguments.get("path").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: path",
+                None,
+            );
+        };
+
+        let tasks: Vec<_> = ai_interface
+            .tasks_touching_file(path)
+            .await
+            .into_iter()
+            .map(|t| json!({
+                "task_id": t.id.to_string(),
+                "title": t.title,
+                "status": format!("{:?}", t.status),
+                "declared": t.declared,
+                "worked_on": t.worked_on
+            }))
+            .collect();
+
+        json!({
+            "success": true,
+            "data": {
+                "path": path,
+                "count": tasks.len(),
+                "tasks": tasks
+            }
+        })
+    }
+
+    async fn handle_recommend_next(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        arguments: &serde_json::Value,
+    ) -> serde_json::Value {
+        let agent = arguments.get("agent_id").and_then(|v| v.as_str()).map(devman_core::AgentId::new);
+        let Some(rec) = ai_interface.recommend_next_task(agent.as_ref()).await else {
+            return json!({
+                "success": true,
+                "data": {
+                    "task_id": null,
+                    "message": "No ready tasks - all open tasks are blocked, in progress or waiting on dependencies"
+                }
+            });
+        };
+
+        let knowledge: Vec<_> = rec
+            .relevant_knowledge
+            .iter()
+            .map(|k| json!({"id": k.id.to_string(), "title": k.title}))
+            .collect();
+
+        json!({
+            "success": true,
+            "data": {
+                "task_id": rec.task.id.to_string(),
+                "title": rec.task.title,
+                "status": format!("{:?}", rec.task.status),
+                "rationale": rec.rationale,
+                "readiness": rec.readiness,
+                "relevant_knowledge": knowledge,
+                "candidates": rec.candidates,
+                "estimated_cost": rec.estimated_cost
             }
         })
     }
 
     async fn handle_log_work(&self, arguments: &serde_json::Value) -> serde_json::Value {
+        let task_id_str = match arguments.get("task_id").and_then(|v| v.as_str()) {
+            Some(s) => s,
+            None => {
+                return mcp_error(
+                    DevManErrorCode::InvalidParams,
+                    "Missing required parameter: task_id",
+                    None,
+                );
+            }
+        };
+
+        let action = arguments.get("action").and_then(|v| v.as_str()).unwrap_or("modified");
+        let description = arguments.get("description").and_then(|v| v.as_str()).unwrap_or("");
+        let files = arguments.get("files").cloned().unwrap_or_else(|| json!([]));
+
+        // Timestamp is taken when the event is built, not when it is persisted
+        let event = devman_core::WorkEvent::new(
+            devman_core::WorkEventType::StepCompleted,
+            description,
+            json!({
+                "action": action,
+                "files": files
+            }),
+            chrono::Utc::now(),
+        );
+        let logged_at = event.timestamp.to_rfc3339();
+
+        if let Some(ai) = &self.ai_interface {
+            let task_id = match task_id_str.parse::<devman_core::TaskId>() {
+                Ok(id) => id,
+                Err(_) => {
+                    return mcp_error(
+                        DevManErrorCode::InvalidParams,
+                        "Invalid task_id format",
+                        None,
+                    );
+                }
+            };
+
+            if let Err(e) = ai.record_work_event(task_id, event).await {
+                return mcp_error(
+                    DevManErrorCode::Business,
+                    &format!("Failed to log work: {}", e),
+                    None,
+                );
+            }
+        }
+
         json!({
             "success": true,
             "message": "Work logged",
             "data": {
-                "recorded": true
+                "recorded": true,
+                "timestamp": logged_at
             }
         })
     }
@@ -1592,22 +3872,244 @@ impl McpServer {
         })
     }
 
-    async fn handle_run_task_quality_check(&self, arguments: &serde_json::Value) -> serde_json::Value {
+    async fn handle_run_task_quality_check(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        arguments: &serde_json::Value,
+    ) -> serde_json::Value {
+        let Some(task_id) = arguments.get("task_id").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: task_id",
+                None,
+            );
+        };
+
+        let Ok(task_id) = task_id.parse::<devman_core::TaskId>() else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Invalid task_id format",
+                None,
+            );
+        };
+
+        if arguments.get("async").and_then(|v| v.as_bool()).unwrap_or(false) {
+            let Some(job_manager) = &self.job_manager else {
+                return mcp_error(DevManErrorCode::Internal, "Internal error: Job manager not configured", None);
+            };
+            let request = crate::CreateJobRequest {
+                job_type: crate::JobType::QualityCheck { check_type: "task".to_string(), target: Some(task_id.to_string()) },
+                timeout_seconds: Some(3600),
+            };
+            let job_id = match job_manager.create_job(request).await {
+                Ok(job_id) => job_id,
+                Err(e) => {
+                    return create_mcp_error_response(e.code, &e.message, e.hint.map(|h| json!({"hint": h})), e.retryable)
+                }
+            };
+            let cancel = job_manager.cancellation_token(&job_id).await.unwrap_or_default();
+            let (job_manager, ai_interface, job) = (job_manager.clone(), ai_interface.clone(), job_id.clone());
+            tokio::spawn(async move {
+                let outcome = match ai_interface.run_task_quality_check(task_id, &cancel).await {
+                    Ok(results) => Ok(quality_check_json(task_id, &results)),
+                    Err(e) => Err(crate::JobError {
+                        code: DevManErrorCode::Business.code(),
+                        message: format!("Failed to run quality check: {}", e),
+                        hint: None,
+                        retryable: true,
+                        data: None,
+                    }),
+                };
+                if let Err(e) = job_manager.finish_job(&job, outcome).await {
+                    warn!("Could not finish quality check job {}: {}", job, e.message);
+                }
+            });
+            return json!({
+                "success": true,
+                "async": true,
+                "job_id": job_id.to_string(),
+                "status": "running"
+            });
+        }
+
+        match ai_interface.run_task_quality_check(task_id, &devman_tools::CancellationToken::new()).await {
+            Ok(results) => {
+                let mut data = quality_check_json(task_id, &results);
+                data["state"] = json!("QualityChecking");
+                data["check_id"] = json!(format!("check_{}", chrono::Utc::now().timestamp()));
+                data["message"] = json!(self.messages.get("quality.running"));
+                json!({"success": true, "data": data})
+            }
+            Err(e) => interface_error("Failed to run quality check", &e),
+        }
+    }
+
+    async fn handle_quality_history(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        arguments: &serde_json::Value,
+    ) -> serde_json::Value {
+        let Some(task_id) = arguments.get("task_id").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: task_id",
+                None,
+            );
+        };
+
+        let Ok(task_id) = task_id.parse::<devman_core::TaskId>() else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Invalid task_id format",
+                None,
+            );
+        };
+
+        let history = match ai_interface.quality_history(task_id).await {
+            Ok(history) => history,
+            Err(e) => return interface_error("Failed to load quality history", &e),
+        };
+        let trend = match ai_interface.quality_trend(task_id).await {
+            Ok(trend) => trend,
+            Err(e) => return interface_error("Failed to load quality history", &e),
+        };
+
+        let history: Vec<_> = history
+            .iter()
+            .map(|check| json!({
+                "check_id": check.check_id.to_string(),
+                "passed": check.passed,
+                "findings": check.findings.len(),
+            }))
+            .collect();
         json!({
             "success": true,
             "data": {
-                "state": "QualityChecking",
-                "check_id": format!("check_{}", chrono::Utc::now().timestamp()),
-                "message": "质检运行中，请使用 devman_get_quality_result() 获取结果"
+                "task_id": task_id.to_string(),
+                "history": history,
+                "trend": trend,
             }
         })
     }
 
+    async fn handle_goal_quality(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        arguments: &serde_json::Value,
+    ) -> serde_json::Value {
+        let Some(goal_id) = arguments.get("goal_id").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: goal_id",
+                None,
+            );
+        };
+
+        let Ok(goal_id) = goal_id.parse::<devman_core::GoalId>() else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Invalid goal_id format",
+                None,
+            );
+        };
+
+        match ai_interface.goal_quality_summary(goal_id).await {
+            Ok(summary) => json!({
+                "success": true,
+                "data": summary
+            }),
+            Err(e) => interface_error("Failed to summarize goal quality", &e),
+        }
+    }
+
+    async fn handle_assign_quality_profile(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        arguments: &serde_json::Value,
+    ) -> serde_json::Value {
+        let Some(phase_id) = arguments.get("phase_id").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: phase_id",
+                None,
+            );
+        };
+        let Ok(phase_id) = phase_id.parse::<devman_core::PhaseId>() else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Invalid phase_id format",
+                None,
+            );
+        };
+
+        let Some(profile_id) = arguments.get("profile_id").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: profile_id",
+                None,
+            );
+        };
+        let Ok(profile_id) = profile_id.parse::<devman_core::QualityProfileId>() else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Invalid profile_id format",
+                None,
+            );
+        };
+
+        match ai_interface.assign_profile(phase_id, profile_id).await {
+            Ok(phase) => json!({
+                "success": true,
+                "data": {
+                    "phase_id": phase.id.to_string(),
+                    "name": phase.name,
+                    "quality_profile": profile_id.to_string()
+                }
+            }),
+            Err(e) => interface_error("Failed to assign quality profile", &e),
+        }
+    }
+
     async fn handle_get_quality_result(&self, arguments: &serde_json::Value) -> serde_json::Value {
+        let check_id = arguments.get("check_id").and_then(|v| v.as_str()).unwrap_or("");
+        if let (Some(ai_interface), Some(task_id)) =
+            (&self.ai_interface, arguments.get("task_id").and_then(|v| v.as_str()))
+        {
+            let Ok(parsed_id) = task_id.parse::<devman_core::TaskId>() else {
+                return mcp_error(DevManErrorCode::InvalidParams, "Invalid task_id format", None);
+            };
+            return match ai_interface.classify_quality_failures(parsed_id).await {
+                Ok(Some(classification)) => json!({
+                    "success": true,
+                    "data": {
+                        "check_id": check_id,
+                        "task_id": task_id,
+                        "status": "completed",
+                        "overall_status": if classification.decision == QualityDecision::AcceptAndComplete {
+                            "passed"
+                        } else {
+                            "failed"
+                        },
+                        "findings_count": classification.kinds.values().sum::<usize>(),
+                        "failure_kinds": classification.kinds,
+                        "recommended_decision": classification.decision,
+                        "reason": classification.reason,
+                        "next_action": "confirm_result"
+                    }
+                }),
+                Ok(None) => mcp_error(
+                    DevManErrorCode::NotFound,
+                    &format!("No quality runs for task: {}", task_id),
+                    None,
+                ),
+                Err(e) => interface_error("Failed to get quality result", &e),
+            };
+        }
+
         json!({
             "success": true,
             "data": {
-                "check_id": arguments.get("check_id").and_then(|v| v.as_str()).unwrap_or(""),
+                "check_id": check_id,
                 "status": "completed",
                 "overall_status": "passed",
                 "findings_count": 0,
@@ -1623,29 +4125,88 @@ impl McpServer {
             "data": {
                 "state": "QualityCompleted",
                 "decision": arguments.get("decision").and_then(|v| v.as_str()).unwrap_or(""),
-                "message": "质检结果已确认"
+                "message": self.messages.get("quality.confirmed")
             }
         })
     }
 
     async fn handle_complete_task(&self, arguments: &serde_json::Value) -> serde_json::Value {
-        json!({
-            "success": true,
-            "data": {
-                "task_id": arguments.get("task_id").and_then(|v| v.as_str()).unwrap_or(""),
-                "state": "Completed",
-                "message": "任务已完成"
-            }
-        })
-    }
-
+        let task_id = arguments.get("task_id").and_then(|v| v.as_str()).unwrap_or("");
+        let Some(ai_interface) = &self.ai_interface else {
+            return json!({
+                "success": true,
+                "data": {
+                    "task_id": task_id,
+                    "state": "Completed",
+                    "message": self.messages.get("task.completed")
+                }
+            });
+        };
+
+        let Ok(parsed_id) = task_id.parse::<devman_core::TaskId>() else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing or invalid parameter: task_id",
+                None,
+            );
+        };
+        let created_knowledge = match arguments.get("created_knowledge_ids") {
+            None | Some(serde_json::Value::Null) => Vec::new(),
+            Some(ids) => {
+                let parsed: Option<Vec<devman_core::KnowledgeId>> = ids
+                    .as_array()
+                    .and_then(|ids| ids.iter().map(|id| id.as_str()?.parse().ok()).collect());
+                let Some(parsed) = parsed else {
+                    return mcp_error(
+                        DevManErrorCode::InvalidParams,
+                        "Invalid created_knowledge_ids: expected an array of knowledge IDs",
+                        None,
+                    );
+                };
+                parsed
+            }
+        };
+
+        let result = devman_core::WorkResult {
+            status: devman_core::CompletionStatus::Success,
+            outputs: arguments
+                .get("summary")
+                .and_then(|v| v.as_str())
+                .map(|summary| devman_core::Output { name: "summary".to_string(), value: summary.to_string() })
+                .into_iter()
+                .collect(),
+            metrics: devman_core::WorkMetrics {
+                token_used: None,
+                time_spent: std::time::Duration::ZERO,
+                tools_invoked: 0,
+                quality_checks_run: 0,
+                quality_checks_passed: 0,
+            },
+            created_knowledge: created_knowledge.clone(),
+        };
+        match ai_interface.complete_task(parsed_id, result).await {
+            Ok(report) => json!({
+                "success": true,
+                "data": {
+                    "task_id": task_id,
+                    "state": "Completed",
+                    "created_knowledge_ids": created_knowledge.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+                    "requeued_tasks": report.requeued.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+                    "warnings": report.warnings,
+                    "message": self.messages.get("task.completed")
+                }
+            }),
+            Err(e) => interface_error("Failed to complete task", &e),
+        }
+    }
+
     async fn handle_pause_task(&self, arguments: &serde_json::Value) -> serde_json::Value {
         json!({
             "success": true,
             "data": {
                 "state": "Paused",
                 "reason": arguments.get("reason").and_then(|v| v.as_str()).unwrap_or(""),
-                "message": "任务已暂停，可使用 devman_resume_task() 恢复"
+                "message": self.messages.get("task.paused")
             }
         })
     }
@@ -1654,175 +4215,781 @@ impl McpServer {
         json!({
             "success": true,
             "data": {
-                "message": "任务已恢复"
+                "message": self.messages.get("task.resumed")
             }
         })
     }
 
     async fn handle_abandon_task(&self, arguments: &serde_json::Value) -> serde_json::Value {
+        let reason_type = arguments.get("reason_type").and_then(|v| v.as_str()).unwrap_or("");
+        let reason_text = arguments.get("reason").and_then(|v| v.as_str()).unwrap_or("");
+        let reason = abandon_reason(reason_type, reason_text.to_string());
+        let task_id = arguments
+            .get("task_id")
+            .and_then(|v| v.as_str())
+            .and_then(|id| id.parse::<devman_core::TaskId>().ok());
+
+        let (impact, report) = match (&self.ai_interface, task_id) {
+            (Some(ai), Some(task_id)) => {
+                let impact = ai.abandonment_impact(task_id).await.ok();
+                match ai.abandon_task(task_id, reason.clone()).await {
+                    Ok(report) => (impact, report),
+                    Err(e) => return interface_error("Failed to abandon task", &e),
+                }
+            }
+            _ => (None, crate::CascadeReport::default()),
+        };
+
         json!({
             "success": true,
             "data": {
                 "state": "Abandoned",
-                "reason_type": arguments.get("reason_type").and_then(|v| v.as_str()).unwrap_or(""),
-                "reason": arguments.get("reason").and_then(|v| v.as_str()).unwrap_or(""),
-                "message": "任务已放弃",
-                "can_be_reassigned": true,
-                "work_preserved": true
+                "reason_type": reason_type,
+                "reason": reason_text,
+                "message": self.messages.get("task.abandoned"),
+                "can_be_reassigned": reason.is_recoverable(),
+                "work_preserved": true,
+                "impact": impact,
+                "requeued_tasks": report.requeued.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+                "warnings": report.warnings
             }
         })
     }
 
-    /// Read a resource.
-    async fn read_resource(&self, _uri: &str) -> serde_json::Value {
-        // Default response - resources would be loaded from storage in full implementation
-        json!({
-            "contents": [{
-                "uri": _uri,
-                "mimeType": "application/json",
-                "text": "{}"
-            }]
-        })
-    }
+    async fn handle_claim_task(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        arguments: &serde_json::Value,
+    ) -> serde_json::Value {
+        let Some(task_id) = arguments.get("task_id").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: task_id",
+                None,
+            );
+        };
+        let Ok(task_id) = task_id.parse::<devman_core::TaskId>() else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Invalid task_id format",
+                None,
+            );
+        };
+        let Some(agent_id) = arguments.get("agent_id").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: agent_id",
+                None,
+            );
+        };
+        let minutes = arguments.get("ttl_minutes").and_then(|v| v.as_u64()).unwrap_or(30).max(1);
 
-    /// Start the MCP server with stdio transport.
-    pub async fn start(&mut self) -> anyhow::Result<()> {
-        self.start_with_stdio().await
+        match ai_interface
+            .claim_task(
+                task_id,
+                devman_core::AgentId::new(agent_id),
+                std::time::Duration::from_secs(minutes * 60),
+            )
+            .await
+        {
+            Ok(token) => json!({
+                "success": true,
+                "data": {
+                    "task_id": task_id.to_string(),
+                    "agent_id": agent_id,
+                    "token": token.to_string(),
+                    "ttl_minutes": minutes
+                }
+            }),
+            Err(e) => interface_error("Failed to claim task", &e),
+        }
     }
 
-    /// Start with stdio transport.
-    async fn start_with_stdio(&mut self) -> anyhow::Result<()> {
-        let stdin = BufReader::new(tokio::io::stdin());
-        let mut lines = stdin.lines();
-        let mut stdout = BufWriter::new(tokio::io::stdout());
+    async fn handle_release_task(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        arguments: &serde_json::Value,
+    ) -> serde_json::Value {
+        let Some(task_id) = arguments.get("task_id").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: task_id",
+                None,
+            );
+        };
+        let Ok(task_id) = task_id.parse::<devman_core::TaskId>() else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Invalid task_id format",
+                None,
+            );
+        };
+        let Some(Ok(token)) = arguments
+            .get("token")
+            .and_then(|v| v.as_str())
+            .map(|t| t.parse::<devman_core::ClaimToken>())
+        else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing or invalid parameter: token",
+                None,
+            );
+        };
 
-        self.running = true;
+        match ai_interface.release_task(task_id, token).await {
+            Ok(()) => json!({
+                "success": true,
+                "data": {
+                    "task_id": task_id.to_string(),
+                    "released": true
+                }
+            }),
+            Err(e) => interface_error("Failed to release task", &e),
+        }
+    }
 
-        while let Some(line_result) = lines.next_line().await? {
-            // Parse JSON-RPC request
-            if line_result.trim().is_empty() {
-                continue;
-            }
+    async fn handle_insert_phase(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        arguments: &serde_json::Value,
+    ) -> serde_json::Value {
+        let Some(project_id) = arguments.get("project_id").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: project_id",
+                None,
+            );
+        };
+        let Ok(project_id) = project_id.parse::<devman_core::ProjectId>() else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Invalid project_id format",
+                None,
+            );
+        };
+        let Some(name) = arguments.get("name").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: name",
+                None,
+            );
+        };
+        let after = match arguments.get("after_phase_id").and_then(|v| v.as_str()) {
+            Some(id) => match id.parse::<devman_core::PhaseId>() {
+                Ok(id) => Some(id),
+                Err(_) => {
+                    return mcp_error(
+                        DevManErrorCode::InvalidParams,
+                        "Invalid after_phase_id format",
+                        None,
+                    )
+                }
+            },
+            None => None,
+        };
+        let Some(depends_on) = parse_phase_ids(arguments.get("depends_on")) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Invalid depends_on: expected an array of phase IDs",
+                None,
+            );
+        };
+        let spec = crate::PhaseSpec {
+            name: name.to_string(),
+            description: arguments.get("description").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+            objectives: arguments
+                .get("objectives")
+                .and_then(|v| v.as_array())
+                .map(|items| items.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+                .unwrap_or_default(),
+            depends_on,
+        };
 
-            let (id, method, params) = match parse_json_rpc_request(&line_result) {
-                Ok(result) => result,
-                Err(e) => {
-                    let error_response = JsonRpcResponse::error(None, -32700, &e);
-                    let error_json = serde_json::to_string(&error_response)
-                        .unwrap_or_else(|_| "{}".to_string());
-                    if let Err(_) = stdout.write_all(error_json.as_bytes()).await { break; }
-                    if let Err(_) = stdout.write_all(b"\n").await { break; }
-                    if let Err(_) = stdout.flush().await { break; }
-                    continue;
+        match ai_interface.insert_phase(project_id, after, spec).await {
+            Ok(phase) => json!({
+                "success": true,
+                "data": {
+                    "project_id": project_id.to_string(),
+                    "phase_id": phase.id.to_string(),
+                    "name": phase.name,
+                    "depends_on": phase.depends_on.iter().map(|id| id.to_string()).collect::<Vec<_>>()
                 }
-            };
+            }),
+            Err(e) => interface_error("Failed to insert phase", &e),
+        }
+    }
 
-            // Handle the request
-            let result = self.handle_request(&method, &params).await;
+    async fn handle_reorder_phases(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        arguments: &serde_json::Value,
+    ) -> serde_json::Value {
+        let Some(project_id) = arguments.get("project_id").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: project_id",
+                None,
+            );
+        };
+        let Ok(project_id) = project_id.parse::<devman_core::ProjectId>() else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Invalid project_id format",
+                None,
+            );
+        };
+        let Some(order) = arguments.get("phase_ids").and_then(|v| parse_phase_ids(Some(v))) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing or invalid parameter: phase_ids",
+                None,
+            );
+        };
 
-            // Check if result is an error
-            let response = if let Some(error) = result.get("error") {
-                JsonRpcResponse::error(id, error.get("code").and_then(|v| v.as_i64()).unwrap_or(-32000) as i32, error.get("message").and_then(|v| v.as_str()).unwrap_or("Unknown error"))
-            } else {
-                JsonRpcResponse::success(id, result)
-            };
+        match ai_interface.reorder_phases(project_id, order).await {
+            Ok(project) => json!({
+                "success": true,
+                "data": {
+                    "project_id": project.id.to_string(),
+                    "phases": project.phases.iter().map(|id| id.to_string()).collect::<Vec<_>>()
+                }
+            }),
+            Err(e) => interface_error("Failed to reorder phases", &e),
+        }
+    }
 
-            let response_json = serde_json::to_string(&response)
-                .unwrap_or_else(|_| "{}".to_string());
+    async fn handle_advance_phase(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        arguments: &serde_json::Value,
+    ) -> serde_json::Value {
+        let Some(goal_id) = arguments.get("goal_id").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: goal_id",
+                None,
+            );
+        };
+        let Ok(goal_id) = goal_id.parse::<devman_core::GoalId>() else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Invalid goal_id format",
+                None,
+            );
+        };
 
-            if let Err(_) = stdout.write_all(response_json.as_bytes()).await { break; }
-            if let Err(_) = stdout.write_all(b"\n").await { break; }
-            if let Err(_) = stdout.flush().await { break; }
+        let phase_id = match ai_interface.advance_phase(goal_id).await {
+            Ok(phase_id) => phase_id,
+            Err(e) => return interface_error("Failed to advance phase", &e),
+        };
+        match ai_interface.get_goal(goal_id).await {
+            Some(goal) => json!({
+                "success": true,
+                "data": {
+                    "goal_id": goal.id.to_string(),
+                    "current_phase": phase_id.to_string(),
+                    "goal_status": format!("{:?}", goal.status)
+                }
+            }),
+            None => mcp_error(
+                DevManErrorCode::NotFound,
+                &format!("Goal not found: {}", goal_id),
+                None,
+            ),
         }
-
-        self.running = false;
-        Ok(())
     }
 
-    /// Start with Unix socket transport.
-    pub async fn start_with_socket(&mut self, socket_path: &std::path::Path) -> anyhow::Result<()> {
-        // Remove existing socket file
-        if socket_path.exists() {
-            std::fs::remove_file(socket_path)?;
+    async fn handle_set_active_phase(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        arguments: &serde_json::Value,
+    ) -> serde_json::Value {
+        let Some(goal_id) = arguments.get("goal_id").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: goal_id",
+                None,
+            );
+        };
+        let Ok(goal_id) = goal_id.parse::<devman_core::GoalId>() else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Invalid goal_id format",
+                None,
+            );
+        };
+        let Some(phase_id) = arguments.get("phase_id").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: phase_id",
+                None,
+            );
+        };
+        let Ok(phase_id) = phase_id.parse::<devman_core::PhaseId>() else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Invalid phase_id format",
+                None,
+            );
+        };
+
+        match ai_interface.set_active_phase(goal_id, phase_id).await {
+            Ok(goal) => json!({
+                "success": true,
+                "data": {
+                    "goal_id": goal.id.to_string(),
+                    "current_phase": goal.current_phase.to_string(),
+                    "goal_status": format!("{:?}", goal.status)
+                }
+            }),
+            Err(e) => interface_error("Failed to set active phase", &e),
         }
+    }
 
-        let listener = tokio::net::UnixListener::bind(socket_path)?;
-        self.running = true;
+    async fn handle_record_actuals(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        arguments: &serde_json::Value,
+    ) -> serde_json::Value {
+        let Some(task_id) = arguments.get("task_id").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: task_id",
+                None,
+            );
+        };
+        let Ok(task_id) = task_id.parse::<devman_core::TaskId>() else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Invalid task_id format",
+                None,
+            );
+        };
+        let Some(minutes) = arguments.get("duration_minutes").and_then(|v| v.as_u64()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing or invalid parameter: duration_minutes",
+                None,
+            );
+        };
+        let tokens = arguments.get("tokens").and_then(|v| v.as_u64()).map(|t| t as usize);
 
-        loop {
-            tokio::select! {
-                result = listener.accept() => {
-                    match result {
-                        Ok((stream, _)) => {
-                            self.handle_connection(stream).await?;
-                        }
-                        Err(_) => {
-                            // Connection error, continue
-                        }
-                    }
-                }
-                _ = tokio::signal::ctrl_c() => {
-                    break;
+        match ai_interface
+            .record_actuals(task_id, std::time::Duration::from_secs(minutes * 60), tokens)
+            .await
+        {
+            Ok(task) => json!({
+                "success": true,
+                "data": {
+                    "task_id": task.id.to_string(),
+                    "actuals": task.actuals,
                 }
-            }
+            }),
+            Err(e) => interface_error("Failed to record actuals", &e),
         }
-
-        self.running = false;
-        Ok(())
     }
 
-    /// Handle a client connection.
-    async fn handle_connection(&self, stream: UnixStream) -> anyhow::Result<()> {
-        let (reader, mut writer) = stream.into_split();
-        let reader = BufReader::new(reader);
-        let mut lines = reader.lines();
+    async fn handle_reopen_task(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        arguments: &serde_json::Value,
+    ) -> serde_json::Value {
+        let Some(task_id_str) = arguments.get("task_id").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: task_id",
+                None,
+            );
+        };
 
-        while let Some(line_result) = lines.next_line().await? {
-            if line_result.trim().is_empty() {
-                continue;
-            }
+        let Ok(task_id) = task_id_str.parse::<devman_core::TaskId>() else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Invalid task_id format",
+                None,
+            );
+        };
 
-            let (id, method, params) = match parse_json_rpc_request(&line_result) {
-                Ok(result) => result,
-                Err(e) => {
-                    let error_response = JsonRpcResponse::error(None, -32700, &e);
-                    let error_json = serde_json::to_string(&error_response)
-                        .unwrap_or_else(|_| "{}".to_string());
-                    if let Err(_) = writer.write_all(error_json.as_bytes()).await { break; }
-                    if let Err(_) = writer.write_all(b"\n").await { break; }
-                    continue;
+        let Some(reason) = arguments.get("reason").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: reason",
+                None,
+            );
+        };
+
+        match ai_interface.reopen_task(task_id, reason).await {
+            Ok(goal_ids) => {
+                let mut data = json!({
+                    "task_id": task_id_str,
+                    "status": format!("{:?}", devman_core::TaskStatus::Queued),
+                    "reactivated_goals": goal_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+                });
+                if !goal_ids.is_empty() {
+                    data["warning"] = json!(format!("Reactivated {} completed goal(s)", goal_ids.len()));
                 }
-            };
+                json!({
+                    "success": true,
+                    "data": data
+                })
+            }
+            Err(e) => interface_error("Failed to reopen task", &e),
+        }
+    }
 
-            let result = self.handle_request(&method, &params).await;
+    async fn handle_preview_transition(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        arguments: &serde_json::Value,
+    ) -> serde_json::Value {
+        let Some(task_id_str) = arguments.get("task_id").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: task_id",
+                None,
+            );
+        };
 
-            let response = if let Some(error) = result.get("error") {
-                JsonRpcResponse::error(id, error.get("code").and_then(|v| v.as_i64()).unwrap_or(-32000) as i32, error.get("message").and_then(|v| v.as_str()).unwrap_or("Unknown error"))
-            } else {
-                JsonRpcResponse::success(id, result)
-            };
+        let Ok(task_id) = task_id_str.parse::<devman_core::TaskId>() else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Invalid task_id format",
+                None,
+            );
+        };
 
-            let response_json = serde_json::to_string(&response)
-                .unwrap_or_else(|_| "{}".to_string());
+        let Some(to) = arguments.get("to_state").and_then(|v| v.as_str()).and_then(parse_task_status) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing or invalid parameter: to_state",
+                None,
+            );
+        };
 
-            if let Err(_) = writer.write_all(response_json.as_bytes()).await { break; }
-            if let Err(_) = writer.write_all(b"\n").await { break; }
+        match ai_interface.preview_transition(task_id, to).await {
+            Ok(preview) => json!({
+                "success": true,
+                "data": preview
+            }),
+            Err(e) => interface_error("Failed to preview transition", &e),
         }
-
-        Ok(())
-    }
-
-    /// Stop the MCP server.
-    pub fn stop(&mut self) {
-        self.running = false;
-        info!("MCP Server stopped");
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::{JobType, CreateJobRequest, InMemoryJobManager};
+    async fn handle_abandonment_impact(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        arguments: &serde_json::Value,
+    ) -> serde_json::Value {
+        let Some(task_id_str) = arguments.get("task_id").and_then(|v| v.as_str()) else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Missing required parameter: task_id",
+                None,
+            );
+        };
+
+        let Ok(task_id) = task_id_str.parse::<devman_core::TaskId>() else {
+            return mcp_error(
+                DevManErrorCode::InvalidParams,
+                "Invalid task_id format",
+                None,
+            );
+        };
+
+        match ai_interface.abandonment_impact(task_id).await {
+            Ok(report) => json!({
+                "success": true,
+                "data": report
+            }),
+            Err(e) => interface_error("Failed to analyse abandonment impact", &e),
+        }
+    }
+
+    /// Read a resource.
+    ///
+    /// Static resources take precedence; otherwise the URI is matched
+    /// against the registered templates.
+    async fn read_resource(&self, uri: &str) -> serde_json::Value {
+        if let (false, Some(ai)) = (self.resources.contains_key(uri), &self.ai_interface) {
+            if let Some(template) = self.resource_templates.iter().find(|t| t.match_uri(uri).is_some()) {
+                let id = template.match_uri(uri).unwrap_or_default();
+                let data = match self.read_templated_resource(ai, &template.uri_template, id).await {
+                    Ok(data) => data,
+                    Err(error) => return error,
+                };
+                return json!({
+                    "contents": [{
+                        "uri": uri,
+                        "mimeType": "application/json",
+                        "text": json!({ "data": data }).to_string()
+                    }]
+                });
+            }
+        }
+
+        let text = match (uri, &self.ai_interface) {
+            ("devman://context/project", Some(ai)) => match ai.project_snapshot().await {
+                Ok(snapshot) => json!({ "data": snapshot }).to_string(),
+                Err(e) => return interface_error("Failed to build project snapshot", &e),
+            },
+            ("devman://knowledge/recent", Some(ai)) => {
+                let items = ai.recent_knowledge(RECENT_KNOWLEDGE_LIMIT).await;
+                json!({
+                    "data": {
+                        "knowledge": items,
+                        "total_count": items.len(),
+                        "view": "recent"
+                    }
+                })
+                .to_string()
+            }
+            // Default response - other resources would be loaded from storage in full implementation
+            _ => "{}".to_string(),
+        };
+
+        json!({
+            "contents": [{
+                "uri": uri,
+                "mimeType": "application/json",
+                "text": text
+            }]
+        })
+    }
+
+    /// Load the entity behind a templated resource URI.
+    async fn read_templated_resource(
+        &self,
+        ai_interface: &Arc<dyn AIInterface>,
+        uri_template: &str,
+        id: &str,
+    ) -> Result<serde_json::Value, serde_json::Value> {
+        let invalid_id = |entity: &str| {
+            mcp_error(
+                DevManErrorCode::InvalidParams,
+                &format!("Invalid {} id: {}", entity, id),
+                None,
+            )
+        };
+        let not_found = |entity: &str| {
+            mcp_error(
+                DevManErrorCode::NotFound,
+                &format!("{} not found: {}", entity, id),
+                Some(json!({"entity_type": entity, "id": id})),
+            )
+        };
+
+        match uri_template {
+            "devman://tasks/{id}" => {
+                let task_id = id.parse::<devman_core::TaskId>().map_err(|_| invalid_id("task"))?;
+                let task = ai_interface.get_task(task_id).await.ok_or_else(|| not_found("task"))?;
+                Ok(json!(task))
+            }
+            "devman://knowledge/{id}" => {
+                let knowledge_id = id
+                    .parse::<devman_core::KnowledgeId>()
+                    .map_err(|_| invalid_id("knowledge"))?;
+                let knowledge = ai_interface
+                    .get_knowledge(knowledge_id)
+                    .await
+                    .ok_or_else(|| not_found("knowledge"))?;
+                Ok(json!(knowledge))
+            }
+            _ => Err(mcp_error(
+                DevManErrorCode::InvalidParams,
+                &format!("Unsupported resource template: {}", uri_template),
+                None,
+            )),
+        }
+    }
+
+    /// Start the MCP server with stdio transport.
+    pub async fn start(&mut self) -> anyhow::Result<()> {
+        self.start_with_stdio().await
+    }
+
+    /// Start with stdio transport.
+    async fn start_with_stdio(&mut self) -> anyhow::Result<()> {
+        let stdin = BufReader::new(tokio::io::stdin());
+        let mut lines = stdin.lines();
+        let mut stdout = BufWriter::new(tokio::io::stdout());
+
+        self.running = true;
+
+        while let Some(line_result) = lines.next_line().await? {
+            if line_result.trim().is_empty() {
+                continue;
+            }
+
+            let Some(response_json) = self.handle_line(&line_result).await else {
+                continue;
+            };
+
+            if let Err(_) = stdout.write_all(response_json.as_bytes()).await { break; }
+            if let Err(_) = stdout.write_all(b"\n").await { break; }
+            if let Err(_) = stdout.flush().await { break; }
+        }
+
+        self.running = false;
+        Ok(())
+    }
+
+    /// Start with Unix socket transport, shutting down gracefully on Ctrl+C.
+    pub async fn start_with_socket(&mut self, socket_path: &std::path::Path) -> anyhow::Result<()> {
+        self.start_with_socket_until(socket_path, async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await
+    }
+
+    /// Start with Unix socket transport and serve until `shutdown` completes.
+    ///
+    /// Connections are served concurrently. On shutdown no new connections
+    /// are accepted and each open connection stops reading once the request
+    /// it is handling, if any, has been answered. Connections still busy
+    /// after [`McpServerConfig::shutdown_grace`] are dropped. The socket
+    /// file is removed before returning.
+    pub async fn start_with_socket_until(
+        &mut self,
+        socket_path: &std::path::Path,
+        shutdown: impl std::future::Future<Output = ()>,
+    ) -> anyhow::Result<()> {
+        // Remove existing socket file
+        if socket_path.exists() {
+            std::fs::remove_file(socket_path)?;
+        }
+
+        let listener = tokio::net::UnixListener::bind(socket_path)?;
+        self.running = true;
+
+        let (stop, stopping) = tokio::sync::watch::channel(false);
+        let server = &*self;
+        let mut connections = futures::stream::FuturesUnordered::new();
+        tokio::pin!(shutdown);
+
+        loop {
+            tokio::select! {
+                result = listener.accept() => {
+                    match result {
+                        Ok((stream, _)) => {
+                            connections.push(server.handle_connection(stream, stopping.clone()));
+                        }
+                        Err(_) => {
+                            // Connection error, continue
+                        }
+                    }
+                }
+                Some(result) = futures::StreamExt::next(&mut connections), if !connections.is_empty() => {
+                    if let Err(e) = result {
+                        debug!("Socket connection closed with error: {}", e);
+                    }
+                }
+                _ = &mut shutdown => {
+                    break;
+                }
+            }
+        }
+
+        drop(listener);
+        let _ = stop.send(true);
+        let drain = async { while futures::StreamExt::next(&mut connections).await.is_some() {} };
+        if tokio::time::timeout(server.config.shutdown_grace, drain).await.is_err() {
+            warn!("Dropping {} socket connection(s) still busy after the shutdown grace period", connections.len());
+        }
+        drop(connections);
+
+        if let Err(e) = std::fs::remove_file(socket_path) {
+            warn!("Failed to remove socket file {}: {}", socket_path.display(), e);
+        }
+        self.running = false;
+        Ok(())
+    }
+
+    /// Handle a client connection until it closes or `stopping` is set.
+    ///
+    /// A request that has been read is always answered; `stopping` is only
+    /// checked while waiting for the next one.
+    async fn handle_connection(
+        &self,
+        stream: UnixStream,
+        mut stopping: tokio::sync::watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let (reader, mut writer) = stream.into_split();
+        let reader = BufReader::new(reader);
+        let mut lines = reader.lines();
+
+        loop {
+            let line_result = tokio::select! {
+                line = lines.next_line() => line?,
+                _ = stopping.wait_for(|stopping| *stopping) => break,
+            };
+            let Some(line_result) = line_result else {
+                break;
+            };
+            if line_result.trim().is_empty() {
+                continue;
+            }
+
+            let Some(response_json) = self.handle_line(&line_result).await else {
+                continue;
+            };
+
+            if writer.write_all(response_json.as_bytes()).await.is_err()
+                || writer.write_all(b"\n").await.is_err()
+                || writer.flush().await.is_err()
+            {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handle one line of JSON-RPC input and return the response line.
+    ///
+    /// Requests get their `id` echoed back unchanged. Notifications (no
+    /// `id` member) are handled but get no response. Lines that cannot be
+    /// parsed get an error with the `id` when one can be read, else `null`.
+    async fn handle_line(&self, line: &str) -> Option<String> {
+        let response = match parse_json_rpc_request(line) {
+            Ok((None, method, params)) => {
+                self.handle_request(&method, &params).await;
+                return None;
+            }
+            Ok((id, method, params)) => {
+                let result = self.handle_request(&method, &params).await;
+                if let Some(error) = result.get("error") {
+                    JsonRpcResponse::error_with_data(
+                        id,
+                        error.get("code").and_then(|v| v.as_i64()).unwrap_or(DevManErrorCode::Business.code() as i64) as i32,
+                        error.get("message").and_then(|v| v.as_str()).unwrap_or("Unknown error"),
+                        error.get("data").cloned(),
+                    )
+                } else {
+                    JsonRpcResponse::success(id, result)
+                }
+            }
+            Err(e) => {
+                let id = serde_json::from_str::<serde_json::Value>(line)
+                    .ok()
+                    .and_then(|v| v.get("id").cloned());
+                JsonRpcResponse::error(id, DevManErrorCode::ParseError.code(), &e)
+            }
+        };
+
+        Some(serde_json::to_string(&response).unwrap_or_else(|_| "{}".to_string()))
+    }
+
+    /// Stop the MCP server.
+    pub fn stop(&mut self) {
+        self.running = false;
+        info!("MCP Server stopped");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{JobType, CreateJobRequest, InMemoryJobManager};
     use tokio::sync::Mutex;
     use std::sync::Arc;
     use tempfile::TempDir;
@@ -1880,6 +5047,146 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_notification_gets_no_response() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        let line = r#"{"jsonrpc": "2.0", "method": "notifications/initialized"}"#;
+        assert!(server.handle_line(line).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_request_id_echoed_verbatim() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        for (id, expected) in [("7", json!(7)), (r#""7""#, json!("7")), ("null", serde_json::Value::Null)] {
+            let line = format!(r#"{{"jsonrpc": "2.0", "id": {}, "method": "tools/list"}}"#, id);
+            let response: serde_json::Value =
+                serde_json::from_str(&server.handle_line(&line).await.unwrap()).unwrap();
+            assert_eq!(response["id"], expected);
+            assert!(response["result"].is_object());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_malformed_line_gets_null_id_error() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        let response: serde_json::Value =
+            serde_json::from_str(&server.handle_line(r#"{"jsonrpc": "2.0", "id": 3, "#).await.unwrap()).unwrap();
+        assert!(response.as_object().unwrap().contains_key("id"));
+        assert_eq!(response["id"], serde_json::Value::Null);
+        assert_eq!(response["error"]["code"], DevManErrorCode::ParseError.code());
+
+        // Well-formed JSON that is not a valid request keeps its id
+        let response: serde_json::Value =
+            serde_json::from_str(&server.handle_line(r#"{"jsonrpc": "1.0", "id": 4, "method": "ping"}"#).await.unwrap())
+                .unwrap();
+        assert_eq!(response["id"], json!(4));
+        assert!(response["error"].is_object());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_method_and_unknown_tool_are_distinct() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        let line = r#"{"jsonrpc": "2.0", "id": 1, "method": "tools/invoke"}"#;
+        let response: serde_json::Value = serde_json::from_str(&server.handle_line(line).await.unwrap()).unwrap();
+        assert_eq!(response["error"]["code"], DevManErrorCode::MethodNotFound.code());
+        assert_eq!(response["error"]["code"], -32601);
+
+        let line = r#"{"jsonrpc": "2.0", "id": 2, "method": "tools/call", "params": {"name": "devman_create_tsak"}}"#;
+        let response: serde_json::Value = serde_json::from_str(&server.handle_line(line).await.unwrap()).unwrap();
+        let error = &response["error"];
+        assert_eq!(error["code"], DevManErrorCode::ToolNotFound.code());
+        assert_ne!(error["code"], DevManErrorCode::MethodNotFound.code());
+        assert_eq!(error["data"]["tool"], "devman_create_tsak");
+        let available: Vec<&str> = error["data"]["available_tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t.as_str().unwrap())
+            .collect();
+        assert_eq!(available.len(), server.tools.len());
+        assert!(available.contains(&"devman_create_task"));
+        assert!(available.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[tokio::test]
+    async fn test_strict_mode_rejects_missing_ai_interface() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server_with = |strict| {
+            McpServer::with_config(McpServerConfig {
+                storage_path: storage_path.clone(),
+                strict,
+                ..Default::default()
+            })
+        };
+        let call = |name: &str| {
+            format!(r#"{{"jsonrpc": "2.0", "id": 1, "method": "tools/call", "params": {{"name": "{}", "arguments": {{"title": "Ship it"}}}}}}"#, name)
+        };
+        async fn respond(server: &McpServer, line: String) -> serde_json::Value {
+            serde_json::from_str(&server.handle_line(&line).await.unwrap()).unwrap()
+        }
+
+        let lenient = server_with(false).await.unwrap();
+        let response = respond(&lenient, call("devman_create_goal")).await;
+        assert!(response["error"].is_null());
+        let text = response["result"]["content"][0]["text"].as_str().unwrap();
+        let body: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert!(body["success"].as_bool().unwrap());
+        assert!(body["data"]["message"].as_str().unwrap().contains("AI interface not configured"));
+
+        let strict = server_with(true).await.unwrap();
+        let response = respond(&strict, call("devman_create_goal")).await;
+        assert_eq!(response["error"]["code"], DevManErrorCode::Internal.code());
+        assert_eq!(response["error"]["data"]["tool"], "devman_create_goal");
+
+        // Tools that never use the AI interface and unknown tools are unaffected
+        assert!(TOOLS_WITHOUT_AI_INTERFACE.iter().all(|t| strict.tools.contains_key(*t)));
+        let response = respond(&strict, call("devman_get_context")).await;
+        assert!(response["error"].is_null());
+        let response = respond(&strict, call("devman_create_tsak")).await;
+        assert_eq!(response["error"]["code"], DevManErrorCode::ToolNotFound.code());
+    }
+
+    #[tokio::test]
+    async fn test_tool_calls_are_audited() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let audit_path = storage_path.join(crate::audit::DEFAULT_AUDIT_FILE);
+        let mut server = McpServer::with_config(McpServerConfig {
+            storage_path: storage_path.clone(),
+            audit_log: Some(audit_path.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        server.set_ai_interface(create_test_server(&storage_path).await.ai_interface.unwrap());
+
+        let calls = [
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "tools/call", "params": {"name": "devman_create_task", "arguments": {"title": "Audited", "api_token": "s3cret"}}}"#,
+            r#"{"jsonrpc": "2.0", "id": 2, "method": "tools/call", "params": {"name": "devman_record_actuals", "arguments": {"task_id": "not-an-id"}}}"#,
+        ];
+        for line in calls {
+            server.handle_line(line).await.unwrap();
+        }
+
+        let entries = crate::audit::read_tail(&audit_path, 10).await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].tool, "devman_create_task");
+        assert_eq!(entries[0].status, AuditStatus::Success);
+        assert_eq!(entries[0].arguments["title"], "Audited");
+        assert_eq!(entries[0].arguments["api_token"], "[REDACTED]");
+        assert_eq!(entries[1].tool, "devman_record_actuals");
+        assert_eq!(entries[1].status, AuditStatus::Error);
+        assert_eq!(entries[1].error_code, Some(DevManErrorCode::InvalidParams.code() as i64));
+        assert!(entries[0].timestamp <= entries[1].timestamp);
+    }
+
     #[test]
     fn test_json_rpc_response_success() {
         let response = JsonRpcResponse::success(Some(serde_json::json!("1")), json!({"status": "ok"}));
@@ -1914,6 +5221,12 @@ mod tests {
             server_name: "custom_devman".to_string(),
             version: "1.0.0".to_string(),
             socket_path: Some("/tmp/custom.sock".into()),
+            idempotency_window: chrono::Duration::hours(1),
+            strict: true,
+            audit_log: Some("/custom/path/audit.jsonl".into()),
+            vector_search: true,
+            shutdown_grace: std::time::Duration::from_secs(1),
+            locale: Locale::En,
         };
         assert_eq!(config.server_name, "custom_devman");
         assert_eq!(config.socket_path, Some(std::path::PathBuf::from("/tmp/custom.sock")));
@@ -2023,6 +5336,66 @@ mod tests {
         assert!(true);
     }
 
+    #[tokio::test]
+    async fn test_job_progress_reported_through_status() {
+        let manager = InMemoryJobManager::new();
+        let job_id = manager
+            .create_job(CreateJobRequest {
+                job_type: JobType::Custom { name: "reindex".to_string(), data: json!({}) },
+                timeout_seconds: Some(600),
+            })
+            .await
+            .unwrap();
+
+        manager.update_progress(&job_id, 40, "Embedded 2 of 5 items".to_string()).await.unwrap();
+        let status = manager.get_job_status(&job_id).await.unwrap();
+        assert_eq!(status.status, "Running");
+        assert_eq!(status.progress, 40);
+        assert_eq!(status.progress_message, "Embedded 2 of 5 items");
+
+        manager.cancel_job(&job_id).await.unwrap();
+        let err = manager.update_progress(&job_id, 60, String::new()).await.unwrap_err();
+        assert_eq!(err.code, DevManErrorCode::StateConflict.code());
+        assert_eq!(manager.get_job_status(&job_id).await.unwrap().progress, 40);
+    }
+
+    #[tokio::test]
+    async fn test_reindex_knowledge_runs_as_job() {
+        use devman_storage::Storage;
+
+        let (_temp_dir, storage_path) = create_test_storage();
+        let mut server = create_test_server(&storage_path).await;
+        let storage = Arc::new(Mutex::new(JsonStorage::new(&storage_path).await.unwrap()));
+        for title in ["Retry flaky requests", "Pin tool versions", "Log request ids"] {
+            storage.lock().await.save_knowledge(&test_knowledge(title, chrono::Utc::now())).await.unwrap();
+        }
+
+        let reindex = server.handle_reindex_knowledge().await;
+        assert_eq!(reindex["error"]["code"], DevManErrorCode::Internal.code());
+
+        let job_manager = Arc::new(InMemoryJobManager::new());
+        server.set_job_manager(job_manager.clone());
+        let config = devman_core::VectorSearchConfig { dimension: 8, ..Default::default() };
+        let provider = Box::new(devman_knowledge::MockEmbeddingProvider::new(8));
+        server.set_vector_service(Arc::new(devman_knowledge::VectorKnowledgeServiceImpl::with_provider(
+            storage.clone(),
+            config,
+            provider,
+        )));
+
+        let reindex = server.handle_reindex_knowledge().await;
+        let job_id = JobId(reindex["data"]["job_id"].as_str().unwrap().to_string());
+        let status = job_manager.wait_for_completion(&job_id, std::time::Duration::from_secs(10)).await.unwrap();
+        assert_eq!(status.status, "Completed");
+        assert_eq!(status.progress, 100);
+        assert_eq!(status.result, Some(json!({"embedded": 3})));
+        assert_eq!(storage.lock().await.list_vector_embeddings().await.unwrap().len(), 3);
+
+        // A finished job cannot finish again
+        let err = job_manager.finish_job(&job_id, Ok(json!({}))).await.unwrap_err();
+        assert_eq!(err.code, DevManErrorCode::StateConflict.code());
+    }
+
     #[test]
     fn test_create_job_request() {
         let request = CreateJobRequest {
@@ -2053,11 +5426,20 @@ mod tests {
 
     /// Helper to create an MCP server with AI interface for testing
     async fn create_test_server(storage_path: &std::path::Path) -> McpServer {
+        create_test_server_sharing_storage(storage_path).await.0
+    }
+
+    /// Test server along with the storage its AI interface uses, so a test
+    /// can hold the storage lock to keep requests in flight
+    async fn create_test_server_sharing_storage(
+        storage_path: &std::path::Path,
+    ) -> (McpServer, Arc<Mutex<devman_storage::JsonStorage>>) {
         let config = McpServerConfig {
             storage_path: storage_path.to_path_buf(),
             server_name: "devman-test".to_string(),
             version: "0.1.0-test".to_string(),
             socket_path: None,
+            ..Default::default()
         };
         let mut server = McpServer::with_config(config).await.unwrap();
 
@@ -2065,7 +5447,17 @@ mod tests {
         let storage = Arc::new(Mutex::new(
             devman_storage::JsonStorage::new(storage_path).await.unwrap()
         ));
+        let ai_interface = create_test_ai_interface(storage_path, storage.clone()).await;
+
+        server.set_ai_interface(Arc::new(ai_interface));
+        (server, storage)
+    }
 
+    /// AI interface over `storage` built from the test implementations
+    async fn create_test_ai_interface(
+        storage_path: &std::path::Path,
+        storage: Arc<Mutex<devman_storage::JsonStorage>>,
+    ) -> BasicAIInterface {
         let work_manager = SimpleWorkManager {
             storage: storage.clone(),
         };
@@ -2084,17 +5476,18 @@ mod tests {
 
         let tool_executor: Arc<dyn devman_tools::ToolExecutor> = Arc::new(SimpleToolExecutor);
 
-        let ai_interface = Arc::new(BasicAIInterface::new(
+        let blocker_detector = devman_progress::BlockerDetector::new(Arc::new(
+            devman_storage::JsonStorage::new(storage_path).await.unwrap(),
+        ));
+        BasicAIInterface::new(
             storage,
             Arc::new(Mutex::new(work_manager)),
             Arc::new(progress_tracker),
             Arc::new(knowledge_service),
             Arc::new(quality_engine),
             tool_executor,
-        ));
-
-        server.set_ai_interface(ai_interface);
-        server
+        )
+        .with_blocker_detector(blocker_detector)
     }
 
     /// Simple work manager for testing
@@ -2120,7 +5513,18 @@ mod tests {
                 phase_id: spec.phase_id,
                 depends_on: Vec::new(),
                 blocks: Vec::new(),
+                parent: None,
+                children: Vec::new(),
+                archived: false,
+                priority: spec.priority.value(),
                 work_records: Vec::new(),
+                actuals: None,
+                claim: None,
+                state: None,
+                merged_into: None,
+                base_priority: None,
+                due_at: spec.due_at,
+                created_knowledge: Vec::new(),
                 created_at: chrono::Utc::now(),
                 updated_at: chrono::Utc::now(),
             };
@@ -2146,11 +5550,15 @@ mod tests {
             Ok(())
         }
 
-        async fn complete_task(&mut self, task_id: devman_core::TaskId, _result: devman_core::WorkResult) -> Result<(), anyhow::Error> {
+        async fn complete_task(&mut self, task_id: devman_core::TaskId, result: devman_core::WorkResult) -> Result<(), anyhow::Error> {
             let mut storage = self.storage.lock().await;
             let mut task = storage.load_task(task_id).await?
                 .ok_or_else(|| anyhow::anyhow!("Task not found"))?;
-            task.status = devman_core::TaskStatus::Done;
+            task.status = if result.status.is_interrupted() {
+                devman_core::TaskStatus::Queued
+            } else {
+                devman_core::TaskStatus::Done
+            };
             task.updated_at = chrono::Utc::now();
             storage.save_task(&task).await?;
             Ok(())
@@ -2216,720 +5624,3947 @@ mod tests {
                 .collect()
         }
 
-        async fn find_similar_tasks(&self, _task: &devman_core::Task) -> Vec<devman_core::Task> {
-            Vec::new()
+        async fn find_similar_tasks(&self, _task: &devman_core::Task) -> Vec<devman_core::Task> {
+            Vec::new()
+        }
+
+        async fn get_best_practices(&self, domain: &str) -> Vec<devman_core::Knowledge> {
+            let storage = self.storage.lock().await;
+            storage.list_knowledge().await.unwrap_or_default()
+                .into_iter()
+                .filter(|k| matches!(k.knowledge_type, devman_core::KnowledgeType::BestPractice { .. }))
+                .filter(|k| k.title.to_lowercase().contains(&domain.to_lowercase()))
+                .take(10)
+                .collect()
+        }
+
+        async fn recommend_knowledge(&self, _context: &devman_core::TaskContext) -> Vec<devman_core::Knowledge> {
+            let storage = self.storage.lock().await;
+            storage.list_knowledge().await.unwrap_or_default()
+        }
+
+        async fn search_by_tags(&self, tags: &[String], limit: usize) -> Vec<devman_core::Knowledge> {
+            let storage = self.storage.lock().await;
+            storage.list_knowledge().await.unwrap_or_default()
+                .into_iter()
+                .filter(|k| tags.iter().any(|t| k.tags.contains(t)))
+                .take(limit)
+                .collect()
+        }
+
+        async fn search_by_tags_all(&self, tags: &[String], limit: usize) -> Vec<devman_core::Knowledge> {
+            self.search_by_tags(tags, limit).await
+        }
+
+        async fn get_all_tags(&self) -> std::collections::HashSet<String> {
+            let storage = self.storage.lock().await;
+            storage.list_knowledge().await.unwrap_or_default()
+                .into_iter()
+                .flat_map(|k| k.tags.into_iter())
+                .collect()
+        }
+
+        async fn get_tag_statistics(&self) -> std::collections::HashMap<String, usize> {
+            let storage = self.storage.lock().await;
+            let mut stats = std::collections::HashMap::new();
+            for knowledge in storage.list_knowledge().await.unwrap_or_default() {
+                for tag in knowledge.tags {
+                    *stats.entry(tag).or_insert(0) += 1;
+                }
+            }
+            stats
+        }
+
+        async fn find_similar_knowledge(&self, knowledge: &devman_core::Knowledge, limit: usize) -> Vec<devman_core::Knowledge> {
+            let storage = self.storage.lock().await;
+            storage.list_knowledge().await.unwrap_or_default()
+                .into_iter()
+                .filter(|k| k.id != knowledge.id && k.tags.iter().any(|t| knowledge.tags.contains(t)))
+                .take(limit)
+                .collect()
+        }
+
+        async fn get_by_type(&self, knowledge_type: devman_core::KnowledgeType) -> Vec<devman_core::Knowledge> {
+            let storage = self.storage.lock().await;
+            storage.list_knowledge().await.unwrap_or_default()
+                .into_iter()
+                .filter(|k| k.knowledge_type == knowledge_type)
+                .collect()
+        }
+
+        async fn suggest_tags(&self, query: &str, limit: usize) -> Vec<String> {
+            let all_tags = self.get_all_tags().await;
+            all_tags.into_iter()
+                .filter(|t| t.to_lowercase().contains(&query.to_lowercase()))
+                .take(limit)
+                .collect()
+        }
+
+        async fn list_recent(&self, limit: usize) -> Vec<devman_core::Knowledge> {
+            let storage = self.storage.lock().await;
+            storage.list_recent_knowledge(limit).await.unwrap_or_default()
+        }
+
+        async fn submit_feedback(
+            &self,
+            id: devman_core::KnowledgeId,
+            feedback: devman_core::Feedback,
+        ) -> anyhow::Result<devman_core::Knowledge> {
+            let mut storage = self.storage.lock().await;
+            let mut knowledge = storage
+                .load_knowledge(id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("Knowledge not found: {}", id))?;
+            knowledge.apply_feedback(feedback);
+            storage.save_knowledge(&knowledge).await?;
+            Ok(knowledge)
+        }
+
+        async fn prune(
+            &self,
+            policy: devman_knowledge::PrunePolicy,
+        ) -> anyhow::Result<devman_knowledge::PruneReport> {
+            let mut storage = self.storage.lock().await;
+            devman_knowledge::prune::prune_storage(&mut *storage, &policy, chrono::Utc::now()).await
+        }
+
+        async fn statistics(&self) -> anyhow::Result<devman_knowledge::KnowledgeStats> {
+            devman_knowledge::stats::compute_statistics(&*self.storage.lock().await).await
+        }
+    }
+
+    /// Simple quality engine for testing; checks whose description contains
+    /// `[fail]` fail, all others pass
+    struct SimpleQualityEngine {
+        storage: Arc<Mutex<dyn devman_storage::Storage>>,
+    }
+
+    #[async_trait::async_trait]
+    impl devman_quality::QualityEngine for SimpleQualityEngine {
+        async fn run_check(&self, check: &devman_core::QualityCheck, _context: &devman_quality::engine::WorkContext) -> devman_core::QualityCheckResult {
+            devman_core::QualityCheckResult {
+                check_id: check.id,
+                passed: !check.description.contains("[fail]"),
+                execution_time: std::time::Duration::ZERO,
+                details: devman_core::CheckDetails {
+                    output: String::new(),
+                    exit_code: None,
+                    error: None,
+                    timeout: None,
+                },
+                findings: Vec::new(),
+                metrics: Vec::new(),
+                human_review: None,
+            }
+        }
+
+        async fn run_checks(&self, checks: &[devman_core::QualityCheck], context: &devman_quality::engine::WorkContext) -> Vec<devman_core::QualityCheckResult> {
+            let mut results = Vec::new();
+            for check in checks {
+                results.push(self.run_check(check, context).await);
+            }
+            results
+        }
+
+        async fn run_gate(
+            &self,
+            gate: &devman_core::QualityGate,
+            context: &devman_quality::engine::WorkContext,
+            cancel: &devman_tools::CancellationToken,
+        ) -> devman_quality::engine::GateResult {
+            let mut check_results = Vec::new();
+            for check_id in &gate.checks {
+                let check = self.storage.lock().await.load_quality_check(*check_id).await;
+                if let Ok(Some(check)) = check {
+                    // A "[slow]" check runs until it is cancelled
+                    if check.description.contains("[slow]") {
+                        cancel.cancelled().await;
+                        let mut result = self.run_check(&check, context).await;
+                        result.passed = false;
+                        result.details.error = Some("Cancelled".to_string());
+                        check_results.push(result);
+                        return devman_quality::engine::GateResult {
+                            gate_name: gate.name.clone(),
+                            passed: false,
+                            check_results,
+                            decision: devman_quality::engine::GateDecision::Cancelled,
+                        };
+                    }
+                    check_results.push(self.run_check(&check, context).await);
+                }
+            }
+            devman_quality::engine::GateResult {
+                gate_name: gate.name.clone(),
+                passed: true,
+                check_results,
+                decision: devman_quality::engine::GateDecision::Pass,
+            }
+        }
+
+        async fn goal_quality_summary(
+            &self,
+            goal_id: devman_core::GoalId,
+        ) -> Result<devman_quality::GoalQualitySummary, devman_storage::StorageError> {
+            devman_quality::goal_quality_summary(&*self.storage.lock().await, goal_id).await
+        }
+    }
+
+    /// Simple tool executor for testing
+    struct SimpleToolExecutor;
+
+    #[async_trait::async_trait]
+    impl devman_tools::ToolExecutor for SimpleToolExecutor {
+        async fn execute_tool(
+            &self,
+            tool: &str,
+            _input: devman_tools::ToolInput,
+            _cancel: &devman_tools::CancellationToken,
+        ) -> Result<devman_tools::ToolOutput, anyhow::Error> {
+            if tool == "missing" {
+                anyhow::bail!("Failed to spawn {}", tool);
+            }
+            Ok(devman_tools::ToolOutput {
+                // Like the shell builtin, `false` always fails
+                exit_code: if tool == "false" { 1 } else { 0 },
+                stdout: "Test tool execution".to_string(),
+                stderr: String::new(),
+                duration: std::time::Duration::ZERO,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_e2e_create_and_list_task() {
+        // Create test storage and server
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        // Verify server has tools registered
+        assert!(server.tools.contains_key("devman_create_task"));
+        assert!(server.tools.contains_key("devman_list_tasks"));
+
+        // Get the tool handlers
+        let _create_task_handler = server.tools.get("devman_create_task").unwrap();
+        let _list_tasks_handler = server.tools.get("devman_list_tasks").unwrap();
+
+        // Test creating a task
+        let create_args = json!({
+            "title": "E2E Test Task",
+            "description": "This is a test task created by E2E test"
+        });
+
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+        let create_result = server.handle_create_task(ai_interface, &create_args).await;
+
+        assert!(create_result["success"].as_bool().unwrap());
+        let task_id = create_result["data"]["task_id"].as_str().unwrap();
+        assert!(!task_id.is_empty());
+        assert_eq!(create_result["data"]["title"], "E2E Test Task");
+
+        // Test listing tasks
+        let list_args = json!({});
+        let list_result = server.handle_list_tasks(ai_interface, &list_args).await;
+
+        assert!(list_result["success"].as_bool().unwrap());
+        let tasks = list_result["data"]["tasks"].as_array().unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0]["title"], "E2E Test Task");
+    }
+
+    #[tokio::test]
+    async fn test_e2e_task_workflow() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        // 1. Create a task
+        let create_args = json!({
+            "title": "Workflow Test Task",
+            "description": "Testing complete task workflow"
+        });
+
+        let create_result = server.handle_create_task(ai_interface, &create_args).await;
+        assert!(create_result["success"].as_bool().unwrap());
+        let task_id = create_result["data"]["task_id"].as_str().unwrap().to_string();
+
+        // 2. List tasks and verify it's there
+        let list_args = json!({});
+        let list_result = server.handle_list_tasks(ai_interface, &list_args).await;
+        let tasks = list_result["data"]["tasks"].as_array().unwrap();
+        assert_eq!(tasks.len(), 1);
+
+        // 3. Get task progress using handle_get_task (existing method)
+        let task_result = ai_interface.get_task(task_id.parse().unwrap()).await;
+        assert!(task_result.is_some());
+        let task = task_result.unwrap();
+        assert_eq!(task.title, "Workflow Test Task");
+
+        // 4. Search knowledge (should be empty initially)
+        let search_args = json!({ "query": "test" });
+        let search_result = server.handle_search_knowledge(ai_interface, &search_args).await;
+        assert!(search_result["success"].as_bool().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_e2e_create_multiple_tasks() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        // Create multiple tasks
+        for i in 1..=5 {
+            let args = json!({
+                "title": format!("Task #{}", i),
+                "description": format!("Description for task {}", i)
+            });
+
+            let result = server.handle_create_task(ai_interface, &args).await;
+            assert!(result["success"].as_bool().unwrap(), "Failed to create task #{}", i);
+        }
+
+        // List all tasks
+        let list_result = server.handle_list_tasks(ai_interface, &json!({})).await;
+        let tasks = list_result["data"]["tasks"].as_array().unwrap();
+        assert_eq!(tasks.len(), 5);
+
+        // Filter by status
+        let filter_result = server.handle_list_tasks(ai_interface, &json!({ "state": "Queued" })).await;
+        let filtered_tasks = filter_result["data"]["tasks"].as_array().unwrap();
+        assert_eq!(filtered_tasks.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_e2e_create_task_with_phase() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        // Create task with phase_id
+        let args = json!({
+            "title": "Task with Phase",
+            "description": "Task associated with a phase",
+            "phase_id": "01JHA1V2B3C4D5E6F7G8H9J0K"
+        });
+
+        let result = server.handle_create_task(ai_interface, &args).await;
+        assert!(result["success"].as_bool().unwrap());
+        assert_eq!(result["data"]["title"], "Task with Phase");
+    }
+
+    #[tokio::test]
+    async fn test_e2e_search_knowledge() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        // Search for non-existent knowledge (should return empty array)
+        let args = json!({
+            "query": "nonexistent query",
+            "limit": 10
+        });
+
+        let result = server.handle_search_knowledge(ai_interface, &args).await;
+        assert!(result["success"].as_bool().unwrap());
+        let results = result["data"]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_e2e_get_goal_progress_no_goal() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        // Get progress for non-existent goal
+        let args = json!({
+            "goal_id": "01JHB0V2B3C4D5E6F7G8H9J0K"
+        });
+
+        let result = server.handle_get_goal_progress(ai_interface, &args).await;
+        // Should return error for non-existent goal
+        assert!(!result["success"].as_bool().unwrap());
+    }
+
+    // ==================== Task State Machine E2E Tests ====================
+
+    #[tokio::test]
+    async fn test_e2e_task_state_machine_full_workflow() {
+        // Test the complete task state machine: Created -> ContextRead -> KnowledgeReviewed -> InProgress -> WorkRecorded -> QualityChecking -> QualityCompleted -> Completed
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        // 1. Create task (state: Queued/Created)
+        let create_args = json!({
+            "title": "State Machine Test Task",
+            "description": "Testing complete task state machine workflow"
+        });
+
+        let create_result = server.handle_create_task(ai_interface, &create_args).await;
+        assert!(create_result["success"].as_bool().unwrap());
+        let task_id = create_result["data"]["task_id"].as_str().unwrap().to_string();
+
+        // Verify initial state
+        let task = ai_interface.get_task(task_id.parse().unwrap()).await.unwrap();
+        assert_eq!(task.status, devman_core::TaskStatus::Queued);
+
+        // 2. Start task execution (state: Active)
+        // Note: handle_start_execution is a placeholder - it returns success but doesn't update state
+        let start_args = json!({ "task_id": task_id });
+        let start_result = server.handle_start_execution(&start_args).await;
+        assert!(start_result["success"].as_bool().unwrap());
+
+        // 3. Log work (state remains Active, adds work record)
+        let log_args = json!({
+            "task_id": task_id,
+            "action": "modified",
+            "description": "Implemented core functionality",
+            "files": ["src/lib.rs", "src/main.rs"]
+        });
+        let log_result = server.handle_log_work(&log_args).await;
+        assert!(log_result["success"].as_bool().unwrap());
+        assert!(log_result["data"]["timestamp"].is_string());
+
+        // The event lands on a work record opened for the task
+        let task = ai_interface.get_task(task_id.parse().unwrap()).await.unwrap();
+        assert_eq!(task.work_records.len(), 1);
+
+        // 4. Finish work (state: WorkRecorded equivalent)
+        let finish_args = json!({
+            "task_id": task_id,
+            "description": "Completed implementation of core features",
+            "artifacts": [
+                { "name": "lib.rs", "type": "code", "path": "src/lib.rs" }
+            ]
+        });
+        let finish_result = server.handle_finish_work(&finish_args).await;
+        assert!(finish_result["success"].as_bool().unwrap());
+
+        // 5. Run quality check
+        let quality_args = json!({
+            "task_id": task_id,
+            "check_types": ["compile", "test"]
+        });
+        let quality_result = server.handle_run_task_quality_check(ai_interface, &quality_args).await;
+        assert!(quality_result["success"].as_bool().unwrap());
+
+        // 6. Confirm quality result and complete task
+        // Note: This would require actual quality check implementation
+        // For now, test that the workflow doesn't error
+    }
+
+    #[tokio::test]
+    async fn test_work_event_after_finished_record_opens_new_record() {
+        use devman_core::{CompletionStatus, WorkEvent, WorkEventType};
+        use devman_storage::Storage;
+
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        let created = server.handle_create_task(ai_interface, &json!({"title": "Rework parser"})).await;
+        let task_id: devman_core::TaskId = created["data"]["task_id"].as_str().unwrap().parse().unwrap();
+        let step = |description: &str| {
+            WorkEvent::new(WorkEventType::StepCompleted, description, json!({}), chrono::Utc::now())
+        };
+
+        let finished = [
+            CompletionStatus::Success,
+            CompletionStatus::Interrupted { at: chrono::Utc::now(), reason: "Crashed".to_string() },
+        ];
+        for (i, status) in finished.into_iter().enumerate() {
+            ai_interface.record_work_event(task_id, step("Before")).await.unwrap();
+            let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+            let record_id = *storage.require_task(task_id).await.unwrap().work_records.last().unwrap();
+            let mut record = storage.load_work_record(record_id).await.unwrap().unwrap();
+            record.result.status = status;
+            storage.save_work_record(&record).await.unwrap();
+
+            ai_interface.record_work_event(task_id, step("After")).await.unwrap();
+            let task = storage.require_task(task_id).await.unwrap();
+            assert_eq!(task.work_records.len(), i + 2);
+            let closed = storage.load_work_record(record_id).await.unwrap().unwrap();
+            assert_eq!(closed.events.len(), record.events.len());
+            let latest = storage.load_work_record(*task.work_records.last().unwrap()).await.unwrap().unwrap();
+            assert_eq!(latest.result.status, CompletionStatus::Running);
+            assert_eq!(latest.events.len(), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_e2e_task_timeline() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        let create_args = json!({
+            "title": "Timeline Test Task",
+            "description": "Testing the task timeline"
+        });
+        let create_result = server.handle_create_task(ai_interface, &create_args).await;
+        let task_id_str = create_result["data"]["task_id"].as_str().unwrap().to_string();
+        let task_id: devman_core::TaskId = task_id_str.parse().unwrap();
+
+        let log_args = json!({
+            "task_id": task_id_str,
+            "action": "modified",
+            "description": "Implemented feature",
+            "files": ["src/lib.rs"]
+        });
+        assert!(server.handle_log_work(&log_args).await["success"].as_bool().unwrap());
+
+        ai_interface
+            .record_work_event(
+                task_id,
+                devman_core::WorkEvent::new(
+                    devman_core::WorkEventType::QualityCheckPassed,
+                    "cargo test",
+                    json!({}),
+                    chrono::Utc::now(),
+                ),
+            )
+            .await
+            .unwrap();
+
+        ai_interface
+            .complete_task(task_id, devman_core::WorkResult {
+                status: devman_core::CompletionStatus::Success,
+                outputs: Vec::new(),
+                metrics: devman_core::WorkMetrics {
+                    token_used: None,
+                    time_spent: std::time::Duration::ZERO,
+                    tools_invoked: 0,
+                    quality_checks_run: 1,
+                    quality_checks_passed: 1,
+                },
+                created_knowledge: Vec::new(),
+            })
+            .await
+            .unwrap();
+
+        let result = server
+            .handle_get_task_timeline(ai_interface, &json!({ "task_id": task_id_str }))
+            .await;
+        assert!(result["success"].as_bool().unwrap());
+
+        let entries = result["data"]["entries"].as_array().unwrap();
+        let kinds: Vec<&str> = entries.iter().map(|e| e["kind"].as_str().unwrap()).collect();
+        assert_eq!(kinds, vec!["state_transition", "work_log", "quality_check", "state_transition"]);
+        assert_eq!(entries[0]["action"], "task_created");
+        assert_eq!(entries[1]["description"], "Implemented feature");
+        assert_eq!(entries[2]["passed"], true);
+        assert_eq!(entries[3]["action"], "task_completed");
+    }
+
+    fn success_result() -> devman_core::WorkResult {
+        devman_core::WorkResult {
+            status: devman_core::CompletionStatus::Success,
+            outputs: Vec::new(),
+            metrics: devman_core::WorkMetrics {
+                token_used: None,
+                time_spent: std::time::Duration::ZERO,
+                tools_invoked: 0,
+                quality_checks_run: 0,
+                quality_checks_passed: 0,
+            },
+            created_knowledge: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_completion_captures_actuals_for_estimates() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        let mut ids = Vec::new();
+        for title in ["Measured", "Overridden", "Next"] {
+            let result = server.handle_create_task(ai_interface, &json!({ "title": title })).await;
+            ids.push(result["data"]["task_id"].as_str().unwrap().parse::<devman_core::TaskId>().unwrap());
+        }
+        let with_metrics = |minutes: u64, tokens: usize| {
+            let mut result = success_result();
+            result.metrics.time_spent = std::time::Duration::from_secs(minutes * 60);
+            result.metrics.token_used = Some(tokens);
+            result
+        };
+
+        ai_interface.complete_task(ids[0], with_metrics(40, 3000)).await.unwrap();
+        let measured = ai_interface.get_task(ids[0]).await.unwrap();
+        let actuals = measured.actuals.clone().unwrap();
+        assert_eq!(actuals.duration, std::time::Duration::from_secs(40 * 60));
+        assert_eq!(actuals.tokens, Some(3000));
+        assert_eq!(actuals.source, devman_core::ActualsSource::Metrics);
+
+        // Manual actuals win over the metrics captured on completion
+        let result = server
+            .handle_record_actuals(ai_interface, &json!({"task_id": ids[1].to_string(), "duration_minutes": 20}))
+            .await;
+        assert_eq!(result["data"]["actuals"]["source"], "manual");
+        ai_interface.complete_task(ids[1], with_metrics(90, 100)).await.unwrap();
+        let overridden = ai_interface.get_task(ids[1]).await.unwrap();
+        let actuals = overridden.actuals.clone().unwrap();
+        assert_eq!(actuals.duration, std::time::Duration::from_secs(20 * 60));
+        assert_eq!(actuals.source, devman_core::ActualsSource::Manual);
+
+        let next = ai_interface.get_task(ids[2]).await.unwrap();
+        let estimate = devman_progress::CompletionEstimator::default()
+            .estimate_from_history(&next, &[measured, overridden]);
+        assert_eq!(estimate.duration_minutes, 30);
+        assert!(estimate.factors.contains(&"History: 2 similar task(s)".to_string()));
+
+        // Cost estimates are priced with the stored price table
+        {
+            use devman_storage::ConfigStore;
+            let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+            let prices = devman_progress::PriceTable { model: "premium".to_string(), usd_per_million_tokens: 60.0 };
+            storage.set_config(devman_storage::config::keys::PROGRESS_PRICE_TABLE, &prices).await.unwrap();
+        }
+        let cost = ai_interface.estimate_task_cost(ids[2]).await.unwrap();
+        assert!(cost.tokens > 0);
+        assert_eq!(cost.usd, cost.tokens as f64 * 60.0 / 1_000_000.0);
+
+        let missing = server
+            .handle_record_actuals(
+                ai_interface,
+                &json!({"task_id": devman_core::TaskId::new().to_string(), "duration_minutes": 5}),
+            )
+            .await;
+        assert_eq!(missing["error"]["code"], DevManErrorCode::NotFound.code());
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_completion_requeues_without_actuals() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        let result = server.handle_create_task(ai_interface, &json!({ "title": "Cut short" })).await;
+        let task_id: devman_core::TaskId = result["data"]["task_id"].as_str().unwrap().parse().unwrap();
+
+        let mut interrupted = success_result();
+        interrupted.status = devman_core::CompletionStatus::Interrupted {
+            at: chrono::Utc::now(),
+            reason: "agent crashed".to_string(),
+        };
+        interrupted.metrics.time_spent = std::time::Duration::from_secs(5 * 60);
+        interrupted.metrics.token_used = Some(200);
+        ai_interface.complete_task(task_id, interrupted).await.unwrap();
+
+        // Requeued, and the partial metrics don't feed the estimates
+        let task = ai_interface.get_task(task_id).await.unwrap();
+        assert_eq!(task.status, devman_core::TaskStatus::Queued);
+        assert!(task.actuals.is_none());
+
+        let timeline = server
+            .handle_get_task_timeline(ai_interface, &json!({ "task_id": task_id.to_string() }))
+            .await;
+        let entries = timeline["data"]["entries"].as_array().unwrap();
+        let last = entries.last().unwrap();
+        assert_eq!(last["action"], "task_interrupted");
+    }
+
+    #[tokio::test]
+    async fn test_quality_result_recommends_decision_from_findings() {
+        use devman_storage::Storage;
+        let (_temp_dir, storage_path) = create_test_storage();
+        let (server, storage) = create_test_server_sharing_storage(&storage_path).await;
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        let result = server.handle_create_task(ai_interface, &json!({ "title": "Checked" })).await;
+        let task_id_str = result["data"]["task_id"].as_str().unwrap().to_string();
+        let task_id: devman_core::TaskId = task_id_str.parse().unwrap();
+
+        let check = |passed: bool, category: devman_core::QualityCategory, message: &str| devman_core::QualityCheckResult {
+            check_id: devman_core::QualityCheckId::new(),
+            passed,
+            execution_time: std::time::Duration::ZERO,
+            details: devman_core::CheckDetails { output: String::new(), exit_code: None, error: None, timeout: None },
+            findings: vec![devman_core::Finding {
+                severity: devman_core::Severity::Error,
+                category,
+                message: message.to_string(),
+                location: None,
+                suggestion: None,
+            }],
+            metrics: vec![],
+            human_review: None,
+        };
+        let args = json!({ "check_id": "check_1", "task_id": task_id_str });
+
+        let missing = server.handle_get_quality_result(&args).await;
+        assert_eq!(missing["error"]["code"], DevManErrorCode::NotFound.code());
+
+        let earlier = chrono::Utc::now() - chrono::Duration::minutes(5);
+        storage
+            .lock()
+            .await
+            .save_quality_run(&devman_core::QualityRun::new(
+                task_id,
+                vec![check(false, devman_core::QualityCategory::Correctness, "Design flaw: state is shared across requests")],
+                earlier,
+            ))
+            .await
+            .unwrap();
+        storage
+            .lock()
+            .await
+            .save_quality_run(&devman_core::QualityRun::new(
+                task_id,
+                vec![
+                    check(false, devman_core::QualityCategory::Correctness, "error[E0308]: mismatched types"),
+                    check(true, devman_core::QualityCategory::Documentation, "missing docs"),
+                ],
+                chrono::Utc::now(),
+            ))
+            .await
+            .unwrap();
+
+        // Only the latest run counts, and its compile error is fixable
+        let result = server.handle_get_quality_result(&args).await;
+        assert!(result["success"].as_bool().unwrap(), "{}", result);
+        let data = &result["data"];
+        assert_eq!(data["overall_status"], "failed");
+        assert_eq!(data["recommended_decision"], "fix_and_continue");
+        assert_eq!(data["failure_kinds"], json!({ "compile": 1 }));
+        assert_eq!(data["findings_count"], 1);
+
+        let invalid = server.handle_get_quality_result(&json!({ "check_id": "x", "task_id": "nope" })).await;
+        assert_eq!(invalid["error"]["code"], DevManErrorCode::InvalidParams.code());
+    }
+
+    #[tokio::test]
+    async fn test_e2e_subtasks() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        let create_result = server
+            .handle_create_task(ai_interface, &json!({ "title": "Parent Task" }))
+            .await;
+        let parent_id_str = create_result["data"]["task_id"].as_str().unwrap().to_string();
+        let parent_id: devman_core::TaskId = parent_id_str.parse().unwrap();
+
+        let mut child_ids = Vec::new();
+        for title in ["Child A", "Child B"] {
+            let result = server
+                .handle_create_subtask(ai_interface, &json!({ "parent_id": parent_id_str, "title": title }))
+                .await;
+            assert!(result["success"].as_bool().unwrap());
+            let child_id: devman_core::TaskId = result["data"]["task_id"].as_str().unwrap().parse().unwrap();
+            child_ids.push(child_id);
+        }
+
+        let parent = ai_interface.get_task(parent_id).await.unwrap();
+        assert_eq!(parent.children, child_ids);
+        assert_eq!(parent.progress.percentage, 0.0);
+        let child = ai_interface.get_task(child_ids[0]).await.unwrap();
+        assert_eq!(child.parent, Some(parent_id));
+        assert_eq!(child.phase_id, parent.phase_id);
+
+        // Parent can't be completed while children are open
+        assert!(ai_interface.complete_task(parent_id, success_result()).await.is_err());
+
+        ai_interface.complete_task(child_ids[0], success_result()).await.unwrap();
+        let parent = ai_interface.get_task(parent_id).await.unwrap();
+        assert_eq!(parent.progress.percentage, 50.0);
+        assert!(ai_interface.complete_task(parent_id, success_result()).await.is_err());
+
+        ai_interface.complete_task(child_ids[1], success_result()).await.unwrap();
+        let parent = ai_interface.get_task(parent_id).await.unwrap();
+        assert_eq!(parent.progress.percentage, 100.0);
+
+        ai_interface.complete_task(parent_id, success_result()).await.unwrap();
+        let parent = ai_interface.get_task(parent_id).await.unwrap();
+        assert_eq!(parent.status, devman_core::TaskStatus::Done);
+    }
+
+    /// Server whose AI interface runs the dependency cascade when `cascade`
+    /// is set, with tasks of the given titles; each listed dependency is
+    /// saved and the dependent marked `Blocked`
+    async fn create_cascade_server(
+        storage_path: &std::path::Path,
+        cascade: bool,
+        titles: &[&str],
+        depends: &[(&str, &[&str])],
+    ) -> (McpServer, HashMap<String, devman_core::TaskId>) {
+        use devman_storage::Storage;
+
+        let (mut server, storage) = create_test_server_sharing_storage(storage_path).await;
+        let cascade = create_test_ai_interface(storage_path, storage.clone()).await.with_dependency_cascade(cascade);
+        server.set_ai_interface(Arc::new(cascade));
+        let ai_interface = server.ai_interface.clone().unwrap();
+
+        let mut ids = HashMap::new();
+        for title in titles {
+            let result = server.handle_create_task(&ai_interface, &json!({ "title": title })).await;
+            ids.insert(title.to_string(), result["data"]["task_id"].as_str().unwrap().parse().unwrap());
+        }
+        let mut storage = storage.lock().await;
+        for (dependent, dependencies) in depends {
+            let mut task = storage.load_task(ids[*dependent]).await.unwrap().unwrap();
+            task.depends_on = dependencies.iter().map(|d| ids[*d]).collect();
+            task.status = devman_core::TaskStatus::Blocked;
+            storage.save_task(&task).await.unwrap();
+        }
+        drop(storage);
+        (server, ids)
+    }
+
+    #[tokio::test]
+    async fn test_completion_cascade_requeues_unblocked_dependents() {
+        use devman_core::TaskStatus;
+
+        let (_temp_dir, storage_path) = create_test_storage();
+        let (server, ids) = create_cascade_server(
+            &storage_path,
+            true,
+            &["A", "B", "C", "D"],
+            &[("B", &["A"]), ("D", &["A", "C"])],
+        )
+        .await;
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        let result = server
+            .handle_complete_task(&json!({ "task_id": ids["A"].to_string() }))
+            .await;
+        assert!(result["success"].as_bool().unwrap(), "{}", result);
+        assert_eq!(result["data"]["requeued_tasks"], json!([ids["B"].to_string()]));
+        assert_eq!(result["data"]["warnings"], json!([]));
+        assert_eq!(ai_interface.get_task(ids["B"]).await.unwrap().status, TaskStatus::Queued);
+        // D still waits on C
+        assert_eq!(ai_interface.get_task(ids["D"]).await.unwrap().status, TaskStatus::Blocked);
+
+        let report = ai_interface.complete_task(ids["C"], success_result()).await.unwrap();
+        assert_eq!(report.requeued, vec![ids["D"]]);
+
+        // Without the cascade dependents are left alone
+        let (_temp_dir, storage_path) = create_test_storage();
+        let (server, ids) = create_cascade_server(&storage_path, false, &["A", "B"], &[("B", &["A"])]).await;
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+        let report = ai_interface.complete_task(ids["A"], success_result()).await.unwrap();
+        assert!(report.requeued.is_empty());
+        assert_eq!(ai_interface.get_task(ids["B"]).await.unwrap().status, TaskStatus::Blocked);
+    }
+
+    #[tokio::test]
+    async fn test_abandon_cascade_unblocks_or_flags_dependents() {
+        use devman_core::{AbandonReason, TaskState, TaskStatus};
+
+        let (_temp_dir, storage_path) = create_test_storage();
+        let (server, ids) = create_cascade_server(
+            &storage_path,
+            true,
+            &["Spike", "Build", "Legacy", "Port"],
+            &[("Build", &["Spike"]), ("Port", &["Legacy"])],
+        )
+        .await;
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        // A task that may be picked up again keeps its dependents waiting
+        let report = ai_interface
+            .abandon_task(ids["Spike"], AbandonReason::Voluntary { reason: "Out of time".to_string(), can_be_reassigned: true })
+            .await
+            .unwrap();
+        assert!(report.requeued.is_empty());
+        assert_eq!(
+            report.warnings,
+            vec!["'Build' still waits on abandoned task 'Spike', which may be picked up again"]
+        );
+        assert_eq!(ai_interface.get_task(ids["Build"]).await.unwrap().status, TaskStatus::Blocked);
+        let spike = ai_interface.get_task(ids["Spike"]).await.unwrap();
+        assert_eq!(spike.status, TaskStatus::Abandoned);
+        assert!(matches!(spike.state, Some(TaskState::Abandoned { .. })));
+
+        // One abandoned for good releases them, with a warning
+        let result = server
+            .handle_abandon_task(&json!({
+                "task_id": ids["Legacy"].to_string(),
+                "reason_type": "technical_limitation",
+                "reason": "The old API is gone"
+            }))
+            .await;
+        assert!(result["success"].as_bool().unwrap(), "{}", result);
+        assert_eq!(result["data"]["can_be_reassigned"], false);
+        assert_eq!(result["data"]["requeued_tasks"], json!([ids["Port"].to_string()]));
+        assert_eq!(
+            result["data"]["warnings"],
+            json!(["Requeued 'Port' although its dependency 'Legacy' was abandoned"])
+        );
+        assert_eq!(ai_interface.get_task(ids["Port"]).await.unwrap().status, TaskStatus::Queued);
+
+        // Closed tasks cannot be abandoned again
+        assert!(ai_interface
+            .abandon_task(ids["Legacy"], AbandonReason::Other { reason: "Again".to_string(), details: None })
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_e2e_create_subtask_unknown_parent() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        let parent_id = devman_core::TaskId::new().to_string();
+        let args = json!({
+            "parent_id": parent_id,
+            "title": "Orphan"
+        });
+        let result = server.handle_create_subtask(ai_interface, &args).await;
+        assert!(!result["success"].as_bool().unwrap());
+        assert_eq!(result["error"]["code"], DevManErrorCode::NotFound.code());
+        assert_eq!(result["error"]["data"]["entity_type"], "task");
+        assert_eq!(result["error"]["data"]["id"], parent_id);
+    }
+
+    #[tokio::test]
+    async fn test_e2e_create_subtask_refuses_closed_parent() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        let created = server.handle_create_task(ai_interface, &json!({ "title": "Retired" })).await;
+        let parent_id: devman_core::TaskId = created["data"]["task_id"].as_str().unwrap().parse().unwrap();
+        let reason = devman_core::AbandonReason::Other { reason: "Dropped".to_string(), details: None };
+        ai_interface.abandon_task(parent_id, reason).await.unwrap();
+
+        let args = json!({ "parent_id": parent_id.to_string(), "title": "Follow-up" });
+        let result = server.handle_create_subtask(ai_interface, &args).await;
+        assert!(!result["success"].as_bool().unwrap());
+        assert!(ai_interface.get_task(parent_id).await.unwrap().children.is_empty());
+        assert_eq!(ai_interface.list_tasks(TaskFilter::default()).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_e2e_task_pause_and_resume() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        // Create and start task
+        let create_args = json!({
+            "title": "Pause/Resume Test",
+            "description": "Testing pause and resume functionality"
+        });
+
+        let create_result = server.handle_create_task(ai_interface, &create_args).await;
+        let task_id = create_result["data"]["task_id"].as_str().unwrap().to_string();
+
+        // Start task execution (placeholder - doesn't actually update state)
+        let start_args = json!({ "task_id": task_id });
+        server.handle_start_execution(&start_args).await;
+
+        // Verify task was created
+        let task = ai_interface.get_task(task_id.parse().unwrap()).await.unwrap();
+        assert_eq!(task.status, devman_core::TaskStatus::Queued);
+
+        // Pause task
+        let pause_args = json!({
+            "task_id": task_id,
+            "reason": "Waiting for dependency review"
+        });
+        let pause_result = server.handle_pause_task(&pause_args).await;
+        assert!(pause_result["success"].as_bool().unwrap());
+
+        // Resume task
+        let resume_args = json!({ "task_id": task_id });
+        let resume_result = server.handle_resume_task(&resume_args).await;
+        assert!(resume_result["success"].as_bool().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_e2e_task_abandon() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        // Create task
+        let create_args = json!({
+            "title": "Abandon Test Task",
+            "description": "Task to be abandoned"
+        });
+
+        let create_result = server.handle_create_task(ai_interface, &create_args).await;
+        let task_id = create_result["data"]["task_id"].as_str().unwrap().to_string();
+
+        // Work is running on it when it is abandoned
+        use devman_storage::Storage;
+        let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+        let mut task = storage.require_task(task_id.parse().unwrap()).await.unwrap();
+        let record = devman_core::WorkRecord::new(task.id, devman_core::Executor::AI { model: "test".to_string() }, chrono::Utc::now());
+        storage.save_work_record(&record).await.unwrap();
+        task.work_records.push(record.id);
+        task.status = devman_core::TaskStatus::Active;
+        storage.save_task(&task).await.unwrap();
+
+        // Abandon with different reason types
+        let abandon_args = json!({
+            "task_id": task_id,
+            "reason_type": "requirement_changed",
+            "reason": "Requirements have changed, this task is no longer needed"
+        });
+        let abandon_result = server.handle_abandon_task(&abandon_args).await;
+        assert!(abandon_result["success"].as_bool().unwrap());
+
+        let record = storage.load_work_record(record.id).await.unwrap().unwrap();
+        assert!(record.result.status.is_interrupted());
+        assert!(record.completed_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_e2e_goal_creation_and_progress() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        // Create goal
+        let goal_args = json!({
+            "title": "E2E Test Goal",
+            "description": "A goal created by E2E test",
+            "success_criteria": [
+                "Complete all E2E tests",
+                "Verify task state machine",
+                "Verify knowledge management"
+            ]
+        });
+
+        let goal_result = server.handle_create_goal(ai_interface, &goal_args).await;
+        assert!(goal_result["success"].as_bool().unwrap());
+        let goal_id = goal_result["data"]["goal_id"].as_str().unwrap().to_string();
+
+        // Verify goal was created
+        let goal = ai_interface.get_goal(goal_id.parse().unwrap()).await.unwrap();
+        assert_eq!(goal.title, "E2E Test Goal");
+        assert_eq!(goal.success_criteria.len(), 3);
+
+        // Get goal progress
+        let progress_args = json!({ "goal_id": goal_id });
+        let progress_result = server.handle_get_goal_progress(ai_interface, &progress_args).await;
+        assert!(progress_result["success"].as_bool().unwrap());
+        assert_eq!(progress_result["data"]["goal_id"], goal_id);
+
+        // List goals
+        use crate::GoalFilter;
+        let goals = ai_interface.list_goals(GoalFilter::default()).await;
+        assert!(goals.len() >= 1);
+    }
+
+    #[tokio::test]
+    async fn test_e2e_knowledge_save_and_retrieve() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        // Save knowledge
+        let save_args = json!({
+            "title": "E2E Test Best Practice",
+            "knowledge_type": "BestPractice",
+            "content": "Always write tests before implementing features. This ensures code quality and prevents regressions.",
+            "tags": ["testing", "best-practice", "e2e"]
+        });
+
+        let save_result = server.handle_save_knowledge(ai_interface, &save_args).await;
+        assert!(save_result["success"].as_bool().unwrap());
+        // Note: handle_save_knowledge is a placeholder, knowledge_id may not be returned
+
+        // Search for the saved knowledge (placeholder returns empty results)
+        let search_args = json!({ "query": "best practice", "limit": 10 });
+        let search_result = server.handle_search_knowledge(ai_interface, &search_args).await;
+        assert!(search_result["success"].as_bool().unwrap());
+
+        // Placeholder returns empty results
+        let results = search_result["data"]["results"].as_array().unwrap();
+        assert!(results.len() >= 0);
+
+        // Filter by type (placeholder returns empty results)
+        let type_args = json!({ "query": "testing" });
+        let type_result = server.handle_search_knowledge(ai_interface, &type_args).await;
+        let type_results = type_result["data"]["results"].as_array().unwrap();
+        assert!(type_results.len() >= 0);  // Placeholder returns empty results
+    }
+
+    #[tokio::test]
+    async fn test_e2e_tool_execution() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        // Execute cargo tool (should work with the builtin executor)
+        let cargo_args = json!({
+            "tool": "cargo",
+            "command": "version",
+            "args": ["--version"]
+        });
+
+        let cargo_result = server.handle_execute_tool(ai_interface, &cargo_args).await;
+        // Result depends on whether cargo is available
+        // We just verify the tool was executed (not an error response)
+        assert!(cargo_result["success"].as_bool().unwrap() || cargo_result["error"].is_object());
+
+        // Execute git tool
+        let git_args = json!({
+            "tool": "git",
+            "command": "status",
+            "args": ["--version"]
+        });
+
+        let git_result = server.handle_execute_tool(ai_interface, &git_args).await;
+        assert!(git_result["success"].as_bool().unwrap() || git_result["error"].is_object());
+
+        // Test unknown tool
+        let unknown_args = json!({
+            "tool": "unknown_tool",
+            "command": "test"
+        });
+
+        // Note: handle_execute_tool is a placeholder - it always returns success
+        // In a real implementation, unknown tools would fail
+        let unknown_result = server.handle_execute_tool(ai_interface, &unknown_args).await;
+        // The placeholder always returns success
+        assert!(unknown_result["success"].as_bool().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_e2e_quality_check_flow() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        // Create a task first
+        let create_args = json!({
+            "title": "Quality Check Test",
+            "description": "Task to run quality checks on"
+        });
+
+        let create_result = server.handle_create_task(ai_interface, &create_args).await;
+        let task_id_opt = create_result["data"]["task_id"].as_str().map(|s| s.to_string());
+
+        // Run quality check on task
+        let quality_args = json!({
+            "task_id": task_id_opt.as_ref().map(|s| s.as_str()).unwrap_or(""),
+            "check_types": ["compile"]
+        });
+
+        let quality_result = server.handle_run_task_quality_check(ai_interface, &quality_args).await;
+        assert!(quality_result["success"].as_bool().unwrap());
+
+        // Run standalone quality check
+        let standalone_args = json!({
+            "check_type": "compile",
+            "target": "."
+        });
+
+        let standalone_result = server.handle_run_quality_check(ai_interface, &standalone_args).await;
+        // Result depends on project state
+        assert!(standalone_result.is_object());
+    }
+
+    #[tokio::test]
+    async fn test_e2e_list_blockers() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        // List blockers (should return empty initially)
+        let blockers_result = server.handle_list_blockers(server.ai_interface.as_ref()).await;
+
+        assert!(blockers_result["success"].as_bool().unwrap());
+        let blockers = blockers_result["data"]["blockers"].as_array().unwrap();
+        // Blockers list can be empty or have items depending on current state
+        assert!(blockers_result["data"]["total_count"].is_number());
+    }
+
+    #[tokio::test]
+    async fn test_list_blockers_includes_dependency_blockers() {
+        use devman_storage::Storage;
+
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        let mut ids = Vec::new();
+        for title in ["Schema", "Migration"] {
+            let created = server.handle_create_task(ai_interface, &json!({"title": title})).await;
+            ids.push(created["data"]["task_id"].as_str().unwrap().parse::<devman_core::TaskId>().unwrap());
+        }
+        let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+        let mut migration = storage.require_task(ids[1]).await.unwrap();
+        migration.status = devman_core::TaskStatus::Blocked;
+        migration.depends_on = vec![ids[0]];
+        storage.save_task(&migration).await.unwrap();
+
+        let result = server.handle_list_blockers(server.ai_interface.as_ref()).await;
+        let blockers = result["data"]["blockers"].as_array().unwrap();
+        assert_eq!(blockers.len(), 1, "{}", result);
+        assert_eq!(result["data"]["total_count"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_e2e_task_with_dependencies() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        // Create first task
+        let task1_args = json!({
+            "title": "Dependency Task 1",
+            "description": "First task in dependency chain"
+        });
+
+        let task1_result = server.handle_create_task(ai_interface, &task1_args).await;
+        let _task1_id = task1_result["data"]["task_id"].as_str().unwrap().to_string();
+
+        // Create second task that depends on first
+        let task2_args = json!({
+            "title": "Dependency Task 2",
+            "description": "Second task that depends on task 1"
+        });
+
+        let task2_result = server.handle_create_task(ai_interface, &task2_args).await;
+        let _task2_id = task2_result["data"]["task_id"].as_str().unwrap().to_string();
+
+        // List both tasks
+        let list_args = json!({});
+        let list_result = server.handle_list_tasks(ai_interface, &list_args).await;
+        let tasks = list_result["data"]["tasks"].as_array().unwrap();
+        assert_eq!(tasks.len(), 2);
+
+        // Verify both tasks are in Queued state
+        assert_eq!(tasks[0]["status"], "Queued");
+        assert_eq!(tasks[1]["status"], "Queued");
+    }
+
+    #[tokio::test]
+    async fn test_e2e_task_filter_by_status() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        // Create multiple tasks with different implied states
+        for i in 1..=3 {
+            let args = json!({
+                "title": format!("Filter Test Task {}", i),
+                "description": format!("Description for task {}", i)
+            });
+            server.handle_create_task(ai_interface, &args).await;
+        }
+
+        // List all tasks
+        let all_args = json!({});
+        let all_result = server.handle_list_tasks(ai_interface, &all_args).await;
+        let all_tasks = all_result["data"]["tasks"].as_array().unwrap();
+        assert_eq!(all_tasks.len(), 3);
+
+        // Filter by Queued state
+        let queued_args = json!({ "state": "Queued" });
+        let queued_result = server.handle_list_tasks(ai_interface, &queued_args).await;
+        let queued_tasks = queued_result["data"]["tasks"].as_array().unwrap();
+        assert_eq!(queued_tasks.len(), 3);
+
+        // Filter by non-existent state
+        let done_args = json!({ "state": "Completed" });
+        let done_result = server.handle_list_tasks(ai_interface, &done_args).await;
+        let done_tasks = done_result["data"]["tasks"].as_array().unwrap();
+        assert_eq!(done_tasks.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_e2e_task_list_with_limit() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        // Create 5 tasks
+        for i in 1..=5 {
+            let args = json!({
+                "title": format!("Limit Test Task {}", i),
+                "description": format!("Task {}", i)
+            });
+            server.handle_create_task(ai_interface, &args).await;
+        }
+
+        // List with limit
+        let limit_args = json!({ "limit": 3 });
+        let limit_result = server.handle_list_tasks(ai_interface, &limit_args).await;
+        let tasks = limit_result["data"]["tasks"].as_array().unwrap();
+        // Note: Limit may be advisory in some implementations
+        assert!(tasks.len() <= 5);
+    }
+
+    #[tokio::test]
+    async fn test_e2e_get_context() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        // Get current context
+        let context_result = server.handle_get_context(server.ai_interface.as_ref()).await;
+
+        // Handle placeholder response
+        assert!(context_result["success"].as_bool().unwrap());
+        assert!(context_result["data"].is_object() || context_result["data"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_e2e_confirm_knowledge_reviewed() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        // Create a task
+        let create_args = json!({
+            "title": "Knowledge Review Test",
+            "description": "Testing knowledge review confirmation"
+        });
+
+        let create_result = server.handle_create_task(ai_interface, &create_args).await;
+        let task_id = create_result["data"]["task_id"].as_str().unwrap().to_string();
+
+        // Simulate knowledge review confirmation
+        // Note: In a real implementation, this would update task state
+        let review_args = json!({
+            "task_id": task_id,
+            "knowledge_ids": ["01JHC0V2B3C4D5E6F7G8H9J0K"]
+        });
+
+        let review_result = server.handle_confirm_knowledge_reviewed(&review_args).await;
+        // Result depends on implementation - should not error
+        assert!(review_result.is_object());
+    }
+
+    fn test_knowledge(title: &str, updated_at: chrono::DateTime<chrono::Utc>) -> devman_core::Knowledge {
+        devman_core::Knowledge {
+            id: devman_core::KnowledgeId::new(),
+            title: title.to_string(),
+            knowledge_type: devman_core::KnowledgeType::LessonLearned {
+                lesson: String::new(),
+                context: String::new(),
+            },
+            content: devman_core::KnowledgeContent {
+                summary: String::new(),
+                detail: String::new(),
+                examples: vec![],
+                references: vec![],
+            },
+            metadata: devman_core::KnowledgeMetadata {
+                domain: vec![],
+                tech_stack: vec![],
+                scenarios: vec![],
+                quality_score: 1.0,
+                verified: false,
+            },
+            tags: vec![],
+            related_to: vec![],
+            derived_from: vec![],
+            usage_stats: devman_core::UsageStats {
+                times_used: 0,
+                last_used: None,
+                success_rate: 0.0,
+                feedback: vec![],
+            },
+            archived: false,
+            created_at: updated_at,
+            updated_at,
+        }
+    }
+
+    /// In-progress phase `name` holding `tasks`
+    fn test_phase(name: &str, tasks: Vec<devman_core::TaskId>) -> devman_core::Phase {
+        devman_core::Phase {
+            id: devman_core::PhaseId::new(),
+            name: name.to_string(),
+            description: String::new(),
+            objectives: vec![],
+            acceptance_criteria: vec![],
+            tasks,
+            depends_on: vec![],
+            status: devman_core::PhaseStatus::InProgress,
+            progress: devman_core::PhaseProgress::default(),
+            estimated_duration: None,
+            actual_duration: None,
+            quality_profile: None,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    /// Cargo project `name` made of `phases`, starting at the first
+    fn test_project(name: &str, phases: Vec<devman_core::PhaseId>) -> devman_core::Project {
+        devman_core::Project {
+            id: devman_core::ProjectId::new(),
+            name: name.to_string(),
+            description: String::new(),
+            config: devman_core::ProjectConfig {
+                tech_stack: vec![],
+                structure: devman_core::DirStructure { dirs: vec![], conventions: vec![] },
+                quality_profile: devman_core::QualityProfileId::default(),
+                default_task_profile: None,
+                tools: devman_core::ToolConfig {
+                    build: devman_core::BuildTool::Cargo,
+                    test_framework: devman_core::TestFramework::Rust,
+                    linters: vec![],
+                    formatters: vec![],
+                },
+            },
+            current_phase: phases[0],
+            phases,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    /// Save `phases` and a project `name` made of them
+    async fn seed_project_phase(
+        storage: &mut devman_storage::JsonStorage,
+        name: &str,
+        phases: &[devman_core::Phase],
+    ) -> devman_core::Project {
+        use devman_storage::Storage;
+
+        for phase in phases {
+            storage.save_phase(phase).await.unwrap();
+        }
+        let project = test_project(name, phases.iter().map(|p| p.id).collect());
+        storage.save_project(&project).await.unwrap();
+        project
+    }
+
+    #[tokio::test]
+    async fn test_recent_knowledge_resource() {
+        use devman_storage::Storage;
+
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+
+        let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+        let base = chrono::Utc::now();
+        for (title, offset) in [("older", 0), ("newest", 2), ("middle", 1)] {
+            storage.save_knowledge(&test_knowledge(title, base + chrono::Duration::minutes(offset))).await.unwrap();
+        }
+
+        let result = server.read_resource("devman://knowledge/recent").await;
+        let text = result["contents"][0]["text"].as_str().unwrap();
+        let body: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(body["data"]["total_count"], 3);
+        let items: Vec<devman_core::Knowledge> =
+            serde_json::from_value(body["data"]["knowledge"].clone()).unwrap();
+        let titles: Vec<_> = items.iter().map(|k| k.title.as_str()).collect();
+        assert_eq!(titles, ["newest", "middle", "older"]);
+    }
+
+    #[tokio::test]
+    async fn test_project_context_resource_snapshots_goal_phases_and_blockers() {
+        use devman_storage::Storage;
+
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        let mut ids = HashMap::new();
+        for title in ["Schema", "Migration", "Docs"] {
+            let created = server.handle_create_task(ai_interface, &json!({"title": title})).await;
+            ids.insert(title, created["data"]["task_id"].as_str().unwrap().parse::<devman_core::TaskId>().unwrap());
+        }
+        let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+        let mut docs = storage.require_task(ids["Docs"]).await.unwrap();
+        docs.status = devman_core::TaskStatus::Done;
+        storage.save_task(&docs).await.unwrap();
+        // Migration waits on the unfinished schema, which the detector reports
+        let mut migration = storage.require_task(ids["Migration"]).await.unwrap();
+        migration.status = devman_core::TaskStatus::Blocked;
+        migration.depends_on = vec![ids["Schema"]];
+        storage.save_task(&migration).await.unwrap();
+
+        let phase = test_phase("Build", vec![ids["Schema"], ids["Migration"], ids["Docs"]]);
+        let project = seed_project_phase(&mut storage, "Snapshot", std::slice::from_ref(&phase)).await;
+        let goal = ai_interface
+            .create_goal(GoalSpec {
+                title: "Ship v1".to_string(),
+                description: String::new(),
+                success_criteria: vec![],
+                project_id: Some(project.id),
+            })
+            .await
+            .unwrap();
+
+        let result = server.read_resource("devman://context/project").await;
+        let text = result["contents"][0]["text"].as_str().unwrap();
+        let data = serde_json::from_str::<serde_json::Value>(text).unwrap()["data"].clone();
+
+        assert_eq!(data["goals"][0]["id"], goal.id.to_string());
+        assert_eq!(data["goals"][0]["title"], "Ship v1");
+        let percentage = data["goals"][0]["percentage"].as_f64().unwrap();
+        assert!((percentage - 100.0 / 3.0).abs() < 0.01, "{}", percentage);
+        assert_eq!(data["phases"][0]["name"], "Build");
+        assert_eq!(data["phases"][0]["completed_tasks"], 1);
+        assert_eq!(data["phases"][0]["total_tasks"], 3);
+        let blockers = data["blockers"].as_array().unwrap();
+        assert_eq!(blockers.len(), 1);
+        assert!(blockers[0]["reason"].as_str().unwrap().contains("Schema"), "{}", blockers[0]);
+        assert_eq!(data["recent_completions"][0]["title"], "Docs");
+        assert_eq!(data["metrics"]["total_tasks"], 3);
+        assert_eq!(data["metrics"]["open_blockers"], 1);
+        assert_eq!(data["metrics"]["active_goals"], 1);
+        assert_eq!(data["metrics"]["tasks_by_status"]["Blocked"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_read_task_by_resource_template() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        let result = server.handle_create_task(ai_interface, &json!({"title": "Templated"})).await;
+        let task_id = result["data"]["task_id"].as_str().unwrap().to_string();
+
+        let uri = format!("devman://tasks/{}", task_id);
+        let result = server.read_resource(&uri).await;
+        assert_eq!(result["contents"][0]["uri"], uri);
+        let body: serde_json::Value =
+            serde_json::from_str(result["contents"][0]["text"].as_str().unwrap()).unwrap();
+        let task: devman_core::Task = serde_json::from_value(body["data"].clone()).unwrap();
+        assert_eq!(task.id.to_string(), task_id);
+        assert_eq!(task.title, "Templated");
+
+        // Static resources under the same prefix still win
+        let result = server.read_resource("devman://tasks/queue").await;
+        assert!(result.get("error").is_none());
+
+        let missing = devman_core::TaskId::new().to_string();
+        let result = server.read_resource(&format!("devman://tasks/{}", missing)).await;
+        assert_eq!(result["error"]["code"], DevManErrorCode::NotFound.code());
+        assert_eq!(result["error"]["data"]["entity_type"], "task");
+        assert_eq!(result["error"]["data"]["id"], missing);
+
+        let result = server.read_resource("devman://knowledge/not-an-id").await;
+        assert_eq!(result["error"]["code"], DevManErrorCode::InvalidParams.code());
+    }
+
+    #[tokio::test]
+    async fn test_describe_reports_tools_and_configuration() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let mut server = McpServer::with_config(McpServerConfig {
+            storage_path,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let line = r#"{"jsonrpc": "2.0", "id": 1, "method": "devman/describe"}"#;
+        let response: serde_json::Value = serde_json::from_str(&server.handle_line(line).await.unwrap()).unwrap();
+        let described = &response["result"];
+        let tools: Vec<_> = described["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(tools.len(), server.tools.len());
+        for builtin in ["devman_create_goal", "devman_create_task", "devman_get_task_guidance"] {
+            assert!(tools.contains(&builtin), "missing {}", builtin);
+        }
+        assert!(described["tools"][0]["input_schema"].is_object());
+        assert_eq!(described["resources"].as_array().unwrap().len(), server.resources.len());
+        assert_eq!(described["resourceTemplates"].as_array().unwrap().len(), 2);
+        assert!(described["storage"]["backends"].as_array().unwrap().contains(&json!("json")));
+        assert_eq!(described["features"]["jobManager"], false);
+        assert_eq!(described["features"]["aiInterface"], false);
+
+        server.set_job_manager(Arc::new(InMemoryJobManager::new()));
+        let described = server.handle_request("devman/describe", &json!({})).await;
+        assert_eq!(described["features"]["jobManager"], true);
+    }
+
+    #[tokio::test]
+    async fn test_resource_templates_list() {
+        let server = McpServer::new().await.unwrap();
+        let result = server.handle_request("resources/templates/list", &json!({})).await;
+        let templates: Vec<_> = result["resourceTemplates"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["uriTemplate"].as_str().unwrap())
+            .collect();
+        assert_eq!(templates, ["devman://tasks/{id}", "devman://knowledge/{id}"]);
+
+        let template = &server.resource_templates[0];
+        assert_eq!(template.match_uri("devman://tasks/abc"), Some("abc"));
+        assert_eq!(template.match_uri("devman://tasks/"), None);
+        assert_eq!(template.match_uri("devman://tasks/a/b"), None);
+        assert_eq!(template.match_uri("devman://knowledge/abc"), None);
+    }
+
+    #[test]
+    fn test_error_code_values() {
+        use crate::job_manager::error_codes::DevManErrorCode::*;
+
+        let expected = [
+            (ParseError, -32700),
+            (MethodNotFound, -32601),
+            (InvalidParams, -32602),
+            (Internal, -32603),
+            (Business, -32000),
+            (StateConflict, -32001),
+            (NotFound, -32002),
+            (Timeout, -32003),
+            (Cancelled, -32004),
+            (ToolNotFound, -32005),
+        ];
+        for (variant, code) in expected {
+            assert_eq!(variant.code(), code, "{:?}", variant);
+            assert_eq!(DevManErrorCode::from_code(code), Some(variant));
+        }
+        assert_eq!(DevManErrorCode::from_code(-1), None);
+
+        assert!(!NotFound.retryable());
+        assert!(!InvalidParams.retryable());
+        assert!(!Internal.retryable());
+        assert!(Timeout.retryable());
+
+        let response = mcp_error(NotFound, "Task not found", None);
+        assert_eq!(response["error"]["code"], -32002);
+        assert_eq!(response["error"]["retryable"], false);
+    }
+
+    #[tokio::test]
+    async fn test_e2e_rate_knowledge() {
+        use devman_storage::Storage;
+
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        let knowledge = test_knowledge("Flaky fix", chrono::Utc::now());
+        devman_storage::JsonStorage::new(&storage_path).await.unwrap()
+            .save_knowledge(&knowledge).await.unwrap();
+
+        let args = json!({
+            "knowledge_id": knowledge.id.to_string(),
+            "helpful": false,
+            "note": "Did not apply"
+        });
+        let result = server.handle_rate_knowledge(ai_interface, &args).await;
+        assert!(result["success"].as_bool().unwrap());
+        assert_eq!(result["data"]["feedback_count"], 1);
+        assert!(result["data"]["quality_score"].as_f64().unwrap() < 1.0);
+
+        let missing = json!({"knowledge_id": devman_core::KnowledgeId::new().to_string(), "helpful": true});
+        assert!(!server.handle_rate_knowledge(ai_interface, &missing).await["success"].as_bool().unwrap());
+
+        let no_rating = json!({"knowledge_id": knowledge.id.to_string()});
+        let result = server.handle_rate_knowledge(ai_interface, &no_rating).await;
+        assert_eq!(result["error"]["code"], -32602);
+    }
+
+    #[tokio::test]
+    async fn test_recommend_next_skips_blocked_tasks() {
+        use devman_core::TaskStatus;
+        use devman_storage::Storage;
+
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        let mut ids = Vec::new();
+        for title in ["Polish docs", "Define schema", "Build API", "Deploy", "Refactor"] {
+            let result = server
+                .handle_create_task(ai_interface, &json!({"title": title, "description": title}))
+                .await;
+            let id: devman_core::TaskId = result["data"]["task_id"].as_str().unwrap().parse().unwrap();
+            ids.push(id);
         }
-
-        async fn get_best_practices(&self, domain: &str) -> Vec<devman_core::Knowledge> {
-            let storage = self.storage.lock().await;
-            storage.list_knowledge().await.unwrap_or_default()
-                .into_iter()
-                .filter(|k| matches!(k.knowledge_type, devman_core::KnowledgeType::BestPractice { .. }))
-                .filter(|k| k.title.to_lowercase().contains(&domain.to_lowercase()))
-                .take(10)
-                .collect()
+        let [_docs, schema, api, deploy, refactor] = ids[..] else { unreachable!() };
+
+        // "Build API" waits on the schema, "Deploy" is blocked and "Refactor"
+        // is already in progress, so only the docs and schema tasks are ready.
+        let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+        for (id, status, depends_on) in [
+            (api, TaskStatus::Queued, vec![schema]),
+            (deploy, TaskStatus::Blocked, vec![schema]),
+            (refactor, TaskStatus::Active, vec![]),
+        ] {
+            let mut task = storage.load_task(id).await.unwrap().unwrap();
+            task.status = status;
+            task.depends_on = depends_on;
+            storage.save_task(&task).await.unwrap();
         }
 
-        async fn recommend_knowledge(&self, _context: &devman_core::TaskContext) -> Vec<devman_core::Knowledge> {
-            let storage = self.storage.lock().await;
-            storage.list_knowledge().await.unwrap_or_default()
+        let result = server.handle_recommend_next(ai_interface, &json!({})).await;
+        assert!(result["success"].as_bool().unwrap());
+        assert_eq!(result["data"]["task_id"], schema.to_string());
+        assert_eq!(result["data"]["candidates"], 2);
+        let rationale = result["data"]["rationale"].as_array().unwrap();
+        assert!(rationale[0].as_str().unwrap().starts_with("Unblocks 2 task(s)"));
+        // No finished history yet, so the cost falls back to the heuristic
+        let cost = &result["data"]["estimated_cost"];
+        assert_eq!(cost["samples"], 0);
+        assert!(cost["tokens"].as_u64().unwrap() > 0);
+        assert!(cost["usd_low"].as_f64().unwrap() <= cost["usd_high"].as_f64().unwrap());
+        assert!(rationale.iter().any(|r| r.as_str().unwrap().starts_with("Estimated cost: ~")));
+
+        // Once only blocked and active work remains there is nothing to recommend.
+        for id in [ids[0], schema, api] {
+            let mut task = storage.load_task(id).await.unwrap().unwrap();
+            task.status = TaskStatus::Blocked;
+            storage.save_task(&task).await.unwrap();
         }
+        let result = server.handle_recommend_next(ai_interface, &json!({})).await;
+        assert!(result["success"].as_bool().unwrap());
+        assert!(result["data"]["task_id"].is_null());
+    }
 
-        async fn search_by_tags(&self, tags: &[String], limit: usize) -> Vec<devman_core::Knowledge> {
-            let storage = self.storage.lock().await;
-            storage.list_knowledge().await.unwrap_or_default()
-                .into_iter()
-                .filter(|k| tags.iter().any(|t| k.tags.contains(t)))
-                .take(limit)
-                .collect()
-        }
+    #[tokio::test]
+    async fn test_tasks_touching_file() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+        let ai_interface = server.ai_interface.as_ref().unwrap();
 
-        async fn search_by_tags_all(&self, tags: &[String], limit: usize) -> Vec<devman_core::Knowledge> {
-            self.search_by_tags(tags, limit).await
+        let mut ids = Vec::new();
+        for (title, files) in [
+            ("Parser rewrite", json!(["src/parser.rs", "src/lib.rs"])),
+            ("Lexer fix", json!(["./src/lexer.rs"])),
+            ("Docs", json!(["README.md"])),
+        ] {
+            let result = server
+                .handle_create_task(ai_interface, &json!({"title": title, "affected_files": files}))
+                .await;
+            ids.push(result["data"]["task_id"].as_str().unwrap().to_string());
         }
 
-        async fn get_all_tags(&self) -> std::collections::HashSet<String> {
-            let storage = self.storage.lock().await;
-            storage.list_knowledge().await.unwrap_or_default()
-                .into_iter()
-                .flat_map(|k| k.tags.into_iter())
-                .collect()
-        }
+        // "Docs" only touches the lexer through logged work
+        let log_args = json!({
+            "task_id": ids[2],
+            "action": "modified",
+            "description": "Fix doc example",
+            "files": ["src/lexer.rs"]
+        });
+        assert!(server.handle_log_work(&log_args).await["success"].as_bool().unwrap());
 
-        async fn get_tag_statistics(&self) -> std::collections::HashMap<String, usize> {
-            let storage = self.storage.lock().await;
-            let mut stats = std::collections::HashMap::new();
-            for knowledge in storage.list_knowledge().await.unwrap_or_default() {
-                for tag in knowledge.tags {
-                    *stats.entry(tag).or_insert(0) += 1;
-                }
+        let result = server
+            .handle_tasks_touching_file(ai_interface, &json!({"path": "src/lexer.rs"}))
+            .await;
+        assert!(result["success"].as_bool().unwrap());
+        let tasks = result["data"]["tasks"].as_array().unwrap();
+        let mut found: Vec<_> = tasks.iter().map(|t| t["task_id"].as_str().unwrap()).collect();
+        found.sort();
+        let mut expected = vec![ids[1].as_str(), ids[2].as_str()];
+        expected.sort();
+        assert_eq!(found, expected);
+        let docs = tasks.iter().find(|t| t["task_id"] == ids[2]).unwrap();
+        assert_eq!(docs["declared"], false);
+        assert_eq!(docs["worked_on"], true);
+
+        let result = server
+            .handle_tasks_touching_file(ai_interface, &json!({"path": "./src/parser.rs"}))
+            .await;
+        assert_eq!(result["data"]["count"], 1);
+        assert_eq!(result["data"]["tasks"][0]["task_id"], ids[0]);
+
+        let result = server
+            .handle_tasks_touching_file(ai_interface, &json!({"path": "src/main.rs"}))
+            .await;
+        assert_eq!(result["data"]["count"], 0);
+
+        let result = server.handle_tasks_touching_file(ai_interface, &json!({})).await;
+        assert_eq!(result["error"]["code"], DevManErrorCode::InvalidParams.code());
+    }
+
+    #[tokio::test]
+    async fn test_task_priority_uses_level_names() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        for (title, priority) in [("Hotfix", Some("critical")), ("Cleanup", Some("Trivial")), ("Feature", None)] {
+            let mut args = json!({"title": title});
+            if let Some(priority) = priority {
+                args["priority"] = json!(priority);
             }
-            stats
+            let result = server.handle_create_task(ai_interface, &args).await;
+            assert!(result["success"].as_bool().unwrap());
         }
 
-        async fn find_similar_knowledge(&self, _knowledge: &devman_core::Knowledge, _limit: usize) -> Vec<devman_core::Knowledge> {
-            Vec::new()
+        let result = server.handle_list_tasks(ai_interface, &json!({})).await;
+        let tasks = result["data"]["tasks"].as_array().unwrap();
+        let priority_of = |title: &str| {
+            let task = tasks.iter().find(|t| t["title"] == title).unwrap();
+            (task["priority"].clone(), task["priority_value"].clone())
+        };
+        assert_eq!(priority_of("Hotfix"), (json!("Critical"), json!(255)));
+        assert_eq!(priority_of("Cleanup"), (json!("Trivial"), json!(0)));
+        assert_eq!(priority_of("Feature"), (json!("Medium"), json!(128)));
+
+        let result = server
+            .handle_list_tasks(ai_interface, &json!({"min_priority": "Medium"}))
+            .await;
+        assert_eq!(result["data"]["total_count"], 2);
+
+        for priority in [json!("urgent"), json!(3)] {
+            let result = server
+                .handle_create_task(ai_interface, &json!({"title": "Bad", "priority": priority}))
+                .await;
+            assert_eq!(result["error"]["code"], DevManErrorCode::InvalidParams.code());
         }
+    }
 
-        async fn get_by_type(&self, knowledge_type: devman_core::KnowledgeType) -> Vec<devman_core::Knowledge> {
-            let storage = self.storage.lock().await;
-            storage.list_knowledge().await.unwrap_or_default()
-                .into_iter()
-                .filter(|k| k.knowledge_type == knowledge_type)
-                .collect()
+    #[tokio::test]
+    async fn test_bulk_update_only_touches_matching_tasks() {
+        use devman_core::TaskStatus;
+        use devman_storage::Storage;
+
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        let mut ids = Vec::new();
+        for title in ["Old idea", "Older idea", "Fresh idea", "Old queued"] {
+            let result = server.handle_create_task(ai_interface, &json!({"title": title})).await;
+            let id: devman_core::TaskId = result["data"]["task_id"].as_str().unwrap().parse().unwrap();
+            ids.push(id);
         }
 
-        async fn suggest_tags(&self, query: &str, limit: usize) -> Vec<String> {
-            let all_tags = self.get_all_tags().await;
-            all_tags.into_iter()
-                .filter(|t| t.to_lowercase().contains(&query.to_lowercase()))
-                .take(limit)
-                .collect()
+        let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+        let now = chrono::Utc::now();
+        for (id, status, age_days) in [
+            (ids[0], TaskStatus::Idea, 120),
+            (ids[1], TaskStatus::Idea, 400),
+            (ids[2], TaskStatus::Idea, 5),
+            (ids[3], TaskStatus::Queued, 200),
+        ] {
+            let mut task = storage.load_task(id).await.unwrap().unwrap();
+            task.status = status;
+            task.created_at = now - chrono::Duration::days(age_days);
+            storage.save_task(&task).await.unwrap();
         }
-    }
+        let before = storage.load_task(ids[3]).await.unwrap().unwrap();
 
-    /// Simple quality engine for testing
-    struct SimpleQualityEngine {
-        storage: Arc<Mutex<dyn devman_storage::Storage>>,
+        let args = json!({
+            "status": "Abandoned",
+            "reason": "Stale backlog",
+            "filter": {"state": "Idea", "older_than_days": 90}
+        });
+        let result = server.handle_bulk_update(ai_interface, &args).await;
+        assert!(result["success"].as_bool().unwrap());
+        assert_eq!(result["data"]["updated"], 2);
+        assert_eq!(result["data"]["skipped"], 0);
+        assert_eq!(result["data"]["failed"], 0);
+
+        let status_of = |task: Option<devman_core::Task>| task.unwrap().status;
+        assert_eq!(status_of(storage.load_task(ids[0]).await.unwrap()), TaskStatus::Abandoned);
+        assert_eq!(status_of(storage.load_task(ids[1]).await.unwrap()), TaskStatus::Abandoned);
+        assert_eq!(status_of(storage.load_task(ids[2]).await.unwrap()), TaskStatus::Idea);
+        let after = storage.load_task(ids[3]).await.unwrap().unwrap();
+        assert_eq!(after.status, TaskStatus::Queued);
+        assert_eq!(after.updated_at, before.updated_at);
+
+        // Re-running skips tasks already abandoned; invalid transitions fail
+        let args = json!({"status": "Abandoned", "reason": "again", "filter": {"state": "Abandoned"}});
+        let result = server.handle_bulk_update(ai_interface, &args).await;
+        assert_eq!(result["data"]["updated"], 0);
+        assert_eq!(result["data"]["skipped"], 2);
+
+        let args = json!({"status": "Done", "reason": "shortcut", "filter": {"state": "Idea"}});
+        let result = server.handle_bulk_update(ai_interface, &args).await;
+        assert_eq!(result["data"]["failed"], 1);
+        assert_eq!(result["data"]["items"][0]["outcome"], "failed");
+        assert_eq!(status_of(storage.load_task(ids[2]).await.unwrap()), TaskStatus::Idea);
+
+        let result = server.handle_bulk_update(ai_interface, &json!({"status": "Gone", "reason": "x"})).await;
+        assert_eq!(result["error"]["code"], DevManErrorCode::InvalidParams.code());
+        let args = json!({"status": "Abandoned", "reason": "x", "filter": {"older_than_days": i64::MAX}});
+        let result = server.handle_bulk_update(ai_interface, &args).await;
+        assert_eq!(result["error"]["code"], DevManErrorCode::InvalidParams.code());
     }
 
-    #[async_trait::async_trait]
-    impl devman_quality::QualityEngine for SimpleQualityEngine {
-        async fn run_check(&self, check: &devman_core::QualityCheck, _context: &devman_quality::engine::WorkContext) -> devman_core::QualityCheckResult {
-            devman_core::QualityCheckResult {
-                check_id: check.id,
-                passed: true,
-                execution_time: std::time::Duration::ZERO,
-                details: devman_core::CheckDetails {
-                    output: String::new(),
-                    exit_code: None,
-                    error: None,
-                },
-                findings: Vec::new(),
-                metrics: Vec::new(),
-                human_review: None,
+    #[tokio::test]
+    async fn test_bulk_close_goes_through_single_task_flow() {
+        use devman_core::{TaskState, TaskStatus};
+        use devman_storage::Storage;
+
+        let (_temp_dir, storage_path) = create_test_storage();
+        let (server, ids) =
+            create_cascade_server(&storage_path, true, &["Schema", "Api", "Epic", "Step"], &[("Api", &["Schema"])]).await;
+        let ai_interface = server.ai_interface.clone().unwrap();
+        {
+            let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+            let mut epic = storage.load_task(ids["Epic"]).await.unwrap().unwrap();
+            epic.children.push(ids["Step"]);
+            storage.save_task(&epic).await.unwrap();
+            let mut step = storage.load_task(ids["Step"]).await.unwrap().unwrap();
+            step.parent = Some(ids["Epic"]);
+            storage.save_task(&step).await.unwrap();
+            for title in ["Schema", "Epic"] {
+                let mut task = storage.load_task(ids[title]).await.unwrap().unwrap();
+                task.status = TaskStatus::Active;
+                storage.save_task(&task).await.unwrap();
             }
         }
 
-        async fn run_checks(&self, checks: &[devman_core::QualityCheck], context: &devman_quality::engine::WorkContext) -> Vec<devman_core::QualityCheckResult> {
-            let mut results = Vec::new();
-            for check in checks {
-                results.push(self.run_check(check, context).await);
+        let args = json!({"status": "Done", "reason": "Shipped", "filter": {"state": "Active"}});
+        let result = server.handle_bulk_update(&ai_interface, &args).await;
+        assert_eq!(result["data"]["updated"], 1, "{}", result);
+        assert_eq!(result["data"]["failed"], 1);
+
+        // The epic still has an open step, and the schema's dependent is released
+        let epic = ai_interface.get_task(ids["Epic"]).await.unwrap();
+        assert_eq!(epic.status, TaskStatus::Active);
+        let schema = ai_interface.get_task(ids["Schema"]).await.unwrap();
+        assert_eq!(schema.status, TaskStatus::Done);
+        assert_eq!(ai_interface.get_task(ids["Api"]).await.unwrap().status, TaskStatus::Queued);
+
+        let args = json!({"status": "Abandoned", "reason": "Descoped", "filter": {"state": "Queued"}});
+        let result = server.handle_bulk_update(&ai_interface, &args).await;
+        assert!(result["success"].as_bool().unwrap(), "{}", result);
+        let api = ai_interface.get_task(ids["Api"]).await.unwrap();
+        assert_eq!(api.status, TaskStatus::Abandoned);
+        assert!(matches!(api.state, Some(TaskState::Abandoned { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_export_tool_catalog_covers_all_tools() {
+        let server = McpServer::new().await.unwrap();
+        let catalog = server.export_tool_catalog();
+        let entries = catalog.as_array().unwrap();
+        assert_eq!(entries.len(), server.tools.len());
+
+        for name in server.tools.keys() {
+            let entry = entries.iter().find(|e| e["name"] == name.as_str()).unwrap();
+            assert!(entry["description"].as_str().is_some_and(|d| !d.is_empty()), "{}", name);
+
+            let parameters = &entry["parameters"];
+            assert_eq!(parameters["type"], "object", "{}", name);
+            let properties = parameters["properties"].as_object().unwrap();
+            for required in parameters["required"].as_array().into_iter().flatten() {
+                assert!(properties.contains_key(required.as_str().unwrap()), "{}: {}", name, required);
             }
-            results
         }
 
-        async fn run_gate(&self, gate: &devman_core::QualityGate, _context: &devman_quality::engine::WorkContext) -> devman_quality::engine::GateResult {
-            devman_quality::engine::GateResult {
-                gate_name: gate.name.clone(),
-                passed: true,
-                check_results: Vec::new(),
-                decision: devman_quality::engine::GateDecision::Pass,
+        let names: Vec<_> = entries.iter().map(|e| e["name"].as_str().unwrap()).collect();
+        assert!(names.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[tokio::test]
+    async fn test_guidance_messages_follow_configured_locale() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let shared = create_test_server(&storage_path).await;
+        let ai_interface = shared.ai_interface.clone().unwrap();
+        let result = shared.handle_create_task(&ai_interface, &json!({"title": "Add parser"})).await;
+        let task_id = result["data"]["task_id"].as_str().unwrap().to_string();
+
+        let server_in = |locale| {
+            let ai_interface = ai_interface.clone();
+            let storage_path = storage_path.clone();
+            async move {
+                let mut server = McpServer::with_config(McpServerConfig {
+                    storage_path,
+                    locale,
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+                server.set_ai_interface(ai_interface);
+                server
             }
-        }
+        };
+
+        let en = server_in(Locale::En).await;
+        let result = en.handle_get_task_guidance(&json!({"task_id": task_id})).await;
+        assert_eq!(
+            result["data"]["guidance_message"],
+            "Call devman_read_task_context() to read the task context"
+        );
+        let result = en.handle_start_execution(&json!({"task_id": task_id})).await;
+        assert_eq!(
+            result["data"]["message"],
+            "Execution started; use devman_log_work() to record progress"
+        );
+
+        let zh = server_in(Locale::Zh).await;
+        let result = zh.handle_get_task_guidance(&json!({"task_id": task_id})).await;
+        assert_eq!(result["data"]["guidance_message"], "请调用 devman_read_task_context() 读取任务上下文");
+        let result = zh.handle_start_execution(&json!({"task_id": task_id})).await;
+        assert_eq!(result["data"]["message"], "开始执行，请使用 devman_log_work() 记录工作进展");
     }
 
-    /// Simple tool executor for testing
-    struct SimpleToolExecutor;
+    #[tokio::test]
+    async fn test_task_guidance_warns_on_unenforced_criteria() {
+        use devman_storage::Storage;
 
-    #[async_trait::async_trait]
-    impl devman_tools::ToolExecutor for SimpleToolExecutor {
-        async fn execute_tool(&self, _tool: &str, _input: devman_tools::ToolInput) -> Result<devman_tools::ToolOutput, anyhow::Error> {
-            Ok(devman_tools::ToolOutput {
-                exit_code: 0,
-                stdout: "Test tool execution".to_string(),
-                stderr: String::new(),
-                duration: std::time::Duration::ZERO,
-            })
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        let result = server.handle_create_task(ai_interface, &json!({"title": "Add parser"})).await;
+        let task_id = result["data"]["task_id"].as_str().unwrap().to_string();
+
+        let result = server.handle_get_task_guidance(&json!({"task_id": task_id})).await;
+        assert_eq!(result["data"]["health"], "healthy");
+
+        let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+        let mut task = storage.load_task(task_id.parse().unwrap()).await.unwrap().unwrap();
+        task.intent.success_criteria = vec!["All tests pass".to_string(), "90% coverage".to_string()];
+        task.quality_gates = vec![devman_core::QualityGate {
+            name: "Tests".to_string(),
+            description: "Unit tests".to_string(),
+            checks: Vec::new(),
+            pass_condition: devman_core::PassCondition::AllPassed,
+            on_failure: devman_core::FailureAction::Block,
+        }];
+        storage.save_task(&task).await.unwrap();
+
+        let result = server.handle_get_task_guidance(&json!({"task_id": task_id})).await;
+        assert_eq!(result["data"]["health"], "warning");
+        assert_eq!(
+            result["data"]["warnings"],
+            json!(["Success criterion not enforced by any quality gate: 90% coverage"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_goal_tree_rolls_up_progress() {
+        use devman_storage::Storage;
+
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        let mut ids = std::collections::HashMap::new();
+        for title in ["Schema", "API", "Docs"] {
+            let result = server.handle_create_task(ai_interface, &json!({"title": title})).await;
+            ids.insert(title, result["data"]["task_id"].as_str().unwrap().parse::<devman_core::TaskId>().unwrap());
         }
+        for title in ["Endpoints", "Auth"] {
+            let result = server
+                .handle_create_subtask(ai_interface, &json!({"parent_id": ids["API"].to_string(), "title": title}))
+                .await;
+            ids.insert(title, result["data"]["task_id"].as_str().unwrap().parse::<devman_core::TaskId>().unwrap());
+        }
+
+        let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+        for title in ["Schema", "Endpoints"] {
+            let mut task = storage.load_task(ids[title]).await.unwrap().unwrap();
+            task.status = devman_core::TaskStatus::Done;
+            storage.save_task(&task).await.unwrap();
+        }
+
+        // The sub-task listed in the phase must appear only under its parent
+        let build = test_phase("Build", vec![ids["Schema"], ids["API"], ids["Endpoints"]]);
+        let release = test_phase("Release", vec![ids["Docs"]]);
+        let project = seed_project_phase(&mut storage, "Tree", &[build.clone(), release]).await;
+
+        let goal = ai_interface
+            .create_goal(GoalSpec {
+                title: "Ship v1".to_string(),
+                description: String::new(),
+                success_criteria: vec![],
+                project_id: Some(project.id),
+            })
+            .await
+            .unwrap();
+
+        let result = server
+            .handle_get_goal_tree(ai_interface, &json!({"goal_id": goal.id.to_string()}))
+            .await;
+        assert!(result["success"].as_bool().unwrap());
+        let data = &result["data"];
+        assert_eq!(data["title"], "Ship v1");
+        assert_eq!(data["percentage"], 50.0);
+
+        let phases = data["phases"].as_array().unwrap();
+        let names: Vec<_> = phases.iter().map(|p| p["name"].as_str().unwrap()).collect();
+        assert_eq!(names, ["Build", "Release"]);
+
+        assert_eq!(phases[0]["total_tasks"], 2);
+        assert_eq!(phases[0]["completed_tasks"], 1);
+        assert_eq!(phases[0]["percentage"], 75.0);
+        let api = &phases[0]["tasks"][1];
+        assert_eq!(api["title"], "API");
+        assert_eq!(api["percentage"], 50.0);
+        assert_eq!(api["children"].as_array().unwrap().len(), 2);
+
+        assert_eq!(phases[1]["percentage"], 0.0);
+        assert_eq!(phases[1]["tasks"][0]["title"], "Docs");
+
+        let missing = server
+            .handle_get_goal_tree(ai_interface, &json!({"goal_id": devman_core::GoalId::new().to_string()}))
+            .await;
+        assert_eq!(missing["error"]["code"], DevManErrorCode::NotFound.code());
     }
 
     #[tokio::test]
-    async fn test_e2e_create_and_list_task() {
-        // Create test storage and server
+    async fn test_create_task_idempotency_key() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let mut server = create_test_server(&storage_path).await;
+        let ai_interface = server.ai_interface.clone().unwrap();
+
+        let args = json!({"title": "Retried", "idempotency_key": "req-1"});
+        let first = server.handle_create_task(&ai_interface, &args).await;
+        let second = server.handle_create_task(&ai_interface, &args).await;
+        assert!(first["success"].as_bool().unwrap());
+        assert_eq!(first["data"]["replayed"], false);
+        assert_eq!(second["data"]["replayed"], true);
+        assert_eq!(first["data"]["task_id"], second["data"]["task_id"]);
+        assert_eq!(ai_interface.list_tasks(TaskFilter::default()).await.len(), 1);
+
+        // Keys are scoped per tool
+        let goal = server
+            .handle_create_goal(&ai_interface, &json!({"title": "Goal", "idempotency_key": "req-1"}))
+            .await;
+        assert_eq!(goal["data"]["replayed"], false);
+
+        let other = server
+            .handle_create_task(&ai_interface, &json!({"title": "Other", "idempotency_key": "req-2"}))
+            .await;
+        assert_ne!(other["data"]["task_id"], first["data"]["task_id"]);
+
+        // Once the window has passed the key no longer matches, and the
+        // retry is a deliberate duplicate of the first task
+        server.config.idempotency_window = chrono::Duration::zero();
+        let args = json!({"title": "Retried", "idempotency_key": "req-1", "allow_duplicate": true});
+        let expired = server.handle_create_task(&ai_interface, &args).await;
+        assert_eq!(expired["data"]["replayed"], false);
+        assert_ne!(expired["data"]["task_id"], first["data"]["task_id"]);
+        assert_eq!(ai_interface.list_tasks(TaskFilter::default()).await.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_create_task_due_date_survives_reprioritizing_and_merge() {
         let (_temp_dir, storage_path) = create_test_storage();
         let server = create_test_server(&storage_path).await;
+        let ai_interface = server.ai_interface.as_ref().unwrap();
 
-        // Verify server has tools registered
-        assert!(server.tools.contains_key("devman_create_task"));
-        assert!(server.tools.contains_key("devman_list_tasks"));
+        let invalid = server.handle_create_task(ai_interface, &json!({"title": "Soon", "due_at": "tomorrow"})).await;
+        assert_eq!(invalid["error"]["code"], DevManErrorCode::InvalidParams.code());
+
+        let due = chrono::Utc::now() + chrono::Duration::hours(6);
+        let created = server
+            .handle_create_task(ai_interface, &json!({"title": "Hotfix", "due_at": due.to_rfc3339(), "priority": "Medium"}))
+            .await;
+        let hotfix: devman_core::TaskId = created["data"]["task_id"].as_str().unwrap().parse().unwrap();
+        assert_eq!(ai_interface.get_task(hotfix).await.unwrap().due_at, Some(due));
+
+        let created = server
+            .handle_create_task(ai_interface, &json!({"title": "Outage", "priority": "Critical", "allow_duplicate": true}))
+            .await;
+        let outage: devman_core::TaskId = created["data"]["task_id"].as_str().unwrap().parse().unwrap();
+
+        // The deadline boost lands on top of the base, which is kept
+        let mut manager = devman_work::BasicWorkManager::new(devman_storage::JsonStorage::new(&storage_path).await.unwrap());
+        manager.recompute_priorities(true).await.unwrap();
+        let boosted = ai_interface.get_task(hotfix).await.unwrap();
+        assert_eq!(boosted.base_priority, Some(devman_core::Priority::Medium.value()));
+        assert!(boosted.priority > devman_core::Priority::Medium.value());
+
+        // Absorbing a critical task raises the base too, so recomputing
+        // does not drop the survivor back below the merged priority
+        ai_interface.merge_tasks(hotfix, outage).await.unwrap();
+        manager.recompute_priorities(true).await.unwrap();
+        let merged = ai_interface.get_task(hotfix).await.unwrap();
+        assert_eq!(merged.base_priority, Some(devman_core::Priority::Critical.value()));
+        assert!(merged.priority >= devman_core::Priority::Critical.value());
+    }
 
-        // Get the tool handlers
-        let _create_task_handler = server.tools.get("devman_create_task").unwrap();
-        let _list_tasks_handler = server.tools.get("devman_list_tasks").unwrap();
+    #[tokio::test]
+    async fn test_create_task_warns_about_duplicates() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+        let ai_interface = server.ai_interface.as_ref().unwrap();
 
-        // Test creating a task
-        let create_args = json!({
-            "title": "E2E Test Task",
-            "description": "This is a test task created by E2E test"
-        });
+        let original = server
+            .handle_create_task(
+                ai_interface,
+                &json!({"title": "Add login page", "description": "Email and password sign-in form"}),
+            )
+            .await;
+        assert!(original["success"].as_bool().unwrap());
+
+        let near_identical = json!({"title": "Add the login page", "description": "Email and password sign-in form"});
+        let warned = server.handle_create_task(ai_interface, &near_identical).await;
+        assert_eq!(warned["error"]["code"], DevManErrorCode::StateConflict.code());
+        let candidates = &warned["error"]["data"]["duplicate_warning"]["candidates"];
+        assert_eq!(candidates.as_array().unwrap().len(), 1);
+        assert_eq!(candidates[0]["task_id"], original["data"]["task_id"]);
+        assert_eq!(ai_interface.list_tasks(TaskFilter::default()).await.len(), 1);
+
+        let distinct = server
+            .handle_create_task(
+                ai_interface,
+                &json!({"title": "Fix flaky CI cache", "description": "Pin the cache key to the lockfile hash"}),
+            )
+            .await;
+        assert!(distinct["success"].as_bool().unwrap());
+
+        let mut forced = near_identical.clone();
+        forced["allow_duplicate"] = json!(true);
+        let forced = server.handle_create_task(ai_interface, &forced).await;
+        assert!(forced["success"].as_bool().unwrap());
+        assert_eq!(ai_interface.list_tasks(TaskFilter::default()).await.len(), 3);
+    }
 
+    #[tokio::test]
+    async fn test_list_tasks_nests_subtasks_under_roots() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
         let ai_interface = server.ai_interface.as_ref().unwrap();
-        let create_result = server.handle_create_task(ai_interface, &create_args).await;
 
-        assert!(create_result["success"].as_bool().unwrap());
-        let task_id = create_result["data"]["task_id"].as_str().unwrap();
-        assert!(!task_id.is_empty());
-        assert_eq!(create_result["data"]["title"], "E2E Test Task");
+        let parent = server.handle_create_task(ai_interface, &json!({"title": "Release"})).await;
+        let parent_id = parent["data"]["task_id"].as_str().unwrap().to_string();
+        for title in ["Changelog", "Tag"] {
+            let child = server
+                .handle_create_subtask(ai_interface, &json!({"parent_id": parent_id, "title": title}))
+                .await;
+            assert!(child["success"].as_bool().unwrap(), "{}", child);
+        }
+        server.handle_create_task(ai_interface, &json!({"title": "Unrelated", "allow_duplicate": true})).await;
 
-        // Test listing tasks
-        let list_args = json!({});
-        let list_result = server.handle_list_tasks(ai_interface, &list_args).await;
+        let flat = server.handle_list_tasks(ai_interface, &json!({})).await;
+        assert_eq!(flat["data"]["total_count"], 4);
 
-        assert!(list_result["success"].as_bool().unwrap());
-        let tasks = list_result["data"]["tasks"].as_array().unwrap();
-        assert_eq!(tasks.len(), 1);
-        assert_eq!(tasks[0]["title"], "E2E Test Task");
+        let roots = server.handle_list_tasks(ai_interface, &json!({"roots_only": true})).await;
+        let titles: Vec<_> = roots["data"]["tasks"].as_array().unwrap().iter().map(|t| t["title"].as_str().unwrap()).collect();
+        assert_eq!(titles.len(), 2);
+        assert!(titles.contains(&"Release") && titles.contains(&"Unrelated"));
+
+        let nested = server.handle_list_tasks(ai_interface, &json!({"nested": true})).await;
+        let tasks = nested["data"]["tasks"].as_array().unwrap();
+        assert_eq!(tasks.len(), 2);
+        let release = tasks.iter().find(|t| t["task_id"] == parent_id.as_str()).unwrap();
+        let subtasks: Vec<_> = release["subtasks"].as_array().unwrap().iter().map(|t| t["title"].as_str().unwrap()).collect();
+        assert_eq!(subtasks, ["Changelog", "Tag"]);
+        assert_eq!(release["subtasks"][0]["parent_id"], parent_id.as_str());
+        assert_eq!(nested["data"]["total_count"], 4);
     }
 
     #[tokio::test]
-    async fn test_e2e_task_workflow() {
+    async fn test_merge_tasks_unions_graph_and_redirects_dependents() {
+        use devman_storage::Storage;
+
         let (_temp_dir, storage_path) = create_test_storage();
         let server = create_test_server(&storage_path).await;
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        let mut ids = std::collections::HashMap::new();
+        for title in ["Keep", "Duplicate", "Shared", "KeepOnly", "DuplicateOnly", "Dependent", "Downstream"] {
+            let result = server.handle_create_task(ai_interface, &json!({"title": title, "allow_duplicate": true})).await;
+            ids.insert(title, result["data"]["task_id"].as_str().unwrap().parse::<devman_core::TaskId>().unwrap());
+        }
+
+        // Keep -> {Shared, KeepOnly}; Duplicate -> {Shared, DuplicateOnly, Keep};
+        // Dependent -> {Duplicate, Keep}; Duplicate blocks Downstream
+        let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+        let record = devman_core::WorkRecord::new(ids["Duplicate"], devman_core::Executor::AI { model: "test".to_string() }, chrono::Utc::now());
+        storage.save_work_record(&record).await.unwrap();
+        for (title, deps) in [
+            ("Keep", vec!["Shared", "KeepOnly"]),
+            ("Duplicate", vec!["Shared", "DuplicateOnly", "Keep"]),
+            ("Dependent", vec!["Duplicate", "Keep"]),
+        ] {
+            let mut task = storage.load_task(ids[title]).await.unwrap().unwrap();
+            task.depends_on = deps.iter().map(|d| ids[d]).collect();
+            if title == "Duplicate" {
+                task.description = "Email and password sign-in with lockout after five attempts".to_string();
+                task.intent.context.affected_files = vec!["src/auth.rs".to_string(), "src/login.rs".to_string()];
+                task.blocks = vec![ids["Downstream"]];
+                task.work_records = vec![record.id];
+            } else if title == "Keep" {
+                task.description = "Login form".to_string();
+                task.intent.context.affected_files = vec!["src/login.rs".to_string()];
+            }
+            storage.save_task(&task).await.unwrap();
+        }
+
+        let result = server
+            .handle_merge_tasks(
+                ai_interface,
+                &json!({"keep_task_id": ids["Keep"].to_string(), "merge_task_id": ids["Duplicate"].to_string()}),
+            )
+            .await;
+        assert!(result["success"].as_bool().unwrap(), "{}", result);
+
+        let keep = ai_interface.get_task(ids["Keep"]).await.unwrap();
+        assert_eq!(keep.depends_on, vec![ids["Shared"], ids["KeepOnly"], ids["DuplicateOnly"]]);
+        assert_eq!(keep.blocks, vec![ids["Downstream"]]);
+        assert_eq!(keep.description, "Email and password sign-in with lockout after five attempts");
+        assert_eq!(keep.intent.context.affected_files, ["src/login.rs", "src/auth.rs"]);
+        assert_eq!(keep.work_records, vec![record.id]);
+        let moved = storage.load_work_record(record.id).await.unwrap().unwrap();
+        assert_eq!(moved.task_id, ids["Keep"]);
+        assert!(storage.list_work_records(ids["Duplicate"]).await.unwrap().is_empty());
+        assert_eq!(storage.list_work_records(ids["Keep"]).await.unwrap().len(), 1);
+
+        let dependent = ai_interface.get_task(ids["Dependent"]).await.unwrap();
+        assert_eq!(dependent.depends_on, vec![ids["Keep"]]);
+
+        let duplicate = ai_interface.get_task(ids["Duplicate"]).await.unwrap();
+        assert!(duplicate.archived);
+        assert_eq!(duplicate.merged_into, Some(ids["Keep"]));
+        assert!(duplicate.work_records.is_empty());
+
+        let again = server
+            .handle_merge_tasks(
+                ai_interface,
+                &json!({"keep_task_id": ids["Keep"].to_string(), "merge_task_id": ids["Duplicate"].to_string()}),
+            )
+            .await;
+        assert_eq!(again["error"]["code"], DevManErrorCode::Business.code());
+        let itself = ai_interface.merge_tasks(ids["Keep"], ids["Keep"]).await;
+        assert!(itself.is_err());
+    }
 
+    #[tokio::test]
+    async fn test_merge_tasks_across_hierarchy_and_cycles() {
+        use devman_storage::Storage;
+
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
         let ai_interface = server.ai_interface.as_ref().unwrap();
 
-        // 1. Create a task
-        let create_args = json!({
-            "title": "Workflow Test Task",
-            "description": "Testing complete task workflow"
-        });
+        let mut ids = std::collections::HashMap::new();
+        for title in ["Root", "Middle", "Leaf", "Parent", "Child", "Api", "Schema", "Migration"] {
+            let result = server.handle_create_task(ai_interface, &json!({"title": title, "allow_duplicate": true})).await;
+            ids.insert(title, result["data"]["task_id"].as_str().unwrap().parse::<devman_core::TaskId>().unwrap());
+        }
 
-        let create_result = server.handle_create_task(ai_interface, &create_args).await;
-        assert!(create_result["success"].as_bool().unwrap());
-        let task_id = create_result["data"]["task_id"].as_str().unwrap().to_string();
+        // Root > Middle > Leaf and Parent > Child; Api -> Schema -> Migration
+        let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+        for (parent, child) in [("Root", "Middle"), ("Middle", "Leaf"), ("Parent", "Child")] {
+            let mut task = storage.load_task(ids[parent]).await.unwrap().unwrap();
+            task.children.push(ids[child]);
+            storage.save_task(&task).await.unwrap();
+            let mut task = storage.load_task(ids[child]).await.unwrap().unwrap();
+            task.parent = Some(ids[parent]);
+            storage.save_task(&task).await.unwrap();
+        }
+        for (title, dep) in [("Api", "Schema"), ("Schema", "Migration")] {
+            let mut task = storage.load_task(ids[title]).await.unwrap().unwrap();
+            task.depends_on = vec![ids[dep]];
+            storage.save_task(&task).await.unwrap();
+        }
 
-        // 2. List tasks and verify it's there
-        let list_args = json!({});
-        let list_result = server.handle_list_tasks(ai_interface, &list_args).await;
-        let tasks = list_result["data"]["tasks"].as_array().unwrap();
-        assert_eq!(tasks.len(), 1);
+        // A child absorbing its parent moves up to the grandparent
+        ai_interface.merge_tasks(ids["Leaf"], ids["Middle"]).await.unwrap();
+        let leaf = ai_interface.get_task(ids["Leaf"]).await.unwrap();
+        assert_eq!(leaf.parent, Some(ids["Root"]));
+        let root = ai_interface.get_task(ids["Root"]).await.unwrap();
+        assert_eq!(root.children, vec![ids["Leaf"]]);
+
+        // A parent absorbing its child drops it from its children
+        ai_interface.merge_tasks(ids["Parent"], ids["Child"]).await.unwrap();
+        let parent = ai_interface.get_task(ids["Parent"]).await.unwrap();
+        assert!(parent.children.is_empty());
+
+        // Schema would wait on Api, which waits on Schema
+        let cycle = ai_interface.merge_tasks(ids["Api"], ids["Migration"]).await;
+        assert!(cycle.unwrap_err().to_string().contains("cycle"));
+        let migration = ai_interface.get_task(ids["Migration"]).await.unwrap();
+        assert!(!migration.archived);
+        let schema = ai_interface.get_task(ids["Schema"]).await.unwrap();
+        assert_eq!(schema.depends_on, vec![ids["Migration"]]);
+    }
 
-        // 3. Get task progress using handle_get_task (existing method)
-        let task_result = ai_interface.get_task(task_id.parse().unwrap()).await;
-        assert!(task_result.is_some());
-        let task = task_result.unwrap();
-        assert_eq!(task.title, "Workflow Test Task");
+    #[tokio::test]
+    async fn test_preview_transition_reports_side_effects_without_changes() {
+        use devman_storage::Storage;
 
-        // 4. Search knowledge (should be empty initially)
-        let search_args = json!({ "query": "test" });
-        let search_result = server.handle_search_knowledge(ai_interface, &search_args).await;
-        assert!(search_result["success"].as_bool().unwrap());
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        let mut ids = std::collections::HashMap::new();
+        for title in ["Schema", "API", "Client", "Docs", "Review notes"] {
+            let result = server.handle_create_task(ai_interface, &json!({"title": title, "allow_duplicate": true})).await;
+            ids.insert(title, result["data"]["task_id"].as_str().unwrap().parse::<devman_core::TaskId>().unwrap());
+        }
+
+        // Schema <- API <- Client; Docs waits on Schema and on Review notes
+        let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+        for (title, deps) in [("API", vec!["Schema"]), ("Client", vec!["API", "Schema"]), ("Docs", vec!["Schema", "Review notes"])] {
+            let mut task = storage.load_task(ids[title]).await.unwrap().unwrap();
+            task.depends_on = deps.iter().map(|d| ids[d]).collect();
+            storage.save_task(&task).await.unwrap();
+        }
+        let mut schema = storage.load_task(ids["Schema"]).await.unwrap().unwrap();
+        schema.status = devman_core::TaskStatus::Active;
+        storage.save_task(&schema).await.unwrap();
+
+        let phase = test_phase("Build", vec![ids["Schema"], ids["API"]]);
+        let project = seed_project_phase(&mut storage, "Preview", std::slice::from_ref(&phase)).await;
+        let goal = ai_interface
+            .create_goal(GoalSpec {
+                title: "Ship v1".to_string(),
+                description: String::new(),
+                success_criteria: vec![],
+                project_id: Some(project.id),
+            })
+            .await
+            .unwrap();
+
+        let preview = |to: &str, title: &str| json!({"task_id": ids[title].to_string(), "to_state": to});
+
+        let done = server.handle_preview_transition(ai_interface, &preview("Done", "Schema")).await;
+        assert!(done["success"].as_bool().unwrap(), "{}", done);
+        let data = &done["data"];
+        assert_eq!(data["allowed"], true);
+        let unblocked: Vec<_> = data["unblocked_tasks"].as_array().unwrap().iter().map(|t| t["title"].as_str().unwrap()).collect();
+        assert_eq!(unblocked, ["API"]);
+        assert_eq!(data["phase_progress"], json!([{"id": phase.id.to_string(), "name": "Build", "before": 0.0, "after": 50.0}]));
+        assert_eq!(data["goal_progress"][0]["id"], goal.id.to_string());
+        assert_eq!(data["goal_progress"][0]["after"], 50.0);
+        assert_eq!(data["quality_gates_pass"], true);
+
+        let abandon = server.handle_preview_transition(ai_interface, &preview("Abandoned", "Schema")).await;
+        let data = &abandon["data"];
+        assert_eq!(data["allowed"], true);
+        assert_eq!(data["stranded_tasks"].as_array().unwrap().len(), 3);
+        assert!(data["unblocked_tasks"].as_array().unwrap().is_empty());
+        assert!(data["warnings"][0].as_str().unwrap().contains("3 open task(s)"));
+
+        let refused = server.handle_preview_transition(ai_interface, &preview("Done", "Client")).await;
+        assert_eq!(refused["data"]["allowed"], false);
+        assert_eq!(refused["data"]["rejections"][0], "Cannot change status from Queued to Done");
+
+        // Nothing was written
+        assert_eq!(ai_interface.get_task(ids["Schema"]).await.unwrap().status, devman_core::TaskStatus::Active);
+
+        let invalid = server
+            .handle_preview_transition(ai_interface, &json!({"task_id": ids["Schema"].to_string(), "to_state": "Finished"}))
+            .await;
+        assert_eq!(invalid["error"]["code"], DevManErrorCode::InvalidParams.code());
     }
 
     #[tokio::test]
-    async fn test_e2e_create_multiple_tasks() {
+    async fn test_abandonment_impact_lists_transitive_dependents() {
+        use devman_storage::Storage;
+
         let (_temp_dir, storage_path) = create_test_storage();
         let server = create_test_server(&storage_path).await;
-
         let ai_interface = server.ai_interface.as_ref().unwrap();
 
-        // Create multiple tasks
-        for i in 1..=5 {
-            let args = json!({
-                "title": format!("Task #{}", i),
-                "description": format!("Description for task {}", i)
-            });
-
-            let result = server.handle_create_task(ai_interface, &args).await;
-            assert!(result["success"].as_bool().unwrap(), "Failed to create task #{}", i);
+        let mut ids = std::collections::HashMap::new();
+        for title in ["Schema", "API", "Client", "Docs", "Spike", "Unrelated"] {
+            let result = server.handle_create_task(ai_interface, &json!({"title": title})).await;
+            ids.insert(title, result["data"]["task_id"].as_str().unwrap().parse::<devman_core::TaskId>().unwrap());
         }
 
-        // List all tasks
-        let list_result = server.handle_list_tasks(ai_interface, &json!({})).await;
-        let tasks = list_result["data"]["tasks"].as_array().unwrap();
-        assert_eq!(tasks.len(), 5);
+        // Schema <- API <- Client; Schema blocks Docs; Spike is done
+        let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+        for (title, deps) in [("API", vec!["Schema"]), ("Client", vec!["API"]), ("Spike", vec!["Schema"])] {
+            let mut task = storage.load_task(ids[title]).await.unwrap().unwrap();
+            task.depends_on = deps.iter().map(|d| ids[d]).collect();
+            if title == "Spike" {
+                task.status = devman_core::TaskStatus::Done;
+            }
+            storage.save_task(&task).await.unwrap();
+        }
+        let mut schema = storage.load_task(ids["Schema"]).await.unwrap().unwrap();
+        schema.blocks = vec![ids["Docs"]];
+        storage.save_task(&schema).await.unwrap();
 
-        // Filter by status
-        let filter_result = server.handle_list_tasks(ai_interface, &json!({ "state": "Queued" })).await;
-        let filtered_tasks = filter_result["data"]["tasks"].as_array().unwrap();
-        assert_eq!(filtered_tasks.len(), 5);
+        let result = server
+            .handle_abandonment_impact(ai_interface, &json!({"task_id": ids["Schema"].to_string()}))
+            .await;
+        assert!(result["success"].as_bool().unwrap());
+        let blocked: Vec<_> = result["data"]["blocked_tasks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| (t["title"].as_str().unwrap(), t["depth"].as_u64().unwrap()))
+            .collect();
+        assert_eq!(blocked.len(), 3);
+        assert!(blocked.contains(&("API", 1)));
+        assert!(blocked.contains(&("Docs", 1)));
+        assert!(blocked.contains(&("Client", 2)));
+        assert_eq!(result["data"]["mitigations"].as_array().unwrap().len(), 2);
+
+        let leaf = server
+            .handle_abandonment_impact(ai_interface, &json!({"task_id": ids["Client"].to_string()}))
+            .await;
+        assert!(leaf["data"]["blocked_tasks"].as_array().unwrap().is_empty());
+
+        // The abandon flow reports the same impact
+        let abandon = server
+            .handle_abandon_task(&json!({
+                "task_id": ids["Schema"].to_string(),
+                "reason_type": "requirement_changed",
+                "reason": "Dropped"
+            }))
+            .await;
+        assert_eq!(abandon["data"]["impact"]["blocked_tasks"].as_array().unwrap().len(), 3);
+
+        let missing = server
+            .handle_abandonment_impact(ai_interface, &json!({"task_id": devman_core::TaskId::new().to_string()}))
+            .await;
+        assert_eq!(missing["error"]["code"], DevManErrorCode::NotFound.code());
     }
 
     #[tokio::test]
-    async fn test_e2e_create_task_with_phase() {
+    async fn test_goal_not_completable_until_command_criteria_pass() {
+        use devman_core::{CommandSpec, CriterionStatus, GoalStatus, VerificationMethod};
+        use devman_storage::Storage;
+
         let (_temp_dir, storage_path) = create_test_storage();
         let server = create_test_server(&storage_path).await;
-
         let ai_interface = server.ai_interface.as_ref().unwrap();
 
-        // Create task with phase_id
-        let args = json!({
-            "title": "Task with Phase",
-            "description": "Task associated with a phase",
-            "phase_id": "01JHA1V2B3C4D5E6F7G8H9J0K"
-        });
-
-        let result = server.handle_create_task(ai_interface, &args).await;
-        assert!(result["success"].as_bool().unwrap());
-        assert_eq!(result["data"]["title"], "Task with Phase");
+        let goal = ai_interface
+            .create_goal(crate::GoalSpec {
+                title: "Ship v1".to_string(),
+                description: String::new(),
+                success_criteria: vec!["Build passes".to_string(), "Tests pass".to_string()],
+                project_id: None,
+            })
+            .await
+            .unwrap();
+
+        let command = |command: &str| {
+            VerificationMethod::Command(CommandSpec {
+                command: command.to_string(),
+                args: Vec::new(),
+                timeout: std::time::Duration::from_secs(60),
+                expected_exit_code: Some(0),
+            })
+        };
+        let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+        let mut stored = storage.require_goal(goal.id).await.unwrap();
+        stored.success_criteria[0].verification = command("true");
+        stored.success_criteria[1].verification = command("false");
+        storage.save_goal(&stored).await.unwrap();
+
+        let results = ai_interface.verify_criteria(goal.id).await.unwrap();
+        let statuses: Vec<_> = results.iter().map(|v| (v.automated, v.status)).collect();
+        assert_eq!(statuses, vec![(true, CriterionStatus::Met), (true, CriterionStatus::NotMet)]);
+        assert_eq!(results[1].exit_code, Some(1));
+
+        let err = ai_interface.set_goal_status(goal.id, GoalStatus::Completed).await.unwrap_err();
+        assert!(err.to_string().contains("Tests pass"));
+        let stored = ai_interface.get_goal(goal.id).await.unwrap();
+        assert_eq!(stored.status, GoalStatus::Active);
+        assert_eq!(stored.success_criteria[1].status, CriterionStatus::NotMet);
+
+        let mut stored = storage.require_goal(goal.id).await.unwrap();
+        stored.success_criteria[1].verification = command("true");
+        storage.save_goal(&stored).await.unwrap();
+        let completed = ai_interface.set_goal_status(goal.id, GoalStatus::Completed).await.unwrap();
+        assert_eq!(completed.status, GoalStatus::Completed);
+        assert!(completed.success_criteria.iter().all(|c| c.status == CriterionStatus::Met));
+
+        // With no expected code any exit passes, but not a command that never ran
+        let any_exit = |command: &str| {
+            VerificationMethod::Command(CommandSpec {
+                command: command.to_string(),
+                args: Vec::new(),
+                timeout: std::time::Duration::from_secs(60),
+                expected_exit_code: None,
+            })
+        };
+        let mut stored = storage.require_goal(goal.id).await.unwrap();
+        stored.success_criteria[0].verification = any_exit("false");
+        stored.success_criteria[1].verification = any_exit("missing");
+        storage.save_goal(&stored).await.unwrap();
+        let results = ai_interface.verify_criteria(goal.id).await.unwrap();
+        let statuses: Vec<_> = results.iter().map(|v| (v.status, v.exit_code)).collect();
+        assert_eq!(statuses, vec![(CriterionStatus::Met, Some(1)), (CriterionStatus::NotMet, Some(-1))]);
     }
 
     #[tokio::test]
-    async fn test_e2e_search_knowledge() {
+    async fn test_read_task_context_trims_dependencies_to_budget() {
+        use devman_storage::Storage;
+
         let (_temp_dir, storage_path) = create_test_storage();
         let server = create_test_server(&storage_path).await;
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        let mut ids = Vec::new();
+        for title in ["Ship login", "Schema", "Sessions", "Tokens"] {
+            let result = server
+                .handle_create_task(ai_interface, &json!({"title": title, "description": "x".repeat(400)}))
+                .await;
+            ids.push(result["data"]["task_id"].as_str().unwrap().parse::<devman_core::TaskId>().unwrap());
+        }
+        let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+        let mut task = storage.require_task(ids[0]).await.unwrap();
+        task.depends_on = ids[1..].to_vec();
+        storage.save_task(&task).await.unwrap();
+
+        let task_id = ids[0].to_string();
+        let full = server.handle_read_task_context(&json!({"task_id": task_id})).await;
+        assert_eq!(full["data"]["task_info"]["title"], "Ship login");
+        assert_eq!(full["data"]["dependencies"].as_array().unwrap().len(), 3);
+        assert_eq!(full["data"]["omitted"]["dependencies"], 0);
+
+        // The task itself costs about 100 tokens; room for one more dependency
+        let trimmed = server.handle_read_task_context(&json!({"task_id": task_id, "max_tokens": 250})).await;
+        assert_eq!(trimmed["data"]["dependencies"].as_array().unwrap().len(), 1);
+        assert_eq!(trimmed["data"]["omitted"]["dependencies"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_reopen_task_from_done_and_abandoned() {
+        use crate::TimelineEntry;
+        use devman_core::{GoalStatus, TaskStatus, WorkEvent, WorkEventType};
+        use devman_storage::Storage;
 
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
         let ai_interface = server.ai_interface.as_ref().unwrap();
 
-        // Search for non-existent knowledge (should return empty array)
-        let args = json!({
-            "query": "nonexistent query",
-            "limit": 10
+        let mut ids = Vec::new();
+        for title in ["Done task", "Abandoned task"] {
+            let result = server.handle_create_task(ai_interface, &json!({"title": title})).await;
+            ids.push(result["data"]["task_id"].as_str().unwrap().parse::<devman_core::TaskId>().unwrap());
+        }
+        ai_interface
+            .record_work_event(ids[0], WorkEvent::new(WorkEventType::StepCompleted, "Implemented", json!({}), chrono::Utc::now()))
+            .await
+            .unwrap();
+
+        let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+        let mut done = storage.require_task(ids[0]).await.unwrap();
+        done.status = TaskStatus::Done;
+        done.progress.percentage = 100.0;
+        storage.save_task(&done).await.unwrap();
+        let mut abandoned = storage.require_task(ids[1]).await.unwrap();
+        abandoned.set_state(devman_core::TaskState::Abandoned {
+            abandoned_at: chrono::Utc::now(),
+            reason: devman_core::AbandonReason::Voluntary { reason: "Out of scope".to_string(), can_be_reassigned: true },
         });
+        storage.save_task(&abandoned).await.unwrap();
+
+        let project = test_project("Reopen", vec![done.phase_id]);
+        storage.save_project(&project).await.unwrap();
+        let goal = ai_interface
+            .create_goal(crate::GoalSpec {
+                title: "Ship v1".to_string(),
+                description: String::new(),
+                success_criteria: vec![],
+                project_id: Some(project.id),
+            })
+            .await
+            .unwrap();
+        ai_interface.set_goal_status(goal.id, GoalStatus::Completed).await.unwrap();
 
-        let result = server.handle_search_knowledge(ai_interface, &args).await;
+        let result = server
+            .handle_reopen_task(ai_interface, &json!({"task_id": ids[0].to_string(), "reason": "Regression found"}))
+            .await;
         assert!(result["success"].as_bool().unwrap());
-        let results = result["data"]["results"].as_array().unwrap();
-        assert_eq!(results.len(), 0);
+        assert_eq!(result["data"]["reactivated_goals"], json!([goal.id.to_string()]));
+        assert!(result["data"]["warning"].is_string());
+
+        let reopened = ai_interface.get_task(ids[0]).await.unwrap();
+        assert_eq!(reopened.status, TaskStatus::Queued);
+        assert_eq!(reopened.progress.percentage, 0.0);
+        assert_eq!(reopened.work_records, done.work_records);
+        assert_eq!(ai_interface.get_goal(goal.id).await.unwrap().status, GoalStatus::Active);
+        let timeline = ai_interface.get_task_timeline(ids[0]).await.unwrap();
+        assert!(timeline.iter().any(|e| matches!(e, TimelineEntry::WorkLog { .. })));
+        assert!(timeline.iter().any(|e| matches!(
+            e,
+            TimelineEntry::StateTransition { action, result, .. }
+                if action == "task_reopened" && result == "Done -> Queued: Regression found"
+        )));
+
+        // The goal is already active again, so nothing else is reactivated
+        assert!(ai_interface.reopen_task(ids[1], "Back in scope").await.unwrap().is_empty());
+        let requeued = ai_interface.get_task(ids[1]).await.unwrap();
+        assert_eq!(requeued.status, TaskStatus::Queued);
+        // The abandoned workflow state goes with the status it described
+        assert!(requeued.state.is_none());
+
+        let again = server
+            .handle_reopen_task(ai_interface, &json!({"task_id": ids[0].to_string(), "reason": "Twice"}))
+            .await;
+        assert_eq!(again["error"]["code"], DevManErrorCode::Business.code());
     }
 
     #[tokio::test]
-    async fn test_e2e_get_goal_progress_no_goal() {
+    async fn test_external_blocker_added_analyzed_and_resolved() {
         let (_temp_dir, storage_path) = create_test_storage();
         let server = create_test_server(&storage_path).await;
-
         let ai_interface = server.ai_interface.as_ref().unwrap();
 
-        // Get progress for non-existent goal
-        let args = json!({
-            "goal_id": "01JHB0V2B3C4D5E6F7G8H9J0K"
-        });
+        let result = server.handle_create_task(ai_interface, &json!({"title": "Integrate payments"})).await;
+        let task_id = result["data"]["task_id"].as_str().unwrap().to_string();
 
-        let result = server.handle_get_goal_progress(ai_interface, &args).await;
-        // Should return error for non-existent goal
-        assert!(!result["success"].as_bool().unwrap());
+        let added = server
+            .handle_add_blocker(
+                ai_interface,
+                &json!({"item_type": "task", "item_id": task_id, "reason": "Waiting on vendor sandbox access"}),
+            )
+            .await;
+        assert!(added["success"].as_bool().unwrap(), "{}", added);
+        assert_eq!(added["data"]["category"], "external");
+        assert_eq!(added["data"]["severity"], "Warning");
+        let blocker_id = added["data"]["id"].as_str().unwrap().to_string();
+
+        let listed = server.handle_list_blockers(server.ai_interface.as_ref()).await;
+        assert_eq!(listed["data"]["total_count"], 1);
+        assert_eq!(listed["data"]["blockers"][0]["id"], blocker_id.as_str());
+
+        let detector = devman_progress::BlockerDetector::new(std::sync::Arc::new(
+            devman_storage::JsonStorage::new(&storage_path).await.unwrap(),
+        ));
+        let analysis = detector.detect_and_analyze().await;
+        assert_eq!(analysis.blockers.len(), 1);
+        assert_eq!(analysis.blockers[0].reason, "Waiting on vendor sandbox access");
+
+        let resolved = server
+            .handle_resolve_blocker(ai_interface, &json!({"blocker_id": blocker_id}))
+            .await;
+        assert!(resolved["success"].as_bool().unwrap(), "{}", resolved);
+        assert!(!resolved["data"]["resolved_at"].is_null());
+
+        let listed = server.handle_list_blockers(server.ai_interface.as_ref()).await;
+        assert_eq!(listed["data"]["total_count"], 0);
+        assert!(detector.detect_and_analyze().await.blockers.is_empty());
+
+        // Resolving twice is refused; unknown items are reported as not found
+        let again = server
+            .handle_resolve_blocker(ai_interface, &json!({"blocker_id": blocker_id}))
+            .await;
+        assert_eq!(again["error"]["code"], -32000);
+        let missing = server
+            .handle_add_blocker(
+                ai_interface,
+                &json!({"item_type": "goal", "item_id": devman_core::GoalId::new().to_string(), "reason": "x"}),
+            )
+            .await;
+        assert_eq!(missing["error"]["code"], -32002);
     }
 
-    // ==================== Task State Machine E2E Tests ====================
-
     #[tokio::test]
-    async fn test_e2e_task_state_machine_full_workflow() {
-        // Test the complete task state machine: Created -> ContextRead -> KnowledgeReviewed -> InProgress -> WorkRecorded -> QualityChecking -> QualityCompleted -> Completed
+    async fn test_task_inherits_phase_quality_profile() {
+        use devman_storage::Storage;
+
         let (_temp_dir, storage_path) = create_test_storage();
         let server = create_test_server(&storage_path).await;
-
         let ai_interface = server.ai_interface.as_ref().unwrap();
 
-        // 1. Create task (state: Queued/Created)
-        let create_args = json!({
-            "title": "State Machine Test Task",
-            "description": "Testing complete task state machine workflow"
-        });
+        let check = devman_core::QualityCheck {
+            id: devman_core::QualityCheckId::new(),
+            name: "clippy".to_string(),
+            description: String::new(),
+            check_type: devman_core::QualityCheckType::Generic(devman_core::GenericCheckType::LintsPass {
+                linter: "clippy".to_string(),
+            }),
+            severity: devman_core::Severity::Error,
+            category: devman_core::QualityCategory::Correctness,
+            timeout: None,
+            scope: devman_core::CheckScope::Full,
+            working_dir: None,
+            blocking: true,
+        };
+        let profile = devman_core::QualityProfile {
+            id: devman_core::QualityProfileId::new(),
+            name: "strict".to_string(),
+            description: "Lint everything".to_string(),
+            checks: vec![check.id],
+            phase_gates: vec![],
+            default_strategy: devman_core::GateStrategy::AllMustPass,
+        };
+        let phase = test_phase("Build", vec![]);
+        {
+            let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+            storage.save_quality_check(&check).await.unwrap();
+            storage.save_quality_profile(&profile).await.unwrap();
+            storage.save_phase(&phase).await.unwrap();
+        }
 
-        let create_result = server.handle_create_task(ai_interface, &create_args).await;
-        assert!(create_result["success"].as_bool().unwrap());
-        let task_id = create_result["data"]["task_id"].as_str().unwrap().to_string();
+        let task = ai_interface
+            .create_task(TaskSpec {
+                title: "Parser".to_string(),
+                description: String::new(),
+                intent: devman_core::TaskIntent {
+                    natural_language: String::new(),
+                    context: devman_core::TaskContext {
+                        relevant_knowledge: vec![],
+                        similar_tasks: vec![],
+                        affected_files: vec![],
+                    },
+                    success_criteria: vec![],
+                },
+                phase_id: phase.id,
+                quality_gates: vec![],
+                priority: Default::default(),
+                quality_profile: None,
+                due_at: None,
+            })
+            .await
+            .unwrap();
+        let args = json!({"task_id": task.id.to_string()});
+
+        // Without a profile there is nothing to run
+        let result = server.handle_run_task_quality_check(ai_interface, &args).await;
+        assert_eq!(result["data"]["gates"], json!([]));
+
+        let assigned = server
+            .handle_assign_quality_profile(
+                ai_interface,
+                &json!({"phase_id": phase.id.to_string(), "profile_id": profile.id.to_string()}),
+            )
+            .await;
+        assert!(assigned["success"].as_bool().unwrap());
+        {
+            // Guided through the interactive flow up to its quality check
+            let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+            let mut recorded = storage.require_task(task.id).await.unwrap();
+            recorded.set_state(devman_core::TaskState::WorkRecorded {
+                record_id: devman_core::WorkRecordId::new(),
+                recorded_at: chrono::Utc::now(),
+            });
+            storage.save_task(&recorded).await.unwrap();
+        }
 
-        // Verify initial state
-        let task = ai_interface.get_task(task_id.parse().unwrap()).await.unwrap();
-        assert_eq!(task.status, devman_core::TaskStatus::Queued);
+        let result = server.handle_run_task_quality_check(ai_interface, &args).await;
+        let gates = result["data"]["gates"].as_array().unwrap();
+        assert_eq!(gates.len(), 1);
+        assert_eq!(gates[0]["gate"], "profile:strict");
+        assert_eq!(gates[0]["checks"], json!([{"check_id": check.id.to_string(), "passed": true}]));
+        let checked = ai_interface.get_task(task.id).await.unwrap();
+        assert!(matches!(
+            checked.state,
+            Some(devman_core::TaskState::QualityCompleted { result, .. })
+                if result.overall_status == devman_core::TaskQualityOverallStatus::Passed
+        ));
 
-        // 2. Start task execution (state: Active)
-        // Note: handle_start_execution is a placeholder - it returns success but doesn't update state
-        let start_args = json!({ "task_id": task_id });
-        let start_result = server.handle_start_execution(&start_args).await;
-        assert!(start_result["success"].as_bool().unwrap());
+        let missing = server
+            .handle_assign_quality_profile(
+                ai_interface,
+                &json!({"phase_id": phase.id.to_string(), "profile_id": devman_core::QualityProfileId::new().to_string()}),
+            )
+            .await;
+        assert_eq!(missing["error"]["code"], DevManErrorCode::NotFound.code());
+    }
 
-        // 3. Log work (state remains Active, adds work record)
-        let log_args = json!({
-            "task_id": task_id,
-            "action": "modified",
-            "description": "Implemented core functionality",
-            "files": ["src/lib.rs", "src/main.rs"]
-        });
-        let log_result = server.handle_log_work(&log_args).await;
-        assert!(log_result["success"].as_bool().unwrap());
+    #[tokio::test]
+    async fn test_cancelling_quality_check_job_stops_the_checks() {
+        use devman_storage::Storage;
 
-        // Note: handle_log_work is a placeholder - it returns success but doesn't create work records
+        let (_temp_dir, storage_path) = create_test_storage();
+        let mut server = create_test_server(&storage_path).await;
+        let ai_interface = server.ai_interface.clone().unwrap();
 
-        // 4. Finish work (state: WorkRecorded equivalent)
-        let finish_args = json!({
-            "task_id": task_id,
-            "description": "Completed implementation of core features",
-            "artifacts": [
-                { "name": "lib.rs", "type": "code", "path": "src/lib.rs" }
-            ]
-        });
-        let finish_result = server.handle_finish_work(&finish_args).await;
-        assert!(finish_result["success"].as_bool().unwrap());
+        let check = devman_core::QualityCheck {
+            id: devman_core::QualityCheckId::new(),
+            name: "tests".to_string(),
+            description: "[slow]".to_string(),
+            check_type: devman_core::QualityCheckType::Generic(devman_core::GenericCheckType::TestsPass {
+                test_suite: "unit".to_string(),
+                min_coverage: None,
+            }),
+            severity: devman_core::Severity::Error,
+            category: devman_core::QualityCategory::Testing,
+            timeout: None,
+            scope: devman_core::CheckScope::Full,
+            working_dir: None,
+            blocking: true,
+        };
+        let created = server.handle_create_task(&ai_interface, &json!({"title": "Slow suite"})).await;
+        let task_id: devman_core::TaskId = created["data"]["task_id"].as_str().unwrap().parse().unwrap();
+        {
+            let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+            storage.save_quality_check(&check).await.unwrap();
+            let mut task = storage.require_task(task_id).await.unwrap();
+            task.quality_gates.push(devman_core::QualityGate {
+                name: "tests".to_string(),
+                description: String::new(),
+                checks: vec![check.id],
+                pass_condition: devman_core::PassCondition::AllPassed,
+                on_failure: devman_core::FailureAction::Block,
+            });
+            storage.save_task(&task).await.unwrap();
+        }
+        let args = json!({"task_id": task_id.to_string(), "async": true});
 
-        // 5. Run quality check
-        let quality_args = json!({
-            "task_id": task_id,
-            "check_types": ["compile", "test"]
-        });
-        let quality_result = server.handle_run_task_quality_check(&quality_args).await;
-        assert!(quality_result["success"].as_bool().unwrap());
+        let missing = server.handle_run_task_quality_check(&ai_interface, &args).await;
+        assert_eq!(missing["error"]["code"], DevManErrorCode::Internal.code());
 
-        // 6. Confirm quality result and complete task
-        // Note: This would require actual quality check implementation
-        // For now, test that the workflow doesn't error
+        server.set_job_manager(Arc::new(InMemoryJobManager::new()));
+        let started = server.handle_run_task_quality_check(&ai_interface, &args).await;
+        assert_eq!(started["async"], true);
+        let job_id = started["job_id"].as_str().unwrap();
+
+        let cancelled = server.handle_cancel_job(&json!({"job_id": job_id})).await;
+        assert!(cancelled["success"].as_bool().unwrap());
+
+        // The running check stops and its cancelled result is recorded
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        let history = loop {
+            let history = ai_interface.quality_history(task_id).await.unwrap();
+            if !history.is_empty() || std::time::Instant::now() > deadline {
+                break history;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        };
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].details.error.as_deref(), Some("Cancelled"));
+        let status = server.handle_get_job_status(&json!({"job_id": job_id})).await;
+        assert_eq!(status["data"]["status"], "Cancelled");
     }
 
     #[tokio::test]
-    async fn test_e2e_task_pause_and_resume() {
+    async fn test_quality_history_detects_regression() {
+        use devman_storage::Storage;
+
         let (_temp_dir, storage_path) = create_test_storage();
         let server = create_test_server(&storage_path).await;
-
         let ai_interface = server.ai_interface.as_ref().unwrap();
 
-        // Create and start task
-        let create_args = json!({
-            "title": "Pause/Resume Test",
-            "description": "Testing pause and resume functionality"
-        });
-
-        let create_result = server.handle_create_task(ai_interface, &create_args).await;
-        let task_id = create_result["data"]["task_id"].as_str().unwrap().to_string();
-
-        // Start task execution (placeholder - doesn't actually update state)
-        let start_args = json!({ "task_id": task_id });
-        server.handle_start_execution(&start_args).await;
+        let mut check = devman_core::QualityCheck {
+            id: devman_core::QualityCheckId::new(),
+            name: "tests".to_string(),
+            description: String::new(),
+            check_type: devman_core::QualityCheckType::Generic(devman_core::GenericCheckType::TestsPass {
+                test_suite: "unit".to_string(),
+                min_coverage: Some(80.0),
+            }),
+            severity: devman_core::Severity::Error,
+            category: devman_core::QualityCategory::Testing,
+            timeout: None,
+            scope: devman_core::CheckScope::Full,
+            working_dir: None,
+            blocking: true,
+        };
+        devman_storage::JsonStorage::new(&storage_path)
+            .await
+            .unwrap()
+            .save_quality_check(&check)
+            .await
+            .unwrap();
+
+        let created = server
+            .handle_create_task(ai_interface, &json!({"title": "Coverage", "description": "Raise coverage"}))
+            .await;
+        let task_id: devman_core::TaskId = created["data"]["task_id"].as_str().unwrap().parse().unwrap();
+        {
+            let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+            let mut task = storage.require_task(task_id).await.unwrap();
+            task.quality_gates.push(devman_core::QualityGate {
+                name: "tests".to_string(),
+                description: String::new(),
+                checks: vec![check.id],
+                pass_condition: devman_core::PassCondition::AllPassed,
+                on_failure: devman_core::FailureAction::Block,
+            });
+            storage.save_task(&task).await.unwrap();
+        }
+        let args = json!({"task_id": task_id.to_string()});
+
+        server.handle_run_task_quality_check(ai_interface, &args).await;
+        server.handle_run_task_quality_check(ai_interface, &args).await;
+        let result = server.handle_quality_history(ai_interface, &args).await;
+        assert_eq!(result["data"]["trend"]["direction"], "stable");
+
+        // Coverage drops on the last attempt
+        check.description = "[fail]".to_string();
+        devman_storage::JsonStorage::new(&storage_path)
+            .await
+            .unwrap()
+            .save_quality_check(&check)
+            .await
+            .unwrap();
+        server.handle_run_task_quality_check(ai_interface, &args).await;
+
+        let history = ai_interface.quality_history(task_id).await.unwrap();
+        let passed: Vec<bool> = history.iter().map(|r| r.passed).collect();
+        assert_eq!(passed, [true, true, false]);
+
+        let result = server.handle_quality_history(ai_interface, &args).await;
+        assert_eq!(result["data"]["history"].as_array().unwrap().len(), 3);
+        assert_eq!(result["data"]["trend"]["direction"], "regressing");
+        assert_eq!(result["data"]["trend"]["pass_rate_change"], -1.0);
+
+        let missing = server
+            .handle_quality_history(ai_interface, &json!({"task_id": devman_core::TaskId::new().to_string()}))
+            .await;
+        assert_eq!(missing["error"]["code"], DevManErrorCode::NotFound.code());
+    }
 
-        // Verify task was created
-        let task = ai_interface.get_task(task_id.parse().unwrap()).await.unwrap();
-        assert_eq!(task.status, devman_core::TaskStatus::Queued);
+    #[tokio::test]
+    async fn test_goal_progress_reports_criterion_conflicts() {
+        use devman_storage::Storage;
 
-        // Pause task
-        let pause_args = json!({
-            "task_id": task_id,
-            "reason": "Waiting for dependency review"
-        });
-        let pause_result = server.handle_pause_task(&pause_args).await;
-        assert!(pause_result["success"].as_bool().unwrap());
+        let (_temp_dir, storage_path) = create_test_storage();
+        let (server, storage) = create_test_server_sharing_storage(&storage_path).await;
+        let ai_interface = server.ai_interface.as_ref().unwrap();
 
-        // Resume task
-        let resume_args = json!({ "task_id": task_id });
-        let resume_result = server.handle_resume_task(&resume_args).await;
-        assert!(resume_result["success"].as_bool().unwrap());
+        let phase = test_phase("Build", vec![]);
+        let project = seed_project_phase(&mut *storage.lock().await, "Criteria", std::slice::from_ref(&phase)).await;
+        let goal = ai_interface
+            .create_goal(GoalSpec {
+                title: "Ship login".to_string(),
+                description: String::new(),
+                success_criteria: vec![
+                    "Users can log in with email".to_string(),
+                    "Export reports as CSV".to_string(),
+                ],
+                project_id: Some(project.id),
+            })
+            .await
+            .unwrap();
+        let login = goal.success_criteria[0].id;
+
+        let mut phase = phase;
+        let tasks = [
+            ("Login form", format!("Covers {}", login)),
+            ("Email auth", "User can log in with their email".to_string()),
+            ("CSV export", "Export reports as CSV".to_string()),
+            ("Old login", "Users can log in with email".to_string()),
+        ];
+        for (title, criterion) in tasks {
+            let created = server.handle_create_task(ai_interface, &json!({ "title": title })).await;
+            let task_id: devman_core::TaskId = created["data"]["task_id"].as_str().unwrap().parse().unwrap();
+            let mut storage = storage.lock().await;
+            let mut task = storage.load_task(task_id).await.unwrap().unwrap();
+            task.intent.success_criteria = vec![criterion];
+            // A finished task no longer competes for its criterion
+            if title == "Old login" {
+                task.status = devman_core::TaskStatus::Done;
+            }
+            storage.save_task(&task).await.unwrap();
+            phase.tasks.push(task_id);
+        }
+        storage.lock().await.save_phase(&phase).await.unwrap();
+
+        let conflicts = ai_interface.detect_criterion_conflicts(goal.id).await;
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].criterion_id, login);
+        let titles: Vec<&str> = conflicts[0].claims.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, vec!["Login form", "Email auth"]);
+        assert_eq!(conflicts[0].claims[0].score, 1.0);
+
+        let result = server
+            .handle_get_goal_progress(ai_interface, &json!({ "goal_id": goal.id.to_string() }))
+            .await;
+        assert!(result["success"].as_bool().unwrap(), "{}", result);
+        let reported = result["data"]["criterion_conflicts"].as_array().unwrap();
+        assert_eq!(reported.len(), 1);
+        assert_eq!(reported[0]["description"], "Users can log in with email");
+        assert_eq!(reported[0]["claims"].as_array().unwrap().len(), 2);
+
+        // Tasks on distinct criteria do not conflict
+        {
+            let mut storage = storage.lock().await;
+            let mut form = storage.load_task(phase.tasks[0]).await.unwrap().unwrap();
+            form.intent.success_criteria = vec!["Password reset email is sent".to_string()];
+            storage.save_task(&form).await.unwrap();
+        }
+        assert!(ai_interface.detect_criterion_conflicts(goal.id).await.is_empty());
+        assert!(ai_interface.detect_criterion_conflicts(devman_core::GoalId::new()).await.is_empty());
     }
 
     #[tokio::test]
-    async fn test_e2e_task_abandon() {
+    async fn test_goal_quality_lists_tasks_with_failing_gates() {
+        use devman_storage::Storage;
+
         let (_temp_dir, storage_path) = create_test_storage();
         let server = create_test_server(&storage_path).await;
-
         let ai_interface = server.ai_interface.as_ref().unwrap();
 
-        // Create task
-        let create_args = json!({
-            "title": "Abandon Test Task",
-            "description": "Task to be abandoned"
-        });
-
-        let create_result = server.handle_create_task(ai_interface, &create_args).await;
-        let task_id = create_result["data"]["task_id"].as_str().unwrap().to_string();
+        let mut ids = Vec::new();
+        for title in ["Parser", "Docs"] {
+            let created = server
+                .handle_create_task(ai_interface, &json!({"title": title, "description": title}))
+                .await;
+            ids.push(created["data"]["task_id"].as_str().unwrap().parse::<devman_core::TaskId>().unwrap());
+        }
 
-        // Abandon with different reason types
-        let abandon_args = json!({
-            "task_id": task_id,
-            "reason_type": "requirement_changed",
-            "reason": "Requirements have changed, this task is no longer needed"
-        });
-        let abandon_result = server.handle_abandon_task(&abandon_args).await;
-        assert!(abandon_result["success"].as_bool().unwrap());
+        let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+        let phase = test_phase("Build", ids.clone());
+        let project = seed_project_phase(&mut storage, "Quality", std::slice::from_ref(&phase)).await;
+
+        let result = |passed: bool, severity: devman_core::Severity| devman_core::QualityCheckResult {
+            check_id: devman_core::QualityCheckId::new(),
+            passed,
+            execution_time: std::time::Duration::ZERO,
+            details: devman_core::CheckDetails { output: String::new(), exit_code: None, error: None, timeout: None },
+            findings: vec![devman_core::Finding {
+                severity,
+                category: devman_core::QualityCategory::Correctness,
+                message: String::new(),
+                location: None,
+                suggestion: None,
+            }],
+            metrics: vec![devman_core::Metric { name: "coverage".to_string(), value: 70.0, unit: None }],
+            human_review: None,
+        };
+        storage
+            .save_quality_run(&devman_core::QualityRun::new(ids[0], vec![result(false, devman_core::Severity::Error)], chrono::Utc::now()))
+            .await
+            .unwrap();
+        storage
+            .save_quality_run(&devman_core::QualityRun::new(ids[1], vec![result(true, devman_core::Severity::Info)], chrono::Utc::now()))
+            .await
+            .unwrap();
+
+        let goal = ai_interface
+            .create_goal(GoalSpec {
+                title: "Ship v1".to_string(),
+                description: String::new(),
+                success_criteria: vec![],
+                project_id: Some(project.id),
+            })
+            .await
+            .unwrap();
+        let result = server
+            .handle_goal_quality(ai_interface, &json!({"goal_id": goal.id.to_string()}))
+            .await;
+        assert!(result["success"].as_bool().unwrap(), "{}", result);
+        let data = &result["data"];
+        assert_eq!(data["checked_tasks"], 2);
+        assert_eq!(data["findings"], json!({"info": 1, "warning": 0, "error": 1, "critical": 0}));
+        assert_eq!(data["average_coverage"], 70.0);
+        assert_eq!(data["failing_tasks"], json!([ids[0].to_string()]));
+
+        let missing = server
+            .handle_goal_quality(ai_interface, &json!({"goal_id": devman_core::GoalId::new().to_string()}))
+            .await;
+        assert_eq!(missing["error"]["code"], DevManErrorCode::NotFound.code());
     }
 
     #[tokio::test]
-    async fn test_e2e_goal_creation_and_progress() {
+    async fn test_advance_phase_enforces_gate_and_completes_goal() {
+        use devman_storage::Storage;
+
         let (_temp_dir, storage_path) = create_test_storage();
         let server = create_test_server(&storage_path).await;
-
         let ai_interface = server.ai_interface.as_ref().unwrap();
 
-        // Create goal
-        let goal_args = json!({
-            "title": "E2E Test Goal",
-            "description": "A goal created by E2E test",
-            "success_criteria": [
-                "Complete all E2E tests",
-                "Verify task state machine",
-                "Verify knowledge management"
-            ]
-        });
+        let mut ids = HashMap::new();
+        for title in ["Parser", "Docs", "Publish"] {
+            let created = server.handle_create_task(ai_interface, &json!({"title": title})).await;
+            ids.insert(title, created["data"]["task_id"].as_str().unwrap().parse::<devman_core::TaskId>().unwrap());
+        }
+        let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+        let mut parser = storage.require_task(ids["Parser"]).await.unwrap();
+        parser.status = devman_core::TaskStatus::Done;
+        parser.quality_gates = vec![devman_core::QualityGate {
+            name: "tests".to_string(),
+            description: String::new(),
+            checks: vec![devman_core::QualityCheckId::new()],
+            pass_condition: devman_core::PassCondition::AllPassed,
+            on_failure: devman_core::FailureAction::Block,
+        }];
+        storage.save_task(&parser).await.unwrap();
+        let run = |passed: bool, at: devman_core::Time| {
+            devman_core::QualityRun::new(
+                ids["Parser"],
+                vec![devman_core::QualityCheckResult {
+                    check_id: parser.quality_gates[0].checks[0],
+                    passed,
+                    execution_time: std::time::Duration::ZERO,
+                    details: devman_core::CheckDetails { output: String::new(), exit_code: None, error: None, timeout: None },
+                    findings: vec![],
+                    metrics: vec![],
+                    human_review: None,
+                }],
+                at,
+            )
+        };
+        storage.save_quality_run(&run(false, chrono::Utc::now())).await.unwrap();
 
-        let goal_result = server.handle_create_goal(ai_interface, &goal_args).await;
-        assert!(goal_result["success"].as_bool().unwrap());
-        let goal_id = goal_result["data"]["goal_id"].as_str().unwrap().to_string();
+        let phase = |name: &str, tasks: Vec<devman_core::TaskId>| devman_core::Phase {
+            status: devman_core::PhaseStatus::NotStarted,
+            ..test_phase(name, tasks)
+        };
+        let build = phase("Build", vec![ids["Parser"], ids["Docs"]]);
+        let release = phase("Release", vec![ids["Publish"]]);
+        let project = seed_project_phase(&mut storage, "Phases", &[build.clone(), release.clone()]).await;
+        let goal = ai_interface
+            .create_goal(GoalSpec {
+                title: "Ship v1".to_string(),
+                description: String::new(),
+                success_criteria: vec![],
+                project_id: Some(project.id),
+            })
+            .await
+            .unwrap();
+        assert_eq!(goal.current_phase, build.id);
+        let advance = json!({"goal_id": goal.id.to_string()});
+
+        // Docs is still open and Parser failed its gate
+        let blocked = server.handle_advance_phase(ai_interface, &advance).await;
+        assert_eq!(blocked["error"]["code"], DevManErrorCode::StateConflict.code(), "{}", blocked);
+        let unmet = blocked["error"]["data"]["unmet"].as_array().unwrap();
+        assert_eq!(unmet.len(), 2, "{:?}", unmet);
+        assert!(unmet[0].as_str().unwrap().contains("'Parser' has not passed its quality gates"));
+        assert!(unmet[1].as_str().unwrap().contains("'Docs' is"));
+        assert_eq!(ai_interface.get_goal(goal.id).await.unwrap().current_phase, build.id);
+
+        storage.save_quality_run(&run(true, chrono::Utc::now() + chrono::Duration::seconds(1))).await.unwrap();
+        let mut docs = storage.require_task(ids["Docs"]).await.unwrap();
+        docs.status = devman_core::TaskStatus::Done;
+        storage.save_task(&docs).await.unwrap();
+        let advanced = server.handle_advance_phase(ai_interface, &advance).await;
+        assert!(advanced["success"].as_bool().unwrap(), "{}", advanced);
+        assert_eq!(advanced["data"]["current_phase"], release.id.to_string());
+        assert_eq!(advanced["data"]["goal_status"], "Active");
+        assert_eq!(storage.require_phase(build.id).await.unwrap().status, devman_core::PhaseStatus::Completed);
+        assert_eq!(storage.require_phase(release.id).await.unwrap().status, devman_core::PhaseStatus::InProgress);
+
+        // Manual override, also back to an earlier phase
+        let set = |phase_id: devman_core::PhaseId| json!({"goal_id": goal.id.to_string(), "phase_id": phase_id.to_string()});
+        let reset = server.handle_set_active_phase(ai_interface, &set(build.id)).await;
+        assert_eq!(reset["data"]["current_phase"], build.id.to_string());
+        let foreign = server.handle_set_active_phase(ai_interface, &set(devman_core::PhaseId::new())).await;
+        assert_eq!(foreign["error"]["code"], DevManErrorCode::InvalidParams.code());
+        server.handle_set_active_phase(ai_interface, &set(release.id)).await;
+
+        let premature = server.handle_advance_phase(ai_interface, &advance).await;
+        assert_eq!(premature["error"]["code"], DevManErrorCode::StateConflict.code());
+        let mut publish = storage.require_task(ids["Publish"]).await.unwrap();
+        publish.status = devman_core::TaskStatus::Done;
+        storage.save_task(&publish).await.unwrap();
+        let finished = server.handle_advance_phase(ai_interface, &advance).await;
+        assert_eq!(finished["data"]["current_phase"], release.id.to_string(), "{}", finished);
+        assert_eq!(finished["data"]["goal_status"], "Completed");
+        assert_eq!(storage.require_phase(release.id).await.unwrap().status, devman_core::PhaseStatus::Completed);
+
+        let again = server.handle_advance_phase(ai_interface, &advance).await;
+        assert_eq!(again["error"]["code"], DevManErrorCode::Business.code());
+    }
 
-        // Verify goal was created
-        let goal = ai_interface.get_goal(goal_id.parse().unwrap()).await.unwrap();
-        assert_eq!(goal.title, "E2E Test Goal");
-        assert_eq!(goal.success_criteria.len(), 3);
+    #[tokio::test]
+    async fn test_new_tasks_get_project_default_profile() {
+        use devman_storage::Storage;
 
-        // Get goal progress
-        let progress_args = json!({ "goal_id": goal_id });
-        let progress_result = server.handle_get_goal_progress(ai_interface, &progress_args).await;
-        assert!(progress_result["success"].as_bool().unwrap());
-        assert_eq!(progress_result["data"]["goal_id"], goal_id);
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+        let ai_interface = server.ai_interface.as_ref().unwrap();
+
+        let check = |name: &str, check_type| devman_core::QualityCheck {
+            id: devman_core::QualityCheckId::new(),
+            name: name.to_string(),
+            description: String::new(),
+            check_type: devman_core::QualityCheckType::Generic(check_type),
+            severity: devman_core::Severity::Error,
+            category: devman_core::QualityCategory::Correctness,
+            timeout: None,
+            scope: devman_core::CheckScope::Full,
+            working_dir: None,
+            blocking: true,
+        };
+        let compile = check("compile", devman_core::GenericCheckType::Compiles { target: "default".to_string() });
+        let test = check("test", devman_core::GenericCheckType::TestsPass { test_suite: "all".to_string(), min_coverage: None });
+        let profile = |name: &str, checks| devman_core::QualityProfile {
+            id: devman_core::QualityProfileId::new(),
+            name: name.to_string(),
+            description: String::new(),
+            checks,
+            phase_gates: vec![],
+            default_strategy: devman_core::GateStrategy::AllMustPass,
+        };
+        let default = profile("baseline", vec![compile.id, test.id]);
+        let empty = profile("none", vec![]);
+        let phase_id = devman_core::PhaseId::new();
+        {
+            let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+            storage.save_quality_profile(&default).await.unwrap();
+            storage.save_quality_profile(&empty).await.unwrap();
+            storage
+                .save_project(&devman_core::Project {
+                    config: devman_core::ProjectConfig {
+                        default_task_profile: Some(default.id),
+                        ..test_project("App", vec![phase_id]).config
+                    },
+                    ..test_project("App", vec![phase_id])
+                })
+                .await
+                .unwrap();
+        }
 
-        // List goals
-        use crate::GoalFilter;
-        let goals = ai_interface.list_goals(GoalFilter::default()).await;
-        assert!(goals.len() >= 1);
+        // No gates given: the project default applies
+        let created = server
+            .handle_create_task(ai_interface, &json!({"title": "Parser", "description": "Parse the config file"}))
+            .await;
+        let task_id = created["data"]["task_id"].as_str().unwrap().parse().unwrap();
+        let task = ai_interface.get_task(task_id).await.unwrap();
+        assert_eq!(task.quality_gates.len(), 1);
+        assert_eq!(task.quality_gates[0].name, "profile:baseline");
+        assert_eq!(task.quality_gates[0].checks, vec![compile.id, test.id]);
+
+        // Explicit gates are kept as they are
+        let own_gate = devman_core::QualityGate {
+            name: "docs".to_string(),
+            description: String::new(),
+            checks: vec![devman_core::QualityCheckId::new()],
+            pass_condition: devman_core::PassCondition::AllPassed,
+            on_failure: devman_core::FailureAction::Warn,
+        };
+        let task = ai_interface
+            .create_task(TaskSpec {
+                title: "Docs".to_string(),
+                description: String::new(),
+                intent: devman_core::TaskIntent {
+                    natural_language: String::new(),
+                    context: devman_core::TaskContext {
+                        relevant_knowledge: vec![],
+                        similar_tasks: vec![],
+                        affected_files: vec![],
+                    },
+                    success_criteria: vec![],
+                },
+                phase_id,
+                quality_gates: vec![own_gate],
+                priority: Default::default(),
+                quality_profile: None,
+                due_at: None,
+            })
+            .await
+            .unwrap();
+        let names: Vec<&str> = task.quality_gates.iter().map(|g| g.name.as_str()).collect();
+        assert_eq!(names, ["docs"]);
+
+        // An empty profile opts out
+        let created = server
+            .handle_create_task(
+                ai_interface,
+                &json!({"title": "Spike", "description": "Throwaway prototype", "quality_profile": empty.id.to_string()}),
+            )
+            .await;
+        let task_id = created["data"]["task_id"].as_str().unwrap().parse().unwrap();
+        assert!(ai_interface.get_task(task_id).await.unwrap().quality_gates.is_empty());
     }
 
     #[tokio::test]
-    async fn test_e2e_knowledge_save_and_retrieve() {
+    async fn test_search_knowledge_returns_scores_and_snippets() {
+        use devman_storage::Storage;
+
         let (_temp_dir, storage_path) = create_test_storage();
         let server = create_test_server(&storage_path).await;
-
         let ai_interface = server.ai_interface.as_ref().unwrap();
 
-        // Save knowledge
-        let save_args = json!({
-            "title": "E2E Test Best Practice",
-            "knowledge_type": "BestPractice",
-            "content": "Always write tests before implementing features. This ensures code quality and prevents regressions.",
-            "tags": ["testing", "best-practice", "e2e"]
-        });
-
-        let save_result = server.handle_save_knowledge(ai_interface, &save_args).await;
-        assert!(save_result["success"].as_bool().unwrap());
-        // Note: handle_save_knowledge is a placeholder, knowledge_id may not be returned
+        let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+        for (title, summary) in [("Backoff", "Retry failed calls with backoff"), ("Timeouts", "Retry after a timeout")] {
+            let mut knowledge = test_knowledge(title, chrono::Utc::now());
+            knowledge.content.summary = summary.to_string();
+            storage.save_knowledge(&knowledge).await.unwrap();
+        }
+        storage.save_knowledge(&test_knowledge("Logging", chrono::Utc::now())).await.unwrap();
 
-        // Search for the saved knowledge (placeholder returns empty results)
-        let search_args = json!({ "query": "best practice", "limit": 10 });
-        let search_result = server.handle_search_knowledge(ai_interface, &search_args).await;
-        assert!(search_result["success"].as_bool().unwrap());
+        let result = server.handle_search_knowledge(ai_interface, &json!({"query": "retry"})).await;
+        assert!(result["success"].as_bool().unwrap());
+        let results = result["data"]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+
+        let scores: Vec<f64> = results.iter().map(|r| r["score"].as_f64().unwrap()).collect();
+        assert!(scores.windows(2).all(|w| w[0] >= w[1]));
+        for hit in results {
+            let snippet = hit["snippet"].as_str().unwrap();
+            assert!(snippet.contains("**Retry**"), "{}", snippet);
+            assert_eq!(hit["search_type"], "keyword");
+        }
 
-        // Placeholder returns empty results
-        let results = search_result["data"]["results"].as_array().unwrap();
-        assert!(results.len() >= 0);
+        let limited = server.handle_search_knowledge(ai_interface, &json!({"query": "retry", "limit": 1})).await;
+        assert_eq!(limited["data"]["total_count"], 1);
+    }
 
-        // Filter by type (placeholder returns empty results)
-        let type_args = json!({ "query": "testing" });
-        let type_result = server.handle_search_knowledge(ai_interface, &type_args).await;
-        let type_results = type_result["data"]["results"].as_array().unwrap();
-        assert!(type_results.len() >= 0);  // Placeholder returns empty results
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_racing_claims_have_one_winner() {
+        let (_temp_dir, storage_path) = create_test_storage();
+        // Two servers over one store, as with two agents in separate processes
+        let first = create_test_server(&storage_path).await;
+        let second = create_test_server(&storage_path).await;
+        let ai_first = first.ai_interface.clone().unwrap();
+        let ai_second = second.ai_interface.clone().unwrap();
+
+        for round in 0..5 {
+            let created = first
+                .handle_create_task(&ai_first, &json!({"title": format!("Shared work {}", round)}))
+                .await;
+            let task_id: devman_core::TaskId = created["data"]["task_id"].as_str().unwrap().parse().unwrap();
+
+            let ttl = std::time::Duration::from_secs(600);
+            let (a, b) = (ai_first.clone(), ai_second.clone());
+            let claims = [
+                tokio::spawn(async move { a.claim_task(task_id, devman_core::AgentId::new("agent-a"), ttl).await }),
+                tokio::spawn(async move { b.claim_task(task_id, devman_core::AgentId::new("agent-b"), ttl).await }),
+            ];
+            let mut outcomes = Vec::new();
+            for claim in claims {
+                outcomes.push(claim.await.unwrap());
+            }
+            assert_eq!(outcomes.iter().filter(|o| o.is_ok()).count(), 1, "round {}", round);
+            let loser = outcomes.iter().find_map(|o| o.as_ref().err()).unwrap();
+            assert_eq!(interface_error("claim", loser)["error"]["code"], DevManErrorCode::StateConflict.code());
+
+            let token = outcomes.iter().find_map(|o| o.as_ref().ok()).copied().unwrap();
+            assert_eq!(ai_first.get_task(task_id).await.unwrap().claim.unwrap().token, token);
+
+            let release = first
+                .handle_release_task(&ai_first, &json!({"task_id": task_id.to_string(), "token": token.to_string()}))
+                .await;
+            assert!(release["success"].as_bool().unwrap());
+            assert!(ai_first.get_task(task_id).await.unwrap().claim.is_none());
+        }
     }
 
     #[tokio::test]
-    async fn test_e2e_tool_execution() {
+    async fn test_claimed_task_is_not_recommended_to_others() {
         let (_temp_dir, storage_path) = create_test_storage();
         let server = create_test_server(&storage_path).await;
-
         let ai_interface = server.ai_interface.as_ref().unwrap();
 
-        // Execute cargo tool (should work with the builtin executor)
-        let cargo_args = json!({
-            "tool": "cargo",
-            "command": "version",
-            "args": ["--version"]
-        });
-
-        let cargo_result = server.handle_execute_tool(ai_interface, &cargo_args).await;
-        // Result depends on whether cargo is available
-        // We just verify the tool was executed (not an error response)
-        assert!(cargo_result["success"].as_bool().unwrap() || cargo_result["error"].is_object());
-
-        // Execute git tool
-        let git_args = json!({
-            "tool": "git",
-            "command": "status",
-            "args": ["--version"]
-        });
+        let result = server.handle_create_task(ai_interface, &json!({"title": "Only task"})).await;
+        let task_id = result["data"]["task_id"].as_str().unwrap().to_string();
+
+        let claim = server
+            .handle_claim_task(ai_interface, &json!({"task_id": task_id, "agent_id": "agent-a"}))
+            .await;
+        assert!(claim["success"].as_bool().unwrap());
+        let token = claim["data"]["token"].as_str().unwrap().to_string();
+
+        let other = server.handle_recommend_next(ai_interface, &json!({"agent_id": "agent-b"})).await;
+        assert!(other["data"]["task_id"].is_null());
+        let anonymous = server.handle_recommend_next(ai_interface, &json!({})).await;
+        assert!(anonymous["data"]["task_id"].is_null());
+        let holder = server.handle_recommend_next(ai_interface, &json!({"agent_id": "agent-a"})).await;
+        assert_eq!(holder["data"]["task_id"], task_id);
+
+        let stolen = server
+            .handle_claim_task(ai_interface, &json!({"task_id": task_id, "agent_id": "agent-b"}))
+            .await;
+        assert_eq!(stolen["error"]["code"], DevManErrorCode::StateConflict.code());
+
+        let wrong_token = devman_core::ClaimToken::new().to_string();
+        let rejected = server
+            .handle_release_task(ai_interface, &json!({"task_id": task_id, "token": wrong_token}))
+            .await;
+        assert_eq!(rejected["error"]["code"], DevManErrorCode::StateConflict.code());
 
-        let git_result = server.handle_execute_tool(ai_interface, &git_args).await;
-        assert!(git_result["success"].as_bool().unwrap() || git_result["error"].is_object());
+        server
+            .handle_release_task(ai_interface, &json!({"task_id": task_id, "token": token}))
+            .await;
+        let other = server.handle_recommend_next(ai_interface, &json!({"agent_id": "agent-b"})).await;
+        assert_eq!(other["data"]["task_id"], task_id);
+
+        // An expired claim no longer holds the task
+        let expired = ai_interface
+            .claim_task(task_id.parse().unwrap(), devman_core::AgentId::new("agent-a"), std::time::Duration::ZERO)
+            .await;
+        assert!(expired.is_ok());
+        let other = server.handle_recommend_next(ai_interface, &json!({"agent_id": "agent-b"})).await;
+        assert_eq!(other["data"]["task_id"], task_id);
+    }
 
-        // Test unknown tool
-        let unknown_args = json!({
-            "tool": "unknown_tool",
-            "command": "test"
-        });
+    #[tokio::test]
+    async fn test_claims_idempotency_and_work_records_follow_clock() {
+        use devman_core::{AgentId, Clock};
+        use devman_storage::Storage;
 
-        // Note: handle_execute_tool is a placeholder - it always returns success
-        // In a real implementation, unknown tools would fail
-        let unknown_result = server.handle_execute_tool(ai_interface, &unknown_args).await;
-        // The placeholder always returns success
-        assert!(unknown_result["success"].as_bool().unwrap());
+        let (_temp_dir, storage_path) = create_test_storage();
+        let (mut server, storage) = create_test_server_sharing_storage(&storage_path).await;
+        let start: chrono::DateTime<chrono::Utc> = "2030-06-01T12:00:00Z".parse().unwrap();
+        let clock = Arc::new(devman_core::MockClock::new(start));
+        let timed = create_test_ai_interface(&storage_path, storage.clone()).await.with_clock(clock.clone());
+        server.set_ai_interface(Arc::new(timed));
+        let ai_interface = server.ai_interface.clone().unwrap();
+
+        let created = server.handle_create_task(&ai_interface, &json!({"title": "Timed"})).await;
+        let task_id: devman_core::TaskId = created["data"]["task_id"].as_str().unwrap().parse().unwrap();
+
+        let ttl = std::time::Duration::from_secs(600);
+        ai_interface.claim_task(task_id, AgentId::new("agent-a"), ttl).await.unwrap();
+        let claim = storage.lock().await.require_task(task_id).await.unwrap().claim.unwrap();
+        assert_eq!(claim.expires_at, start + chrono::Duration::minutes(10));
+        assert!(ai_interface.claim_task(task_id, AgentId::new("agent-b"), ttl).await.is_err());
+        clock.advance(chrono::Duration::minutes(11));
+        ai_interface.claim_task(task_id, AgentId::new("agent-b"), ttl).await.unwrap();
+
+        let window = chrono::Duration::hours(1);
+        ai_interface.remember_idempotent_entity("req-1", "entity", window).await.unwrap();
+        assert_eq!(ai_interface.find_idempotent_entity("req-1", window).await.as_deref(), Some("entity"));
+        clock.advance(chrono::Duration::hours(2));
+        assert_eq!(ai_interface.find_idempotent_entity("req-1", window).await, None);
+
+        let event = devman_core::WorkEvent::new(devman_core::WorkEventType::StepCompleted, "Step", json!({}), clock.now());
+        ai_interface.record_work_event(task_id, event).await.unwrap();
+        let storage = storage.lock().await;
+        let record_id = storage.require_task(task_id).await.unwrap().work_records[0];
+        assert_eq!(storage.load_work_record(record_id).await.unwrap().unwrap().started_at, clock.now());
     }
 
     #[tokio::test]
-    async fn test_e2e_quality_check_flow() {
+    async fn test_insert_and_reorder_phases_keep_tree_consistent() {
+        use devman_storage::Storage;
+
         let (_temp_dir, storage_path) = create_test_storage();
         let server = create_test_server(&storage_path).await;
-
         let ai_interface = server.ai_interface.as_ref().unwrap();
 
-        // Create a task first
-        let create_args = json!({
-            "title": "Quality Check Test",
-            "description": "Task to run quality checks on"
-        });
+        let mut ids = std::collections::HashMap::new();
+        for title in ["Schema", "Docs"] {
+            let result = server.handle_create_task(ai_interface, &json!({"title": title})).await;
+            ids.insert(title, result["data"]["task_id"].as_str().unwrap().parse::<devman_core::TaskId>().unwrap());
+        }
 
-        let create_result = server.handle_create_task(ai_interface, &create_args).await;
-        let task_id_opt = create_result["data"]["task_id"].as_str().map(|s| s.to_string());
+        let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+        let mut schema = storage.load_task(ids["Schema"]).await.unwrap().unwrap();
+        schema.status = devman_core::TaskStatus::Done;
+        storage.save_task(&schema).await.unwrap();
+
+        let build = test_phase("Build", vec![ids["Schema"]]);
+        let release = devman_core::Phase { depends_on: vec![build.id], ..test_phase("Release", vec![ids["Docs"]]) };
+        let project = seed_project_phase(&mut storage, "Phases", &[build.clone(), release.clone()]).await;
+
+        let goal = ai_interface
+            .create_goal(GoalSpec {
+                title: "Ship v1".to_string(),
+                description: String::new(),
+                success_criteria: vec![],
+                project_id: Some(project.id),
+            })
+            .await
+            .unwrap();
+        let tree = || async {
+            let result = server
+                .handle_get_goal_tree(ai_interface, &json!({"goal_id": goal.id.to_string()}))
+                .await;
+            let data = result["data"].clone();
+            let phases: Vec<(String, f64)> = data["phases"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|p| (p["name"].as_str().unwrap().to_string(), p["percentage"].as_f64().unwrap()))
+                .collect();
+            (phases, data["percentage"].as_f64().unwrap())
+        };
 
-        // Run quality check on task
-        let quality_args = json!({
-            "task_id": task_id_opt.as_ref().map(|s| s.as_str()).unwrap_or(""),
-            "check_types": ["compile"]
-        });
+        let inserted = server
+            .handle_insert_phase(
+                ai_interface,
+                &json!({
+                    "project_id": project.id.to_string(),
+                    "after_phase_id": build.id.to_string(),
+                    "name": "Review",
+                    "depends_on": [build.id.to_string()]
+                }),
+            )
+            .await;
+        assert!(inserted["success"].as_bool().unwrap(), "{}", inserted);
+        let review: devman_core::PhaseId = inserted["data"]["phase_id"].as_str().unwrap().parse().unwrap();
+
+        let stored = storage.load_project(project.id).await.unwrap().unwrap();
+        assert_eq!(stored.phases, [build.id, review, release.id]);
+        let (phases, percentage) = tree().await;
+        assert_eq!(
+            phases,
+            [("Build".to_string(), 100.0), ("Review".to_string(), 0.0), ("Release".to_string(), 0.0)]
+        );
+        assert_eq!(percentage, 50.0);
+
+        let reordered = server
+            .handle_reorder_phases(
+                ai_interface,
+                &json!({
+                    "project_id": project.id.to_string(),
+                    "phase_ids": [build.id.to_string(), release.id.to_string(), review.to_string()]
+                }),
+            )
+            .await;
+        assert!(reordered["success"].as_bool().unwrap(), "{}", reordered);
+        let (phases, percentage) = tree().await;
+        let names: Vec<_> = phases.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["Build", "Release", "Review"]);
+        assert_eq!(percentage, 50.0);
+
+        // Release depends on Build, so it cannot move ahead of it
+        let rejected = server
+            .handle_reorder_phases(
+                ai_interface,
+                &json!({
+                    "project_id": project.id.to_string(),
+                    "phase_ids": [release.id.to_string(), build.id.to_string(), review.to_string()]
+                }),
+            )
+            .await;
+        assert_eq!(rejected["error"]["code"], DevManErrorCode::InvalidParams.code());
+        let missing = server
+            .handle_reorder_phases(
+                ai_interface,
+                &json!({"project_id": project.id.to_string(), "phase_ids": [build.id.to_string()]}),
+            )
+            .await;
+        assert_eq!(missing["error"]["code"], DevManErrorCode::InvalidParams.code());
+        let stored = storage.load_project(project.id).await.unwrap().unwrap();
+        assert_eq!(stored.phases, [build.id, release.id, review]);
+
+        let stray = server
+            .handle_insert_phase(
+                ai_interface,
+                &json!({
+                    "project_id": project.id.to_string(),
+                    "after_phase_id": devman_core::PhaseId::new().to_string(),
+                    "name": "Stray"
+                }),
+            )
+            .await;
+        assert_eq!(stray["error"]["code"], DevManErrorCode::InvalidParams.code());
+        assert_eq!(storage.load_project(project.id).await.unwrap().unwrap().phases.len(), 3);
+    }
 
-        let quality_result = server.handle_run_task_quality_check(&quality_args).await;
-        assert!(quality_result["success"].as_bool().unwrap());
+    #[tokio::test]
+    async fn test_completing_task_links_created_knowledge() {
+        use devman_storage::Storage;
 
-        // Run standalone quality check
-        let standalone_args = json!({
-            "check_type": "compile",
-            "target": "."
-        });
+        let (_temp_dir, storage_path) = create_test_storage();
+        let server = create_test_server(&storage_path).await;
+        let ai_interface = server.ai_interface.clone().unwrap();
 
-        let standalone_result = server.handle_run_quality_check(ai_interface, &standalone_args).await;
-        // Result depends on project state
-        assert!(standalone_result.is_object());
+        let created = server.handle_create_task(&ai_interface, &json!({"title": "Add retries"})).await;
+        let task_id: devman_core::TaskId = created["data"]["task_id"].as_str().unwrap().parse().unwrap();
+
+        let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+        let record = devman_core::WorkRecordId::new();
+        let mut task = storage.load_task(task_id).await.unwrap().unwrap();
+        task.work_records.push(record);
+        storage.save_task(&task).await.unwrap();
+
+        let tagged = |title: &str, tags: &[&str]| {
+            let mut knowledge = test_knowledge(title, chrono::Utc::now());
+            knowledge.tags = tags.iter().map(|t| t.to_string()).collect();
+            knowledge
+        };
+        let prior = tagged("Backoff for HTTP calls", &["retry"]);
+        let unrelated = tagged("Logging format", &["logging"]);
+        let lesson = tagged("Retry budget", &["retry"]);
+        let pattern = tagged("Idempotent handlers", &["api"]);
+        for knowledge in [&prior, &unrelated, &lesson, &pattern] {
+            storage.save_knowledge(knowledge).await.unwrap();
+        }
+
+        let complete = |ids: Vec<String>| {
+            json!({
+                "jsonrpc": "2.0", "id": 1, "method": "tools/call",
+                "params": {"name": "devman_complete_task", "arguments": {
+                    "task_id": task_id.to_string(),
+                    "summary": "Retries added",
+                    "created_knowledge_ids": ids
+                }}
+            })
+            .to_string()
+        };
+
+        // Unknown knowledge is rejected before the task is touched
+        let response = server.handle_line(&complete(vec![devman_core::KnowledgeId::new().to_string()])).await.unwrap();
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(response["error"]["code"], DevManErrorCode::NotFound.code());
+        assert_ne!(storage.load_task(task_id).await.unwrap().unwrap().status, devman_core::TaskStatus::Done);
+
+        let response = server
+            .handle_line(&complete(vec![lesson.id.to_string(), pattern.id.to_string()]))
+            .await
+            .unwrap();
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert!(response.get("error").is_none(), "{}", response);
+
+        let task = storage.load_task(task_id).await.unwrap().unwrap();
+        assert_eq!(task.status, devman_core::TaskStatus::Done);
+        assert_eq!(task.created_knowledge, [lesson.id, pattern.id]);
+
+        let lesson = storage.load_knowledge(lesson.id).await.unwrap().unwrap();
+        assert_eq!(lesson.related_to, [pattern.id, prior.id]);
+        assert_eq!(lesson.derived_from, [record]);
+        let pattern = storage.load_knowledge(pattern.id).await.unwrap().unwrap();
+        assert_eq!(pattern.related_to, [lesson.id]);
+        assert_eq!(pattern.derived_from, [record]);
+        assert!(storage.load_knowledge(prior.id).await.unwrap().unwrap().related_to.is_empty());
     }
 
     #[tokio::test]
-    async fn test_e2e_list_blockers() {
+    async fn test_tasks_created_from_template_fill_placeholders() {
+        use devman_storage::Storage;
+
         let (_temp_dir, storage_path) = create_test_storage();
         let server = create_test_server(&storage_path).await;
+        let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+
+        let template = devman_core::TaskTemplate {
+            id: devman_core::TaskTemplateId::new(),
+            name: "add-endpoint".to_string(),
+            title: "Add {{method}} {{path}}".to_string(),
+            description: "Expose {{path}} over HTTP".to_string(),
+            steps: vec![devman_core::ExecutionStep {
+                order: 1,
+                description: "Run the {{path}} tests".to_string(),
+                tool: devman_core::ToolInvocation {
+                    tool: "cargo".to_string(),
+                    args: vec!["test".to_string(), "{{module}}".to_string()],
+                    env: vec![],
+                    timeout: None,
+                },
+                verify: None,
+            }],
+            quality_gates: vec![devman_core::QualityGate {
+                name: "tests".to_string(),
+                description: String::new(),
+                checks: vec![],
+                pass_condition: devman_core::PassCondition::AllPassed,
+                on_failure: devman_core::FailureAction::Block,
+            }],
+            success_criteria: vec!["{{method}} {{path}} returns 200".to_string()],
+            parameters: ["method", "path", "module"]
+                .iter()
+                .map(|name| devman_core::TemplateParameter {
+                    name: name.to_string(),
+                    description: String::new(),
+                    default_value: (*name == "method").then(|| "GET".to_string()),
+                    required: *name != "method",
+                })
+                .collect(),
+            priority: devman_core::Priority::High.value(),
+            created_at: chrono::Utc::now(),
+        };
+        storage.save_task_template(&template).await.unwrap();
+        let phase_id = devman_core::PhaseId::new();
+
+        let create = |params: serde_json::Value| {
+            json!({
+                "jsonrpc": "2.0", "id": 1, "method": "tools/call",
+                "params": {"name": "devman_create_from_template", "arguments": {
+                    "template_id": template.id.to_string(),
+                    "phase_id": phase_id.to_string(),
+                    "params": params
+                }}
+            })
+            .to_string()
+        };
 
-        // List blockers (should return empty initially)
-        let blockers_result = server.handle_list_blockers(server.ai_interface.as_ref()).await;
+        let mut tasks = Vec::new();
+        for params in [
+            json!({"path": "/users", "module": "users"}),
+            json!({"method": "POST", "path": "/orders", "module": "orders"}),
+        ] {
+            let response = server.handle_line(&create(params)).await.unwrap();
+            let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+            assert!(response.get("error").is_none(), "{}", response);
+            let data: serde_json::Value =
+                serde_json::from_str(response["result"]["content"][0]["text"].as_str().unwrap()).unwrap();
+            let task_id = data["data"]["task_id"].as_str().unwrap().parse().unwrap();
+            tasks.push(storage.load_task(task_id).await.unwrap().unwrap());
+        }
 
-        assert!(blockers_result["success"].as_bool().unwrap());
-        let blockers = blockers_result["data"]["blockers"].as_array().unwrap();
-        // Blockers list can be empty or have items depending on current state
-        assert!(blockers_result["data"]["total_count"].is_number());
+        assert_eq!(tasks[0].title, "Add GET /users");
+        assert_eq!(tasks[1].title, "Add POST /orders");
+        assert_eq!(tasks[1].description, "Expose /orders over HTTP");
+        assert_eq!(tasks[0].intent.success_criteria, ["GET /users returns 200"]);
+        assert_eq!(tasks[0].steps[0].description, "Run the /users tests");
+        assert_eq!(tasks[0].steps[0].tool.args, ["test", "users"]);
+        assert_eq!(tasks[1].steps[0].tool.args, ["test", "orders"]);
+        for task in &tasks {
+            assert_eq!(task.phase_id, phase_id);
+            assert_eq!(task.quality_gates.len(), 1);
+            assert_eq!(task.priority_level(), devman_core::Priority::High);
+        }
+
+        let response = server.handle_line(&create(json!({"path": "/carts"}))).await.unwrap();
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(response["error"]["code"], DevManErrorCode::InvalidParams.code());
+        assert!(response["error"]["message"].as_str().unwrap().contains("module"));
     }
 
     #[tokio::test]
-    async fn test_e2e_task_with_dependencies() {
+    async fn test_arguments_validated_against_input_schema() {
         let (_temp_dir, storage_path) = create_test_storage();
         let server = create_test_server(&storage_path).await;
+        let call = |arguments: serde_json::Value| {
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": {"name": "devman_create_goal", "arguments": arguments}
+            })
+            .to_string()
+        };
+        async fn respond(server: &McpServer, line: String) -> serde_json::Value {
+            serde_json::from_str(&server.handle_line(&line).await.unwrap()).unwrap()
+        }
 
-        let ai_interface = server.ai_interface.as_ref().unwrap();
-
-        // Create first task
-        let task1_args = json!({
-            "title": "Dependency Task 1",
-            "description": "First task in dependency chain"
-        });
+        let response = respond(&server, call(json!({"description": "No title"}))).await;
+        assert_eq!(response["error"]["code"], DevManErrorCode::InvalidParams.code());
+        assert_eq!(response["error"]["data"]["path"], "title");
+        assert!(response["error"]["message"].as_str().unwrap().contains("required"));
+
+        let response = respond(&server, call(json!({"title": "Ship", "success_criteria": "all green"}))).await;
+        assert_eq!(response["error"]["code"], DevManErrorCode::InvalidParams.code());
+        assert_eq!(response["error"]["data"]["path"], "success_criteria");
+        assert_eq!(response["error"]["data"]["reason"], "expected array, got string");
+
+        let response = respond(&server, call(json!({"title": "Ship", "success_criteria": ["all green"]}))).await;
+        assert!(response.get("error").is_none(), "{}", response);
+        let storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+        let goals = devman_storage::Storage::list_goals(&storage).await.unwrap();
+        assert_eq!(goals.len(), 1);
+        assert_eq!(goals[0].title, "Ship");
+    }
 
-        let task1_result = server.handle_create_task(ai_interface, &task1_args).await;
-        let _task1_id = task1_result["data"]["task_id"].as_str().unwrap().to_string();
+    #[tokio::test]
+    async fn test_socket_shutdown_answers_in_flight_request() {
+        let (temp_dir, storage_path) = create_test_storage();
+        let (mut server, storage) = create_test_server_sharing_storage(&storage_path).await;
+        let socket_path = temp_dir.path().join("devman.sock");
+        let (trigger, shutdown) = tokio::sync::oneshot::channel::<()>();
+
+        let client = async {
+            let mut stream = loop {
+                match UnixStream::connect(&socket_path).await {
+                    Ok(stream) => break stream,
+                    Err(_) => tokio::time::sleep(std::time::Duration::from_millis(10)).await,
+                }
+            };
 
-        // Create second task that depends on first
-        let task2_args = json!({
-            "title": "Dependency Task 2",
-            "description": "Second task that depends on task 1"
+            // Holding the storage lock keeps the request in flight
+            let guard = storage.lock().await;
+            let request = json!({
+                "jsonrpc": "2.0",
+                "id": 7,
+                "method": "tools/call",
+                "params": {"name": "devman_list_tasks", "arguments": {}}
+            });
+            stream.write_all(format!("{}\n", request).as_bytes()).await.unwrap();
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            trigger.send(()).unwrap();
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            drop(guard);
+
+            let mut response = String::new();
+            BufReader::new(stream).read_line(&mut response).await.unwrap();
+            response
+        };
+        let serve = server.start_with_socket_until(&socket_path, async {
+            let _ = shutdown.await;
         });
 
-        let task2_result = server.handle_create_task(ai_interface, &task2_args).await;
-        let _task2_id = task2_result["data"]["task_id"].as_str().unwrap().to_string();
-
-        // List both tasks
-        let list_args = json!({});
-        let list_result = server.handle_list_tasks(ai_interface, &list_args).await;
-        let tasks = list_result["data"]["tasks"].as_array().unwrap();
-        assert_eq!(tasks.len(), 2);
-
-        // Verify both tasks are in Queued state
-        assert_eq!(tasks[0]["status"], "Queued");
-        assert_eq!(tasks[1]["status"], "Queued");
+        let (served, response) = tokio::join!(serve, client);
+        served.unwrap();
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(response["id"], 7);
+        assert!(response["result"].is_object(), "{}", response);
+        assert!(!socket_path.exists());
+        assert!(!server.running);
     }
 
     #[tokio::test]
-    async fn test_e2e_task_filter_by_status() {
+    async fn test_e2e_archive_task() {
         let (_temp_dir, storage_path) = create_test_storage();
         let server = create_test_server(&storage_path).await;
-
         let ai_interface = server.ai_interface.as_ref().unwrap();
 
-        // Create multiple tasks with different implied states
-        for i in 1..=3 {
-            let args = json!({
-                "title": format!("Filter Test Task {}", i),
-                "description": format!("Description for task {}", i)
-            });
-            server.handle_create_task(ai_interface, &args).await;
+        let mut ids = Vec::new();
+        for title in ["Keep", "Archive me"] {
+            let result = server
+                .handle_create_task(ai_interface, &json!({"title": title, "description": title}))
+                .await;
+            ids.push(result["data"]["task_id"].as_str().unwrap().to_string());
         }
 
-        // List all tasks
-        let all_args = json!({});
-        let all_result = server.handle_list_tasks(ai_interface, &all_args).await;
-        let all_tasks = all_result["data"]["tasks"].as_array().unwrap();
-        assert_eq!(all_tasks.len(), 3);
+        let result = server.handle_set_task_archived(ai_interface, &json!({"task_id": ids[1]}), true).await;
+        assert!(result["success"].as_bool().unwrap());
+        assert_eq!(result["data"]["archived"], true);
 
-        // Filter by Queued state
-        let queued_args = json!({ "state": "Queued" });
-        let queued_result = server.handle_list_tasks(ai_interface, &queued_args).await;
-        let queued_tasks = queued_result["data"]["tasks"].as_array().unwrap();
-        assert_eq!(queued_tasks.len(), 3);
+        // Hidden by default, visible with the flag
+        let listed = server.handle_list_tasks(ai_interface, &json!({})).await;
+        let tasks = listed["data"]["tasks"].as_array().unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0]["title"], "Keep");
 
-        // Filter by non-existent state
-        let done_args = json!({ "state": "Completed" });
-        let done_result = server.handle_list_tasks(ai_interface, &done_args).await;
-        let done_tasks = done_result["data"]["tasks"].as_array().unwrap();
-        assert_eq!(done_tasks.len(), 0);
+        let listed = server.handle_list_tasks(ai_interface, &json!({"include_archived": true})).await;
+        let tasks = listed["data"]["tasks"].as_array().unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert!(tasks.iter().any(|t| t["task_id"] == ids[1].as_str() && t["archived"] == true));
+
+        let result = server.handle_set_task_archived(ai_interface, &json!({"task_id": ids[1]}), false).await;
+        assert_eq!(result["data"]["archived"], false);
+        let listed = server.handle_list_tasks(ai_interface, &json!({})).await;
+        assert_eq!(listed["data"]["total_count"], 2);
+
+        // Hard delete needs an explicit force flag
+        let task_id: devman_core::TaskId = ids[0].parse().unwrap();
+        assert!(ai_interface.delete_task(task_id, false).await.is_err());
+        assert!(ai_interface.get_task(task_id).await.is_some());
+        ai_interface.delete_task(task_id, true).await.unwrap();
+        assert!(ai_interface.get_task(task_id).await.is_none());
     }
 
     #[tokio::test]
-    async fn test_e2e_task_list_with_limit() {
+    async fn test_delete_task_unlinks_references() {
+        use devman_storage::Storage;
+
         let (_temp_dir, storage_path) = create_test_storage();
         let server = create_test_server(&storage_path).await;
-
         let ai_interface = server.ai_interface.as_ref().unwrap();
 
-        // Create 5 tasks
-        for i in 1..=5 {
-            let args = json!({
-                "title": format!("Limit Test Task {}", i),
-                "description": format!("Task {}", i)
-            });
-            server.handle_create_task(ai_interface, &args).await;
+        let mut ids = HashMap::new();
+        for title in ["Parent", "Dependent", "Absorbed"] {
+            let created = server.handle_create_task(ai_interface, &json!({"title": title})).await;
+            ids.insert(title, created["data"]["task_id"].as_str().unwrap().parse::<devman_core::TaskId>().unwrap());
+        }
+        for (parent, title) in [("Parent", "Doomed"), ("Parent", "Finished"), ("Doomed", "Grandchild")] {
+            let args = json!({"parent_id": ids[parent].to_string(), "title": title});
+            let created = server.handle_create_subtask(ai_interface, &args).await;
+            ids.insert(title, created["data"]["task_id"].as_str().unwrap().parse().unwrap());
+        }
+        let (doomed, done, grandchild) = (ids["Doomed"], ids["Finished"], ids["Grandchild"]);
+        ai_interface.merge_tasks(doomed, ids["Absorbed"]).await.unwrap();
+        {
+            let mut storage = devman_storage::JsonStorage::new(&storage_path).await.unwrap();
+            storage.link_tasks(ids["Dependent"], doomed).await.unwrap();
+            let mut finished = storage.require_task(done).await.unwrap();
+            finished.status = devman_core::TaskStatus::Done;
+            storage.save_task(&finished).await.unwrap();
         }
 
-        // List with limit
-        let limit_args = json!({ "limit": 3 });
-        let limit_result = server.handle_list_tasks(ai_interface, &limit_args).await;
-        let tasks = limit_result["data"]["tasks"].as_array().unwrap();
-        // Note: Limit may be advisory in some implementations
-        assert!(tasks.len() <= 5);
-    }
-
-    #[tokio::test]
-    async fn test_e2e_get_context() {
-        let (_temp_dir, storage_path) = create_test_storage();
-        let server = create_test_server(&storage_path).await;
-
-        // Get current context
-        let context_result = server.handle_get_context(server.ai_interface.as_ref()).await;
+        ai_interface.delete_task(doomed, true).await.unwrap();
 
-        // Handle placeholder response
-        assert!(context_result["success"].as_bool().unwrap());
-        assert!(context_result["data"].is_object() || context_result["data"].is_null());
+        let parent = ai_interface.get_task(ids["Parent"]).await.unwrap();
+        assert_eq!(parent.children, vec![done]);
+        assert_eq!(parent.progress.percentage, 100.0);
+        assert_eq!(ai_interface.get_task(grandchild).await.unwrap().parent, None);
+        assert!(ai_interface.get_task(ids["Dependent"]).await.unwrap().depends_on.is_empty());
+        assert_eq!(ai_interface.get_task(ids["Absorbed"]).await.unwrap().merged_into, None);
     }
 
     #[tokio::test]
-    async fn test_e2e_confirm_knowledge_reviewed() {
+    async fn test_set_goal_status_persists_history() {
+        use devman_core::GoalStatus;
+
         let (_temp_dir, storage_path) = create_test_storage();
         let server = create_test_server(&storage_path).await;
-
         let ai_interface = server.ai_interface.as_ref().unwrap();
 
-        // Create a task
-        let create_args = json!({
-            "title": "Knowledge Review Test",
-            "description": "Testing knowledge review confirmation"
-        });
-
-        let create_result = server.handle_create_task(ai_interface, &create_args).await;
-        let task_id = create_result["data"]["task_id"].as_str().unwrap().to_string();
+        let goal = ai_interface
+            .create_goal(crate::GoalSpec {
+                title: "Ship v1".to_string(),
+                description: String::new(),
+                success_criteria: Vec::new(),
+                project_id: None,
+            })
+            .await
+            .unwrap();
 
-        // Simulate knowledge review confirmation
-        // Note: In a real implementation, this would update task state
-        let review_args = json!({
-            "task_id": task_id,
-            "knowledge_ids": ["01JHC0V2B3C4D5E6F7G8H9J0K"]
-        });
+        ai_interface.set_goal_status(goal.id, GoalStatus::Completed).await.unwrap();
+        let err = ai_interface.set_goal_status(goal.id, GoalStatus::Active).await.unwrap_err();
+        assert!(err.downcast_ref::<devman_core::TransitionError>().is_some());
 
-        let review_result = server.handle_confirm_knowledge_reviewed(&review_args).await;
-        // Result depends on implementation - should not error
-        assert!(review_result.is_object());
+        ai_interface.reopen_goal(goal.id).await.unwrap();
+        let stored = ai_interface.get_goal(goal.id).await.unwrap();
+        assert_eq!(stored.status, GoalStatus::Active);
+        assert_eq!(stored.status_history.len(), 2);
     }
 }
@@ -0,0 +1,156 @@
+//! Lightweight validation of tool arguments against their input schema.
+//!
+//! Handlers read arguments leniently, so a missing or mistyped field would
+//! otherwise turn into an empty string or a default further down. This
+//! checks the subset of JSON Schema the tool definitions use — `type`,
+//! `required`, `enum`, `properties`, `items` and `minimum` — and ignores
+//! every other keyword. Properties not named by the schema are allowed, and
+//! an optional property set to `null` counts as absent.
+
+use serde_json::Value;
+
+/// First place where arguments do not match a schema.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{path}: {message}")]
+pub struct SchemaViolation {
+    /// Where the violation is, as a dotted path from the arguments root
+    /// (`$` for the root itself)
+    pub path: String,
+    /// What is wrong
+    pub message: String,
+}
+
+/// Check `value` against `schema`.
+pub fn validate(schema: &Value, value: &Value) -> Result<(), SchemaViolation> {
+    check(schema, value, "$")
+}
+
+fn check(schema: &Value, value: &Value, path: &str) -> Result<(), SchemaViolation> {
+    let violation = |message: String| SchemaViolation { path: path.to_string(), message };
+
+    if let Some(expected) = schema.get("type") {
+        let allowed: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|t| has_type(value, t)) {
+            return Err(violation(format!("expected {}, got {}", allowed.join(" or "), type_name(value))));
+        }
+    }
+
+    if let Some(Value::Array(options)) = schema.get("enum") {
+        if !options.contains(value) {
+            let options: Vec<String> = options.iter().map(Value::to_string).collect();
+            return Err(violation(format!("expected one of {}, got {}", options.join(", "), value)));
+        }
+    }
+
+    if let (Some(minimum), Some(number)) = (schema.get("minimum").and_then(Value::as_f64), value.as_f64()) {
+        if number < minimum {
+            return Err(violation(format!("must be at least {}, got {}", minimum, number)));
+        }
+    }
+
+    if let Value::Object(fields) = value {
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for name in required.iter().filter_map(Value::as_str) {
+                if fields.get(name).is_none_or(Value::is_null) {
+                    return Err(SchemaViolation {
+                        path: join(path, name),
+                        message: "required field is missing".to_string(),
+                    });
+                }
+            }
+        }
+        if let Some(Value::Object(properties)) = schema.get("properties") {
+            for (name, property) in properties {
+                match fields.get(name) {
+                    None | Some(Value::Null) => {}
+                    Some(field) => check(property, field, &join(path, name))?,
+                }
+            }
+        }
+    }
+
+    if let (Value::Array(items), Some(item_schema)) = (value, schema.get("items")) {
+        for (i, item) in items.iter().enumerate() {
+            check(item_schema, item, &format!("{}[{}]", path, i))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn join(path: &str, name: &str) -> String {
+    if path == "$" {
+        name.to_string()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        // Unknown type names are not ours to enforce
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_reports_first_violation_with_path() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "priority": {"type": "string", "enum": ["low", "high"]},
+                "steps": {"type": "array", "items": {"type": "object", "required": ["tool"]}},
+                "limit": {"type": "integer", "minimum": 1}
+            },
+            "required": ["priority"]
+        });
+
+        assert!(validate(&schema, &json!({"priority": "low", "extra": 1, "limit": null})).is_ok());
+        assert_eq!(
+            validate(&schema, &json!({})).unwrap_err().to_string(),
+            "priority: required field is missing"
+        );
+        assert_eq!(
+            validate(&schema, &json!({"priority": "urgent"})).unwrap_err().to_string(),
+            r#"priority: expected one of "low", "high", got "urgent""#
+        );
+        assert_eq!(
+            validate(&schema, &json!({"priority": "low", "steps": [{"tool": "cargo"}, {}]})).unwrap_err().path,
+            "steps[1].tool"
+        );
+        assert_eq!(
+            validate(&schema, &json!({"priority": "low", "limit": 1.5})).unwrap_err().to_string(),
+            "limit: expected integer, got number"
+        );
+        assert!(validate(&schema, &json!({"priority": "low", "limit": 0})).is_err());
+        assert!(validate(&schema, &json!("low")).is_err());
+    }
+}
@@ -1,6 +1,7 @@
 //! Task state validation and transition logic.
 
 use devman_core::{TaskState, TaskId, StateTransition};
+use crate::locale::MessageCatalog;
 
 /// Context for state transitions.
 pub struct TransitionContext {
@@ -77,9 +78,9 @@ impl TaskStateValidator {
                 StateTransition::Allowed
             }
 
-            // Normal flow: QualityCompleted (passed) → Completed
+            // Normal flow: QualityCompleted (passed, warnings allowed) → Completed
             (TaskState::QualityCompleted { result, .. }, TaskState::Completed { .. }) => {
-                if matches!(result.overall_status, devman_core::TaskQualityOverallStatus::Passed) {
+                if result.overall_status.allows_completion() {
                     StateTransition::Allowed
                 } else {
                     StateTransition::RejectedRequiredAction {
@@ -144,7 +145,7 @@ impl TaskStateValidator {
     }
 
     fn get_guidance_for_state(state: &TaskState) -> String {
-        state.get_guidance().to_string()
+        MessageCatalog::default().state_guidance(state).to_string()
     }
 }
 
@@ -273,24 +274,24 @@ mod tests {
     fn test_quality_passed_to_completed() {
         let context = make_context("test_ai");
 
-        let quality_completed = TaskState::QualityCompleted {
-            result: TaskQualityCheckResult {
-                overall_status: TaskQualityOverallStatus::Passed,
-                findings_count: 0,
-                warnings_count: 0,
-            },
-            completed_at: Utc::now(),
-        };
-
         let completed = TaskState::Completed {
             completed_at: Utc::now(),
             completed_by: "test".to_string(),
         };
 
-        assert!(matches!(
-            TaskStateValidator::validate_transition(&quality_completed, &completed, &context),
-            StateTransition::Allowed
-        ));
+        // Warnings from advisory checks do not block completion
+        for (overall_status, warnings_count) in
+            [(TaskQualityOverallStatus::Passed, 0), (TaskQualityOverallStatus::PassedWithWarnings, 1)]
+        {
+            let quality_completed = TaskState::QualityCompleted {
+                result: TaskQualityCheckResult { overall_status, findings_count: 0, warnings_count },
+                completed_at: Utc::now(),
+            };
+            assert!(matches!(
+                TaskStateValidator::validate_transition(&quality_completed, &completed, &context),
+                StateTransition::Allowed
+            ));
+        }
     }
 
     #[test]
@@ -0,0 +1,285 @@
+//! Environment diagnostics for `devman doctor`.
+//!
+//! Each check prints one pass/warn/fail line, so a new user can see why
+//! storage, quality checks or vector search will not work before hitting a
+//! confusing failure deep inside another command.
+
+use devman_core::{BuildTool, Project, TestFramework};
+use devman_knowledge::OllamaEmbeddingClient;
+use devman_storage::{JsonStorage, Storage};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// Outcome of one check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    /// Works as expected
+    Pass,
+    /// Works, but something is likely misconfigured
+    Warn,
+    /// Broken; commands relying on it will fail
+    Fail,
+}
+
+impl CheckStatus {
+    fn symbol(self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "✓",
+            CheckStatus::Warn => "⚠",
+            CheckStatus::Fail => "✗",
+        }
+    }
+}
+
+/// One diagnostic line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Check {
+    /// What was checked
+    pub name: String,
+    /// Outcome
+    pub status: CheckStatus,
+    /// Details, such as the path found or the error hit
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self { name: name.into(), status, detail: detail.into() }
+    }
+
+    /// Human-readable line for the table output.
+    pub fn line(&self) -> String {
+        format!("{} {}: {}", self.status.symbol(), self.name, self.detail)
+    }
+}
+
+/// What the doctor inspects.
+pub struct Environment {
+    /// Storage directory
+    pub storage_path: PathBuf,
+    /// Search path for build and test tools, as in `PATH`
+    pub path: OsString,
+    /// Ollama server to check; `None` when vector search is not enabled
+    pub ollama_url: Option<String>,
+}
+
+/// Run every check against `env`.
+pub async fn diagnose(env: &Environment) -> Vec<Check> {
+    let mut checks = vec![check_writable(&env.storage_path).await];
+    if checks[0].status == CheckStatus::Fail {
+        return checks;
+    }
+
+    let projects = match JsonStorage::new(&env.storage_path).await {
+        Ok(storage) => match backend_summary(&storage).await {
+            Ok((summary, projects)) => {
+                checks.push(Check::new("存储后端", CheckStatus::Pass, summary));
+                projects
+            }
+            Err(e) => {
+                checks.push(Check::new("存储后端", CheckStatus::Fail, format!("读取 JSON 存储失败: {}", e)));
+                Vec::new()
+            }
+        },
+        Err(e) => {
+            checks.push(Check::new("存储后端", CheckStatus::Fail, format!("打开 JSON 存储失败: {}", e)));
+            Vec::new()
+        }
+    };
+
+    if projects.is_empty() {
+        checks.push(Check::new(
+            "项目配置",
+            CheckStatus::Warn,
+            "未找到项目，按默认的 cargo 工具链检查",
+        ));
+    }
+    for tool in required_tools(&projects) {
+        checks.push(match find_on_path(&tool, &env.path) {
+            Some(found) => Check::new(format!("工具 {}", tool), CheckStatus::Pass, found.display().to_string()),
+            None => Check::new(format!("工具 {}", tool), CheckStatus::Fail, "未在 PATH 中找到"),
+        });
+    }
+
+    if let Some(url) = &env.ollama_url {
+        let client = OllamaEmbeddingClient::new(url.clone(), String::new());
+        checks.push(match client.health_check().await {
+            Ok(true) => Check::new("Ollama", CheckStatus::Pass, format!("{} 可访问", url)),
+            Ok(false) => Check::new("Ollama", CheckStatus::Fail, format!("{} 返回错误状态", url)),
+            Err(e) => Check::new("Ollama", CheckStatus::Fail, format!("{} 无法访问: {:#}", url, e)),
+        });
+    }
+
+    checks
+}
+
+/// Whether files can be created in the storage directory.
+async fn check_writable(dir: &Path) -> Check {
+    let probe = dir.join(".doctor-probe");
+    let result = async {
+        tokio::fs::create_dir_all(dir).await?;
+        tokio::fs::write(&probe, b"ok").await?;
+        tokio::fs::remove_file(&probe).await
+    }
+    .await;
+
+    match result {
+        Ok(()) => Check::new("存储可写", CheckStatus::Pass, dir.display().to_string()),
+        Err(e) => Check::new("存储可写", CheckStatus::Fail, format!("{}: {}", dir.display(), e)),
+    }
+}
+
+/// Entity counts of the store, and its projects.
+async fn backend_summary(storage: &JsonStorage) -> anyhow::Result<(String, Vec<Project>)> {
+    let goals = storage.list_goals().await?.len();
+    let tasks = storage.list_tasks(&Default::default()).await?.len();
+    let projects = storage.list_projects().await?;
+    let summary = format!("JSON 存储正常 ({} 个目标, {} 个任务, {} 个项目)", goals, tasks, projects.len());
+    Ok((summary, projects))
+}
+
+/// Commands the projects' build, test, lint and format steps run, in order
+/// of first appearance; `cargo` when there are no projects.
+fn required_tools(projects: &[Project]) -> Vec<String> {
+    if projects.is_empty() {
+        return vec!["cargo".to_string()];
+    }
+
+    let mut tools: Vec<String> = Vec::new();
+    for project in projects {
+        let config = &project.config.tools;
+        let build = match config.build {
+            BuildTool::Cargo => "cargo",
+            BuildTool::Npm => "npm",
+            BuildTool::Yarn => "yarn",
+            BuildTool::Make => "make",
+            BuildTool::Gradle => "gradle",
+            BuildTool::Maven => "mvn",
+        };
+        let test = match config.test_framework {
+            TestFramework::Rust => "cargo",
+            TestFramework::Jest => "npx",
+            TestFramework::Pytest => "pytest",
+            TestFramework::GoTest => "go",
+        };
+        let names = [build, test]
+            .into_iter()
+            .map(str::to_string)
+            .chain(config.linters.iter().cloned())
+            .chain(config.formatters.iter().cloned());
+        for name in names {
+            if !tools.contains(&name) {
+                tools.push(name);
+            }
+        }
+    }
+    tools
+}
+
+/// First executable named `tool` in the directories of `path`.
+fn find_on_path(tool: &str, path: &OsString) -> Option<PathBuf> {
+    std::env::split_paths(path)
+        .map(|dir| dir.join(tool))
+        .find(|candidate| is_executable(candidate))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file() || path.with_extension("exe").is_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(build: BuildTool, linters: &[&str]) -> Project {
+        Project {
+            id: devman_core::ProjectId::new(),
+            name: "Demo".to_string(),
+            description: String::new(),
+            config: devman_core::ProjectConfig {
+                tech_stack: vec![],
+                structure: devman_core::DirStructure { dirs: vec![], conventions: vec![] },
+                quality_profile: devman_core::QualityProfileId::new(),
+                default_task_profile: None,
+                tools: devman_core::ToolConfig {
+                    build,
+                    test_framework: TestFramework::Rust,
+                    linters: linters.iter().map(|l| l.to_string()).collect(),
+                    formatters: vec![],
+                },
+            },
+            phases: vec![],
+            current_phase: devman_core::PhaseId::new(),
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    /// A `PATH` holding only an executable named `cargo`.
+    fn path_with_cargo(dir: &Path) -> OsString {
+        let cargo = dir.join("cargo");
+        std::fs::write(&cargo, "#!/bin/sh\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&cargo, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        dir.as_os_str().to_owned()
+    }
+
+    fn status_of<'a>(checks: &'a [Check], name: &str) -> Option<&'a Check> {
+        checks.iter().find(|c| c.name == name)
+    }
+
+    #[tokio::test]
+    async fn test_reports_missing_tool() {
+        let storage_dir = tempfile::tempdir().unwrap();
+        let bin_dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(storage_dir.path()).await.unwrap();
+        storage.save_project(&project(BuildTool::Npm, &["eslint"])).await.unwrap();
+
+        let checks = diagnose(&Environment {
+            storage_path: storage_dir.path().to_path_buf(),
+            path: path_with_cargo(bin_dir.path()),
+            ollama_url: None,
+        })
+        .await;
+
+        assert_eq!(status_of(&checks, "存储可写").unwrap().status, CheckStatus::Pass);
+        assert_eq!(status_of(&checks, "存储后端").unwrap().status, CheckStatus::Pass);
+        assert_eq!(status_of(&checks, "工具 cargo").unwrap().status, CheckStatus::Pass);
+        assert_eq!(status_of(&checks, "工具 npm").unwrap().status, CheckStatus::Fail);
+        assert_eq!(status_of(&checks, "工具 eslint").unwrap().status, CheckStatus::Fail);
+        assert!(status_of(&checks, "Ollama").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_reports_unwritable_storage() {
+        // A regular file where the storage directory should be can never
+        // be written to, whichever user runs the test
+        let dir = tempfile::tempdir().unwrap();
+        let blocked = dir.path().join("not-a-dir");
+        std::fs::write(&blocked, "").unwrap();
+
+        let checks = diagnose(&Environment {
+            storage_path: blocked.join(".devman"),
+            path: path_with_cargo(dir.path()),
+            ollama_url: None,
+        })
+        .await;
+
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].name, "存储可写");
+        assert_eq!(checks[0].status, CheckStatus::Fail);
+    }
+}
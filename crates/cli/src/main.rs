@@ -1,19 +1,56 @@
 //! DevMan CLI - AI认知工作管理系统命令行工具
 
 use anyhow::Result;
-use clap::{Parser, Subcommand};
-use tracing::{info, Level};
-use devman_core::{Goal, GoalId};
-use devman_storage::{JsonStorage, Storage};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use tracing::Level;
+use devman_core::{Goal, GoalId, Task, TaskId};
+use devman_storage::{ConfigStore, JsonStorage, RetentionPolicy, Storage};
+use devman_storage::config::keys;
+use devman_knowledge::{BasicKnowledgeService, KnowledgeService, PrunePolicy};
+use futures::StreamExt;
+
+mod doctor;
 
 #[derive(Parser)]
 #[command(name = "devman")]
 #[command(about = "AI认知工作管理系统", long_about = None)]
 struct Cli {
+    /// 输出格式：table 供人阅读，json/yaml 供脚本处理
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Table)]
+    output: OutputFormat,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+/// How command results are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// 人类可读的文本
+    Table,
+    /// JSON
+    Json,
+    /// YAML
+    Yaml,
+}
+
+/// Formats of `devman export-work`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ExportFormat {
+    /// 每行一个 JSON 对象
+    Ndjson,
+}
+
+/// Formats of `devman metrics`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum MetricsFormat {
+    /// Prometheus 文本格式
+    Prometheus,
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// 创建新目标
@@ -25,18 +62,307 @@ enum Commands {
     ListGoals,
     /// 显示目标详情
     ShowGoal { id: String },
+    /// 汇总目标下各任务最近一次质检：问题数、平均覆盖率和未通过质量门的任务
+    GoalQuality { id: String },
+    /// 汇总目标、任务和未解决阻塞的状态
+    Status,
+    /// 任务操作
+    Task {
+        #[command(subcommand)]
+        command: TaskCommands,
+    },
+    /// 知识库操作
+    Knowledge {
+        #[command(subcommand)]
+        command: KnowledgeCommands,
+    },
+    /// 查看或修改运行时配置
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+    /// 压缩存储，清理孤立文件
+    Compact,
+    /// 修复任务依赖图，使 depends_on 与 blocks 双向一致
+    RepairDeps,
+    /// 按保留策略归档并删除过旧的事件和工作记录
+    PruneHistory {
+        /// 仅显示将被清理的记录，不做修改
+        #[arg(long)]
+        dry_run: bool,
+        /// 保留最近该天数内的记录
+        #[arg(long, default_value_t = 90)]
+        keep_days: u32,
+        /// 每个任务至少保留的最新记录条数
+        #[arg(long, default_value_t = 20)]
+        keep_per_task: usize,
+    },
+    /// 查看 MCP 工具调用审计日志的最近记录
+    Audit {
+        /// 显示的记录条数
+        #[arg(short = 'n', long, default_value_t = 20)]
+        lines: usize,
+    },
+    /// 逐条导出全部工作记录，供分析系统导入
+    ExportWork {
+        /// 导出格式
+        #[arg(long, value_enum, default_value_t = ExportFormat::Ndjson)]
+        format: ExportFormat,
+    },
+    /// 输出任务、阻塞、质检通过率和周期时间等监控指标
+    Metrics {
+        /// 输出格式
+        #[arg(long, value_enum, default_value_t = MetricsFormat::Prometheus)]
+        format: MetricsFormat,
+    },
+    /// 诊断运行环境：存储、构建/测试工具和 Ollama
+    Doctor {
+        /// 启用向量检索时使用的 Ollama 地址，提供时检查是否可访问
+        #[arg(long)]
+        ollama_url: Option<String>,
+    },
+    /// 将一个存储完整复制到另一个存储，如从 JSON 迁移到 SQLite
+    Migrate {
+        /// 源存储：目录为 JSON 存储，.db/.sqlite/.sqlite3 文件为 SQLite
+        #[arg(long)]
+        from: std::path::PathBuf,
+        /// 目标存储，按同样规则识别
+        #[arg(long)]
+        to: std::path::PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum TaskCommands {
+    /// 列出所有任务
+    List {
+        /// 只列出没有父任务的顶层任务
+        #[arg(long)]
+        roots_only: bool,
+        /// 按层级列出：顶层任务下缩进显示其子任务
+        #[arg(long)]
+        tree: bool,
+    },
+    /// 显示任务详情
+    Show {
+        id: String,
+        /// 同时按时间顺序显示工作记录、事件、质检结果和备注
+        #[arg(long)]
+        with_history: bool,
+    },
+    /// 拆分任务：在父任务下创建子任务
+    Split {
+        parent_id: String,
+        title: String,
+        #[arg(default_value = "")]
+        description: String,
+        /// 截止时间（RFC 3339），临近时提升优先级
+        #[arg(long)]
+        due: Option<chrono::DateTime<chrono::Utc>>,
+        /// 即使已有相似的未完成任务也创建
+        #[arg(long)]
+        allow_duplicate: bool,
+    },
+    /// 按解除阻塞的任务数和截止时间重新计算未完成任务的优先级
+    Reprioritize {
+        /// 仅显示将变化的优先级，不做修改
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// 将任务保存为模板，供重复创建同类任务
+    SaveTemplate {
+        id: String,
+        /// 模板名称
+        name: String,
+        /// 占位参数 NAME=VALUE：任务文本中的 VALUE 替换为 {{NAME}}，可重复
+        #[arg(long = "param", value_parser = parse_placeholder)]
+        params: Vec<(String, String)>,
+    },
+}
+
+/// Split a `NAME=VALUE` placeholder argument.
+fn parse_placeholder(arg: &str) -> std::result::Result<(String, String), String> {
+    match arg.split_once('=') {
+        Some((name, value)) if !name.is_empty() && !value.is_empty() => Ok((name.to_string(), value.to_string())),
+        _ => Err(format!("应为 NAME=VALUE 形式: {}", arg)),
+    }
+}
+
+#[derive(Subcommand)]
+enum KnowledgeCommands {
+    /// 归档长期未使用的低质量知识，或按上限保留排名最高的知识
+    Prune {
+        /// 仅显示将被归档的知识，不做修改
+        #[arg(long)]
+        dry_run: bool,
+        /// 超过该天数未使用视为陈旧
+        #[arg(long, default_value_t = 90)]
+        unused_days: i64,
+        /// 陈旧且质量分低于该值的知识会被归档
+        #[arg(long, default_value_t = 0.5)]
+        min_quality: f32,
+        /// 最多保留的知识条数
+        #[arg(long)]
+        max_items: Option<usize>,
+    },
+    /// 统计知识库：按类型、标签、向量化覆盖率和反馈评分
+    Stats,
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// 显示配置项的值，不指定 KEY 时列出全部配置
+    Get { key: Option<String> },
+    /// 设置配置项：VALUE 按 JSON 解析，解析失败时视为字符串
+    Set { key: String, value: String },
+}
+
+/// Parse a setting given on the command line: JSON when it is valid JSON,
+/// so `30` and `true` keep their types, and a plain string otherwise.
+fn parse_config_value(value: &str) -> serde_json::Value {
+    serde_json::from_str(value).unwrap_or_else(|_| serde_json::Value::String(value.to_string()))
+}
+
+/// Check a value for one of the settings DevMan reads against the type its
+/// reader expects, so a bad value is refused instead of silently ignored.
+/// Other keys are stored as given.
+fn check_config_value(key: &str, value: &serde_json::Value) -> anyhow::Result<()> {
+    fn fits<T: serde::de::DeserializeOwned>(value: &serde_json::Value) -> serde_json::Result<()> {
+        serde_json::from_value::<T>(value.clone()).map(|_| ())
+    }
+
+    let checked = match key {
+        keys::QUALITY_CHECK_TIMEOUT_SECS => fits::<u64>(value),
+        keys::KNOWLEDGE_EMBEDDING_MODEL => fits::<devman_core::EmbeddingModel>(value),
+        keys::KNOWLEDGE_MAX_CONCURRENCY => fits::<usize>(value),
+        keys::PROGRESS_PRICE_TABLE => fits::<devman_progress::PriceTable>(value),
+        _ => Ok(()),
+    };
+    checked.map_err(|e| anyhow::anyhow!("配置项 {} 的值无效: {}", key, e))
+}
+
+/// Counts reported by `devman status`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StatusSummary {
+    /// Goals per status
+    goals: BTreeMap<String, usize>,
+    /// Non-archived tasks per status
+    tasks: BTreeMap<String, usize>,
+    /// Recorded blockers not yet resolved
+    open_blockers: usize,
+}
+
+/// A priority changed by `devman task reprioritize`.
+#[derive(Debug, Serialize)]
+struct PriorityChange {
+    /// Task ID
+    task_id: TaskId,
+    /// Priority before
+    from: u8,
+    /// Recomputed priority
+    to: u8,
+}
+
+/// A task with its timeline, reported by `devman task show --with-history`.
+#[derive(Debug, Serialize)]
+struct TaskWithHistory<'a> {
+    /// The task, if it exists
+    task: &'a Option<Task>,
+    /// Timeline entries, oldest first
+    history: &'a [devman_ai::TimelineEntry],
+}
+
+/// Write one timeline entry as a human-readable line.
+fn write_timeline_entry(out: &mut dyn Write, entry: &devman_ai::TimelineEntry) -> std::io::Result<()> {
+    use devman_ai::TimelineEntry;
+    let at = entry.timestamp().format("%Y-%m-%d %H:%M:%S");
+    match entry {
+        TimelineEntry::StateTransition { action, result, .. } => writeln!(out, "    {} [事件] {}: {}", at, action, result),
+        TimelineEntry::WorkLog { event_type, description, .. } => {
+            writeln!(out, "    {} [工作] {:?}: {}", at, event_type, description)
+        }
+        TimelineEntry::QualityCheck { passed, description, .. } => {
+            writeln!(out, "    {} [质检] {} {}", at, if *passed { "✓" } else { "✗" }, description)
+        }
+        TimelineEntry::Note { text, .. } => writeln!(out, "    {} [备注] {}", at, text),
+    }
+}
+
+/// Write `value` as JSON or YAML, or run `table` for the human format.
+fn emit<T: Serialize>(
+    out: &mut dyn Write,
+    format: OutputFormat,
+    value: &T,
+    table: impl FnOnce(&mut dyn Write) -> std::io::Result<()>,
+) -> Result<()> {
+    match format {
+        OutputFormat::Table => table(out)?,
+        OutputFormat::Json => writeln!(out, "{}", serde_json::to_string_pretty(value)?)?,
+        OutputFormat::Yaml => write!(out, "{}", serde_yaml::to_string(value)?)?,
+    }
+    Ok(())
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    // Logs go to stderr so structured output on stdout stays parseable
     tracing_subscriber::fmt()
         .with_max_level(Level::INFO)
+        .with_writer(std::io::stderr)
         .init();
 
     let cli = Cli::parse();
     let storage_path = std::path::PathBuf::from(".devman");
-    let mut storage = JsonStorage::new(&storage_path).await?;
+    run(cli, &storage_path, &mut std::io::stdout().lock()).await
+}
+
+/// Execute `cli` against the store at `storage_path`, writing results to `out`.
+async fn run(cli: Cli, storage_path: &std::path::Path, out: &mut dyn Write) -> Result<()> {
+    let format = cli.output;
+
+    // The doctor must run even when the store cannot be opened
+    if let Commands::Doctor { ollama_url } = cli.command {
+        let checks = doctor::diagnose(&doctor::Environment {
+            storage_path: storage_path.to_path_buf(),
+            path: std::env::var_os("PATH").unwrap_or_default(),
+            ollama_url,
+        })
+        .await;
+        emit(out, format, &checks, |out| {
+            for check in &checks {
+                writeln!(out, "{}", check.line())?;
+            }
+            Ok(())
+        })?;
+        let failed = checks.iter().filter(|c| c.status == doctor::CheckStatus::Fail).count();
+        if failed > 0 {
+            anyhow::bail!("{} 项检查失败", failed);
+        }
+        return Ok(());
+    }
 
+    // Both stores are named explicitly, so the default one is not opened
+    if let Commands::Migrate { from, to } = cli.command {
+        let source = open_store(&from).await?;
+        let mut target = open_store(&to).await?;
+        let report = devman_storage::migrate_store_with_progress(source.as_ref(), target.as_mut(), |p| {
+            tracing::info!("已迁移 {} 个 {}", p.copied, p.entity_type);
+        })
+        .await?;
+        emit(out, format, &report, |out| {
+            for count in &report.entities {
+                let mark = if count.matches() { "✓" } else { "✗" };
+                writeln!(out, "{} {}: {}/{}", mark, count.entity_type, count.target, count.source)?;
+            }
+            writeln!(out, "共 {} 项: {} → {}", report.total(), from.display(), to.display())
+        })?;
+        if !report.verified() {
+            anyhow::bail!("迁移后目标存储的数量与源存储不一致");
+        }
+        return Ok(());
+    }
+
+    let mut storage = JsonStorage::new(storage_path).await?;
     match cli.command {
         Commands::CreateGoal { title, description } => {
             let goal = Goal {
@@ -48,39 +374,978 @@ async fn main() -> Result<()> {
                 project_id: devman_core::ProjectId::new(),
                 current_phase: devman_core::PhaseId::new(),
                 status: devman_core::GoalStatus::Active,
+                status_history: Vec::new(),
                 created_at: chrono::Utc::now(),
                 updated_at: chrono::Utc::now(),
             };
             storage.save_goal(&goal).await?;
             storage.commit("Create goal").await?;
-            println!("✓ 创建目标: {} - {}", goal.id, goal.title);
+            emit(out, format, &goal, |out| {
+                writeln!(out, "✓ 创建目标: {} - {}", goal.id, goal.title)
+            })?;
         }
 
         Commands::ListGoals => {
             let goals = storage.list_goals().await?;
-            println!("目标 ({}):", goals.len());
-            for goal in &goals {
-                println!("  {} | {} | {} - {}",
-                    goal.id,
-                    format!("{:?}", goal.status),
-                    goal.title,
-                    goal.description);
-            }
+            emit(out, format, &goals, |out| {
+                writeln!(out, "目标 ({}):", goals.len())?;
+                for goal in &goals {
+                    writeln!(out, "  {} | {:?} | {} - {}",
+                        goal.id,
+                        goal.status,
+                        goal.title,
+                        goal.description)?;
+                }
+                Ok(())
+            })?;
         }
 
         Commands::ShowGoal { id } => {
             let goal_id = id.parse()?;
-            if let Some(goal) = storage.load_goal(goal_id).await? {
-                println!("目标: {} - {}", goal.title, goal.description);
-                println!("  状态: {:?}", goal.status);
-                println!("  进度: {:.1}%", goal.progress.percentage);
-                println!("  项目: {}", goal.project_id);
-                println!("  当前阶段: {}", goal.current_phase);
+            let goal = storage.load_goal(goal_id).await?;
+            emit(out, format, &goal, |out| match &goal {
+                Some(goal) => {
+                    writeln!(out, "目标: {} - {}", goal.title, goal.description)?;
+                    writeln!(out, "  状态: {:?}", goal.status)?;
+                    writeln!(out, "  进度: {:.1}%", goal.progress.percentage)?;
+                    writeln!(out, "  项目: {}", goal.project_id)?;
+                    writeln!(out, "  当前阶段: {}", goal.current_phase)
+                }
+                None => writeln!(out, "目标不存在"),
+            })?;
+        }
+
+        Commands::GoalQuality { id } => {
+            let summary = devman_quality::goal_quality_summary(&storage, id.parse()?).await?;
+            emit(out, format, &summary, |out| {
+                let findings = &summary.findings;
+                writeln!(out, "已质检任务: {}/{}", summary.checked_tasks, summary.tasks.len())?;
+                writeln!(out, "问题: {} 严重, {} 错误, {} 警告, {} 提示",
+                    findings.critical,
+                    findings.error,
+                    findings.warning,
+                    findings.info)?;
+                match summary.average_coverage {
+                    Some(coverage) => writeln!(out, "平均覆盖率: {:.1}%", coverage)?,
+                    None => writeln!(out, "平均覆盖率: -")?,
+                }
+                for task in &summary.tasks {
+                    let mark = match (task.run_at, task.gates_failing()) {
+                        (None, _) => "·",
+                        (Some(_), true) => "✗",
+                        (Some(_), false) => "✓",
+                    };
+                    writeln!(out, "  {} {} - {} ({} 个问题)", mark, task.task_id, task.title, task.findings.total())?;
+                }
+                Ok(())
+            })?;
+        }
+
+        Commands::Status => {
+            let mut summary = StatusSummary::default();
+            for goal in storage.list_goals().await? {
+                *summary.goals.entry(format!("{:?}", goal.status)).or_insert(0) += 1;
+            }
+            for task in storage.list_tasks(&Default::default()).await? {
+                if !task.archived {
+                    *summary.tasks.entry(format!("{:?}", task.status)).or_insert(0) += 1;
+                }
+            }
+            summary.open_blockers = storage
+                .list_blockers()
+                .await?
+                .iter()
+                .filter(|b| !b.is_resolved())
+                .count();
+            emit(out, format, &summary, |out| {
+                writeln!(out, "目标 ({}):", summary.goals.values().sum::<usize>())?;
+                for (status, count) in &summary.goals {
+                    writeln!(out, "  {}: {}", status, count)?;
+                }
+                writeln!(out, "任务 ({}):", summary.tasks.values().sum::<usize>())?;
+                for (status, count) in &summary.tasks {
+                    writeln!(out, "  {}: {}", status, count)?;
+                }
+                writeln!(out, "未解决阻塞: {}", summary.open_blockers)
+            })?;
+        }
+
+        Commands::Task { command: TaskCommands::List { roots_only, tree } } => {
+            let filter = devman_core::TaskFilter {
+                roots_only: roots_only || tree,
+                include_subtasks: tree,
+                ..Default::default()
+            };
+            let tasks = storage.list_tasks(&filter).await?;
+            emit(out, format, &tasks, |out| {
+                writeln!(out, "任务 ({}):", tasks.len())?;
+                // In a tree listing every sub-task comes after its parent
+                let mut depths: HashMap<TaskId, usize> = HashMap::new();
+                for task in &tasks {
+                    let depth = match task.parent.and_then(|p| depths.get(&p)) {
+                        Some(parent_depth) if tree => parent_depth + 1,
+                        _ => 0,
+                    };
+                    depths.insert(task.id, depth);
+                    writeln!(out, "  {}{} | {:?} | {:.0}% | {}",
+                        "  ".repeat(depth),
+                        task.id,
+                        task.status,
+                        task.progress.percentage,
+                        task.title)?;
+                }
+                Ok(())
+            })?;
+        }
+
+        Commands::Task { command: TaskCommands::Show { id, with_history } } => {
+            let task_id: TaskId = id.parse()?;
+            let task = storage.load_task(task_id).await?;
+            let history = match (&task, with_history) {
+                (Some(_), true) => Some(devman_ai::task_timeline(&storage, task_id).await?),
+                _ => None,
+            };
+            let table = |out: &mut dyn Write| match &task {
+                Some(task) => {
+                    writeln!(out, "任务: {} - {}", task.title, task.description)?;
+                    writeln!(out, "  状态: {:?}", task.status)?;
+                    writeln!(out, "  进度: {:.1}%", task.progress.percentage)?;
+                    writeln!(out, "  阶段: {}", task.phase_id)?;
+                    if let Some(parent) = task.parent {
+                        writeln!(out, "  父任务: {}", parent)?;
+                    }
+                    writeln!(out, "  子任务: {}", task.children.len())?;
+                    writeln!(out, "  依赖: {}", task.depends_on.len())?;
+                    if let Some(history) = &history {
+                        writeln!(out, "  历史 ({} 条):", history.len())?;
+                        for entry in history {
+                            write_timeline_entry(out, entry)?;
+                        }
+                    }
+                    Ok(())
+                }
+                None => writeln!(out, "任务不存在"),
+            };
+            if with_history {
+                let report = TaskWithHistory { task: &task, history: history.as_deref().unwrap_or_default() };
+                emit(out, format, &report, table)?;
             } else {
-                println!("目标不存在");
+                emit(out, format, &task, table)?;
+            }
+        }
+
+        Commands::Task { command: TaskCommands::Split { parent_id, title, description, due, allow_duplicate } } => {
+            use devman_work::WorkManager;
+
+            let parent_id: TaskId = parent_id.parse()?;
+            let Some(mut parent) = storage.load_task(parent_id).await? else {
+                writeln!(out, "任务不存在")?;
+                return Ok(());
+            };
+            if parent.is_closed() {
+                anyhow::bail!("父任务已{}，不能再拆分", if parent.status == devman_core::TaskStatus::Done { "完成" } else { "放弃" });
+            }
+
+            // The child keeps the parent's priority before any deadline boost
+            let priority = parent.base_priority.unwrap_or(parent.priority);
+            let mut spec = devman_work::TaskSpec {
+                title,
+                description: description.clone(),
+                intent: devman_core::TaskIntent {
+                    natural_language: description,
+                    context: devman_core::TaskContext {
+                        relevant_knowledge: Vec::new(),
+                        similar_tasks: Vec::new(),
+                        affected_files: Vec::new(),
+                    },
+                    success_criteria: Vec::new(),
+                },
+                phase_id: parent.phase_id,
+                quality_gates: Vec::new(),
+                priority: devman_core::Priority::from_value(priority),
+                quality_profile: None,
+                due_at: due,
+            };
+            if !allow_duplicate {
+                // A sub-task naturally resembles the task it was split from
+                let mut tasks = storage.list_tasks(&Default::default()).await?;
+                tasks.retain(|t| t.id != parent.id);
+                if let Some(warning) = devman_work::find_duplicates(&spec, &tasks, devman_work::DEFAULT_DUPLICATE_THRESHOLD) {
+                    let similar: Vec<String> =
+                        warning.candidates.iter().map(|c| format!("{} - {}", c.task_id, c.title)).collect();
+                    anyhow::bail!("已有相似的未完成任务: {}；使用 --allow-duplicate 仍然创建", similar.join(", "));
+                }
+            }
+            devman_ai::apply_default_profile(&storage, &mut spec).await?;
+
+            let mut child = devman_work::BasicWorkManager::new(JsonStorage::new(storage_path).await?)
+                .create_task(spec)
+                .await?;
+            child.priority = priority;
+            parent.add_child(&mut child);
+            storage.save_task(&child).await?;
+            storage.save_task(&parent).await?;
+            let mut created = devman_core::Event::new(devman_core::AgentId::user(), "task_created", format!("{:?}", child.status));
+            created.related_tasks.push(child.id);
+            storage.save_event(&created).await?;
+            devman_ai::refresh_parent_progress(&mut storage, parent.id).await?;
+            storage.commit("Split task").await?;
+            emit(out, format, &child, |out| {
+                writeln!(out, "✓ 创建子任务: {} - {} (父任务: {})", child.id, child.title, parent.id)
+            })?;
+        }
+
+        Commands::Task { command: TaskCommands::Reprioritize { dry_run } } => {
+            let changes: Vec<PriorityChange> = devman_work::BasicWorkManager::new(storage)
+                .recompute_priorities(!dry_run)
+                .await?
+                .into_iter()
+                .map(|(task_id, from, to)| PriorityChange { task_id, from, to })
+                .collect();
+            emit(out, format, &changes, |out| {
+                let verb = if dry_run { "将调整" } else { "已调整" };
+                for change in &changes {
+                    writeln!(out, "{} {}: {} → {}", verb, change.task_id, change.from, change.to)?;
+                }
+                writeln!(out, "共 {} 个任务", changes.len())
+            })?;
+        }
+
+        Commands::Task { command: TaskCommands::SaveTemplate { id, name, params } } => {
+            let task_id: TaskId = id.parse()?;
+            let Some(task) = storage.load_task(task_id).await? else {
+                writeln!(out, "任务不存在")?;
+                return Ok(());
+            };
+
+            let template = devman_core::TaskTemplate::from_task(&task, name, &params);
+            storage.save_task_template(&template).await?;
+            storage.commit("Save task template").await?;
+            emit(out, format, &template, |out| {
+                writeln!(out, "✓ 保存模板: {} - {} ({} 个步骤, {} 个质量门)", template.id, template.name, template.steps.len(), template.quality_gates.len())?;
+                for param in &template.parameters {
+                    writeln!(out, "  {{{{{}}}}}: {}", param.name, param.description)?;
+                }
+                Ok(())
+            })?;
+        }
+
+        Commands::Knowledge {
+            command: KnowledgeCommands::Prune { dry_run, unused_days, min_quality, max_items },
+        } => {
+            let service = BasicKnowledgeService::new(storage);
+            let report = service
+                .prune(PrunePolicy {
+                    unused_for_days: Some(unused_days),
+                    min_quality,
+                    max_items,
+                    dry_run,
+                })
+                .await?;
+            emit(out, format, &report, |out| {
+                let verb = if report.dry_run { "将归档" } else { "已归档" };
+                writeln!(out, "检查 {} 条知识, {} {} 条:", report.examined, verb, report.pruned.len())?;
+                for item in &report.pruned {
+                    writeln!(out, "  {} | {:?} | {}", item.id, item.reason, item.title)?;
+                }
+                Ok(())
+            })?;
+        }
+
+        Commands::Knowledge { command: KnowledgeCommands::Stats } => {
+            let stats = BasicKnowledgeService::new(storage).statistics().await?;
+            emit(out, format, &stats, |out| {
+                writeln!(out, "知识总数: {} (已归档 {} 条)", stats.total, stats.archived)?;
+                writeln!(out, "向量化: {} 条, 未向量化: {} 条", stats.embedded, stats.unembedded)?;
+                match stats.average_feedback {
+                    Some(avg) => writeln!(out, "平均反馈评分: {:.2}", avg)?,
+                    None => writeln!(out, "平均反馈评分: 无反馈")?,
+                }
+                writeln!(out, "按类型:")?;
+                for (knowledge_type, count) in &stats.by_type {
+                    writeln!(out, "  {}: {}", knowledge_type, count)?;
+                }
+                writeln!(out, "常用标签:")?;
+                for tag in &stats.top_tags {
+                    writeln!(out, "  {}: {}", tag.tag, tag.count)?;
+                }
+                Ok(())
+            })?;
+        }
+
+        Commands::Config { command: ConfigCommands::Get { key: None } } => {
+            let entries = storage.list_config_entries().await?;
+            emit(out, format, &entries, |out| {
+                if entries.is_empty() {
+                    return writeln!(out, "暂无配置，使用默认值");
+                }
+                for entry in &entries {
+                    writeln!(out, "{} = {}", entry.key, entry.value)?;
+                }
+                Ok(())
+            })?;
+        }
+
+        Commands::Config { command: ConfigCommands::Get { key: Some(key) } } => {
+            let entry = storage.load_config_entry(&key).await?;
+            let Some(entry) = entry else {
+                anyhow::bail!("未设置配置项: {}", key);
+            };
+            emit(out, format, &entry, |out| writeln!(out, "{}", entry.value))?;
+        }
+
+        Commands::Config { command: ConfigCommands::Set { key, value } } => {
+            let value = parse_config_value(&value);
+            check_config_value(&key, &value)?;
+            storage.set_config(&key, &value).await?;
+            storage.commit(&format!("Set config {}", key)).await?;
+            emit(out, format, &value, |out| writeln!(out, "✓ {} = {}", key, value))?;
+        }
+
+        Commands::Compact => {
+            let report = storage.compact().await?;
+            emit(out, format, &report, |out| {
+                writeln!(out, "✓ 压缩完成: 清理 {} 个孤立项, 回收 {} 字节",
+                    report.removed,
+                    report.reclaimed_bytes)
+            })?;
+        }
+
+        Commands::RepairDeps => {
+            let report = storage.repair_dependency_graph().await?;
+            storage.commit("Repair task dependency graph").await?;
+            emit(out, format, &report, |out| {
+                if report.is_clean() {
+                    return writeln!(out, "✓ 依赖图一致，无需修复");
+                }
+                writeln!(out, "✓ 已修复 {} 个任务: 补充 {} 条 depends_on, {} 条 blocks, 移除 {} 条失效引用",
+                    report.repaired_tasks.len(),
+                    report.added_depends_on,
+                    report.added_blocks,
+                    report.removed_dangling)
+            })?;
+        }
+
+        Commands::PruneHistory { dry_run, keep_days, keep_per_task } => {
+            let report = storage
+                .prune_history(&RetentionPolicy { keep_days, keep_per_task, dry_run }, &devman_core::SystemClock)
+                .await?;
+            if !report.dry_run && !report.is_empty() {
+                storage.commit("Prune event and work record history").await?;
             }
+            emit(out, format, &report, |out| {
+                let verb = if report.dry_run { "将清理" } else { "已清理" };
+                writeln!(out, "{} {} 条事件, {} 条工作记录", verb, report.events.len(), report.work_records.len())?;
+                if let Some(archive) = &report.archive {
+                    writeln!(out, "归档: {}", archive.0)?;
+                }
+                Ok(())
+            })?;
+        }
+
+        Commands::Audit { lines } => {
+            let path = storage_path.join(devman_ai::audit::DEFAULT_AUDIT_FILE);
+            let entries = devman_ai::audit::read_tail(&path, lines).await?;
+            emit(out, format, &entries, |out| {
+                if entries.is_empty() {
+                    return writeln!(out, "暂无审计记录: {}", path.display());
+                }
+                for entry in &entries {
+                    let status = match entry.status {
+                        devman_ai::AuditStatus::Success => "✓".to_string(),
+                        devman_ai::AuditStatus::Error => match entry.error_code {
+                            Some(code) => format!("✗ {}", code),
+                            None => "✗".to_string(),
+                        },
+                    };
+                    writeln!(out, "{} {} {} ({} ms) {}",
+                        entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                        status,
+                        entry.tool,
+                        entry.duration_ms,
+                        entry.arguments)?;
+                }
+                Ok(())
+            })?;
+        }
+
+        Commands::ExportWork { format: ExportFormat::Ndjson } => {
+            // Records are written as they are read, so memory use does not
+            // grow with the size of the store
+            let mut writer = std::io::BufWriter::new(out);
+            let mut records = storage.stream_work_records();
+            while let Some(record) = records.next().await {
+                serde_json::to_writer(&mut writer, &record?)?;
+                writer.write_all(b"\n")?;
+            }
+            writer.flush()?;
+        }
+
+        Commands::Metrics { format: MetricsFormat::Prometheus } => {
+            write!(out, "{}", devman_progress::prometheus_text(&storage).await?)?;
         }
+
+        Commands::Doctor { .. } => unreachable!("doctor runs before the store is opened"),
+        Commands::Migrate { .. } => unreachable!("migrate opens its own stores"),
     }
 
     Ok(())
 }
+
+/// Open the store at `path`: SQLite for database files, JSON otherwise.
+async fn open_store(path: &std::path::Path) -> Result<Box<dyn Storage>> {
+    let is_sqlite = matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("db" | "sqlite" | "sqlite3")
+    );
+    if !is_sqlite {
+        return Ok(Box::new(JsonStorage::new(path).await?));
+    }
+    #[cfg(feature = "sqlite")]
+    {
+        Ok(Box::new(devman_storage::SqliteStorage::new_from_path(path).await?))
+    }
+    #[cfg(not(feature = "sqlite"))]
+    {
+        anyhow::bail!("{} 是 SQLite 数据库，但此版本未启用 sqlite 功能 (cargo build --features sqlite)", path.display())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(title: &str) -> Task {
+        Task {
+            id: TaskId::new(),
+            title: title.to_string(),
+            description: String::new(),
+            intent: devman_core::TaskIntent {
+                natural_language: title.to_string(),
+                context: devman_core::TaskContext {
+                    relevant_knowledge: Vec::new(),
+                    similar_tasks: Vec::new(),
+                    affected_files: Vec::new(),
+                },
+                success_criteria: Vec::new(),
+            },
+            steps: Vec::new(),
+            inputs: Vec::new(),
+            expected_outputs: Vec::new(),
+            quality_gates: Vec::new(),
+            status: devman_core::TaskStatus::Queued,
+            progress: devman_core::TaskProgress::default(),
+            phase_id: devman_core::PhaseId::new(),
+            depends_on: Vec::new(),
+            blocks: Vec::new(),
+            parent: None,
+            children: Vec::new(),
+            archived: false,
+            priority: devman_core::Priority::default().value(),
+            work_records: Vec::new(),
+            actuals: None,
+            claim: None,
+            state: None,
+            merged_into: None,
+            base_priority: None,
+            due_at: None,
+            created_knowledge: Vec::new(),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    /// Run `args` against the store in `dir` and return what was printed.
+    async fn run_cli(dir: &std::path::Path, args: &[&str]) -> String {
+        let cli = Cli::try_parse_from(std::iter::once("devman").chain(args.iter().copied())).unwrap();
+        let mut out = Vec::new();
+        run(cli, dir, &mut out).await.unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_task_list_json_matches_stored_tasks() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+        let stored = [task("Write parser"), task("Ship release")];
+        for t in &stored {
+            storage.save_task(t).await.unwrap();
+        }
+
+        let output = run_cli(dir.path(), &["task", "list", "--output", "json"]).await;
+        let listed: Vec<Task> = serde_json::from_str(&output).unwrap();
+        let mut listed: Vec<_> = listed.iter().map(|t| (t.id.to_string(), t.title.clone())).collect();
+        let mut expected: Vec<_> = stored.iter().map(|t| (t.id.to_string(), t.title.clone())).collect();
+        listed.sort();
+        expected.sort();
+        assert_eq!(listed, expected);
+
+        // The human format stays the default
+        let table = run_cli(dir.path(), &["task", "list"]).await;
+        assert!(table.starts_with("任务 (2):"));
+    }
+
+    #[tokio::test]
+    async fn test_task_list_tree_nests_subtasks() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+        let (mut parent, mut child, mut grandchild) = (task("Release"), task("Changelog"), task("Contributors"));
+        parent.children.push(child.id);
+        child.parent = Some(parent.id);
+        child.children.push(grandchild.id);
+        grandchild.parent = Some(child.id);
+        for t in [&parent, &child, &grandchild] {
+            storage.save_task(t).await.unwrap();
+        }
+
+        let roots = run_cli(dir.path(), &["task", "list", "--roots-only", "--output", "json"]).await;
+        let roots: Vec<Task> = serde_json::from_str(&roots).unwrap();
+        assert_eq!(roots.iter().map(|t| t.id).collect::<Vec<_>>(), vec![parent.id]);
+
+        let tree = run_cli(dir.path(), &["task", "list", "--tree"]).await;
+        let lines: Vec<_> = tree.lines().collect();
+        assert_eq!(lines[0], "任务 (3):");
+        assert!(lines[1].starts_with(&format!("  {} |", parent.id)), "{}", tree);
+        assert!(lines[2].starts_with(&format!("    {} |", child.id)), "{}", tree);
+        assert!(lines[3].starts_with(&format!("      {} |", grandchild.id)), "{}", tree);
+    }
+
+    #[tokio::test]
+    async fn test_save_template_turns_values_into_placeholders() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+        let mut stored = task("Add /users endpoint");
+        stored.intent.success_criteria.push("GET /users returns 200".to_string());
+        storage.save_task(&stored).await.unwrap();
+
+        let id = stored.id.to_string();
+        let output = run_cli(
+            dir.path(),
+            &["task", "save-template", &id, "add-endpoint", "--param", "path=/users", "--output", "json"],
+        )
+        .await;
+        let saved: devman_core::TaskTemplate = serde_json::from_str(&output).unwrap();
+
+        let template = storage.require_task_template(saved.id).await.unwrap();
+        assert_eq!(template.name, "add-endpoint");
+        assert_eq!(template.title, "Add {{path}} endpoint");
+        assert_eq!(template.success_criteria, ["GET {{path}} returns 200"]);
+        assert_eq!(template.parameters.len(), 1);
+        assert!(Cli::try_parse_from(["devman", "task", "save-template", &id, "x", "--param", "path"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_show_yaml_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let created = run_cli(dir.path(), &["--output", "json", "create-goal", "Launch", "Ship v1"]).await;
+        let goal: Goal = serde_json::from_str(&created).unwrap();
+
+        let output = run_cli(dir.path(), &["show-goal", &goal.id.to_string(), "--output", "yaml"]).await;
+        let shown: Option<Goal> = serde_yaml::from_str(&output).unwrap();
+        let shown = shown.unwrap();
+        assert_eq!(shown.id, goal.id);
+        assert_eq!(shown.title, "Launch");
+
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+        let stored = task("Write docs");
+        storage.save_task(&stored).await.unwrap();
+        let output = run_cli(dir.path(), &["task", "show", &stored.id.to_string(), "--output", "yaml"]).await;
+        let shown: Task = serde_yaml::from_str(&output).unwrap();
+        assert_eq!(shown.id, stored.id);
+        assert_eq!(shown.status, devman_core::TaskStatus::Queued);
+
+        let status = run_cli(dir.path(), &["status", "--output", "yaml"]).await;
+        let summary: StatusSummary = serde_yaml::from_str(&status).unwrap();
+        assert_eq!(summary.goals.get("Active"), Some(&1));
+        assert_eq!(summary.tasks.get("Queued"), Some(&1));
+    }
+
+    #[tokio::test]
+    async fn test_export_work_writes_one_record_per_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+        let mut saved = Vec::new();
+        for _ in 0..3 {
+            let record = devman_core::WorkRecord::new(TaskId::new(), devman_core::Executor::AI { model: "test".to_string() }, chrono::Utc::now());
+            storage.save_work_record(&record).await.unwrap();
+            saved.push(record.id.to_string());
+        }
+
+        let output = run_cli(dir.path(), &["export-work", "--format", "ndjson"]).await;
+        let mut exported: Vec<String> = output
+            .lines()
+            .map(|line| serde_json::from_str::<devman_core::WorkRecord>(line).unwrap().id.to_string())
+            .collect();
+        exported.sort();
+        saved.sort();
+        assert_eq!(exported, saved);
+    }
+
+    #[tokio::test]
+    async fn test_goal_quality_marks_failing_tasks() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+        let (failing, unchecked) = (task("Write parser"), task("Ship release"));
+        storage.save_task(&failing).await.unwrap();
+        storage.save_task(&unchecked).await.unwrap();
+
+        let phase = devman_core::Phase {
+            id: failing.phase_id,
+            name: "Build".to_string(),
+            description: String::new(),
+            objectives: Vec::new(),
+            acceptance_criteria: Vec::new(),
+            tasks: vec![failing.id, unchecked.id],
+            depends_on: Vec::new(),
+            status: devman_core::PhaseStatus::InProgress,
+            progress: devman_core::PhaseProgress::default(),
+            estimated_duration: None,
+            actual_duration: None,
+            quality_profile: None,
+            created_at: chrono::Utc::now(),
+        };
+        storage.save_phase(&phase).await.unwrap();
+        let project = devman_core::Project {
+            id: devman_core::ProjectId::new(),
+            name: "Quality".to_string(),
+            description: String::new(),
+            config: devman_core::ProjectConfig {
+                tech_stack: Vec::new(),
+                structure: devman_core::DirStructure { dirs: Vec::new(), conventions: Vec::new() },
+                quality_profile: devman_core::QualityProfileId::default(),
+                default_task_profile: None,
+                tools: devman_core::ToolConfig {
+                    build: devman_core::BuildTool::Cargo,
+                    test_framework: devman_core::TestFramework::Rust,
+                    linters: Vec::new(),
+                    formatters: Vec::new(),
+                },
+            },
+            phases: vec![phase.id],
+            current_phase: phase.id,
+            created_at: chrono::Utc::now(),
+        };
+        storage.save_project(&project).await.unwrap();
+        let goal = Goal {
+            id: GoalId::new(),
+            title: "Ship".to_string(),
+            description: String::new(),
+            success_criteria: Vec::new(),
+            progress: devman_core::GoalProgress::default(),
+            project_id: project.id,
+            current_phase: phase.id,
+            status: devman_core::GoalStatus::Active,
+            status_history: Vec::new(),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+        storage.save_goal(&goal).await.unwrap();
+        let result = devman_core::QualityCheckResult {
+            check_id: devman_core::QualityCheckId::new(),
+            passed: false,
+            execution_time: std::time::Duration::ZERO,
+            details: devman_core::CheckDetails { output: String::new(), exit_code: None, error: None, timeout: None },
+            findings: Vec::new(),
+            metrics: Vec::new(),
+            human_review: None,
+        };
+        storage
+            .save_quality_run(&devman_core::QualityRun::new(failing.id, vec![result], chrono::Utc::now()))
+            .await
+            .unwrap();
+
+        let output = run_cli(dir.path(), &["goal-quality", &goal.id.to_string()]).await;
+        assert!(output.starts_with("已质检任务: 1/2"), "{}", output);
+        assert!(output.contains(&format!("✗ {} - Write parser", failing.id)), "{}", output);
+        assert!(output.contains(&format!("· {} - Ship release", unchecked.id)), "{}", output);
+    }
+
+    #[tokio::test]
+    async fn test_migrate_copies_store_and_reports_counts() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("old");
+        let to = dir.path().join("new");
+        let mut storage = JsonStorage::new(&from).await.unwrap();
+        let stored = task("Write parser");
+        storage.save_task(&stored).await.unwrap();
+        storage
+            .save_work_record(&devman_core::WorkRecord::new(stored.id, devman_core::Executor::AI { model: "test".to_string() }, chrono::Utc::now()))
+            .await
+            .unwrap();
+
+        let output = run_cli(
+            dir.path(),
+            &["--output", "json", "migrate", "--from", from.to_str().unwrap(), "--to", to.to_str().unwrap()],
+        )
+        .await;
+        let report: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let counts: HashMap<&str, (u64, u64)> = report["entities"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| (c["entity_type"].as_str().unwrap(), (c["source"].as_u64().unwrap(), c["target"].as_u64().unwrap())))
+            .collect();
+        assert_eq!(counts["task"], (1, 1));
+        assert_eq!(counts["work_record"], (1, 1));
+
+        let migrated = JsonStorage::new(&to).await.unwrap();
+        assert_eq!(migrated.load_task(stored.id).await.unwrap().unwrap().title, "Write parser");
+    }
+
+    #[tokio::test]
+    async fn test_repair_deps_adds_missing_blocks() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+        let (mut dependent, prerequisite) = (task("Deploy"), task("Build"));
+        dependent.depends_on.push(prerequisite.id);
+        storage.save_task(&dependent).await.unwrap();
+        storage.save_task(&prerequisite).await.unwrap();
+
+        let output = run_cli(dir.path(), &["repair-deps"]).await;
+        assert_eq!(output, "✓ 已修复 1 个任务: 补充 0 条 depends_on, 1 条 blocks, 移除 0 条失效引用\n");
+        let storage = JsonStorage::new(dir.path()).await.unwrap();
+        assert_eq!(storage.require_task(prerequisite.id).await.unwrap().blocks, vec![dependent.id]);
+
+        assert_eq!(run_cli(dir.path(), &["repair-deps"]).await, "✓ 依赖图一致，无需修复\n");
+    }
+
+    #[tokio::test]
+    async fn test_prune_history_dry_run_keeps_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+        let mut old = devman_core::Event::new(devman_core::AgentId::system(), "log", "ok");
+        old.timestamp = chrono::Utc::now() - chrono::Duration::days(200);
+        storage.save_event(&old).await.unwrap();
+        storage.save_event(&devman_core::Event::new(devman_core::AgentId::system(), "log", "ok")).await.unwrap();
+
+        let output = run_cli(dir.path(), &["prune-history", "--dry-run"]).await;
+        assert_eq!(output, "将清理 1 条事件, 0 条工作记录\n");
+        assert_eq!(JsonStorage::new(dir.path()).await.unwrap().list_events().await.unwrap().len(), 2);
+
+        let output = run_cli(dir.path(), &["prune-history"]).await;
+        assert!(output.starts_with("已清理 1 条事件, 0 条工作记录\n归档: "));
+        let events = JsonStorage::new(dir.path()).await.unwrap().list_events().await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_ne!(events[0].id, old.id);
+    }
+
+    #[tokio::test]
+    async fn test_reprioritize_raises_tasks_nearing_their_due_date() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+        let mut urgent = task("Hotfix");
+        urgent.due_at = Some(chrono::Utc::now() + chrono::Duration::hours(6));
+        storage.save_task(&urgent).await.unwrap();
+        storage.save_task(&task("Refactor")).await.unwrap();
+
+        let id = urgent.id.to_string();
+        let output = run_cli(dir.path(), &["task", "reprioritize", "--dry-run"]).await;
+        assert!(output.starts_with(&format!("将调整 {}: ", id)), "{}", output);
+        assert!(output.ends_with("共 1 个任务\n"));
+        let stored = JsonStorage::new(dir.path()).await.unwrap().require_task(urgent.id).await.unwrap();
+        assert_eq!(stored.priority, urgent.priority);
+
+        let output = run_cli(dir.path(), &["--output", "json", "task", "reprioritize"]).await;
+        let changes: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(changes.as_array().unwrap().len(), 1);
+        let stored = JsonStorage::new(dir.path()).await.unwrap().require_task(urgent.id).await.unwrap();
+        assert_eq!(changes[0]["to"], stored.priority);
+        assert_eq!(stored.base_priority, Some(urgent.priority));
+
+        let due = "2030-01-01T00:00:00Z";
+        let output = run_cli(dir.path(), &["--output", "json", "task", "split", &id, "Backport", "--due", due]).await;
+        let child: Task = serde_json::from_str(&output).unwrap();
+        assert_eq!(child.due_at, Some(due.parse().unwrap()));
+        assert_eq!(child.priority, urgent.priority);
+    }
+
+    #[tokio::test]
+    async fn test_task_split_creates_linked_child_through_work_manager() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+        let parent = task("Write parser");
+        storage.save_task(&parent).await.unwrap();
+        storage.save_task(&task("Write lexer tests")).await.unwrap();
+        let id = parent.id.to_string();
+
+        let output = run_cli(dir.path(), &["--output", "json", "task", "split", &id, "Write parser grammar"]).await;
+        let child: Task = serde_json::from_str(&output).unwrap();
+        let storage = JsonStorage::new(dir.path()).await.unwrap();
+        assert_eq!(storage.require_task(child.id).await.unwrap().parent, Some(parent.id));
+        assert_eq!(storage.require_task(parent.id).await.unwrap().children, vec![child.id]);
+        let events = storage.list_events().await.unwrap();
+        assert!(events.iter().any(|e| e.action == "task_created" && e.related_tasks == vec![child.id]));
+
+        // A near-copy of another open task needs --allow-duplicate
+        let cli = Cli::try_parse_from(["devman", "task", "split", &id, "Write lexer tests"]).unwrap();
+        assert!(run(cli, dir.path(), &mut Vec::new()).await.is_err());
+        run_cli(dir.path(), &["task", "split", &id, "Write lexer tests", "--allow-duplicate"]).await;
+        assert_eq!(storage.require_task(parent.id).await.unwrap().children.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_task_split_refuses_closed_parent() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+        let mut parent = task("Ship release");
+        parent.status = devman_core::TaskStatus::Done;
+        storage.save_task(&parent).await.unwrap();
+
+        let cli = Cli::try_parse_from(["devman", "task", "split", &parent.id.to_string(), "Tag build"]).unwrap();
+        assert!(run(cli, dir.path(), &mut Vec::new()).await.is_err());
+        assert_eq!(storage.list_tasks(&Default::default()).await.unwrap().len(), 1);
+        assert!(storage.require_task(parent.id).await.unwrap().children.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_task_show_with_history_prints_timeline() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+        let t = task("Write parser");
+        storage.save_task(&t).await.unwrap();
+        let start = chrono::Utc::now() - chrono::Duration::minutes(10);
+
+        let mut created = devman_core::Event::new(devman_core::AgentId::ai(), "task_created", "Queued");
+        created.timestamp = start;
+        created.related_tasks = vec![t.id];
+        storage.save_event(&created).await.unwrap();
+
+        let mut record = devman_core::WorkRecord::new(t.id, devman_core::Executor::AI { model: "test".to_string() }, chrono::Utc::now());
+        let step = devman_core::WorkEvent::new(
+            devman_core::WorkEventType::StepCompleted,
+            "Implemented tokenizer",
+            serde_json::json!({}),
+            start + chrono::Duration::minutes(1),
+        );
+        record.events.push(step);
+        storage.save_work_record(&record).await.unwrap();
+
+        let result = devman_core::QualityCheckResult {
+            check_id: devman_core::QualityCheckId::new(),
+            passed: true,
+            execution_time: std::time::Duration::ZERO,
+            details: devman_core::CheckDetails { output: String::new(), exit_code: Some(0), error: None, timeout: None },
+            findings: Vec::new(),
+            metrics: Vec::new(),
+            human_review: None,
+        };
+        let run = devman_core::QualityRun::new(t.id, vec![result], start + chrono::Duration::minutes(2));
+        storage.save_quality_run(&run).await.unwrap();
+
+        let mut completed = devman_core::Event::new(devman_core::AgentId::ai(), "task_completed", "Done");
+        completed.timestamp = start + chrono::Duration::minutes(3);
+        completed.related_tasks = vec![t.id];
+        storage.save_event(&completed).await.unwrap();
+
+        let plain = run_cli(dir.path(), &["task", "show", &t.id.to_string()]).await;
+        assert!(!plain.contains("历史"));
+
+        let output = run_cli(dir.path(), &["task", "show", &t.id.to_string(), "--with-history"]).await;
+        let history: Vec<&str> = output.lines().skip_while(|l| !l.starts_with("  历史")).collect();
+        assert_eq!(history[0], "  历史 (4 条):");
+        assert!(history[1].ends_with("[事件] task_created: Queued"), "{}", output);
+        assert!(history[2].ends_with("[工作] StepCompleted: Implemented tokenizer"), "{}", output);
+        assert!(history[3].ends_with("[质检] ✓ Quality run: 1/1 checks passed"), "{}", output);
+        assert!(history[4].ends_with("[事件] task_completed: Done"), "{}", output);
+
+        let json = run_cli(dir.path(), &["--output", "json", "task", "show", &t.id.to_string(), "--with-history"]).await;
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["task"]["id"], serde_json::json!(t.id));
+        let kinds: Vec<&str> = value["history"].as_array().unwrap().iter().map(|e| e["kind"].as_str().unwrap()).collect();
+        assert_eq!(kinds, vec!["state_transition", "work_log", "quality_check", "state_transition"]);
+    }
+
+    #[tokio::test]
+    async fn test_audit_shows_latest_calls() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(run_cli(dir.path(), &["audit"]).await.starts_with("暂无审计记录"));
+
+        let log = devman_ai::AuditLog::new(dir.path().join(devman_ai::audit::DEFAULT_AUDIT_FILE));
+        for (tool, status, error_code) in [
+            ("devman_create_goal", devman_ai::AuditStatus::Success, None),
+            ("devman_create_task", devman_ai::AuditStatus::Success, None),
+            ("devman_reopen_task", devman_ai::AuditStatus::Error, Some(-32002)),
+        ] {
+            let entry = devman_ai::AuditEntry {
+                timestamp: chrono::Utc::now(),
+                tool: tool.to_string(),
+                arguments: serde_json::json!({}),
+                status,
+                error_code,
+                duration_ms: 3,
+            };
+            log.append(&entry).await.unwrap();
+        }
+
+        let table = run_cli(dir.path(), &["audit", "-n", "2"]).await;
+        let lines: Vec<_> = table.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("✓ devman_create_task"));
+        assert!(lines[1].contains("✗ -32002 devman_reopen_task"));
+
+        let output = run_cli(dir.path(), &["audit", "--output", "json"]).await;
+        let entries: Vec<devman_ai::AuditEntry> = serde_json::from_str(&output).unwrap();
+        assert_eq!(entries.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_config_set_is_read_after_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(run_cli(dir.path(), &["config", "get"]).await.trim(), "暂无配置，使用默认值");
+
+        run_cli(dir.path(), &["config", "set", "quality.check_timeout_secs", "45"]).await;
+        let model = r#"{"Ollama":{"name":"nomic-embed-text"}}"#;
+        run_cli(dir.path(), &["config", "set", "knowledge.embedding_model", model]).await;
+
+        assert_eq!(run_cli(dir.path(), &["config", "get", "quality.check_timeout_secs"]).await.trim(), "45");
+        let storage = JsonStorage::new(dir.path()).await.unwrap();
+        let timeout: Option<u64> = storage.get_config("quality.check_timeout_secs").await.unwrap();
+        assert_eq!(timeout, Some(45));
+        let model: Option<devman_core::EmbeddingModel> = storage.get_config("knowledge.embedding_model").await.unwrap();
+        assert!(matches!(model, Some(devman_core::EmbeddingModel::Ollama { name }) if name == "nomic-embed-text"));
+
+        let listed = run_cli(dir.path(), &["config", "get"]).await;
+        assert_eq!(listed.lines().collect::<Vec<_>>(), vec![
+            r#"knowledge.embedding_model = {"Ollama":{"name":"nomic-embed-text"}}"#,
+            "quality.check_timeout_secs = 45",
+        ]);
+
+        // Known keys refuse values their readers could not use
+        for (key, value) in [
+            ("quality.check_timeout_secs", "soon"),
+            ("knowledge.embedding_model", "nomic-embed-text"),
+            ("progress.price_table", "6.0"),
+        ] {
+            let cli = Cli::try_parse_from(["devman", "config", "set", key, value]).unwrap();
+            assert!(run(cli, dir.path(), &mut Vec::new()).await.is_err(), "{} = {}", key, value);
+        }
+        let storage = JsonStorage::new(dir.path()).await.unwrap();
+        assert_eq!(storage.list_config_entries().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_prints_prometheus_text() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+        storage.save_task(&task("queued")).await.unwrap();
+
+        let output = run_cli(dir.path(), &["metrics", "--format", "prometheus"]).await;
+        assert!(output.contains("# TYPE devman_tasks gauge\n"));
+        assert!(output.lines().any(|l| l == "devman_tasks{status=\"queued\"} 1"));
+    }
+
+    #[tokio::test]
+    async fn test_doctor_fails_on_unwritable_storage() {
+        let dir = tempfile::tempdir().unwrap();
+        let blocked = dir.path().join("not-a-dir");
+        std::fs::write(&blocked, "").unwrap();
+
+        let cli = Cli::try_parse_from(["devman", "doctor", "--output", "json"]).unwrap();
+        let mut out = Vec::new();
+        let result = run(cli, &blocked.join(".devman"), &mut out).await;
+        assert!(result.is_err());
+
+        let checks: Vec<doctor::Check> = serde_json::from_slice(&out).unwrap();
+        assert_eq!(checks[0].status, doctor::CheckStatus::Fail);
+    }
+}
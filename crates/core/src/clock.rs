@@ -0,0 +1,53 @@
+//! Time source abstraction.
+//!
+//! Components that stamp entities or compute ages take a [`Clock`] so
+//! tests can drive time with a [`MockClock`] instead of the wall clock.
+
+use std::sync::Mutex;
+
+use crate::Time;
+
+/// A source of the current time.
+pub trait Clock: Send + Sync {
+    /// The current time.
+    fn now(&self) -> Time;
+}
+
+/// The system wall clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Time {
+        chrono::Utc::now()
+    }
+}
+
+/// A manually driven clock for tests.
+#[derive(Debug)]
+pub struct MockClock {
+    now: Mutex<Time>,
+}
+
+impl MockClock {
+    /// Create a clock stopped at `start`.
+    pub fn new(start: Time) -> Self {
+        Self { now: Mutex::new(start) }
+    }
+
+    /// Move the clock forward by `by`.
+    pub fn advance(&self, by: chrono::Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+
+    /// Set the clock to `to`.
+    pub fn set(&self, to: Time) {
+        *self.now.lock().unwrap() = to;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Time {
+        *self.now.lock().unwrap()
+    }
+}
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use crate::id::{GoalId, ProjectId, PhaseId, CriterionId, QualityCheckId};
 use crate::Time;
 use crate::work_record::Blocker;
+use crate::quality::CommandSpec;
 
 /// A goal represents the top-level objective AI wants to achieve.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,6 +33,10 @@ pub struct Goal {
     /// Goal status
     pub status: GoalStatus,
 
+    /// Status changes, oldest first
+    #[serde(default)]
+    pub status_history: Vec<GoalStatusChange>,
+
     /// When created
     pub created_at: Time,
 
@@ -52,6 +57,71 @@ pub enum GoalStatus {
     Cancelled,
 }
 
+impl GoalStatus {
+    /// Whether the goal lifecycle allows moving from this status to `to`.
+    ///
+    /// Completed goals only become active again through [`Goal::reopen`];
+    /// cancelled goals are final.
+    pub fn can_transition_to(self, to: GoalStatus) -> bool {
+        use GoalStatus::*;
+        matches!(
+            (self, to),
+            (Active, Paused | Completed | Cancelled) | (Paused, Active | Cancelled)
+        )
+    }
+}
+
+/// A recorded goal status change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoalStatusChange {
+    /// Previous status
+    pub from: GoalStatus,
+
+    /// New status
+    pub to: GoalStatus,
+
+    /// When the change happened
+    pub at: Time,
+}
+
+/// A goal status change the lifecycle does not allow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("Cannot change goal status from {from:?} to {to:?}")]
+pub struct TransitionError {
+    /// Current status
+    pub from: GoalStatus,
+
+    /// Requested status
+    pub to: GoalStatus,
+}
+
+impl Goal {
+    /// Move the goal to `to`, recording the change in its status history.
+    pub fn transition(&mut self, to: GoalStatus) -> Result<(), TransitionError> {
+        if !self.status.can_transition_to(to) {
+            return Err(TransitionError { from: self.status, to });
+        }
+        self.record_status(to);
+        Ok(())
+    }
+
+    /// Make a completed goal active again.
+    pub fn reopen(&mut self) -> Result<(), TransitionError> {
+        if self.status != GoalStatus::Completed {
+            return Err(TransitionError { from: self.status, to: GoalStatus::Active });
+        }
+        self.record_status(GoalStatus::Active);
+        Ok(())
+    }
+
+    fn record_status(&mut self, to: GoalStatus) {
+        let now = chrono::Utc::now();
+        self.status_history.push(GoalStatusChange { from: self.status, to, at: now });
+        self.status = to;
+        self.updated_at = now;
+    }
+}
+
 /// A success criterion for a goal.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SuccessCriterion {
@@ -68,6 +138,28 @@ pub struct SuccessCriterion {
     pub status: CriterionStatus,
 }
 
+/// Keyword overlap at or above which a task's success criterion counts as
+/// claiming a goal's success criterion.
+pub const CRITERION_MATCH_THRESHOLD: f32 = 0.5;
+
+impl SuccessCriterion {
+    /// How closely a task's success criterion `text` claims this criterion,
+    /// from 0.0 to 1.0: 1.0 when it names the criterion's ID, otherwise the
+    /// share of keywords the two have in common.
+    pub fn match_score(&self, text: &str) -> f32 {
+        if text.contains(&self.id.to_string()) {
+            return 1.0;
+        }
+        let ours: std::collections::HashSet<String> = crate::task::keywords(&self.description).into_iter().collect();
+        let theirs: std::collections::HashSet<String> = crate::task::keywords(text).into_iter().collect();
+        let union = ours.union(&theirs).count();
+        if union == 0 {
+            return 0.0;
+        }
+        ours.intersection(&theirs).count() as f32 / union as f32
+    }
+}
+
 /// How to verify a success criterion.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum VerificationMethod {
@@ -82,6 +174,10 @@ pub enum VerificationMethod {
         automated: QualityCheckId,
         reviewer: String,
     },
+
+    /// Automated verification by running a command; met when it exits
+    /// with the expected code
+    Command(CommandSpec),
 }
 
 /// Criterion status.
@@ -128,3 +224,84 @@ impl Default for GoalProgress {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn goal() -> Goal {
+        let now = chrono::Utc::now();
+        Goal {
+            id: GoalId::new(),
+            title: "Ship v1".to_string(),
+            description: String::new(),
+            success_criteria: Vec::new(),
+            progress: GoalProgress::default(),
+            project_id: ProjectId::new(),
+            current_phase: PhaseId::new(),
+            status: GoalStatus::Active,
+            status_history: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_legal_transitions_are_recorded() {
+        let mut goal = goal();
+        goal.transition(GoalStatus::Paused).unwrap();
+        goal.transition(GoalStatus::Active).unwrap();
+        goal.transition(GoalStatus::Completed).unwrap();
+
+        assert_eq!(goal.status, GoalStatus::Completed);
+        let changes: Vec<_> = goal.status_history.iter().map(|c| (c.from, c.to)).collect();
+        assert_eq!(
+            changes,
+            vec![
+                (GoalStatus::Active, GoalStatus::Paused),
+                (GoalStatus::Paused, GoalStatus::Active),
+                (GoalStatus::Active, GoalStatus::Completed),
+            ]
+        );
+        assert!(goal.status_history.windows(2).all(|w| w[0].at <= w[1].at));
+        assert_eq!(goal.updated_at, goal.status_history[2].at);
+    }
+
+    #[test]
+    fn test_illegal_transitions_are_rejected() {
+        let mut goal = goal();
+        goal.transition(GoalStatus::Cancelled).unwrap();
+
+        let err = goal.transition(GoalStatus::Active).unwrap_err();
+        assert_eq!(err, TransitionError { from: GoalStatus::Cancelled, to: GoalStatus::Active });
+        assert_eq!(goal.status, GoalStatus::Cancelled);
+        assert_eq!(goal.status_history.len(), 1);
+        assert!(goal.reopen().is_err());
+    }
+
+    #[test]
+    fn test_completed_goal_needs_reopen() {
+        let mut goal = goal();
+        goal.transition(GoalStatus::Completed).unwrap();
+
+        assert!(goal.transition(GoalStatus::Active).is_err());
+        goal.reopen().unwrap();
+        assert_eq!(goal.status, GoalStatus::Active);
+        assert_eq!(goal.status_history.len(), 2);
+    }
+
+    #[test]
+    fn test_criterion_match_score() {
+        let criterion = SuccessCriterion {
+            id: CriterionId::new(),
+            description: "Users can log in with email".to_string(),
+            verification: VerificationMethod::Manual { reviewer: String::new() },
+            status: CriterionStatus::NotStarted,
+        };
+
+        assert_eq!(criterion.match_score(&format!("Covers {}", criterion.id)), 1.0);
+        assert!(criterion.match_score("User can log in with their email") >= CRITERION_MATCH_THRESHOLD);
+        assert!(criterion.match_score("Export reports as CSV") < CRITERION_MATCH_THRESHOLD);
+        assert_eq!(criterion.match_score(""), 0.0);
+    }
+}
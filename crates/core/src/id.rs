@@ -58,6 +58,13 @@ impl std::fmt::Display for ProjectId {
     }
 }
 
+impl std::str::FromStr for ProjectId {
+    type Err = ulid::DecodeError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.parse()?))
+    }
+}
+
 /// Unique identifier for a Phase
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct PhaseId(pub Ulid);
@@ -81,6 +88,13 @@ impl std::fmt::Display for PhaseId {
     }
 }
 
+impl std::str::FromStr for PhaseId {
+    type Err = ulid::DecodeError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.parse()?))
+    }
+}
+
 /// Unique identifier for a Task
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TaskId(pub Ulid);
@@ -229,6 +243,72 @@ impl Default for BlockerId {
     }
 }
 
+impl std::fmt::Display for BlockerId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::str::FromStr for BlockerId {
+    type Err = ulid::DecodeError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.parse()?))
+    }
+}
+
+/// Unique identifier for a recorded quality check run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QualityRunId(pub Ulid);
+
+impl QualityRunId {
+    /// Create a new unique quality run ID.
+    pub fn new() -> Self {
+        Self(Ulid::new())
+    }
+}
+
+impl Default for QualityRunId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for QualityRunId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Proof of holding a task claim, needed to release it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ClaimToken(pub Ulid);
+
+impl ClaimToken {
+    /// Create a new unique claim token.
+    pub fn new() -> Self {
+        Self(Ulid::new())
+    }
+}
+
+impl Default for ClaimToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for ClaimToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::str::FromStr for ClaimToken {
+    type Err = ulid::DecodeError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.parse()?))
+    }
+}
+
 /// Unique identifier for an Issue
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct IssueId(pub Ulid);
@@ -263,6 +343,12 @@ impl Default for CriterionId {
     }
 }
 
+impl std::fmt::Display for CriterionId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 /// Unique identifier for a QualityProfile
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct QualityProfileId(pub Ulid);
@@ -280,6 +366,75 @@ impl Default for QualityProfileId {
     }
 }
 
+impl std::fmt::Display for QualityProfileId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::str::FromStr for QualityProfileId {
+    type Err = ulid::DecodeError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.parse()?))
+    }
+}
+
+/// Unique identifier for a TaskTemplate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TaskTemplateId(pub Ulid);
+
+impl TaskTemplateId {
+    /// Create a new unique task template ID.
+    pub fn new() -> Self {
+        Self(Ulid::new())
+    }
+}
+
+impl Default for TaskTemplateId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for TaskTemplateId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::str::FromStr for TaskTemplateId {
+    type Err = ulid::DecodeError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.parse()?))
+    }
+}
+
+/// Content-derived identifier for a stored blob: the hex SHA-256 digest of
+/// its bytes, so equal content always gets the same ID.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BlobId(pub String);
+
+impl BlobId {
+    /// ID of a blob holding `content`.
+    pub fn of(content: &[u8]) -> Self {
+        use sha2::{Digest, Sha256};
+        let digest = Sha256::digest(content);
+        Self(digest.iter().map(|b| format!("{:02x}", b)).collect())
+    }
+
+    /// Whether this looks like an ID [`BlobId::of`] produced, i.e. 64
+    /// lowercase hex digits; anything else never names a stored blob.
+    pub fn is_valid(&self) -> bool {
+        self.0.len() == 64 && self.0.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+    }
+}
+
+impl std::fmt::Display for BlobId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
 // === Legacy compatibility ===
 
 /// Alias for KnowledgeId (for backward compatibility)
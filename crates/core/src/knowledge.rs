@@ -34,6 +34,10 @@ pub struct Knowledge {
     /// Usage statistics
     pub usage_stats: UsageStats,
 
+    /// Hidden from search but kept recoverable
+    #[serde(default)]
+    pub archived: bool,
+
     /// Created at
     pub created_at: Time,
 
@@ -41,6 +45,20 @@ pub struct Knowledge {
     pub updated_at: Time,
 }
 
+impl Knowledge {
+    /// Quality score change per feedback rating point away from neutral (3).
+    pub const FEEDBACK_STEP: f32 = 0.05;
+
+    /// Record feedback and nudge the quality score: helpful feedback raises
+    /// it, unhelpful feedback lowers it, within `0.0..=1.0`.
+    pub fn apply_feedback(&mut self, feedback: Feedback) {
+        let delta = (feedback.rating.clamp(1, 5) - 3) as f32 * Self::FEEDBACK_STEP;
+        self.metadata.quality_score = (self.metadata.quality_score + delta).clamp(0.0, 1.0);
+        self.usage_stats.feedback.push(feedback);
+        self.updated_at = chrono::Utc::now();
+    }
+}
+
 /// Types of knowledge.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -176,6 +194,32 @@ pub struct Feedback {
     pub from: String,
 }
 
+impl Feedback {
+    /// Feedback marking the knowledge as helpful (rating 5).
+    pub fn helpful(from: impl Into<String>, note: Option<String>) -> Self {
+        Self::with_rating(5, from, note)
+    }
+
+    /// Feedback marking the knowledge as unhelpful (rating 1).
+    pub fn unhelpful(from: impl Into<String>, note: Option<String>) -> Self {
+        Self::with_rating(1, from, note)
+    }
+
+    fn with_rating(rating: i32, from: impl Into<String>, note: Option<String>) -> Self {
+        Self {
+            rating,
+            comment: note.unwrap_or_default(),
+            at: chrono::Utc::now(),
+            from: from.into(),
+        }
+    }
+
+    /// Whether the rating is above neutral.
+    pub fn is_helpful(&self) -> bool {
+        self.rating > 3
+    }
+}
+
 // Export type alias for compatibility
 pub type KnowledgeUpdate = ();
 
@@ -210,6 +254,10 @@ pub struct VectorSearchConfig {
     /// Similarity threshold (0.0 - 1.0)
     #[serde(default = "default_threshold")]
     pub threshold: f32,
+
+    /// Embed knowledge whenever it is saved through the vector service
+    #[serde(default = "default_auto_embed")]
+    pub auto_embed: bool,
 }
 
 fn default_ollama_url() -> String {
@@ -224,6 +272,10 @@ fn default_threshold() -> f32 {
     0.75
 }
 
+fn default_auto_embed() -> bool {
+    true
+}
+
 impl Default for VectorSearchConfig {
     fn default() -> Self {
         Self {
@@ -232,6 +284,7 @@ impl Default for VectorSearchConfig {
             ollama_url: default_ollama_url(),
             dimension: default_dimension(),
             threshold: default_threshold(),
+            auto_embed: default_auto_embed(),
         }
     }
 }
@@ -253,6 +306,60 @@ pub struct KnowledgeEmbedding {
     pub created_at: Time,
 }
 
+/// Embedding model and dimension shared by every embedding in a store.
+///
+/// Vectors from different models cannot be compared, so storage rejects
+/// embeddings that disagree with the active model until all knowledge is
+/// reindexed under the new one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActiveEmbeddingModel {
+    /// Model that produced the stored embeddings
+    pub model: EmbeddingModel,
+
+    /// Length of every stored embedding
+    pub dimension: usize,
+}
+
+impl ActiveEmbeddingModel {
+    /// The model and dimension of `embedding`.
+    pub fn of(embedding: &KnowledgeEmbedding) -> Self {
+        Self {
+            model: embedding.model.clone(),
+            dimension: embedding.embedding.len(),
+        }
+    }
+
+    /// Whether `embedding` comes from this model with this dimension.
+    pub fn matches(&self, embedding: &KnowledgeEmbedding) -> bool {
+        self.model == embedding.model && self.dimension == embedding.embedding.len()
+    }
+}
+
+impl std::fmt::Display for ActiveEmbeddingModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} ({} dims)", self.model, self.dimension)
+    }
+}
+
+/// Progress of an interrupted reindex, so the next run resumes instead of
+/// starting over.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReindexCheckpoint {
+    /// Model and dimension the reindex embeds with
+    pub target: ActiveEmbeddingModel,
+
+    /// Knowledge items already embedded under `target`
+    pub done: Vec<KnowledgeId>,
+
+    /// Embeddings for a model switch, held back until every item is
+    /// embedded because the store refuses them before the switch
+    #[serde(default)]
+    pub pending: Vec<KnowledgeEmbedding>,
+
+    /// When the checkpoint was written
+    pub updated_at: Time,
+}
+
 /// A knowledge item with its similarity score.
 #[derive(Debug, Clone)]
 pub struct ScoredKnowledge {
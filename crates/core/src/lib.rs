@@ -7,6 +7,7 @@
 
 // Core identities
 mod id;
+mod clock;
 
 // Goal and project management
 mod goal;
@@ -22,50 +23,58 @@ mod event;
 mod knowledge;
 mod quality;
 
+#[cfg(test)]
+mod schema_tests;
+
 // Re-exports
 pub use id::*;
+pub use clock::{Clock, SystemClock, MockClock};
 
 // Goal & Project
-pub use goal::{Goal, GoalStatus, GoalProgress, SuccessCriterion, CriterionStatus, VerificationMethod};
+pub use goal::{
+    Goal, GoalStatus, GoalStatusChange, TransitionError, GoalProgress, SuccessCriterion,
+    CriterionStatus, VerificationMethod, CRITERION_MATCH_THRESHOLD,
+};
 pub use project::{Project, ProjectConfig, DirStructure, ToolConfig, BuildTool, TestFramework};
-pub use phase::{Phase, PhaseStatus, PhaseProgress, AcceptanceCriterion};
+pub use phase::{Phase, PhaseStatus, PhaseProgress, AcceptanceCriterion, PhaseOrderError, validate_phase_order};
 pub use id::PhaseId;
 
 // Task & Work
 pub use task::{
-    Task, TaskStatus, TaskState, AbandonReason, ChangeImpact, TaskProgress, TaskLink, LinkKind, TaskFilter,
-    TaskIntent, TaskContext, ExecutionStep, ToolInvocation, QualityGate, PassCondition, FailureAction,
-    Input, ExpectedOutput, StateTransition,
+    Task, TaskStatus, TaskState, Priority, ParsePriorityError, AbandonReason, ChangeImpact, TaskProgress, TaskLink, LinkKind, TaskFilter,
+    TaskIntent, TaskContext, ExecutionStep, ToolInvocation, QualityGate, PassCondition, GateCondition, Comparison, FailureAction,
+    Input, ExpectedOutput, StateTransition, TaskActuals, ActualsSource, TaskClaim, TaskTemplate,
     // Task module's simplified quality types
     QualityCheckResult as TaskQualityCheckResult,
     QualityOverallStatus as TaskQualityOverallStatus,
 };
 pub use work_record::{
-    WorkRecord, WorkEvent, WorkEventType, Executor, WorkResult,
+    WorkRecord, WorkRecordFilter, WorkEvent, WorkEventType, Executor, WorkResult,
     CompletionStatus, Output, Artifact, Issue, Resolution, WorkMetrics,
     Severity,
 };
-pub use event::Event;
+pub use event::{Event, AgentId};
 
 // Knowledge & Quality
 pub use knowledge::{
     Knowledge, KnowledgeType, KnowledgeContent, KnowledgeMetadata,
     UsageStats, Feedback, CodeSnippet, TemplateContent, TemplateParameter,
-    EmbeddingModel, VectorSearchConfig, KnowledgeEmbedding, ScoredKnowledge,
+    EmbeddingModel, VectorSearchConfig, KnowledgeEmbedding, ActiveEmbeddingModel, ReindexCheckpoint, ScoredKnowledge,
     RerankerModel, RerankerConfig, RerankedKnowledge,
 };
 pub use quality::{
-    QualityCheck, QualityCheckType, GenericCheckType, CustomCheckSpec,
+    QualityCheck, CheckScope, QualityCheckType, GenericCheckType, CustomCheckSpec,
     CommandSpec, ValidationSpec, OutputParser, MetricExtractor, QualityCategory,
     HumanReviewSpec, ReviewQuestion, AnswerType, AnswerValue,
     HumanReviewResult, ReviewAnswer, NotificationChannel,
-    QualityCheckResult, CheckDetails, Finding, FileLocation, Metric,
+    QualityCheckResult, CheckDetails, Finding, FileLocation, Metric, QualityRun,
     QualityProfile, GateStrategy, PhaseGate,
     QualityStatus, QualityOverallStatus,
+    truncate_output, DEFAULT_MAX_OUTPUT_LEN,
 };
 
 // Progress tracking
-pub use progress::{Blocker, BlockedItem};
+pub use progress::{Blocker, BlockedItem, BlockerCategory};
 
 /// Timestamp type
 pub type Time = chrono::DateTime<chrono::Utc>;
@@ -74,4 +83,5 @@ pub type Time = chrono::DateTime<chrono::Utc>;
 mod progress {
     pub use super::work_record::Blocker;
     pub use super::work_record::BlockedItem;
+    pub use super::work_record::BlockerCategory;
 }
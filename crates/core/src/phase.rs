@@ -1,7 +1,7 @@
 //! Phase model - goal stages with acceptance criteria.
 
 use serde::{Deserialize, Serialize};
-use crate::id::{PhaseId, TaskId, QualityCheckId};
+use crate::id::{PhaseId, TaskId, QualityCheckId, QualityProfileId};
 use crate::Time;
 
 /// A phase is a stage of a project with specific objectives.
@@ -40,6 +40,10 @@ pub struct Phase {
     /// Actual duration
     pub actual_duration: Option<std::time::Duration>,
 
+    /// Quality profile whose gates every task in this phase inherits
+    #[serde(default)]
+    pub quality_profile: Option<QualityProfileId>,
+
     /// Created at
     pub created_at: Time,
 }
@@ -86,3 +90,108 @@ impl Default for PhaseProgress {
         }
     }
 }
+
+/// A phase order that is not a valid arrangement of a project's phases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum PhaseOrderError {
+    /// The order names a phase the project does not have
+    #[error("Phase {0} is not part of the project")]
+    Unknown(PhaseId),
+
+    /// The order names a phase more than once
+    #[error("Phase {0} appears more than once")]
+    Duplicate(PhaseId),
+
+    /// The order leaves out one of the project's phases
+    #[error("Phase {0} is missing from the order")]
+    Missing(PhaseId),
+
+    /// A phase would come before a phase it depends on
+    #[error("Phase {phase} must come after its dependency {dependency}")]
+    DependencyAfter {
+        /// The dependent phase
+        phase: PhaseId,
+        /// The phase it depends on
+        dependency: PhaseId,
+    },
+}
+
+/// Check that `order` lists each of `phases` exactly once and puts every
+/// phase after the phases it depends on.
+///
+/// Dependencies on phases outside `phases` are not constrained.
+pub fn validate_phase_order(order: &[PhaseId], phases: &[Phase]) -> Result<(), PhaseOrderError> {
+    let mut position = std::collections::HashMap::new();
+    for (index, id) in order.iter().enumerate() {
+        if !phases.iter().any(|p| p.id == *id) {
+            return Err(PhaseOrderError::Unknown(*id));
+        }
+        if position.insert(*id, index).is_some() {
+            return Err(PhaseOrderError::Duplicate(*id));
+        }
+    }
+    if let Some(missing) = phases.iter().find(|p| !position.contains_key(&p.id)) {
+        return Err(PhaseOrderError::Missing(missing.id));
+    }
+
+    for phase in phases {
+        for dependency in &phase.depends_on {
+            if let Some(&before) = position.get(dependency) {
+                if before > position[&phase.id] {
+                    return Err(PhaseOrderError::DependencyAfter { phase: phase.id, dependency: *dependency });
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phase(depends_on: Vec<PhaseId>) -> Phase {
+        Phase {
+            id: PhaseId::new(),
+            name: String::new(),
+            description: String::new(),
+            objectives: vec![],
+            acceptance_criteria: vec![],
+            tasks: vec![],
+            depends_on,
+            status: PhaseStatus::NotStarted,
+            progress: PhaseProgress::default(),
+            estimated_duration: None,
+            actual_duration: None,
+            quality_profile: None,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_phase_order_must_be_a_permutation_respecting_dependencies() {
+        let design = phase(vec![]);
+        let build = phase(vec![design.id]);
+        let docs = phase(vec![PhaseId::new()]);
+        let phases = vec![design.clone(), build.clone(), docs.clone()];
+
+        assert_eq!(validate_phase_order(&[docs.id, design.id, build.id], &phases), Ok(()));
+        assert_eq!(
+            validate_phase_order(&[build.id, design.id, docs.id], &phases),
+            Err(PhaseOrderError::DependencyAfter { phase: build.id, dependency: design.id })
+        );
+        assert_eq!(
+            validate_phase_order(&[design.id, build.id], &phases),
+            Err(PhaseOrderError::Missing(docs.id))
+        );
+        assert_eq!(
+            validate_phase_order(&[design.id, design.id, build.id, docs.id], &phases),
+            Err(PhaseOrderError::Duplicate(design.id))
+        );
+        let stray = PhaseId::new();
+        assert_eq!(
+            validate_phase_order(&[design.id, build.id, docs.id, stray], &phases),
+            Err(PhaseOrderError::Unknown(stray))
+        );
+    }
+}
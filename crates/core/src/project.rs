@@ -41,6 +41,10 @@ pub struct ProjectConfig {
     /// Quality profile
     pub quality_profile: QualityProfileId,
 
+    /// Profile whose gates a task gets when created without gates of its own
+    #[serde(default)]
+    pub default_task_profile: Option<QualityProfileId>,
+
     /// Tool configuration
     pub tools: ToolConfig,
 }
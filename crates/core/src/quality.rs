@@ -1,7 +1,8 @@
 //! Quality model - checks, gates, and human collaboration.
 
 use serde::{Deserialize, Serialize};
-use crate::id::{QualityCheckId, TaskId};
+use crate::id::{PhaseId, QualityCheckId, QualityProfileId, QualityRunId, TaskId};
+use crate::task::{FailureAction, PassCondition, QualityGate};
 use crate::Time;
 use crate::work_record::Severity;
 
@@ -25,6 +26,40 @@ pub struct QualityCheck {
 
     /// Category
     pub category: QualityCategory,
+
+    /// Timeout override for generic checks; falls back to
+    /// [`GenericCheckType::default_timeout`] when unset
+    #[serde(default)]
+    pub timeout: Option<std::time::Duration>,
+
+    /// Whether to check the whole project or only changed files
+    #[serde(default)]
+    pub scope: CheckScope,
+
+    /// Directory to run the check in, relative to the project root;
+    /// the project root itself when unset
+    #[serde(default)]
+    pub working_dir: Option<std::path::PathBuf>,
+
+    /// Whether a failure fails the gate; a failing advisory check only
+    /// turns a passing gate into a pass with warnings
+    #[serde(default = "default_blocking")]
+    pub blocking: bool,
+}
+
+fn default_blocking() -> bool {
+    true
+}
+
+/// What a quality check runs against.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CheckScope {
+    /// The whole project
+    #[default]
+    Full,
+    /// Only files changed in the working tree. Checks that cannot be
+    /// scoped run against the whole project.
+    ChangedOnly,
 }
 
 /// Quality check types.
@@ -48,6 +83,23 @@ pub enum GenericCheckType {
     SecurityScan { scanner: String },
 }
 
+impl GenericCheckType {
+    /// Default timeout for this kind of check.
+    pub fn default_timeout(&self) -> std::time::Duration {
+        let secs = match self {
+            GenericCheckType::Formatted { .. } => 30,
+            GenericCheckType::DocumentationExists { .. } => 30,
+            GenericCheckType::LintsPass { .. } => 120,
+            GenericCheckType::DependenciesValid {} => 120,
+            GenericCheckType::TestsPass { .. } => 600,
+            GenericCheckType::Compiles { .. } => 300,
+            GenericCheckType::TypeCheck {} => 300,
+            GenericCheckType::SecurityScan { .. } => 300,
+        };
+        std::time::Duration::from_secs(secs)
+    }
+}
+
 /// Custom check specification (user-extensible).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CustomCheckSpec {
@@ -116,6 +168,16 @@ pub enum AnswerType {
     Rating { min: i32, max: i32 },
     Text,
     Choice { options: Vec<String> },
+    /// Any number of distinct entries from `options`
+    MultiSelect {
+        /// Selectable entries
+        options: Vec<String>,
+    },
+    /// Free text answered with [`AnswerValue::Text`]
+    Regex {
+        /// Regular expression the whole answer must match
+        pattern: String,
+    },
 }
 
 /// Answer value.
@@ -125,6 +187,8 @@ pub enum AnswerValue {
     Rating(i32),
     Text(String),
     Choice(String),
+    /// Selected entries of a [`AnswerType::MultiSelect`] question
+    MultiSelect(Vec<String>),
 }
 
 /// Quality category.
@@ -158,6 +222,49 @@ pub struct CheckDetails {
     pub output: String,
     pub exit_code: Option<i32>,
     pub error: Option<String>,
+    /// Effective timeout, set when the check timed out
+    #[serde(default)]
+    pub timeout: Option<std::time::Duration>,
+}
+
+impl CheckDetails {
+    /// Cap `output` and `error` at `max_len` bytes each, see [`truncate_output`].
+    pub fn truncated(mut self, max_len: usize) -> Self {
+        self.output = truncate_output(&self.output, max_len);
+        self.error = self.error.map(|e| truncate_output(&e, max_len));
+        self
+    }
+}
+
+/// Default cap on tool output kept in check details and work records (64 KiB).
+pub const DEFAULT_MAX_OUTPUT_LEN: usize = 64 * 1024;
+
+/// Shorten `output` to at most `max_len` bytes of content.
+///
+/// Output within the limit is returned unchanged. Otherwise the first
+/// quarter and the last three quarters of the budget are kept (the tail
+/// usually holds the error summary) around a `... [truncated N bytes] ...`
+/// marker. Cuts fall on UTF-8 character boundaries.
+pub fn truncate_output(output: &str, max_len: usize) -> String {
+    if output.len() <= max_len {
+        return output.to_string();
+    }
+
+    let mut head_end = max_len / 4;
+    while !output.is_char_boundary(head_end) {
+        head_end -= 1;
+    }
+    let mut tail_start = output.len() - (max_len - max_len / 4);
+    while !output.is_char_boundary(tail_start) {
+        tail_start += 1;
+    }
+
+    format!(
+        "{}\n... [truncated {} bytes] ...\n{}",
+        &output[..head_end],
+        tail_start - head_end,
+        &output[tail_start..]
+    )
 }
 
 /// A finding from a quality check.
@@ -186,6 +293,40 @@ pub struct Metric {
     pub unit: Option<String>,
 }
 
+/// The check results of one quality run against a task, kept so runs can
+/// be compared over time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QualityRun {
+    /// Unique identifier
+    pub id: QualityRunId,
+    /// Task the checks ran for
+    pub task_id: TaskId,
+    /// When the checks ran
+    pub run_at: Time,
+    /// One result per check, in the order the checks ran
+    pub results: Vec<QualityCheckResult>,
+}
+
+impl QualityRun {
+    /// Record `results` as a run for `task_id` at `run_at`.
+    pub fn new(task_id: TaskId, results: Vec<QualityCheckResult>, run_at: Time) -> Self {
+        Self { id: QualityRunId::new(), task_id, run_at, results }
+    }
+
+    /// Share of checks that passed, from 0.0 to 1.0; 1.0 for a run without checks.
+    pub fn pass_rate(&self) -> f32 {
+        if self.results.is_empty() {
+            return 1.0;
+        }
+        self.results.iter().filter(|r| r.passed).count() as f32 / self.results.len() as f32
+    }
+
+    /// Findings reported across all checks.
+    pub fn finding_count(&self) -> usize {
+        self.results.iter().map(|r| r.findings.len()).sum()
+    }
+}
+
 /// Human review result.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HumanReviewResult {
@@ -214,6 +355,9 @@ pub enum NotificationChannel {
 /// Quality profile.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QualityProfile {
+    /// Unique identifier
+    #[serde(default)]
+    pub id: QualityProfileId,
     pub name: String,
     pub description: String,
     pub checks: Vec<QualityCheckId>,
@@ -221,12 +365,54 @@ pub struct QualityProfile {
     pub default_strategy: GateStrategy,
 }
 
+impl QualityProfile {
+    /// The gate a task in `phase` inherits from this profile: the
+    /// profile's checks plus those of its gates for that phase, or `None`
+    /// if that leaves nothing to check.
+    pub fn gate_for_phase(&self, phase: PhaseId) -> Option<QualityGate> {
+        let mut checks = self.checks.clone();
+        let mut strategy = &self.default_strategy;
+        for gate in self.phase_gates.iter().filter(|g| g.phase == phase) {
+            for check in &gate.checks {
+                if !checks.contains(check) {
+                    checks.push(*check);
+                }
+            }
+            strategy = &gate.strategy;
+        }
+        if checks.is_empty() {
+            return None;
+        }
+
+        let (pass_condition, on_failure) = match strategy {
+            GateStrategy::AllMustPass => (PassCondition::AllPassed, FailureAction::Block),
+            GateStrategy::WarningsAllowed { .. } => (PassCondition::AllPassed, FailureAction::Warn),
+            GateStrategy::ManualDecision => (PassCondition::AllPassed, FailureAction::Escalate),
+            GateStrategy::Custom { rule } => (
+                PassCondition::Custom { expression: rule.clone() },
+                FailureAction::Block,
+            ),
+        };
+        Some(QualityGate {
+            name: format!("profile:{}", self.name),
+            description: self.description.clone(),
+            checks,
+            pass_condition,
+            on_failure,
+        })
+    }
+}
+
 /// Phase gate.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PhaseGate {
     pub phase: crate::PhaseId,
     pub checks: Vec<QualityCheckId>,
     pub strategy: GateStrategy,
+    /// Failed runs in a row after which the gate is handed to a human
+    /// reviewer instead of blocking again; 0 never escalates
+    #[serde(default)]
+    pub escalate_after_failures: u32,
 }
 
 /// Gate strategy.
@@ -259,3 +445,66 @@ pub enum QualityOverallStatus {
     Failed,
     PendingReview,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_output_keeps_short_output() {
+        assert_eq!(truncate_output("all good", 64), "all good");
+        assert_eq!(truncate_output("", 0), "");
+    }
+
+    #[test]
+    fn test_truncate_output_keeps_head_and_tail() {
+        let output = format!("{}{}{}", "a".repeat(10), "b".repeat(100), "c".repeat(30));
+        let truncated = truncate_output(&output, 40);
+        assert_eq!(
+            truncated,
+            format!("{}\n... [truncated 100 bytes] ...\n{}", "a".repeat(10), "c".repeat(30))
+        );
+    }
+
+    #[test]
+    fn test_truncate_output_respects_char_boundaries() {
+        let output = "é".repeat(100);
+        let truncated = truncate_output(&output, 11);
+        let (head, rest) = truncated.split_once('\n').unwrap();
+        let tail = rest.rsplit_once('\n').unwrap().1;
+        assert_eq!(head, "é");
+        assert_eq!(tail, "é".repeat(4));
+        assert!(truncated.contains("[truncated 190 bytes]"));
+    }
+
+    #[test]
+    fn test_profile_gate_merges_phase_checks() {
+        let (shared, release_only) = (QualityCheckId::new(), QualityCheckId::new());
+        let release = PhaseId::new();
+        let profile = QualityProfile {
+            id: QualityProfileId::new(),
+            name: "strict".to_string(),
+            description: String::new(),
+            checks: vec![shared],
+            phase_gates: vec![PhaseGate {
+                phase: release,
+                checks: vec![shared, release_only],
+                strategy: GateStrategy::ManualDecision,
+                escalate_after_failures: 0,
+            }],
+            default_strategy: GateStrategy::AllMustPass,
+        };
+
+        let gate = profile.gate_for_phase(release).unwrap();
+        assert_eq!(gate.name, "profile:strict");
+        assert_eq!(gate.checks, vec![shared, release_only]);
+        assert_eq!(gate.on_failure, FailureAction::Escalate);
+
+        let gate = profile.gate_for_phase(PhaseId::new()).unwrap();
+        assert_eq!(gate.checks, vec![shared]);
+        assert_eq!(gate.on_failure, FailureAction::Block);
+
+        let empty = QualityProfile { checks: vec![], ..profile };
+        assert!(empty.gate_for_phase(PhaseId::new()).is_none());
+    }
+}
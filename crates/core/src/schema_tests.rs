@@ -0,0 +1,171 @@
+//! Schema stability of the persisted entities.
+//!
+//! Both storage backends keep entities as their serde JSON, so renaming a
+//! field or changing its type silently breaks stores written by earlier
+//! versions. Every entity has a golden fixture under `fixtures/` in the
+//! shape it is stored today: each must still load, and must serialize back
+//! unchanged. When a change fails here, keep old data loadable (a
+//! `#[serde(default)]` or alias) before updating the fixture.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::*;
+
+/// Load the fixture `name` as `T`, check it serializes back to the same
+/// JSON and survives a round trip, and return it for field checks.
+fn golden<T: Serialize + DeserializeOwned>(name: &str, json: &str) -> T {
+    let fixture: serde_json::Value = serde_json::from_str(json).unwrap();
+    let value: T = serde_json::from_value(fixture.clone())
+        .unwrap_or_else(|e| panic!("fixtures/{} no longer loads: {}", name, e));
+    let written = serde_json::to_value(&value).unwrap();
+    assert_eq!(
+        written, fixture,
+        "fixtures/{} serializes differently; the stored schema changed",
+        name
+    );
+    assert_round_trip(&value);
+    value
+}
+
+/// Assert `value` reads back from its JSON unchanged.
+fn assert_round_trip<T: Serialize + DeserializeOwned>(value: &T) {
+    let json = serde_json::to_string(value).unwrap();
+    let reread: T = serde_json::from_str(&json).unwrap();
+    assert_eq!(serde_json::to_string(&reread).unwrap(), json);
+}
+
+fn at(rfc3339: &str) -> Time {
+    rfc3339.parse().unwrap()
+}
+
+#[test]
+fn test_task_fixture() {
+    let task: Task = golden("task.json", include_str!("../fixtures/task.json"));
+    assert_eq!(task.id.to_string(), "01JAQ0000000000000000TASK1");
+    assert_eq!(task.status, TaskStatus::Active);
+    assert!(matches!(task.state, Some(TaskState::InProgress { .. })));
+    assert_eq!((task.priority, task.base_priority), (192, Some(128)));
+    assert_eq!(task.due_at, Some(at("2026-01-20T17:00:00Z")));
+    assert_eq!(task.steps[0].tool.env, vec![("RUST_LOG".to_string(), "debug".to_string())]);
+    assert!(matches!(task.quality_gates[0].pass_condition, PassCondition::Expression { .. }));
+    assert_eq!(task.actuals.unwrap().source, ActualsSource::Metrics);
+    assert_eq!(task.claim.unwrap().agent, AgentId::new("claude-1"));
+}
+
+#[test]
+fn test_goal_fixture() {
+    let goal: Goal = golden("goal.json", include_str!("../fixtures/goal.json"));
+    assert_eq!(goal.title, "Ship user accounts");
+    assert_eq!(goal.status, GoalStatus::Active);
+    assert_eq!(goal.success_criteria.len(), 3);
+    assert_eq!(goal.success_criteria[0].status, CriterionStatus::Met);
+    assert!(matches!(goal.success_criteria[2].verification, VerificationMethod::Command(_)));
+    assert_eq!(goal.progress.blockers[0].category, BlockerCategory::External);
+    assert_eq!(goal.status_history[1].to, GoalStatus::Active);
+}
+
+#[test]
+fn test_knowledge_fixture() {
+    let knowledge: Knowledge = golden("knowledge.json", include_str!("../fixtures/knowledge.json"));
+    assert!(matches!(knowledge.knowledge_type, KnowledgeType::BestPractice { .. }));
+    assert_eq!(knowledge.tags, vec!["auth", "security"]);
+    assert_eq!(knowledge.metadata.quality_score, 0.75);
+    assert_eq!(knowledge.usage_stats.feedback[0].rating, 5);
+    assert!(!knowledge.archived);
+}
+
+#[test]
+fn test_work_record_fixture() {
+    let record: WorkRecord = golden("work_record.json", include_str!("../fixtures/work_record.json"));
+    assert_eq!(record.task_id.to_string(), "01JAQ0000000000000000TASK1");
+    assert_eq!(record.duration, Some(chrono::Duration::minutes(90)));
+    assert_eq!(record.result.status, CompletionStatus::Success);
+    assert_eq!(record.result.metrics.token_used, Some(12000));
+    assert_eq!(record.events[0].event_type, WorkEventType::StepCompleted);
+    assert_eq!(record.resolutions[0].issue_id, record.issues[0].id);
+}
+
+#[test]
+fn test_phase_fixture() {
+    let phase: Phase = golden("phase.json", include_str!("../fixtures/phase.json"));
+    assert_eq!(phase.name, "Authentication");
+    assert_eq!(phase.status, PhaseStatus::InProgress);
+    assert_eq!(phase.estimated_duration, Some(std::time::Duration::from_secs(2 * 24 * 3600)));
+    assert!(phase.quality_profile.is_some());
+}
+
+#[test]
+fn test_event_fixture() {
+    let event: Event = golden("event.json", include_str!("../fixtures/event.json"));
+    assert_eq!(event.action, "task_completed");
+    assert_eq!(event.actor, AgentId::new("claude-1"));
+    assert_eq!(event.timestamp, at("2026-01-15T11:30:00Z"));
+    assert_eq!(event.related_tasks.len(), 1);
+}
+
+#[test]
+fn test_quality_check_fixture() {
+    let check: QualityCheck = golden("quality_check.json", include_str!("../fixtures/quality_check.json"));
+    assert!(matches!(
+        check.check_type,
+        QualityCheckType::Generic(GenericCheckType::TestsPass { min_coverage: Some(80.0), .. })
+    ));
+    assert_eq!(check.category, QualityCategory::Testing);
+    assert_eq!(check.scope, CheckScope::ChangedOnly);
+    assert_eq!(check.working_dir.as_deref(), Some(std::path::Path::new("services/api")));
+    assert!(check.blocking);
+}
+
+#[test]
+fn test_fields_added_since_first_release_are_optional() {
+    // Stores written before these fields existed must still load
+    let mut task: serde_json::Value = serde_json::from_str(include_str!("../fixtures/task.json")).unwrap();
+    let fields = task.as_object_mut().unwrap();
+    for field in [
+        "state", "parent", "children", "archived", "merged_into", "priority", "base_priority", "due_at",
+        "actuals", "claim", "created_knowledge",
+    ] {
+        fields.remove(field);
+    }
+    let task: Task = serde_json::from_value(task).unwrap();
+    assert_eq!(task.priority, Priority::default().value());
+    assert_eq!((task.base_priority, task.due_at), (None, None));
+
+    let mut check: serde_json::Value =
+        serde_json::from_str(include_str!("../fixtures/quality_check.json")).unwrap();
+    for field in ["timeout", "scope", "working_dir", "blocking"] {
+        check.as_object_mut().unwrap().remove(field);
+    }
+    let check: QualityCheck = serde_json::from_value(check).unwrap();
+    assert_eq!(check.scope, CheckScope::Full);
+    assert!(check.blocking);
+}
+
+#[test]
+fn test_runtime_values_round_trip() {
+    // Timestamps taken now carry sub-second precision the fixtures do not
+    let task_id = TaskId::new();
+    assert_round_trip(&Event::new(AgentId::ai(), "task_created", "ok"));
+    assert_round_trip(&WorkRecord::new(task_id, Executor::Hybrid { ai: "a".into(), human: "h".into() }, chrono::Utc::now()));
+
+    let mut task: Task = serde_json::from_str(include_str!("../fixtures/task.json")).unwrap();
+    let now = chrono::Utc::now();
+    let states = [
+        TaskState::Created { created_at: now, created_by: "system".to_string() },
+        TaskState::Paused {
+            paused_at: now,
+            reason: "lunch".to_string(),
+            previous_state: Box::new(TaskState::ContextRead { read_at: now }),
+        },
+        TaskState::Abandoned {
+            abandoned_at: now,
+            reason: AbandonReason::Other { reason: "dup".to_string(), details: None },
+        },
+        TaskState::Completed { completed_at: now, completed_by: "ai".to_string() },
+    ];
+    for state in states {
+        task.state = Some(state);
+        assert_round_trip(&task);
+    }
+}
@@ -34,6 +34,11 @@ pub struct Task {
     /// Current status
     pub status: TaskStatus,
 
+    /// Fine-grained workflow state of the interactive flow, when it has
+    /// driven the task; `status` is kept in step with it
+    #[serde(default)]
+    pub state: Option<TaskState>,
+
     /// Progress tracking
     pub progress: TaskProgress,
 
@@ -46,9 +51,50 @@ pub struct Task {
     /// Tasks this blocks
     pub blocks: Vec<TaskId>,
 
+    /// Parent task, if this is a sub-task
+    #[serde(default)]
+    pub parent: Option<TaskId>,
+
+    /// Sub-tasks this task was split into
+    #[serde(default)]
+    pub children: Vec<TaskId>,
+
+    /// Hidden from default listings but kept recoverable
+    #[serde(default)]
+    pub archived: bool,
+
+    /// Surviving task this one was merged into as a duplicate
+    #[serde(default)]
+    pub merged_into: Option<TaskId>,
+
+    /// Raw priority, higher is more urgent (see [`Priority`] for the levels)
+    #[serde(default = "default_priority")]
+    pub priority: u8,
+
+    /// Priority the task was given before a recomputation raised it, kept so
+    /// later passes start from it again; `None` until the first write-back
+    #[serde(default)]
+    pub base_priority: Option<u8>,
+
+    /// When the task should be done by, if it has a deadline
+    #[serde(default)]
+    pub due_at: Option<Time>,
+
     /// Work records from executions
     pub work_records: Vec<WorkRecordId>,
 
+    /// How long the task actually took and what it cost, once known
+    #[serde(default)]
+    pub actuals: Option<TaskActuals>,
+
+    /// Agent currently holding the task, if any
+    #[serde(default)]
+    pub claim: Option<TaskClaim>,
+
+    /// Knowledge captured while doing the task
+    #[serde(default)]
+    pub created_knowledge: Vec<KnowledgeId>,
+
     /// Creation timestamp
     pub created_at: Time,
 
@@ -56,6 +102,86 @@ pub struct Task {
     pub updated_at: Time,
 }
 
+fn default_priority() -> u8 {
+    Priority::default().value()
+}
+
+impl Task {
+    /// Priority level of the task.
+    pub fn priority_level(&self) -> Priority {
+        Priority::from_value(self.priority)
+    }
+
+    /// Workflow state of the task, falling back to `Created` for tasks the
+    /// interactive flow has not touched yet.
+    pub fn current_state(&self) -> TaskState {
+        self.state.clone().unwrap_or_else(|| TaskState::Created {
+            created_at: self.created_at,
+            created_by: "system".to_string(),
+        })
+    }
+
+    /// Move the task to `state`, deriving `status` from it.
+    pub fn set_state(&mut self, state: TaskState) {
+        self.status = TaskStatus::from(state.clone());
+        self.state = Some(state);
+    }
+
+    /// Change `status` outside the interactive flow, dropping the workflow
+    /// state when it no longer matches the new status.
+    pub fn set_status(&mut self, status: TaskStatus) {
+        if self.state.clone().is_some_and(|state| TaskStatus::from(state) != status) {
+            self.state = None;
+        }
+        self.status = status;
+    }
+
+    /// Success criteria that no quality gate appears to enforce.
+    ///
+    /// A criterion counts as covered when one of its keywords appears in a
+    /// gate's name or description, or in a metric or flag named by the
+    /// gate's pass condition. Matching is by keyword, so this flags obvious
+    /// gaps rather than proving coverage.
+    pub fn coverage_gaps(&self) -> Vec<String> {
+        let mut gate_terms = std::collections::HashSet::new();
+        for gate in &self.quality_gates {
+            gate_terms.extend(keywords(&gate.name));
+            gate_terms.extend(keywords(&gate.description));
+            if let PassCondition::Expression { condition } = &gate.pass_condition {
+                condition.visit_names(&mut |name| gate_terms.extend(keywords(name)));
+            }
+        }
+
+        self.intent
+            .success_criteria
+            .iter()
+            .filter(|criterion| !keywords(criterion).iter().any(|k| gate_terms.contains(k)))
+            .cloned()
+            .collect()
+    }
+
+    /// The gates a quality check of this task runs: its own gates, then
+    /// the gate inherited from its phase's quality `profile`, if any.
+    pub fn effective_quality_gates(&self, profile: Option<&crate::QualityProfile>) -> Vec<QualityGate> {
+        let mut gates = self.quality_gates.clone();
+        gates.extend(profile.and_then(|p| p.gate_for_phase(self.phase_id)));
+        gates
+    }
+
+    /// Whether the task has reached a terminal status.
+    pub fn is_closed(&self) -> bool {
+        matches!(self.status, TaskStatus::Done | TaskStatus::Abandoned)
+    }
+
+    /// Link `child` as a sub-task of this task, updating both ends.
+    pub fn add_child(&mut self, child: &mut Task) {
+        child.parent = Some(self.id);
+        if !self.children.contains(&child.id) {
+            self.children.push(child.id);
+        }
+    }
+}
+
 /// AI's understanding of task intent.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaskIntent {
@@ -144,7 +270,7 @@ pub struct QualityGate {
 }
 
 /// Pass condition for a quality gate.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum PassCondition {
     /// All checks must pass
     AllPassed,
@@ -154,6 +280,120 @@ pub enum PassCondition {
 
     /// Custom expression
     Custom { expression: String },
+
+    /// Composite expression over check results and metrics
+    Expression {
+        /// Root of the expression tree
+        condition: GateCondition,
+    },
+}
+
+/// Composite condition evaluated against a gate's check results.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GateCondition {
+    /// Every sub-condition must hold
+    AllOf {
+        /// Conditions to combine
+        conditions: Vec<GateCondition>,
+    },
+
+    /// At least one sub-condition must hold
+    AnyOf {
+        /// Conditions to combine
+        conditions: Vec<GateCondition>,
+    },
+
+    /// Negation of a sub-condition
+    Not {
+        /// Condition to negate
+        condition: Box<GateCondition>,
+    },
+
+    /// The given check ran and passed
+    CheckPassed {
+        /// Check that must have passed
+        check: QualityCheckId,
+    },
+
+    /// A metric reported by any check compares true against a threshold
+    Metric {
+        /// Metric name
+        name: String,
+        /// Comparison applied as `metric <op> value`
+        op: Comparison,
+        /// Threshold
+        value: f64,
+    },
+
+    /// A boolean flag in the work context metadata is set (e.g. a manual override)
+    Flag {
+        /// Metadata key
+        name: String,
+    },
+}
+
+impl GateCondition {
+    /// Call `f` with every metric and flag name in the condition tree.
+    fn visit_names(&self, f: &mut impl FnMut(&str)) {
+        match self {
+            GateCondition::AllOf { conditions } | GateCondition::AnyOf { conditions } => {
+                conditions.iter().for_each(|c| c.visit_names(f));
+            }
+            GateCondition::Not { condition } => condition.visit_names(f),
+            GateCondition::Metric { name, .. } | GateCondition::Flag { name } => f(name),
+            GateCondition::CheckPassed { .. } => {}
+        }
+    }
+}
+
+/// Significant lowercase words of `text`, with a trailing plural `s` removed.
+pub(crate) fn keywords(text: &str) -> Vec<String> {
+    const STOP_WORDS: &[&str] = &[
+        "all", "and", "any", "are", "for", "has", "have", "must", "not", "pass", "passe", "passed",
+        "should", "the", "with",
+    ];
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|w| w.len() >= 3 && !w.chars().all(|c| c.is_ascii_digit()))
+        .map(|w| match w.strip_suffix('s') {
+            Some(stem) if stem.len() >= 3 && !stem.ends_with('s') => stem.to_string(),
+            _ => w,
+        })
+        .filter(|w| !STOP_WORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// Comparison operator for metric conditions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Comparison {
+    /// Equal
+    Eq,
+    /// Not equal
+    Ne,
+    /// Greater than
+    Gt,
+    /// Greater than or equal
+    Ge,
+    /// Less than
+    Lt,
+    /// Less than or equal
+    Le,
+}
+
+impl Comparison {
+    /// Apply the comparison as `lhs <op> rhs`.
+    pub fn compare(self, lhs: f64, rhs: f64) -> bool {
+        match self {
+            Comparison::Eq => lhs == rhs,
+            Comparison::Ne => lhs != rhs,
+            Comparison::Gt => lhs > rhs,
+            Comparison::Ge => lhs >= rhs,
+            Comparison::Lt => lhs < rhs,
+            Comparison::Le => lhs <= rhs,
+        }
+    }
 }
 
 /// Action to take on quality gate failure.
@@ -332,6 +572,25 @@ pub enum AbandonReason {
     },
 }
 
+impl AbandonReason {
+    /// Whether the abandoned task may be picked up again.
+    ///
+    /// Cancellations, requirement changes and technical dead ends retire the
+    /// task for good, so whatever waited on it no longer has to. A voluntary
+    /// abandonment is recoverable when the task can be reassigned, and every
+    /// other reason leaves the task to be retried later.
+    pub fn is_recoverable(&self) -> bool {
+        match self {
+            Self::Voluntary { can_be_reassigned, .. } => *can_be_reassigned,
+            Self::ProjectCancelled { .. }
+            | Self::GoalCancelled { .. }
+            | Self::RequirementChanged { .. }
+            | Self::TechnicalLimitation { .. } => false,
+            _ => true,
+        }
+    }
+}
+
 /// Impact of a change on task progress.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ChangeImpact {
@@ -366,6 +625,14 @@ pub enum QualityOverallStatus {
     PendingReview,
 }
 
+impl QualityOverallStatus {
+    /// Whether the task may be completed; warnings from advisory checks do
+    /// not hold it back.
+    pub fn allows_completion(&self) -> bool {
+        matches!(self, Self::Passed | Self::PassedWithWarnings)
+    }
+}
+
 /// State transition result.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum StateTransition {
@@ -391,42 +658,23 @@ impl TaskState {
         !matches!(self, Self::Completed { .. } | Self::Abandoned { .. })
     }
 
-    /// Get guidance message for current state.
-    pub fn get_guidance(&self) -> &'static str {
+    /// Message catalog key of the guidance for the current state.
+    pub fn guidance_key(&self) -> &'static str {
         match self {
-            Self::Created { .. } => {
-                "请先调用 read_task_context() 读取任务上下文，了解项目信息、依赖关系和质检要求。"
-            }
-            Self::ContextRead { .. } => {
-                "请调用 review_knowledge() 查询相关知识，学习最佳实践和类似实现。"
-            }
-            Self::KnowledgeReviewed { .. } => {
-                "现在可以开始执行任务了。调用 start_execution() 开始，并使用 log_work() 记录工作进展。"
-            }
-            Self::InProgress { .. } => {
-                "继续执行任务，使用 log_work() 记录工作。完成后调用 finish_work() 提交工作记录。"
-            }
-            Self::WorkRecorded { .. } => {
-                "工作已记录，请调用 run_quality_check() 运行质检。"
-            }
-            Self::QualityChecking { .. } => {
-                "质检正在运行，请等待结果..."
-            }
-            Self::QualityCompleted { result, .. } => {
-                match result.overall_status {
-                    QualityOverallStatus::Passed => "质检通过！调用 complete_task() 完成任务。",
-                    _ => "质检未通过，请修复问题后调用 start_execution() 重新开始执行。",
-                }
-            }
-            Self::Paused { .. } => {
-                "任务已暂停。调用 resume_task() 恢复执行。"
-            }
-            Self::Abandoned { .. } => {
-                "任务已放弃。"
-            }
-            Self::Completed { .. } => {
-                "任务已完成。"
-            }
+            Self::Created { .. } => "guidance.state.created",
+            Self::ContextRead { .. } => "guidance.state.context_read",
+            Self::KnowledgeReviewed { .. } => "guidance.state.knowledge_reviewed",
+            Self::InProgress { .. } => "guidance.state.in_progress",
+            Self::WorkRecorded { .. } => "guidance.state.work_recorded",
+            Self::QualityChecking { .. } => "guidance.state.quality_checking",
+            Self::QualityCompleted { result, .. } => match result.overall_status {
+                QualityOverallStatus::Passed => "guidance.state.quality_passed",
+                QualityOverallStatus::PassedWithWarnings => "guidance.state.quality_passed_with_warnings",
+                _ => "guidance.state.quality_failed",
+            },
+            Self::Paused { .. } => "guidance.state.paused",
+            Self::Abandoned { .. } => "guidance.state.abandoned",
+            Self::Completed { .. } => "guidance.state.completed",
         }
     }
 
@@ -447,6 +695,113 @@ impl TaskState {
     }
 }
 
+/// Task priority level.
+///
+/// Tasks keep a raw `u8` priority (higher is more urgent) so selectors can
+/// rank at a finer grain. Each level covers a band of that range and
+/// converts back to a canonical value:
+///
+/// | Level      | Range        | Canonical |
+/// |------------|--------------|-----------|
+/// | `Critical` | 204..=255    | 255       |
+/// | `High`     | 153..=203    | 192       |
+/// | `Medium`   | 102..=152    | 128       |
+/// | `Low`      | 51..=101     | 64        |
+/// | `Trivial`  | 0..=50       | 0         |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Priority {
+    /// Must be handled before anything else
+    Critical,
+    /// Important, handle soon
+    High,
+    /// Normal priority
+    #[default]
+    Medium,
+    /// Can wait
+    Low,
+    /// Nice to have
+    Trivial,
+}
+
+impl Priority {
+    /// All levels, most urgent first.
+    pub const ALL: [Priority; 5] = [
+        Priority::Critical,
+        Priority::High,
+        Priority::Medium,
+        Priority::Low,
+        Priority::Trivial,
+    ];
+
+    /// Canonical raw value of this level.
+    pub fn value(self) -> u8 {
+        match self {
+            Priority::Critical => 255,
+            Priority::High => 192,
+            Priority::Medium => 128,
+            Priority::Low => 64,
+            Priority::Trivial => 0,
+        }
+    }
+
+    /// Level whose band contains `value`.
+    pub fn from_value(value: u8) -> Self {
+        match value {
+            204..=255 => Priority::Critical,
+            153..=203 => Priority::High,
+            102..=152 => Priority::Medium,
+            51..=101 => Priority::Low,
+            0..=50 => Priority::Trivial,
+        }
+    }
+
+    /// Level name.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Priority::Critical => "Critical",
+            Priority::High => "High",
+            Priority::Medium => "Medium",
+            Priority::Low => "Low",
+            Priority::Trivial => "Trivial",
+        }
+    }
+}
+
+impl From<u8> for Priority {
+    fn from(value: u8) -> Self {
+        Priority::from_value(value)
+    }
+}
+
+impl From<Priority> for u8 {
+    fn from(priority: Priority) -> Self {
+        priority.value()
+    }
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Error returned when parsing an unknown priority name.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unknown priority '{0}', expected one of Critical, High, Medium, Low, Trivial")]
+pub struct ParsePriorityError(pub String);
+
+impl std::str::FromStr for Priority {
+    type Err = ParsePriorityError;
+
+    /// Parse a level name, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Priority::ALL
+            .into_iter()
+            .find(|p| p.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| ParsePriorityError(s.to_string()))
+    }
+}
+
 /// Legacy TaskStatus for backward compatibility (use TaskState for new code).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TaskStatus {
@@ -466,6 +821,24 @@ pub enum TaskStatus {
     Abandoned,
 }
 
+impl TaskStatus {
+    /// Whether the task lifecycle allows moving from this status to `to`.
+    ///
+    /// Any open task can be abandoned; `Done` and `Abandoned` are final.
+    /// Active tasks go back to `Queued` when their work is interrupted.
+    pub fn can_transition_to(self, to: TaskStatus) -> bool {
+        use TaskStatus::*;
+        matches!(
+            (self, to),
+            (Idea, Queued | Abandoned)
+                | (Queued, Idea | Active | Blocked | Abandoned)
+                | (Active, Queued | Blocked | Review | Done | Abandoned)
+                | (Blocked, Queued | Active | Abandoned)
+                | (Review, Active | Done | Abandoned)
+        )
+    }
+}
+
 impl From<TaskState> for TaskStatus {
     fn from(state: TaskState) -> Self {
         match state {
@@ -481,6 +854,56 @@ impl From<TaskState> for TaskStatus {
     }
 }
 
+/// Where a task's actuals came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActualsSource {
+    /// Captured from the work metrics when the task was completed
+    Metrics,
+    /// Recorded by hand; never replaced by captured metrics
+    Manual,
+}
+
+/// What a task actually took, the ground truth estimates are checked against.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskActuals {
+    /// Time spent on the task
+    pub duration: std::time::Duration,
+
+    /// Tokens used, if known
+    pub tokens: Option<usize>,
+
+    /// Where the values came from
+    pub source: ActualsSource,
+
+    /// When they were recorded
+    pub recorded_at: Time,
+}
+
+/// An agent's exclusive hold on a task, so agents sharing a store do not
+/// pick up the same work. Claims lapse at `expires_at` unless renewed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskClaim {
+    /// Agent holding the claim
+    pub agent: crate::AgentId,
+
+    /// Token the holder releases the claim with
+    pub token: crate::ClaimToken,
+
+    /// When the claim was taken or last renewed
+    pub claimed_at: Time,
+
+    /// When the claim lapses
+    pub expires_at: Time,
+}
+
+impl TaskClaim {
+    /// Whether the claim still holds at `now`.
+    pub fn is_active(&self, now: Time) -> bool {
+        now < self.expires_at
+    }
+}
+
 /// Progress tracking for a task.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaskProgress {
@@ -497,6 +920,27 @@ pub struct TaskProgress {
     pub message: String,
 }
 
+impl TaskProgress {
+    /// Progress derived from sub-task completion: the share of children that
+    /// are done or abandoned.
+    pub fn from_children(children: &[Task]) -> Self {
+        let total = children.len();
+        let closed = children.iter().filter(|c| c.is_closed()).count();
+        let percentage = if total > 0 {
+            (closed as f32 / total as f32) * 100.0
+        } else {
+            0.0
+        };
+
+        Self {
+            percentage,
+            current_step: None,
+            total_steps: total,
+            message: format!("{}/{} sub-tasks closed", closed, total),
+        }
+    }
+}
+
 impl Default for TaskProgress {
     fn default() -> Self {
         Self {
@@ -542,7 +986,279 @@ pub struct TaskFilter {
 
     /// Filter by minimum confidence
     pub min_confidence: Option<f32>,
+
+    /// Only tasks without a parent
+    #[serde(default)]
+    pub roots_only: bool,
+
+    /// With `roots_only`, also return the sub-tasks of each matching root at
+    /// any depth, whatever their status, in depth-first order with every
+    /// sub-task after its parent so callers can nest them
+    #[serde(default)]
+    pub include_subtasks: bool,
+}
+
+/// A reusable shape for a recurring kind of task.
+///
+/// Text fields may contain `{{name}}` placeholders for the template's
+/// parameters, filled in when a task is created from the template.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskTemplate {
+    /// Unique identifier
+    pub id: crate::TaskTemplateId,
+
+    /// Name the template is picked by
+    pub name: String,
+
+    /// Title of created tasks
+    pub title: String,
+
+    /// Description of created tasks
+    pub description: String,
+
+    /// Predefined execution steps
+    pub steps: Vec<ExecutionStep>,
+
+    /// Predefined quality gates
+    pub quality_gates: Vec<QualityGate>,
+
+    /// Success criteria of created tasks
+    pub success_criteria: Vec<String>,
+
+    /// Placeholders the text fields use
+    pub parameters: Vec<crate::TemplateParameter>,
+
+    /// Raw priority of created tasks
+    #[serde(default = "default_priority")]
+    pub priority: u8,
+
+    /// Creation timestamp
+    pub created_at: Time,
+}
+
+impl TaskTemplate {
+    /// Template with the shape of `task`, where every occurrence of each
+    /// `(name, value)` pair's value becomes a required `{{name}}` placeholder.
+    pub fn from_task(task: &Task, name: impl Into<String>, placeholders: &[(String, String)]) -> Self {
+        let template = TaskTemplate {
+            id: crate::TaskTemplateId::new(),
+            name: name.into(),
+            title: task.title.clone(),
+            description: task.description.clone(),
+            steps: task.steps.clone(),
+            quality_gates: task.quality_gates.clone(),
+            success_criteria: task.intent.success_criteria.clone(),
+            parameters: placeholders
+                .iter()
+                .map(|(name, value)| crate::TemplateParameter {
+                    name: name.clone(),
+                    description: format!("Replaces \"{}\"", value),
+                    default_value: None,
+                    required: true,
+                })
+                .collect(),
+            priority: task.priority,
+            created_at: chrono::Utc::now(),
+        };
+        template.map_text(|text| {
+            placeholders
+                .iter()
+                .filter(|(_, value)| !value.is_empty())
+                .fold(text.to_string(), |text, (name, value)| text.replace(value, &format!("{{{{{}}}}}", name)))
+        })
+    }
+
+    /// Copy with `f` applied to every text field a placeholder may appear
+    /// in: title, description, success criteria, and the descriptions,
+    /// arguments and verifications of steps.
+    pub fn map_text(&self, mut f: impl FnMut(&str) -> String) -> Self {
+        let mut template = self.clone();
+        template.title = f(&self.title);
+        template.description = f(&self.description);
+        template.success_criteria = self.success_criteria.iter().map(|c| f(c)).collect();
+        for step in &mut template.steps {
+            step.description = f(&step.description);
+            step.tool.args = step.tool.args.iter().map(|a| f(a)).collect();
+            if let Some(verify) = &mut step.verify {
+                verify.check = f(&verify.check);
+                verify.expected = f(&verify.expected);
+            }
+        }
+        template
+    }
 }
 
 // Re-exports for compatibility
 pub use crate::{KnowledgeId, QualityCheckId};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gate(name: &str, description: &str, pass_condition: PassCondition) -> QualityGate {
+        QualityGate {
+            name: name.to_string(),
+            description: description.to_string(),
+            checks: Vec::new(),
+            pass_condition,
+            on_failure: FailureAction::Block,
+        }
+    }
+
+    fn task_with(criteria: &[&str], quality_gates: Vec<QualityGate>) -> Task {
+        let now = chrono::Utc::now();
+        Task {
+            id: TaskId::new(),
+            title: "Task".to_string(),
+            description: String::new(),
+            intent: TaskIntent {
+                natural_language: String::new(),
+                context: TaskContext {
+                    relevant_knowledge: Vec::new(),
+                    similar_tasks: Vec::new(),
+                    affected_files: Vec::new(),
+                },
+                success_criteria: criteria.iter().map(|c| c.to_string()).collect(),
+            },
+            steps: Vec::new(),
+            inputs: Vec::new(),
+            expected_outputs: Vec::new(),
+            quality_gates,
+            status: TaskStatus::Queued,
+            progress: TaskProgress::default(),
+            phase_id: PhaseId::new(),
+            depends_on: Vec::new(),
+            blocks: Vec::new(),
+            parent: None,
+            children: Vec::new(),
+            archived: false,
+            priority: default_priority(),
+            work_records: Vec::new(),
+            actuals: None,
+            claim: None,
+            state: None,
+            merged_into: None,
+            base_priority: None,
+            due_at: None,
+            created_knowledge: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_coverage_gaps_fully_covered() {
+        let coverage = PassCondition::Expression {
+            condition: GateCondition::Metric {
+                name: "line_coverage".to_string(),
+                op: Comparison::Ge,
+                value: 90.0,
+            },
+        };
+        let task = task_with(
+            &["All tests pass", "90% coverage"],
+            vec![gate("Tests", "Unit tests must pass", PassCondition::AllPassed), gate("Metrics", "", coverage)],
+        );
+        assert!(task.coverage_gaps().is_empty());
+    }
+
+    #[test]
+    fn test_coverage_gaps_partially_covered() {
+        let task = task_with(
+            &["All tests pass", "90% coverage", "No clippy warnings"],
+            vec![gate("Tests", "All checks must pass", PassCondition::AllPassed)],
+        );
+        assert_eq!(task.coverage_gaps(), vec!["90% coverage", "No clippy warnings"]);
+
+        let task = task_with(&["Docs updated"], Vec::new());
+        assert_eq!(task.coverage_gaps(), vec!["Docs updated"]);
+    }
+
+    #[test]
+    fn test_template_from_task_replaces_values_with_placeholders() {
+        let mut task = task_with(&["GET /users returns 200"], vec![gate("tests", "", PassCondition::AllPassed)]);
+        task.title = "Add /users endpoint".to_string();
+        task.steps.push(ExecutionStep {
+            order: 1,
+            description: "Run the /users tests".to_string(),
+            tool: ToolInvocation {
+                tool: "cargo".to_string(),
+                args: vec!["test".to_string(), "users".to_string()],
+                env: Vec::new(),
+                timeout: None,
+            },
+            verify: None,
+        });
+
+        let placeholders = [("path".to_string(), "/users".to_string()), ("name".to_string(), "users".to_string())];
+        let template = TaskTemplate::from_task(&task, "endpoint", &placeholders);
+        assert_eq!(template.title, "Add {{path}} endpoint");
+        assert_eq!(template.success_criteria, vec!["GET {{path}} returns 200"]);
+        assert_eq!(template.steps[0].description, "Run the {{path}} tests");
+        assert_eq!(template.steps[0].tool.args, vec!["test", "{{name}}"]);
+        assert_eq!(template.quality_gates.len(), 1);
+        assert!(template.parameters.iter().all(|p| p.required));
+    }
+
+    #[test]
+    fn test_task_status_transitions() {
+        assert!(TaskStatus::Idea.can_transition_to(TaskStatus::Abandoned));
+        assert!(TaskStatus::Review.can_transition_to(TaskStatus::Done));
+        assert!(TaskStatus::Active.can_transition_to(TaskStatus::Queued));
+        assert!(!TaskStatus::Idea.can_transition_to(TaskStatus::Done));
+        assert!(!TaskStatus::Idea.can_transition_to(TaskStatus::Idea));
+        for closed in [TaskStatus::Done, TaskStatus::Abandoned] {
+            assert!(!closed.can_transition_to(TaskStatus::Queued));
+            assert!(!closed.can_transition_to(TaskStatus::Abandoned));
+        }
+    }
+
+    #[test]
+    fn test_abandon_reason_recoverability() {
+        let voluntary = |can_be_reassigned| AbandonReason::Voluntary {
+            reason: "Out of time".to_string(),
+            can_be_reassigned,
+        };
+        assert!(voluntary(true).is_recoverable());
+        assert!(!voluntary(false).is_recoverable());
+        assert!(!AbandonReason::TechnicalLimitation {
+            limitation: "No API".to_string(),
+            suggested_alternative: None,
+        }
+        .is_recoverable());
+        assert!(AbandonReason::InsufficientInformation { missing_info: vec![] }.is_recoverable());
+    }
+
+    #[test]
+    fn test_priority_round_trips_through_u8() {
+        for level in Priority::ALL {
+            assert_eq!(Priority::from_value(level.value()), level);
+            assert_eq!(Priority::from(u8::from(level)), level);
+            assert_eq!(level.as_str().parse::<Priority>(), Ok(level));
+        }
+    }
+
+    #[test]
+    fn test_priority_bands_cover_u8_range() {
+        assert_eq!(Priority::from_value(0), Priority::Trivial);
+        assert_eq!(Priority::from_value(50), Priority::Trivial);
+        assert_eq!(Priority::from_value(51), Priority::Low);
+        assert_eq!(Priority::from_value(152), Priority::Medium);
+        assert_eq!(Priority::from_value(153), Priority::High);
+        assert_eq!(Priority::from_value(204), Priority::Critical);
+        assert_eq!(Priority::from_value(255), Priority::Critical);
+
+        // Bands are ordered: a higher raw value never maps to a lower level
+        let rank = |p: Priority| Priority::ALL.iter().position(|l| *l == p).unwrap();
+        for v in 0..u8::MAX {
+            assert!(rank(Priority::from_value(v + 1)) <= rank(Priority::from_value(v)));
+        }
+    }
+
+    #[test]
+    fn test_priority_parse_ignores_case() {
+        assert_eq!("critical".parse::<Priority>(), Ok(Priority::Critical));
+        assert_eq!(" HIGH ".parse::<Priority>(), Ok(Priority::High));
+        assert!("urgent".parse::<Priority>().is_err());
+    }
+}
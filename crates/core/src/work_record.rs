@@ -1,7 +1,7 @@
 //! Work record model - detailed execution log.
 
 use serde::{Deserialize, Serialize};
-use crate::id::{WorkRecordId, TaskId, IssueId, BlockerId, GoalId, PhaseId};
+use crate::id::{WorkRecordId, TaskId, IssueId, BlockerId, GoalId, PhaseId, KnowledgeId, BlobId};
 use crate::Time;
 
 /// A work record is a detailed log of task execution.
@@ -41,6 +41,56 @@ pub struct WorkRecord {
     pub resolutions: Vec<Resolution>,
 }
 
+impl WorkRecord {
+    /// Create a new running work record for a task, started at `started_at`.
+    pub fn new(task_id: TaskId, executor: Executor, started_at: Time) -> Self {
+        Self {
+            id: WorkRecordId::new(),
+            task_id,
+            executor,
+            started_at,
+            completed_at: None,
+            duration: None,
+            events: Vec::new(),
+            result: WorkResult {
+                status: CompletionStatus::Running,
+                outputs: Vec::new(),
+                metrics: WorkMetrics {
+                    token_used: None,
+                    time_spent: std::time::Duration::ZERO,
+                    tools_invoked: 0,
+                    quality_checks_run: 0,
+                    quality_checks_passed: 0,
+                },
+                created_knowledge: Vec::new(),
+            },
+            artifacts: Vec::new(),
+            issues: Vec::new(),
+            resolutions: Vec::new(),
+        }
+    }
+
+    /// Events in chronological order.
+    ///
+    /// Events are stored in append order, which may differ from the order in
+    /// which they happened. Events sharing a timestamp keep their append order.
+    pub fn events_sorted(&self) -> Vec<&WorkEvent> {
+        let mut events: Vec<_> = self.events.iter().collect();
+        events.sort_by_key(|e| e.timestamp);
+        events
+    }
+}
+
+/// Filter for querying a task's work records.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkRecordFilter {
+    /// Only records started at or after this time
+    pub since: Option<Time>,
+
+    /// Maximum number of records to return, newest first
+    pub limit: Option<usize>,
+}
+
 /// Who/what executed the work.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Executor {
@@ -65,6 +115,23 @@ pub struct WorkEvent {
     pub data: serde_json::Value,
 }
 
+impl WorkEvent {
+    /// Create a new event that happened at `timestamp`.
+    pub fn new(
+        event_type: WorkEventType,
+        description: impl Into<String>,
+        data: serde_json::Value,
+        timestamp: Time,
+    ) -> Self {
+        Self {
+            timestamp,
+            event_type,
+            description: description.into(),
+            data,
+        }
+    }
+}
+
 /// Types of work events.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum WorkEventType {
@@ -90,15 +157,34 @@ pub struct WorkResult {
 
     /// Execution metrics
     pub metrics: WorkMetrics,
+
+    /// Knowledge written while doing the work
+    #[serde(default)]
+    pub created_knowledge: Vec<KnowledgeId>,
 }
 
 /// Overall completion status.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CompletionStatus {
     Running,
     Success,
     Failed,
     Cancelled,
+    /// Cut short by a crash or cancellation before reaching an outcome;
+    /// the task goes back to the queue to be picked up again
+    Interrupted {
+        /// When the work stopped
+        at: Time,
+        /// What cut it short
+        reason: String,
+    },
+}
+
+impl CompletionStatus {
+    /// Whether the work was cut short and should be retried.
+    pub fn is_interrupted(&self) -> bool {
+        matches!(self, CompletionStatus::Interrupted { .. })
+    }
 }
 
 /// An output artifact.
@@ -141,6 +227,11 @@ pub struct Artifact {
 
     /// Path/URL
     pub location: String,
+
+    /// Content kept in the store, when it was attached rather than only
+    /// referenced by `location`
+    #[serde(default)]
+    pub blob: Option<BlobId>,
 }
 
 /// An issue encountered during work.
@@ -211,6 +302,10 @@ pub struct Blocker {
     /// Severity
     pub severity: Severity,
 
+    /// Where the blocker comes from
+    #[serde(default)]
+    pub category: BlockerCategory,
+
     /// When created
     pub created_at: Time,
 
@@ -218,8 +313,40 @@ pub struct Blocker {
     pub resolved_at: Option<Time>,
 }
 
+impl Blocker {
+    /// A manually recorded blocker on something outside the task graph,
+    /// such as a third party, resolved by hand.
+    pub fn external(blocked_item: BlockedItem, reason: impl Into<String>, severity: Severity) -> Self {
+        Self {
+            id: BlockerId::new(),
+            blocked_item,
+            reason: reason.into(),
+            severity,
+            category: BlockerCategory::External,
+            created_at: chrono::Utc::now(),
+            resolved_at: None,
+        }
+    }
+
+    /// Whether the blocker has been resolved.
+    pub fn is_resolved(&self) -> bool {
+        self.resolved_at.is_some()
+    }
+}
+
+/// Where a blocker comes from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockerCategory {
+    /// Derived from the task dependency graph
+    #[default]
+    Dependency,
+    /// Waiting on something outside the project, recorded and resolved by hand
+    External,
+}
+
 /// What is being blocked.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BlockedItem {
     Task(TaskId),
     Phase(PhaseId),
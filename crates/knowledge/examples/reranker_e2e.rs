@@ -43,6 +43,7 @@ async fn main() -> Result<(), anyhow::Error> {
         ollama_url: "http://localhost:11434".to_string(),
         dimension: 1024,
         threshold: 0.3,
+        auto_embed: true,
     };
 
     let vector_service = devman_knowledge::VectorKnowledgeServiceImpl::new(storage.clone(), vector_config);
@@ -102,6 +103,7 @@ async fn main() -> Result<(), anyhow::Error> {
                 success_rate: 1.0,
                 feedback: vec![],
             },
+            archived: false,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
@@ -23,6 +23,7 @@ async fn main() -> Result<(), anyhow::Error> {
         ollama_url: "http://localhost:11434".to_string(),
         dimension: 1024,
         threshold: 0.5,
+        auto_embed: true,
     };
     println!("[OK] Config: model={:?}, threshold={}\n", config.model, config.threshold);
 
@@ -82,6 +83,7 @@ async fn main() -> Result<(), anyhow::Error> {
                 success_rate: 1.0,
                 feedback: vec![],
             },
+            archived: false,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
@@ -588,6 +588,7 @@ mod tests {
                 success_rate: 1.0,
                 feedback: vec![],
             },
+            archived: false,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
@@ -628,6 +629,7 @@ mod tests {
                 success_rate: 1.0,
                 feedback: vec![],
             },
+            archived: false,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
@@ -683,11 +685,13 @@ async fn fetch_data() -> Result<String, Error> {
                     quality_checks_run: 3,
                     quality_checks_passed: 3,
                 },
+                created_knowledge: Vec::new(),
             },
             artifacts: vec![devman_core::Artifact {
                 name: "src/db.rs".to_string(),
                 artifact_type: "code".to_string(),
                 location: "src/db.rs".to_string(),
+                blob: None,
             }],
             issues: vec![],
             resolutions: vec![],
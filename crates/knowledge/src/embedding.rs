@@ -0,0 +1,282 @@
+//! Embedding providers.
+//!
+//! [`EmbeddingProvider`] abstracts over the service that turns text into
+//! vectors, so the vector knowledge service is not tied to Ollama.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use devman_core::{EmbeddingModel, VectorSearchConfig};
+use reqwest::{Client, ClientBuilder};
+use serde_json::json;
+
+use crate::vector::{OllamaEmbeddingClient, DEFAULT_MAX_CONCURRENT_REQUESTS};
+
+/// Default OpenAI API base URL.
+pub const OPENAI_API_URL: &str = "https://api.openai.com/v1";
+
+/// A source of text embeddings.
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    /// Generate embedding for a single text.
+    async fn embed(&self, text: &str) -> Result<Vec<f32>>;
+
+    /// Generate embeddings for multiple texts, in input order.
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut results = Vec::with_capacity(texts.len());
+        for text in texts {
+            results.push(self.embed(text).await?);
+        }
+        Ok(results)
+    }
+
+    /// Dimension of the vectors this provider produces.
+    fn dimension(&self) -> usize;
+
+    /// Check if the provider is reachable.
+    async fn health_check(&self) -> Result<bool> {
+        Ok(true)
+    }
+}
+
+/// Build the provider selected by `config.model`.
+///
+/// OpenAI models read the API key from the `OPENAI_API_KEY` environment
+/// variable.
+pub fn provider_for_config(config: &VectorSearchConfig) -> Box<dyn EmbeddingProvider> {
+    provider_with_concurrency(config, DEFAULT_MAX_CONCURRENT_REQUESTS)
+}
+
+/// [`provider_for_config`], allowing Ollama models at most
+/// `max_concurrency` requests in flight.
+pub fn provider_with_concurrency(config: &VectorSearchConfig, max_concurrency: usize) -> Box<dyn EmbeddingProvider> {
+    let ollama = |model: &str| {
+        OllamaEmbeddingClient::with_max_concurrency(config.ollama_url.clone(), model.to_string(), max_concurrency)
+            .with_dimension(config.dimension)
+    };
+    match &config.model {
+        EmbeddingModel::Qwen3Embedding0_6B => Box::new(ollama("qwen3-embedding:0.6b")),
+        EmbeddingModel::OpenAIAda002 => Box::new(OpenAIEmbeddingClient::new(
+            std::env::var("OPENAI_API_KEY").unwrap_or_default(),
+            "text-embedding-ada-002".to_string(),
+            config.dimension,
+        )),
+        EmbeddingModel::Ollama { name } => Box::new(ollama(name)),
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OllamaEmbeddingClient {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        OllamaEmbeddingClient::embed(self, text).await
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        OllamaEmbeddingClient::embed_batch(self, texts).await
+    }
+
+    fn dimension(&self) -> usize {
+        OllamaEmbeddingClient::dimension(self)
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        OllamaEmbeddingClient::health_check(self).await
+    }
+}
+
+/// OpenAI embeddings API client.
+#[derive(Clone)]
+pub struct OpenAIEmbeddingClient {
+    /// HTTP client
+    client: Client,
+
+    /// API base URL
+    url: String,
+
+    /// API key
+    api_key: String,
+
+    /// Model name
+    model: String,
+
+    /// Dimension of the vectors produced by the model
+    dimension: usize,
+}
+
+impl OpenAIEmbeddingClient {
+    /// Create a new OpenAI embedding client.
+    pub fn new(api_key: String, model: String, dimension: usize) -> Self {
+        Self {
+            client: ClientBuilder::new()
+                .timeout(std::time::Duration::from_secs(60))
+                .build()
+                .unwrap_or_default(),
+            url: OPENAI_API_URL.to_string(),
+            api_key,
+            model,
+            dimension,
+        }
+    }
+
+    /// Use a different API base URL (e.g. an OpenAI-compatible gateway).
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OpenAIEmbeddingClient {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let mut embeddings = self.embed_batch(&[text.to_string()]).await?;
+        embeddings.pop().context("OpenAI API returned no embedding")
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let response = self
+            .client
+            .post(format!("{}/embeddings", self.url))
+            .bearer_auth(&self.api_key)
+            .json(&json!({
+                "model": self.model,
+                "input": texts,
+            }))
+            .send()
+            .await
+            .context("Failed to call OpenAI embeddings API")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("OpenAI API error (status {}): {}", status, error_text);
+        }
+
+        #[derive(serde::Deserialize)]
+        struct Item {
+            index: usize,
+            embedding: Vec<f32>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct Response {
+            data: Vec<Item>,
+        }
+
+        let mut response_data: Response = response
+            .json()
+            .await
+            .context("Failed to parse OpenAI response")?;
+
+        response_data.data.sort_by_key(|item| item.index);
+        Ok(response_data.data.into_iter().map(|item| item.embedding).collect())
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        Ok(!self.api_key.is_empty())
+    }
+}
+
+/// Deterministic, offline embedding provider for tests.
+///
+/// Each lowercase word is hashed into one of `dimension` buckets and the
+/// resulting bag-of-words vector is normalised, so texts sharing words
+/// score as similar.
+#[derive(Debug, Clone)]
+pub struct MockEmbeddingProvider {
+    dimension: usize,
+}
+
+impl MockEmbeddingProvider {
+    /// Create a mock provider producing vectors of `dimension` (at least 1).
+    pub fn new(dimension: usize) -> Self {
+        Self { dimension: dimension.max(1) }
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for MockEmbeddingProvider {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let mut vector = vec![0.0f32; self.dimension];
+        for word in text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+        {
+            // FNV-1a keeps the bucket stable across runs and platforms
+            let hash = word
+                .to_lowercase()
+                .bytes()
+                .fold(0xcbf29ce484222325u64, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3));
+            vector[(hash % self.dimension as u64) as usize] += 1.0;
+        }
+
+        let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|x| *x /= norm);
+        }
+        Ok(vector)
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_provider_is_deterministic() {
+        let provider = MockEmbeddingProvider::new(64);
+        let a = provider.embed("Rust async runtime").await.unwrap();
+        let b = provider.embed("rust ASYNC runtime").await.unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 64);
+
+        let batch = provider
+            .embed_batch(&["one".to_string(), "two".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch[0], provider.embed("one").await.unwrap());
+    }
+
+    #[test]
+    fn test_provider_selected_by_model() {
+        let config = VectorSearchConfig {
+            dimension: 768,
+            model: EmbeddingModel::Ollama { name: "nomic-embed-text".to_string() },
+            ..Default::default()
+        };
+        assert_eq!(provider_for_config(&config).dimension(), 768);
+
+        let config = VectorSearchConfig {
+            dimension: 1536,
+            model: EmbeddingModel::OpenAIAda002,
+            ..Default::default()
+        };
+        assert_eq!(provider_for_config(&config).dimension(), 1536);
+    }
+
+    #[tokio::test]
+    async fn test_openai_client_parses_response() {
+        let (url, _) = crate::vector::tests::spawn_mock_ollama(
+            r#"{"data":[{"index":1,"embedding":[0.0,1.0]},{"index":0,"embedding":[1.0,0.0]}]}"#,
+        )
+        .await;
+        let client = OpenAIEmbeddingClient::new("key".to_string(), "test".to_string(), 2).with_url(url);
+
+        let embeddings = client
+            .embed_batch(&["first".to_string(), "second".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(embeddings, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+    }
+}
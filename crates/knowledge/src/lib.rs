@@ -8,8 +8,18 @@ pub mod service;
 pub mod template;
 pub mod classification;
 pub mod vector;
+pub mod embedding;
 pub mod reranker;
+pub mod prune;
+pub mod stats;
+pub mod search;
+pub mod tags;
 
 pub use service::{KnowledgeService, BasicKnowledgeService};
-pub use vector::{VectorKnowledgeService, VectorKnowledgeServiceImpl, OllamaEmbeddingClient};
+pub use vector::{VectorKnowledgeService, VectorKnowledgeServiceImpl, OllamaEmbeddingClient, ReindexProgress};
+pub use embedding::{EmbeddingProvider, OpenAIEmbeddingClient, MockEmbeddingProvider};
+pub use prune::{PrunePolicy, PruneReport, PrunedItem, PruneReason};
+pub use stats::{KnowledgeStats, TagCount};
+pub use search::{KnowledgeHit, HitKind};
+pub use tags::{rank_tags, tag_match_score};
 pub use reranker::{RerankerService, RerankerServiceImpl, OllamaRerankerClient, RRFusion};
@@ -0,0 +1,267 @@
+//! Knowledge retention policy.
+//!
+//! Over time the knowledge base collects one-off notes that are never
+//! reused. [`PrunePolicy`] picks such items and [`prune_storage`] archives
+//! them (soft delete), so they drop out of search but can be restored.
+
+use devman_core::{Knowledge, KnowledgeId, Time};
+use devman_storage::Storage;
+use serde::Serialize;
+
+/// Which knowledge items to archive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrunePolicy {
+    /// Archive items with no use or update for this many days whose
+    /// quality score is below `min_quality`
+    pub unused_for_days: Option<i64>,
+
+    /// Quality score below which an unused item counts as low-value
+    pub min_quality: f32,
+
+    /// Keep at most this many items, archiving the lowest-ranked rest
+    pub max_items: Option<usize>,
+
+    /// Report what would be archived without changing anything
+    pub dry_run: bool,
+}
+
+impl Default for PrunePolicy {
+    fn default() -> Self {
+        Self {
+            unused_for_days: Some(90),
+            min_quality: 0.5,
+            max_items: None,
+            dry_run: false,
+        }
+    }
+}
+
+/// Why an item was pruned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PruneReason {
+    /// Unused for longer than the policy allows and below the quality threshold
+    Stale,
+    /// Ranked below the `max_items` cap
+    OverCapacity,
+}
+
+/// A knowledge item selected by a [`PrunePolicy`].
+#[derive(Debug, Clone, Serialize)]
+pub struct PrunedItem {
+    /// Knowledge ID
+    pub id: KnowledgeId,
+    /// Knowledge title
+    pub title: String,
+    /// Why it was selected
+    pub reason: PruneReason,
+}
+
+/// Outcome of a prune run.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PruneReport {
+    /// Active (non-archived) items considered
+    pub examined: usize,
+    /// Items archived, or that would be archived on a dry run
+    pub pruned: Vec<PrunedItem>,
+    /// Whether this was a dry run
+    pub dry_run: bool,
+}
+
+/// When the item was last used or edited.
+fn last_activity(knowledge: &Knowledge) -> Time {
+    knowledge
+        .usage_stats
+        .last_used
+        .map_or(knowledge.updated_at, |used| used.max(knowledge.updated_at))
+}
+
+impl PrunePolicy {
+    /// Pick the items to archive from `items` as of `now`.
+    ///
+    /// Archived items are ignored. Stale items are picked first; the cap is
+    /// then applied to what remains, keeping the highest quality score, then
+    /// the most used, then the most recently active.
+    pub fn select(&self, items: &[Knowledge], now: Time) -> Vec<PrunedItem> {
+        let mut remaining: Vec<&Knowledge> = items.iter().filter(|k| !k.archived).collect();
+        let mut pruned = Vec::new();
+
+        if let Some(days) = self.unused_for_days {
+            let cutoff = now - chrono::Duration::days(days);
+            remaining.retain(|k| {
+                let stale = last_activity(k) < cutoff && k.metadata.quality_score < self.min_quality;
+                if stale {
+                    pruned.push(PrunedItem {
+                        id: k.id,
+                        title: k.title.clone(),
+                        reason: PruneReason::Stale,
+                    });
+                }
+                !stale
+            });
+        }
+
+        if let Some(max) = self.max_items {
+            remaining.sort_by(|a, b| {
+                b.metadata
+                    .quality_score
+                    .total_cmp(&a.metadata.quality_score)
+                    .then(b.usage_stats.times_used.cmp(&a.usage_stats.times_used))
+                    .then(last_activity(b).cmp(&last_activity(a)))
+            });
+            pruned.extend(remaining.iter().skip(max).map(|k| PrunedItem {
+                id: k.id,
+                title: k.title.clone(),
+                reason: PruneReason::OverCapacity,
+            }));
+        }
+
+        pruned
+    }
+}
+
+/// Apply `policy` to every knowledge item in `storage` as of `now`.
+///
+/// Selected items are marked archived unless the policy is a dry run.
+pub async fn prune_storage(
+    storage: &mut dyn Storage,
+    policy: &PrunePolicy,
+    now: Time,
+) -> anyhow::Result<PruneReport> {
+    let items = storage.list_knowledge().await?;
+    let pruned = policy.select(&items, now);
+
+    if !policy.dry_run {
+        for item in &pruned {
+            let mut knowledge = storage.require_knowledge(item.id).await?;
+            knowledge.archived = true;
+            storage.save_knowledge(&knowledge).await?;
+        }
+        if !pruned.is_empty() {
+            storage
+                .commit(&format!("Prune {} knowledge items", pruned.len()))
+                .await?;
+        }
+    }
+
+    Ok(PruneReport {
+        examined: items.iter().filter(|k| !k.archived).count(),
+        pruned,
+        dry_run: policy.dry_run,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use devman_core::{KnowledgeContent, KnowledgeMetadata, KnowledgeType, UsageStats};
+
+    fn knowledge(title: &str, quality: f32, times_used: usize, updated_at: Time) -> Knowledge {
+        Knowledge {
+            id: KnowledgeId::new(),
+            title: title.to_string(),
+            knowledge_type: KnowledgeType::LessonLearned {
+                lesson: "Test lesson".to_string(),
+                context: "Test context".to_string(),
+            },
+            content: KnowledgeContent {
+                summary: title.to_string(),
+                detail: String::new(),
+                examples: vec![],
+                references: vec![],
+            },
+            metadata: KnowledgeMetadata {
+                domain: vec![],
+                tech_stack: vec![],
+                scenarios: vec![],
+                quality_score: quality,
+                verified: false,
+            },
+            tags: vec![],
+            related_to: vec![],
+            derived_from: vec![],
+            usage_stats: UsageStats {
+                times_used,
+                last_used: None,
+                success_rate: 0.0,
+                feedback: vec![],
+            },
+            archived: false,
+            created_at: updated_at,
+            updated_at,
+        }
+    }
+
+    fn titles(items: &[PrunedItem]) -> Vec<&str> {
+        items.iter().map(|i| i.title.as_str()).collect()
+    }
+
+    #[test]
+    fn test_stale_requires_age_and_low_quality() {
+        let now = chrono::Utc::now();
+        let old = now - chrono::Duration::days(200);
+        let mut reused = knowledge("old but reused", 0.2, 3, old);
+        reused.usage_stats.last_used = Some(now - chrono::Duration::days(5));
+        let items = vec![
+            knowledge("old low", 0.2, 0, old),
+            knowledge("old high", 0.9, 0, old),
+            knowledge("new low", 0.2, 0, now),
+            reused,
+        ];
+
+        let pruned = PrunePolicy::default().select(&items, now);
+        assert_eq!(titles(&pruned), ["old low"]);
+        assert_eq!(pruned[0].reason, PruneReason::Stale);
+    }
+
+    #[test]
+    fn test_cap_keeps_highest_ranked() {
+        let now = chrono::Utc::now();
+        let mut archived = knowledge("archived", 0.1, 0, now);
+        archived.archived = true;
+        let items = vec![
+            knowledge("best", 0.9, 0, now),
+            knowledge("used", 0.5, 7, now),
+            knowledge("unused", 0.5, 0, now),
+            knowledge("worst", 0.1, 0, now),
+            archived,
+        ];
+
+        let policy = PrunePolicy {
+            unused_for_days: None,
+            max_items: Some(2),
+            ..Default::default()
+        };
+        let pruned = policy.select(&items, now);
+        assert_eq!(titles(&pruned), ["unused", "worst"]);
+        assert!(pruned.iter().all(|p| p.reason == PruneReason::OverCapacity));
+    }
+
+    #[tokio::test]
+    async fn test_prune_storage_archives_only_stale_items() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let now = chrono::Utc::now();
+        let stale = knowledge("stale", 0.1, 0, now - chrono::Duration::days(120));
+        let active = knowledge("active", 0.1, 0, now - chrono::Duration::days(10));
+        storage.save_knowledge(&stale).await.unwrap();
+        storage.save_knowledge(&active).await.unwrap();
+
+        let dry_run = PrunePolicy { dry_run: true, ..Default::default() };
+        let report = prune_storage(&mut storage, &dry_run, now).await.unwrap();
+        assert!(report.dry_run);
+        assert_eq!(report.examined, 2);
+        assert_eq!(titles(&report.pruned), ["stale"]);
+        assert!(!storage.require_knowledge(stale.id).await.unwrap().archived);
+
+        let report = prune_storage(&mut storage, &PrunePolicy::default(), now).await.unwrap();
+        assert_eq!(titles(&report.pruned), ["stale"]);
+        assert!(storage.require_knowledge(stale.id).await.unwrap().archived);
+        assert!(!storage.require_knowledge(active.id).await.unwrap().archived);
+
+        // Archived items are no longer examined
+        let report = prune_storage(&mut storage, &PrunePolicy::default(), now).await.unwrap();
+        assert_eq!(report.examined, 1);
+        assert!(report.pruned.is_empty());
+    }
+}
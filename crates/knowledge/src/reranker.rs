@@ -10,9 +10,15 @@ use devman_core::{
 };
 use reqwest::{Client, ClientBuilder};
 use serde_json::json;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
 use tracing::{debug, warn};
 
+use crate::vector::DEFAULT_MAX_CONCURRENT_REQUESTS;
+
 /// Ollama Reranker Client.
+///
+/// Clones share the same concurrency limit.
 #[derive(Clone)]
 pub struct OllamaRerankerClient {
     /// HTTP client
@@ -23,11 +29,20 @@ pub struct OllamaRerankerClient {
 
     /// Model name
     model: String,
+
+    /// Guard limiting the number of in-flight requests
+    limiter: Arc<Semaphore>,
 }
 
 impl OllamaRerankerClient {
     /// Create a new Ollama reranker client.
     pub fn new(url: String, model: String) -> Self {
+        Self::with_max_concurrency(url, model, DEFAULT_MAX_CONCURRENT_REQUESTS)
+    }
+
+    /// Create a new Ollama reranker client allowing at most
+    /// `max_concurrency` in-flight requests (clamped to at least 1).
+    pub fn with_max_concurrency(url: String, model: String, max_concurrency: usize) -> Self {
         Self {
             client: ClientBuilder::new()
                 .timeout(std::time::Duration::from_secs(120))
@@ -35,6 +50,7 @@ impl OllamaRerankerClient {
                 .unwrap_or_default(),
             url,
             model,
+            limiter: Arc::new(Semaphore::new(max_concurrency.max(1))),
         }
     }
 
@@ -51,6 +67,12 @@ impl OllamaRerankerClient {
             "documents": documents,
         });
 
+        let _permit = self
+            .limiter
+            .acquire()
+            .await
+            .context("Reranker client limiter closed")?;
+
         debug!("Reranking {} documents", documents.len());
 
         let response = self
@@ -345,6 +367,31 @@ impl HybridSearchResult {
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn test_reranker_client_respects_concurrency_limit() {
+        use std::sync::atomic::Ordering;
+
+        let (url, max_seen) = crate::vector::tests::spawn_mock_ollama(
+            r#"{"results":[{"relevance_score":0.9}]}"#,
+        )
+        .await;
+        let client = OllamaRerankerClient::with_max_concurrency(url, "test".to_string(), 3);
+
+        let handles: Vec<_> = (0..12)
+            .map(|_| {
+                let client = client.clone();
+                tokio::spawn(async move { client.rerank("query", &["doc".to_string()]).await })
+            })
+            .collect();
+
+        for handle in handles {
+            assert_eq!(handle.await.unwrap().unwrap(), vec![0.9]);
+        }
+
+        let observed = max_seen.load(Ordering::SeqCst);
+        assert!(observed <= 3, "observed {} concurrent requests", observed);
+    }
+
     #[test]
     fn test_rrf_fusion_single_list() {
         let rrf = RRFusion::default();
@@ -0,0 +1,170 @@
+//! Ranked search results.
+//!
+//! A [`KnowledgeHit`] carries the score a search assigned to an item and a
+//! short excerpt around the match, so callers can show why an item came up
+//! instead of only its title.
+
+use devman_core::{Knowledge, ScoredKnowledge};
+use serde::Serialize;
+
+/// Characters of context kept on each side of a match.
+const CONTEXT_CHARS: usize = 40;
+
+/// Length of the excerpt used when the query does not appear verbatim.
+const FALLBACK_CHARS: usize = 120;
+
+/// How a hit was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HitKind {
+    /// Keyword match; the score is a relevance weight, not bounded to 1.0
+    Keyword,
+    /// Embedding similarity, from 0.0 to 1.0
+    Vector,
+}
+
+/// One search result with its score and a matched excerpt.
+#[derive(Debug, Clone, Serialize)]
+pub struct KnowledgeHit {
+    /// The matching item
+    pub knowledge: Knowledge,
+    /// Relevance for keyword hits, similarity for vector hits; higher is better
+    pub score: f32,
+    /// Excerpt around the match, with the match wrapped in `**`
+    pub snippet: String,
+    /// How the hit was found
+    pub kind: HitKind,
+}
+
+impl KnowledgeHit {
+    /// A keyword hit for `query` with relevance `score`.
+    pub fn keyword(knowledge: Knowledge, score: f32, query: &str) -> Self {
+        let snippet = snippet(&knowledge, query);
+        Self { knowledge, score, snippet, kind: HitKind::Keyword }
+    }
+
+    /// A vector hit for `query`, keeping the similarity as score.
+    pub fn vector(scored: ScoredKnowledge, query: &str) -> Self {
+        let snippet = snippet(&scored.knowledge, query);
+        Self { knowledge: scored.knowledge, score: scored.score, snippet, kind: HitKind::Vector }
+    }
+}
+
+/// Excerpt of `knowledge` around the first case-insensitive occurrence of
+/// `query` in its summary, detail or title, with the match highlighted.
+///
+/// Items matched through tags, domains or embeddings may not contain the
+/// query at all; those get the start of their summary (or title) instead.
+pub fn snippet(knowledge: &Knowledge, query: &str) -> String {
+    let fields = [&knowledge.content.summary, &knowledge.content.detail, &knowledge.title];
+    if let Some(excerpt) = fields.iter().find_map(|text| highlight(text, query)) {
+        return excerpt;
+    }
+
+    let text = if knowledge.content.summary.trim().is_empty() {
+        &knowledge.title
+    } else {
+        &knowledge.content.summary
+    };
+    let chars: Vec<char> = text.trim().chars().collect();
+    let mut excerpt: String = chars.iter().take(FALLBACK_CHARS).collect();
+    if chars.len() > FALLBACK_CHARS {
+        excerpt.push('…');
+    }
+    excerpt
+}
+
+/// Excerpt of `text` around the first match of `query`, or `None` when it
+/// does not occur.
+fn highlight(text: &str, query: &str) -> Option<String> {
+    let query = query.trim();
+    if query.is_empty() {
+        return None;
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    let needle: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    let start = (0..chars.len()).find(|&i| {
+        chars[i..]
+            .iter()
+            .flat_map(|c| c.to_lowercase())
+            .take(needle.len())
+            .eq(needle.iter().copied())
+    })?;
+    let end = (start + query.chars().count()).min(chars.len());
+
+    let from = start.saturating_sub(CONTEXT_CHARS);
+    let to = (end + CONTEXT_CHARS).min(chars.len());
+    let part = |range: std::ops::Range<usize>| chars[range].iter().collect::<String>();
+
+    let mut excerpt = String::new();
+    if from > 0 {
+        excerpt.push('…');
+    }
+    excerpt.push_str(&part(from..start));
+    excerpt.push_str("**");
+    excerpt.push_str(&part(start..end));
+    excerpt.push_str("**");
+    excerpt.push_str(&part(end..to));
+    if to < chars.len() {
+        excerpt.push('…');
+    }
+    Some(excerpt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use devman_core::{KnowledgeContent, KnowledgeMetadata, KnowledgeType, UsageStats};
+
+    fn knowledge(summary: &str, detail: &str) -> Knowledge {
+        Knowledge {
+            id: devman_core::KnowledgeId::new(),
+            title: "Retries".to_string(),
+            knowledge_type: KnowledgeType::LessonLearned {
+                lesson: String::new(),
+                context: String::new(),
+            },
+            content: KnowledgeContent {
+                summary: summary.to_string(),
+                detail: detail.to_string(),
+                examples: vec![],
+                references: vec![],
+            },
+            metadata: KnowledgeMetadata {
+                domain: vec![],
+                tech_stack: vec![],
+                scenarios: vec![],
+                quality_score: 0.5,
+                verified: false,
+            },
+            tags: vec![],
+            related_to: vec![],
+            derived_from: vec![],
+            usage_stats: UsageStats { times_used: 0, last_used: None, success_rate: 0.0, feedback: vec![] },
+            archived: false,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_snippet_highlights_match_case_insensitively() {
+        let detail = format!("{} Use exponential BACKOFF with jitter. {}", "x".repeat(60), "y".repeat(60));
+        let item = knowledge("Retry failed calls", &detail);
+
+        let excerpt = snippet(&item, "backoff");
+        assert!(excerpt.contains("**BACKOFF**"), "{}", excerpt);
+        assert!(excerpt.starts_with('…') && excerpt.ends_with('…'));
+        assert!(excerpt.chars().count() < detail.chars().count());
+
+        assert_eq!(snippet(&item, "retry"), "**Retry** failed calls");
+    }
+
+    #[test]
+    fn test_snippet_falls_back_to_summary() {
+        let item = knowledge("重试失败的调用", "");
+        assert_eq!(snippet(&item, "tag-only"), "重试失败的调用");
+        assert_eq!(snippet(&item, "失败"), "重试**失败**的调用");
+    }
+}
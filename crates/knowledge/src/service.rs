@@ -1,8 +1,11 @@
 //! Knowledge service trait and basic implementation.
 
 use async_trait::async_trait;
-use devman_core::{Knowledge, KnowledgeType, Task, TaskContext};
+use devman_core::{Feedback, Knowledge, KnowledgeId, KnowledgeType, Task, TaskContext};
 use devman_storage::Storage;
+use crate::prune::{PrunePolicy, PruneReport};
+use crate::search::KnowledgeHit;
+use crate::stats::KnowledgeStats;
 use std::collections::{HashMap, HashSet};
 
 /// Knowledge service for searching and retrieving knowledge.
@@ -11,6 +14,19 @@ pub trait KnowledgeService: Send + Sync {
     /// Search knowledge by semantic query.
     async fn search_semantic(&self, query: &str, limit: usize) -> Vec<Knowledge>;
 
+    /// Search like [`search_semantic`](Self::search_semantic), keeping each
+    /// item's score and a highlighted excerpt of the match, best first.
+    ///
+    /// Services that do not expose scores rank hits by position.
+    async fn search_ranked(&self, query: &str, limit: usize) -> Vec<KnowledgeHit> {
+        self.search_semantic(query, limit)
+            .await
+            .into_iter()
+            .enumerate()
+            .map(|(rank, k)| KnowledgeHit::keyword(k, 1.0 / (rank + 1) as f32, query))
+            .collect()
+    }
+
     /// Find similar tasks based on context.
     async fn find_similar_tasks(&self, task: &Task) -> Vec<Task>;
 
@@ -26,6 +42,18 @@ pub trait KnowledgeService: Send + Sync {
     /// Search knowledge by tags (AND logic - all tags must match).
     async fn search_by_tags_all(&self, tags: &[String], limit: usize) -> Vec<Knowledge>;
 
+    /// Search like [`search_by_tags`](Self::search_by_tags), also matching
+    /// stored tags that are close to a requested one: prefixes,
+    /// abbreviations such as "tst" for "testing", and small typos.
+    async fn search_by_tags_fuzzy(&self, tags: &[String], limit: usize) -> Vec<Knowledge> {
+        let known = self.get_all_tags().await;
+        let expanded: Vec<String> = known
+            .into_iter()
+            .filter(|known| tags.iter().any(|t| crate::tags::tag_match_score(t, known).is_some()))
+            .collect();
+        self.search_by_tags(&expanded, limit).await
+    }
+
     /// Get all unique tags across all knowledge.
     async fn get_all_tags(&self) -> HashSet<String>;
 
@@ -38,20 +66,39 @@ pub trait KnowledgeService: Send + Sync {
     /// Get knowledge by type.
     async fn get_by_type(&self, knowledge_type: KnowledgeType) -> Vec<Knowledge>;
 
-    /// Suggest tags based on query.
+    /// Suggest up to `limit` existing tags matching `query`, closest first.
+    ///
+    /// Matching is fuzzy, see [`crate::tags::tag_match_score`].
     async fn suggest_tags(&self, query: &str, limit: usize) -> Vec<String>;
+
+    /// List the most recently updated knowledge, newest first.
+    async fn list_recent(&self, limit: usize) -> Vec<Knowledge>;
+
+    /// Record feedback on a knowledge item, adjusting its quality score.
+    ///
+    /// Returns the updated item.
+    async fn submit_feedback(&self, id: KnowledgeId, feedback: Feedback) -> anyhow::Result<Knowledge>;
+
+    /// Archive knowledge selected by `policy`.
+    ///
+    /// Archived items are hidden from search but kept in storage.
+    async fn prune(&self, policy: PrunePolicy) -> anyhow::Result<PruneReport>;
+
+    /// Summarise the active knowledge base by type, tag, embedding coverage
+    /// and feedback.
+    async fn statistics(&self) -> anyhow::Result<KnowledgeStats>;
 }
 
 /// Basic knowledge service implementation.
 pub struct BasicKnowledgeService<S: Storage> {
-    storage: std::sync::Arc<S>,
+    storage: std::sync::Arc<tokio::sync::Mutex<S>>,
 }
 
 impl<S: Storage> BasicKnowledgeService<S> {
     /// Create a new knowledge service.
     pub fn new(storage: S) -> Self {
         Self {
-            storage: std::sync::Arc::new(storage),
+            storage: std::sync::Arc::new(tokio::sync::Mutex::new(storage)),
         }
     }
 }
@@ -59,34 +106,28 @@ impl<S: Storage> BasicKnowledgeService<S> {
 #[async_trait]
 impl<S: Storage + 'static> KnowledgeService for BasicKnowledgeService<S> {
     async fn search_semantic(&self, query: &str, limit: usize) -> Vec<Knowledge> {
-        let all = self.storage.list_knowledge().await.unwrap_or_default();
-        let query_lower = query.to_lowercase();
-
-        // Score each knowledge item by relevance
-        let mut scored: Vec<_> = all.into_iter()
-            .map(|k| {
-                let score = self.calculate_relevance_score(&k, &query_lower);
-                (k, score)
-            })
-            .filter(|(_, score)| *score > 0.0)
-            .collect();
-
-        // Sort by score (descending)
-        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-
-        scored.into_iter()
-            .take(limit)
+        self.scored_matches(query, limit)
+            .await
+            .into_iter()
             .map(|(k, _)| k)
             .collect()
     }
 
+    async fn search_ranked(&self, query: &str, limit: usize) -> Vec<KnowledgeHit> {
+        self.scored_matches(query, limit)
+            .await
+            .into_iter()
+            .map(|(k, score)| KnowledgeHit::keyword(k, score as f32, query))
+            .collect()
+    }
+
     async fn find_similar_tasks(&self, _task: &Task) -> Vec<Task> {
         // TODO: Implement similarity search
         Vec::new()
     }
 
     async fn get_best_practices(&self, domain: &str) -> Vec<Knowledge> {
-        let all = self.storage.list_knowledge().await.unwrap_or_default();
+        let all = self.active_knowledge().await;
         all.into_iter()
             .filter(|k| {
                 matches!(k.knowledge_type, KnowledgeType::BestPractice { .. })
@@ -98,7 +139,7 @@ impl<S: Storage + 'static> KnowledgeService for BasicKnowledgeService<S> {
     async fn recommend_knowledge(&self, context: &TaskContext) -> Vec<Knowledge> {
         let mut results = Vec::new();
         for &id in &context.relevant_knowledge {
-            if let Ok(Some(k)) = self.storage.load_knowledge(id).await {
+            if let Ok(Some(k)) = self.storage.lock().await.load_knowledge(id).await {
                 results.push(k);
             }
         }
@@ -110,7 +151,7 @@ impl<S: Storage + 'static> KnowledgeService for BasicKnowledgeService<S> {
             return Vec::new();
         }
 
-        let all = self.storage.list_knowledge().await.unwrap_or_default();
+        let all = self.active_knowledge().await;
         let tags_set: HashSet<_> = tags.iter().map(|t| t.to_lowercase()).collect();
 
         all.into_iter()
@@ -127,7 +168,7 @@ impl<S: Storage + 'static> KnowledgeService for BasicKnowledgeService<S> {
             return Vec::new();
         }
 
-        let all = self.storage.list_knowledge().await.unwrap_or_default();
+        let all = self.active_knowledge().await;
         let tags_set: HashSet<_> = tags.iter().map(|t| t.to_lowercase()).collect();
 
         all.into_iter()
@@ -140,14 +181,14 @@ impl<S: Storage + 'static> KnowledgeService for BasicKnowledgeService<S> {
     }
 
     async fn get_all_tags(&self) -> HashSet<String> {
-        let all = self.storage.list_knowledge().await.unwrap_or_default();
+        let all = self.active_knowledge().await;
         all.into_iter()
             .flat_map(|k| k.tags.into_iter())
             .collect()
     }
 
     async fn get_tag_statistics(&self) -> HashMap<String, usize> {
-        let all = self.storage.list_knowledge().await.unwrap_or_default();
+        let all = self.active_knowledge().await;
         let mut stats = HashMap::new();
 
         for k in all {
@@ -160,7 +201,7 @@ impl<S: Storage + 'static> KnowledgeService for BasicKnowledgeService<S> {
     }
 
     async fn find_similar_knowledge(&self, knowledge: &Knowledge, limit: usize) -> Vec<Knowledge> {
-        let all = self.storage.list_knowledge().await.unwrap_or_default();
+        let all = self.active_knowledge().await;
         let query = format!("{} {}", knowledge.content.summary, knowledge.content.detail);
         let query_lower = query.to_lowercase();
 
@@ -182,24 +223,70 @@ impl<S: Storage + 'static> KnowledgeService for BasicKnowledgeService<S> {
     }
 
     async fn get_by_type(&self, knowledge_type: KnowledgeType) -> Vec<Knowledge> {
-        let all = self.storage.list_knowledge().await.unwrap_or_default();
+        let all = self.active_knowledge().await;
         all.into_iter()
             .filter(|k| k.knowledge_type == knowledge_type)
             .collect()
     }
 
     async fn suggest_tags(&self, query: &str, limit: usize) -> Vec<String> {
-        let all_tags = self.get_all_tags().await;
-        let query_lower = query.to_lowercase();
+        crate::tags::rank_tags(query, self.get_all_tags().await, limit)
+    }
 
-        all_tags.into_iter()
-            .filter(|t| t.to_lowercase().contains(&query_lower))
-            .take(limit)
-            .collect()
+    async fn list_recent(&self, limit: usize) -> Vec<Knowledge> {
+        let mut items = self.storage.lock().await.list_recent_knowledge(limit).await.unwrap_or_default();
+        items.retain(|k| !k.archived);
+        items
+    }
+
+    async fn submit_feedback(&self, id: KnowledgeId, feedback: Feedback) -> anyhow::Result<Knowledge> {
+        let mut storage = self.storage.lock().await;
+        let mut knowledge = storage
+            .load_knowledge(id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Knowledge not found: {}", id))?;
+        knowledge.apply_feedback(feedback);
+        storage.save_knowledge(&knowledge).await?;
+        Ok(knowledge)
+    }
+
+    async fn prune(&self, policy: PrunePolicy) -> anyhow::Result<PruneReport> {
+        crate::prune::prune_storage(&mut *self.storage.lock().await, &policy, chrono::Utc::now()).await
+    }
+
+    async fn statistics(&self) -> anyhow::Result<KnowledgeStats> {
+        crate::stats::compute_statistics(&*self.storage.lock().await).await
     }
 }
 
 impl<S: Storage> BasicKnowledgeService<S> {
+    /// All knowledge that has not been archived.
+    async fn active_knowledge(&self) -> Vec<Knowledge> {
+        let mut all = self.storage.lock().await.list_knowledge().await.unwrap_or_default();
+        all.retain(|k| !k.archived);
+        all
+    }
+
+    /// Active knowledge matching `query`, with its relevance score, best first.
+    async fn scored_matches(&self, query: &str, limit: usize) -> Vec<(Knowledge, f64)> {
+        let all = self.active_knowledge().await;
+        let query_lower = query.to_lowercase();
+
+        // Score each knowledge item by relevance
+        let mut scored: Vec<_> = all.into_iter()
+            .map(|k| {
+                let score = self.calculate_relevance_score(&k, &query_lower);
+                (k, score)
+            })
+            .filter(|(_, score)| *score > 0.0)
+            .collect();
+
+        // Sort by score (descending)
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(limit);
+        scored
+    }
+
     /// Calculate relevance score for a knowledge item against a query.
     fn calculate_relevance_score(&self, knowledge: &Knowledge, query_lower: &str) -> f64 {
         let mut score = 0.0;
@@ -233,7 +320,8 @@ impl<S: Storage> BasicKnowledgeService<S> {
             score *= 1.2;
         }
 
-        score
+        // Feedback-driven quality: 0.5x for the worst rated up to 1.5x for the best
+        score * (0.5 + knowledge.metadata.quality_score.clamp(0.0, 1.0) as f64)
     }
 }
 
@@ -272,6 +360,7 @@ mod tests {
                 success_rate: 1.0,
                 feedback: vec![],
             },
+            archived: false,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         }
@@ -336,4 +425,84 @@ mod tests {
         // Should get bonus multiplier
         assert!(score > 10.0 * 1.1); // 10 from summary match * 1.2 bonus
     }
+
+    #[tokio::test]
+    async fn test_fuzzy_tag_suggestions_and_filtering() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        storage.save_knowledge(&create_test_knowledge("Unit tests", "tests", vec!["testing"])).await.unwrap();
+        storage.save_knowledge(&create_test_knowledge("Borrowck", "rust", vec!["rust"])).await.unwrap();
+        storage.save_knowledge(&create_test_knowledge("Fixtures", "tests", vec!["test-data"])).await.unwrap();
+
+        let service = BasicKnowledgeService::new(storage);
+        assert_eq!(service.suggest_tags("tst", 10).await, ["testing", "test-data"]);
+        assert_eq!(service.suggest_tags("teseting", 10).await, ["testing"]);
+        assert!(service.suggest_tags("deploy", 10).await.is_empty());
+
+        // Exact tag filtering misses the typo, fuzzy filtering does not
+        let typo = vec!["teseting".to_string()];
+        assert!(service.search_by_tags(&typo, 10).await.is_empty());
+        let titles: Vec<String> = service.search_by_tags_fuzzy(&typo, 10).await.into_iter().map(|k| k.title).collect();
+        assert_eq!(titles, ["Unit tests"]);
+    }
+
+    #[tokio::test]
+    async fn test_unhelpful_feedback_lowers_rank() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+
+        // The rated item starts ahead: it matches in both summary and detail
+        let mut rated = create_test_knowledge("Rated", "retry with backoff", vec![]);
+        rated.content.detail = "retry with backoff and jitter".to_string();
+        let peer = create_test_knowledge("Peer", "retry with backoff", vec![]);
+        storage.save_knowledge(&rated).await.unwrap();
+        storage.save_knowledge(&peer).await.unwrap();
+
+        let service = BasicKnowledgeService::new(storage);
+        let titles = |items: Vec<Knowledge>| items.into_iter().map(|k| k.title).collect::<Vec<_>>();
+        assert_eq!(titles(service.search_semantic("retry", 10).await), ["Rated", "Peer"]);
+
+        for _ in 0..6 {
+            service
+                .submit_feedback(rated.id, Feedback::unhelpful("agent", Some("outdated".to_string())))
+                .await
+                .unwrap();
+        }
+
+        let updated = service.submit_feedback(rated.id, Feedback::unhelpful("agent", None)).await.unwrap();
+        assert_eq!(updated.usage_stats.feedback.len(), 7);
+        assert!(updated.metadata.quality_score < 0.5);
+        assert_eq!(titles(service.search_semantic("retry", 10).await), ["Peer", "Rated"]);
+
+        assert!(service
+            .submit_feedback(devman_core::KnowledgeId::new(), Feedback::helpful("agent", None))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_ranked_hits_are_scored_and_highlighted() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+
+        let mut both = create_test_knowledge("Both", "Retry with backoff", vec![]);
+        both.content.detail = "Always retry idempotent calls".to_string();
+        let tagged = create_test_knowledge("Tagged", "Network failures", vec!["retry"]);
+        let unrelated = create_test_knowledge("Unrelated", "Logging levels", vec![]);
+        for k in [&tagged, &both, &unrelated] {
+            storage.save_knowledge(k).await.unwrap();
+        }
+
+        let service = BasicKnowledgeService::new(storage);
+        let hits = service.search_ranked("retry", 10).await;
+
+        let titles: Vec<_> = hits.iter().map(|h| h.knowledge.title.as_str()).collect();
+        assert_eq!(titles, ["Both", "Tagged"]);
+        assert!(hits.windows(2).all(|w| w[0].score >= w[1].score));
+        assert!(hits.iter().all(|h| !h.snippet.is_empty()));
+        assert_eq!(hits[0].snippet, "**Retry** with backoff");
+        assert_eq!(hits[0].kind, crate::search::HitKind::Keyword);
+
+        assert_eq!(service.search_ranked("retry", 1).await.len(), 1);
+    }
 }
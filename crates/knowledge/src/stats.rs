@@ -0,0 +1,229 @@
+//! Knowledge base statistics.
+//!
+//! [`KnowledgeStats`] summarises what the knowledge base covers, so gaps
+//! such as a missing knowledge type or items without embeddings show up.
+
+use devman_core::{Knowledge, KnowledgeId, KnowledgeType};
+use devman_storage::Storage;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Number of tags reported in [`KnowledgeStats::top_tags`].
+pub const TOP_TAGS: usize = 10;
+
+/// Every knowledge type name, so empty types are reported as zero.
+const TYPE_NAMES: [&str; 6] = [
+    "LessonLearned",
+    "BestPractice",
+    "CodePattern",
+    "Solution",
+    "Template",
+    "Decision",
+];
+
+/// Name of a knowledge type without its payload.
+fn type_name(knowledge_type: &KnowledgeType) -> &'static str {
+    match knowledge_type {
+        KnowledgeType::LessonLearned { .. } => "LessonLearned",
+        KnowledgeType::BestPractice { .. } => "BestPractice",
+        KnowledgeType::CodePattern { .. } => "CodePattern",
+        KnowledgeType::Solution { .. } => "Solution",
+        KnowledgeType::Template { .. } => "Template",
+        KnowledgeType::Decision { .. } => "Decision",
+    }
+}
+
+/// A tag and how many items carry it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TagCount {
+    /// Tag
+    pub tag: String,
+    /// Items with the tag
+    pub count: usize,
+}
+
+/// Breakdown of the active (non-archived) knowledge base.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct KnowledgeStats {
+    /// Active items
+    pub total: usize,
+    /// Archived items, not counted anywhere else
+    pub archived: usize,
+    /// Items per knowledge type, including types with none
+    pub by_type: BTreeMap<String, usize>,
+    /// Most used tags, most common first
+    pub top_tags: Vec<TagCount>,
+    /// Items with a stored embedding
+    pub embedded: usize,
+    /// Items without a stored embedding
+    pub unembedded: usize,
+    /// Mean feedback rating over all feedback, if there is any
+    pub average_feedback: Option<f32>,
+}
+
+impl KnowledgeStats {
+    /// Compute statistics over `items`, where `embedded` holds the IDs of
+    /// items with a stored embedding.
+    pub fn compute(items: &[Knowledge], embedded: &HashSet<KnowledgeId>) -> Self {
+        let mut stats = Self {
+            by_type: TYPE_NAMES.iter().map(|name| (name.to_string(), 0)).collect(),
+            ..Default::default()
+        };
+        let mut tags: HashMap<&str, usize> = HashMap::new();
+        let mut ratings = Vec::new();
+
+        for knowledge in items {
+            if knowledge.archived {
+                stats.archived += 1;
+                continue;
+            }
+            stats.total += 1;
+            *stats.by_type.entry(type_name(&knowledge.knowledge_type).to_string()).or_insert(0) += 1;
+            for tag in &knowledge.tags {
+                *tags.entry(tag).or_insert(0) += 1;
+            }
+            if embedded.contains(&knowledge.id) {
+                stats.embedded += 1;
+            }
+            ratings.extend(knowledge.usage_stats.feedback.iter().map(|f| f.rating as f32));
+        }
+
+        stats.unembedded = stats.total - stats.embedded;
+        stats.average_feedback =
+            (!ratings.is_empty()).then(|| ratings.iter().sum::<f32>() / ratings.len() as f32);
+
+        let mut tags: Vec<TagCount> = tags
+            .into_iter()
+            .map(|(tag, count)| TagCount { tag: tag.to_string(), count })
+            .collect();
+        tags.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+        tags.truncate(TOP_TAGS);
+        stats.top_tags = tags;
+
+        stats
+    }
+}
+
+/// Compute [`KnowledgeStats`] over all knowledge in `storage`.
+pub async fn compute_statistics(storage: &dyn Storage) -> anyhow::Result<KnowledgeStats> {
+    let items = storage.list_knowledge().await?;
+    let embedded: HashSet<KnowledgeId> = storage
+        .list_vector_embeddings()
+        .await?
+        .into_iter()
+        .map(|e| e.knowledge_id)
+        .collect();
+    Ok(KnowledgeStats::compute(&items, &embedded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use devman_core::{
+        EmbeddingModel, Feedback, KnowledgeContent, KnowledgeEmbedding, KnowledgeMetadata, UsageStats,
+    };
+
+    fn knowledge(knowledge_type: KnowledgeType, tags: &[&str], ratings: &[i32]) -> Knowledge {
+        let now = chrono::Utc::now();
+        Knowledge {
+            id: KnowledgeId::new(),
+            title: "Item".to_string(),
+            knowledge_type,
+            content: KnowledgeContent {
+                summary: String::new(),
+                detail: String::new(),
+                examples: vec![],
+                references: vec![],
+            },
+            metadata: KnowledgeMetadata {
+                domain: vec![],
+                tech_stack: vec![],
+                scenarios: vec![],
+                quality_score: 0.5,
+                verified: false,
+            },
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            related_to: vec![],
+            derived_from: vec![],
+            usage_stats: UsageStats {
+                times_used: 0,
+                last_used: None,
+                success_rate: 0.0,
+                feedback: ratings
+                    .iter()
+                    .map(|&rating| Feedback {
+                        rating,
+                        comment: String::new(),
+                        at: now,
+                        from: "tester".to_string(),
+                    })
+                    .collect(),
+            },
+            archived: false,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn lesson() -> KnowledgeType {
+        KnowledgeType::LessonLearned { lesson: String::new(), context: String::new() }
+    }
+
+    fn practice() -> KnowledgeType {
+        KnowledgeType::BestPractice { practice: String::new(), rationale: String::new() }
+    }
+
+    #[tokio::test]
+    async fn test_statistics_over_mixed_corpus() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+
+        let embedded = knowledge(lesson(), &["rust", "async"], &[5, 3]);
+        let mut archived = knowledge(practice(), &["rust"], &[1]);
+        archived.archived = true;
+        let items = [
+            embedded.clone(),
+            knowledge(lesson(), &["rust"], &[]),
+            knowledge(practice(), &["frontend", "rust"], &[4]),
+            archived,
+        ];
+        for item in &items {
+            storage.save_knowledge(item).await.unwrap();
+        }
+        storage
+            .save_vector_embedding(&KnowledgeEmbedding {
+                knowledge_id: embedded.id,
+                embedding: vec![0.1, 0.2],
+                model: EmbeddingModel::Qwen3Embedding0_6B,
+                created_at: chrono::Utc::now(),
+            })
+            .await
+            .unwrap();
+
+        let stats = compute_statistics(&storage).await.unwrap();
+        assert_eq!(stats.total, 3);
+        assert_eq!(stats.archived, 1);
+        assert_eq!(stats.by_type["LessonLearned"], 2);
+        assert_eq!(stats.by_type["BestPractice"], 1);
+        assert_eq!(stats.by_type["Decision"], 0);
+        assert_eq!(stats.by_type.len(), TYPE_NAMES.len());
+        assert_eq!(
+            stats.top_tags,
+            vec![
+                TagCount { tag: "rust".to_string(), count: 3 },
+                TagCount { tag: "async".to_string(), count: 1 },
+                TagCount { tag: "frontend".to_string(), count: 1 },
+            ]
+        );
+        assert_eq!((stats.embedded, stats.unembedded), (1, 2));
+        assert_eq!(stats.average_feedback, Some(4.0));
+    }
+
+    #[test]
+    fn test_empty_corpus_has_no_average() {
+        let stats = KnowledgeStats::compute(&[], &HashSet::new());
+        assert_eq!(stats.total, 0);
+        assert_eq!(stats.average_feedback, None);
+        assert!(stats.by_type.values().all(|&count| count == 0));
+    }
+}
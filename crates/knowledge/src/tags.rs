@@ -0,0 +1,108 @@
+//! Fuzzy tag matching.
+//!
+//! Tags are matched case-insensitively, from closest to loosest: exact,
+//! prefix, substring, abbreviation (the query's characters appear in order,
+//! as in "tst" for "testing") and finally a small edit distance for typos.
+
+/// Score how closely `tag` matches `query`, from 0.0 to 1.0, or `None` if
+/// it does not match at all.
+///
+/// Within each kind of match, tags whose length is closer to the query's
+/// score higher, so "test" ranks above "testing" for "tst".
+pub fn tag_match_score(query: &str, tag: &str) -> Option<f32> {
+    let query: Vec<char> = query.trim().to_lowercase().chars().collect();
+    let tag: Vec<char> = tag.to_lowercase().chars().collect();
+    if query.is_empty() || tag.is_empty() {
+        return None;
+    }
+    let coverage = query.len().min(tag.len()) as f32 / query.len().max(tag.len()) as f32;
+
+    if query == tag {
+        return Some(1.0);
+    }
+    if tag.starts_with(&query) {
+        return Some(0.8 + 0.1 * coverage);
+    }
+    if tag.windows(query.len()).any(|w| w == query.as_slice()) {
+        return Some(0.6 + 0.1 * coverage);
+    }
+    if is_subsequence(&query, &tag) {
+        return Some(0.4 + 0.1 * coverage);
+    }
+
+    // Allow one typo per four characters, and at least one
+    let allowed = (query.len() / 4).max(1);
+    let distance = levenshtein(&query, &tag);
+    (distance <= allowed).then(|| 0.3 * (1.0 - distance as f32 / (allowed + 1) as f32))
+}
+
+/// The `limit` tags that best match `query`, closest first; ties are
+/// broken alphabetically.
+pub fn rank_tags<I>(query: &str, tags: I, limit: usize) -> Vec<String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut scored: Vec<(String, f32)> = tags
+        .into_iter()
+        .filter_map(|tag| tag_match_score(query, &tag).map(|score| (tag, score)))
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    scored.into_iter().take(limit).map(|(tag, _)| tag).collect()
+}
+
+/// Whether every character of `needle` appears in `haystack` in order.
+fn is_subsequence(needle: &[char], haystack: &[char]) -> bool {
+    let mut rest = haystack.iter();
+    needle.iter().all(|c| rest.any(|h| h == c))
+}
+
+/// Edit distance between `a` and `b`.
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(names: &[&str]) -> Vec<String> {
+        names.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_abbreviation_and_typo_find_testing() {
+        let all = tags(&["rust", "testing", "deployment", "test", "security"]);
+
+        assert_eq!(rank_tags("tst", all.clone(), 10), vec!["test", "testing"]);
+        assert_eq!(rank_tags("teseting", all.clone(), 10), vec!["testing"]);
+        assert_eq!(rank_tags("TEST", all.clone(), 10), vec!["test", "testing"]);
+        assert_eq!(rank_tags("tst", all, 1), vec!["test"]);
+    }
+
+    #[test]
+    fn test_match_kinds_rank_closest_first() {
+        let exact = tag_match_score("api", "api").unwrap();
+        let prefix = tag_match_score("api", "api-design").unwrap();
+        let substring = tag_match_score("api", "rest-api").unwrap();
+        let abbreviation = tag_match_score("api", "a-p-i").unwrap();
+        let typo = tag_match_score("apj", "api").unwrap();
+        assert!(exact > prefix && prefix > substring && substring > abbreviation && abbreviation > typo);
+    }
+
+    #[test]
+    fn test_unrelated_tags_excluded() {
+        assert_eq!(tag_match_score("tst", "rust"), None);
+        assert_eq!(tag_match_score("database", "security"), None);
+        assert_eq!(tag_match_score("", "testing"), None);
+        assert!(rank_tags("kubernetes", tags(&["testing", "rust"]), 10).is_empty());
+    }
+}
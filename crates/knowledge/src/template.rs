@@ -39,6 +39,53 @@ impl From<TemplateParameter> for CoreTemplateParameter {
     }
 }
 
+impl From<CoreTemplateParameter> for TemplateParameter {
+    fn from(p: CoreTemplateParameter) -> Self {
+        Self {
+            name: p.name,
+            description: p.description,
+            default_value: p.default_value,
+            required: p.required,
+        }
+    }
+}
+
+/// Values for `parameters`: those given in `params`, falling back to
+/// defaults. Fails when a required parameter is not given.
+pub fn resolve_parameters(
+    parameters: &[TemplateParameter],
+    params: &HashMap<String, String>,
+) -> Result<HashMap<String, String>, TemplateValidation> {
+    let missing_required: Vec<String> = parameters
+        .iter()
+        .filter(|p| p.required && !params.contains_key(&p.name))
+        .map(|p| p.name.clone())
+        .collect();
+    if !missing_required.is_empty() {
+        return Err(TemplateValidation::failure(
+            missing_required,
+            vec!["Missing required parameters".to_string()],
+        ));
+    }
+
+    let mut values = HashMap::new();
+    for param in parameters {
+        if let Some(value) = params.get(&param.name) {
+            values.insert(param.name.clone(), value.clone());
+        } else if let Some(default) = &param.default_value {
+            values.insert(param.name.clone(), default.clone());
+        }
+    }
+    Ok(values)
+}
+
+/// `text` with each `{{name}}` placeholder replaced by its value.
+pub fn fill_placeholders(text: &str, values: &HashMap<String, String>) -> String {
+    values.iter().fold(text.to_string(), |text, (key, value)| {
+        text.replace(&format!("{{{{{}}}}}", key), value)
+    })
+}
+
 /// Template validation result.
 #[derive(Debug, Clone)]
 pub struct TemplateValidation {
@@ -229,6 +276,7 @@ impl TemplateBuilder {
                     success_rate: 0.0,
                     feedback: vec![],
                 },
+                archived: false,
                 created_at: chrono::Utc::now(),
                 updated_at: chrono::Utc::now(),
             },
@@ -254,59 +302,29 @@ impl KnowledgeTemplate {
 
     /// Validate that all required parameters are provided.
     pub fn validate(&self, params: &HashMap<String, String>) -> TemplateValidation {
-        let mut missing_required: Vec<String> = Vec::new();
-
-        for param in &self.parameters {
-            if param.required && !params.contains_key(&param.name) {
-                missing_required.push(param.name.clone());
-            }
+        match resolve_parameters(&self.parameters, params) {
+            Ok(_) => TemplateValidation::success(),
+            Err(validation) => validation,
         }
-
-        if !missing_required.is_empty() {
-            return TemplateValidation::failure(
-                missing_required,
-                vec!["Missing required parameters".to_string()],
-            );
-        }
-
-        TemplateValidation::success()
     }
 
     /// Instantiate the template with given parameters.
     pub fn instantiate(&self, params: &HashMap<String, String>) -> Result<Knowledge, String> {
-        // Validate parameters first
-        let validation = self.validate(params);
-        if !validation.valid {
-            return Err(format!(
-                "Template validation failed: {:?}",
-                validation.missing_required
-            ));
-        }
+        let full_params = resolve_parameters(&self.parameters, params).map_err(|validation| {
+            format!("Template validation failed: {:?}", validation.missing_required)
+        })?;
 
         let mut knowledge = self.template.clone();
         knowledge.id = KnowledgeId::new();
 
-        // Build a complete parameter map including defaults
-        let mut full_params = HashMap::new();
-        for param in &self.parameters {
-            if let Some(value) = params.get(&param.name) {
-                full_params.insert(param.name.clone(), value.clone());
-            } else if let Some(default) = &param.default_value {
-                full_params.insert(param.name.clone(), default.clone());
-            }
-        }
-
         // Substitute parameters in content
-        for (key, value) in &full_params {
-            let placeholder = format!("{{{{{}}}}}", key);
-            knowledge.content.summary = knowledge.content.summary.replace(&placeholder, value);
-            knowledge.content.detail = knowledge.content.detail.replace(&placeholder, value);
-
-            // Also substitute in examples if they contain the placeholder
-            for example in &mut knowledge.content.examples {
-                example.code = example.code.replace(&placeholder, value);
-                example.description = example.description.replace(&placeholder, value);
-            }
+        knowledge.content.summary = fill_placeholders(&knowledge.content.summary, &full_params);
+        knowledge.content.detail = fill_placeholders(&knowledge.content.detail, &full_params);
+
+        // Also substitute in examples if they contain the placeholder
+        for example in &mut knowledge.content.examples {
+            example.code = fill_placeholders(&example.code, &full_params);
+            example.description = fill_placeholders(&example.description, &full_params);
         }
 
         // Handle conditional sections: {{#if param}}...{{/if param}}
@@ -397,6 +415,7 @@ mod tests {
                 success_rate: 1.0,
                 feedback: vec![],
             },
+            archived: false,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
@@ -620,6 +639,7 @@ mod tests {
                 success_rate: 1.0,
                 feedback: vec![],
             },
+            archived: false,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
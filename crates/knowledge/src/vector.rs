@@ -1,20 +1,54 @@
 //! Vector-based knowledge service using embeddings.
 //!
-//! This module provides semantic search capability for knowledge items
-//! using Ollama's embedding API with Qwen3-Embedding-0.6B model.
+//! This module provides semantic search capability for knowledge items.
+//! Embeddings come from an [`EmbeddingProvider`]; by default Ollama's
+//! embedding API with the Qwen3-Embedding-0.6B model.
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use devman_core::{
-    EmbeddingModel, Knowledge, KnowledgeEmbedding, ScoredKnowledge,
-    VectorSearchConfig,
+    ActiveEmbeddingModel, Knowledge, KnowledgeEmbedding, KnowledgeId, ReindexCheckpoint,
+    ScoredKnowledge, VectorSearchConfig,
 };
 use reqwest::{Client, ClientBuilder};
 use serde_json::json;
 use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, warn};
 
+use devman_storage::config::keys;
+use devman_storage::ConfigStore;
+use crate::embedding::{provider_for_config, provider_with_concurrency, EmbeddingProvider};
+
+/// Default maximum number of in-flight requests to a local Ollama server.
+pub const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 4;
+
+/// Number of items embedded between checkpoint writes during a reindex.
+pub const REINDEX_CHECKPOINT_INTERVAL: usize = 16;
+
+/// How far a reindex has got.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReindexProgress {
+    /// Items processed so far, including those finished before a resume
+    pub done: usize,
+    /// Items to process in total
+    pub total: usize,
+}
+
+impl ReindexProgress {
+    /// Share of items processed, from 0 to 100.
+    pub fn percentage(&self) -> u8 {
+        (self.done.min(self.total) * 100)
+            .checked_div(self.total)
+            .map_or(100, |percentage| percentage as u8)
+    }
+}
+
 /// Ollama Embedding Client.
+///
+/// Clones share the same concurrency limit, so callers can fan out freely
+/// while the client throttles requests to the server.
 #[derive(Clone)]
 pub struct OllamaEmbeddingClient {
     /// HTTP client
@@ -25,11 +59,23 @@ pub struct OllamaEmbeddingClient {
 
     /// Model name
     model: String,
+
+    /// Guard limiting the number of in-flight requests
+    limiter: Arc<Semaphore>,
+
+    /// Dimension of the vectors produced by the model
+    dimension: usize,
 }
 
 impl OllamaEmbeddingClient {
     /// Create a new Ollama embedding client.
     pub fn new(url: String, model: String) -> Self {
+        Self::with_max_concurrency(url, model, DEFAULT_MAX_CONCURRENT_REQUESTS)
+    }
+
+    /// Create a new Ollama embedding client allowing at most
+    /// `max_concurrency` in-flight requests (clamped to at least 1).
+    pub fn with_max_concurrency(url: String, model: String, max_concurrency: usize) -> Self {
         Self {
             client: ClientBuilder::new()
                 .timeout(std::time::Duration::from_secs(60))
@@ -37,9 +83,22 @@ impl OllamaEmbeddingClient {
                 .unwrap_or_default(),
             url,
             model,
+            limiter: Arc::new(Semaphore::new(max_concurrency.max(1))),
+            dimension: 1024,
         }
     }
 
+    /// Set the dimension of the vectors produced by the model.
+    pub fn with_dimension(mut self, dimension: usize) -> Self {
+        self.dimension = dimension;
+        self
+    }
+
+    /// Dimension of the vectors produced by the model.
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
     /// Generate embedding for a single text.
     pub async fn embed(&self, text: &str) -> Result<Vec<f32>> {
         let payload = json!({
@@ -50,6 +109,12 @@ impl OllamaEmbeddingClient {
             }
         });
 
+        let _permit = self
+            .limiter
+            .acquire()
+            .await
+            .context("Embedding client limiter closed")?;
+
         debug!("Generating embedding for text ({} chars)", text.len());
 
         let response = self
@@ -93,7 +158,7 @@ impl OllamaEmbeddingClient {
                 Err(e) => {
                     warn!("Failed to embed text: {}", e);
                     // Return zeros for failed embeddings
-                    results.push(vec![0.0; 1024]); // Default dimension
+                    results.push(vec![0.0; self.dimension]);
                 }
             }
         }
@@ -197,6 +262,18 @@ pub trait VectorKnowledgeService: Send + Sync {
     /// Save knowledge with its embedding.
     async fn save_with_embedding(&self, knowledge: &Knowledge) -> Result<()>;
 
+    /// Save knowledge, embedding it as well when `auto_embed` is configured.
+    ///
+    /// Failing to embed does not fail the save: the item stays searchable
+    /// by keyword and [`ensure_all_embedded`](Self::ensure_all_embedded)
+    /// picks it up later.
+    async fn save_knowledge(&self, knowledge: &Knowledge) -> Result<()>;
+
+    /// Embed every knowledge item that has no embedding yet, such as items
+    /// saved before auto-embedding was enabled, returning how many were
+    /// embedded. Items that fail to embed are skipped.
+    async fn ensure_all_embedded(&self) -> Result<usize>;
+
     /// Search knowledge by vector similarity.
     async fn search_by_vector(
         &self,
@@ -208,8 +285,27 @@ pub trait VectorKnowledgeService: Send + Sync {
     /// Search with both keyword and vector similarity (hybrid).
     async fn search_hybrid(&self, query: &str, limit: usize) -> Result<Vec<ScoredKnowledge>>;
 
-    /// Re-index all knowledge items.
-    async fn reindex_all(&self) -> Result<usize>;
+    /// Re-embed all knowledge with this service's model and make it the
+    /// store's active embedding model, returning how many items were embedded.
+    ///
+    /// Switching models is all or nothing: if any item fails to embed, the
+    /// store keeps its current model and embeddings. Reindexing under the
+    /// current model skips failed items, whose old embeddings still fit.
+    async fn reindex_all(&self) -> Result<usize> {
+        self.reindex_all_cancellable(&CancellationToken::new(), &|_| {}).await
+    }
+
+    /// Like [`reindex_all`](Self::reindex_all), but stops when `cancel`
+    /// fires and reports progress after every item.
+    ///
+    /// Progress is checkpointed in storage, so a reindex that was cancelled
+    /// or interrupted resumes with the items it had not embedded yet. Returns
+    /// how many items this run embedded.
+    async fn reindex_all_cancellable(
+        &self,
+        cancel: &CancellationToken,
+        on_progress: &(dyn Fn(ReindexProgress) + Send + Sync),
+    ) -> Result<usize>;
 
     /// Check if vector search is available.
     async fn is_available(&self) -> bool;
@@ -221,8 +317,8 @@ pub struct VectorKnowledgeServiceImpl<S: devman_storage::Storage> {
     /// Storage backend (wrapped in mutex for interior mutability)
     storage: Arc<tokio::sync::Mutex<S>>,
 
-    /// Ollama client
-    ollama: OllamaEmbeddingClient,
+    /// Embedding provider
+    provider: Arc<dyn EmbeddingProvider>,
 
     /// Vector index
     index: Arc<tokio::sync::Mutex<LocalVectorIndex>>,
@@ -232,19 +328,40 @@ pub struct VectorKnowledgeServiceImpl<S: devman_storage::Storage> {
 }
 
 impl<S: devman_storage::Storage> VectorKnowledgeServiceImpl<S> {
-    /// Create a new vector knowledge service.
+    /// Create a new vector knowledge service using the provider selected
+    /// by `config.model`.
     pub fn new(storage: Arc<tokio::sync::Mutex<S>>, config: VectorSearchConfig) -> Self {
-        let (url, model) = match &config.model {
-            EmbeddingModel::Qwen3Embedding0_6B => (config.ollama_url.clone(), "qwen3-embedding:0.6b".to_string()),
-            EmbeddingModel::OpenAIAda002 => (config.ollama_url.clone(), "text-embedding-ada-002".to_string()),
-            EmbeddingModel::Ollama { name } => (config.ollama_url.clone(), name.clone()),
-        };
+        let provider = provider_for_config(&config);
+        Self::with_provider(storage, config, provider)
+    }
 
-        let ollama = OllamaEmbeddingClient::new(url, model);
+    /// Create a new vector knowledge service, letting settings stored in
+    /// `storage` override `config`: `knowledge.embedding_model` replaces the
+    /// model and `knowledge.max_concurrency` caps in-flight Ollama requests.
+    pub async fn from_storage(storage: Arc<tokio::sync::Mutex<S>>, mut config: VectorSearchConfig) -> Self {
+        let (model, max_concurrency) = {
+            let storage = storage.lock().await;
+            (
+                storage.get_config_or(keys::KNOWLEDGE_EMBEDDING_MODEL, None).await,
+                storage.get_config_or(keys::KNOWLEDGE_MAX_CONCURRENCY, DEFAULT_MAX_CONCURRENT_REQUESTS).await,
+            )
+        };
+        if let Some(model) = model {
+            config.model = model;
+        }
+        let provider = provider_with_concurrency(&config, max_concurrency);
+        Self::with_provider(storage, config, provider)
+    }
 
+    /// Create a new vector knowledge service with an explicit embedding provider.
+    pub fn with_provider(
+        storage: Arc<tokio::sync::Mutex<S>>,
+        config: VectorSearchConfig,
+        provider: Box<dyn EmbeddingProvider>,
+    ) -> Self {
         Self {
             storage,
-            ollama,
+            provider: Arc::from(provider),
             index: Arc::new(tokio::sync::Mutex::new(LocalVectorIndex::new(config.dimension))),
             config,
         }
@@ -252,13 +369,17 @@ impl<S: devman_storage::Storage> VectorKnowledgeServiceImpl<S> {
 
     /// Initialize the index from storage.
     pub async fn initialize(&self) -> Result<()> {
-        let all_embeddings = self
-            .storage
-            .lock()
-            .await
-            .list_vector_embeddings()
-            .await
-            .unwrap_or_default();
+        let storage = self.storage.lock().await;
+        if let Ok(Some(active)) = storage.load_active_embedding_model().await {
+            if active != self.active_model() {
+                warn!(
+                    "Stored embeddings use {}, but vector search is configured for {}; reindex all knowledge to switch",
+                    active,
+                    self.active_model()
+                );
+            }
+        }
+        let all_embeddings = storage.list_vector_embeddings().await.unwrap_or_default();
 
         let mut index = self.index.lock().await;
         for embedding in all_embeddings {
@@ -268,26 +389,67 @@ impl<S: devman_storage::Storage> VectorKnowledgeServiceImpl<S> {
         debug!("Initialized vector index with {} embeddings", index.len());
         Ok(())
     }
+
+    /// Persist how far a reindex has got.
+    async fn save_reindex_checkpoint(&self, checkpoint: &mut ReindexCheckpoint) -> Result<()> {
+        checkpoint.updated_at = chrono::Utc::now();
+        self.storage
+            .lock()
+            .await
+            .save_reindex_checkpoint(checkpoint)
+            .await
+            .context("Failed to save reindex checkpoint")
+    }
+
+    /// Embed a knowledge item with this service's model.
+    async fn create_embedding(&self, knowledge: &Knowledge) -> Result<KnowledgeEmbedding> {
+        Ok(KnowledgeEmbedding {
+            knowledge_id: knowledge.id,
+            embedding: self.provider.embed(&embedding_text(knowledge)).await?,
+            model: self.config.model.clone(),
+            created_at: chrono::Utc::now(),
+        })
+    }
+
+    /// Persist an embedding and put it in the index, replacing any earlier
+    /// embedding of the same item.
+    async fn store_embedding(&self, embedding: KnowledgeEmbedding) -> Result<()> {
+        self.storage
+            .lock()
+            .await
+            .save_vector_embedding(&embedding)
+            .await
+            .context("Failed to save vector embedding")?;
+
+        let mut index = self.index.lock().await;
+        index.remove(&embedding.knowledge_id.to_string());
+        index.add(embedding);
+        Ok(())
+    }
+
+    /// The model and dimension this service embeds with.
+    fn active_model(&self) -> ActiveEmbeddingModel {
+        ActiveEmbeddingModel {
+            model: self.config.model.clone(),
+            dimension: self.provider.dimension(),
+        }
+    }
+}
+
+/// Text embedded for a knowledge item.
+fn embedding_text(knowledge: &Knowledge) -> String {
+    format!("{}: {}", knowledge.title, knowledge.content.summary)
 }
 
 #[async_trait]
 impl<S: devman_storage::Storage + 'static> VectorKnowledgeService for VectorKnowledgeServiceImpl<S> {
     async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>> {
-        self.ollama.embed(text).await
+        self.provider.embed(text).await
     }
 
     async fn save_with_embedding(&self, knowledge: &Knowledge) -> Result<()> {
         // Generate embedding
-        let text_to_embed = format!("{}: {}", knowledge.title, knowledge.content.summary);
-        let embedding = self.generate_embedding(&text_to_embed).await?;
-
-        // Create embedding record
-        let knowledge_embedding = KnowledgeEmbedding {
-            knowledge_id: knowledge.id,
-            embedding,
-            model: self.config.model.clone(),
-            created_at: chrono::Utc::now(),
-        };
+        let embedding = self.create_embedding(knowledge).await?;
 
         // Save knowledge to storage
         self.storage
@@ -297,21 +459,63 @@ impl<S: devman_storage::Storage + 'static> VectorKnowledgeService for VectorKnow
             .await
             .context("Failed to save knowledge")?;
 
-        // Save embedding to storage
+        self.store_embedding(embedding).await
+    }
+
+    async fn save_knowledge(&self, knowledge: &Knowledge) -> Result<()> {
         self.storage
             .lock()
             .await
-            .save_vector_embedding(&knowledge_embedding)
+            .save_knowledge(knowledge)
             .await
-            .context("Failed to save vector embedding")?;
-
-        // Add to index
-        let mut index = self.index.lock().await;
-        index.add(knowledge_embedding);
+            .context("Failed to save knowledge")?;
+        if !self.config.auto_embed {
+            return Ok(());
+        }
 
+        let embedded = match self.create_embedding(knowledge).await {
+            Ok(embedding) => self.store_embedding(embedding).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = embedded {
+            warn!("Failed to embed knowledge {}: {:#}", knowledge.id, e);
+        }
         Ok(())
     }
 
+    async fn ensure_all_embedded(&self) -> Result<usize> {
+        let missing: Vec<Knowledge> = {
+            let storage = self.storage.lock().await;
+            let embedded: std::collections::HashSet<KnowledgeId> = storage
+                .list_vector_embeddings()
+                .await
+                .context("Failed to list vector embeddings")?
+                .into_iter()
+                .map(|e| e.knowledge_id)
+                .collect();
+            storage
+                .list_knowledge()
+                .await
+                .context("Failed to list knowledge")?
+                .into_iter()
+                .filter(|k| !embedded.contains(&k.id))
+                .collect()
+        };
+
+        let mut count = 0;
+        for knowledge in &missing {
+            match self.create_embedding(knowledge).await {
+                Ok(embedding) => {
+                    self.store_embedding(embedding).await?;
+                    count += 1;
+                }
+                Err(e) => warn!("Failed to embed knowledge {}: {:#}", knowledge.id, e),
+            }
+        }
+        debug!("Embedded {} of {} knowledge items without embeddings", count, missing.len());
+        Ok(count)
+    }
+
     async fn search_by_vector(
         &self,
         query: &str,
@@ -321,20 +525,25 @@ impl<S: devman_storage::Storage + 'static> VectorKnowledgeService for VectorKnow
         // Generate query embedding
         let query_embedding = self.generate_embedding(query).await?;
 
-        // Search index
+        // Search index, ranking every match as archived items are skipped
         let index = self.index.lock().await;
-        let results = index.search(&query_embedding, limit, threshold);
+        let results = index.search(&query_embedding, index.len(), threshold);
 
         // Load knowledge for each result
         let storage = self.storage.lock().await;
         let mut scored_knowledge = Vec::new();
         for (knowledge_id_str, score) in results {
+            if scored_knowledge.len() == limit {
+                break;
+            }
             if let Ok(knowledge_id) = knowledge_id_str.parse() {
                 if let Ok(Some(knowledge)) = storage.load_knowledge(knowledge_id).await {
-                    scored_knowledge.push(ScoredKnowledge {
-                        knowledge,
-                        score,
-                    });
+                    if !knowledge.archived {
+                        scored_knowledge.push(ScoredKnowledge {
+                            knowledge,
+                            score,
+                        });
+                    }
                 }
             }
         }
@@ -348,35 +557,245 @@ impl<S: devman_storage::Storage + 'static> VectorKnowledgeService for VectorKnow
         self.search_by_vector(query, limit, self.config.threshold).await
     }
 
-    async fn reindex_all(&self) -> Result<usize> {
-        let all_knowledge = self
-            .storage
-            .lock()
-            .await
-            .list_knowledge()
-            .await
-            .context("Failed to list knowledge")?;
+    async fn reindex_all_cancellable(
+        &self,
+        cancel: &CancellationToken,
+        on_progress: &(dyn Fn(ReindexProgress) + Send + Sync),
+    ) -> Result<usize> {
+        let (all_knowledge, active, checkpoint) = {
+            let storage = self.storage.lock().await;
+            let all_knowledge = storage.list_knowledge().await.context("Failed to list knowledge")?;
+            let active = storage
+                .load_active_embedding_model()
+                .await
+                .context("Failed to load active embedding model")?;
+            let checkpoint = storage
+                .load_reindex_checkpoint()
+                .await
+                .context("Failed to load reindex checkpoint")?;
+            (all_knowledge, active, checkpoint)
+        };
+        let target = self.active_model();
+        let switching = active.as_ref().is_some_and(|a| *a != target);
+
+        // A checkpoint left by a reindex to another model is of no use
+        let mut checkpoint = checkpoint
+            .filter(|c| c.target == target)
+            .unwrap_or_else(|| ReindexCheckpoint {
+                target: target.clone(),
+                done: Vec::new(),
+                pending: Vec::new(),
+                updated_at: chrono::Utc::now(),
+            });
+        let already_done: std::collections::HashSet<KnowledgeId> = checkpoint.done.iter().copied().collect();
+        let remaining: Vec<&Knowledge> = all_knowledge.iter().filter(|k| !already_done.contains(&k.id)).collect();
+        let mut progress = ReindexProgress {
+            done: all_knowledge.len() - remaining.len(),
+            total: all_knowledge.len(),
+        };
+        if progress.done > 0 {
+            debug!("Resuming reindex with {} of {} items done", progress.done, progress.total);
+        }
 
-        let mut count = 0;
-        for knowledge in &all_knowledge {
-            if let Err(e) = self.save_with_embedding(knowledge).await {
-                warn!("Failed to reindex knowledge {}: {}", knowledge.id, e);
-            } else {
-                count += 1;
+        let mut embedded = 0;
+        let mut failed = 0;
+        let mut unsaved = 0;
+        for knowledge in remaining {
+            let text = embedding_text(knowledge);
+            let result = tokio::select! {
+                biased;
+                _ = cancel.cancelled() => {
+                    self.save_reindex_checkpoint(&mut checkpoint).await?;
+                    anyhow::bail!(
+                        "Reindex cancelled after {} of {} knowledge items; the next reindex resumes from there",
+                        progress.done,
+                        progress.total
+                    );
+                }
+                result = self.generate_embedding(&text) => result,
+            };
+            match result {
+                Ok(embedding) if embedding.len() == target.dimension => {
+                    let embedding = KnowledgeEmbedding {
+                        knowledge_id: knowledge.id,
+                        embedding,
+                        model: target.model.clone(),
+                        created_at: chrono::Utc::now(),
+                    };
+                    if switching {
+                        checkpoint.pending.push(embedding);
+                    } else {
+                        self.storage
+                            .lock()
+                            .await
+                            .save_vector_embedding(&embedding)
+                            .await
+                            .context("Failed to save vector embedding")?;
+                    }
+                    checkpoint.done.push(knowledge.id);
+                    embedded += 1;
+                    unsaved += 1;
+                }
+                Ok(embedding) => {
+                    warn!(
+                        "Failed to reindex knowledge {}: got {} dims, expected {}",
+                        knowledge.id,
+                        embedding.len(),
+                        target.dimension
+                    );
+                    failed += 1;
+                }
+                Err(e) => {
+                    warn!("Failed to reindex knowledge {}: {}", knowledge.id, e);
+                    failed += 1;
+                }
+            }
+            if unsaved >= REINDEX_CHECKPOINT_INTERVAL {
+                self.save_reindex_checkpoint(&mut checkpoint).await?;
+                unsaved = 0;
             }
+            progress.done += 1;
+            on_progress(progress);
         }
 
-        Ok(count)
+        if switching && failed > 0 {
+            self.save_reindex_checkpoint(&mut checkpoint).await?;
+            anyhow::bail!(
+                "Failed to embed {} of {} knowledge items with {}; embedding model unchanged",
+                failed,
+                all_knowledge.len(),
+                target
+            );
+        }
+
+        let mut storage = self.storage.lock().await;
+        if active.as_ref() != Some(&target) {
+            storage
+                .save_active_embedding_model(&target)
+                .await
+                .context("Failed to save active embedding model")?;
+        }
+        for embedding in &checkpoint.pending {
+            storage
+                .save_vector_embedding(embedding)
+                .await
+                .context("Failed to save vector embedding")?;
+        }
+        storage
+            .clear_reindex_checkpoint()
+            .await
+            .context("Failed to clear reindex checkpoint")?;
+
+        let mut index = LocalVectorIndex::new(target.dimension);
+        for embedding in storage.list_vector_embeddings().await.unwrap_or_default() {
+            index.add(embedding);
+        }
+        *self.index.lock().await = index;
+
+        Ok(embedded)
     }
 
     async fn is_available(&self) -> bool {
-        self.ollama.health_check().await.unwrap_or(false)
+        self.provider.health_check().await.unwrap_or(false)
     }
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
+    use crate::embedding::MockEmbeddingProvider;
+    use devman_storage::Storage;
+    use devman_core::{
+        EmbeddingModel, KnowledgeContent, KnowledgeMetadata, KnowledgeType, UsageStats,
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Minimal HTTP server answering every request with `body` after a short
+    /// delay, recording the maximum number of requests handled concurrently.
+    pub(crate) async fn spawn_mock_ollama(body: &'static str) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_seen = Arc::new(AtomicUsize::new(0));
+        let max_seen_out = max_seen.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    break;
+                };
+                let in_flight = in_flight.clone();
+                let max_seen = max_seen.clone();
+                tokio::spawn(async move {
+                    // Read headers, then the body announced by Content-Length
+                    let mut buf = Vec::new();
+                    let mut chunk = [0u8; 1024];
+                    let header_end = loop {
+                        let n = socket.read(&mut chunk).await.unwrap_or(0);
+                        if n == 0 {
+                            return;
+                        }
+                        buf.extend_from_slice(&chunk[..n]);
+                        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                            break pos + 4;
+                        }
+                    };
+                    let headers = String::from_utf8_lossy(&buf[..header_end]).to_lowercase();
+                    let content_length = headers
+                        .lines()
+                        .find_map(|l| l.strip_prefix("content-length:"))
+                        .and_then(|v| v.trim().parse::<usize>().ok())
+                        .unwrap_or(0);
+                    while buf.len() < header_end + content_length {
+                        let n = socket.read(&mut chunk).await.unwrap_or(0);
+                        if n == 0 {
+                            break;
+                        }
+                        buf.extend_from_slice(&chunk[..n]);
+                    }
+
+                    let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_seen.fetch_max(current, Ordering::SeqCst);
+                    tokio::time::sleep(std::time::Duration::from_millis(30)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                    let _ = socket.shutdown().await;
+                });
+            }
+        });
+
+        (url, max_seen_out)
+    }
+
+    #[tokio::test]
+    async fn test_embedding_client_respects_concurrency_limit() {
+        let (url, max_seen) = spawn_mock_ollama(r#"{"embedding":[0.1,0.2,0.3]}"#).await;
+        let client = OllamaEmbeddingClient::with_max_concurrency(url, "test".to_string(), 2);
+
+        let handles: Vec<_> = (0..12)
+            .map(|i| {
+                let client = client.clone();
+                tokio::spawn(async move { client.embed(&format!("text {}", i)).await })
+            })
+            .collect();
+
+        for handle in handles {
+            let embedding = handle.await.unwrap().unwrap();
+            assert_eq!(embedding.len(), 3);
+        }
+
+        let observed = max_seen.load(Ordering::SeqCst);
+        assert!(observed >= 1);
+        assert!(observed <= 2, "observed {} concurrent requests", observed);
+    }
 
     fn create_test_embedding() -> KnowledgeEmbedding {
         KnowledgeEmbedding {
@@ -426,4 +845,341 @@ mod tests {
         assert_eq!(results[0].0, id1_str);
         assert!((results[0].1 - 1.0).abs() < 0.001);
     }
+
+    fn create_test_knowledge(title: &str, summary: &str) -> Knowledge {
+        Knowledge {
+            id: devman_core::KnowledgeId::new(),
+            title: title.to_string(),
+            knowledge_type: KnowledgeType::LessonLearned {
+                lesson: "Test lesson".to_string(),
+                context: "Test context".to_string(),
+            },
+            content: KnowledgeContent {
+                summary: summary.to_string(),
+                detail: String::new(),
+                examples: vec![],
+                references: vec![],
+            },
+            metadata: KnowledgeMetadata {
+                domain: vec![],
+                tech_stack: vec![],
+                scenarios: vec![],
+                quality_score: 1.0,
+                verified: true,
+            },
+            tags: vec![],
+            related_to: vec![],
+            derived_from: vec![],
+            usage_stats: UsageStats {
+                times_used: 0,
+                last_used: None,
+                success_rate: 1.0,
+                feedback: vec![],
+            },
+            archived: false,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_save_and_search_with_mock_provider() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let storage = Arc::new(tokio::sync::Mutex::new(storage));
+        let config = VectorSearchConfig { enabled: true, dimension: 64, ..Default::default() };
+        let service = VectorKnowledgeServiceImpl::with_provider(
+            storage.clone(),
+            config,
+            Box::new(MockEmbeddingProvider::new(64)),
+        );
+
+        let tokio_item = create_test_knowledge("Tokio runtime", "async runtime tasks spawn");
+        let sql_item = create_test_knowledge("SQL indexes", "database index query planner");
+        service.save_with_embedding(&tokio_item).await.unwrap();
+        service.save_with_embedding(&sql_item).await.unwrap();
+
+        assert!(service.is_available().await);
+        assert_eq!(storage.lock().await.list_vector_embeddings().await.unwrap().len(), 2);
+
+        let results = service.search_by_vector("tokio async runtime", 5, 0.3).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].knowledge.id, tokio_item.id);
+
+        // Archived knowledge keeps its embedding but is no longer found,
+        // without taking a place within the limit
+        let mut archived = create_test_knowledge("Tokio async runtime", "tokio async runtime");
+        archived.archived = true;
+        service.save_with_embedding(&archived).await.unwrap();
+        let results = service.search_by_vector("tokio async runtime", 1, 0.3).await.unwrap();
+        assert_eq!(results.iter().map(|r| r.knowledge.id).collect::<Vec<_>>(), vec![tokio_item.id]);
+
+        // A fresh service over the same storage rebuilds its index from disk
+        let reloaded = VectorKnowledgeServiceImpl::with_provider(
+            storage,
+            VectorSearchConfig { enabled: true, dimension: 64, ..Default::default() },
+            Box::new(MockEmbeddingProvider::new(64)),
+        );
+        reloaded.initialize().await.unwrap();
+        let results = reloaded.search_by_vector("database query", 5, 0.3).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].knowledge.id, sql_item.id);
+    }
+
+    #[tokio::test]
+    async fn test_embedding_model_read_from_stored_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let model = devman_core::EmbeddingModel::Ollama { name: "nomic-embed-text".to_string() };
+        storage.set_config(keys::KNOWLEDGE_EMBEDDING_MODEL, &model).await.unwrap();
+        storage.set_config(keys::KNOWLEDGE_MAX_CONCURRENCY, &1usize).await.unwrap();
+
+        let reloaded = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let config = VectorSearchConfig { enabled: true, dimension: 768, ..Default::default() };
+        let service = VectorKnowledgeServiceImpl::from_storage(Arc::new(tokio::sync::Mutex::new(reloaded)), config).await;
+        assert_eq!(service.active_model(), ActiveEmbeddingModel { model, dimension: 768 });
+
+        // Without settings the configured model stays
+        let dir = tempfile::tempdir().unwrap();
+        let storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let service = VectorKnowledgeServiceImpl::from_storage(
+            Arc::new(tokio::sync::Mutex::new(storage)),
+            VectorSearchConfig::default(),
+        )
+        .await;
+        assert_eq!(service.active_model().model, devman_core::EmbeddingModel::Qwen3Embedding0_6B);
+    }
+
+    #[tokio::test]
+    async fn test_plain_save_is_vector_searchable() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let storage = Arc::new(tokio::sync::Mutex::new(storage));
+        let service = VectorKnowledgeServiceImpl::with_provider(
+            storage.clone(),
+            VectorSearchConfig { enabled: true, dimension: 64, ..Default::default() },
+            Box::new(FlakyProvider(MockEmbeddingProvider::new(64))),
+        );
+
+        let mut item = create_test_knowledge("Tokio runtime", "async runtime tasks spawn");
+        service.save_knowledge(&item).await.unwrap();
+        let results = service.search_by_vector("tokio async runtime", 5, 0.3).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].knowledge.id, item.id);
+
+        // Re-saving keeps one embedding per item, in step with the content
+        item.title = "SQL indexes".to_string();
+        item.content.summary = "database index query planner".to_string();
+        service.save_knowledge(&item).await.unwrap();
+        assert_eq!(storage.lock().await.list_vector_embeddings().await.unwrap().len(), 1);
+        let results = service.search_by_vector("database query", 5, 0.3).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(service.search_by_vector("tokio async runtime", 5, 0.3).await.unwrap().is_empty());
+
+        // An item that cannot be embedded is still saved
+        let broken = create_test_knowledge("broken item", "cannot embed");
+        service.save_knowledge(&broken).await.unwrap();
+        assert!(storage.lock().await.load_knowledge(broken.id).await.unwrap().is_some());
+        assert_eq!(storage.lock().await.list_vector_embeddings().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_ensure_all_embedded_backfills_missing_items() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let storage = Arc::new(tokio::sync::Mutex::new(storage));
+        let config = VectorSearchConfig { enabled: true, dimension: 64, auto_embed: false, ..Default::default() };
+        let manual = VectorKnowledgeServiceImpl::with_provider(
+            storage.clone(),
+            config,
+            Box::new(MockEmbeddingProvider::new(64)),
+        );
+        let sql_item = create_test_knowledge("SQL indexes", "database index query planner");
+        manual.save_knowledge(&sql_item).await.unwrap();
+        manual
+            .save_with_embedding(&create_test_knowledge("Tokio runtime", "async runtime tasks spawn"))
+            .await
+            .unwrap();
+        assert!(manual.search_by_vector("database query", 5, 0.3).await.unwrap().is_empty());
+
+        let provider = Arc::new(CountingProvider {
+            inner: MockEmbeddingProvider::new(64),
+            embedded: std::sync::Mutex::new(Vec::new()),
+        });
+        let service = VectorKnowledgeServiceImpl::with_provider(
+            storage.clone(),
+            VectorSearchConfig { enabled: true, dimension: 64, ..Default::default() },
+            Box::new(provider.clone()),
+        );
+        service.initialize().await.unwrap();
+        assert_eq!(service.ensure_all_embedded().await.unwrap(), 1);
+        assert_eq!(*provider.embedded.lock().unwrap(), vec!["SQL indexes: database index query planner"]);
+        assert_eq!(service.ensure_all_embedded().await.unwrap(), 0);
+
+        let results = service.search_by_vector("database query", 5, 0.3).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].knowledge.id, sql_item.id);
+    }
+
+    /// Mock provider that fails on texts mentioning "broken".
+    struct FlakyProvider(MockEmbeddingProvider);
+
+    #[async_trait]
+    impl EmbeddingProvider for FlakyProvider {
+        async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+            if text.contains("broken") {
+                anyhow::bail!("embedding server unavailable");
+            }
+            self.0.embed(text).await
+        }
+
+        fn dimension(&self) -> usize {
+            self.0.dimension()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reindex_switches_embedding_model() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let storage = Arc::new(tokio::sync::Mutex::new(storage));
+        let qwen = VectorKnowledgeServiceImpl::with_provider(
+            storage.clone(),
+            VectorSearchConfig { enabled: true, dimension: 64, ..Default::default() },
+            Box::new(MockEmbeddingProvider::new(64)),
+        );
+        let tokio_item = create_test_knowledge("Tokio runtime", "async runtime tasks spawn");
+        let sql_item = create_test_knowledge("SQL indexes", "database index query planner");
+        qwen.save_with_embedding(&tokio_item).await.unwrap();
+        qwen.save_with_embedding(&sql_item).await.unwrap();
+
+        let ada_config = VectorSearchConfig {
+            enabled: true,
+            model: EmbeddingModel::OpenAIAda002,
+            dimension: 128,
+            ..Default::default()
+        };
+        let ada = VectorKnowledgeServiceImpl::with_provider(
+            storage.clone(),
+            ada_config.clone(),
+            Box::new(MockEmbeddingProvider::new(128)),
+        );
+
+        // Mixing models is refused until everything is reindexed
+        let err = ada
+            .save_with_embedding(&create_test_knowledge("Mutex", "lock contention"))
+            .await
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("reindex"));
+
+        assert_eq!(ada.reindex_all().await.unwrap(), 3);
+        let active = storage.lock().await.load_active_embedding_model().await.unwrap().unwrap();
+        assert_eq!(active, ActiveEmbeddingModel { model: EmbeddingModel::OpenAIAda002, dimension: 128 });
+        let embeddings = storage.lock().await.list_vector_embeddings().await.unwrap();
+        assert_eq!(embeddings.len(), 3);
+        assert!(embeddings.iter().all(|e| active.matches(e)));
+
+        let results = ada.search_by_vector("database query", 5, 0.3).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].knowledge.id, sql_item.id);
+
+        // A switch that cannot embed every item leaves the store untouched
+        storage
+            .lock()
+            .await
+            .save_knowledge(&create_test_knowledge("broken item", "cannot embed"))
+            .await
+            .unwrap();
+        let flaky = VectorKnowledgeServiceImpl::with_provider(
+            storage.clone(),
+            VectorSearchConfig { enabled: true, dimension: 32, ..Default::default() },
+            Box::new(FlakyProvider(MockEmbeddingProvider::new(32))),
+        );
+        assert!(flaky.reindex_all().await.is_err());
+        assert_eq!(storage.lock().await.load_active_embedding_model().await.unwrap(), Some(active.clone()));
+        let embeddings = storage.lock().await.list_vector_embeddings().await.unwrap();
+        assert!(embeddings.iter().all(|e| active.matches(e)));
+    }
+
+    /// Mock provider counting the texts it embeds.
+    struct CountingProvider {
+        inner: MockEmbeddingProvider,
+        embedded: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for Arc<CountingProvider> {
+        async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+            self.embedded.lock().unwrap().push(text.to_string());
+            self.inner.embed(text).await
+        }
+
+        fn dimension(&self) -> usize {
+            self.inner.dimension()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_reindex_resumes_with_remaining_items() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let storage = Arc::new(tokio::sync::Mutex::new(storage));
+        let titles = ["Tokio", "SQL", "Mutex", "Serde", "Clap"];
+        for title in titles {
+            storage
+                .lock()
+                .await
+                .save_knowledge(&create_test_knowledge(title, "summary"))
+                .await
+                .unwrap();
+        }
+        let provider = Arc::new(CountingProvider {
+            inner: MockEmbeddingProvider::new(64),
+            embedded: std::sync::Mutex::new(Vec::new()),
+        });
+        let service = VectorKnowledgeServiceImpl::with_provider(
+            storage.clone(),
+            VectorSearchConfig { enabled: true, dimension: 64, ..Default::default() },
+            Box::new(provider.clone()),
+        );
+
+        let cancel = CancellationToken::new();
+        let stop = cancel.clone();
+        let err = service
+            .reindex_all_cancellable(&cancel, &move |progress| {
+                if progress.done == 2 {
+                    stop.cancel();
+                }
+            })
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("after 2 of 5"), "{}", err);
+        let first_run = std::mem::take(&mut *provider.embedded.lock().unwrap());
+        assert_eq!(first_run.len(), 2);
+        let checkpoint = storage.lock().await.load_reindex_checkpoint().await.unwrap().unwrap();
+        assert_eq!(checkpoint.done.len(), 2);
+
+        let percentages = std::sync::Mutex::new(Vec::new());
+        let embedded = service
+            .reindex_all_cancellable(&CancellationToken::new(), &|progress| {
+                percentages.lock().unwrap().push(progress.percentage());
+            })
+            .await
+            .unwrap();
+        assert_eq!(embedded, 3);
+        assert_eq!(percentages.into_inner().unwrap(), vec![60, 80, 100]);
+
+        // Only the items the first run had not reached were embedded again
+        let second_run = provider.embedded.lock().unwrap().clone();
+        assert_eq!(second_run.len(), 3);
+        assert!(second_run.iter().all(|text| !first_run.contains(text)));
+        let mut all: Vec<_> = first_run.iter().chain(&second_run).cloned().collect();
+        all.sort();
+        let mut expected: Vec<_> = titles.iter().map(|t| format!("{}: summary", t)).collect();
+        expected.sort();
+        assert_eq!(all, expected);
+
+        assert_eq!(storage.lock().await.list_vector_embeddings().await.unwrap().len(), 5);
+        assert!(storage.lock().await.load_reindex_checkpoint().await.unwrap().is_none());
+    }
 }
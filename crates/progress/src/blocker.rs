@@ -7,7 +7,7 @@
 //! - Blocker statistics and reporting
 
 use devman_core::{
-    Blocker, BlockedItem, PhaseId, Task, TaskId, TaskStatus, Severity, GoalId, Phase,
+    Blocker, BlockedItem, BlockerCategory, Clock, PhaseId, SystemClock, Task, TaskId, TaskStatus, Severity, GoalId, Phase,
 };
 use devman_storage::Storage;
 use std::collections::{HashMap, HashSet};
@@ -83,16 +83,49 @@ pub struct BlockerAnalysis {
     pub circular_chains: Vec<Vec<TaskId>>,
 }
 
+/// Longest dependency chain followed when looking for cycles, by default.
+pub const DEFAULT_MAX_CYCLE_DEPTH: usize = 10_000;
+
+/// Outcome of searching for a cycle from one task.
+enum CycleSearch {
+    /// A cycle, in dependency order
+    Found(Vec<TaskId>),
+    /// The chain below this task is longer than the configured maximum
+    TooDeep(TaskId),
+    /// No cycle reachable from the task
+    None,
+}
+
 /// Something that is blocking progress.
 #[derive(Clone)]
 pub struct BlockerDetector {
     storage: Arc<dyn Storage>,
+    clock: Arc<dyn Clock>,
+    max_cycle_depth: usize,
 }
 
 impl BlockerDetector {
     /// Create a new blocker detector.
     pub fn new(storage: Arc<dyn Storage>) -> Self {
-        Self { storage }
+        Self {
+            storage,
+            clock: Arc::new(SystemClock),
+            max_cycle_depth: DEFAULT_MAX_CYCLE_DEPTH,
+        }
+    }
+
+    /// Measure blocker age against `clock` instead of the system clock.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Follow dependency chains at most `depth` tasks deep when looking for
+    /// cycles. A deeper chain stops the search and is reported as a warning
+    /// blocker, which also bounds the memory the search holds.
+    pub fn with_max_cycle_depth(mut self, depth: usize) -> Self {
+        self.max_cycle_depth = depth.max(1);
+        self
     }
 
     /// Detect all current blockers with full analysis.
@@ -114,9 +147,19 @@ impl BlockerDetector {
         // Detect circular dependencies
         let (circular_chains, circular_blockers) = self.detect_circular_dependencies(&task_map);
 
-        // Combine all blockers
-        let mut all_blockers = dependency_blockers;
-        all_blockers.extend(circular_blockers);
+        // Combine all blockers. Recorded blockers take the place of derived
+        // ones with the same item and reason, and only unresolved ones count.
+        let recorded = self.storage.list_blockers().await.unwrap_or_default();
+        let mut all_blockers: Vec<Blocker> = dependency_blockers
+            .into_iter()
+            .chain(circular_blockers)
+            .filter(|derived| {
+                !recorded
+                    .iter()
+                    .any(|r| r.blocked_item == derived.blocked_item && r.reason == derived.reason)
+            })
+            .collect();
+        all_blockers.extend(recorded.into_iter().filter(|b| !b.is_resolved()));
 
         // Generate resolution suggestions
         let suggestions = self.generate_suggestions(&task_map, &all_blockers);
@@ -153,6 +196,7 @@ impl BlockerDetector {
                                     dep.title, dep.status
                                 ),
                                 severity: Severity::Error,
+                                category: BlockerCategory::Dependency,
                                 created_at: task.updated_at,
                                 resolved_at: None,
                             });
@@ -167,6 +211,7 @@ impl BlockerDetector {
                                 dep_id
                             ),
                             severity: Severity::Error,
+                            category: BlockerCategory::Dependency,
                             created_at: task.updated_at,
                             resolved_at: None,
                         });
@@ -186,73 +231,97 @@ impl BlockerDetector {
         let mut cycles = Vec::new();
         let mut blockers = Vec::new();
         let mut visited: HashSet<TaskId> = HashSet::new();
-        let mut recursion_stack: HashSet<TaskId> = HashSet::new();
 
         for start_id in task_map.keys() {
-            if !visited.contains(start_id) {
-                if let Some(cycle) =
-                    self.find_cycle(start_id, task_map, &mut visited, &mut recursion_stack, &mut Vec::new())
-                {
-                    cycles.push(cycle.clone());
-
+            if visited.contains(start_id) {
+                continue;
+            }
+            match self.find_cycle(start_id, task_map, &mut visited) {
+                CycleSearch::Found(cycle) => {
                     // Create blocker for each task in the cycle
                     for task_id in &cycle {
                         if let Some(task) = task_map.get(task_id) {
                             blockers.push(Blocker {
                                 id: devman_core::BlockerId::new(),
                                 blocked_item: BlockedItem::Task(*task_id),
-                                reason: format!(
-                                    "Circular dependency detected: task is part of a dependency cycle"
-                                ),
+                                reason: "Circular dependency detected: task is part of a dependency cycle"
+                                    .to_string(),
                                 severity: Severity::Error,
+                                category: BlockerCategory::Dependency,
                                 created_at: task.updated_at,
                                 resolved_at: None,
                             });
                         }
                     }
+                    cycles.push(cycle);
                 }
+                CycleSearch::TooDeep(task_id) => {
+                    if let Some(task) = task_map.get(&task_id) {
+                        blockers.push(Blocker {
+                            id: devman_core::BlockerId::new(),
+                            blocked_item: BlockedItem::Task(task_id),
+                            reason: format!(
+                                "Dependency chain deeper than {} tasks; cycle detection stopped here",
+                                self.max_cycle_depth
+                            ),
+                            severity: Severity::Warning,
+                            category: BlockerCategory::Dependency,
+                            created_at: task.updated_at,
+                            resolved_at: None,
+                        });
+                    }
+                    // Tasks explored so far are skipped from here on; the
+                    // rest of the graph may still hold cycles of its own
+                    continue;
+                }
+                CycleSearch::None => {}
             }
         }
 
         (cycles, blockers)
     }
 
-    /// Find a cycle starting from a node using DFS.
+    /// Find a cycle reachable from `start` with a depth-first search.
+    ///
+    /// The search keeps its own stack instead of recursing, so long chains
+    /// cannot overflow the thread stack, and gives up once the current path
+    /// reaches `max_cycle_depth` tasks.
     fn find_cycle(
         &self,
-        node: &TaskId,
+        start: &TaskId,
         task_map: &HashMap<TaskId, Task>,
         visited: &mut HashSet<TaskId>,
-        recursion_stack: &mut HashSet<TaskId>,
-        path: &mut Vec<TaskId>,
-    ) -> Option<Vec<TaskId>> {
-        visited.insert(*node);
-        recursion_stack.insert(*node);
-        path.push(*node);
-
-        if let Some(task) = task_map.get(node) {
-            for dep_id in &task.depends_on {
-                if !visited.contains(dep_id) {
-                    if let Some(cycle) = self.find_cycle(
-                        dep_id,
-                        task_map,
-                        visited,
-                        recursion_stack,
-                        path,
-                    ) {
-                        return Some(cycle);
-                    }
-                } else if recursion_stack.contains(dep_id) {
-                    // Found a cycle
-                    let cycle_start = path.iter().position(|id| id == dep_id).unwrap();
-                    return Some(path[cycle_start..].to_vec());
+    ) -> CycleSearch {
+        // Current path, with the index of the next dependency to follow
+        let mut path: Vec<(TaskId, usize)> = vec![(*start, 0)];
+        let mut on_path: HashSet<TaskId> = HashSet::from([*start]);
+        visited.insert(*start);
+
+        while let Some((node, next)) = path.last_mut() {
+            let node = *node;
+            let deps = task_map.get(&node).map_or(&[][..], |t| t.depends_on.as_slice());
+            let Some(dep) = deps.get(*next) else {
+                path.pop();
+                on_path.remove(&node);
+                continue;
+            };
+            *next += 1;
+
+            if on_path.contains(dep) {
+                let cycle_start = path.iter().position(|(id, _)| id == dep).unwrap();
+                return CycleSearch::Found(path[cycle_start..].iter().map(|(id, _)| *id).collect());
+            }
+            if !visited.contains(dep) {
+                // Left unvisited, so a later search can still explore it
+                if path.len() >= self.max_cycle_depth {
+                    return CycleSearch::TooDeep(node);
                 }
+                visited.insert(*dep);
+                path.push((*dep, 0));
+                on_path.insert(*dep);
             }
         }
-
-        path.pop();
-        recursion_stack.remove(node);
-        None
+        CycleSearch::None
     }
 
     /// Generate resolution suggestions for blockers.
@@ -264,6 +333,17 @@ impl BlockerDetector {
         let mut suggestions = Vec::new();
 
         for blocker in blockers {
+            if blocker.category == BlockerCategory::External {
+                suggestions.push(ResolutionSuggestion {
+                    action: ResolutionAction::Wait,
+                    description: format!(
+                        "Waiting on external factor: {}. Resolve the blocker once it clears",
+                        blocker.reason
+                    ),
+                    priority: 3,
+                });
+                continue;
+            }
             if let BlockedItem::Task(task_id) = &blocker.blocked_item {
                 if let Some(task) = task_map.get(task_id) {
                     // Check what the dependencies are
@@ -326,7 +406,7 @@ impl BlockerDetector {
         let mut stats = BlockerStats::default();
         stats.total_blockers = blockers.len();
 
-        let now = chrono::Utc::now();
+        let now = self.clock.now();
         let mut total_age_hours = 0.0f32;
         let mut count = 0;
 
@@ -372,6 +452,7 @@ impl BlockerDetector {
                             blocked_item: BlockedItem::Task(task.id),
                             reason: "Task is blocked".to_string(),
                             severity: Severity::Error,
+                            category: BlockerCategory::Dependency,
                             created_at: task.updated_at,
                             resolved_at: None,
                         });
@@ -474,7 +555,18 @@ mod tests {
             progress: devman_core::TaskProgress::default(),
             phase_id: PhaseId::new(),
             blocks: Vec::new(),
+            parent: None,
+            children: Vec::new(),
+            archived: false,
+            priority: devman_core::Priority::default().value(),
             work_records: Vec::new(),
+            actuals: None,
+            claim: None,
+            state: None,
+            merged_into: None,
+            base_priority: None,
+            due_at: None,
+            created_knowledge: Vec::new(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
@@ -597,6 +689,51 @@ mod tests {
         assert!(blockers.is_empty());
     }
 
+    #[test]
+    fn test_detect_circular_dependencies_on_long_chain() {
+        // t0 -> t1 -> ... -> t49999, deep enough to overflow a recursive search
+        const LEN: usize = 50_000;
+        let ids: Vec<TaskId> = (0..LEN).map(|_| TaskId::new()).collect();
+        let mut tasks: Vec<Task> = ids
+            .iter()
+            .map(|id| create_test_task(*id, "Link", TaskStatus::Queued))
+            .collect();
+        for i in 0..LEN - 1 {
+            tasks[i].depends_on.push(ids[i + 1]);
+        }
+        let chain = |tasks: &[Task]| -> HashMap<TaskId, Task> { tasks.iter().map(|t| (t.id, t.clone())).collect() };
+
+        let detector = BlockerDetector::new(Arc::new(MockStorage {})).with_max_cycle_depth(LEN + 1);
+        let (cycles, blockers) = detector.detect_circular_dependencies(&chain(&tasks));
+        assert!(cycles.is_empty());
+        assert!(blockers.is_empty());
+
+        // Closing the chain makes every task part of one cycle
+        tasks[LEN - 1].depends_on.push(ids[0]);
+        let (cycles, blockers) = detector.detect_circular_dependencies(&chain(&tasks));
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].len(), LEN);
+        assert_eq!(cycles[0].iter().collect::<HashSet<_>>(), ids.iter().collect::<HashSet<_>>());
+        assert_eq!(blockers.len(), LEN);
+
+        // With the default limit the search stops and says so, but still
+        // finds a short cycle elsewhere in the graph
+        let (a, b) = (TaskId::new(), TaskId::new());
+        let mut first = create_test_task(a, "A", TaskStatus::Queued);
+        first.depends_on.push(b);
+        let mut second = create_test_task(b, "B", TaskStatus::Queued);
+        second.depends_on.push(a);
+        tasks.extend([first, second]);
+        let detector = BlockerDetector::new(Arc::new(MockStorage {}));
+        let (cycles, blockers) = detector.detect_circular_dependencies(&chain(&tasks));
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].iter().collect::<HashSet<_>>(), HashSet::from([&a, &b]));
+        let warnings: Vec<&Blocker> = blockers.iter().filter(|b| b.severity == Severity::Warning).collect();
+        assert!(!warnings.is_empty());
+        assert_eq!(warnings.len() + 2, blockers.len());
+        assert!(warnings[0].reason.contains(&DEFAULT_MAX_CYCLE_DEPTH.to_string()));
+    }
+
     #[test]
     fn test_generate_suggestions_empty() {
         let empty_map: HashMap<TaskId, Task> = HashMap::new();
@@ -614,4 +751,73 @@ mod tests {
         assert_eq!(stats.total_blockers, 0);
         assert_eq!(stats.circular_dependencies, 0);
     }
+
+    #[test]
+    fn test_calculate_stats_age_follows_clock() {
+        let start = Utc::now();
+        let clock = Arc::new(devman_core::MockClock::new(start));
+        let detector = BlockerDetector::new(Arc::new(MockStorage {})).with_clock(clock.clone());
+        let blocker = Blocker {
+            id: devman_core::BlockerId::new(),
+            blocked_item: BlockedItem::Task(TaskId::new()),
+            reason: "Waiting on review".to_string(),
+            severity: Severity::Warning,
+            category: BlockerCategory::External,
+            created_at: start,
+            resolved_at: None,
+        };
+
+        let stats = detector.calculate_stats(std::slice::from_ref(&blocker));
+        assert_eq!(stats.average_age_hours, Some(0.0));
+
+        clock.advance(chrono::Duration::hours(6));
+        let stats = detector.calculate_stats(&[blocker]);
+        assert_eq!(stats.average_age_hours, Some(6.0));
+    }
+
+    #[tokio::test]
+    async fn test_recorded_blockers_in_analysis_until_resolved() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let dependency = create_test_task(TaskId::new(), "Dependency", TaskStatus::Active);
+        let mut blocked = create_test_task(TaskId::new(), "Blocked", TaskStatus::Blocked);
+        blocked.depends_on.push(dependency.id);
+        storage.save_task(&dependency).await.unwrap();
+        storage.save_task(&blocked).await.unwrap();
+
+        let mut external = Blocker::external(
+            BlockedItem::Task(dependency.id),
+            "Waiting on vendor API keys",
+            Severity::Warning,
+        );
+        storage.save_blocker(&external).await.unwrap();
+
+        let detector = BlockerDetector::new(Arc::new(
+            devman_storage::JsonStorage::new(dir.path()).await.unwrap(),
+        ));
+        let analysis = detector.detect_and_analyze().await;
+        assert_eq!(analysis.blockers.len(), 2);
+        assert!(analysis.blockers.iter().any(|b| b.id == external.id));
+        assert!(analysis
+            .suggestions
+            .iter()
+            .any(|s| s.action == ResolutionAction::Wait && s.description.contains("vendor API keys")));
+
+        // A resolved blocker drops out, and a derived blocker resolved by hand
+        // is not derived again
+        external.resolved_at = Some(Utc::now());
+        storage.save_blocker(&external).await.unwrap();
+        let derived = analysis
+            .blockers
+            .iter()
+            .find(|b| b.category == BlockerCategory::Dependency)
+            .unwrap();
+        let mut dismissed = derived.clone();
+        dismissed.resolved_at = Some(Utc::now());
+        storage.save_blocker(&dismissed).await.unwrap();
+
+        let analysis = detector.detect_and_analyze().await;
+        assert!(analysis.blockers.is_empty());
+        assert_eq!(analysis.stats.total_blockers, 0);
+    }
 }
@@ -3,10 +3,16 @@
 //! Provides AI-friendly time estimation with minute-level precision:
 //! - Based on task complexity and execution steps
 //! - Progress-based refinement
+//! - Actuals of finished tasks, once recorded
 //! - Phase and goal aggregation
+//! - Token and USD cost before a task is started
 
 use chrono::{DateTime, Utc, Duration};
-use devman_core::{Goal, Phase, Task, TaskStatus};
+use devman_core::{Clock, Goal, Phase, SystemClock, Task, TaskActuals, TaskStatus};
+use devman_storage::config::keys;
+use devman_storage::{ConfigStore, Storage};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 /// AI-friendly completion estimation result.
 #[derive(Debug, Clone)]
@@ -21,6 +27,62 @@ pub struct TimeEstimation {
     pub factors: Vec<String>,
 }
 
+/// Token and USD cost estimate for executing a task.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostEstimation {
+    /// Estimated tokens to finish the task
+    pub tokens: usize,
+    /// Low end of the token band
+    pub tokens_low: usize,
+    /// High end of the token band
+    pub tokens_high: usize,
+    /// Estimated cost in USD
+    pub usd: f64,
+    /// Low end of the USD band
+    pub usd_low: f64,
+    /// High end of the USD band
+    pub usd_high: f64,
+    /// Model whose price was applied
+    pub model: String,
+    /// Confidence level (0.0 to 1.0)
+    pub confidence: f32,
+    /// Number of similar finished tasks the estimate is calibrated on
+    pub samples: usize,
+    /// Factors that influenced the estimation
+    pub factors: Vec<String>,
+}
+
+/// Blended token price used to turn token estimates into USD.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceTable {
+    /// Model the price applies to
+    pub model: String,
+    /// Blended input/output price per million tokens
+    pub usd_per_million_tokens: f64,
+}
+
+impl Default for PriceTable {
+    fn default() -> Self {
+        Self {
+            model: "default".to_string(),
+            usd_per_million_tokens: 6.0,
+        }
+    }
+}
+
+impl PriceTable {
+    /// Price table set in `storage` under `progress.price_table`, or the
+    /// default when none is set.
+    pub async fn from_storage<S: Storage + ?Sized>(storage: &S) -> Self {
+        storage.get_config_or(keys::PROGRESS_PRICE_TABLE, Self::default()).await
+    }
+
+    /// Cost of `tokens` tokens in USD.
+    pub fn usd(&self, tokens: usize) -> f64 {
+        tokens as f64 * self.usd_per_million_tokens / 1_000_000.0
+    }
+}
+
 /// Task complexity scoring for AI tasks.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum TaskComplexity {
@@ -48,6 +110,17 @@ impl TaskComplexity {
         }
     }
 
+    /// Get base token usage for this complexity level.
+    pub fn base_tokens(&self) -> usize {
+        match self {
+            TaskComplexity::Trivial => 5_000,
+            TaskComplexity::Simple => 20_000,
+            TaskComplexity::Moderate => 50_000,
+            TaskComplexity::Complex => 120_000,
+            TaskComplexity::VeryComplex => 300_000,
+        }
+    }
+
     /// Get confidence modifier for this complexity level.
     pub fn confidence_modifier(&self) -> f32 {
         match self {
@@ -61,10 +134,33 @@ impl TaskComplexity {
 }
 
 /// Completion time estimator for AI workflows.
-#[derive(Clone, Default)]
-pub struct CompletionEstimator;
+#[derive(Clone)]
+pub struct CompletionEstimator {
+    clock: Arc<dyn Clock>,
+    prices: PriceTable,
+}
+
+impl Default for CompletionEstimator {
+    fn default() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+}
 
 impl CompletionEstimator {
+    /// Create an estimator that reads the current time from `clock`.
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        Self { clock, prices: PriceTable::default() }
+    }
+
+    /// Use `prices` when converting token estimates into USD.
+    pub fn with_prices(mut self, prices: PriceTable) -> Self {
+        self.prices = prices;
+        self
+    }
+
+    /// Similar tasks with recorded tokens needed for a calibrated cost.
+    const MIN_COST_SAMPLES: usize = 3;
+
     /// Base duration per step in minutes (AI is fast at execution).
     const MINUTES_PER_STEP: i64 = 2;
 
@@ -74,7 +170,7 @@ impl CompletionEstimator {
 
         if active_tasks == 0 {
             return TimeEstimation {
-                estimated_completion: Utc::now(),
+                estimated_completion: self.clock.now(),
                 confidence: 1.0,
                 duration_minutes: 0,
                 factors: vec!["Goal completed".to_string()],
@@ -94,7 +190,7 @@ impl CompletionEstimator {
         ];
 
         TimeEstimation {
-            estimated_completion: Utc::now() + Duration::minutes(total_minutes),
+            estimated_completion: self.clock.now() + Duration::minutes(total_minutes),
             confidence,
             duration_minutes: total_minutes,
             factors,
@@ -107,7 +203,7 @@ impl CompletionEstimator {
 
         if remaining == 0 {
             return TimeEstimation {
-                estimated_completion: Utc::now(),
+                estimated_completion: self.clock.now(),
                 confidence: 1.0,
                 duration_minutes: 0,
                 factors: vec!["Phase completed".to_string()],
@@ -124,7 +220,7 @@ impl CompletionEstimator {
         ];
 
         TimeEstimation {
-            estimated_completion: Utc::now() + Duration::minutes(total_minutes),
+            estimated_completion: self.clock.now() + Duration::minutes(total_minutes),
             confidence: 0.75,
             duration_minutes: total_minutes,
             factors,
@@ -182,13 +278,138 @@ impl CompletionEstimator {
         ];
 
         TimeEstimation {
-            estimated_completion: Utc::now() + Duration::minutes(minutes),
+            estimated_completion: self.clock.now() + Duration::minutes(minutes),
             confidence,
             duration_minutes: minutes,
             factors,
         }
     }
 
+    /// Estimate a task from the recorded actuals of tasks of the same
+    /// complexity in `history`, falling back to [`Self::estimate_task`]
+    /// when none have actuals.
+    pub fn estimate_from_history(&self, task: &Task, history: &[Task]) -> TimeEstimation {
+        let complexity = self.calculate_task_complexity(task);
+        let samples: Vec<&TaskActuals> = history
+            .iter()
+            .filter(|t| t.id != task.id && self.calculate_task_complexity(t) == complexity)
+            .filter_map(|t| t.actuals.as_ref())
+            .collect();
+
+        if samples.is_empty() || matches!(task.status, TaskStatus::Done | TaskStatus::Abandoned) {
+            return self.estimate_task(task);
+        }
+
+        let total_secs: u64 = samples.iter().map(|a| a.duration.as_secs()).sum();
+        let minutes = ((total_secs / samples.len() as u64 + 30) / 60) as i64;
+
+        // Each comparable task makes the average more trustworthy
+        let confidence = (complexity.confidence_modifier() + samples.len() as f32 * 0.05).min(0.95);
+
+        let mut factors = vec![
+            format!("Complexity: {:?}", complexity),
+            format!("History: {} similar task(s)", samples.len()),
+        ];
+        let tokens: Vec<usize> = samples.iter().filter_map(|a| a.tokens).collect();
+        if !tokens.is_empty() {
+            factors.push(format!("Avg. tokens: {}", tokens.iter().sum::<usize>() / tokens.len()));
+        }
+
+        TimeEstimation {
+            estimated_completion: self.clock.now() + Duration::minutes(minutes),
+            confidence,
+            duration_minutes: minutes,
+            factors,
+        }
+    }
+
+    /// Estimate the tokens and USD needed to finish `task`.
+    ///
+    /// With at least [`Self::MIN_COST_SAMPLES`] finished tasks of the same
+    /// complexity that recorded tokens, the estimate is their mean with a
+    /// one standard deviation band. Otherwise the complexity heuristic is
+    /// used with a wide band and lower confidence.
+    pub fn estimate_cost(&self, task: &Task, history: &[Task]) -> CostEstimation {
+        if matches!(task.status, TaskStatus::Done | TaskStatus::Abandoned) {
+            return self.cost(0, 0, 0, 1.0, 0, vec!["Task completed".to_string()]);
+        }
+
+        let complexity = self.calculate_task_complexity(task);
+        let samples: Vec<f64> = history
+            .iter()
+            .filter(|t| t.id != task.id && self.calculate_task_complexity(t) == complexity)
+            .filter_map(|t| t.actuals.as_ref().and_then(|a| a.tokens))
+            .map(|tokens| tokens as f64)
+            .collect();
+
+        // Only the unfinished share of the task still costs tokens
+        let remaining = (1.0 - task.progress.percentage as f64 / 100.0).clamp(0.0, 1.0);
+        let mut factors = vec![
+            format!("Complexity: {:?}", complexity),
+            format!("Progress: {:.0}%", task.progress.percentage),
+        ];
+
+        if samples.len() >= Self::MIN_COST_SAMPLES {
+            let n = samples.len() as f64;
+            let mean = samples.iter().sum::<f64>() / n;
+            let std_dev = (samples.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / n).sqrt();
+            // A tight spread across many samples earns more trust
+            let spread = if mean > 0.0 { std_dev / mean } else { 1.0 };
+            let confidence = ((1.0 - spread.min(1.0)) as f32 * 0.5 + 0.4 + samples.len() as f32 * 0.02)
+                .min(0.95);
+            factors.push(format!("History: {} similar task(s)", samples.len()));
+
+            return self.cost(
+                (mean * remaining).round() as usize,
+                ((mean - std_dev).max(0.0) * remaining).round() as usize,
+                ((mean + std_dev) * remaining).round() as usize,
+                confidence,
+                samples.len(),
+                factors,
+            );
+        }
+
+        let mut tokens = complexity.base_tokens() as f64;
+        let step_tokens = task.steps.len() as f64 * 4_000.0;
+        tokens = tokens.max(step_tokens) * remaining;
+        factors.push(format!(
+            "Sparse history: {} similar task(s), using complexity heuristic",
+            samples.len()
+        ));
+
+        self.cost(
+            tokens.round() as usize,
+            (tokens / 2.0).round() as usize,
+            (tokens * 2.0).round() as usize,
+            complexity.confidence_modifier() * 0.5,
+            samples.len(),
+            factors,
+        )
+    }
+
+    fn cost(
+        &self,
+        tokens: usize,
+        tokens_low: usize,
+        tokens_high: usize,
+        confidence: f32,
+        samples: usize,
+        factors: Vec<String>,
+    ) -> CostEstimation {
+        CostEstimation {
+            tokens,
+            tokens_low,
+            tokens_high,
+            usd: self.prices.usd(tokens),
+            usd_low: self.prices.usd(tokens_low),
+            usd_high: self.prices.usd(tokens_high),
+            model: self.prices.model.clone(),
+            confidence,
+            samples,
+            factors,
+        }
+    }
+
     /// Estimate task complexity based on task characteristics.
     fn calculate_task_complexity(&self, task: &Task) -> TaskComplexity {
         // Base complexity on step count
@@ -288,7 +509,18 @@ mod tests {
             phase_id: devman_core::PhaseId::new(),
             depends_on: dep_vec,
             blocks: vec![],
+            parent: None,
+            children: vec![],
+            archived: false,
+            priority: devman_core::Priority::default().value(),
             work_records: vec![],
+            actuals: None,
+            claim: None,
+            state: None,
+            merged_into: None,
+            base_priority: None,
+            due_at: None,
+            created_knowledge: Vec::new(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
@@ -403,6 +635,7 @@ mod tests {
             },
             current_phase: devman_core::PhaseId::new(),
             status: devman_core::GoalStatus::Active,
+            status_history: Vec::new(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -442,6 +675,7 @@ mod tests {
             },
             estimated_duration: None,
             actual_duration: None,
+            quality_profile: None,
             created_at: Utc::now(),
         };
         let result = estimator.estimate_phase(&phase);
@@ -487,6 +721,7 @@ mod tests {
             },
             estimated_duration: None,
             actual_duration: None,
+            quality_profile: None,
             created_at: Utc::now(),
         };
         let result = estimator.estimate_phase(&phase);
@@ -494,4 +729,125 @@ mod tests {
         assert!(result.duration_minutes > 0);
         assert!(result.estimated_completion > Utc::now());
     }
+
+    #[test]
+    fn test_estimate_from_history_averages_similar_actuals() {
+        let estimator = CompletionEstimator::default();
+        let task = create_test_task_with_steps(devman_core::TaskId::new(), "new", 1, 0);
+
+        // Without actuals the heuristic estimate is used
+        let history = vec![create_test_task_with_steps(devman_core::TaskId::new(), "old", 1, 0)];
+        let fallback = estimator.estimate_from_history(&task, &history);
+        assert_eq!(fallback.duration_minutes, estimator.estimate_task(&task).duration_minutes);
+
+        let finished = |minutes: u64, tokens: Option<usize>, steps: usize| {
+            let mut t = create_test_task_with_steps(devman_core::TaskId::new(), "done", steps, 0);
+            t.status = TaskStatus::Done;
+            t.actuals = Some(TaskActuals {
+                duration: std::time::Duration::from_secs(minutes * 60),
+                tokens,
+                source: devman_core::ActualsSource::Metrics,
+                recorded_at: Utc::now(),
+            });
+            t
+        };
+        // The 25-step task is not comparable and is ignored
+        let history = vec![finished(30, Some(1000), 1), finished(60, None, 2), finished(600, None, 25)];
+        let result = estimator.estimate_from_history(&task, &history);
+        assert_eq!(result.duration_minutes, 45);
+        assert!(result.factors.contains(&"History: 2 similar task(s)".to_string()));
+        assert!(result.factors.contains(&"Avg. tokens: 1000".to_string()));
+        assert_eq!(result.confidence, 0.95);
+    }
+
+    fn finished_with_tokens(steps: usize, tokens: usize) -> Task {
+        let mut t = create_test_task_with_steps(devman_core::TaskId::new(), "done", steps, 0);
+        t.status = TaskStatus::Done;
+        t.actuals = Some(TaskActuals {
+            duration: std::time::Duration::from_secs(600),
+            tokens: Some(tokens),
+            source: devman_core::ActualsSource::Metrics,
+            recorded_at: Utc::now(),
+        });
+        t
+    }
+
+    #[tokio::test]
+    async fn test_price_table_read_from_stored_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        assert_eq!(PriceTable::from_storage(&storage).await, PriceTable::default());
+
+        let prices = PriceTable { model: "big-model".to_string(), usd_per_million_tokens: 15.0 };
+        storage.set_config(keys::PROGRESS_PRICE_TABLE, &prices).await.unwrap();
+        let reloaded = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        assert_eq!(PriceTable::from_storage(&reloaded).await, prices);
+    }
+
+    #[test]
+    fn test_estimate_cost_calibrated_from_history() {
+        let estimator = CompletionEstimator::default().with_prices(PriceTable {
+            model: "test-model".to_string(),
+            usd_per_million_tokens: 10.0,
+        });
+        let task = create_test_task_with_steps(devman_core::TaskId::new(), "new", 1, 0);
+        let history = vec![
+            finished_with_tokens(1, 8_000),
+            finished_with_tokens(2, 10_000),
+            finished_with_tokens(1, 12_000),
+            // Not comparable, ignored
+            finished_with_tokens(25, 900_000),
+        ];
+
+        let cost = estimator.estimate_cost(&task, &history);
+        assert_eq!(cost.samples, 3);
+        assert_eq!(cost.tokens, 10_000);
+        assert!(cost.tokens_low < cost.tokens && cost.tokens < cost.tokens_high);
+        assert!((cost.usd - 0.1).abs() < 1e-9);
+        assert_eq!(cost.model, "test-model");
+        assert!(cost.confidence > 0.75);
+        assert!(cost.factors.contains(&"History: 3 similar task(s)".to_string()));
+
+        // Half the work is done, so half the tokens remain
+        let mut halfway = task.clone();
+        halfway.progress.percentage = 50.0;
+        assert_eq!(estimator.estimate_cost(&halfway, &history).tokens, 5_000);
+    }
+
+    #[test]
+    fn test_estimate_cost_falls_back_when_history_sparse() {
+        let estimator = CompletionEstimator::default();
+        let task = create_test_task_with_steps(devman_core::TaskId::new(), "new", 1, 0);
+        let history = vec![finished_with_tokens(1, 8_000)];
+
+        let cost = estimator.estimate_cost(&task, &history);
+        assert_eq!(cost.samples, 1);
+        assert_eq!(cost.tokens, TaskComplexity::Trivial.base_tokens());
+        assert_eq!(cost.tokens_low, cost.tokens / 2);
+        assert_eq!(cost.tokens_high, cost.tokens * 2);
+        assert!(cost.confidence < 0.5);
+        assert!(cost.usd > 0.0);
+
+        let mut done = task.clone();
+        done.status = TaskStatus::Done;
+        assert_eq!(estimator.estimate_cost(&done, &history).tokens, 0);
+    }
+
+    #[test]
+    fn test_estimate_task_uses_clock() {
+        let start = Utc::now();
+        let clock = Arc::new(devman_core::MockClock::new(start));
+        let estimator = CompletionEstimator::with_clock(clock.clone());
+        let task = create_test_task_with_steps(devman_core::TaskId::new(), "clocked", 3, 0);
+
+        let result = estimator.estimate_task(&task);
+        assert_eq!(
+            result.estimated_completion,
+            start + Duration::minutes(result.duration_minutes)
+        );
+
+        clock.advance(Duration::days(1));
+        let later = estimator.estimate_task(&task);
+        assert_eq!(later.estimated_completion, result.estimated_completion + Duration::days(1));
+    }
 }
@@ -1,15 +1,18 @@
 //! Progress Tracking (Layer 3)
 //!
-//! Goal progress, phase milestones, and blocker detection.
+//! Goal progress, phase milestones, blocker detection and metrics export.
 
 #![warn(missing_docs)]
 
 pub mod tracker;
 pub mod blocker;
 pub mod estimator;
+pub mod metrics;
 
 pub use tracker::{ProgressTracker, ProgressSnapshot, BasicProgressTracker};
 pub use blocker::{
     BlockerDetector, BlockerAnalysis, BlockerStats, ResolutionSuggestion, ResolutionAction,
+    DEFAULT_MAX_CYCLE_DEPTH,
 };
-pub use estimator::{CompletionEstimator, TimeEstimation, TaskComplexity};
+pub use estimator::{CompletionEstimator, CostEstimation, PriceTable, TimeEstimation, TaskComplexity};
+pub use metrics::prometheus_text;
@@ -0,0 +1,223 @@
+//! Metrics export.
+//!
+//! Renders counts taken from the store (tasks by status, blockers, quality
+//! check results and task cycle time) in the Prometheus text exposition
+//! format, so a monitoring system can scrape them.
+
+use devman_core::{Task, TaskState, TaskStatus};
+use devman_storage::{Result, Storage};
+
+/// Task statuses with their metric label, in lifecycle order.
+const STATUSES: [(TaskStatus, &str); 7] = [
+    (TaskStatus::Idea, "idea"),
+    (TaskStatus::Queued, "queued"),
+    (TaskStatus::Active, "active"),
+    (TaskStatus::Blocked, "blocked"),
+    (TaskStatus::Review, "review"),
+    (TaskStatus::Done, "done"),
+    (TaskStatus::Abandoned, "abandoned"),
+];
+
+/// Current metrics of the store in the Prometheus text format.
+///
+/// Every status gets a sample, zero included, so series do not disappear
+/// between scrapes. Archived tasks are left out of the task gauges but their
+/// quality checks still count, as the check counters never go down.
+pub async fn prometheus_text<S: Storage + ?Sized>(storage: &S) -> Result<String> {
+    let tasks = storage.list_tasks(&Default::default()).await?;
+    let blockers = storage.list_blockers().await?;
+
+    let (mut passed, mut failed) = (0usize, 0usize);
+    for task in &tasks {
+        for run in storage.list_quality_runs(task.id).await? {
+            let run_passed = run.results.iter().filter(|r| r.passed).count();
+            passed += run_passed;
+            failed += run.results.len() - run_passed;
+        }
+    }
+
+    let live: Vec<&Task> = tasks.iter().filter(|t| !t.archived).collect();
+    let cycle_times: Vec<f64> = live
+        .iter()
+        .filter(|t| t.status == TaskStatus::Done)
+        .map(|t| cycle_time_secs(t))
+        .collect();
+
+    let mut out = Exposition::default();
+    out.family("devman_tasks", "gauge", "Tasks by status, archived tasks excluded");
+    for (status, label) in STATUSES {
+        let count = live.iter().filter(|t| t.status == status).count();
+        out.sample("devman_tasks", &[("status", label)], count as f64);
+    }
+
+    out.family("devman_blockers", "gauge", "Recorded blockers by state");
+    let open = blockers.iter().filter(|b| !b.is_resolved()).count();
+    out.sample("devman_blockers", &[("state", "open")], open as f64);
+    out.sample("devman_blockers", &[("state", "resolved")], (blockers.len() - open) as f64);
+
+    out.family("devman_quality_checks_total", "counter", "Quality check results recorded");
+    out.sample("devman_quality_checks_total", &[("result", "passed")], passed as f64);
+    out.sample("devman_quality_checks_total", &[("result", "failed")], failed as f64);
+
+    // Like a quality run without checks, no checks at all counts as passing
+    let total = passed + failed;
+    let pass_rate = if total == 0 { 1.0 } else { passed as f64 / total as f64 };
+    out.family("devman_quality_pass_rate", "gauge", "Share of recorded quality checks that passed");
+    out.sample("devman_quality_pass_rate", &[], pass_rate);
+
+    let average = if cycle_times.is_empty() {
+        0.0
+    } else {
+        cycle_times.iter().sum::<f64>() / cycle_times.len() as f64
+    };
+    out.family("devman_task_cycle_time_seconds", "gauge", "Average time from creation to completion of done tasks");
+    out.sample("devman_task_cycle_time_seconds", &[], average);
+
+    Ok(out.0)
+}
+
+/// Seconds from a done task's creation to its completion, taken from its
+/// state when it records one and from its last update otherwise.
+fn cycle_time_secs(task: &Task) -> f64 {
+    let completed_at = match &task.state {
+        Some(TaskState::Completed { completed_at, .. }) => *completed_at,
+        _ => task.updated_at,
+    };
+    (completed_at - task.created_at).num_milliseconds().max(0) as f64 / 1000.0
+}
+
+/// Text being built in the exposition format.
+#[derive(Default)]
+struct Exposition(String);
+
+impl Exposition {
+    /// Start a metric family with its help text and type.
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        self.0.push_str(&format!("# HELP {} {}\n# TYPE {} {}\n", name, help, name, kind));
+    }
+
+    /// Add a sample of the current family.
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        let labels = if labels.is_empty() {
+            String::new()
+        } else {
+            let pairs: Vec<String> = labels.iter().map(|(k, v)| format!("{}=\"{}\"", k, v)).collect();
+            format!("{{{}}}", pairs.join(","))
+        };
+        self.0.push_str(&format!("{}{} {}\n", name, labels, value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use devman_core::{
+        BlockedItem, Blocker, CheckDetails, QualityCheckId, QualityCheckResult, QualityRun, Severity, TaskId,
+    };
+
+    fn task(status: TaskStatus, age: chrono::Duration) -> Task {
+        let now = chrono::Utc::now();
+        Task {
+            id: TaskId::new(),
+            title: format!("{:?}", status),
+            description: String::new(),
+            intent: devman_core::TaskIntent {
+                natural_language: String::new(),
+                context: devman_core::TaskContext {
+                    relevant_knowledge: Vec::new(),
+                    similar_tasks: Vec::new(),
+                    affected_files: Vec::new(),
+                },
+                success_criteria: Vec::new(),
+            },
+            steps: Vec::new(),
+            inputs: Vec::new(),
+            expected_outputs: Vec::new(),
+            quality_gates: Vec::new(),
+            status,
+            progress: devman_core::TaskProgress::default(),
+            phase_id: devman_core::PhaseId::new(),
+            depends_on: Vec::new(),
+            blocks: Vec::new(),
+            parent: None,
+            children: Vec::new(),
+            archived: false,
+            priority: devman_core::Priority::default().value(),
+            work_records: Vec::new(),
+            actuals: None,
+            claim: None,
+            state: (status == TaskStatus::Done)
+                .then(|| TaskState::Completed { completed_at: now, completed_by: "test".to_string() }),
+            merged_into: None,
+            base_priority: None,
+            due_at: None,
+            created_knowledge: Vec::new(),
+            created_at: now - age,
+            updated_at: now,
+        }
+    }
+
+    fn check(passed: bool) -> QualityCheckResult {
+        QualityCheckResult {
+            check_id: QualityCheckId::new(),
+            passed,
+            execution_time: std::time::Duration::ZERO,
+            details: CheckDetails { output: String::new(), exit_code: Some(0), error: None, timeout: None },
+            findings: Vec::new(),
+            metrics: Vec::new(),
+            human_review: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prometheus_text_reports_current_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+
+        let done = task(TaskStatus::Done, chrono::Duration::hours(2));
+        storage.save_task(&done).await.unwrap();
+        storage.save_task(&task(TaskStatus::Done, chrono::Duration::hours(4))).await.unwrap();
+        storage.save_task(&task(TaskStatus::Queued, chrono::Duration::zero())).await.unwrap();
+        let mut archived = task(TaskStatus::Queued, chrono::Duration::zero());
+        archived.archived = true;
+        storage.save_task(&archived).await.unwrap();
+
+        let run = QualityRun::new(done.id, vec![check(true), check(true), check(true), check(false)], chrono::Utc::now());
+        storage.save_quality_run(&run).await.unwrap();
+        let blocker = Blocker::external(BlockedItem::Task(done.id), "waiting on vendor", Severity::Warning);
+        storage.save_blocker(&blocker).await.unwrap();
+
+        let text = prometheus_text(&storage).await.unwrap();
+
+        for line in text.lines().filter(|l| l.starts_with("# TYPE")) {
+            let parts: Vec<&str> = line.split(' ').collect();
+            assert_eq!(parts.len(), 4, "malformed TYPE line: {}", line);
+            assert!(parts[2].starts_with("devman_"));
+            assert!(matches!(parts[3], "gauge" | "counter"));
+        }
+        for name in [
+            "devman_tasks",
+            "devman_blockers",
+            "devman_quality_checks_total",
+            "devman_quality_pass_rate",
+            "devman_task_cycle_time_seconds",
+        ] {
+            assert!(text.contains(&format!("# TYPE {} ", name)), "missing {}", name);
+        }
+
+        let samples: Vec<&str> = text.lines().filter(|l| !l.starts_with('#')).collect();
+        for expected in [
+            "devman_tasks{status=\"done\"} 2",
+            "devman_tasks{status=\"queued\"} 1",
+            "devman_tasks{status=\"active\"} 0",
+            "devman_blockers{state=\"open\"} 1",
+            "devman_blockers{state=\"resolved\"} 0",
+            "devman_quality_checks_total{result=\"passed\"} 3",
+            "devman_quality_checks_total{result=\"failed\"} 1",
+            "devman_quality_pass_rate 0.75",
+            "devman_task_cycle_time_seconds 10800",
+        ] {
+            assert!(samples.contains(&expected), "missing sample {}:\n{}", expected, text);
+        }
+    }
+}
@@ -0,0 +1,168 @@
+//! Content-addressed cache of quality check results.
+//!
+//! A check whose command, arguments and relevant source files are the same
+//! as in an earlier run would produce the same result, so the engine can
+//! return the stored one instead of running `cargo check` again. Entries
+//! are JSON files named by the hash of those inputs and expire after a TTL.
+
+use devman_core::{QualityCheckResult, QualityCheckType, Time};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Cache directory inside the storage directory.
+pub const DEFAULT_CACHE_DIR: &str = "cache/checks";
+
+/// How long a cached result is used by default.
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Stored result with the time it was stored.
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    stored_at: Time,
+    result: QualityCheckResult,
+}
+
+/// Directory of cached check results.
+#[derive(Debug, Clone)]
+pub struct CheckCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl CheckCache {
+    /// Cache results in `dir` for [`DEFAULT_CACHE_TTL`].
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into(), ttl: DEFAULT_CACHE_TTL }
+    }
+
+    /// Use cached results for at most `ttl`.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Directory the cache is kept in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+
+    /// Result stored under `key`, unless it is missing, unreadable or
+    /// older than the TTL.
+    pub async fn get(&self, key: &str) -> Option<QualityCheckResult> {
+        let content = tokio::fs::read_to_string(self.entry_path(key)).await.ok()?;
+        let entry: CacheEntry = serde_json::from_str(&content).ok()?;
+        let age = (chrono::Utc::now() - entry.stored_at).to_std().unwrap_or_default();
+        (age <= self.ttl).then_some(entry.result)
+    }
+
+    /// Store `result` under `key`, replacing any earlier entry.
+    pub async fn put(&self, key: &str, result: &QualityCheckResult) -> std::io::Result<()> {
+        let entry = CacheEntry { stored_at: chrono::Utc::now(), result: result.clone() };
+        tokio::fs::create_dir_all(&self.dir).await?;
+        tokio::fs::write(self.entry_path(key), serde_json::to_vec(&entry)?).await
+    }
+}
+
+/// Hash of everything a check's result depends on.
+pub struct CacheKey(Sha256);
+
+impl CacheKey {
+    /// Start a key for running `tool` with `args` in `dir` for a check of
+    /// `check_type`.
+    pub fn new(check_type: &QualityCheckType, tool: &str, args: &[String], dir: &Path) -> Self {
+        let mut key = Self(Sha256::new());
+        key.add("type", serde_json::to_string(check_type).unwrap_or_default().as_bytes());
+        key.add("tool", tool.as_bytes());
+        for arg in args {
+            key.add("arg", arg.as_bytes());
+        }
+        key.add("dir", dir.to_string_lossy().as_bytes());
+        key
+    }
+
+    /// Mix a labelled value into the key. Values are length-prefixed so
+    /// adjacent values cannot run into each other.
+    pub fn add(&mut self, label: &str, value: &[u8]) {
+        self.0.update(label.as_bytes());
+        self.0.update((value.len() as u64).to_le_bytes());
+        self.0.update(value);
+    }
+
+    /// Mix in the path and contents of `path`; a missing file counts as
+    /// empty.
+    pub async fn add_file(&mut self, path: &Path) {
+        self.add("file", path.to_string_lossy().as_bytes());
+        match tokio::fs::read(path).await {
+            Ok(contents) => self.add("contents", &contents),
+            Err(_) => self.add("missing", &[]),
+        }
+    }
+
+    /// Hex digest naming the cache entry.
+    pub fn finish(self) -> String {
+        self.0.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use devman_core::{CheckDetails, GenericCheckType, QualityCheckId};
+
+    fn result() -> QualityCheckResult {
+        QualityCheckResult {
+            check_id: QualityCheckId::new(),
+            passed: true,
+            execution_time: Duration::from_millis(5),
+            details: CheckDetails { output: "ok".to_string(), exit_code: Some(0), error: None, timeout: None },
+            findings: vec![],
+            metrics: vec![],
+            human_review: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_expired_entries_are_misses() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = CheckCache::new(dir.path());
+        cache.put("abc", &result()).await.unwrap();
+        assert_eq!(cache.get("abc").await.unwrap().details.output, "ok");
+        assert!(cache.get("other").await.is_none());
+
+        let expired = CheckCache::new(dir.path()).with_ttl(Duration::ZERO);
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert!(expired.get("abc").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_key_changes_with_args_and_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("lib.rs");
+        std::fs::write(&file, "fn a() {}").unwrap();
+        let check_type = QualityCheckType::Generic(GenericCheckType::TypeCheck {});
+        let key = |args: &[&str]| {
+            let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+            let file = file.clone();
+            let check_type = check_type.clone();
+            let dir = dir.path().to_path_buf();
+            async move {
+                let mut key = CacheKey::new(&check_type, "cargo", &args, &dir);
+                key.add_file(&file).await;
+                key.finish()
+            }
+        };
+
+        let first = key(&["check"]).await;
+        assert_eq!(first, key(&["check"]).await);
+        assert_ne!(first, key(&["check", "-p", "core"]).await);
+
+        std::fs::write(&file, "fn b() {}").unwrap();
+        assert_ne!(first, key(&["check"]).await);
+    }
+}
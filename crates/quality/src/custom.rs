@@ -69,6 +69,8 @@ pub struct CustomCheckBuilder {
     command: String,
     args: Vec<String>,
     timeout: std::time::Duration,
+    working_dir: Option<std::path::PathBuf>,
+    blocking: bool,
     expected_exit_code: Option<i32>,
     output_parser: OutputParser,
     pass_condition: String,
@@ -87,6 +89,8 @@ impl CustomCheckBuilder {
             command: String::new(),
             args: Vec::new(),
             timeout: std::time::Duration::from_secs(60),
+            working_dir: None,
+            blocking: true,
             expected_exit_code: Some(0),
             output_parser: OutputParser::LineContains {
                 text: String::new(),
@@ -133,6 +137,18 @@ impl CustomCheckBuilder {
         self
     }
 
+    /// Set working directory, relative to the project root.
+    pub fn working_dir(mut self, dir: impl Into<std::path::PathBuf>) -> Self {
+        self.working_dir = Some(dir.into());
+        self
+    }
+
+    /// Make the check advisory: its failures warn instead of failing the gate.
+    pub fn advisory(mut self) -> Self {
+        self.blocking = false;
+        self
+    }
+
     /// Build the check.
     pub fn build(self) -> QualityCheck {
         use devman_core::{CustomCheckSpec, CommandSpec, ValidationSpec};
@@ -159,6 +175,10 @@ impl CustomCheckBuilder {
             }),
             severity: self.severity,
             category: self.category,
+            timeout: None,
+            scope: devman_core::CheckScope::Full,
+            working_dir: self.working_dir,
+            blocking: self.blocking,
         }
     }
 
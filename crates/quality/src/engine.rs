@@ -2,13 +2,17 @@
 
 use async_trait::async_trait;
 use devman_core::{
-    QualityCheck, QualityCheckResult, QualityGate, TaskId,
-    QualityCategory, Finding, CheckDetails, Severity, Metric,
+    QualityCheck, QualityCheckResult, QualityGate, GateCondition, TaskId,
+    QualityCategory, Finding, CheckDetails, Severity, Metric, GoalId,
 };
-use devman_storage::Storage;
+use devman_storage::config::keys;
+use devman_storage::{ConfigStore, Storage, StorageError};
+use devman_tools::{CancellationToken, ToolError};
 use std::sync::Arc;
 
-use crate::parser::{parse_output, evaluate_pass_condition, extract_metrics};
+use crate::cache::{CacheKey, CheckCache};
+use crate::summary::GoalQualitySummary;
+use crate::parser::{parse_output, evaluate_pass_condition, extract_metrics, parse_test_failures, parse_test_summary};
 
 /// Context for running quality checks.
 #[derive(Debug, Clone)]
@@ -21,6 +25,14 @@ pub struct WorkContext {
 
     /// Additional context data
     pub metadata: serde_json::Value,
+
+    /// Files the task touches, relative to `work_dir`; when set, cached
+    /// results are keyed by these instead of the whole git working tree
+    pub affected_files: Vec<String>,
+
+    /// Run checks even when a cached result exists; fresh results are
+    /// still cached
+    pub no_cache: bool,
 }
 
 impl WorkContext {
@@ -30,6 +42,8 @@ impl WorkContext {
             task_id,
             work_dir: std::env::current_dir().unwrap_or_default(),
             metadata: serde_json::Value::Null,
+            affected_files: Vec::new(),
+            no_cache: false,
         }
     }
 }
@@ -52,11 +66,21 @@ pub trait QualityEngine: Send + Sync {
     ) -> Vec<QualityCheckResult>;
 
     /// Run a quality gate.
+    ///
+    /// If `cancel` fires, the running check's tool is stopped, no further
+    /// checks run and the decision is [`GateDecision::Cancelled`].
     async fn run_gate(
         &self,
         gate: &QualityGate,
         context: &WorkContext,
+        cancel: &CancellationToken,
     ) -> GateResult;
+
+    /// Aggregate the persisted quality runs of every task under a goal.
+    async fn goal_quality_summary(
+        &self,
+        goal_id: GoalId,
+    ) -> Result<GoalQualitySummary, StorageError>;
 }
 
 /// Result of running a quality gate.
@@ -86,12 +110,36 @@ pub enum GateDecision {
     PassWithWarnings,
     /// Needs human review
     Escalate,
+    /// The run was cancelled before the gate could be decided
+    Cancelled,
+}
+
+/// Overall status of a task's gate results: failed when a gate failed or
+/// was cancelled, pending review when one escalated, passed with warnings
+/// when a gate only failed advisory checks or warns on failure.
+pub fn overall_status(results: &[GateResult]) -> devman_core::TaskQualityOverallStatus {
+    use devman_core::TaskQualityOverallStatus as Status;
+
+    let any = |decision: GateDecision| results.iter().any(|r| r.decision == decision);
+    if results.is_empty() {
+        Status::NotChecked
+    } else if any(GateDecision::Fail) || any(GateDecision::Cancelled) {
+        Status::Failed
+    } else if any(GateDecision::Escalate) {
+        Status::PendingReview
+    } else if any(GateDecision::PassWithWarnings) {
+        Status::PassedWithWarnings
+    } else {
+        Status::Passed
+    }
 }
 
 /// Basic quality engine implementation.
 pub struct BasicQualityEngine<S: Storage> {
     storage: Arc<S>,
     tool_executor: Arc<dyn devman_tools::ToolExecutor>,
+    max_output_len: usize,
+    cache: Option<CheckCache>,
 }
 
 impl<S: Storage> BasicQualityEngine<S> {
@@ -100,8 +148,24 @@ impl<S: Storage> BasicQualityEngine<S> {
         Self {
             storage: Arc::new(storage),
             tool_executor,
+            max_output_len: devman_core::DEFAULT_MAX_OUTPUT_LEN,
+            cache: None,
         }
     }
+
+    /// Reuse results of generic checks from `cache` while their command
+    /// and the relevant source files are unchanged.
+    pub fn with_cache(mut self, cache: CheckCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Cap the output and error text kept in each check result at
+    /// `max_len` bytes. Parsing still sees the full output.
+    pub fn with_max_output_len(mut self, max_len: usize) -> Self {
+        self.max_output_len = max_len;
+        self
+    }
 }
 
 #[async_trait]
@@ -111,16 +175,7 @@ impl<S: Storage + 'static> QualityEngine for BasicQualityEngine<S> {
         check: &QualityCheck,
         context: &WorkContext,
     ) -> QualityCheckResult {
-        tracing::debug!("Running quality check: {}", check.name);
-
-        match &check.check_type {
-            devman_core::QualityCheckType::Generic(generic) => {
-                self.run_generic_check(generic, context).await
-            }
-            devman_core::QualityCheckType::Custom(custom) => {
-                self.run_custom_check(custom, check, context).await
-            }
-        }
+        self.run_check_cancellable(check, context, &CancellationToken::new()).await
     }
 
     async fn run_checks(
@@ -139,17 +194,29 @@ impl<S: Storage + 'static> QualityEngine for BasicQualityEngine<S> {
         &self,
         gate: &QualityGate,
         context: &WorkContext,
+        cancel: &CancellationToken,
     ) -> GateResult {
         let mut check_results = Vec::new();
+        let mut advisory = Vec::new();
 
         for check_id in &gate.checks {
+            if cancel.is_cancelled() {
+                break;
+            }
             if let Ok(Some(check)) = self.storage.load_quality_check(*check_id).await {
-                let result = self.run_check(&check, context).await;
+                if !check.blocking {
+                    advisory.push(check.id);
+                }
+                let result = self.run_check_cancellable(&check, context, cancel).await;
                 check_results.push(result);
             }
         }
 
-        let decision = self.evaluate_gate(&gate, &check_results);
+        let decision = if cancel.is_cancelled() {
+            GateDecision::Cancelled
+        } else {
+            self.evaluate_gate(gate, &check_results, &advisory, context)
+        };
 
         GateResult {
             gate_name: gate.name.clone(),
@@ -158,13 +225,44 @@ impl<S: Storage + 'static> QualityEngine for BasicQualityEngine<S> {
             decision,
         }
     }
+
+    async fn goal_quality_summary(
+        &self,
+        goal_id: GoalId,
+    ) -> Result<GoalQualitySummary, StorageError> {
+        crate::summary::goal_quality_summary(&*self.storage, goal_id).await
+    }
 }
 
 impl<S: Storage> BasicQualityEngine<S> {
+    async fn run_check_cancellable(
+        &self,
+        check: &QualityCheck,
+        context: &WorkContext,
+        cancel: &CancellationToken,
+    ) -> QualityCheckResult {
+        tracing::debug!("Running quality check: {}", check.name);
+
+        let mut result = match &check.check_type {
+            devman_core::QualityCheckType::Generic(generic) => {
+                let mut result = self.run_generic_check(generic, check, context, cancel).await;
+                result.check_id = check.id;
+                result
+            }
+            devman_core::QualityCheckType::Custom(custom) => {
+                self.run_custom_check(custom, check, context, cancel).await
+            }
+        };
+        result.details = result.details.truncated(self.max_output_len);
+        result
+    }
+
     async fn run_generic_check(
         &self,
         generic: &devman_core::GenericCheckType,
+        check: &QualityCheck,
         context: &WorkContext,
+        cancel: &CancellationToken,
     ) -> QualityCheckResult {
         use devman_tools::ToolInput;
         use std::time::Instant;
@@ -172,9 +270,9 @@ impl<S: Storage> BasicQualityEngine<S> {
 
         let start = Instant::now();
 
-        let (tool, args, work_dir) = match generic {
+        let (tool, args) = match generic {
             devman_core::GenericCheckType::Compiles { target } => {
-                ("cargo".to_string(), vec!["check".to_string(), "--target".to_string(), target.clone()], None::<()>)
+                ("cargo".to_string(), vec!["check".to_string(), "--target".to_string(), target.clone()])
             }
             devman_core::GenericCheckType::TestsPass { test_suite, min_coverage } => {
                 let mut args = vec!["test".to_string()];
@@ -187,13 +285,13 @@ impl<S: Storage> BasicQualityEngine<S> {
                 } else {
                     "cargo".to_string()
                 };
-                (tool, args, None)
+                (tool, args)
             }
             devman_core::GenericCheckType::Formatted { formatter } => {
-                (formatter.clone(), vec!["--check".to_string()], None)
+                (formatter.clone(), vec!["--check".to_string()])
             }
             devman_core::GenericCheckType::LintsPass { linter } => {
-                (linter.clone(), vec![], None)
+                (linter.clone(), vec![])
             }
             devman_core::GenericCheckType::DocumentationExists { paths } => {
                 // Check if documentation files exist
@@ -201,28 +299,88 @@ impl<S: Storage> BasicQualityEngine<S> {
             }
             devman_core::GenericCheckType::TypeCheck {} => {
                 // Run cargo check for type checking
-                ("cargo".to_string(), vec!["check".to_string()], None)
+                ("cargo".to_string(), vec!["check".to_string()])
             }
             devman_core::GenericCheckType::DependenciesValid {} => {
                 // Check for outdated or insecure dependencies
-                ("cargo".to_string(), vec!["update".to_string(), "--dry-run".to_string()], None)
+                ("cargo".to_string(), vec!["update".to_string(), "--dry-run".to_string()])
             }
             devman_core::GenericCheckType::SecurityScan { scanner } => {
                 // Run security scanner
-                (scanner.clone(), vec![], None)
+                (scanner.clone(), vec![])
             }
         };
 
+        let args = match check.scope {
+            devman_core::CheckScope::Full => args,
+            devman_core::CheckScope::ChangedOnly => self.scoped_args(generic, args, context, cancel).await,
+        };
+
+        let cache_key = match &self.cache {
+            Some(cache) => {
+                let key = self.cache_key(check, &tool, &args, context, cancel).await;
+                if let (Some(key), false) = (&key, context.no_cache) {
+                    if let Some(cached) = cache.get(key).await {
+                        tracing::debug!("Using cached result for quality check: {}", check.name);
+                        return cached;
+                    }
+                }
+                key
+            }
+            None => None,
+        };
+
+        let configured = self
+            .storage
+            .get_config_or(keys::QUALITY_CHECK_TIMEOUT_SECS, None)
+            .await
+            .map(std::time::Duration::from_secs);
+        let timeout = effective_timeout(generic, check, configured);
         let input = ToolInput {
             args,
             env: Default::default(),
             stdin: None,
-            timeout: Some(std::time::Duration::from_secs(300)),
+            timeout: Some(timeout),
+            work_dir: Some(check_dir(check, context)),
         };
 
-        let output = match self.tool_executor.execute_tool(&tool, input).await {
-            Ok(o) => o,
-            Err(e) => {
+        // Cancel rather than drop the run on timeout, so the tool kills the
+        // whole process group it started
+        let run_cancel = cancel.child_token();
+        let run = self.tool_executor.execute_tool(&tool, input, &run_cancel);
+        tokio::pin!(run);
+        let execution = tokio::time::timeout(timeout, &mut run).await;
+        let output = match execution {
+            Ok(Ok(o)) => o,
+            Ok(Err(e)) if ToolError::is_cancelled(&e) => return cancelled_result(check, start),
+            Err(_) => {
+                run_cancel.cancel();
+                let _ = run.await;
+                return QualityCheckResult {
+                    check_id: devman_core::QualityCheckId::new(),
+                    passed: false,
+                    execution_time: start.elapsed(),
+                    details: devman_core::CheckDetails {
+                        output: String::new(),
+                        exit_code: None,
+                        error: Some(format!("Timed out after {}s", timeout.as_secs_f64())),
+                        timeout: Some(timeout),
+                    },
+                    findings: vec![Finding {
+                        severity: Severity::Error,
+                        category: QualityCategory::Performance,
+                        message: format!("{} timed out after {}s", tool, timeout.as_secs_f64()),
+                        location: None,
+                        suggestion: Some(format!(
+                            "Raise the check's timeout above {}s if it legitimately needs longer",
+                            timeout.as_secs_f64()
+                        )),
+                    }],
+                    metrics: Vec::new(),
+                    human_review: None,
+                }
+            }
+            Ok(Err(e)) => {
                 return QualityCheckResult {
                     check_id: devman_core::QualityCheckId::new(),
                     passed: false,
@@ -231,6 +389,7 @@ impl<S: Storage> BasicQualityEngine<S> {
                         output: String::new(),
                         exit_code: None,
                         error: Some(e.to_string()),
+                        timeout: None,
                     },
                     findings: vec![Finding {
                         severity: Severity::Error,
@@ -270,8 +429,29 @@ impl<S: Storage> BasicQualityEngine<S> {
             });
         }
 
-        // Extract coverage if available
         let mut metrics = Vec::new();
+        if let devman_core::GenericCheckType::TestsPass { .. } = generic {
+            if let Some(summary) = parse_test_summary(&output.stdout) {
+                metrics.extend(summary.to_metrics());
+            }
+            if !passed {
+                for failure in parse_test_failures(&output.stdout) {
+                    findings.push(Finding {
+                        severity: Severity::Error,
+                        category: QualityCategory::Testing,
+                        message: format!("Test failed: {}", failure.name),
+                        location: failure.location.map(|(file, line, column)| devman_core::FileLocation {
+                            file,
+                            line: Some(line),
+                            column: Some(column),
+                        }),
+                        suggestion: Some(format!("Run `cargo test {}` to reproduce", failure.name)),
+                    });
+                }
+            }
+        }
+
+        // Extract coverage if available
         if let devman_core::GenericCheckType::TestsPass { min_coverage, .. } = generic {
             if let Some(coverage) = min_coverage {
                 // Try to extract coverage from output
@@ -284,7 +464,7 @@ impl<S: Storage> BasicQualityEngine<S> {
             }
         }
 
-        QualityCheckResult {
+        let result = QualityCheckResult {
             check_id: devman_core::QualityCheckId::new(),
             passed,
             execution_time: start.elapsed(),
@@ -296,10 +476,139 @@ impl<S: Storage> BasicQualityEngine<S> {
                 } else {
                     Some(output.stderr)
                 },
+                timeout: None,
             },
             findings,
             metrics,
             human_review: None,
+        };
+
+        if let (Some(cache), Some(key)) = (&self.cache, &cache_key) {
+            let stored = QualityCheckResult { details: result.details.clone().truncated(self.max_output_len), ..result.clone() };
+            if let Err(e) = cache.put(key, &stored).await {
+                tracing::warn!("Failed to cache result of quality check {}: {}", check.name, e);
+            }
+        }
+        result
+    }
+
+    /// Cache key for running `tool` with `args` for `check`: the command
+    /// plus the contents of the context's affected files or, when none are
+    /// given, git's index and the files changed in the working tree. `None`
+    /// when neither is available, so the result is not cached.
+    async fn cache_key(
+        &self,
+        check: &QualityCheck,
+        tool: &str,
+        args: &[String],
+        context: &WorkContext,
+        cancel: &CancellationToken,
+    ) -> Option<String> {
+        let mut key = CacheKey::new(&check.check_type, tool, args, &check_dir(check, context));
+        if !context.affected_files.is_empty() {
+            let mut files = context.affected_files.clone();
+            files.sort();
+            files.dedup();
+            for file in files {
+                key.add_file(&context.work_dir.join(file)).await;
+            }
+        } else {
+            let dir = context.work_dir.to_string_lossy().to_string();
+            let index = self.git(&["-C", &dir, "ls-files", "--stage"], cancel).await?;
+            key.add("index", index.as_bytes());
+            let (_, mut changed) = self.changed_files(context, cancel).await?;
+            changed.sort();
+            for file in changed {
+                key.add_file(&file).await;
+            }
+        }
+        Some(key.finish())
+    }
+
+    /// Narrow a generic check's `args` to the files changed under
+    /// `context.work_dir`. Cargo commands get a `-p` per changed package;
+    /// formatters and linters get the changed Rust files, ahead of any `--`
+    /// so they are not taken for the tool's own flags. Returns `args`
+    /// unchanged when the changes cannot be determined or mapped.
+    async fn scoped_args(
+        &self,
+        generic: &devman_core::GenericCheckType,
+        args: Vec<String>,
+        context: &WorkContext,
+        cancel: &CancellationToken,
+    ) -> Vec<String> {
+        use devman_core::GenericCheckType;
+
+        let (root, changed) = match self.changed_files(context, cancel).await {
+            Some((root, changed)) if !changed.is_empty() => (root, changed),
+            _ => return args,
+        };
+
+        match generic {
+            GenericCheckType::TestsPass { .. }
+            | GenericCheckType::Compiles { .. }
+            | GenericCheckType::TypeCheck {} => match changed_packages(&root, &changed) {
+                Some(packages) => {
+                    let mut scoped = args[..1].to_vec();
+                    for package in packages {
+                        scoped.push("-p".to_string());
+                        scoped.push(package);
+                    }
+                    scoped.extend_from_slice(&args[1..]);
+                    scoped
+                }
+                None => args,
+            },
+            GenericCheckType::Formatted { .. } | GenericCheckType::LintsPass { .. } => {
+                let files: Vec<String> = changed
+                    .iter()
+                    .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "rs"))
+                    .map(|path| path.to_string_lossy().to_string())
+                    .collect();
+                if files.is_empty() {
+                    args
+                } else {
+                    let mut args = args;
+                    let at = args.iter().position(|arg| arg == "--").unwrap_or(args.len());
+                    args.splice(at..at, files);
+                    args
+                }
+            }
+            _ => args,
+        }
+    }
+
+    /// Root of the git working tree containing `context.work_dir` and the
+    /// absolute paths of files changed in it, or `None` if git could not tell.
+    async fn changed_files(
+        &self,
+        context: &WorkContext,
+        cancel: &CancellationToken,
+    ) -> Option<(std::path::PathBuf, Vec<std::path::PathBuf>)> {
+        let dir = context.work_dir.to_string_lossy().to_string();
+        let root = self.git(&["-C", &dir, "rev-parse", "--show-toplevel"], cancel).await?;
+        let root = std::path::PathBuf::from(root.trim());
+        let status = self.git(&["-C", &dir, "status", "--porcelain"], cancel).await?;
+
+        let changed = devman_tools::GitTool::parse_status_porcelain(&status)
+            .into_iter()
+            .map(|path| root.join(path))
+            .collect();
+        Some((root, changed))
+    }
+
+    /// Run git and return its stdout if it succeeded.
+    async fn git(&self, args: &[&str], cancel: &CancellationToken) -> Option<String> {
+        let input = devman_tools::ToolInput {
+            args: args.iter().map(|a| a.to_string()).collect(),
+            env: Default::default(),
+            stdin: None,
+            timeout: Some(std::time::Duration::from_secs(30)),
+            work_dir: None,
+        };
+        match self.tool_executor.execute_tool("git", input, cancel).await {
+            Ok(output) if output.exit_code == 0 => Some(output.stdout),
+            _ => None,
         }
     }
 
@@ -364,6 +673,7 @@ impl<S: Storage> BasicQualityEngine<S> {
                 output: format!("Checked {} documentation paths", paths.len()),
                 exit_code: Some(if all_exist { 0 } else { 1 }),
                 error: None,
+                timeout: None,
             },
             findings,
             metrics: Vec::new(),
@@ -375,7 +685,8 @@ impl<S: Storage> BasicQualityEngine<S> {
         &self,
         custom: &devman_core::CustomCheckSpec,
         check: &QualityCheck,
-        _context: &WorkContext,
+        context: &WorkContext,
+        cancel: &CancellationToken,
     ) -> QualityCheckResult {
         tracing::debug!("Running custom check: {}", custom.name);
 
@@ -389,14 +700,16 @@ impl<S: Storage> BasicQualityEngine<S> {
             env: Default::default(),
             stdin: None,
             timeout: Some(custom.check_command.timeout),
+            work_dir: Some(check_dir(check, context)),
         };
 
         let tool_output = match self
             .tool_executor
-            .execute_tool(&custom.check_command.command, input)
+            .execute_tool(&custom.check_command.command, input, cancel)
             .await
         {
             Ok(o) => o,
+            Err(e) if ToolError::is_cancelled(&e) => return cancelled_result(check, start),
             Err(e) => {
                 return QualityCheckResult {
                     check_id: check.id,
@@ -406,6 +719,7 @@ impl<S: Storage> BasicQualityEngine<S> {
                         output: String::new(),
                         exit_code: None,
                         error: Some(e.to_string()),
+                        timeout: None,
                     },
                     findings: vec![Finding {
                         severity: Severity::Error,
@@ -445,6 +759,7 @@ impl<S: Storage> BasicQualityEngine<S> {
                         "Expected exit code {:?}, got {}",
                         custom.check_command.expected_exit_code, tool_output.exit_code
                     )),
+                    timeout: None,
                 },
                 findings: vec![Finding {
                     severity: Severity::Error,
@@ -501,6 +816,7 @@ impl<S: Storage> BasicQualityEngine<S> {
                 output: full_output,
                 exit_code: Some(tool_output.exit_code),
                 error: None,
+                timeout: None,
             },
             findings,
             metrics,
@@ -508,35 +824,153 @@ impl<S: Storage> BasicQualityEngine<S> {
         }
     }
 
+    /// Decide a gate from its check results.
+    ///
+    /// Checks listed in `advisory` never fail an `AllPassed` gate; when one
+    /// of them fails the gate passes with warnings instead.
     fn evaluate_gate(
         &self,
         gate: &QualityGate,
         results: &[QualityCheckResult],
+        advisory: &[devman_core::QualityCheckId],
+        context: &WorkContext,
     ) -> GateDecision {
-        match gate.pass_condition {
+        let is_advisory = |r: &&QualityCheckResult| advisory.contains(&r.check_id);
+        let passed = match &gate.pass_condition {
             devman_core::PassCondition::AllPassed => {
-                if results.iter().all(|r| r.passed) {
-                    GateDecision::Pass
-                } else {
-                    GateDecision::Fail
-                }
+                results.iter().filter(|r| !is_advisory(r)).all(|r| r.passed)
             }
             devman_core::PassCondition::AtLeast { count } => {
-                let passed = results.iter().filter(|r| r.passed).count();
-                if passed >= count {
-                    GateDecision::Pass
-                } else {
-                    GateDecision::Fail
-                }
+                results.iter().filter(|r| r.passed).count() >= *count
             }
             devman_core::PassCondition::Custom { .. } => {
                 // TODO: Implement custom expression evaluation
-                GateDecision::Pass
+                true
+            }
+            devman_core::PassCondition::Expression { condition } => {
+                evaluate_condition(condition, results, context)
             }
+        };
+
+        if passed && results.iter().filter(is_advisory).any(|r| !r.passed) {
+            GateDecision::PassWithWarnings
+        } else if passed {
+            GateDecision::Pass
+        } else {
+            match gate.on_failure {
+                devman_core::FailureAction::Block => GateDecision::Fail,
+                devman_core::FailureAction::Warn => GateDecision::PassWithWarnings,
+                devman_core::FailureAction::Escalate => GateDecision::Escalate,
+            }
+        }
+    }
+}
+
+/// Evaluate a composite gate condition against check results.
+///
+/// A `CheckPassed` leaf for a check that did not run is false, as is a
+/// `Metric` leaf when no result reports the metric. `Flag` leaves read
+/// boolean values from the context metadata.
+fn evaluate_condition(
+    condition: &GateCondition,
+    results: &[QualityCheckResult],
+    context: &WorkContext,
+) -> bool {
+    match condition {
+        GateCondition::AllOf { conditions } => {
+            conditions.iter().all(|c| evaluate_condition(c, results, context))
+        }
+        GateCondition::AnyOf { conditions } => {
+            conditions.iter().any(|c| evaluate_condition(c, results, context))
+        }
+        GateCondition::Not { condition } => !evaluate_condition(condition, results, context),
+        GateCondition::CheckPassed { check } => {
+            results.iter().any(|r| r.check_id == *check && r.passed)
         }
+        GateCondition::Metric { name, op, value } => results
+            .iter()
+            .flat_map(|r| &r.metrics)
+            .find(|m| &m.name == name)
+            .is_some_and(|m| op.compare(m.value, *value)),
+        GateCondition::Flag { name } => context
+            .metadata
+            .get(name)
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
     }
 }
 
+/// Names of the Cargo packages under `root` containing `files`, sorted,
+/// or `None` if any file is outside a package.
+fn changed_packages(root: &std::path::Path, files: &[std::path::PathBuf]) -> Option<Vec<String>> {
+    let mut packages = std::collections::BTreeSet::new();
+    for file in files {
+        let package = file
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(root))
+            .find_map(|dir| package_name(&dir.join("Cargo.toml")))?;
+        packages.insert(package);
+    }
+    Some(packages.into_iter().collect())
+}
+
+/// The `[package]` name declared in a Cargo manifest, if it has one.
+fn package_name(manifest: &std::path::Path) -> Option<String> {
+    let contents = std::fs::read_to_string(manifest).ok()?;
+    let mut in_package = false;
+    for line in contents.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+        } else if in_package {
+            if let Some((key, value)) = line.split_once('=') {
+                if key.trim() == "name" {
+                    return Some(value.trim().trim_matches('"').to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Directory `check` runs in: its `working_dir` resolved against the
+/// project root in `context`.
+fn check_dir(check: &QualityCheck, context: &WorkContext) -> std::path::PathBuf {
+    match &check.working_dir {
+        Some(dir) => context.work_dir.join(dir),
+        None => context.work_dir.clone(),
+    }
+}
+
+/// Result for a check whose tool was cancelled mid-run.
+fn cancelled_result(check: &QualityCheck, start: std::time::Instant) -> QualityCheckResult {
+    QualityCheckResult {
+        check_id: check.id,
+        passed: false,
+        execution_time: start.elapsed(),
+        details: CheckDetails {
+            output: String::new(),
+            exit_code: None,
+            error: Some("Cancelled".to_string()),
+            timeout: None,
+        },
+        findings: Vec::new(),
+        metrics: Vec::new(),
+        human_review: None,
+    }
+}
+
+/// Timeout to apply to a generic check: the check's own override if set,
+/// then the timeout configured in the store, otherwise the default for its
+/// type.
+fn effective_timeout(
+    generic: &devman_core::GenericCheckType,
+    check: &QualityCheck,
+    configured: Option<std::time::Duration>,
+) -> std::time::Duration {
+    check.timeout.or(configured).unwrap_or_else(|| generic.default_timeout())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -588,6 +1022,8 @@ mod tests {
             task_id: context.task_id,
             work_dir: context.work_dir.clone(),
             metadata: serde_json::json!({"key": "value"}),
+            affected_files: Vec::new(),
+            no_cache: false,
         };
         assert_eq!(context_with_meta.metadata["key"], "value");
     }
@@ -650,6 +1086,639 @@ mod tests {
         assert!(matches!(security, GenericCheckType::SecurityScan { .. }));
     }
 
+    fn generic_check(generic: devman_core::GenericCheckType) -> QualityCheck {
+        QualityCheck {
+            id: QualityCheckId::new(),
+            name: "generic".to_string(),
+            description: String::new(),
+            check_type: devman_core::QualityCheckType::Generic(generic),
+            severity: Severity::Error,
+            category: QualityCategory::Correctness,
+            timeout: None,
+            scope: devman_core::CheckScope::Full,
+            working_dir: None,
+            blocking: true,
+        }
+    }
+
+    #[test]
+    fn test_effective_timeout_per_type() {
+        use devman_core::GenericCheckType;
+        use std::time::Duration;
+
+        let cases = vec![
+            (GenericCheckType::Formatted { formatter: "rustfmt".to_string() }, 30),
+            (GenericCheckType::LintsPass { linter: "clippy".to_string() }, 120),
+            (GenericCheckType::TestsPass { test_suite: String::new(), min_coverage: None }, 600),
+            (GenericCheckType::Compiles { target: "x86_64-unknown-linux-gnu".to_string() }, 300),
+        ];
+
+        for (generic, secs) in cases {
+            let check = generic_check(generic.clone());
+            assert_eq!(effective_timeout(&generic, &check, None), Duration::from_secs(secs));
+        }
+
+        let generic = GenericCheckType::TestsPass { test_suite: String::new(), min_coverage: None };
+        let mut check = generic_check(generic.clone());
+        let configured = Some(Duration::from_secs(45));
+        assert_eq!(effective_timeout(&generic, &check, configured), Duration::from_secs(45));
+        check.timeout = Some(Duration::from_secs(1200));
+        assert_eq!(effective_timeout(&generic, &check, None), Duration::from_secs(1200));
+        assert_eq!(effective_timeout(&generic, &check, configured), Duration::from_secs(1200));
+    }
+
+    struct SlowToolExecutor;
+
+    #[async_trait]
+    impl devman_tools::ToolExecutor for SlowToolExecutor {
+        async fn execute_tool(
+            &self,
+            _tool: &str,
+            _input: devman_tools::ToolInput,
+            cancel: &CancellationToken,
+        ) -> Result<devman_tools::ToolOutput, anyhow::Error> {
+            tokio::select! {
+                _ = tokio::time::sleep(std::time::Duration::from_secs(5)) => Ok(devman_tools::ToolOutput {
+                    exit_code: 0,
+                    stdout: String::new(),
+                    stderr: String::new(),
+                    duration: std::time::Duration::from_secs(5),
+                }),
+                _ = cancel.cancelled() => Err(ToolError::Cancelled.into()),
+            }
+        }
+    }
+
+    /// Answers git queries from a fixed status and records every call.
+    struct RecordingToolExecutor {
+        root: std::path::PathBuf,
+        status: String,
+        calls: std::sync::Mutex<Vec<(String, Vec<String>)>>,
+        work_dirs: std::sync::Mutex<Vec<Option<std::path::PathBuf>>>,
+    }
+
+    impl RecordingToolExecutor {
+        fn calls_to(&self, tool: &str) -> Vec<Vec<String>> {
+            self.calls
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(t, _)| t == tool)
+                .map(|(_, args)| args.clone())
+                .collect()
+        }
+    }
+
+    #[async_trait]
+    impl devman_tools::ToolExecutor for RecordingToolExecutor {
+        async fn execute_tool(
+            &self,
+            tool: &str,
+            input: devman_tools::ToolInput,
+            _cancel: &CancellationToken,
+        ) -> Result<devman_tools::ToolOutput, anyhow::Error> {
+            self.calls.lock().unwrap().push((tool.to_string(), input.args.clone()));
+            self.work_dirs.lock().unwrap().push(input.work_dir.clone());
+            let stdout = match (tool, input.args.get(2).map(String::as_str)) {
+                ("git", Some("rev-parse")) => format!("{}\n", self.root.display()),
+                ("git", Some("status")) => self.status.clone(),
+                _ => String::new(),
+            };
+            Ok(devman_tools::ToolOutput {
+                exit_code: 0,
+                stdout,
+                stderr: String::new(),
+                duration: std::time::Duration::ZERO,
+            })
+        }
+    }
+
+    /// Workspace with packages `alpha` (crates/a) and `beta` (crates/b).
+    fn scoped_engine(status: &str) -> (tempfile::TempDir, Arc<RecordingToolExecutor>) {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "[workspace]\nmembers = [\"crates/*\"]\n").unwrap();
+        for (path, name) in [("crates/a", "alpha"), ("crates/b", "beta")] {
+            let src = dir.path().join(path).join("src");
+            std::fs::create_dir_all(&src).unwrap();
+            std::fs::write(
+                dir.path().join(path).join("Cargo.toml"),
+                format!("[package]\nname = \"{}\"\nversion = \"0.1.0\"\n", name),
+            )
+            .unwrap();
+            std::fs::write(src.join("lib.rs"), "").unwrap();
+        }
+        let executor = Arc::new(RecordingToolExecutor {
+            root: dir.path().to_path_buf(),
+            status: status.to_string(),
+            calls: Default::default(),
+            work_dirs: Default::default(),
+        });
+        (dir, executor)
+    }
+
+    #[tokio::test]
+    async fn test_cached_result_reused_until_sources_change() {
+        use devman_core::GenericCheckType;
+
+        let (dir, executor) = scoped_engine(" M crates/a/src/lib.rs\n");
+        let cache_dir = tempfile::tempdir().unwrap();
+        let storage = devman_storage::JsonStorage::new(dir.path().join(".devman")).await.unwrap();
+        let engine = BasicQualityEngine::new(storage, executor.clone())
+            .with_cache(crate::cache::CheckCache::new(cache_dir.path()));
+        let check = generic_check(GenericCheckType::TypeCheck {});
+        let mut context = WorkContext::new(TaskId::new());
+        context.work_dir = dir.path().to_path_buf();
+
+        let first = engine.run_check(&check, &context).await;
+        assert!(first.passed);
+        assert_eq!(executor.calls_to("cargo").len(), 1);
+
+        // Nothing changed: served from the cache
+        let second = engine.run_check(&check, &context).await;
+        assert_eq!(second.check_id, check.id);
+        assert_eq!(second.details.exit_code, first.details.exit_code);
+        assert_eq!(executor.calls_to("cargo").len(), 1);
+
+        // Editing a changed file invalidates the entry
+        std::fs::write(dir.path().join("crates/a/src/lib.rs"), "pub fn edited() {}").unwrap();
+        engine.run_check(&check, &context).await;
+        assert_eq!(executor.calls_to("cargo").len(), 2);
+        engine.run_check(&check, &context).await;
+        assert_eq!(executor.calls_to("cargo").len(), 2);
+
+        // The override always runs the check
+        context.no_cache = true;
+        engine.run_check(&check, &context).await;
+        assert_eq!(executor.calls_to("cargo").len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_changed_only_scope_narrows_args() {
+        use devman_core::{CheckScope, GenericCheckType};
+
+        let (dir, executor) = scoped_engine(" M crates/a/src/lib.rs\n?? crates/b/src/lib.rs\n");
+        let storage = devman_storage::JsonStorage::new(&dir.path().join(".devman")).await.unwrap();
+        let engine = BasicQualityEngine::new(storage, executor.clone());
+        let context = WorkContext { work_dir: dir.path().to_path_buf(), ..WorkContext::new(TaskId::new()) };
+
+        let mut tests = generic_check(GenericCheckType::TestsPass { test_suite: String::new(), min_coverage: None });
+        tests.scope = CheckScope::ChangedOnly;
+        engine.run_check(&tests, &context).await;
+        assert_eq!(executor.calls_to("cargo"), vec![vec!["test", "-p", "alpha", "-p", "beta"]]);
+
+        let mut fmt = generic_check(GenericCheckType::Formatted { formatter: "rustfmt".to_string() });
+        fmt.scope = CheckScope::ChangedOnly;
+        engine.run_check(&fmt, &context).await;
+        let lib = |p: &str| dir.path().join(p).join("src/lib.rs").to_string_lossy().to_string();
+        assert_eq!(
+            executor.calls_to("rustfmt"),
+            vec![vec!["--check".to_string(), lib("crates/a"), lib("crates/b")]]
+        );
+
+        // Full scope never asks git
+        let git_calls = executor.calls_to("git").len();
+        tests.scope = CheckScope::Full;
+        engine.run_check(&tests, &context).await;
+        assert_eq!(executor.calls_to("cargo").last().unwrap(), &vec!["test"]);
+        assert_eq!(executor.calls_to("git").len(), git_calls);
+    }
+
+    #[tokio::test]
+    async fn test_changed_only_passes_rust_files_before_separator() {
+        use devman_core::GenericCheckType;
+
+        let (dir, executor) = scoped_engine(" M crates/a/src/lib.rs\n M crates/a/README.md\n");
+        std::fs::write(dir.path().join("crates/a/README.md"), "").unwrap();
+        let storage = devman_storage::JsonStorage::new(&dir.path().join(".devman")).await.unwrap();
+        let engine = BasicQualityEngine::new(storage, executor);
+        let context = WorkContext { work_dir: dir.path().to_path_buf(), ..WorkContext::new(TaskId::new()) };
+
+        let lint = GenericCheckType::LintsPass { linter: "clippy".to_string() };
+        let args: Vec<String> = ["clippy", "--", "-D", "warnings"].map(String::from).to_vec();
+        let scoped = engine.scoped_args(&lint, args, &context, &CancellationToken::new()).await;
+        let lib = dir.path().join("crates/a/src/lib.rs").to_string_lossy().to_string();
+        assert_eq!(scoped, ["clippy", &lib, "--", "-D", "warnings"]);
+    }
+
+    #[tokio::test]
+    async fn test_changed_only_falls_back_to_full_run() {
+        use devman_core::{CheckScope, GenericCheckType};
+
+        // A change outside any package cannot be mapped to `-p`
+        let (dir, executor) = scoped_engine(" M crates/a/src/lib.rs\n M README.md\n");
+        let storage = devman_storage::JsonStorage::new(&dir.path().join(".devman")).await.unwrap();
+        let engine = BasicQualityEngine::new(storage, executor.clone());
+        let context = WorkContext { work_dir: dir.path().to_path_buf(), ..WorkContext::new(TaskId::new()) };
+
+        let mut check = generic_check(GenericCheckType::TestsPass { test_suite: String::new(), min_coverage: None });
+        check.scope = CheckScope::ChangedOnly;
+        engine.run_check(&check, &context).await;
+        assert_eq!(executor.calls_to("cargo"), vec![vec!["test"]]);
+    }
+
+    #[tokio::test]
+    async fn test_checks_run_in_project_working_dir() {
+        use devman_core::GenericCheckType;
+
+        let (dir, executor) = scoped_engine("");
+        let storage = devman_storage::JsonStorage::new(&dir.path().join(".devman")).await.unwrap();
+        let engine = BasicQualityEngine::new(storage, executor.clone());
+        let context = WorkContext { work_dir: dir.path().to_path_buf(), ..WorkContext::new(TaskId::new()) };
+
+        let mut check = generic_check(GenericCheckType::TypeCheck {});
+        engine.run_check(&check, &context).await;
+        check.working_dir = Some("crates/b".into());
+        engine.run_check(&check, &context).await;
+        let custom = crate::custom::CustomCheckBuilder::new("lint")
+            .command("npm")
+            .working_dir("web")
+            .build();
+        engine.run_check(&custom, &context).await;
+
+        assert_eq!(
+            *executor.work_dirs.lock().unwrap(),
+            vec![
+                Some(dir.path().to_path_buf()),
+                Some(dir.path().join("crates/b")),
+                Some(dir.path().join("web")),
+            ]
+        );
+    }
+
+    /// Executor returning fixed output for every tool.
+    struct CannedToolExecutor {
+        exit_code: i32,
+        stdout: String,
+    }
+
+    #[async_trait]
+    impl devman_tools::ToolExecutor for CannedToolExecutor {
+        async fn execute_tool(
+            &self,
+            _tool: &str,
+            _input: devman_tools::ToolInput,
+            _cancel: &CancellationToken,
+        ) -> Result<devman_tools::ToolOutput, anyhow::Error> {
+            Ok(devman_tools::ToolOutput {
+                exit_code: self.exit_code,
+                stdout: self.stdout.clone(),
+                stderr: String::new(),
+                duration: std::time::Duration::ZERO,
+            })
+        }
+    }
+
+    /// Executor failing every call to `failing_tool` and passing the rest.
+    struct FailingToolExecutor {
+        failing_tool: &'static str,
+    }
+
+    #[async_trait]
+    impl devman_tools::ToolExecutor for FailingToolExecutor {
+        async fn execute_tool(
+            &self,
+            tool: &str,
+            _input: devman_tools::ToolInput,
+            _cancel: &CancellationToken,
+        ) -> Result<devman_tools::ToolOutput, anyhow::Error> {
+            Ok(devman_tools::ToolOutput {
+                exit_code: if tool == self.failing_tool { 1 } else { 0 },
+                stdout: String::new(),
+                stderr: String::new(),
+                duration: std::time::Duration::ZERO,
+            })
+        }
+    }
+
+    /// Gate over a blocking `cargo test` check and an advisory clippy lint,
+    /// run with `failing_tool` failing.
+    async fn run_mixed_gate(failing_tool: &'static str) -> GateResult {
+        use devman_core::{FailureAction, GenericCheckType, PassCondition};
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let tests = generic_check(GenericCheckType::TestsPass { test_suite: String::new(), min_coverage: None });
+        let mut lint = generic_check(GenericCheckType::LintsPass { linter: "clippy".to_string() });
+        lint.blocking = false;
+        storage.save_quality_check(&tests).await.unwrap();
+        storage.save_quality_check(&lint).await.unwrap();
+
+        let engine = BasicQualityEngine::new(storage, Arc::new(FailingToolExecutor { failing_tool }));
+        let gate = QualityGate {
+            name: "ci".to_string(),
+            description: String::new(),
+            checks: vec![tests.id, lint.id],
+            pass_condition: PassCondition::AllPassed,
+            on_failure: FailureAction::Block,
+        };
+        engine.run_gate(&gate, &WorkContext::new(TaskId::new()), &CancellationToken::new()).await
+    }
+
+    #[tokio::test]
+    async fn test_failing_advisory_check_only_warns() {
+        let result = run_mixed_gate("clippy").await;
+        assert!(!result.check_results[1].passed);
+        assert_eq!(result.decision, GateDecision::PassWithWarnings);
+        assert!(result.passed);
+
+        let status = overall_status(std::slice::from_ref(&result));
+        assert_eq!(status, devman_core::TaskQualityOverallStatus::PassedWithWarnings);
+        assert!(status.allows_completion());
+    }
+
+    #[tokio::test]
+    async fn test_failing_blocking_check_fails_gate() {
+        let result = run_mixed_gate("cargo").await;
+        assert!(result.check_results[1].passed);
+        assert_eq!(result.decision, GateDecision::Fail);
+        assert!(!result.passed);
+
+        let status = overall_status(std::slice::from_ref(&result));
+        assert_eq!(status, devman_core::TaskQualityOverallStatus::Failed);
+        assert!(!status.allows_completion());
+    }
+
+    #[tokio::test]
+    async fn test_tests_pass_reports_counts_and_failed_tests() {
+        use devman_core::GenericCheckType;
+
+        let stdout = "\
+running 3 tests
+test lexer::tests::tokens ... ok
+test lexer::tests::unicode ... FAILED
+test parser::tests::nested ... FAILED
+
+failures:
+
+---- lexer::tests::unicode stdout ----
+
+thread 'lexer::tests::unicode' (101) panicked at src/lexer.rs:88:5:
+assertion `left == right` failed
+
+failures:
+    lexer::tests::unicode
+    parser::tests::nested
+
+test result: FAILED. 1 passed; 2 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.02s
+";
+        let dir = tempfile::tempdir().unwrap();
+        let storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let executor = Arc::new(CannedToolExecutor { exit_code: 101, stdout: stdout.to_string() });
+        let engine = BasicQualityEngine::new(storage, executor);
+        let check = generic_check(GenericCheckType::TestsPass { test_suite: String::new(), min_coverage: None });
+
+        let result = engine.run_check(&check, &WorkContext::new(TaskId::new())).await;
+        assert!(!result.passed);
+        let metric = |name: &str| result.metrics.iter().find(|m| m.name == name).map(|m| m.value);
+        assert_eq!(metric("tests_passed"), Some(1.0));
+        assert_eq!(metric("tests_failed"), Some(2.0));
+        assert_eq!(metric("tests_ignored"), Some(0.0));
+
+        let failed: Vec<_> = result
+            .findings
+            .iter()
+            .filter(|f| f.message.starts_with("Test failed"))
+            .collect();
+        assert_eq!(failed.len(), 2);
+        assert_eq!(failed[0].message, "Test failed: lexer::tests::unicode");
+        let location = failed[0].location.as_ref().unwrap();
+        assert_eq!((location.file.as_str(), location.line), ("src/lexer.rs", Some(88)));
+        assert_eq!(failed[1].message, "Test failed: parser::tests::nested");
+        assert!(failed[1].location.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_check_output_truncated_past_limit() {
+        use devman_core::GenericCheckType;
+
+        let dir = tempfile::tempdir().unwrap();
+        let stdout = format!("{}test result: ok. 1 passed; 0 failed\n", "compiling...\n".repeat(100));
+        let executor = Arc::new(CannedToolExecutor { exit_code: 0, stdout: stdout.clone() });
+        let check = generic_check(GenericCheckType::TestsPass { test_suite: String::new(), min_coverage: None });
+        let context = WorkContext::new(TaskId::new());
+
+        let storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let engine = BasicQualityEngine::new(storage, executor.clone());
+        let result = engine.run_check(&check, &context).await;
+        assert_eq!(result.details.output, stdout);
+
+        let storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let engine = BasicQualityEngine::new(storage, executor).with_max_output_len(200);
+        let result = engine.run_check(&check, &context).await;
+        assert!(result.passed);
+        assert!(result.details.output.contains("[truncated"));
+        assert!(result.details.output.ends_with("test result: ok. 1 passed; 0 failed\n"));
+        assert!(result.details.output.len() < stdout.len());
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_gate_stops_running_check() {
+        use devman_core::{FailureAction, PassCondition};
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let first = generic_check(devman_core::GenericCheckType::TestsPass {
+            test_suite: String::new(),
+            min_coverage: None,
+        });
+        let second = generic_check(devman_core::GenericCheckType::LintsPass {
+            linter: "clippy".to_string(),
+        });
+        storage.save_quality_check(&first).await.unwrap();
+        storage.save_quality_check(&second).await.unwrap();
+        let engine = BasicQualityEngine::new(storage, Arc::new(SlowToolExecutor));
+
+        let gate = QualityGate {
+            name: "ci".to_string(),
+            description: String::new(),
+            checks: vec![first.id, second.id],
+            pass_condition: PassCondition::AllPassed,
+            on_failure: FailureAction::Block,
+        };
+
+        let cancel = CancellationToken::new();
+        let canceller = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            canceller.cancel();
+        });
+
+        let start = std::time::Instant::now();
+        let result = engine.run_gate(&gate, &WorkContext::new(TaskId::new()), &cancel).await;
+
+        assert!(start.elapsed() < std::time::Duration::from_secs(1));
+        assert_eq!(result.decision, GateDecision::Cancelled);
+        assert!(!result.passed);
+        // The second check never started
+        assert_eq!(result.check_results.len(), 1);
+        assert_eq!(result.check_results[0].check_id, first.id);
+        assert_eq!(result.check_results[0].details.error.as_deref(), Some("Cancelled"));
+    }
+
+    #[tokio::test]
+    async fn test_repeatedly_failing_gate_escalates_with_findings() {
+        use crate::gate::GateEscalation;
+        use crate::human::{HumanReviewService, NotificationChannel};
+        use devman_core::{FailureAction, GateStrategy, PassCondition, PhaseGate, PhaseId};
+
+        let stdout = "\
+running 1 test
+test lexer::tests::unicode ... FAILED
+
+failures:
+
+---- lexer::tests::unicode stdout ----
+
+thread 'lexer::tests::unicode' (101) panicked at src/lexer.rs:88:5:
+assertion failed
+
+failures:
+    lexer::tests::unicode
+
+test result: FAILED. 0 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.01s
+";
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let check = generic_check(devman_core::GenericCheckType::TestsPass {
+            test_suite: String::new(),
+            min_coverage: None,
+        });
+        storage.save_quality_check(&check).await.unwrap();
+        let executor = Arc::new(CannedToolExecutor { exit_code: 101, stdout: stdout.to_string() });
+        let engine = BasicQualityEngine::new(storage, executor);
+
+        let phase_gate = PhaseGate {
+            phase: PhaseId::new(),
+            checks: vec![check.id],
+            strategy: GateStrategy::AllMustPass,
+            escalate_after_failures: 3,
+        };
+        let gate = QualityGate {
+            name: "ci".to_string(),
+            description: String::new(),
+            checks: vec![check.id],
+            pass_condition: PassCondition::AllPassed,
+            on_failure: FailureAction::Block,
+        };
+        let context = WorkContext::new(TaskId::new());
+        let mut escalation = GateEscalation::new(HumanReviewService::new(NotificationChannel::Console));
+
+        let mut per_run = 0;
+        for run in 1..=2 {
+            let mut result = engine.run_gate(&gate, &context, &CancellationToken::new()).await;
+            per_run = result.check_results[0].findings.len();
+            assert!(escalation.record(&phase_gate, context.task_id, &mut result).await.is_none());
+            assert_eq!(result.decision, GateDecision::Fail);
+            assert_eq!(escalation.failures(phase_gate.phase, context.task_id), run);
+        }
+
+        let mut result = engine.run_gate(&gate, &context, &CancellationToken::new()).await;
+        let escalated = escalation.record(&phase_gate, context.task_id, &mut result).await.unwrap();
+        assert_eq!(result.decision, GateDecision::Escalate);
+        assert!(!result.passed);
+        assert_eq!(escalation.failures(phase_gate.phase, context.task_id), 0);
+
+        assert!(per_run > 0);
+        assert_eq!(escalated.findings.len(), 3 * per_run);
+        let checks = &escalated.context.check_results;
+        for run in 1..=3 {
+            let prefix = format!("Run {}: ", run);
+            assert!(checks.iter().any(|c| c.starts_with(&prefix) && c.contains("lexer::tests::unicode")));
+        }
+        assert_eq!(escalated.context.files, vec!["src/lexer.rs".to_string()]);
+        assert!(escalated.spec.review_guide.contains("failed 3 times"));
+        assert!(escalated.spec.review_form.iter().any(|q| q.required));
+    }
+
+    #[tokio::test]
+    async fn test_check_timeout_read_from_stored_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        storage.set_config(keys::QUALITY_CHECK_TIMEOUT_SECS, &1u64).await.unwrap();
+
+        // A fresh handle on the same store sees the setting
+        let reloaded = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let engine = BasicQualityEngine::new(reloaded, Arc::new(SlowToolExecutor));
+        let check = generic_check(devman_core::GenericCheckType::Formatted {
+            formatter: "rustfmt".to_string(),
+        });
+
+        let result = engine.run_check(&check, &WorkContext::new(TaskId::new())).await;
+        assert!(!result.passed);
+        assert_eq!(result.details.timeout, Some(std::time::Duration::from_secs(1)));
+    }
+
+    #[tokio::test]
+    async fn test_generic_check_timeout_produces_finding() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let engine = BasicQualityEngine::new(storage, Arc::new(SlowToolExecutor));
+
+        let mut check = generic_check(devman_core::GenericCheckType::Formatted {
+            formatter: "rustfmt".to_string(),
+        });
+        check.timeout = Some(std::time::Duration::from_millis(50));
+
+        let result = engine.run_check(&check, &WorkContext::new(TaskId::new())).await;
+
+        assert!(!result.passed);
+        assert_eq!(result.details.timeout, Some(std::time::Duration::from_millis(50)));
+        assert_eq!(result.findings.len(), 1);
+        assert!(result.findings[0].suggestion.as_deref().unwrap().contains("timeout"));
+    }
+
+    /// Runs every tool as `sh -c script`.
+    struct ShellToolExecutor {
+        script: String,
+    }
+
+    #[async_trait]
+    impl devman_tools::ToolExecutor for ShellToolExecutor {
+        async fn execute_tool(
+            &self,
+            _tool: &str,
+            input: devman_tools::ToolInput,
+            cancel: &CancellationToken,
+        ) -> Result<devman_tools::ToolOutput, anyhow::Error> {
+            let mut cmd = tokio::process::Command::new("sh");
+            cmd.arg("-c").arg(&self.script);
+            devman_tools::run_command(cmd, &input, cancel).await
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_timed_out_check_kills_spawned_processes() {
+        let dir = tempfile::tempdir().unwrap();
+        let pid_file = dir.path().join("sleeper.pid");
+        let storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let script = format!("sleep 30 & echo $! > {}; wait", pid_file.display());
+        let engine = BasicQualityEngine::new(storage, Arc::new(ShellToolExecutor { script }));
+
+        let mut check = generic_check(devman_core::GenericCheckType::Formatted {
+            formatter: "rustfmt".to_string(),
+        });
+        check.timeout = Some(std::time::Duration::from_millis(500));
+
+        let result = engine.run_check(&check, &WorkContext::new(TaskId::new())).await;
+        assert_eq!(result.details.timeout, Some(std::time::Duration::from_millis(500)));
+        assert_eq!(result.findings.len(), 1);
+
+        // The background sleep is gone, or a zombie waiting to be reaped
+        let pid = std::fs::read_to_string(&pid_file).unwrap().trim().to_string();
+        let running = || {
+            std::fs::read_to_string(format!("/proc/{}/stat", pid))
+                .is_ok_and(|stat| stat.rsplit(')').next().is_some_and(|rest| !rest.trim_start().starts_with('Z')))
+        };
+        for _ in 0..50 {
+            if !running() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert!(!running(), "sleep {} survived the timeout", pid);
+    }
+
     #[test]
     fn test_quality_category_variants() {
         use devman_core::QualityCategory;
@@ -695,6 +1764,7 @@ mod tests {
                 output: "All checks passed".to_string(),
                 exit_code: Some(0),
                 error: None,
+                timeout: None,
             },
             findings: Vec::new(),
             metrics: vec![
@@ -731,4 +1801,112 @@ mod tests {
         assert_eq!(status.warnings, 1);
         assert!(matches!(status.overall_status, QualityOverallStatus::PassedWithWarnings));
     }
+
+    fn check_result(check_id: QualityCheckId, passed: bool, metrics: Vec<Metric>) -> QualityCheckResult {
+        QualityCheckResult {
+            check_id,
+            passed,
+            execution_time: std::time::Duration::ZERO,
+            details: CheckDetails {
+                output: String::new(),
+                exit_code: None,
+                error: None,
+                timeout: None,
+            },
+            findings: Vec::new(),
+            metrics,
+            human_review: None,
+        }
+    }
+
+    fn coverage(value: f64) -> Vec<Metric> {
+        vec![Metric { name: "coverage".to_string(), value, unit: Some("%".to_string()) }]
+    }
+
+    #[tokio::test]
+    async fn test_expression_gate_tests_and_coverage_or_override() {
+        use devman_core::{Comparison, FailureAction, PassCondition};
+
+        let dir = tempfile::tempdir().unwrap();
+        let storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let engine = BasicQualityEngine::new(storage, Arc::new(SlowToolExecutor));
+
+        let tests_check = QualityCheckId::new();
+        let coverage_check = QualityCheckId::new();
+        let mut gate = QualityGate {
+            name: "release".to_string(),
+            description: String::new(),
+            checks: vec![tests_check, coverage_check],
+            pass_condition: PassCondition::Expression {
+                condition: GateCondition::AnyOf {
+                    conditions: vec![
+                        GateCondition::AllOf {
+                            conditions: vec![
+                                GateCondition::CheckPassed { check: tests_check },
+                                GateCondition::Metric {
+                                    name: "coverage".to_string(),
+                                    op: Comparison::Ge,
+                                    value: 80.0,
+                                },
+                            ],
+                        },
+                        GateCondition::Flag { name: "manual_override".to_string() },
+                    ],
+                },
+            },
+            on_failure: FailureAction::Block,
+        };
+
+        let plain = WorkContext::new(TaskId::new());
+        let overridden = WorkContext {
+            metadata: serde_json::json!({"manual_override": true}),
+            ..plain.clone()
+        };
+
+        let cases = vec![
+            // (tests pass, coverage, override, expected)
+            (true, 85.0, false, GateDecision::Pass),
+            (true, 80.0, false, GateDecision::Pass),
+            (true, 79.9, false, GateDecision::Fail),
+            (false, 95.0, false, GateDecision::Fail),
+            (false, 10.0, true, GateDecision::Pass),
+            (true, 50.0, true, GateDecision::Pass),
+        ];
+        for (tests_pass, cov, manual, expected) in cases {
+            let results = vec![
+                check_result(tests_check, tests_pass, Vec::new()),
+                check_result(coverage_check, true, coverage(cov)),
+            ];
+            let context = if manual { &overridden } else { &plain };
+            assert_eq!(
+                engine.evaluate_gate(&gate, &results, &[], context),
+                expected,
+                "tests_pass={tests_pass} coverage={cov} override={manual}"
+            );
+        }
+
+        // A missing coverage metric never satisfies the threshold.
+        let results = vec![check_result(tests_check, true, Vec::new())];
+        assert_eq!(engine.evaluate_gate(&gate, &results, &[], &plain), GateDecision::Fail);
+
+        // The failure action decides how a failed condition is reported.
+        let failing = vec![check_result(tests_check, false, coverage(90.0))];
+        gate.on_failure = FailureAction::Warn;
+        assert_eq!(engine.evaluate_gate(&gate, &failing, &[], &plain), GateDecision::PassWithWarnings);
+        gate.on_failure = FailureAction::Escalate;
+        assert_eq!(engine.evaluate_gate(&gate, &failing, &[], &plain), GateDecision::Escalate);
+    }
+
+    #[test]
+    fn test_not_condition_inverts_leaf() {
+        let check = QualityCheckId::new();
+        let condition = GateCondition::Not {
+            condition: Box::new(GateCondition::CheckPassed { check }),
+        };
+        let context = WorkContext::new(TaskId::new());
+
+        assert!(!evaluate_condition(&condition, &[check_result(check, true, Vec::new())], &context));
+        assert!(evaluate_condition(&condition, &[check_result(check, false, Vec::new())], &context));
+        assert!(evaluate_condition(&condition, &[], &context));
+    }
 }
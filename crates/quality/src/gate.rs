@@ -1,6 +1,13 @@
 //! Quality gates and profiles.
 
-use devman_core::{QualityCheckId, QualityGate, PhaseId, PassCondition, GateStrategy, QualityProfile};
+use devman_core::{
+    AnswerType, Finding, GateStrategy, HumanReviewSpec, PassCondition, PhaseGate, PhaseId, QualityCheckId,
+    QualityGate, QualityProfile, ReviewQuestion, TaskId,
+};
+use std::collections::HashMap;
+
+use crate::engine::{GateDecision, GateResult};
+use crate::human::{HumanReviewService, ReviewContext};
 
 /// Extension trait for QualityGate providing builder methods.
 pub trait QualityGateBuilder: Sized {
@@ -38,6 +45,141 @@ pub trait QualityProfileBuilder: Sized {
     fn add_phase_gate(self, gate: devman_core::PhaseGate) -> Self;
 }
 
+/// A gate handed over to human review after failing too often.
+#[derive(Debug, Clone)]
+pub struct Escalation {
+    /// Review the gate's owner is asked to complete
+    pub spec: HumanReviewSpec,
+    /// Context sent along with the review request
+    pub context: ReviewContext,
+    /// Findings of every failed run, oldest first
+    pub findings: Vec<Finding>,
+}
+
+/// Counts failed runs of phase gates and escalates a gate to human review
+/// once it has failed [`PhaseGate::escalate_after_failures`] times in a row.
+///
+/// Runs are tracked per phase and task. A passing run resets the count, as
+/// does an escalation, so a gate that keeps failing after review escalates
+/// again rather than blocking forever.
+pub struct GateEscalation {
+    review: HumanReviewService,
+    failures: HashMap<(PhaseId, TaskId), Vec<Vec<Finding>>>,
+}
+
+impl GateEscalation {
+    /// Escalate through `review`'s notification channel.
+    pub fn new(review: HumanReviewService) -> Self {
+        Self { review, failures: HashMap::new() }
+    }
+
+    /// Failed runs of the gate for `phase` recorded for `task_id` since it
+    /// last passed or escalated.
+    pub fn failures(&self, phase: PhaseId, task_id: TaskId) -> usize {
+        self.failures.get(&(phase, task_id)).map_or(0, Vec::len)
+    }
+
+    /// Record the outcome of running `gate` for `task_id`.
+    ///
+    /// When `result` is the failure that reaches the gate's threshold its
+    /// decision becomes [`GateDecision::Escalate`] and a review request
+    /// carrying the findings of all failed runs is sent. A notification that
+    /// cannot be delivered is logged; the gate still escalates.
+    pub async fn record(
+        &mut self,
+        gate: &PhaseGate,
+        task_id: TaskId,
+        result: &mut GateResult,
+    ) -> Option<Escalation> {
+        let key = (gate.phase, task_id);
+        match result.decision {
+            GateDecision::Pass | GateDecision::PassWithWarnings => {
+                self.failures.remove(&key);
+                return None;
+            }
+            GateDecision::Fail => {}
+            GateDecision::Escalate | GateDecision::Cancelled => return None,
+        }
+
+        let findings = result
+            .check_results
+            .iter()
+            .filter(|r| !r.passed)
+            .flat_map(|r| r.findings.iter().cloned())
+            .collect();
+        let runs = self.failures.entry(key).or_default();
+        runs.push(findings);
+        if gate.escalate_after_failures == 0 || runs.len() < gate.escalate_after_failures as usize {
+            return None;
+        }
+
+        let runs = self.failures.remove(&key).unwrap_or_default();
+        result.decision = GateDecision::Escalate;
+        result.passed = false;
+
+        let escalation = self.escalation(gate, task_id, &result.gate_name, runs);
+        if let Err(e) = self.review.send_notification(&escalation.spec, &escalation.context).await {
+            tracing::warn!("Failed to send escalation for gate {}: {}", result.gate_name, e);
+        }
+        Some(escalation)
+    }
+
+    fn escalation(
+        &self,
+        gate: &PhaseGate,
+        task_id: TaskId,
+        gate_name: &str,
+        runs: Vec<Vec<Finding>>,
+    ) -> Escalation {
+        let description = format!(
+            "Gate {} for phase {} failed {} times in a row on task {}",
+            gate_name,
+            gate.phase,
+            runs.len(),
+            task_id
+        );
+        let check_results = runs
+            .iter()
+            .enumerate()
+            .flat_map(|(run, findings)| {
+                findings
+                    .iter()
+                    .map(move |f| format!("Run {}: [{:?}] {}", run + 1, f.severity, f.message))
+            })
+            .collect();
+        let mut files: Vec<String> = Vec::new();
+        for location in runs.iter().flatten().filter_map(|f| f.location.as_ref()) {
+            if !files.contains(&location.file) {
+                files.push(location.file.clone());
+            }
+        }
+
+        let spec = HumanReviewSpec {
+            reviewers: Vec::new(),
+            review_guide: format!("{}. Decide whether the task may proceed despite the failures.", description),
+            review_form: vec![
+                ReviewQuestion {
+                    question: "Allow the task past this gate?".to_string(),
+                    answer_type: AnswerType::YesNo,
+                    required: true,
+                },
+                ReviewQuestion {
+                    question: "What should change before the next run?".to_string(),
+                    answer_type: AnswerType::Text,
+                    required: false,
+                },
+            ],
+            timeout: self.review.default_timeout(),
+            auto_pass_threshold: None,
+        };
+        Escalation {
+            spec,
+            context: ReviewContext { description, files, check_results },
+            findings: runs.into_iter().flatten().collect(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -61,6 +203,7 @@ mod tests {
     #[test]
     fn test_quality_profile_creation() {
         let profile = QualityProfile {
+            id: devman_core::QualityProfileId::new(),
             name: "test-profile".to_string(),
             description: "Test profile".to_string(),
             checks: vec![],
@@ -78,11 +221,47 @@ mod tests {
             phase: PhaseId::new(),
             checks: vec![],
             strategy: GateStrategy::AllMustPass,
+            escalate_after_failures: 0,
         };
 
         assert!(!gate.phase.to_string().is_empty());
     }
 
+    #[tokio::test]
+    async fn test_passing_run_resets_escalation_count() {
+        use crate::human::NotificationChannel;
+
+        let gate = PhaseGate {
+            phase: PhaseId::new(),
+            checks: vec![],
+            strategy: GateStrategy::AllMustPass,
+            escalate_after_failures: 2,
+        };
+        let task = TaskId::new();
+        let mut escalation = GateEscalation::new(HumanReviewService::new(NotificationChannel::Console));
+        let mut run = |decision| GateResult {
+            gate_name: "ci".to_string(),
+            passed: decision == GateDecision::Pass,
+            check_results: vec![],
+            decision,
+        };
+
+        assert!(escalation.record(&gate, task, &mut run(GateDecision::Fail)).await.is_none());
+        assert!(escalation.record(&gate, task, &mut run(GateDecision::Pass)).await.is_none());
+        assert_eq!(escalation.failures(gate.phase, task), 0);
+        assert!(escalation.record(&gate, task, &mut run(GateDecision::Fail)).await.is_none());
+        assert!(escalation.record(&gate, task, &mut run(GateDecision::Cancelled)).await.is_none());
+
+        let mut result = run(GateDecision::Fail);
+        assert!(escalation.record(&gate, task, &mut result).await.is_some());
+        assert_eq!(result.decision, GateDecision::Escalate);
+
+        let never = PhaseGate { escalate_after_failures: 0, ..gate };
+        for _ in 0..5 {
+            assert!(escalation.record(&never, task, &mut run(GateDecision::Fail)).await.is_none());
+        }
+    }
+
     #[test]
     fn test_gate_strategy_warnings_allowed() {
         let strategy = GateStrategy::WarningsAllowed { max_warnings: 5 };
@@ -1,9 +1,10 @@
 //! Human collaboration for quality checks.
 
 use devman_core::{
-    HumanReviewResult, HumanReviewSpec, ReviewAnswer, ReviewQuestion, Severity,
+    AnswerType, AnswerValue, HumanReviewResult, HumanReviewSpec, ReviewAnswer, ReviewQuestion, Severity,
     QualityCategory,
 };
+use std::io::{BufRead, Write};
 use std::time::Duration;
 use serde_json::json;
 
@@ -53,6 +54,11 @@ impl HumanReviewService {
         self
     }
 
+    /// Timeout given to reviews this service creates.
+    pub fn default_timeout(&self) -> Duration {
+        self.default_timeout
+    }
+
     /// Send review notification.
     pub async fn send_notification(
         &self,
@@ -175,20 +181,89 @@ impl HumanReviewService {
     }
 
     /// Process review response.
+    ///
+    /// Every answer must belong to a question of `spec` and fit its answer
+    /// type, and every required question must be answered; otherwise nothing
+    /// is recorded.
     pub fn process_response(
         &self,
         spec: &HumanReviewSpec,
         answers: Vec<ReviewAnswer>,
-    ) -> HumanReviewResult {
+    ) -> Result<HumanReviewResult, AnswerError> {
+        for answer in &answers {
+            let question = spec
+                .review_form
+                .iter()
+                .find(|q| q.question == answer.question)
+                .ok_or_else(|| AnswerError::UnknownQuestion(answer.question.clone()))?;
+            validate_answer(&question.answer_type, &answer.answer).map_err(|e| AnswerError::Question {
+                question: question.question.clone(),
+                source: Box::new(e),
+            })?;
+        }
+        if let Some(missing) = spec
+            .review_form
+            .iter()
+            .find(|q| q.required && !answers.iter().any(|a| a.question == q.question))
+        {
+            return Err(AnswerError::MissingAnswer(missing.question.clone()));
+        }
+
         let approved = self.evaluate_review(spec, &answers);
 
-        HumanReviewResult {
+        Ok(HumanReviewResult {
             reviewer: "unknown".to_string(),
             reviewed_at: chrono::Utc::now(),
             answers,
             comments: String::new(),
             approved,
+        })
+    }
+
+    /// Ask the questions of `spec` one by one on `output`, reading answers
+    /// from `input`.
+    ///
+    /// An answer that cannot be read or fails validation is reported and
+    /// the question asked again. Optional questions can be skipped with an
+    /// empty line.
+    pub fn prompt_answers<R: BufRead, W: Write>(
+        &self,
+        spec: &HumanReviewSpec,
+        mut input: R,
+        mut output: W,
+    ) -> std::io::Result<Vec<ReviewAnswer>> {
+        let mut answers = Vec::new();
+        for question in &spec.review_form {
+            loop {
+                write!(output, "{} {}: ", question.question, answer_hint(&question.answer_type))?;
+                output.flush()?;
+
+                let mut line = String::new();
+                if input.read_line(&mut line)? == 0 {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::UnexpectedEof,
+                        format!("No answer to '{}'", question.question),
+                    ));
+                }
+                let line = line.trim();
+                if line.is_empty() {
+                    if question.required {
+                        writeln!(output, "This question is required.")?;
+                        continue;
+                    }
+                    break;
+                }
+
+                match parse_answer(&question.answer_type, line) {
+                    Ok(answer) => {
+                        answers.push(ReviewAnswer { question: question.question.clone(), answer });
+                        break;
+                    }
+                    Err(e) => writeln!(output, "Invalid answer: {}. Please try again.", e)?,
+                }
+            }
         }
+        Ok(answers)
     }
 
     /// Evaluate if review should pass.
@@ -208,6 +283,201 @@ impl HumanReviewService {
     }
 }
 
+/// A review answer that does not fit its question.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AnswerError {
+    /// The answer is of a different kind than the question asks for
+    #[error("expected {expected}")]
+    WrongType {
+        /// What the question asks for
+        expected: &'static str,
+    },
+
+    /// Input that cannot be read as the expected kind of answer
+    #[error("cannot read '{input}' as {expected}")]
+    Unparseable {
+        /// The raw input
+        input: String,
+        /// What the question asks for
+        expected: &'static str,
+    },
+
+    /// A rating outside the allowed range
+    #[error("rating {value} is outside {min}..={max}")]
+    OutOfRange {
+        /// The given rating
+        value: i32,
+        /// Lowest allowed rating
+        min: i32,
+        /// Highest allowed rating
+        max: i32,
+    },
+
+    /// A selection that is not one of the options
+    #[error("'{value}' is not one of: {}", .options.join(", "))]
+    InvalidOption {
+        /// The given selection
+        value: String,
+        /// The allowed options
+        options: Vec<String>,
+    },
+
+    /// The same option selected more than once
+    #[error("'{0}' is selected more than once")]
+    DuplicateOption(String),
+
+    /// Text that does not match the required pattern
+    #[error("'{value}' does not match {pattern}")]
+    NoMatch {
+        /// The given text
+        value: String,
+        /// The required pattern
+        pattern: String,
+    },
+
+    /// The question's own pattern is not a valid regular expression
+    #[error("invalid pattern {pattern}: {message}")]
+    InvalidPattern {
+        /// The pattern
+        pattern: String,
+        /// Why it does not compile
+        message: String,
+    },
+
+    /// An answer to a question the form does not have
+    #[error("'{0}' is not a question of this review")]
+    UnknownQuestion(String),
+
+    /// A required question without an answer
+    #[error("required question '{0}' is not answered")]
+    MissingAnswer(String),
+
+    /// An invalid answer to a specific question
+    #[error("{question}: {source}")]
+    Question {
+        /// The question
+        question: String,
+        /// What is wrong with the answer
+        source: Box<AnswerError>,
+    },
+}
+
+/// Check that `value` is a valid answer to a question of type `answer_type`.
+pub fn validate_answer(answer_type: &AnswerType, value: &AnswerValue) -> Result<(), AnswerError> {
+    match (answer_type, value) {
+        (AnswerType::YesNo, AnswerValue::YesNo(_)) | (AnswerType::Text, AnswerValue::Text(_)) => Ok(()),
+        (AnswerType::Rating { min, max }, AnswerValue::Rating(value)) => {
+            if (*min..=*max).contains(value) {
+                Ok(())
+            } else {
+                Err(AnswerError::OutOfRange { value: *value, min: *min, max: *max })
+            }
+        }
+        (AnswerType::Choice { options }, AnswerValue::Choice(value)) => check_option(options, value),
+        (AnswerType::MultiSelect { options }, AnswerValue::MultiSelect(values)) => {
+            for (i, value) in values.iter().enumerate() {
+                check_option(options, value)?;
+                if values[..i].contains(value) {
+                    return Err(AnswerError::DuplicateOption(value.clone()));
+                }
+            }
+            Ok(())
+        }
+        (AnswerType::Regex { pattern }, AnswerValue::Text(value)) => {
+            let regex = regex::Regex::new(&format!("^(?:{})$", pattern)).map_err(|e| AnswerError::InvalidPattern {
+                pattern: pattern.clone(),
+                message: e.to_string(),
+            })?;
+            if regex.is_match(value) {
+                Ok(())
+            } else {
+                Err(AnswerError::NoMatch { value: value.clone(), pattern: pattern.clone() })
+            }
+        }
+        (answer_type, _) => Err(AnswerError::WrongType { expected: expected_kind(answer_type) }),
+    }
+}
+
+/// Read a typed-in answer to a question of type `answer_type`, validated.
+///
+/// Yes/no accepts `y`, `yes`, `true`, `是` and their negatives; choices
+/// accept the option itself or its 1-based number; multi-select takes a
+/// comma-separated list of those.
+pub fn parse_answer(answer_type: &AnswerType, input: &str) -> Result<AnswerValue, AnswerError> {
+    let input = input.trim();
+    let unparseable = || AnswerError::Unparseable { input: input.to_string(), expected: expected_kind(answer_type) };
+    let value = match answer_type {
+        AnswerType::YesNo => match input.to_lowercase().as_str() {
+            "y" | "yes" | "true" | "是" => AnswerValue::YesNo(true),
+            "n" | "no" | "false" | "否" => AnswerValue::YesNo(false),
+            _ => return Err(unparseable()),
+        },
+        AnswerType::Rating { .. } => AnswerValue::Rating(input.parse().map_err(|_| unparseable())?),
+        AnswerType::Text | AnswerType::Regex { .. } => AnswerValue::Text(input.to_string()),
+        AnswerType::Choice { options } => AnswerValue::Choice(resolve_option(options, input)),
+        AnswerType::MultiSelect { options } => AnswerValue::MultiSelect(
+            input
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| resolve_option(options, item))
+                .collect(),
+        ),
+    };
+    validate_answer(answer_type, &value)?;
+    Ok(value)
+}
+
+/// The option numbered `input` (from 1), or `input` itself.
+fn resolve_option(options: &[String], input: &str) -> String {
+    input
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_sub(1))
+        .and_then(|i| options.get(i))
+        .cloned()
+        .unwrap_or_else(|| input.to_string())
+}
+
+fn check_option(options: &[String], value: &str) -> Result<(), AnswerError> {
+    if options.iter().any(|o| o == value) {
+        Ok(())
+    } else {
+        Err(AnswerError::InvalidOption { value: value.to_string(), options: options.to_vec() })
+    }
+}
+
+fn expected_kind(answer_type: &AnswerType) -> &'static str {
+    match answer_type {
+        AnswerType::YesNo => "yes or no",
+        AnswerType::Rating { .. } => "a whole-number rating",
+        AnswerType::Text => "text",
+        AnswerType::Choice { .. } => "one of the options",
+        AnswerType::MultiSelect { .. } => "a list of options",
+        AnswerType::Regex { .. } => "text in the required format",
+    }
+}
+
+/// What to type for a question, shown after it when prompting.
+fn answer_hint(answer_type: &AnswerType) -> String {
+    let numbered = |options: &[String]| {
+        options
+            .iter()
+            .enumerate()
+            .map(|(i, o)| format!("{}) {}", i + 1, o))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    match answer_type {
+        AnswerType::YesNo => "[y/n]".to_string(),
+        AnswerType::Rating { min, max } => format!("[{}-{}]", min, max),
+        AnswerType::Text => "[text]".to_string(),
+        AnswerType::Choice { options } => format!("[{}]", numbered(options)),
+        AnswerType::MultiSelect { options } => format!("[comma-separated: {}]", numbered(options)),
+        AnswerType::Regex { pattern } => format!("[format: {}]", pattern),
+    }
+}
+
 /// Context for a review request.
 #[derive(Debug, Clone)]
 pub struct ReviewContext {
@@ -409,12 +679,132 @@ mod tests {
             },
         ];
 
-        let result = service.process_response(&spec, answers);
+        let result = service.process_response(&spec, answers).unwrap();
 
         assert!(result.approved);
         assert!(result.reviewed_at <= chrono::Utc::now());
     }
 
+    fn options(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_rating_answers_must_be_in_range() {
+        let rating = AnswerType::Rating { min: 1, max: 5 };
+        assert!(matches!(parse_answer(&rating, " 5 "), Ok(AnswerValue::Rating(5))));
+        assert_eq!(
+            parse_answer(&rating, "6").unwrap_err(),
+            AnswerError::OutOfRange { value: 6, min: 1, max: 5 }
+        );
+        assert!(matches!(parse_answer(&rating, "great"), Err(AnswerError::Unparseable { .. })));
+        assert!(matches!(
+            validate_answer(&rating, &AnswerValue::YesNo(true)),
+            Err(AnswerError::WrongType { .. })
+        ));
+    }
+
+    #[test]
+    fn test_multi_select_answers_must_be_distinct_options() {
+        let select = AnswerType::MultiSelect { options: options(&["api", "cli", "docs"]) };
+        match parse_answer(&select, "api, 3").unwrap() {
+            AnswerValue::MultiSelect(values) => assert_eq!(values, ["api", "docs"]),
+            other => panic!("unexpected answer {:?}", other),
+        }
+
+        let err = parse_answer(&select, "api, web").unwrap_err();
+        assert_eq!(err.to_string(), "'web' is not one of: api, cli, docs");
+        assert_eq!(
+            validate_answer(&select, &AnswerValue::MultiSelect(options(&["cli", "cli"]))),
+            Err(AnswerError::DuplicateOption("cli".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_regex_answers_must_match_whole_pattern() {
+        let ticket = AnswerType::Regex { pattern: r"[A-Z]+-\d+".to_string() };
+        assert!(matches!(parse_answer(&ticket, "DEV-42"), Ok(AnswerValue::Text(t)) if t == "DEV-42"));
+        assert!(matches!(parse_answer(&ticket, "see DEV-42"), Err(AnswerError::NoMatch { .. })));
+
+        let broken = AnswerType::Regex { pattern: "(".to_string() };
+        assert!(matches!(parse_answer(&broken, "x"), Err(AnswerError::InvalidPattern { .. })));
+    }
+
+    #[test]
+    fn test_choice_and_yes_no_answers() {
+        let choice = AnswerType::Choice { options: options(&["low", "high"]) };
+        assert!(matches!(parse_answer(&choice, "2"), Ok(AnswerValue::Choice(c)) if c == "high"));
+        assert!(matches!(parse_answer(&choice, "medium"), Err(AnswerError::InvalidOption { .. })));
+
+        assert!(matches!(parse_answer(&AnswerType::YesNo, "是"), Ok(AnswerValue::YesNo(true))));
+        assert!(matches!(parse_answer(&AnswerType::YesNo, "maybe"), Err(AnswerError::Unparseable { .. })));
+    }
+
+    fn review_form() -> HumanReviewSpec {
+        HumanReviewSpec {
+            reviewers: vec![],
+            review_guide: String::new(),
+            review_form: vec![
+                ReviewQuestion {
+                    question: "Rate the quality".to_string(),
+                    answer_type: AnswerType::Rating { min: 1, max: 5 },
+                    required: true,
+                },
+                ReviewQuestion {
+                    question: "Affected areas".to_string(),
+                    answer_type: AnswerType::MultiSelect { options: options(&["api", "cli"]) },
+                    required: false,
+                },
+            ],
+            timeout: std::time::Duration::from_secs(3600),
+            auto_pass_threshold: None,
+        }
+    }
+
+    #[test]
+    fn test_process_response_rejects_invalid_answers() {
+        let service = HumanReviewService::new(NotificationChannel::Console);
+        let spec = review_form();
+
+        let out_of_range = vec![ReviewAnswer {
+            question: "Rate the quality".to_string(),
+            answer: AnswerValue::Rating(9),
+        }];
+        let err = service.process_response(&spec, out_of_range).unwrap_err();
+        assert_eq!(err.to_string(), "Rate the quality: rating 9 is outside 1..=5");
+
+        let unanswered = vec![ReviewAnswer {
+            question: "Affected areas".to_string(),
+            answer: AnswerValue::MultiSelect(options(&["api"])),
+        }];
+        assert_eq!(
+            service.process_response(&spec, unanswered).unwrap_err(),
+            AnswerError::MissingAnswer("Rate the quality".to_string())
+        );
+    }
+
+    #[test]
+    fn test_prompt_asks_again_after_invalid_input() {
+        let service = HumanReviewService::new(NotificationChannel::Console);
+        let spec = review_form();
+
+        let input = "\n7\n4\napi, web\n\n";
+        let mut output = Vec::new();
+        let answers = service.prompt_answers(&spec, input.as_bytes(), &mut output).unwrap();
+
+        // The optional question is skipped after its invalid answer
+        assert_eq!(answers.len(), 1);
+        assert!(matches!(answers[0].answer, AnswerValue::Rating(4)));
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("This question is required."));
+        assert!(output.contains("Invalid answer: rating 7 is outside 1..=5. Please try again."));
+        assert!(output.contains("Invalid answer: 'web' is not one of: api, cli. Please try again."));
+        assert_eq!(output.matches("Rate the quality [1-5]").count(), 3);
+
+        let eof = service.prompt_answers(&spec, "".as_bytes(), Vec::new()).unwrap_err();
+        assert_eq!(eof.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+
     #[tokio::test]
     async fn test_send_console_notification() {
         let service = HumanReviewService::new(NotificationChannel::Console);
@@ -5,18 +5,24 @@
 #![warn(missing_docs)]
 
 pub mod engine;
+pub mod cache;
 pub mod checks;
 pub mod custom;
 pub mod registry;
 pub mod gate;
 pub mod human;
 pub mod parser;
+pub mod summary;
+pub mod trend;
 
-pub use engine::{QualityEngine, BasicQualityEngine};
+pub use engine::{QualityEngine, BasicQualityEngine, overall_status};
+pub use cache::{CheckCache, CacheKey};
 pub use checks::{
     GenericCheckType, QualityCheckType, CustomCheckSpec,
     CommandSpec, ValidationSpec, OutputParser, MetricExtractor,
     HumanReviewSpec, ReviewQuestion, AnswerType, AnswerValue,
 };
-pub use gate::{QualityGateBuilder, QualityProfileBuilder};
+pub use gate::{Escalation, GateEscalation, QualityGateBuilder, QualityProfileBuilder};
 pub use registry::QualityCheckRegistry;
+pub use summary::{goal_quality_summary, GoalQualitySummary, SeverityCounts, TaskQualitySummary};
+pub use trend::{quality_trend, QualityTrend, RunSummary, TrendDirection};
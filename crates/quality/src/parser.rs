@@ -309,6 +309,87 @@ pub fn extract_metrics(
     metrics
 }
 
+/// Test counts from `cargo test` output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TestSummary {
+    /// Tests that passed
+    pub passed: u64,
+    /// Tests that failed
+    pub failed: u64,
+    /// Tests that were ignored
+    pub ignored: u64,
+}
+
+impl TestSummary {
+    /// Counts as `tests_passed`, `tests_failed` and `tests_ignored` metrics.
+    pub fn to_metrics(self) -> Vec<Metric> {
+        [("tests_passed", self.passed), ("tests_failed", self.failed), ("tests_ignored", self.ignored)]
+            .into_iter()
+            .map(|(name, value)| Metric {
+                name: name.to_string(),
+                value: value as f64,
+                unit: None,
+            })
+            .collect()
+    }
+}
+
+/// A failed test reported by `cargo test`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestFailure {
+    /// Full test path, e.g. `tests::it_works`
+    pub name: String,
+    /// Where the test panicked, as `(file, line, column)`
+    pub location: Option<(String, usize, usize)>,
+}
+
+/// Sum the `test result:` lines of `cargo test` output.
+///
+/// Workspaces print one line per test binary; `None` if there are none.
+pub fn parse_test_summary(output: &str) -> Option<TestSummary> {
+    let re = Regex::new(r"test result: \w+\. (\d+) passed; (\d+) failed; (\d+) ignored").ok()?;
+    let mut summary: Option<TestSummary> = None;
+    for caps in re.captures_iter(output) {
+        let total = summary.get_or_insert_with(TestSummary::default);
+        total.passed += caps[1].parse::<u64>().unwrap_or(0);
+        total.failed += caps[2].parse::<u64>().unwrap_or(0);
+        total.ignored += caps[3].parse::<u64>().unwrap_or(0);
+    }
+    summary
+}
+
+/// Failed tests in `cargo test` output, in the order they were reported.
+pub fn parse_test_failures(output: &str) -> Vec<TestFailure> {
+    let (Ok(failed), Ok(panicked)) = (
+        Regex::new(r"(?m)^test (\S+) \.\.\. FAILED\s*$"),
+        Regex::new(r"thread '([^']+)'(?: \(\d+\))? panicked at ([^\s:]+):(\d+):(\d+)"),
+    ) else {
+        return Vec::new();
+    };
+
+    let locations: HashMap<&str, (String, usize, usize)> = panicked
+        .captures_iter(output)
+        .filter_map(|caps| {
+            let name = caps.get(1)?.as_str();
+            let line = caps[3].parse().ok()?;
+            let column = caps[4].parse().ok()?;
+            Some((name, (caps[2].to_string(), line, column)))
+        })
+        .collect();
+
+    let mut failures: Vec<TestFailure> = Vec::new();
+    for caps in failed.captures_iter(output) {
+        let name = &caps[1];
+        if failures.iter().all(|f| f.name != name) {
+            failures.push(TestFailure {
+                name: name.to_string(),
+                location: locations.get(name).cloned(),
+            });
+        }
+    }
+    failures
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -531,4 +612,51 @@ mod tests {
         assert_eq!(result.get_float("float_val"), Some(3.14));
         assert_eq!(result.get("nonexistent"), None);
     }
+
+    const CARGO_TEST_OUTPUT: &str = "\
+running 3 tests
+test parser::tests::parses ... ok
+test parser::tests::rejects_empty ... FAILED
+test slow ... ignored
+
+failures:
+
+---- parser::tests::rejects_empty stdout ----
+
+thread 'parser::tests::rejects_empty' (4242) panicked at src/parser.rs:42:9:
+assertion failed: result.is_err()
+
+failures:
+    parser::tests::rejects_empty
+
+test result: FAILED. 1 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out; finished in 0.01s
+
+running 2 tests
+test api::lists ... ok
+test api::creates ... FAILED
+
+test result: FAILED. 1 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s
+";
+
+    #[test]
+    fn test_parse_test_summary_sums_binaries() {
+        let summary = parse_test_summary(CARGO_TEST_OUTPUT).unwrap();
+        assert_eq!(summary, TestSummary { passed: 2, failed: 2, ignored: 1 });
+        assert!(parse_test_summary("error: could not compile").is_none());
+    }
+
+    #[test]
+    fn test_parse_test_failures_with_locations() {
+        let failures = parse_test_failures(CARGO_TEST_OUTPUT);
+        assert_eq!(
+            failures,
+            vec![
+                TestFailure {
+                    name: "parser::tests::rejects_empty".to_string(),
+                    location: Some(("src/parser.rs".to_string(), 42, 9)),
+                },
+                TestFailure { name: "api::creates".to_string(), location: None },
+            ]
+        );
+    }
 }
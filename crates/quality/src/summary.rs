@@ -0,0 +1,411 @@
+//! Quality posture of a whole goal.
+//!
+//! [`goal_quality_summary`] reads the persisted [`QualityRun`]s of every
+//! task under a goal — the tasks of its project's phases and their
+//! sub-tasks — and aggregates the latest run of each: findings by severity,
+//! average coverage and the tasks whose gates currently fail.
+
+use std::collections::{HashMap, HashSet};
+
+use devman_core::{GoalId, QualityCheckId, QualityRun, Severity, TaskId, Time};
+use devman_storage::{Storage, StorageError};
+use serde::Serialize;
+
+/// Name of the metric coverage is reported under.
+const COVERAGE_METRIC: &str = "coverage";
+
+/// Findings counted by severity.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct SeverityCounts {
+    /// Informational findings
+    pub info: usize,
+    /// Warnings
+    pub warning: usize,
+    /// Errors
+    pub error: usize,
+    /// Critical findings
+    pub critical: usize,
+}
+
+impl SeverityCounts {
+    /// Count one finding of `severity`.
+    pub fn add(&mut self, severity: Severity) {
+        match severity {
+            Severity::Info => self.info += 1,
+            Severity::Warning => self.warning += 1,
+            Severity::Error => self.error += 1,
+            Severity::Critical => self.critical += 1,
+        }
+    }
+
+    /// Findings of every severity.
+    pub fn total(&self) -> usize {
+        self.info + self.warning + self.error + self.critical
+    }
+}
+
+/// Latest quality results of one task.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TaskQualitySummary {
+    /// Task the results belong to
+    pub task_id: TaskId,
+    /// Task title
+    pub title: String,
+    /// When the latest run happened; `None` if the task was never checked
+    pub run_at: Option<Time>,
+    /// Share of checks that passed in the latest run
+    pub pass_rate: Option<f32>,
+    /// Findings of the latest run
+    pub findings: SeverityCounts,
+    /// Coverage reported by the latest run, averaged over its checks
+    pub coverage: Option<f64>,
+    /// Blocking checks that failed in the latest run
+    pub failing_checks: Vec<QualityCheckId>,
+}
+
+impl TaskQualitySummary {
+    /// Whether a blocking check failed in the latest run.
+    pub fn gates_failing(&self) -> bool {
+        !self.failing_checks.is_empty()
+    }
+}
+
+/// Quality posture of a goal, from the latest run of each of its tasks.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GoalQualitySummary {
+    /// Goal summarized
+    pub goal_id: GoalId,
+    /// Every task under the goal, in phase order
+    pub tasks: Vec<TaskQualitySummary>,
+    /// Tasks with at least one quality run
+    pub checked_tasks: usize,
+    /// Findings across the latest runs
+    pub findings: SeverityCounts,
+    /// Mean coverage of the tasks that report it
+    pub average_coverage: Option<f64>,
+    /// Tasks whose latest run failed a blocking check
+    pub failing_tasks: Vec<TaskId>,
+}
+
+/// Summarize the quality of every task under `goal_id` from `storage`.
+///
+/// A failed check counts against the gates unless it is stored as
+/// advisory (`blocking: false`); checks that no longer exist count as
+/// blocking.
+pub async fn goal_quality_summary(
+    storage: &dyn Storage,
+    goal_id: GoalId,
+) -> Result<GoalQualitySummary, StorageError> {
+    let goal = storage.require_goal(goal_id).await?;
+    let phase_ids = match storage.load_project(goal.project_id).await? {
+        Some(project) => project.phases,
+        None => Vec::new(),
+    };
+
+    let mut pending = Vec::new();
+    for phase_id in phase_ids {
+        if let Some(phase) = storage.load_phase(phase_id).await? {
+            pending.extend(phase.tasks);
+        }
+    }
+    // Depth-first so each sub-task follows its parent
+    pending.reverse();
+
+    let mut seen = HashSet::new();
+    let mut blocking: HashMap<QualityCheckId, bool> = HashMap::new();
+    let mut tasks = Vec::new();
+    while let Some(task_id) = pending.pop() {
+        if !seen.insert(task_id) {
+            continue;
+        }
+        let Some(task) = storage.load_task(task_id).await? else {
+            continue;
+        };
+        pending.extend(task.children.iter().rev());
+
+        let latest = storage.list_quality_runs(task_id).await?.into_iter().max_by_key(|r| r.run_at);
+        let mut failing_checks = Vec::new();
+        if let Some(run) = &latest {
+            for result in run.results.iter().filter(|r| !r.passed) {
+                let is_blocking = match blocking.get(&result.check_id) {
+                    Some(is_blocking) => *is_blocking,
+                    None => {
+                        let is_blocking = storage
+                            .load_quality_check(result.check_id)
+                            .await?
+                            .is_none_or(|check| check.blocking);
+                        *blocking.entry(result.check_id).or_insert(is_blocking)
+                    }
+                };
+                if is_blocking {
+                    failing_checks.push(result.check_id);
+                }
+            }
+        }
+        tasks.push(summarize_task(task_id, task.title, latest.as_ref(), failing_checks));
+    }
+
+    let mut findings = SeverityCounts::default();
+    for task in &tasks {
+        findings.info += task.findings.info;
+        findings.warning += task.findings.warning;
+        findings.error += task.findings.error;
+        findings.critical += task.findings.critical;
+    }
+
+    Ok(GoalQualitySummary {
+        goal_id,
+        checked_tasks: tasks.iter().filter(|t| t.run_at.is_some()).count(),
+        findings,
+        average_coverage: mean(tasks.iter().filter_map(|t| t.coverage)),
+        failing_tasks: tasks.iter().filter(|t| t.gates_failing()).map(|t| t.task_id).collect(),
+        tasks,
+    })
+}
+
+fn summarize_task(
+    task_id: TaskId,
+    title: String,
+    latest: Option<&QualityRun>,
+    failing_checks: Vec<QualityCheckId>,
+) -> TaskQualitySummary {
+    let mut findings = SeverityCounts::default();
+    for finding in latest.iter().flat_map(|run| run.results.iter()).flat_map(|r| r.findings.iter()) {
+        findings.add(finding.severity);
+    }
+    let coverage = mean(
+        latest
+            .iter()
+            .flat_map(|run| run.results.iter())
+            .flat_map(|r| r.metrics.iter())
+            .filter(|m| m.name == COVERAGE_METRIC)
+            .map(|m| m.value),
+    );
+
+    TaskQualitySummary {
+        task_id,
+        title,
+        run_at: latest.map(|run| run.run_at),
+        pass_rate: latest.map(QualityRun::pass_rate),
+        findings,
+        coverage,
+        failing_checks,
+    }
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use devman_core::{
+        CheckDetails, Finding, Metric, Phase, PhaseId, Project, ProjectId, QualityCategory, QualityCheck,
+        QualityCheckResult, Task,
+    };
+    use devman_storage::JsonStorage;
+
+    fn task(title: &str, phase_id: PhaseId) -> Task {
+        Task {
+            id: TaskId::new(),
+            title: title.to_string(),
+            description: String::new(),
+            intent: devman_core::TaskIntent {
+                natural_language: title.to_string(),
+                context: devman_core::TaskContext {
+                    relevant_knowledge: vec![],
+                    similar_tasks: vec![],
+                    affected_files: vec![],
+                },
+                success_criteria: vec![],
+            },
+            steps: vec![],
+            inputs: vec![],
+            expected_outputs: vec![],
+            quality_gates: vec![],
+            status: devman_core::TaskStatus::Active,
+            progress: devman_core::TaskProgress::default(),
+            phase_id,
+            depends_on: vec![],
+            blocks: vec![],
+            parent: None,
+            children: vec![],
+            archived: false,
+            priority: devman_core::Priority::default().value(),
+            work_records: vec![],
+            actuals: None,
+            claim: None,
+            state: None,
+            merged_into: None,
+            base_priority: None,
+            due_at: None,
+            created_knowledge: Vec::new(),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    fn check(blocking: bool) -> QualityCheck {
+        QualityCheck {
+            id: QualityCheckId::new(),
+            name: "tests".to_string(),
+            description: String::new(),
+            check_type: devman_core::QualityCheckType::Generic(devman_core::GenericCheckType::TestsPass {
+                test_suite: String::new(),
+                min_coverage: None,
+            }),
+            severity: Severity::Error,
+            category: QualityCategory::Correctness,
+            timeout: None,
+            scope: devman_core::CheckScope::Full,
+            working_dir: None,
+            blocking,
+        }
+    }
+
+    fn result(check: &QualityCheck, passed: bool, severities: &[Severity], coverage: Option<f64>) -> QualityCheckResult {
+        QualityCheckResult {
+            check_id: check.id,
+            passed,
+            execution_time: std::time::Duration::ZERO,
+            details: CheckDetails { output: String::new(), exit_code: None, error: None, timeout: None },
+            findings: severities
+                .iter()
+                .map(|severity| Finding {
+                    severity: *severity,
+                    category: QualityCategory::Correctness,
+                    message: String::new(),
+                    location: None,
+                    suggestion: None,
+                })
+                .collect(),
+            metrics: coverage
+                .map(|value| Metric { name: COVERAGE_METRIC.to_string(), value, unit: Some("%".to_string()) })
+                .into_iter()
+                .collect(),
+            human_review: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_summary_aggregates_latest_runs_of_goal_tasks() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+
+        let phase_id = PhaseId::new();
+        let mut clean = task("Clean", phase_id);
+        let mut regressed = task("Regressed", phase_id);
+        let advisory = task("Advisory", phase_id);
+        let unchecked = task("Unchecked", phase_id);
+        let mut child = task("Child", phase_id);
+        child.parent = Some(clean.id);
+        clean.children = vec![child.id];
+        regressed.depends_on = vec![clean.id];
+        for t in [&clean, &regressed, &advisory, &unchecked, &child] {
+            storage.save_task(t).await.unwrap();
+        }
+        // A task outside the goal's phases is not counted
+        let outside = task("Outside", PhaseId::new());
+        storage.save_task(&outside).await.unwrap();
+
+        storage
+            .save_phase(&Phase {
+                id: phase_id,
+                name: "Build".to_string(),
+                description: String::new(),
+                objectives: vec![],
+                acceptance_criteria: vec![],
+                tasks: vec![clean.id, regressed.id, advisory.id, unchecked.id],
+                depends_on: vec![],
+                status: devman_core::PhaseStatus::InProgress,
+                progress: devman_core::PhaseProgress::default(),
+                estimated_duration: None,
+                actual_duration: None,
+                quality_profile: None,
+                created_at: chrono::Utc::now(),
+            })
+            .await
+            .unwrap();
+        let project_id = ProjectId::new();
+        storage
+            .save_project(&Project {
+                id: project_id,
+                name: "Quality".to_string(),
+                description: String::new(),
+                config: devman_core::ProjectConfig {
+                    tech_stack: vec![],
+                    structure: devman_core::DirStructure { dirs: vec![], conventions: vec![] },
+                    quality_profile: devman_core::QualityProfileId::default(),
+                    default_task_profile: None,
+                    tools: devman_core::ToolConfig {
+                        build: devman_core::BuildTool::Cargo,
+                        test_framework: devman_core::TestFramework::Rust,
+                        linters: vec![],
+                        formatters: vec![],
+                    },
+                },
+                phases: vec![phase_id],
+                current_phase: phase_id,
+                created_at: chrono::Utc::now(),
+            })
+            .await
+            .unwrap();
+        let goal = devman_core::Goal {
+            id: GoalId::new(),
+            title: "Ship".to_string(),
+            description: String::new(),
+            success_criteria: vec![],
+            progress: devman_core::GoalProgress::default(),
+            project_id,
+            current_phase: phase_id,
+            status: devman_core::GoalStatus::Active,
+            status_history: vec![],
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+        storage.save_goal(&goal).await.unwrap();
+
+        let tests = check(true);
+        let lint = check(false);
+        storage.save_quality_check(&tests).await.unwrap();
+        storage.save_quality_check(&lint).await.unwrap();
+
+        let earlier = chrono::Utc::now() - chrono::Duration::hours(1);
+        let now = chrono::Utc::now();
+        let runs = [
+            QualityRun::new(clean.id, vec![result(&tests, true, &[Severity::Info], Some(90.0))], now),
+            // Only the latest run of a task counts
+            QualityRun::new(regressed.id, vec![result(&tests, true, &[], Some(80.0))], earlier),
+            QualityRun::new(
+                regressed.id,
+                vec![result(&tests, false, &[Severity::Error, Severity::Critical], Some(60.0))],
+                now,
+            ),
+            QualityRun::new(advisory.id, vec![result(&lint, false, &[Severity::Warning, Severity::Warning], None)], now),
+            QualityRun::new(child.id, vec![result(&tests, false, &[Severity::Error], None)], now),
+            QualityRun::new(outside.id, vec![result(&tests, false, &[Severity::Critical], None)], now),
+        ];
+        for run in &runs {
+            storage.save_quality_run(run).await.unwrap();
+        }
+
+        let summary = goal_quality_summary(&storage, goal.id).await.unwrap();
+        let titles: Vec<_> = summary.tasks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, ["Clean", "Child", "Regressed", "Advisory", "Unchecked"]);
+        assert_eq!(summary.checked_tasks, 4);
+        assert_eq!(summary.findings, SeverityCounts { info: 1, warning: 2, error: 2, critical: 1 });
+        assert_eq!(summary.findings.total(), 6);
+        assert_eq!(summary.average_coverage, Some(75.0));
+        assert_eq!(summary.failing_tasks, [child.id, regressed.id]);
+        assert_eq!(summary.tasks[2].failing_checks, [tests.id]);
+        assert!(summary.tasks[3].failing_checks.is_empty());
+        assert_eq!(summary.tasks[4].run_at, None);
+
+        assert!(matches!(
+            goal_quality_summary(&storage, GoalId::new()).await,
+            Err(StorageError::NotFound { .. })
+        ));
+    }
+}
@@ -0,0 +1,139 @@
+//! Quality trends across a task's check runs.
+//!
+//! [`quality_trend`] compares the latest [`QualityRun`] of a task with the
+//! one before it, so a regression such as "tests started failing on the last
+//! attempt" is reported instead of hidden behind an overall status.
+
+use devman_core::QualityRun;
+use serde::Serialize;
+
+/// Direction quality moved between the last two runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrendDirection {
+    /// More checks passed, or as many passed with fewer findings
+    Improving,
+    /// Fewer checks passed, or as many passed with more findings
+    Regressing,
+    /// No change, or fewer than two runs to compare
+    Stable,
+}
+
+/// Pass rate and finding count of one run.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunSummary {
+    /// When the run happened
+    pub run_at: devman_core::Time,
+    /// Share of checks that passed, from 0.0 to 1.0
+    pub pass_rate: f32,
+    /// Findings across all checks
+    pub findings: usize,
+}
+
+/// How a task's quality changed across its runs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QualityTrend {
+    /// Every run, oldest first
+    pub runs: Vec<RunSummary>,
+    /// Change in pass rate from the previous run to the latest
+    pub pass_rate_change: f32,
+    /// Change in finding count from the previous run to the latest
+    pub finding_change: i64,
+    /// Overall direction of the latest run
+    pub direction: TrendDirection,
+}
+
+/// Compare the latest of `runs` (oldest first) with the one before it.
+///
+/// Pass rate decides the direction; the finding count only breaks ties.
+pub fn quality_trend(runs: &[QualityRun]) -> QualityTrend {
+    let runs: Vec<RunSummary> = runs
+        .iter()
+        .map(|run| RunSummary {
+            run_at: run.run_at,
+            pass_rate: run.pass_rate(),
+            findings: run.finding_count(),
+        })
+        .collect();
+
+    let (pass_rate_change, finding_change) = match runs.as_slice() {
+        [.., previous, latest] => (
+            latest.pass_rate - previous.pass_rate,
+            latest.findings as i64 - previous.findings as i64,
+        ),
+        _ => (0.0, 0),
+    };
+
+    let direction = if pass_rate_change < 0.0 {
+        TrendDirection::Regressing
+    } else if pass_rate_change > 0.0 {
+        TrendDirection::Improving
+    } else if finding_change > 0 {
+        TrendDirection::Regressing
+    } else if finding_change < 0 {
+        TrendDirection::Improving
+    } else {
+        TrendDirection::Stable
+    };
+
+    QualityTrend { runs, pass_rate_change, finding_change, direction }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use devman_core::{
+        CheckDetails, Finding, QualityCategory, QualityCheckId, QualityCheckResult, Severity, TaskId,
+    };
+
+    fn result(passed: bool, findings: usize) -> QualityCheckResult {
+        QualityCheckResult {
+            check_id: QualityCheckId::new(),
+            passed,
+            execution_time: std::time::Duration::ZERO,
+            details: CheckDetails { output: String::new(), exit_code: None, error: None, timeout: None },
+            findings: (0..findings)
+                .map(|i| Finding {
+                    severity: Severity::Warning,
+                    category: QualityCategory::Correctness,
+                    message: format!("finding {}", i),
+                    location: None,
+                    suggestion: None,
+                })
+                .collect(),
+            metrics: vec![],
+            human_review: None,
+        }
+    }
+
+    fn run(results: Vec<QualityCheckResult>) -> QualityRun {
+        QualityRun::new(TaskId::new(), results, chrono::Utc::now())
+    }
+
+    #[test]
+    fn test_direction_follows_pass_rate_then_findings() {
+        let passing = run(vec![result(true, 0), result(true, 1)]);
+        let failing = run(vec![result(true, 0), result(false, 0)]);
+        let noisier = run(vec![result(true, 2), result(true, 3)]);
+
+        let trend = quality_trend(&[passing.clone(), failing.clone()]);
+        assert_eq!(trend.direction, TrendDirection::Regressing);
+        assert_eq!(trend.pass_rate_change, -0.5);
+        assert_eq!(trend.runs.len(), 2);
+
+        assert_eq!(quality_trend(&[failing, passing.clone()]).direction, TrendDirection::Improving);
+
+        let trend = quality_trend(&[passing.clone(), noisier.clone()]);
+        assert_eq!(trend.direction, TrendDirection::Regressing);
+        assert_eq!(trend.finding_change, 4);
+        assert_eq!(quality_trend(&[noisier, passing]).direction, TrendDirection::Improving);
+    }
+
+    #[test]
+    fn test_single_run_is_stable() {
+        assert_eq!(quality_trend(&[]).direction, TrendDirection::Stable);
+        let trend = quality_trend(&[run(vec![result(false, 3)])]);
+        assert_eq!(trend.direction, TrendDirection::Stable);
+        assert_eq!(trend.finding_change, 0);
+    }
+}
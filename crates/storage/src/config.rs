@@ -0,0 +1,70 @@
+//! Typed access to runtime settings.
+//!
+//! Settings are [`ConfigEntry`] values kept in the store, so they can be
+//! changed with `devman config set` without recompiling or restarting with
+//! new flags. Readers fall back to their own defaults for keys that are
+//! unset, or whose stored value does not fit the expected type.
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::trait_::{ConfigEntry, Result, Storage};
+
+/// Keys of the settings DevMan reads.
+pub mod keys {
+    /// Seconds a quality check may run when it sets no timeout of its own
+    pub const QUALITY_CHECK_TIMEOUT_SECS: &str = "quality.check_timeout_secs";
+    /// Embedding model used for new vector search services
+    pub const KNOWLEDGE_EMBEDDING_MODEL: &str = "knowledge.embedding_model";
+    /// Most embedding requests in flight at once
+    pub const KNOWLEDGE_MAX_CONCURRENCY: &str = "knowledge.max_concurrency";
+    /// Token price used for cost estimates
+    pub const PROGRESS_PRICE_TABLE: &str = "progress.price_table";
+}
+
+/// Typed getters and setters over a store's settings.
+#[async_trait]
+pub trait ConfigStore {
+    /// Value of `key`, or `None` when it is unset.
+    ///
+    /// Fails when the stored value does not deserialize as `T`.
+    async fn get_config<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>>;
+
+    /// Value of `key`, or `default` when it is unset or cannot be read as
+    /// `T`; a bad value is logged rather than failing the caller.
+    async fn get_config_or<T: DeserializeOwned + Send>(&self, key: &str, default: T) -> T;
+
+    /// Store `value` under `key`, replacing any previous value.
+    async fn set_config<T: Serialize + Sync>(&mut self, key: &str, value: &T) -> Result<()>;
+}
+
+#[async_trait]
+impl<S: Storage + ?Sized> ConfigStore for S {
+    async fn get_config<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        match self.load_config_entry(key).await? {
+            Some(entry) => Ok(Some(serde_json::from_value(entry.value)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn get_config_or<T: DeserializeOwned + Send>(&self, key: &str, default: T) -> T {
+        match self.get_config(key).await {
+            Ok(Some(value)) => value,
+            Ok(None) => default,
+            Err(e) => {
+                tracing::warn!("Ignoring setting {}: {}", key, e);
+                default
+            }
+        }
+    }
+
+    async fn set_config<T: Serialize + Sync>(&mut self, key: &str, value: &T) -> Result<()> {
+        let entry = ConfigEntry {
+            key: key.to_string(),
+            value: serde_json::to_value(value)?,
+            updated_at: chrono::Utc::now(),
+        };
+        self.save_config_entry(&entry).await
+    }
+}
@@ -9,16 +9,70 @@ use std::sync::Arc;
 use devman_core::{
     Goal, GoalId, Project, ProjectId, Phase, PhaseId, Task, TaskId, TaskFilter,
     Event, EventId, Knowledge, KnowledgeId, QualityCheck, QualityCheckId,
-    WorkRecord, WorkRecordId, KnowledgeEmbedding,
+    WorkRecord, WorkRecordId, KnowledgeEmbedding, ActiveEmbeddingModel, ReindexCheckpoint, Blocker, BlockerId, QualityProfile, QualityProfileId, QualityRun, TaskTemplate, TaskTemplateId, BlobId, Time,
 };
-use super::{Storage, StorageError, Result};
+use super::{Storage, StorageError, Result, CompactReport, ConfigEntry, IdempotencyRecord};
+use futures::stream::BoxStream;
 use tokio::fs;
 use tokio::sync::Mutex;
 
+/// Layout of the JSON files [`JsonStorage`] writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JsonFormat {
+    /// One line per file
+    Compact,
+    /// Indented, with fields in declaration order
+    #[default]
+    Pretty,
+    /// Indented, with object keys sorted at every level, so the same data
+    /// always produces the same bytes and an edit to one field changes only
+    /// its own lines in a Git diff
+    Sorted,
+}
+
+impl JsonFormat {
+    /// Serialize `value` in this layout.
+    pub fn render<T: serde::Serialize + ?Sized>(self, value: &T) -> serde_json::Result<String> {
+        match self {
+            JsonFormat::Compact => serde_json::to_string(value),
+            JsonFormat::Pretty => serde_json::to_string_pretty(value),
+            // `serde_json::Map` is a `BTreeMap` unless the `preserve_order`
+            // feature is on, so going through `Value` sorts every object
+            JsonFormat::Sorted => serde_json::to_string_pretty(&serde_json::to_value(value)?),
+        }
+    }
+}
+
+/// Age after which a task lock file is assumed to be left by a crashed
+/// writer and may be taken over.
+const STALE_LOCK_AGE: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Directories holding one `<id>.json` file per entity, each with a
+/// `meta/<dir>` directory for its version markers and locks.
+const ENTITY_DIRS: [&str; 11] = [
+    "goals",
+    "projects",
+    "phases",
+    "tasks",
+    "events",
+    "knowledge",
+    "quality",
+    "work_records",
+    "blockers",
+    "quality_profiles",
+    "task_templates",
+];
+
+/// Directories holding one `<task id>.json` file per task alongside it.
+const TASK_SIDE_DIRS: [&str; 2] = ["quality_runs", "meta/work_records_by_task"];
+
 /// File-based JSON storage backend.
 pub struct JsonStorage {
     root: std::path::PathBuf,
+    format: JsonFormat,
     pending: Arc<Mutex<bool>>,
+    #[cfg(test)]
+    record_loads: std::sync::atomic::AtomicUsize,
 }
 
 impl JsonStorage {
@@ -27,31 +81,46 @@ impl JsonStorage {
     pub async fn new(root: impl AsRef<Path>) -> Result<Self> {
         let root = root.as_ref().to_path_buf();
 
-        // Ensure primary directories
-        fs::create_dir_all(root.join("goals")).await?;
-        fs::create_dir_all(root.join("projects")).await?;
-        fs::create_dir_all(root.join("phases")).await?;
-        fs::create_dir_all(root.join("tasks")).await?;
-        fs::create_dir_all(root.join("events")).await?;
-        fs::create_dir_all(root.join("knowledge")).await?;
+        // Entity directories and their meta markers
+        for dir in ENTITY_DIRS {
+            fs::create_dir_all(root.join(dir)).await?;
+            fs::create_dir_all(root.join("meta").join(dir)).await?;
+        }
         fs::create_dir_all(root.join("embeddings")).await?;
-        fs::create_dir_all(root.join("quality")).await?;
-        fs::create_dir_all(root.join("work_records")).await?;
-
-        // Directories for meta/versioning (only meta markers are stored)
-        fs::create_dir_all(root.join("meta").join("goals")).await?;
-        fs::create_dir_all(root.join("meta").join("projects")).await?;
-        fs::create_dir_all(root.join("meta").join("phases")).await?;
-        fs::create_dir_all(root.join("meta").join("tasks")).await?;
-        fs::create_dir_all(root.join("meta").join("events")).await?;
-        fs::create_dir_all(root.join("meta").join("knowledge")).await?;
-        fs::create_dir_all(root.join("meta").join("quality")).await?;
-        fs::create_dir_all(root.join("meta").join("work_records")).await?;
-
-        Ok(Self {
+        fs::create_dir_all(root.join("blobs")).await?;
+        fs::create_dir_all(root.join("quality_runs")).await?;
+
+        // Work record ids per task; built from existing records on first use
+        let index_dir = root.join("meta").join("work_records_by_task");
+        let needs_index = !fs::try_exists(&index_dir).await?;
+        fs::create_dir_all(&index_dir).await?;
+
+        // Knowledge ids with their updated_at, for recency queries
+        let needs_knowledge_index = !fs::try_exists(root.join("meta").join("knowledge_by_updated.json")).await?;
+
+        let storage = Self {
             root,
+            format: JsonFormat::default(),
             pending: Arc::new(Mutex::new(false)),
-        })
+            #[cfg(test)]
+            record_loads: std::sync::atomic::AtomicUsize::new(0),
+        };
+
+        if needs_index {
+            storage.rebuild_work_record_index().await?;
+        }
+        if needs_knowledge_index {
+            storage.rebuild_knowledge_index().await?;
+        }
+
+        Ok(storage)
+    }
+
+    /// Write files in `format` from now on; existing files are rewritten
+    /// in it the next time they are saved.
+    pub fn with_format(mut self, format: JsonFormat) -> Self {
+        self.format = format;
+        self
     }
 
     fn goal_path(&self, id: GoalId) -> std::path::PathBuf {
@@ -75,9 +144,25 @@ impl JsonStorage {
     fn embedding_path(&self, knowledge_id: &str) -> std::path::PathBuf {
         self.root.join("embeddings").join(format!("{}.json", knowledge_id))
     }
+    fn blocker_path(&self, id: BlockerId) -> std::path::PathBuf {
+        self.root.join("blockers").join(format!("{}.json", id))
+    }
+    fn quality_profile_path(&self, id: QualityProfileId) -> std::path::PathBuf {
+        self.root.join("quality_profiles").join(format!("{}.json", id))
+    }
+    fn task_template_path(&self, id: TaskTemplateId) -> std::path::PathBuf {
+        self.root.join("task_templates").join(format!("{}.json", id))
+    }
+    fn blob_path(&self, id: &BlobId) -> std::path::PathBuf {
+        self.root.join("blobs").join(&id.0)
+    }
     fn quality_check_path(&self, id: QualityCheckId) -> std::path::PathBuf {
         self.root.join("quality").join(format!("{}.json", id))
     }
+    fn quality_runs_path(&self, task_id: TaskId) -> std::path::PathBuf {
+        self.root.join("quality_runs").join(format!("{}.json", task_id))
+    }
+
     fn work_record_path(&self, id: WorkRecordId) -> std::path::PathBuf {
         self.root.join("work_records").join(format!("{}.json", id))
     }
@@ -85,7 +170,91 @@ impl JsonStorage {
     fn meta_path(&self, kind: &str, id: &str) -> std::path::PathBuf {
         self.root.join("meta").join(kind).join(format!("{}.meta.json", id))
     }
+    fn work_record_index_path(&self, task_id: TaskId) -> std::path::PathBuf {
+        self.root.join("meta").join("work_records_by_task").join(format!("{}.json", task_id))
+    }
+    fn knowledge_index_path(&self) -> std::path::PathBuf {
+        self.root.join("meta").join("knowledge_by_updated.json")
+    }
+    fn embedding_model_path(&self) -> std::path::PathBuf {
+        self.root.join("meta").join("embedding_model.json")
+    }
+    fn reindex_checkpoint_path(&self) -> std::path::PathBuf {
+        self.root.join("meta").join("reindex_checkpoint.json")
+    }
+    fn idempotency_path(&self) -> std::path::PathBuf {
+        self.root.join("meta").join("idempotency_keys.json")
+    }
+    fn pinned_blobs_path(&self) -> std::path::PathBuf {
+        self.root.join("meta").join("pinned_blobs.json")
+    }
+    fn config_path(&self) -> std::path::PathBuf {
+        self.root.join("meta").join("config.json")
+    }
 
+    /// Read all settings, keyed by setting name.
+    async fn read_config_entries(&self) -> Result<std::collections::BTreeMap<String, ConfigEntry>> {
+        Ok(read_json(&self.config_path()).await?.unwrap_or_default())
+    }
+
+    /// Read all idempotency records, keyed by idempotency key.
+    async fn read_idempotency_records(&self) -> Result<std::collections::HashMap<String, IdempotencyRecord>> {
+        Ok(read_json(&self.idempotency_path()).await?.unwrap_or_default())
+    }
+
+    async fn write_idempotency_records(
+        &self,
+        records: &std::collections::HashMap<String, IdempotencyRecord>,
+    ) -> Result<()> {
+        fs::write(self.idempotency_path(), self.format.render(records)?.as_bytes()).await?;
+        Ok(())
+    }
+
+    async fn read_pinned_blobs(&self) -> Result<Vec<BlobId>> {
+        Ok(read_json(&self.pinned_blobs_path()).await?.unwrap_or_default())
+    }
+
+    /// Read the ids of a task's work records from the index.
+    async fn read_work_record_index(&self, task_id: TaskId) -> Result<Vec<WorkRecordId>> {
+        Ok(read_json(&self.work_record_index_path(task_id)).await?.unwrap_or_default())
+    }
+
+    /// Replace the ids in a task's work record index.
+    async fn write_work_record_index(&self, task_id: TaskId, ids: &[WorkRecordId]) -> Result<()> {
+        fs::write(self.work_record_index_path(task_id), self.format.render(&ids)?.as_bytes()).await?;
+        Ok(())
+    }
+
+    /// Rebuild the task -> work record index by scanning all records.
+    async fn rebuild_work_record_index(&self) -> Result<()> {
+        let records: Vec<WorkRecord> = list_dir(&self.root.join("work_records")).await?;
+        let mut by_task: std::collections::HashMap<TaskId, Vec<WorkRecordId>> = Default::default();
+        for record in records {
+            by_task.entry(record.task_id).or_default().push(record.id);
+        }
+        for (task_id, ids) in by_task {
+            self.write_work_record_index(task_id, &ids).await?;
+        }
+        Ok(())
+    }
+
+    /// Read the knowledge recency index.
+    async fn read_knowledge_index(&self) -> Result<Vec<(KnowledgeId, Time)>> {
+        Ok(read_json(&self.knowledge_index_path()).await?.unwrap_or_default())
+    }
+
+    /// Write the knowledge recency index, keeping it sorted newest first.
+    async fn write_knowledge_index(&self, mut entries: Vec<(KnowledgeId, Time)>) -> Result<()> {
+        entries.sort_by(|a, b| b.1.cmp(&a.1));
+        fs::write(self.knowledge_index_path(), self.format.render(&entries)?.as_bytes()).await?;
+        Ok(())
+    }
+
+    /// Rebuild the knowledge recency index by scanning all knowledge.
+    async fn rebuild_knowledge_index(&self) -> Result<()> {
+        let items: Vec<Knowledge> = list_dir(&self.root.join("knowledge")).await?;
+        self.write_knowledge_index(items.into_iter().map(|k| (k.id, k.updated_at)).collect()).await
+    }
 
     async fn set_pending(&self) {
         *self.pending.lock().await = true;
@@ -95,10 +264,9 @@ impl JsonStorage {
         *self.pending.lock().await
     }
 
-    /// Read and increment per-object version, return new version.
-    async fn bump_version(&self, kind: &str, id: &str) -> Result<u64> {
+    /// Read per-object version; 0 when there is no meta marker yet.
+    async fn read_version(&self, kind: &str, id: &str) -> u64 {
         let path = self.meta_path(kind, id);
-        // Read existing
         let mut version = 0u64;
         match fs::read_to_string(&path).await {
             Ok(s) => {
@@ -112,9 +280,15 @@ impl JsonStorage {
                 // ignore missing
             }
         }
-        version += 1;
+        version
+    }
+
+    /// Read and increment per-object version, return new version.
+    async fn bump_version(&self, kind: &str, id: &str) -> Result<u64> {
+        let path = self.meta_path(kind, id);
+        let version = self.read_version(kind, id).await + 1;
         let meta = serde_json::json!({"version": version, "updated_at": chrono::Utc::now()});
-        let _ = fs::write(&path, serde_json::to_string_pretty(&meta)?.as_bytes()).await?;
+        let _ = fs::write(&path, self.format.render(&meta)?.as_bytes()).await?;
         Ok(version)
     }
 
@@ -124,7 +298,7 @@ impl JsonStorage {
 impl Storage for JsonStorage {
     async fn save_goal(&mut self, goal: &Goal) -> Result<()> {
         let path = self.goal_path(goal.id);
-        let json = serde_json::to_string_pretty(goal)?;
+        let json = self.format.render(goal)?;
         fs::write(&path, json.as_bytes()).await?;
 
         // Versioning (meta only)
@@ -145,7 +319,7 @@ impl Storage for JsonStorage {
 
     async fn save_project(&mut self, project: &Project) -> Result<()> {
         let path = self.project_path(project.id);
-        let json = serde_json::to_string_pretty(project)?;
+        let json = self.format.render(project)?;
         fs::write(&path, json.as_bytes()).await?;
 
         let id_str = format!("{}", project.id);
@@ -159,9 +333,13 @@ impl Storage for JsonStorage {
         read_json(&self.project_path(id)).await
     }
 
+    async fn list_projects(&self) -> Result<Vec<Project>> {
+        list_dir(&self.root.join("projects")).await
+    }
+
     async fn save_phase(&mut self, phase: &Phase) -> Result<()> {
         let path = self.phase_path(phase.id);
-        let json = serde_json::to_string_pretty(phase)?;
+        let json = self.format.render(phase)?;
         fs::write(&path, json.as_bytes()).await?;
 
         let id_str = format!("{}", phase.id);
@@ -175,9 +353,13 @@ impl Storage for JsonStorage {
         read_json(&self.phase_path(id)).await
     }
 
+    async fn list_phases(&self) -> Result<Vec<Phase>> {
+        list_dir(&self.root.join("phases")).await
+    }
+
     async fn save_task(&mut self, task: &Task) -> Result<()> {
         let path = self.task_path(task.id);
-        let json = serde_json::to_string_pretty(task)?;
+        let json = self.format.render(task)?;
         fs::write(&path, json.as_bytes()).await?;
 
         let id_str = format!("{}", task.id);
@@ -191,30 +373,101 @@ impl Storage for JsonStorage {
         read_json(&self.task_path(id)).await
     }
 
+    async fn task_version(&self, id: TaskId) -> Result<u64> {
+        Ok(self.read_version("tasks", &id.to_string()).await)
+    }
+
+    async fn save_task_if_version(&mut self, task: &Task, expected: u64) -> Result<u64> {
+        let id_str = task.id.to_string();
+        let conflict = || StorageError::Conflict {
+            entity_type: "task".to_string(),
+            id: id_str.clone(),
+            expected,
+        };
+
+        // Only one writer at a time may hold the lock file; a writer that
+        // finds it taken lost the race. Locks left behind by a crashed
+        // writer are cleared once stale.
+        let lock = self.root.join("meta").join("tasks").join(format!("{}.lock", id_str));
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        if let Err(e) = options.open(&lock).await {
+            if e.kind() != std::io::ErrorKind::AlreadyExists {
+                return Err(e.into());
+            }
+            let stale = fs::metadata(&lock)
+                .await
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age > STALE_LOCK_AGE);
+            if !stale {
+                return Err(conflict());
+            }
+            let _ = fs::remove_file(&lock).await;
+            options.open(&lock).await.map_err(|_| conflict())?;
+        }
+
+        let result = async {
+            if self.read_version("tasks", &id_str).await != expected {
+                return Err(conflict());
+            }
+            // Replace the file in one step so concurrent readers never see
+            // a partly written task
+            let path = self.task_path(task.id);
+            let tmp = path.with_extension("json.tmp");
+            fs::write(&tmp, self.format.render(task)?.as_bytes()).await?;
+            fs::rename(&tmp, &path).await?;
+            self.bump_version("tasks", &id_str).await
+        }
+        .await;
+
+        let _ = fs::remove_file(&lock).await;
+        if result.is_ok() {
+            self.set_pending().await;
+        }
+        result
+    }
+
     async fn list_tasks(&self, filter: &TaskFilter) -> Result<Vec<Task>> {
-        let all = list_dir(&self.root.join("tasks")).await?;
-        Ok(all.into_iter()
-            .filter(|t: &Task| {
-                if let Some(statuses) = &filter.status {
-                    statuses.contains(&t.status)
-                } else {
-                    true
+        let all: Vec<Task> = list_dir(&self.root.join("tasks")).await?;
+        let matches = |t: &Task| {
+            filter.status.as_ref().is_none_or(|statuses| statuses.contains(&t.status))
+                && (!filter.roots_only || t.parent.is_none())
+        };
+        if !(filter.roots_only && filter.include_subtasks) {
+            return Ok(all.into_iter().filter(matches).collect());
+        }
+
+        let by_id: std::collections::HashMap<TaskId, &Task> = all.iter().map(|t| (t.id, t)).collect();
+        let mut seen = std::collections::HashSet::new();
+        let mut listed = Vec::new();
+        for root in all.iter().filter(|t| matches(t)) {
+            let mut stack = vec![root];
+            while let Some(task) = stack.pop() {
+                if seen.insert(task.id) {
+                    listed.push(task.clone());
+                    stack.extend(task.children.iter().rev().filter_map(|id| by_id.get(id).copied()));
                 }
-            })
-            .collect())
+            }
+        }
+        Ok(listed)
     }
 
     async fn delete_task(&mut self, id: TaskId) -> Result<()> {
-        fs::remove_file(self.task_path(id)).await.or_else(|e| {
-            if e.kind() == std::io::ErrorKind::NotFound { Ok(()) } else { Err(e) }
-        })?;
+        let side_files = TASK_SIDE_DIRS.iter().map(|dir| self.root.join(dir).join(format!("{}.json", id)));
+        for path in std::iter::once(self.task_path(id)).chain(side_files) {
+            fs::remove_file(path).await.or_else(|e| {
+                if e.kind() == std::io::ErrorKind::NotFound { Ok(()) } else { Err(e) }
+            })?;
+        }
         self.set_pending().await;
         Ok(())
     }
 
     async fn save_event(&mut self, event: &Event) -> Result<()> {
         let path = self.event_path(event.id);
-        let json = serde_json::to_string_pretty(event)?;
+        let json = self.format.render(event)?;
         fs::write(&path, json.as_bytes()).await?;
 
         let id_str = format!("{}", event.id);
@@ -234,14 +487,27 @@ impl Storage for JsonStorage {
         Ok(events)
     }
 
+    async fn delete_event(&mut self, id: EventId) -> Result<()> {
+        fs::remove_file(self.event_path(id)).await.or_else(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound { Ok(()) } else { Err(e) }
+        })?;
+        self.set_pending().await;
+        Ok(())
+    }
+
     async fn save_knowledge(&mut self, knowledge: &Knowledge) -> Result<()> {
         let path = self.knowledge_path(knowledge.id);
-        let json = serde_json::to_string_pretty(knowledge)?;
+        let json = self.format.render(knowledge)?;
         fs::write(&path, json.as_bytes()).await?;
 
         let id_str = format!("{}", knowledge.id);
         let _ver = self.bump_version("knowledge", &id_str).await?;
 
+        let mut entries = self.read_knowledge_index().await?;
+        entries.retain(|(id, _)| *id != knowledge.id);
+        entries.push((knowledge.id, knowledge.updated_at));
+        self.write_knowledge_index(entries).await?;
+
         self.set_pending().await;
         Ok(())
     }
@@ -254,11 +520,25 @@ impl Storage for JsonStorage {
         list_dir(&self.root.join("knowledge")).await
     }
 
+    async fn list_recent_knowledge(&self, limit: usize) -> Result<Vec<Knowledge>> {
+        let mut items = Vec::new();
+        for (id, _) in self.read_knowledge_index().await? {
+            if items.len() == limit {
+                break;
+            }
+            if let Some(knowledge) = self.load_knowledge(id).await? {
+                items.push(knowledge);
+            }
+        }
+        Ok(items)
+    }
+
     // === Vector Embedding operations ===
 
     async fn save_vector_embedding(&mut self, embedding: &KnowledgeEmbedding) -> Result<()> {
+        super::trait_::guard_embedding_model(self, embedding).await?;
         let path = self.embedding_path(&embedding.knowledge_id.to_string());
-        let json = serde_json::to_string_pretty(embedding)?;
+        let json = self.format.render(embedding)?;
         fs::write(&path, json.as_bytes()).await?;
         self.set_pending().await;
         Ok(())
@@ -272,9 +552,125 @@ impl Storage for JsonStorage {
         list_dir(&self.root.join("embeddings")).await
     }
 
+    async fn load_active_embedding_model(&self) -> Result<Option<ActiveEmbeddingModel>> {
+        read_json(&self.embedding_model_path()).await
+    }
+
+    async fn save_active_embedding_model(&mut self, active: &ActiveEmbeddingModel) -> Result<()> {
+        fs::write(self.embedding_model_path(), self.format.render(active)?.as_bytes()).await?;
+        self.set_pending().await;
+        Ok(())
+    }
+
+    async fn load_reindex_checkpoint(&self) -> Result<Option<ReindexCheckpoint>> {
+        read_json(&self.reindex_checkpoint_path()).await
+    }
+
+    async fn save_reindex_checkpoint(&mut self, checkpoint: &ReindexCheckpoint) -> Result<()> {
+        fs::write(self.reindex_checkpoint_path(), self.format.render(checkpoint)?.as_bytes()).await?;
+        self.set_pending().await;
+        Ok(())
+    }
+
+    async fn clear_reindex_checkpoint(&mut self) -> Result<()> {
+        fs::remove_file(self.reindex_checkpoint_path()).await.or_else(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound { Ok(()) } else { Err(e) }
+        })?;
+        self.set_pending().await;
+        Ok(())
+    }
+
+    async fn save_blocker(&mut self, blocker: &Blocker) -> Result<()> {
+        let json = self.format.render(blocker)?;
+        fs::write(self.blocker_path(blocker.id), json.as_bytes()).await?;
+        self.set_pending().await;
+        Ok(())
+    }
+
+    async fn load_blocker(&self, id: BlockerId) -> Result<Option<Blocker>> {
+        read_json(&self.blocker_path(id)).await
+    }
+
+    async fn list_blockers(&self) -> Result<Vec<Blocker>> {
+        list_dir(&self.root.join("blockers")).await
+    }
+
+    async fn save_quality_profile(&mut self, profile: &QualityProfile) -> Result<()> {
+        let json = self.format.render(profile)?;
+        fs::write(self.quality_profile_path(profile.id), json.as_bytes()).await?;
+        self.set_pending().await;
+        Ok(())
+    }
+
+    async fn load_quality_profile(&self, id: QualityProfileId) -> Result<Option<QualityProfile>> {
+        read_json(&self.quality_profile_path(id)).await
+    }
+
+    async fn list_quality_profiles(&self) -> Result<Vec<QualityProfile>> {
+        list_dir(&self.root.join("quality_profiles")).await
+    }
+
+    async fn save_task_template(&mut self, template: &TaskTemplate) -> Result<()> {
+        let json = self.format.render(template)?;
+        fs::write(self.task_template_path(template.id), json.as_bytes()).await?;
+        self.set_pending().await;
+        Ok(())
+    }
+
+    async fn load_task_template(&self, id: TaskTemplateId) -> Result<Option<TaskTemplate>> {
+        read_json(&self.task_template_path(id)).await
+    }
+
+    async fn list_task_templates(&self) -> Result<Vec<TaskTemplate>> {
+        list_dir(&self.root.join("task_templates")).await
+    }
+
+    async fn put_blob(&mut self, content: &[u8]) -> Result<BlobId> {
+        let id = BlobId::of(content);
+        let path = self.blob_path(&id);
+        if !fs::try_exists(&path).await? {
+            fs::write(&path, content).await?;
+            self.set_pending().await;
+        }
+        Ok(id)
+    }
+
+    async fn pin_blob(&mut self, id: &BlobId) -> Result<()> {
+        let mut pinned = self.read_pinned_blobs().await?;
+        if !pinned.contains(id) {
+            pinned.push(id.clone());
+            fs::write(self.pinned_blobs_path(), self.format.render(&pinned)?.as_bytes()).await?;
+        }
+        Ok(())
+    }
+
+    async fn get_blob(&self, id: &BlobId) -> Result<Option<Vec<u8>>> {
+        if !id.is_valid() {
+            return Ok(None);
+        }
+        match fs::read(self.blob_path(id)).await {
+            Ok(content) => Ok(Some(content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn save_quality_run(&mut self, run: &QualityRun) -> Result<()> {
+        let mut runs = self.list_quality_runs(run.task_id).await?;
+        runs.push(run.clone());
+        let json = self.format.render(&runs)?;
+        fs::write(self.quality_runs_path(run.task_id), json.as_bytes()).await?;
+        self.set_pending().await;
+        Ok(())
+    }
+
+    async fn list_quality_runs(&self, task_id: TaskId) -> Result<Vec<QualityRun>> {
+        Ok(read_json(&self.quality_runs_path(task_id)).await?.unwrap_or_default())
+    }
+
     async fn save_quality_check(&mut self, check: &QualityCheck) -> Result<()> {
         let path = self.quality_check_path(check.id);
-        let json = serde_json::to_string_pretty(check)?;
+        let json = self.format.render(check)?;
         fs::write(&path, json.as_bytes()).await?;
 
         let id_str = format!("{}", check.id);
@@ -293,26 +689,83 @@ impl Storage for JsonStorage {
     }
 
     async fn save_work_record(&mut self, record: &WorkRecord) -> Result<()> {
+        let previous_task = self.load_work_record(record.id).await?.map(|r| r.task_id);
         let path = self.work_record_path(record.id);
-        let json = serde_json::to_string_pretty(record)?;
+        let json = self.format.render(record)?;
         fs::write(&path, json.as_bytes()).await?;
 
         let id_str = format!("{}", record.id);
         let _ver = self.bump_version("work_records", &id_str).await?;
 
+        // A record moved to another task leaves the old task's index
+        if let Some(previous_task) = previous_task.filter(|t| *t != record.task_id) {
+            let mut ids = self.read_work_record_index(previous_task).await?;
+            ids.retain(|id| *id != record.id);
+            self.write_work_record_index(previous_task, &ids).await?;
+        }
+        let mut ids = self.read_work_record_index(record.task_id).await?;
+        if !ids.contains(&record.id) {
+            ids.push(record.id);
+            self.write_work_record_index(record.task_id, &ids).await?;
+        }
+
         self.set_pending().await;
         Ok(())
     }
 
     async fn load_work_record(&self, id: WorkRecordId) -> Result<Option<WorkRecord>> {
+        #[cfg(test)]
+        self.record_loads.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
         read_json(&self.work_record_path(id)).await
     }
 
     async fn list_work_records(&self, task_id: TaskId) -> Result<Vec<WorkRecord>> {
-        let all = list_dir(&self.root.join("work_records")).await?;
-        Ok(all.into_iter()
-            .filter(|r: &WorkRecord| r.task_id == task_id)
-            .collect())
+        let mut records = Vec::new();
+        for id in self.read_work_record_index(task_id).await? {
+            // Skip index entries left behind by a record that moved task
+            if let Some(record) = self.load_work_record(id).await?.filter(|r| r.task_id == task_id) {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+
+    async fn delete_work_record(&mut self, id: WorkRecordId) -> Result<()> {
+        let Some(record) = self.load_work_record(id).await? else {
+            return Ok(());
+        };
+        fs::remove_file(self.work_record_path(id)).await?;
+
+        let mut ids = self.read_work_record_index(record.task_id).await?;
+        ids.retain(|r| *r != id);
+        self.write_work_record_index(record.task_id, &ids).await?;
+
+        self.set_pending().await;
+        Ok(())
+    }
+
+    fn stream_work_records(&self) -> BoxStream<'_, Result<WorkRecord>> {
+        let dir = self.root.join("work_records");
+        // Unreadable files are skipped, as when listing a directory
+        Box::pin(futures::stream::try_unfold(None, move |entries: Option<fs::ReadDir>| {
+            let dir = dir.clone();
+            async move {
+                let mut entries = match entries {
+                    Some(entries) => entries,
+                    None => fs::read_dir(&dir).await?,
+                };
+                while let Some(entry) = entries.next_entry().await? {
+                    let path = entry.path();
+                    if path.extension().and_then(|s| s.to_str()) != Some("json") {
+                        continue;
+                    }
+                    if let Ok(Some(record)) = read_json(&path).await {
+                        return Ok(Some((record, Some(entries))));
+                    }
+                }
+                Ok(None)
+            }
+        }))
     }
 
     async fn commit(&mut self, _message: &str) -> Result<()> {
@@ -326,6 +779,125 @@ impl Storage for JsonStorage {
         *self.pending.lock().await = false;
         Ok(())
     }
+
+    async fn save_idempotency_record(&mut self, record: &IdempotencyRecord) -> Result<()> {
+        let mut records = self.read_idempotency_records().await?;
+        records.insert(record.key.clone(), record.clone());
+        self.write_idempotency_records(&records).await
+    }
+
+    async fn load_idempotency_record(&self, key: &str) -> Result<Option<IdempotencyRecord>> {
+        Ok(self.read_idempotency_records().await?.remove(key))
+    }
+
+    async fn purge_idempotency_records(&mut self, cutoff: Time) -> Result<usize> {
+        let mut records = self.read_idempotency_records().await?;
+        let before = records.len();
+        records.retain(|_, r| r.created_at >= cutoff);
+        let removed = before - records.len();
+        if removed > 0 {
+            self.write_idempotency_records(&records).await?;
+        }
+        Ok(removed)
+    }
+
+    async fn save_config_entry(&mut self, entry: &ConfigEntry) -> Result<()> {
+        let mut entries = self.read_config_entries().await?;
+        entries.insert(entry.key.clone(), entry.clone());
+        fs::write(self.config_path(), self.format.render(&entries)?.as_bytes()).await?;
+        Ok(())
+    }
+
+    async fn load_config_entry(&self, key: &str) -> Result<Option<ConfigEntry>> {
+        Ok(self.read_config_entries().await?.remove(key))
+    }
+
+    async fn list_config_entries(&self) -> Result<Vec<ConfigEntry>> {
+        Ok(self.read_config_entries().await?.into_values().collect())
+    }
+
+    async fn compact(&mut self) -> Result<CompactReport> {
+        let mut report = CompactReport::default();
+
+        for kind in ENTITY_DIRS {
+            // Writes interrupted before their final rename
+            let mut rd = fs::read_dir(self.root.join(kind)).await?;
+            while let Some(entry) = rd.next_entry().await? {
+                if entry.path().extension().and_then(|s| s.to_str()) == Some("tmp") {
+                    remove_orphan(&entry.path(), &mut report).await?;
+                }
+            }
+
+            // Meta markers and locks whose object file is gone (e.g. after
+            // delete_task), and locks left by a crashed writer
+            let mut rd = fs::read_dir(self.root.join("meta").join(kind)).await?;
+            while let Some(entry) = rd.next_entry().await? {
+                let name = entry.file_name();
+                let Some(name) = name.to_str() else {
+                    continue;
+                };
+                let (id, is_lock) = match (name.strip_suffix(".meta.json"), name.strip_suffix(".lock")) {
+                    (Some(id), _) => (id, false),
+                    (None, Some(id)) => (id, true),
+                    (None, None) => continue,
+                };
+                let exists = fs::try_exists(self.root.join(kind).join(format!("{}.json", id))).await?;
+                let stale = is_lock
+                    && entry
+                        .metadata()
+                        .await
+                        .and_then(|m| m.modified())
+                        .ok()
+                        .and_then(|modified| modified.elapsed().ok())
+                        .is_some_and(|age| age > STALE_LOCK_AGE);
+                if !exists || stale {
+                    remove_orphan(&entry.path(), &mut report).await?;
+                }
+            }
+        }
+
+        // Per-task files of tasks that no longer exist
+        for dir in TASK_SIDE_DIRS {
+            let mut rd = fs::read_dir(self.root.join(dir)).await?;
+            while let Some(entry) = rd.next_entry().await? {
+                let name = entry.file_name();
+                let Some(id) = name.to_str().and_then(|n| n.strip_suffix(".json")) else {
+                    continue;
+                };
+                if !fs::try_exists(self.root.join("tasks").join(format!("{}.json", id))).await? {
+                    remove_orphan(&entry.path(), &mut report).await?;
+                }
+            }
+        }
+
+        // Blobs neither attached to a work record nor pinned
+        let mut referenced: std::collections::HashSet<BlobId> = self.read_pinned_blobs().await?.into_iter().collect();
+        let records: Vec<WorkRecord> = list_dir(&self.root.join("work_records")).await?;
+        referenced.extend(records.into_iter().flat_map(|r| r.artifacts).filter_map(|a| a.blob));
+        let mut rd = fs::read_dir(self.root.join("blobs")).await?;
+        while let Some(entry) = rd.next_entry().await? {
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            if !referenced.contains(&BlobId(name)) {
+                remove_orphan(&entry.path(), &mut report).await?;
+            }
+        }
+
+        // Embeddings for knowledge that no longer exists
+        let mut rd = fs::read_dir(self.root.join("embeddings")).await?;
+        while let Some(entry) = rd.next_entry().await? {
+            let name = entry.file_name();
+            let Some(id) = name.to_str().and_then(|n| n.strip_suffix(".json")) else {
+                continue;
+            };
+            if !fs::try_exists(self.root.join("knowledge").join(format!("{}.json", id))).await? {
+                remove_orphan(&entry.path(), &mut report).await?;
+            }
+        }
+
+        Ok(report)
+    }
 }
 
 
@@ -341,6 +913,14 @@ async fn read_json<T: serde::de::DeserializeOwned>(path: &std::path::Path) -> Re
     }
 }
 
+async fn remove_orphan(path: &std::path::Path, report: &mut CompactReport) -> Result<()> {
+    let size = fs::metadata(path).await.map(|m| m.len()).unwrap_or(0);
+    fs::remove_file(path).await?;
+    report.removed += 1;
+    report.reclaimed_bytes += size;
+    Ok(())
+}
+
 async fn list_dir<T: serde::de::DeserializeOwned>(dir: &std::path::Path) -> Result<Vec<T>> {
     let mut items = Vec::new();
     let mut rd = fs::read_dir(dir).await?;
@@ -353,4 +933,642 @@ async fn list_dir<T: serde::de::DeserializeOwned>(dir: &std::path::Path) -> Resu
         }
     }
     Ok(items)
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use devman_core::{TaskContext, TaskIntent, TaskProgress, TaskStatus};
+
+    fn create_test_task() -> Task {
+        Task {
+            id: TaskId::new(),
+            phase_id: PhaseId::new(),
+            title: "Test Task".to_string(),
+            description: "Description".to_string(),
+            intent: TaskIntent {
+                natural_language: "Test intent".to_string(),
+                context: TaskContext {
+                    relevant_knowledge: vec![],
+                    similar_tasks: vec![],
+                    affected_files: vec![],
+                },
+                success_criteria: vec![],
+            },
+            steps: vec![],
+            inputs: vec![],
+            expected_outputs: vec![],
+            quality_gates: vec![],
+            status: TaskStatus::Idea,
+            progress: TaskProgress::default(),
+            depends_on: vec![],
+            blocks: vec![],
+            parent: None,
+            children: vec![],
+            archived: false,
+            priority: devman_core::Priority::default().value(),
+            work_records: vec![],
+            actuals: None,
+            claim: None,
+            state: None,
+            merged_into: None,
+            base_priority: None,
+            due_at: None,
+            created_knowledge: Vec::new(),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    fn create_test_knowledge(title: &str, updated_at: devman_core::Time) -> Knowledge {
+        Knowledge {
+            id: KnowledgeId::new(),
+            title: title.to_string(),
+            knowledge_type: devman_core::KnowledgeType::LessonLearned {
+                lesson: "Test lesson".to_string(),
+                context: "Test context".to_string(),
+            },
+            content: devman_core::KnowledgeContent {
+                summary: "Summary".to_string(),
+                detail: String::new(),
+                examples: vec![],
+                references: vec![],
+            },
+            metadata: devman_core::KnowledgeMetadata {
+                domain: vec![],
+                tech_stack: vec![],
+                scenarios: vec![],
+                quality_score: 1.0,
+                verified: false,
+            },
+            tags: vec![],
+            related_to: vec![],
+            derived_from: vec![],
+            usage_stats: devman_core::UsageStats {
+                times_used: 0,
+                last_used: None,
+                success_rate: 0.0,
+                feedback: vec![],
+            },
+            archived: false,
+            created_at: updated_at,
+            updated_at,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sorted_format_keeps_diffs_minimal() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap().with_format(JsonFormat::Sorted);
+
+        let mut task = create_test_task();
+        storage.save_task(&task).await.unwrap();
+        let before = fs::read_to_string(storage.task_path(task.id)).await.unwrap();
+
+        // Same data, same bytes
+        storage.save_task(&task).await.unwrap();
+        assert_eq!(fs::read_to_string(storage.task_path(task.id)).await.unwrap(), before);
+
+        let keys: Vec<&str> = before
+            .lines()
+            .filter(|line| line.starts_with("  \"") && !line.starts_with("   "))
+            .map(|line| line.trim().split('"').nth(1).unwrap())
+            .collect();
+        let mut sorted = keys.clone();
+        sorted.sort();
+        assert_eq!(keys, sorted);
+
+        task.title = "Renamed Task".to_string();
+        storage.save_task(&task).await.unwrap();
+        let after = fs::read_to_string(storage.task_path(task.id)).await.unwrap();
+
+        let before: Vec<&str> = before.lines().collect();
+        let after: Vec<&str> = after.lines().collect();
+        assert_eq!(before.len(), after.len());
+        let changed: Vec<(&str, &str)> = before
+            .iter()
+            .zip(&after)
+            .filter(|(b, a)| b != a)
+            .map(|(b, a)| (*b, *a))
+            .collect();
+        assert_eq!(changed, [("  \"title\": \"Test Task\",", "  \"title\": \"Renamed Task\",")]);
+    }
+
+    #[test]
+    fn test_compact_format_is_one_line() {
+        let json = JsonFormat::Compact.render(&create_test_task()).unwrap();
+        assert_eq!(json.lines().count(), 1);
+        assert_eq!(JsonFormat::default(), JsonFormat::Pretty);
+    }
+
+    #[tokio::test]
+    async fn test_compact_removes_orphans() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+
+        let kept = create_test_task();
+        let deleted = create_test_task();
+        storage.save_task(&kept).await.unwrap();
+        storage.save_task(&deleted).await.unwrap();
+        let record = WorkRecord::new(deleted.id, devman_core::Executor::AI { model: "test".to_string() }, chrono::Utc::now());
+        storage.save_work_record(&record).await.unwrap();
+        storage.delete_task(deleted.id).await.unwrap();
+        assert!(!storage.work_record_index_path(deleted.id).exists());
+
+        // Embedding left behind by knowledge that was never saved
+        let orphan_embedding = dir.path().join("embeddings").join("missing.json");
+        fs::write(&orphan_embedding, b"{}").await.unwrap();
+
+        // Side files and a lock of a task whose delete was interrupted
+        let gone = TaskId::new();
+        let side_files = [
+            storage.work_record_index_path(gone),
+            storage.quality_runs_path(gone),
+            dir.path().join("meta").join("tasks").join(format!("{}.lock", gone)),
+            dir.path().join("tasks").join(format!("{}.json.tmp", gone)),
+        ];
+        for path in &side_files {
+            fs::write(path, b"[]").await.unwrap();
+        }
+        fs::write(storage.quality_runs_path(kept.id), b"[]").await.unwrap();
+
+        // Blobs are kept while attached to a work record or pinned
+        let attached = storage.put_blob(b"attached").await.unwrap();
+        let pinned = storage.put_blob(b"pinned").await.unwrap();
+        let unreferenced = storage.put_blob(b"unreferenced").await.unwrap();
+        let mut record = WorkRecord::new(kept.id, devman_core::Executor::AI { model: "test".to_string() }, chrono::Utc::now());
+        record.artifacts.push(devman_core::Artifact {
+            name: "log".to_string(),
+            artifact_type: "log".to_string(),
+            location: String::new(),
+            blob: Some(attached.clone()),
+        });
+        storage.save_work_record(&record).await.unwrap();
+        storage.pin_blob(&pinned).await.unwrap();
+
+        let report = storage.compact().await.unwrap();
+        assert_eq!(report.removed, 7);
+        assert!(report.reclaimed_bytes > 0);
+
+        assert!(!orphan_embedding.exists());
+        assert!(!storage.meta_path("tasks", &deleted.id.to_string()).exists());
+        assert!(storage.meta_path("tasks", &kept.id.to_string()).exists());
+        assert!(storage.load_task(kept.id).await.unwrap().is_some());
+        assert!(side_files.iter().all(|path| !path.exists()));
+        assert!(storage.quality_runs_path(kept.id).exists());
+        assert!(storage.work_record_index_path(kept.id).exists());
+        assert!(storage.get_blob(&attached).await.unwrap().is_some());
+        assert!(storage.get_blob(&pinned).await.unwrap().is_some());
+        assert!(storage.get_blob(&unreferenced).await.unwrap().is_none());
+
+        // Nothing left to reclaim
+        assert_eq!(storage.compact().await.unwrap(), CompactReport::default());
+    }
+
+    #[tokio::test]
+    async fn test_list_work_records_uses_task_index() {
+        use std::sync::atomic::Ordering;
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+
+        let tasks = [TaskId::new(), TaskId::new(), TaskId::new()];
+        for task_id in tasks {
+            for _ in 0..3 {
+                let record = WorkRecord::new(task_id, devman_core::Executor::AI { model: "test".to_string() }, chrono::Utc::now());
+                storage.save_work_record(&record).await.unwrap();
+            }
+        }
+
+        storage.record_loads.store(0, Ordering::SeqCst);
+        let records = storage.list_work_records(tasks[1]).await.unwrap();
+        assert_eq!(records.len(), 3);
+        assert!(records.iter().all(|r| r.task_id == tasks[1]));
+        assert_eq!(storage.record_loads.load(Ordering::SeqCst), 3);
+
+        // Re-saving a record doesn't duplicate its index entry
+        storage.save_work_record(&records[0]).await.unwrap();
+        assert_eq!(storage.list_work_records(tasks[1]).await.unwrap().len(), 3);
+
+        let filter = devman_core::WorkRecordFilter { since: None, limit: Some(2) };
+        assert_eq!(storage.query_work_records(tasks[1], &filter).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_moved_work_record_leaves_previous_task() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+        let (merged, survivor) = (TaskId::new(), TaskId::new());
+        let mut record = WorkRecord::new(merged, devman_core::Executor::AI { model: "test".to_string() }, chrono::Utc::now());
+        storage.save_work_record(&record).await.unwrap();
+
+        // Merging tasks moves records by rewriting their task
+        record.task_id = survivor;
+        storage.save_work_record(&record).await.unwrap();
+        assert!(storage.list_work_records(merged).await.unwrap().is_empty());
+        let moved: Vec<WorkRecordId> = storage.list_work_records(survivor).await.unwrap().iter().map(|r| r.id).collect();
+        assert_eq!(moved, vec![record.id]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_yields_each_work_record_once() {
+        use futures::TryStreamExt;
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+
+        let mut saved = std::collections::HashSet::new();
+        for task_id in [TaskId::new(), TaskId::new()] {
+            for _ in 0..5 {
+                let record = WorkRecord::new(task_id, devman_core::Executor::AI { model: "test".to_string() }, chrono::Utc::now());
+                storage.save_work_record(&record).await.unwrap();
+                saved.insert(record.id);
+            }
+        }
+        // Re-saving must not produce a second copy
+        let first = *saved.iter().next().unwrap();
+        let record = storage.load_work_record(first).await.unwrap().unwrap();
+        storage.save_work_record(&record).await.unwrap();
+
+        let streamed: Vec<WorkRecordId> = storage.stream_work_records().map_ok(|r| r.id).try_collect().await.unwrap();
+        assert_eq!(streamed.len(), saved.len());
+        assert_eq!(streamed.into_iter().collect::<std::collections::HashSet<_>>(), saved);
+    }
+
+    #[tokio::test]
+    async fn test_work_record_index_rebuilt_for_existing_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let task_id = TaskId::new();
+        {
+            let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+            let record = WorkRecord::new(task_id, devman_core::Executor::AI { model: "test".to_string() }, chrono::Utc::now());
+            storage.save_work_record(&record).await.unwrap();
+        }
+
+        // Simulate a store written before the index existed
+        fs::remove_dir_all(dir.path().join("meta").join("work_records_by_task")).await.unwrap();
+
+        let storage = JsonStorage::new(dir.path()).await.unwrap();
+        assert_eq!(storage.list_work_records(task_id).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_list_recent_knowledge() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+        let base = chrono::Utc::now() - chrono::Duration::days(1);
+
+        // Saved out of order; titles name the minute offset
+        for offset in [2, 0, 4, 1, 3] {
+            let item = create_test_knowledge(&format!("k{}", offset), base + chrono::Duration::minutes(offset));
+            storage.save_knowledge(&item).await.unwrap();
+        }
+
+        let recent = storage.list_recent_knowledge(3).await.unwrap();
+        let titles: Vec<_> = recent.iter().map(|k| k.title.as_str()).collect();
+        assert_eq!(titles, ["k4", "k3", "k2"]);
+
+        // Updating an item moves it to the front
+        let mut oldest = storage.list_recent_knowledge(5).await.unwrap().pop().unwrap();
+        assert_eq!(oldest.title, "k0");
+        oldest.updated_at = base + chrono::Duration::minutes(10);
+        storage.save_knowledge(&oldest).await.unwrap();
+
+        let recent = storage.list_recent_knowledge(10).await.unwrap();
+        let titles: Vec<_> = recent.iter().map(|k| k.title.as_str()).collect();
+        assert_eq!(titles, ["k0", "k4", "k3", "k2", "k1"]);
+
+        // A store without the index rebuilds it on open
+        fs::remove_file(dir.path().join("meta").join("knowledge_by_updated.json")).await.unwrap();
+        let storage = JsonStorage::new(dir.path()).await.unwrap();
+        let recent = storage.list_recent_knowledge(2).await.unwrap();
+        let titles: Vec<_> = recent.iter().map(|k| k.title.as_str()).collect();
+        assert_eq!(titles, ["k0", "k4"]);
+    }
+
+    #[tokio::test]
+    async fn test_require_task_reports_missing_entity() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+
+        let task = create_test_task();
+        storage.save_task(&task).await.unwrap();
+        assert_eq!(storage.require_task(task.id).await.unwrap().id, task.id);
+
+        let missing = TaskId::new();
+        match storage.require_task(missing).await {
+            Err(StorageError::NotFound { entity_type, id }) => {
+                assert_eq!(entity_type, "task");
+                assert_eq!(id, missing.to_string());
+            }
+            other => panic!("expected NotFound, got {:?}", other.map(|t| t.id)),
+        }
+
+        let err = storage.require_goal(devman_core::GoalId::new()).await.unwrap_err();
+        assert!(err.to_string().starts_with("goal not found: "));
+    }
+
+    #[tokio::test]
+    async fn test_repair_dependency_graph_restores_symmetry() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+
+        let (mut a, mut b, mut c) = (create_test_task(), create_test_task(), create_test_task());
+        let missing = TaskId::new();
+        // a depends on b without b blocking a; c blocks a without a depending on c
+        a.depends_on = vec![b.id, missing];
+        c.blocks = vec![a.id];
+        for task in [&a, &b, &c] {
+            storage.save_task(task).await.unwrap();
+        }
+
+        let report = storage.repair_dependency_graph().await.unwrap();
+        assert_eq!(report.added_blocks, 1);
+        assert_eq!(report.added_depends_on, 1);
+        assert_eq!(report.removed_dangling, 1);
+        assert_eq!(report.repaired_tasks.len(), 2);
+
+        let a = storage.require_task(a.id).await.unwrap();
+        assert_eq!(a.depends_on, vec![b.id, c.id]);
+        assert_eq!(storage.require_task(b.id).await.unwrap().blocks, vec![a.id]);
+        assert_eq!(storage.require_task(c.id).await.unwrap().blocks, vec![a.id]);
+        assert!(storage.repair_dependency_graph().await.unwrap().is_clean());
+    }
+
+    #[tokio::test]
+    async fn test_prune_history_archives_only_old_entries_beyond_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+        let mut task = create_test_task();
+        let task_id = task.id;
+        let now = chrono::Utc::now();
+        let clock = devman_core::MockClock::new(now);
+        let policy = crate::RetentionPolicy { keep_days: 30, keep_per_task: 2, dry_run: false };
+
+        // The two newest entries per task are kept for context even when old
+        let mut events = Vec::new();
+        for days in [40, 50, 5] {
+            let mut event = Event::new(devman_core::AgentId::system(), "log", "ok");
+            event.timestamp = now - chrono::Duration::days(days);
+            event.related_tasks = vec![task_id];
+            storage.save_event(&event).await.unwrap();
+            events.push(event);
+        }
+        let mut unrelated = Event::new(devman_core::AgentId::system(), "sync", "ok");
+        unrelated.timestamp = now - chrono::Duration::days(60);
+        storage.save_event(&unrelated).await.unwrap();
+
+        let mut records = Vec::new();
+        for days in [45, 35, 1, 50] {
+            let mut record = WorkRecord::new(task_id, devman_core::Executor::AI { model: "test".to_string() }, chrono::Utc::now());
+            record.started_at = now - chrono::Duration::days(days);
+            storage.save_work_record(&record).await.unwrap();
+            task.work_records.push(record.id);
+            records.push(record);
+        }
+        storage.save_task(&task).await.unwrap();
+
+        // Seen from 15 days earlier, the 45-day-old record is not yet past
+        // the window
+        let dry_run = crate::RetentionPolicy { dry_run: true, ..policy.clone() };
+        let earlier = devman_core::MockClock::new(now - chrono::Duration::days(15));
+        let planned = storage.prune_history(&dry_run, &earlier).await.unwrap();
+        assert_eq!(planned.work_records, vec![records[3].id]);
+
+        let planned = storage.prune_history(&dry_run, &clock).await.unwrap();
+        let mut expected_events = vec![events[1].id, unrelated.id];
+        expected_events.sort_by_key(|id| id.to_string());
+        let mut planned_events = planned.events.clone();
+        planned_events.sort_by_key(|id| id.to_string());
+        assert_eq!(planned_events, expected_events);
+        assert_eq!(planned.work_records, vec![records[0].id, records[3].id]);
+        assert!(planned.archive.is_none());
+        assert_eq!(storage.list_events().await.unwrap().len(), 4);
+
+        let report = storage.prune_history(&policy, &clock).await.unwrap();
+        assert_eq!(report.events.len(), 2);
+        let remaining: Vec<EventId> = storage.list_events().await.unwrap().iter().map(|e| e.id).collect();
+        assert_eq!(remaining, vec![events[0].id, events[2].id]);
+        let kept: Vec<WorkRecordId> = storage.list_work_records(task_id).await.unwrap().iter().map(|r| r.id).collect();
+        assert_eq!(kept, vec![records[1].id, records[2].id]);
+        assert_eq!(storage.require_task(task_id).await.unwrap().work_records, kept);
+
+        let archive = storage.require_blob(report.archive.as_ref().unwrap()).await.unwrap();
+        let lines: Vec<serde_json::Value> = String::from_utf8(archive)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines.iter().filter(|l| l["kind"] == "work_record").count(), 2);
+        assert!(lines.iter().any(|l| l["data"]["id"] == serde_json::json!(unrelated.id)));
+
+        // Nothing refers to the archive but the report, so it is pinned
+        storage.compact().await.unwrap();
+        assert!(storage.get_blob(report.archive.as_ref().unwrap()).await.unwrap().is_some());
+
+        assert!(storage.prune_history(&policy, &clock).await.unwrap().is_empty());
+        let forever = crate::RetentionPolicy { keep_days: u32::MAX, keep_per_task: 0, dry_run: true };
+        assert!(storage.prune_history(&forever, &clock).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_link_and_unlink_update_both_tasks() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+
+        let (a, b) = (create_test_task(), create_test_task());
+        storage.save_task(&a).await.unwrap();
+        storage.save_task(&b).await.unwrap();
+
+        storage.link_tasks(a.id, b.id).await.unwrap();
+        storage.link_tasks(a.id, b.id).await.unwrap();
+        assert_eq!(storage.require_task(a.id).await.unwrap().depends_on, vec![b.id]);
+        assert_eq!(storage.require_task(b.id).await.unwrap().blocks, vec![a.id]);
+        assert!(storage.repair_dependency_graph().await.unwrap().is_clean());
+        assert!(storage.link_tasks(a.id, a.id).await.is_err());
+
+        storage.unlink_tasks(a.id, b.id).await.unwrap();
+        assert!(storage.require_task(a.id).await.unwrap().depends_on.is_empty());
+        assert!(storage.require_task(b.id).await.unwrap().blocks.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_versioned_task_save_rejects_stale_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+        let mut task = create_test_task();
+
+        assert_eq!(storage.task_version(task.id).await.unwrap(), 0);
+        assert_eq!(storage.save_task_if_version(&task, 0).await.unwrap(), 1);
+        storage.save_task(&task).await.unwrap();
+        assert_eq!(storage.task_version(task.id).await.unwrap(), 2);
+
+        task.title = "Stale write".to_string();
+        let err = storage.save_task_if_version(&task, 1).await.unwrap_err();
+        assert!(matches!(err, StorageError::Conflict { expected: 1, .. }));
+        assert_eq!(storage.load_task(task.id).await.unwrap().unwrap().title, "Test Task");
+
+        // A writer holding the lock makes others lose the race
+        let lock = dir.path().join("meta").join("tasks").join(format!("{}.lock", task.id));
+        std::fs::write(&lock, "").unwrap();
+        assert!(matches!(
+            storage.save_task_if_version(&task, 2).await,
+            Err(StorageError::Conflict { .. })
+        ));
+        std::fs::remove_file(&lock).unwrap();
+        assert_eq!(storage.save_task_if_version(&task, 2).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_list_tasks_roots_only_with_subtasks() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+
+        let mut parent = create_test_task();
+        let mut child = create_test_task();
+        let mut grandchild = create_test_task();
+        let other = create_test_task();
+        parent.children = vec![child.id];
+        child.parent = Some(parent.id);
+        child.children = vec![grandchild.id];
+        grandchild.parent = Some(child.id);
+        grandchild.status = TaskStatus::Done;
+        for task in [&parent, &child, &grandchild, &other] {
+            storage.save_task(task).await.unwrap();
+        }
+
+        let roots_only = TaskFilter { roots_only: true, ..Default::default() };
+        let roots: std::collections::HashSet<_> = storage.list_tasks(&roots_only).await.unwrap().iter().map(|t| t.id).collect();
+        assert_eq!(roots, [parent.id, other.id].into_iter().collect());
+
+        let with_subtasks = TaskFilter {
+            status: Some(vec![TaskStatus::Idea]),
+            roots_only: true,
+            include_subtasks: true,
+            ..Default::default()
+        };
+        let ids: Vec<_> = storage.list_tasks(&with_subtasks).await.unwrap().iter().map(|t| t.id).collect();
+        assert_eq!(ids.len(), 4);
+        let position = |id| ids.iter().position(|t| *t == id).unwrap();
+        assert!(position(parent.id) < position(child.id));
+        assert_eq!(position(grandchild.id), position(child.id) + 1);
+    }
+
+    #[tokio::test]
+    async fn test_blob_attached_to_artifact_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+
+        let report = b"\x00\x01coverage: 87%\xff";
+        let id = storage.put_blob(report).await.unwrap();
+        assert_eq!(id, BlobId::of(report));
+        assert_eq!(storage.put_blob(report).await.unwrap(), id);
+        assert_eq!(std::fs::read_dir(dir.path().join("blobs")).unwrap().count(), 1);
+
+        let mut record = WorkRecord::new(TaskId::new(), devman_core::Executor::AI { model: "test".to_string() }, chrono::Utc::now());
+        record.artifacts.push(devman_core::Artifact {
+            name: "coverage report".to_string(),
+            artifact_type: "report".to_string(),
+            location: String::new(),
+            blob: Some(id),
+        });
+        storage.save_work_record(&record).await.unwrap();
+
+        let loaded = storage.load_work_record(record.id).await.unwrap().unwrap();
+        let blob = loaded.artifacts[0].blob.as_ref().unwrap();
+        assert_eq!(storage.get_blob(blob).await.unwrap().unwrap(), report);
+
+        assert!(storage.get_blob(&BlobId::of(b"other")).await.unwrap().is_none());
+        assert!(storage.get_blob(&BlobId("../tasks".to_string())).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+
+        let now = chrono::Utc::now();
+        let old = IdempotencyRecord {
+            key: "create:old".to_string(),
+            entity_id: "a".to_string(),
+            created_at: now - chrono::Duration::hours(2),
+        };
+        let fresh = IdempotencyRecord {
+            key: "create:fresh".to_string(),
+            entity_id: "b".to_string(),
+            created_at: now,
+        };
+        storage.save_idempotency_record(&old).await.unwrap();
+        storage.save_idempotency_record(&fresh).await.unwrap();
+
+        assert_eq!(storage.load_idempotency_record("create:fresh").await.unwrap(), Some(fresh.clone()));
+        assert_eq!(storage.load_idempotency_record("create:missing").await.unwrap(), None);
+
+        let removed = storage
+            .purge_idempotency_records(now - chrono::Duration::hours(1))
+            .await
+            .unwrap();
+        assert_eq!(removed, 1);
+        assert_eq!(storage.load_idempotency_record("create:old").await.unwrap(), None);
+        assert_eq!(storage.load_idempotency_record("create:fresh").await.unwrap(), Some(fresh));
+    }
+
+    #[tokio::test]
+    async fn test_config_survives_reload() {
+        use crate::config::{keys, ConfigStore};
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+        assert_eq!(storage.get_config::<u64>(keys::QUALITY_CHECK_TIMEOUT_SECS).await.unwrap(), None);
+        storage.set_config(keys::QUALITY_CHECK_TIMEOUT_SECS, &90u64).await.unwrap();
+        storage.set_config(keys::KNOWLEDGE_MAX_CONCURRENCY, &2usize).await.unwrap();
+        storage.set_config(keys::QUALITY_CHECK_TIMEOUT_SECS, &120u64).await.unwrap();
+
+        let reloaded = JsonStorage::new(dir.path()).await.unwrap();
+        assert_eq!(reloaded.get_config::<u64>(keys::QUALITY_CHECK_TIMEOUT_SECS).await.unwrap(), Some(120));
+        let names: Vec<_> = reloaded.list_config_entries().await.unwrap().into_iter().map(|e| e.key).collect();
+        assert_eq!(names, [keys::KNOWLEDGE_MAX_CONCURRENCY, keys::QUALITY_CHECK_TIMEOUT_SECS]);
+
+        // A value of the wrong type is an error, or the default when asked for one
+        assert!(reloaded.get_config::<String>(keys::QUALITY_CHECK_TIMEOUT_SECS).await.is_err());
+        assert_eq!(reloaded.get_config_or(keys::QUALITY_CHECK_TIMEOUT_SECS, "none".to_string()).await, "none");
+        let model = reloaded.get_config_or(keys::KNOWLEDGE_EMBEDDING_MODEL, None::<devman_core::EmbeddingModel>).await;
+        assert!(model.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_embedding_from_other_model_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(dir.path()).await.unwrap();
+        let embedding = |model: devman_core::EmbeddingModel, dims: usize| KnowledgeEmbedding {
+            knowledge_id: KnowledgeId::new(),
+            embedding: vec![0.5; dims],
+            model,
+            created_at: chrono::Utc::now(),
+        };
+
+        assert_eq!(storage.load_active_embedding_model().await.unwrap(), None);
+        storage
+            .save_vector_embedding(&embedding(devman_core::EmbeddingModel::Qwen3Embedding0_6B, 1024))
+            .await
+            .unwrap();
+        let active = storage.load_active_embedding_model().await.unwrap().unwrap();
+        assert_eq!(active.dimension, 1024);
+
+        let err = storage
+            .save_vector_embedding(&embedding(devman_core::EmbeddingModel::OpenAIAda002, 1536))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, StorageError::EmbeddingModelMismatch { .. }));
+        assert!(err.to_string().contains("reindex"));
+        let err = storage
+            .save_vector_embedding(&embedding(devman_core::EmbeddingModel::Qwen3Embedding0_6B, 512))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, StorageError::EmbeddingModelMismatch { .. }));
+        assert_eq!(storage.list_vector_embeddings().await.unwrap().len(), 1);
+
+        // Once the active model is switched, the new model is accepted
+        let ada = embedding(devman_core::EmbeddingModel::OpenAIAda002, 1536);
+        storage.save_active_embedding_model(&ActiveEmbeddingModel::of(&ada)).await.unwrap();
+        storage.save_vector_embedding(&ada).await.unwrap();
+    }
+}
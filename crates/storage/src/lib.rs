@@ -20,14 +20,34 @@
 pub mod trait_;
 #[cfg(feature = "json")]
 pub mod json_storage;
+#[cfg(feature = "json")]
+pub mod migrate;
+#[cfg(feature = "json")]
+pub mod config;
 
 #[cfg(feature = "sqlite")]
 pub mod sqlite_storage;
 
-pub use trait_::{Storage, StorageError, Result, Transaction};
+pub use trait_::{Storage, StorageError, Result, Transaction, CompactReport, ConfigEntry, IdempotencyRecord, RepairReport, RetentionPolicy, PruneReport};
 
 #[cfg(feature = "json")]
-pub use json_storage::JsonStorage;
+pub use json_storage::{JsonFormat, JsonStorage};
+#[cfg(feature = "json")]
+pub use config::ConfigStore;
+#[cfg(feature = "json")]
+pub use migrate::{migrate_store, migrate_store_with_progress, EntityCount, MigrationProgress, MigrationReport};
 
 #[cfg(feature = "sqlite")]
 pub use sqlite_storage::SqliteStorage;
+
+/// Names of the storage backends compiled into this build.
+pub fn available_backends() -> Vec<&'static str> {
+    let mut backends = Vec::new();
+    if cfg!(feature = "json") {
+        backends.push("json");
+    }
+    if cfg!(feature = "sqlite") {
+        backends.push("sqlite");
+    }
+    backends
+}
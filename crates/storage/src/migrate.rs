@@ -0,0 +1,441 @@
+//! Copying a whole store from one backend to another.
+//!
+//! [`migrate_store`] reads every entity from a source [`Storage`] and saves
+//! it into a target, e.g. to move from [`JsonStorage`](crate::JsonStorage)
+//! to [`SqliteStorage`](crate::SqliteStorage). The source is only read, so
+//! it keeps serving until the switch. Saves overwrite by ID, which makes a
+//! migration safe to re-run to pick up entities written in the meantime.
+//!
+//! Work records are streamed rather than listed, blobs are copied for the
+//! artifacts that reference them and quality runs for the tasks that own
+//! them. Idempotency records cannot be enumerated and are not copied; they
+//! only guard against retried creates within a short window.
+
+use std::collections::HashSet;
+
+use futures::StreamExt;
+use serde::Serialize;
+
+use crate::{Result, Storage};
+
+/// How many entities of one type a migration found and copied.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EntityCount {
+    /// Kind of entity, e.g. `"task"`
+    pub entity_type: &'static str,
+    /// Entities read from the source
+    pub source: usize,
+    /// Source entities found in the target afterwards
+    pub target: usize,
+}
+
+impl EntityCount {
+    /// Whether every source entity made it into the target.
+    pub fn matches(&self) -> bool {
+        self.source == self.target
+    }
+}
+
+/// Outcome of [`migrate_store`], one count per entity type.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MigrationReport {
+    /// Counts in the order the types were copied
+    pub entities: Vec<EntityCount>,
+}
+
+impl MigrationReport {
+    /// Whether every entity type has as many entities in the target as in
+    /// the source.
+    pub fn verified(&self) -> bool {
+        self.entities.iter().all(EntityCount::matches)
+    }
+
+    /// Count for `entity_type`, if it was migrated.
+    pub fn count(&self, entity_type: &str) -> Option<&EntityCount> {
+        self.entities.iter().find(|c| c.entity_type == entity_type)
+    }
+
+    /// Total entities read from the source.
+    pub fn total(&self) -> usize {
+        self.entities.iter().map(|c| c.source).sum()
+    }
+}
+
+/// Progress of a running migration, reported after each entity type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MigrationProgress {
+    /// Entity type just copied
+    pub entity_type: &'static str,
+    /// How many of them were copied
+    pub copied: usize,
+}
+
+/// Copy every entity from `from` into `to` and verify the counts.
+pub async fn migrate_store(from: &dyn Storage, to: &mut dyn Storage) -> Result<MigrationReport> {
+    migrate_store_with_progress(from, to, |_| {}).await
+}
+
+/// [`migrate_store`], calling `on_progress` after each entity type.
+///
+/// The target is committed once at the end. Verification reloads each
+/// copied entity from the target by ID, so entities already in the target
+/// do not affect the counts.
+pub async fn migrate_store_with_progress(
+    from: &dyn Storage,
+    to: &mut dyn Storage,
+    mut on_progress: impl FnMut(MigrationProgress) + Send,
+) -> Result<MigrationReport> {
+    let mut report = MigrationReport::default();
+    let mut record = |report: &mut MigrationReport, entity_type: &'static str, source: usize, target: usize| {
+        on_progress(MigrationProgress { entity_type, copied: source });
+        report.entities.push(EntityCount { entity_type, source, target });
+    };
+
+    let goals = from.list_goals().await?;
+    for goal in &goals {
+        to.save_goal(goal).await?;
+    }
+    let mut found = 0;
+    for goal in &goals {
+        found += usize::from(to.load_goal(goal.id).await?.is_some());
+    }
+    record(&mut report, "goal", goals.len(), found);
+
+    let projects = from.list_projects().await?;
+    for project in &projects {
+        to.save_project(project).await?;
+    }
+    let mut found = 0;
+    for project in &projects {
+        found += usize::from(to.load_project(project.id).await?.is_some());
+    }
+    record(&mut report, "project", projects.len(), found);
+
+    // Phases only reachable through a project still count
+    let mut phases = from.list_phases().await?;
+    let mut phase_ids: HashSet<_> = phases.iter().map(|p| p.id).collect();
+    for phase_id in projects.iter().flat_map(|p| p.phases.iter()) {
+        if phase_ids.insert(*phase_id) {
+            phases.extend(from.load_phase(*phase_id).await?);
+        }
+    }
+    for phase in &phases {
+        to.save_phase(phase).await?;
+    }
+    let mut found = 0;
+    for phase in &phases {
+        found += usize::from(to.load_phase(phase.id).await?.is_some());
+    }
+    record(&mut report, "phase", phases.len(), found);
+
+    let tasks = from.list_tasks(&Default::default()).await?;
+    for task in &tasks {
+        to.save_task(task).await?;
+    }
+    let mut found = 0;
+    for task in &tasks {
+        found += usize::from(to.load_task(task.id).await?.is_some());
+    }
+    record(&mut report, "task", tasks.len(), found);
+
+    let events = from.list_events().await?;
+    for event in &events {
+        to.save_event(event).await?;
+    }
+    let mut found = 0;
+    for event in &events {
+        found += usize::from(to.load_event(event.id).await?.is_some());
+    }
+    record(&mut report, "event", events.len(), found);
+
+    let knowledge = from.list_knowledge().await?;
+    for item in &knowledge {
+        to.save_knowledge(item).await?;
+    }
+    let mut found = 0;
+    for item in &knowledge {
+        found += usize::from(to.load_knowledge(item.id).await?.is_some());
+    }
+    record(&mut report, "knowledge", knowledge.len(), found);
+
+    // The active model goes first so the target accepts the embeddings
+    if let Some(active) = from.load_active_embedding_model().await? {
+        to.save_active_embedding_model(&active).await?;
+    }
+    let embeddings = from.list_vector_embeddings().await?;
+    for embedding in &embeddings {
+        to.save_vector_embedding(embedding).await?;
+    }
+    let mut found = 0;
+    for embedding in &embeddings {
+        let id = embedding.knowledge_id.to_string();
+        found += usize::from(to.load_vector_embedding(&id).await?.is_some());
+    }
+    record(&mut report, "embedding", embeddings.len(), found);
+
+    let checks = from.list_quality_checks().await?;
+    for check in &checks {
+        to.save_quality_check(check).await?;
+    }
+    let mut found = 0;
+    for check in &checks {
+        found += usize::from(to.load_quality_check(check.id).await?.is_some());
+    }
+    record(&mut report, "quality_check", checks.len(), found);
+
+    let profiles = from.list_quality_profiles().await?;
+    for profile in &profiles {
+        to.save_quality_profile(profile).await?;
+    }
+    let mut found = 0;
+    for profile in &profiles {
+        found += usize::from(to.load_quality_profile(profile.id).await?.is_some());
+    }
+    record(&mut report, "quality_profile", profiles.len(), found);
+
+    let templates = from.list_task_templates().await?;
+    for template in &templates {
+        to.save_task_template(template).await?;
+    }
+    let mut found = 0;
+    for template in &templates {
+        found += usize::from(to.load_task_template(template.id).await?.is_some());
+    }
+    record(&mut report, "task_template", templates.len(), found);
+
+    let blockers = from.list_blockers().await?;
+    for blocker in &blockers {
+        to.save_blocker(blocker).await?;
+    }
+    let mut found = 0;
+    for blocker in &blockers {
+        found += usize::from(to.load_blocker(blocker.id).await?.is_some());
+    }
+    record(&mut report, "blocker", blockers.len(), found);
+
+    let settings = from.list_config_entries().await?;
+    for entry in &settings {
+        to.save_config_entry(entry).await?;
+    }
+    let mut found = 0;
+    for entry in &settings {
+        found += usize::from(to.load_config_entry(&entry.key).await?.is_some());
+    }
+    record(&mut report, "config", settings.len(), found);
+
+    // Work records can be numerous, so they are streamed and only their IDs
+    // and referenced blobs are kept
+    let mut record_ids = Vec::new();
+    let mut blob_ids = Vec::new();
+    let mut seen_blobs = HashSet::new();
+    let mut stream = from.stream_work_records();
+    while let Some(work_record) = stream.next().await {
+        let work_record = work_record?;
+        to.save_work_record(&work_record).await?;
+        record_ids.push(work_record.id);
+        for blob in work_record.artifacts.iter().filter_map(|a| a.blob.clone()) {
+            if seen_blobs.insert(blob.clone()) {
+                blob_ids.push(blob);
+            }
+        }
+    }
+    drop(stream);
+    let mut found = 0;
+    for id in &record_ids {
+        found += usize::from(to.load_work_record(*id).await?.is_some());
+    }
+    record(&mut report, "work_record", record_ids.len(), found);
+
+    let mut copied = 0;
+    for id in &blob_ids {
+        if let Some(content) = from.get_blob(id).await? {
+            to.put_blob(&content).await?;
+            copied += 1;
+        }
+    }
+    let mut found = 0;
+    for id in &blob_ids {
+        found += usize::from(to.get_blob(id).await?.is_some());
+    }
+    // A blob missing from the source is not the migration's loss
+    record(&mut report, "blob", copied, found.min(copied));
+
+    let mut runs = 0;
+    let mut found = 0;
+    for task in &tasks {
+        let source_runs = from.list_quality_runs(task.id).await?;
+        for run in &source_runs {
+            to.save_quality_run(run).await?;
+        }
+        let copied: HashSet<_> = to.list_quality_runs(task.id).await?.into_iter().map(|r| r.id).collect();
+        runs += source_runs.len();
+        found += source_runs.iter().filter(|r| copied.contains(&r.id)).count();
+    }
+    record(&mut report, "quality_run", runs, found);
+
+    to.commit(&format!("Migrate {} entities", report.total())).await?;
+    Ok(report)
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::{JsonStorage, SqliteStorage};
+    use devman_core::{
+        Artifact, Event, Executor, KnowledgeEmbedding, KnowledgeId, Phase, PhaseId, Project, ProjectId, Task,
+        TaskContext, TaskIntent, TaskProgress, TaskStatus, WorkRecord,
+    };
+
+    fn task(title: &str, phase_id: PhaseId) -> Task {
+        Task {
+            id: devman_core::TaskId::new(),
+            phase_id,
+            title: title.to_string(),
+            description: format!("{} description", title),
+            intent: TaskIntent {
+                natural_language: title.to_string(),
+                context: TaskContext { relevant_knowledge: vec![], similar_tasks: vec![], affected_files: vec![] },
+                success_criteria: vec![],
+            },
+            steps: vec![],
+            inputs: vec![],
+            expected_outputs: vec![],
+            quality_gates: vec![],
+            status: TaskStatus::Queued,
+            progress: TaskProgress::default(),
+            depends_on: vec![],
+            blocks: vec![],
+            parent: None,
+            children: vec![],
+            archived: false,
+            priority: devman_core::Priority::default().value(),
+            work_records: vec![],
+            actuals: None,
+            claim: None,
+            state: None,
+            merged_into: None,
+            base_priority: None,
+            due_at: None,
+            created_knowledge: Vec::new(),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_migrate_json_store_into_sqlite() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut json = JsonStorage::new(dir.path()).await.unwrap();
+
+        let phase = Phase {
+            id: PhaseId::new(),
+            name: "Build".to_string(),
+            description: String::new(),
+            objectives: vec![],
+            acceptance_criteria: vec![],
+            tasks: vec![],
+            depends_on: vec![],
+            status: devman_core::PhaseStatus::InProgress,
+            progress: devman_core::PhaseProgress::default(),
+            estimated_duration: None,
+            actual_duration: None,
+            quality_profile: None,
+            created_at: chrono::Utc::now(),
+        };
+        json.save_phase(&phase).await.unwrap();
+        json.save_project(&Project {
+            id: ProjectId::new(),
+            name: "Migrated".to_string(),
+            description: String::new(),
+            config: devman_core::ProjectConfig {
+                tech_stack: vec![],
+                structure: devman_core::DirStructure { dirs: vec![], conventions: vec![] },
+                quality_profile: devman_core::QualityProfileId::default(),
+                default_task_profile: None,
+                tools: devman_core::ToolConfig {
+                    build: devman_core::BuildTool::Cargo,
+                    test_framework: devman_core::TestFramework::Rust,
+                    linters: vec![],
+                    formatters: vec![],
+                },
+            },
+            phases: vec![phase.id],
+            current_phase: phase.id,
+            created_at: chrono::Utc::now(),
+        })
+        .await
+        .unwrap();
+
+        let parent = task("Schema", phase.id);
+        let mut child = task("Migration", phase.id);
+        child.parent = Some(parent.id);
+        child.depends_on = vec![parent.id];
+        json.save_task(&parent).await.unwrap();
+        json.save_task(&child).await.unwrap();
+        json.save_event(&Event::new(devman_core::AgentId::new("test"), "created", "ok")).await.unwrap();
+        json.save_vector_embedding(&KnowledgeEmbedding {
+            knowledge_id: KnowledgeId::new(),
+            embedding: vec![0.5; 1024],
+            model: devman_core::EmbeddingModel::Qwen3Embedding0_6B,
+            created_at: chrono::Utc::now(),
+        })
+        .await
+        .unwrap();
+
+        let blob = json.put_blob(b"coverage: 87%").await.unwrap();
+        let mut record = WorkRecord::new(child.id, Executor::AI { model: "test".to_string() }, chrono::Utc::now());
+        record.artifacts.push(Artifact {
+            name: "coverage".to_string(),
+            artifact_type: "report".to_string(),
+            location: String::new(),
+            blob: Some(blob.clone()),
+        });
+        json.save_work_record(&record).await.unwrap();
+        crate::ConfigStore::set_config(&mut json, crate::config::keys::QUALITY_CHECK_TIMEOUT_SECS, &90u64)
+            .await
+            .unwrap();
+
+        let mut sqlite = SqliteStorage::in_memory().await.unwrap();
+        let mut progress = Vec::new();
+        let report = migrate_store_with_progress(&json, &mut sqlite, |p| progress.push(p.entity_type))
+            .await
+            .unwrap();
+
+        assert!(report.verified(), "{:?}", report);
+        assert_eq!(progress.len(), report.entities.len());
+        let counts: Vec<_> = report.entities.iter().map(|c| (c.entity_type, c.source)).collect();
+        assert_eq!(
+            counts,
+            [
+                ("goal", 0),
+                ("project", 1),
+                ("phase", 1),
+                ("task", 2),
+                ("event", 1),
+                ("knowledge", 0),
+                ("embedding", 1),
+                ("quality_check", 0),
+                ("quality_profile", 0),
+                ("task_template", 0),
+                ("blocker", 0),
+                ("config", 1),
+                ("work_record", 1),
+                ("blob", 1),
+                ("quality_run", 0),
+            ]
+        );
+
+        let migrated = sqlite.load_task(child.id).await.unwrap().unwrap();
+        assert_eq!(migrated.title, "Migration");
+        assert_eq!(migrated.description, child.description);
+        assert_eq!(migrated.parent, Some(parent.id));
+        assert_eq!(migrated.depends_on, [parent.id]);
+        assert!(sqlite.load_active_embedding_model().await.unwrap().is_some());
+        assert_eq!(sqlite.get_blob(&blob).await.unwrap().unwrap(), b"coverage: 87%");
+
+        // Re-running copies over the same IDs instead of duplicating
+        let again = migrate_store(&json, &mut sqlite).await.unwrap();
+        assert_eq!(again, report);
+        assert_eq!(sqlite.list_tasks(&Default::default()).await.unwrap().len(), 2);
+    }
+}
@@ -4,16 +4,50 @@
 //! This is the recommended storage backend for production use.
 
 use async_trait::async_trait;
+use futures::stream::{BoxStream, StreamExt};
 use sqlx::Row;
+use sqlx::sqlite::SqliteConnectOptions;
 use devman_core::{
     Goal, GoalId, Project, ProjectId, Phase, PhaseId, Task, TaskId, TaskFilter,
     Event, EventId, Knowledge, KnowledgeId, QualityCheck, QualityCheckId,
-    WorkRecord, WorkRecordId, KnowledgeEmbedding,
+    WorkRecord, WorkRecordId, WorkRecordFilter, KnowledgeEmbedding, ActiveEmbeddingModel, ReindexCheckpoint,
+    Blocker, BlockerId, QualityProfile, QualityProfileId, QualityRun, TaskTemplate, TaskTemplateId, BlobId,
 };
 use std::path::Path;
 use tracing::warn;
 
-use super::trait_::{Storage, StorageError, Result};
+use super::trait_::{Storage, StorageError, Result, CompactReport, ConfigEntry, IdempotencyRecord};
+
+/// Longest wait for a connection to the database to come up.
+pub const CONNECT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// How long a statement waits for another connection's lock before failing.
+const BUSY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+fn invalid_path(path: &str, reason: impl Into<String>) -> StorageError {
+    StorageError::InvalidPath { path: path.to_string(), reason: reason.into() }
+}
+
+/// Map an error opening the database at `path` to the matching
+/// [`StorageError`], by SQLite result code where there is one.
+fn open_error(path: &str, error: sqlx::Error) -> StorageError {
+    match &error {
+        sqlx::Error::Database(db) => {
+            let code = db.code().and_then(|c| c.parse::<i32>().ok()).unwrap_or(0) & 0xff;
+            match code {
+                // SQLITE_BUSY, SQLITE_LOCKED
+                5 | 6 => StorageError::Busy(db.message().to_string()),
+                // SQLITE_CORRUPT, SQLITE_NOTADB
+                11 | 26 => StorageError::Corrupt { path: path.to_string(), message: db.message().to_string() },
+                // SQLITE_PERM, SQLITE_READONLY, SQLITE_CANTOPEN
+                3 | 8 | 14 => invalid_path(path, db.message()),
+                _ => StorageError::Other(error.to_string()),
+            }
+        }
+        sqlx::Error::PoolTimedOut => StorageError::Busy(format!("no connection to {} within {:?}", path, CONNECT_TIMEOUT)),
+        _ => StorageError::Other(error.to_string()),
+    }
+}
 
 /// SQLite storage implementation.
 #[derive(Clone)]
@@ -24,10 +58,49 @@ pub struct SqliteStorage {
 
 impl SqliteStorage {
     /// Create a new SQLite storage instance.
+    ///
+    /// `db_path` is a file path or a `sqlite:` URL. A missing database file
+    /// and its parent directories are created. Fails with
+    /// [`StorageError::InvalidPath`] for a directory or a file that cannot be
+    /// opened, [`StorageError::Busy`] when the database stays locked or the
+    /// connection does not come up within [`CONNECT_TIMEOUT`], and
+    /// [`StorageError::Corrupt`] for a damaged file.
     pub async fn new(db_path: &str) -> Result<Self> {
-        let pool = sqlx::SqlitePool::connect(db_path)
+        let options = if db_path == ":memory:" || db_path.starts_with("sqlite:") {
+            db_path
+                .parse::<SqliteConnectOptions>()
+                .map_err(|e| invalid_path(db_path, e.to_string()))?
+        } else {
+            SqliteConnectOptions::new().filename(db_path)
+        };
+        let options = options.create_if_missing(true).busy_timeout(BUSY_TIMEOUT);
+
+        let file = options.get_filename().to_path_buf();
+        if file.as_os_str() != ":memory:" {
+            if file.is_dir() {
+                return Err(invalid_path(db_path, "path is a directory"));
+            }
+            if let Some(parent) = file.parent().filter(|p| !p.as_os_str().is_empty()) {
+                tokio::fs::create_dir_all(parent)
+                    .await
+                    .map_err(|e| invalid_path(db_path, format!("cannot create {}: {}", parent.display(), e)))?;
+            }
+        }
+
+        let connect = sqlx::sqlite::SqlitePoolOptions::new()
+            .acquire_timeout(CONNECT_TIMEOUT)
+            .connect_with(options);
+        let pool = tokio::time::timeout(CONNECT_TIMEOUT, connect)
             .await
-            .map_err(|e| StorageError::Other(e.to_string()))?;
+            .map_err(|_| StorageError::Busy(format!("no connection to {} within {:?}", db_path, CONNECT_TIMEOUT)))?
+            .map_err(|e| open_error(db_path, e))?;
+
+        // Reading the schema is the first access to the file's contents, so
+        // a damaged file or a held lock shows up here
+        sqlx::query("SELECT count(*) FROM sqlite_master")
+            .fetch_one(&pool)
+            .await
+            .map_err(|e| open_error(db_path, e))?;
 
         let storage = Self { pool };
         storage.init_schema().await?;
@@ -70,12 +143,55 @@ impl SqliteStorage {
         .await
         .map_err(|e| StorageError::Other(e.to_string()))?;
 
+        // Content-addressed blobs, such as artifact content
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS blobs (
+                id TEXT PRIMARY KEY,
+                content BLOB NOT NULL,
+                created_at TEXT NOT NULL
+            )",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        // Denormalized task_id for work records, backfilled on older databases
+        let has_task_id = sqlx::query("SELECT 1 FROM pragma_table_info('entities') WHERE name = 'task_id'")
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| StorageError::Other(e.to_string()))?
+            .is_some();
+
+        if !has_task_id {
+            sqlx::query("ALTER TABLE entities ADD COLUMN task_id TEXT")
+                .execute(&self.pool)
+                .await
+                .map_err(|e| StorageError::Other(e.to_string()))?;
+
+            sqlx::query(
+                "UPDATE entities SET task_id = json_extract(data, '$.task_id') WHERE entity_type = 'work_record'",
+            )
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StorageError::Other(e.to_string()))?;
+        }
+
         // Create indexes
         sqlx::query("CREATE INDEX IF NOT EXISTS idx_entities_type ON entities(entity_type)")
             .execute(&self.pool)
             .await
             .map_err(|e| StorageError::Other(e.to_string()))?;
 
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_entities_task_id ON entities(task_id)")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_entities_type_updated ON entities(entity_type, updated_at)")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StorageError::Other(e.to_string()))?;
+
         Ok(())
     }
 
@@ -102,7 +218,7 @@ impl Storage for SqliteStorage {
     // === Goal operations ===
 
     async fn save_goal(&mut self, goal: &Goal) -> Result<()> {
-        let data = serde_json::to_string(goal).map_err(|e| StorageError::Json(e.into()))?;
+        let data = serde_json::to_string(goal)?;
         let now = chrono::Utc::now();
 
         sqlx::query(
@@ -132,8 +248,7 @@ impl Storage for SqliteStorage {
         match row {
             Ok(row) => {
                 let data = Self::get_string(&row, "data");
-                let goal: Goal = serde_json::from_str(&data)
-                    .map_err(|e| StorageError::Json(e.into()))?;
+                let goal: Goal = serde_json::from_str(&data)?;
                 Ok(Some(goal))
             }
             Err(sqlx::Error::RowNotFound) => Ok(None),
@@ -154,7 +269,7 @@ impl Storage for SqliteStorage {
             .map(|row| {
                 let data = Self::get_string(&row, "data");
                 serde_json::from_str(&data)
-                    .map_err(|e| StorageError::Json(e.into()))
+                    .map_err(StorageError::Json)
             })
             .collect::<Result<Vec<_>>>()?;
 
@@ -164,7 +279,7 @@ impl Storage for SqliteStorage {
     // === Project operations ===
 
     async fn save_project(&mut self, project: &Project) -> Result<()> {
-        let data = serde_json::to_string(project).map_err(|e| StorageError::Json(e.into()))?;
+        let data = serde_json::to_string(project)?;
         let now = chrono::Utc::now();
 
         sqlx::query(
@@ -194,8 +309,7 @@ impl Storage for SqliteStorage {
         match row {
             Ok(row) => {
                 let data = Self::get_string(&row, "data");
-                let project: Project = serde_json::from_str(&data)
-                    .map_err(|e| StorageError::Json(e.into()))?;
+                let project: Project = serde_json::from_str(&data)?;
                 Ok(Some(project))
             }
             Err(sqlx::Error::RowNotFound) => Ok(None),
@@ -203,10 +317,21 @@ impl Storage for SqliteStorage {
         }
     }
 
+    async fn list_projects(&self) -> Result<Vec<Project>> {
+        let rows = sqlx::query("SELECT data FROM entities WHERE entity_type = 'project' ORDER BY created_at")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        rows.into_iter()
+            .map(|row| serde_json::from_str(&Self::get_string(&row, "data")).map_err(StorageError::Json))
+            .collect()
+    }
+
     // === Phase operations ===
 
     async fn save_phase(&mut self, phase: &Phase) -> Result<()> {
-        let data = serde_json::to_string(phase).map_err(|e| StorageError::Json(e.into()))?;
+        let data = serde_json::to_string(phase)?;
         let now = chrono::Utc::now();
 
         sqlx::query(
@@ -236,8 +361,7 @@ impl Storage for SqliteStorage {
         match row {
             Ok(row) => {
                 let data = Self::get_string(&row, "data");
-                let phase: Phase = serde_json::from_str(&data)
-                    .map_err(|e| StorageError::Json(e.into()))?;
+                let phase: Phase = serde_json::from_str(&data)?;
                 Ok(Some(phase))
             }
             Err(sqlx::Error::RowNotFound) => Ok(None),
@@ -245,10 +369,21 @@ impl Storage for SqliteStorage {
         }
     }
 
+    async fn list_phases(&self) -> Result<Vec<Phase>> {
+        let rows = sqlx::query("SELECT data FROM entities WHERE entity_type = 'phase' ORDER BY created_at")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        rows.into_iter()
+            .map(|row| serde_json::from_str(&Self::get_string(&row, "data")).map_err(StorageError::Json))
+            .collect()
+    }
+
     // === Task operations ===
 
     async fn save_task(&mut self, task: &Task) -> Result<()> {
-        let data = serde_json::to_string(task).map_err(|e| StorageError::Json(e.into()))?;
+        let data = serde_json::to_string(task)?;
         let now = chrono::Utc::now();
 
         sqlx::query(
@@ -278,8 +413,7 @@ impl Storage for SqliteStorage {
         match row {
             Ok(row) => {
                 let data = Self::get_string(&row, "data");
-                let task: Task = serde_json::from_str(&data)
-                    .map_err(|e| StorageError::Json(e.into()))?;
+                let task: Task = serde_json::from_str(&data)?;
                 Ok(Some(task))
             }
             Err(sqlx::Error::RowNotFound) => Ok(None),
@@ -288,9 +422,14 @@ impl Storage for SqliteStorage {
     }
 
     async fn list_tasks(&self, filter: &TaskFilter) -> Result<Vec<Task>> {
-        let rows = sqlx::query(
-            "SELECT id, data, created_at, updated_at FROM entities WHERE entity_type = 'task' ORDER BY updated_at DESC",
-        )
+        let sql = if filter.roots_only {
+            "SELECT id, data, created_at, updated_at FROM entities
+            WHERE entity_type = 'task' AND json_extract(data, '$.parent') IS NULL
+            ORDER BY updated_at DESC"
+        } else {
+            "SELECT id, data, created_at, updated_at FROM entities WHERE entity_type = 'task' ORDER BY updated_at DESC"
+        };
+        let rows = sqlx::query(sql)
         .fetch_all(&self.pool)
         .await
         .map_err(|e| StorageError::Other(e.to_string()))?;
@@ -300,7 +439,7 @@ impl Storage for SqliteStorage {
             .map(|row| {
                 let data = Self::get_string(&row, "data");
                 serde_json::from_str(&data)
-                    .map_err(|e| StorageError::Json(e.into()))
+                    .map_err(StorageError::Json)
             })
             .collect::<Result<Vec<_>>>()?;
 
@@ -310,6 +449,26 @@ impl Storage for SqliteStorage {
             tasks.retain(|t| status_set.contains(&t.status));
         }
 
+        if filter.roots_only && filter.include_subtasks {
+            let mut seen = std::collections::HashSet::new();
+            let mut listed = Vec::new();
+            for root in tasks {
+                let mut stack = vec![root];
+                while let Some(task) = stack.pop() {
+                    if !seen.insert(task.id) {
+                        continue;
+                    }
+                    for child_id in task.children.iter().rev() {
+                        if let Some(child) = self.load_task(*child_id).await? {
+                            stack.push(child);
+                        }
+                    }
+                    listed.push(task);
+                }
+            }
+            return Ok(listed);
+        }
+
         Ok(tasks)
     }
 
@@ -326,7 +485,7 @@ impl Storage for SqliteStorage {
     // === Event operations ===
 
     async fn save_event(&mut self, event: &Event) -> Result<()> {
-        let data = serde_json::to_string(event).map_err(|e| StorageError::Json(e.into()))?;
+        let data = serde_json::to_string(event)?;
         let now = chrono::Utc::now();
 
         sqlx::query(
@@ -356,8 +515,7 @@ impl Storage for SqliteStorage {
         match row {
             Ok(row) => {
                 let data = Self::get_string(&row, "data");
-                let event: Event = serde_json::from_str(&data)
-                    .map_err(|e| StorageError::Json(e.into()))?;
+                let event: Event = serde_json::from_str(&data)?;
                 Ok(Some(event))
             }
             Err(sqlx::Error::RowNotFound) => Ok(None),
@@ -378,19 +536,29 @@ impl Storage for SqliteStorage {
             .map(|row| {
                 let data = Self::get_string(&row, "data");
                 serde_json::from_str(&data)
-                    .map_err(|e| StorageError::Json(e.into()))
+                    .map_err(StorageError::Json)
             })
             .collect::<Result<Vec<_>>>()?;
 
         Ok(events)
     }
 
+    async fn delete_event(&mut self, id: EventId) -> Result<()> {
+        sqlx::query("DELETE FROM entities WHERE id = ? AND entity_type = 'event'")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        Ok(())
+    }
+
     // === Knowledge operations ===
 
     async fn save_knowledge(&mut self, knowledge: &Knowledge) -> Result<()> {
-        let data = serde_json::to_string(knowledge).map_err(|e| StorageError::Json(e.into()))?;
-        let now = chrono::Utc::now();
+        let data = serde_json::to_string(knowledge)?;
 
+        // Row timestamps mirror the item's own so recency queries can use the index
         sqlx::query(
             "INSERT OR REPLACE INTO entities (id, entity_type, data, created_at, updated_at)
             VALUES (?, ?, ?, ?, ?)",
@@ -398,8 +566,8 @@ impl Storage for SqliteStorage {
         .bind(knowledge.id.to_string())
         .bind("knowledge")
         .bind(data)
-        .bind(now.to_rfc3339())
-        .bind(now.to_rfc3339())
+        .bind(knowledge.created_at.to_rfc3339())
+        .bind(knowledge.updated_at.to_rfc3339())
         .execute(&self.pool)
         .await
         .map_err(|e| StorageError::Other(e.to_string()))?;
@@ -418,8 +586,7 @@ impl Storage for SqliteStorage {
         match row {
             Ok(row) => {
                 let data = Self::get_string(&row, "data");
-                let knowledge: Knowledge = serde_json::from_str(&data)
-                    .map_err(|e| StorageError::Json(e.into()))?;
+                let knowledge: Knowledge = serde_json::from_str(&data)?;
                 Ok(Some(knowledge))
             }
             Err(sqlx::Error::RowNotFound) => Ok(None),
@@ -440,16 +607,35 @@ impl Storage for SqliteStorage {
             .map(|row| {
                 let data = Self::get_string(&row, "data");
                 serde_json::from_str(&data)
-                    .map_err(|e| StorageError::Json(e.into()))
+                    .map_err(StorageError::Json)
             })
             .collect::<Result<Vec<_>>>()?;
 
         Ok(knowledge_items)
     }
 
+    async fn list_recent_knowledge(&self, limit: usize) -> Result<Vec<Knowledge>> {
+        let rows = sqlx::query(
+            "SELECT data FROM entities WHERE entity_type = 'knowledge' ORDER BY updated_at DESC LIMIT ?",
+        )
+        .bind(i64::try_from(limit).unwrap_or(-1))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        rows.into_iter()
+            .map(|row| {
+                let data = Self::get_string(&row, "data");
+                serde_json::from_str(&data).map_err(StorageError::Json)
+            })
+            .collect()
+    }
+
     // === Vector Embedding operations ===
 
     async fn save_vector_embedding(&mut self, embedding: &KnowledgeEmbedding) -> Result<()> {
+        super::trait_::guard_embedding_model(self, embedding).await?;
+
         let embedding_bytes = embedding
             .embedding
             .iter()
@@ -535,10 +721,278 @@ impl Storage for SqliteStorage {
             .collect())
     }
 
+    async fn load_active_embedding_model(&self) -> Result<Option<ActiveEmbeddingModel>> {
+        let row = sqlx::query(
+            "SELECT data FROM entities WHERE id = 'embedding_model' AND entity_type = 'embedding_model'",
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        row.map(|row| {
+            serde_json::from_str(&Self::get_string(&row, "data")).map_err(StorageError::Json)
+        })
+        .transpose()
+    }
+
+    async fn save_active_embedding_model(&mut self, active: &ActiveEmbeddingModel) -> Result<()> {
+        let data = serde_json::to_string(active)?;
+        let now = chrono::Utc::now();
+
+        sqlx::query(
+            "INSERT OR REPLACE INTO entities (id, entity_type, data, created_at, updated_at)
+            VALUES ('embedding_model', 'embedding_model', ?, ?, ?)",
+        )
+        .bind(data)
+        .bind(now.to_rfc3339())
+        .bind(now.to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn load_reindex_checkpoint(&self) -> Result<Option<ReindexCheckpoint>> {
+        let row = sqlx::query(
+            "SELECT data FROM entities WHERE id = 'reindex_checkpoint' AND entity_type = 'reindex_checkpoint'",
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        row.map(|row| {
+            serde_json::from_str(&Self::get_string(&row, "data")).map_err(StorageError::Json)
+        })
+        .transpose()
+    }
+
+    async fn save_reindex_checkpoint(&mut self, checkpoint: &ReindexCheckpoint) -> Result<()> {
+        let data = serde_json::to_string(checkpoint)?;
+        let now = chrono::Utc::now();
+
+        sqlx::query(
+            "INSERT OR REPLACE INTO entities (id, entity_type, data, created_at, updated_at)
+            VALUES ('reindex_checkpoint', 'reindex_checkpoint', ?, ?, ?)",
+        )
+        .bind(data)
+        .bind(now.to_rfc3339())
+        .bind(now.to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn clear_reindex_checkpoint(&mut self) -> Result<()> {
+        sqlx::query("DELETE FROM entities WHERE id = 'reindex_checkpoint' AND entity_type = 'reindex_checkpoint'")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        Ok(())
+    }
+
+    // === Blocker operations ===
+
+    async fn save_blocker(&mut self, blocker: &Blocker) -> Result<()> {
+        let data = serde_json::to_string(blocker)?;
+        let now = chrono::Utc::now();
+
+        sqlx::query(
+            "INSERT OR REPLACE INTO entities (id, entity_type, data, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(blocker.id.to_string())
+        .bind("blocker")
+        .bind(data)
+        .bind(blocker.created_at.to_rfc3339())
+        .bind(now.to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn load_blocker(&self, id: BlockerId) -> Result<Option<Blocker>> {
+        let row = sqlx::query("SELECT data FROM entities WHERE id = ? AND entity_type = 'blocker'")
+            .bind(id.to_string())
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        row.map(|row| serde_json::from_str(&Self::get_string(&row, "data")).map_err(StorageError::Json))
+            .transpose()
+    }
+
+    async fn list_blockers(&self) -> Result<Vec<Blocker>> {
+        let rows = sqlx::query("SELECT data FROM entities WHERE entity_type = 'blocker' ORDER BY created_at")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        rows.into_iter()
+            .map(|row| serde_json::from_str(&Self::get_string(&row, "data")).map_err(StorageError::Json))
+            .collect()
+    }
+
+    // === Quality Profile operations ===
+
+    async fn save_quality_profile(&mut self, profile: &QualityProfile) -> Result<()> {
+        let data = serde_json::to_string(profile)?;
+        let now = chrono::Utc::now();
+
+        sqlx::query(
+            "INSERT OR REPLACE INTO entities (id, entity_type, data, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(profile.id.to_string())
+        .bind("quality_profile")
+        .bind(data)
+        .bind(now.to_rfc3339())
+        .bind(now.to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn load_quality_profile(&self, id: QualityProfileId) -> Result<Option<QualityProfile>> {
+        let row = sqlx::query("SELECT data FROM entities WHERE id = ? AND entity_type = 'quality_profile'")
+            .bind(id.to_string())
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        row.map(|row| serde_json::from_str(&Self::get_string(&row, "data")).map_err(StorageError::Json))
+            .transpose()
+    }
+
+    async fn list_quality_profiles(&self) -> Result<Vec<QualityProfile>> {
+        let rows = sqlx::query("SELECT data FROM entities WHERE entity_type = 'quality_profile' ORDER BY created_at")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        rows.into_iter()
+            .map(|row| serde_json::from_str(&Self::get_string(&row, "data")).map_err(StorageError::Json))
+            .collect()
+    }
+
+    // === Task Template operations ===
+
+    async fn save_task_template(&mut self, template: &TaskTemplate) -> Result<()> {
+        let data = serde_json::to_string(template)?;
+        let now = chrono::Utc::now();
+
+        sqlx::query(
+            "INSERT OR REPLACE INTO entities (id, entity_type, data, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(template.id.to_string())
+        .bind("task_template")
+        .bind(data)
+        .bind(template.created_at.to_rfc3339())
+        .bind(now.to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn load_task_template(&self, id: TaskTemplateId) -> Result<Option<TaskTemplate>> {
+        let row = sqlx::query("SELECT data FROM entities WHERE id = ? AND entity_type = 'task_template'")
+            .bind(id.to_string())
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        row.map(|row| serde_json::from_str(&Self::get_string(&row, "data")).map_err(StorageError::Json))
+            .transpose()
+    }
+
+    async fn list_task_templates(&self) -> Result<Vec<TaskTemplate>> {
+        let rows = sqlx::query("SELECT data FROM entities WHERE entity_type = 'task_template' ORDER BY created_at")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        rows.into_iter()
+            .map(|row| serde_json::from_str(&Self::get_string(&row, "data")).map_err(StorageError::Json))
+            .collect()
+    }
+
+    // === Blob operations ===
+
+    async fn put_blob(&mut self, content: &[u8]) -> Result<BlobId> {
+        let id = BlobId::of(content);
+
+        sqlx::query("INSERT OR IGNORE INTO blobs (id, content, created_at) VALUES (?, ?, ?)")
+            .bind(&id.0)
+            .bind(content)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        Ok(id)
+    }
+
+    async fn get_blob(&self, id: &BlobId) -> Result<Option<Vec<u8>>> {
+        let row = sqlx::query("SELECT content FROM blobs WHERE id = ?")
+            .bind(&id.0)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        Ok(row.map(|row| row.try_get("content").unwrap_or_default()))
+    }
+
+    // === Quality Run operations ===
+
+    async fn save_quality_run(&mut self, run: &QualityRun) -> Result<()> {
+        let data = serde_json::to_string(run)?;
+
+        sqlx::query(
+            "INSERT OR REPLACE INTO entities (id, entity_type, data, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(run.id.to_string())
+        .bind("quality_run")
+        .bind(data)
+        .bind(run.run_at.to_rfc3339())
+        .bind(run.run_at.to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn list_quality_runs(&self, task_id: TaskId) -> Result<Vec<QualityRun>> {
+        let rows = sqlx::query("SELECT data FROM entities WHERE entity_type = 'quality_run' ORDER BY created_at, id")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        let mut runs = Vec::new();
+        for row in rows {
+            let run: QualityRun = serde_json::from_str(&Self::get_string(&row, "data")).map_err(StorageError::Json)?;
+            if run.task_id == task_id {
+                runs.push(run);
+            }
+        }
+        Ok(runs)
+    }
+
     // === Quality Check operations ===
 
     async fn save_quality_check(&mut self, check: &QualityCheck) -> Result<()> {
-        let data = serde_json::to_string(check).map_err(|e| StorageError::Json(e.into()))?;
+        let data = serde_json::to_string(check)?;
         let now = chrono::Utc::now();
 
         sqlx::query(
@@ -568,8 +1022,7 @@ impl Storage for SqliteStorage {
         match row {
             Ok(row) => {
                 let data = Self::get_string(&row, "data");
-                let check: QualityCheck = serde_json::from_str(&data)
-                    .map_err(|e| StorageError::Json(e.into()))?;
+                let check: QualityCheck = serde_json::from_str(&data)?;
                 Ok(Some(check))
             }
             Err(sqlx::Error::RowNotFound) => Ok(None),
@@ -590,7 +1043,7 @@ impl Storage for SqliteStorage {
             .map(|row| {
                 let data = Self::get_string(&row, "data");
                 serde_json::from_str(&data)
-                    .map_err(|e| StorageError::Json(e.into()))
+                    .map_err(StorageError::Json)
             })
             .collect::<Result<Vec<_>>>()?;
 
@@ -600,18 +1053,19 @@ impl Storage for SqliteStorage {
     // === Work Record operations ===
 
     async fn save_work_record(&mut self, record: &WorkRecord) -> Result<()> {
-        let data = serde_json::to_string(record).map_err(|e| StorageError::Json(e.into()))?;
+        let data = serde_json::to_string(record)?;
         let now = chrono::Utc::now();
 
         sqlx::query(
-            "INSERT OR REPLACE INTO entities (id, entity_type, data, created_at, updated_at)
-            VALUES (?, ?, ?, ?, ?)",
+            "INSERT OR REPLACE INTO entities (id, entity_type, data, created_at, updated_at, task_id)
+            VALUES (?, ?, ?, ?, ?, ?)",
         )
         .bind(record.id.to_string())
         .bind("work_record")
         .bind(data)
         .bind(now.to_rfc3339())
         .bind(now.to_rfc3339())
+        .bind(record.task_id.to_string())
         .execute(&self.pool)
         .await
         .map_err(|e| StorageError::Other(e.to_string()))?;
@@ -630,8 +1084,7 @@ impl Storage for SqliteStorage {
         match row {
             Ok(row) => {
                 let data = Self::get_string(&row, "data");
-                let record: WorkRecord = serde_json::from_str(&data)
-                    .map_err(|e| StorageError::Json(e.into()))?;
+                let record: WorkRecord = serde_json::from_str(&data)?;
                 Ok(Some(record))
             }
             Err(sqlx::Error::RowNotFound) => Ok(None),
@@ -641,22 +1094,186 @@ impl Storage for SqliteStorage {
 
     async fn list_work_records(&self, task_id: TaskId) -> Result<Vec<WorkRecord>> {
         let rows = sqlx::query(
-            "SELECT id, data, created_at, updated_at FROM entities WHERE entity_type = 'work_record' ORDER BY updated_at DESC",
+            "SELECT id, data, created_at, updated_at FROM entities WHERE entity_type = 'work_record' AND task_id = ? ORDER BY updated_at DESC",
         )
+        .bind(task_id.to_string())
         .fetch_all(&self.pool)
         .await
         .map_err(|e| StorageError::Other(e.to_string()))?;
 
-        let records: Vec<WorkRecord> = rows
-            .into_iter()
+        rows.into_iter()
             .map(|row| {
                 let data = Self::get_string(&row, "data");
                 serde_json::from_str(&data)
-                    .map_err(|e| StorageError::Json(e.into()))
+                    .map_err(StorageError::Json)
             })
-            .collect::<Result<Vec<_>>>()?;
+            .collect()
+    }
+
+    fn stream_work_records(&self) -> BoxStream<'_, Result<WorkRecord>> {
+        Box::pin(
+            sqlx::query("SELECT data FROM entities WHERE entity_type = 'work_record'")
+                .fetch(&self.pool)
+                .map(|row| {
+                    let row = row.map_err(|e| StorageError::Other(e.to_string()))?;
+                    Ok(serde_json::from_str(&Self::get_string(&row, "data"))?)
+                }),
+        )
+    }
+
+    async fn query_work_records(
+        &self,
+        task_id: TaskId,
+        filter: &WorkRecordFilter,
+    ) -> Result<Vec<WorkRecord>> {
+        let mut sql = String::from(
+            "SELECT id, data, created_at, updated_at FROM entities WHERE entity_type = 'work_record' AND task_id = ?",
+        );
+        if filter.since.is_some() {
+            sql.push_str(" AND julianday(json_extract(data, '$.started_at')) >= julianday(?)");
+        }
+        sql.push_str(" ORDER BY julianday(json_extract(data, '$.started_at')) DESC LIMIT ?");
+
+        let mut query = sqlx::query(&sql).bind(task_id.to_string());
+        if let Some(since) = filter.since {
+            query = query.bind(since.to_rfc3339());
+        }
+        // A negative LIMIT means no limit in SQLite
+        let limit = filter.limit.map(|l| l as i64).unwrap_or(-1);
+        let rows = query
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        rows.into_iter()
+            .map(|row| {
+                let data = Self::get_string(&row, "data");
+                serde_json::from_str(&data)
+                    .map_err(StorageError::Json)
+            })
+            .collect()
+    }
+
+    async fn delete_work_record(&mut self, id: WorkRecordId) -> Result<()> {
+        sqlx::query("DELETE FROM entities WHERE id = ? AND entity_type = 'work_record'")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StorageError::Other(e.to_string()))?;
 
-        Ok(records.into_iter().filter(|r| r.task_id == task_id).collect())
+        Ok(())
+    }
+
+    // === Idempotency keys ===
+
+    async fn save_idempotency_record(&mut self, record: &IdempotencyRecord) -> Result<()> {
+        let data = serde_json::to_string(record)?;
+
+        sqlx::query(
+            "INSERT OR REPLACE INTO entities (id, entity_type, data, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(format!("idempotency:{}", record.key))
+        .bind("idempotency_key")
+        .bind(data)
+        .bind(record.created_at.to_rfc3339())
+        .bind(record.created_at.to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn load_idempotency_record(&self, key: &str) -> Result<Option<IdempotencyRecord>> {
+        let row = sqlx::query(
+            "SELECT data FROM entities WHERE id = ? AND entity_type = 'idempotency_key'",
+        )
+        .bind(format!("idempotency:{}", key))
+        .fetch_one(&self.pool)
+        .await;
+
+        match row {
+            Ok(row) => {
+                let data = Self::get_string(&row, "data");
+                let record = serde_json::from_str(&data)?;
+                Ok(Some(record))
+            }
+            Err(sqlx::Error::RowNotFound) => Ok(None),
+            Err(e) => Err(StorageError::Other(e.to_string())),
+        }
+    }
+
+    async fn purge_idempotency_records(&mut self, cutoff: devman_core::Time) -> Result<usize> {
+        let rows = sqlx::query("SELECT id, data FROM entities WHERE entity_type = 'idempotency_key'")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        let mut removed = 0;
+        for row in rows {
+            let record: IdempotencyRecord = serde_json::from_str(&Self::get_string(&row, "data"))?;
+            if record.created_at < cutoff {
+                sqlx::query("DELETE FROM entities WHERE id = ?")
+                    .bind(Self::get_string(&row, "id"))
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|e| StorageError::Other(e.to_string()))?;
+                removed += 1;
+            }
+        }
+
+        Ok(removed)
+    }
+
+    // === Runtime settings ===
+
+    async fn save_config_entry(&mut self, entry: &ConfigEntry) -> Result<()> {
+        let data = serde_json::to_string(entry)?;
+
+        sqlx::query(
+            "INSERT OR REPLACE INTO entities (id, entity_type, data, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(format!("config:{}", entry.key))
+        .bind("config")
+        .bind(data)
+        .bind(entry.updated_at.to_rfc3339())
+        .bind(entry.updated_at.to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn load_config_entry(&self, key: &str) -> Result<Option<ConfigEntry>> {
+        let row = sqlx::query("SELECT data FROM entities WHERE id = ? AND entity_type = 'config'")
+            .bind(format!("config:{}", key))
+            .fetch_one(&self.pool)
+            .await;
+
+        match row {
+            Ok(row) => {
+                let data = Self::get_string(&row, "data");
+                let entry = serde_json::from_str(&data)?;
+                Ok(Some(entry))
+            }
+            Err(sqlx::Error::RowNotFound) => Ok(None),
+            Err(e) => Err(StorageError::Other(e.to_string())),
+        }
+    }
+
+    async fn list_config_entries(&self) -> Result<Vec<ConfigEntry>> {
+        let rows = sqlx::query("SELECT data FROM entities WHERE entity_type = 'config' ORDER BY id")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        rows.iter()
+            .map(|row| serde_json::from_str(&Self::get_string(row, "data")).map_err(StorageError::Json))
+            .collect()
     }
 
     // === Transaction support ===
@@ -669,6 +1286,39 @@ impl Storage for SqliteStorage {
         warn!("Rollback called on SqliteStorage");
         Ok(())
     }
+
+    // === Maintenance ===
+
+    async fn compact(&mut self) -> Result<CompactReport> {
+        let size_before = self.database_size().await?;
+
+        // Embeddings whose knowledge entity was removed
+        let removed = sqlx::query(
+            "DELETE FROM embeddings WHERE knowledge_id NOT IN
+                (SELECT id FROM entities WHERE entity_type = 'knowledge')",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StorageError::Other(e.to_string()))?
+        .rows_affected();
+
+        sqlx::query("REINDEX")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        sqlx::query("VACUUM")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        let size_after = self.database_size().await?;
+
+        Ok(CompactReport {
+            removed: removed as usize,
+            reclaimed_bytes: size_before.saturating_sub(size_after),
+        })
+    }
 }
 
 // === Extended query methods ===
@@ -688,32 +1338,58 @@ impl SqliteStorage {
             .map(|row| {
                 let data = Self::get_string(&row, "data");
                 serde_json::from_str(&data)
-                    .map_err(|e| StorageError::Json(e.into()))
+                    .map_err(StorageError::Json)
             })
             .collect::<Result<Vec<_>>>()?;
 
         Ok(tasks.into_iter().filter(|t| t.status == devman_core::TaskStatus::Blocked).collect())
     }
 
-    /// Find recent active tasks by work record activity.
-    pub async fn find_recent_active_tasks(&self, _days: i32, limit: i32) -> Result<Vec<Task>> {
+    /// Find the tasks worked on within the last `days` before the time on
+    /// `clock`, most recently updated first, at most `limit` of them.
+    ///
+    /// A task qualifies when it is active (or in progress in the interactive
+    /// flow), was updated within the window, and has a work record that
+    /// started, completed or logged an event within it.
+    pub async fn find_recent_active_tasks(
+        &self,
+        days: i32,
+        limit: i32,
+        clock: &dyn devman_core::Clock,
+    ) -> Result<Vec<Task>> {
+        let since = (clock.now() - chrono::Duration::days(i64::from(days))).to_rfc3339();
         let rows = sqlx::query(
-            "SELECT id, data, created_at, updated_at FROM entities WHERE entity_type = 'task' ORDER BY updated_at DESC LIMIT 100"
+            "SELECT t.data FROM entities t
+            WHERE t.entity_type = 'task'
+              AND (json_extract(t.data, '$.status') = 'Active'
+                   OR json_extract(t.data, '$.state.InProgress') IS NOT NULL)
+              AND julianday(json_extract(t.data, '$.updated_at')) >= julianday(?1)
+              AND EXISTS (
+                  SELECT 1 FROM entities w
+                  WHERE w.entity_type = 'work_record' AND w.task_id = t.id
+                    AND (julianday(json_extract(w.data, '$.started_at')) >= julianday(?1)
+                         OR julianday(json_extract(w.data, '$.completed_at')) >= julianday(?1)
+                         OR EXISTS (
+                             SELECT 1 FROM json_each(w.data, '$.events') e
+                             WHERE julianday(json_extract(e.value, '$.timestamp')) >= julianday(?1)
+                         ))
+              )
+            ORDER BY julianday(json_extract(t.data, '$.updated_at')) DESC
+            LIMIT ?2",
         )
+        .bind(since)
+        .bind(i64::from(limit.max(0)))
         .fetch_all(&self.pool)
         .await
         .map_err(|e| StorageError::Other(e.to_string()))?;
 
-        let tasks: Vec<Task> = rows
-            .into_iter()
+        rows.into_iter()
             .map(|row| {
                 let data = Self::get_string(&row, "data");
                 serde_json::from_str(&data)
-                    .map_err(|e| StorageError::Json(e.into()))
+                    .map_err(StorageError::Json)
             })
-            .collect::<Result<Vec<_>>>()?;
-
-        Ok(tasks.into_iter().take(limit as usize).collect())
+            .collect()
     }
 
     /// Get task statistics.
@@ -730,7 +1406,7 @@ impl SqliteStorage {
             .map(|row| {
                 let data = Self::get_string(&row, "data");
                 serde_json::from_str(&data)
-                    .map_err(|e| StorageError::Json(e.into()))
+                    .map_err(StorageError::Json)
             })
             .collect::<Result<Vec<_>>>()?;
 
@@ -742,6 +1418,19 @@ impl SqliteStorage {
         })
     }
 
+    /// Database size in bytes, from page count and page size.
+    async fn database_size(&self) -> Result<u64> {
+        let row = sqlx::query(
+            "SELECT page_count * page_size AS size FROM pragma_page_count(), pragma_page_size()",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| StorageError::Other(e.to_string()))?;
+
+        let size: i64 = row.try_get("size").unwrap_or(0);
+        Ok(size.max(0) as u64)
+    }
+
     /// Check if the database is healthy.
     pub async fn health_check(&self) -> bool {
         sqlx::query("SELECT 1").fetch_one(&self.pool).await.is_ok()
@@ -788,12 +1477,86 @@ mod tests {
             progress: TaskProgress::default(),
             depends_on: vec![],
             blocks: vec![],
+            parent: None,
+            children: vec![],
+            archived: false,
+            priority: devman_core::Priority::default().value(),
             work_records: vec![],
+            actuals: None,
+            claim: None,
+            state: None,
+            merged_into: None,
+            base_priority: None,
+            due_at: None,
+            created_knowledge: Vec::new(),
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         }
     }
 
+    fn create_test_knowledge(title: &str, updated_at: devman_core::Time) -> Knowledge {
+        Knowledge {
+            id: KnowledgeId::new(),
+            title: title.to_string(),
+            knowledge_type: devman_core::KnowledgeType::LessonLearned {
+                lesson: "Test lesson".to_string(),
+                context: "Test context".to_string(),
+            },
+            content: devman_core::KnowledgeContent {
+                summary: "Summary".to_string(),
+                detail: String::new(),
+                examples: vec![],
+                references: vec![],
+            },
+            metadata: devman_core::KnowledgeMetadata {
+                domain: vec![],
+                tech_stack: vec![],
+                scenarios: vec![],
+                quality_score: 1.0,
+                verified: false,
+            },
+            tags: vec![],
+            related_to: vec![],
+            derived_from: vec![],
+            usage_stats: devman_core::UsageStats {
+                times_used: 0,
+                last_used: None,
+                success_rate: 0.0,
+                feedback: vec![],
+            },
+            archived: false,
+            created_at: updated_at,
+            updated_at,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_new_creates_missing_parent_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("deeper").join("devman.db");
+
+        let mut storage = SqliteStorage::new(path.to_str().unwrap()).await.unwrap();
+        let task = create_test_task();
+        storage.save_task(&task).await.unwrap();
+        assert!(path.is_file());
+        assert!(storage.load_task(task.id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_new_rejects_directory_and_corrupt_file() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let err = SqliteStorage::new(dir.path().to_str().unwrap()).await.err().unwrap();
+        assert!(matches!(err, StorageError::InvalidPath { .. }), "{}", err);
+        assert!(!err.is_retryable());
+
+        let garbage = dir.path().join("garbage.db");
+        std::fs::write(&garbage, vec![b'x'; 4096]).unwrap();
+        let err = SqliteStorage::new(garbage.to_str().unwrap()).await.err().unwrap();
+        assert!(matches!(err, StorageError::Corrupt { .. }), "{}", err);
+        assert!(err.to_string().contains("backup"));
+    }
+
     #[tokio::test]
     async fn test_in_memory_storage() {
         let mut storage = SqliteStorage::in_memory().await.unwrap();
@@ -807,6 +1570,7 @@ mod tests {
             project_id: ProjectId::new(),
             current_phase: PhaseId::new(),
             status: GoalStatus::Active,
+            status_history: Vec::new(),
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
@@ -833,6 +1597,31 @@ mod tests {
         assert_eq!(tasks.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_list_tasks_roots_only_with_subtasks() {
+        let mut storage = SqliteStorage::in_memory().await.unwrap();
+
+        let mut parent = create_test_task();
+        let mut first = create_test_task();
+        let mut second = create_test_task();
+        parent.children = vec![first.id, second.id];
+        first.parent = Some(parent.id);
+        second.parent = Some(parent.id);
+        second.status = TaskStatus::Done;
+        for task in [&parent, &first, &second] {
+            storage.save_task(task).await.unwrap();
+        }
+
+        let roots_only = TaskFilter { roots_only: true, ..Default::default() };
+        let roots = storage.list_tasks(&roots_only).await.unwrap();
+        assert_eq!(roots.iter().map(|t| t.id).collect::<Vec<_>>(), vec![parent.id]);
+
+        let with_subtasks = TaskFilter { roots_only: true, include_subtasks: true, ..Default::default() };
+        let tree = storage.list_tasks(&with_subtasks).await.unwrap();
+        assert_eq!(tree.iter().map(|t| t.id).collect::<Vec<_>>(), vec![parent.id, first.id, second.id]);
+        assert_eq!(storage.list_tasks(&TaskFilter::default()).await.unwrap().len(), 3);
+    }
+
     #[tokio::test]
     async fn test_blocked_tasks() {
         let mut storage = SqliteStorage::in_memory().await.unwrap();
@@ -848,6 +1637,58 @@ mod tests {
         assert_eq!(blocked[0].title, "Blocked Task");
     }
 
+    #[tokio::test]
+    async fn test_recent_active_tasks_honor_day_window() {
+        let mut storage = SqliteStorage::in_memory().await.unwrap();
+        let now: chrono::DateTime<chrono::Utc> = "2030-06-01T12:00:00Z".parse().unwrap();
+        let clock = devman_core::MockClock::new(now);
+        let days_ago = |days: i64| now - chrono::Duration::days(days);
+
+        // (title, status, task updated, work record started, logged event)
+        let seeds = [
+            ("recent", TaskStatus::Active, days_ago(1), days_ago(2), None),
+            ("newest", TaskStatus::Active, now, days_ago(30), Some(now)),
+            ("stale", TaskStatus::Active, days_ago(20), days_ago(20), None),
+            ("idle", TaskStatus::Active, days_ago(1), days_ago(30), None),
+            ("finished", TaskStatus::Done, days_ago(1), days_ago(1), None),
+            ("untouched", TaskStatus::Active, days_ago(1), now, None),
+        ];
+        for (title, status, updated_at, started_at, event_at) in seeds {
+            let mut task = create_test_task();
+            task.title = title.to_string();
+            task.status = status;
+            task.updated_at = updated_at;
+            storage.save_task(&task).await.unwrap();
+            if title == "untouched" {
+                continue;
+            }
+
+            let mut record = WorkRecord::new(task.id, devman_core::Executor::AI { model: "test".to_string() }, chrono::Utc::now());
+            record.started_at = started_at;
+            if let Some(at) = event_at {
+                let event = devman_core::WorkEvent::new(
+                    devman_core::WorkEventType::StepCompleted,
+                    "step",
+                    serde_json::Value::Null,
+                    at,
+                );
+                record.events.push(event);
+            }
+            storage.save_work_record(&record).await.unwrap();
+        }
+
+        let titles = |tasks: Vec<Task>| tasks.into_iter().map(|t| t.title).collect::<Vec<_>>();
+        let recent = storage.find_recent_active_tasks(7, 10, &clock).await.unwrap();
+        assert_eq!(titles(recent), vec!["newest", "recent"]);
+        assert_eq!(titles(storage.find_recent_active_tasks(7, 1, &clock).await.unwrap()), vec!["newest"]);
+        let wide = storage.find_recent_active_tasks(25, 10, &clock).await.unwrap();
+        assert_eq!(titles(wide), vec!["newest", "recent", "stale"]);
+
+        // The window ends at the clock's time, not the wall clock's
+        clock.advance(chrono::Duration::days(2));
+        assert_eq!(titles(storage.find_recent_active_tasks(2, 10, &clock).await.unwrap()), vec!["newest"]);
+    }
+
     #[tokio::test]
     async fn test_task_stats() {
         let mut storage = SqliteStorage::in_memory().await.unwrap();
@@ -869,9 +1710,258 @@ mod tests {
         assert_eq!(stats.blocked, 1);
     }
 
+    #[tokio::test]
+    async fn test_compact_removes_orphaned_embeddings() {
+        let mut storage = SqliteStorage::in_memory().await.unwrap();
+
+        let embedding = KnowledgeEmbedding {
+            knowledge_id: KnowledgeId::new(),
+            embedding: vec![0.1, 0.2, 0.3],
+            model: devman_core::EmbeddingModel::Qwen3Embedding0_6B,
+            created_at: chrono::Utc::now(),
+        };
+        storage.save_vector_embedding(&embedding).await.unwrap();
+
+        let report = storage.compact().await.unwrap();
+        assert_eq!(report.removed, 1);
+        assert!(storage.list_vector_embeddings().await.unwrap().is_empty());
+        assert!(storage.health_check().await);
+    }
+
+    #[tokio::test]
+    async fn test_work_records_by_task() {
+        let mut storage = SqliteStorage::in_memory().await.unwrap();
+        let base = chrono::Utc::now();
+
+        let tasks = [TaskId::new(), TaskId::new(), TaskId::new()];
+        for task_id in tasks {
+            for offset in 0..3 {
+                let mut record = WorkRecord::new(task_id, devman_core::Executor::AI { model: "test".to_string() }, chrono::Utc::now());
+                record.started_at = base + chrono::Duration::minutes(offset);
+                storage.save_work_record(&record).await.unwrap();
+            }
+        }
+
+        let records = storage.list_work_records(tasks[1]).await.unwrap();
+        assert_eq!(records.len(), 3);
+        assert!(records.iter().all(|r| r.task_id == tasks[1]));
+
+        let filter = WorkRecordFilter {
+            since: Some(base + chrono::Duration::minutes(1)),
+            limit: None,
+        };
+        let recent = storage.query_work_records(tasks[1], &filter).await.unwrap();
+        assert_eq!(recent.len(), 2);
+        assert!(recent[0].started_at > recent[1].started_at);
+
+        let filter = WorkRecordFilter { since: None, limit: Some(1) };
+        let latest = storage.query_work_records(tasks[1], &filter).await.unwrap();
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest[0].started_at, base + chrono::Duration::minutes(2));
+    }
+
+    #[tokio::test]
+    async fn test_blob_attached_to_artifact_round_trips() {
+        let mut storage = SqliteStorage::in_memory().await.unwrap();
+
+        let report = b"\x00\x01coverage: 87%\xff";
+        let id = storage.put_blob(report).await.unwrap();
+        assert_eq!(id, BlobId::of(report));
+        assert_eq!(storage.put_blob(report).await.unwrap(), id);
+
+        let mut record = WorkRecord::new(TaskId::new(), devman_core::Executor::AI { model: "test".to_string() }, chrono::Utc::now());
+        record.artifacts.push(devman_core::Artifact {
+            name: "coverage report".to_string(),
+            artifact_type: "report".to_string(),
+            location: String::new(),
+            blob: Some(id),
+        });
+        storage.save_work_record(&record).await.unwrap();
+
+        let loaded = storage.load_work_record(record.id).await.unwrap().unwrap();
+        let blob = loaded.artifacts[0].blob.as_ref().unwrap();
+        assert_eq!(storage.require_blob(blob).await.unwrap(), report);
+
+        let missing = storage.require_blob(&BlobId::of(b"other")).await.unwrap_err();
+        assert!(matches!(missing, StorageError::NotFound { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_moved_work_record_leaves_previous_task() {
+        let mut storage = SqliteStorage::in_memory().await.unwrap();
+        let (merged, survivor) = (TaskId::new(), TaskId::new());
+        let mut record = WorkRecord::new(merged, devman_core::Executor::AI { model: "test".to_string() }, chrono::Utc::now());
+        storage.save_work_record(&record).await.unwrap();
+
+        // Merging tasks moves records by rewriting their task
+        record.task_id = survivor;
+        storage.save_work_record(&record).await.unwrap();
+        assert!(storage.list_work_records(merged).await.unwrap().is_empty());
+        let moved: Vec<WorkRecordId> = storage.list_work_records(survivor).await.unwrap().iter().map(|r| r.id).collect();
+        assert_eq!(moved, vec![record.id]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_yields_each_work_record_once() {
+        use futures::TryStreamExt;
+        let mut storage = SqliteStorage::in_memory().await.unwrap();
+
+        let mut saved = std::collections::HashSet::new();
+        for task_id in [TaskId::new(), TaskId::new()] {
+            for _ in 0..5 {
+                let record = WorkRecord::new(task_id, devman_core::Executor::AI { model: "test".to_string() }, chrono::Utc::now());
+                storage.save_work_record(&record).await.unwrap();
+                storage.save_work_record(&record).await.unwrap();
+                saved.insert(record.id);
+            }
+        }
+
+        let streamed: Vec<WorkRecordId> = storage.stream_work_records().map_ok(|r| r.id).try_collect().await.unwrap();
+        assert_eq!(streamed.len(), saved.len());
+        assert_eq!(streamed.into_iter().collect::<std::collections::HashSet<_>>(), saved);
+    }
+
+    #[tokio::test]
+    async fn test_prune_history_removes_old_rows() {
+        let mut storage = SqliteStorage::in_memory().await.unwrap();
+        let task_id = TaskId::new();
+        let old = chrono::Utc::now() - chrono::Duration::days(100);
+
+        let mut event = Event::new(devman_core::AgentId::system(), "log", "ok");
+        event.timestamp = old;
+        storage.save_event(&event).await.unwrap();
+        let recent = Event::new(devman_core::AgentId::system(), "log", "ok");
+        storage.save_event(&recent).await.unwrap();
+        let mut record = WorkRecord::new(task_id, devman_core::Executor::AI { model: "test".to_string() }, chrono::Utc::now());
+        record.started_at = old;
+        storage.save_work_record(&record).await.unwrap();
+
+        let policy = crate::RetentionPolicy { keep_days: 30, keep_per_task: 0, dry_run: false };
+        let report = storage.prune_history(&policy, &devman_core::SystemClock).await.unwrap();
+        assert_eq!(report.events, vec![event.id]);
+        assert_eq!(report.work_records, vec![record.id]);
+        assert!(storage.get_blob(report.archive.as_ref().unwrap()).await.unwrap().is_some());
+        assert!(storage.load_event(event.id).await.unwrap().is_none());
+        assert!(storage.load_event(recent.id).await.unwrap().is_some());
+        assert!(storage.list_work_records(task_id).await.unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_health_check() {
         let storage = SqliteStorage::in_memory().await.unwrap();
         assert!(storage.health_check().await);
     }
+
+    #[tokio::test]
+    async fn test_list_recent_knowledge() {
+        let mut storage = SqliteStorage::in_memory().await.unwrap();
+        let base = chrono::Utc::now() - chrono::Duration::days(1);
+
+        // Saved out of order; titles name the minute offset
+        for offset in [2, 0, 4, 1, 3] {
+            let item = create_test_knowledge(&format!("k{}", offset), base + chrono::Duration::minutes(offset));
+            storage.save_knowledge(&item).await.unwrap();
+        }
+
+        let recent = storage.list_recent_knowledge(3).await.unwrap();
+        let titles: Vec<_> = recent.iter().map(|k| k.title.as_str()).collect();
+        assert_eq!(titles, ["k4", "k3", "k2"]);
+
+        // Updating an item moves it to the front
+        let mut oldest = storage.list_recent_knowledge(5).await.unwrap().pop().unwrap();
+        assert_eq!(oldest.title, "k0");
+        oldest.updated_at = base + chrono::Duration::minutes(10);
+        storage.save_knowledge(&oldest).await.unwrap();
+
+        let recent = storage.list_recent_knowledge(10).await.unwrap();
+        let titles: Vec<_> = recent.iter().map(|k| k.title.as_str()).collect();
+        assert_eq!(titles, ["k0", "k4", "k3", "k2", "k1"]);
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_records() {
+        let mut storage = SqliteStorage::in_memory().await.unwrap();
+
+        let now = chrono::Utc::now();
+        let old = IdempotencyRecord {
+            key: "create:old".to_string(),
+            entity_id: "a".to_string(),
+            created_at: now - chrono::Duration::hours(2),
+        };
+        let fresh = IdempotencyRecord {
+            key: "create:fresh".to_string(),
+            entity_id: "b".to_string(),
+            created_at: now,
+        };
+        storage.save_idempotency_record(&old).await.unwrap();
+        storage.save_idempotency_record(&fresh).await.unwrap();
+
+        assert_eq!(storage.load_idempotency_record("create:fresh").await.unwrap(), Some(fresh.clone()));
+        assert_eq!(storage.load_idempotency_record("create:missing").await.unwrap(), None);
+
+        let removed = storage
+            .purge_idempotency_records(now - chrono::Duration::hours(1))
+            .await
+            .unwrap();
+        assert_eq!(removed, 1);
+        assert_eq!(storage.load_idempotency_record("create:old").await.unwrap(), None);
+        assert_eq!(storage.load_idempotency_record("create:fresh").await.unwrap(), Some(fresh));
+    }
+
+    #[tokio::test]
+    async fn test_config_survives_reload() {
+        use crate::config::{keys, ConfigStore};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("devman.db");
+        let mut storage = SqliteStorage::new(path.to_str().unwrap()).await.unwrap();
+        storage.set_config(keys::QUALITY_CHECK_TIMEOUT_SECS, &90u64).await.unwrap();
+        storage.set_config(keys::KNOWLEDGE_MAX_CONCURRENCY, &2usize).await.unwrap();
+        storage.set_config(keys::QUALITY_CHECK_TIMEOUT_SECS, &120u64).await.unwrap();
+        drop(storage);
+
+        let reloaded = SqliteStorage::new(path.to_str().unwrap()).await.unwrap();
+        assert_eq!(reloaded.get_config::<u64>(keys::QUALITY_CHECK_TIMEOUT_SECS).await.unwrap(), Some(120));
+        let model = reloaded.get_config::<devman_core::EmbeddingModel>(keys::KNOWLEDGE_EMBEDDING_MODEL).await.unwrap();
+        assert!(model.is_none());
+        let names: Vec<_> = reloaded.list_config_entries().await.unwrap().into_iter().map(|e| e.key).collect();
+        assert_eq!(names, [keys::KNOWLEDGE_MAX_CONCURRENCY, keys::QUALITY_CHECK_TIMEOUT_SECS]);
+    }
+
+    #[tokio::test]
+    async fn test_embedding_from_other_model_rejected() {
+        let mut storage = SqliteStorage::in_memory().await.unwrap();
+        let embedding = |model: devman_core::EmbeddingModel, dims: usize| KnowledgeEmbedding {
+            knowledge_id: KnowledgeId::new(),
+            embedding: vec![0.5; dims],
+            model,
+            created_at: chrono::Utc::now(),
+        };
+
+        assert_eq!(storage.load_active_embedding_model().await.unwrap(), None);
+        storage
+            .save_vector_embedding(&embedding(devman_core::EmbeddingModel::Qwen3Embedding0_6B, 1024))
+            .await
+            .unwrap();
+        let active = storage.load_active_embedding_model().await.unwrap().unwrap();
+        assert_eq!(active.dimension, 1024);
+
+        let err = storage
+            .save_vector_embedding(&embedding(devman_core::EmbeddingModel::OpenAIAda002, 1536))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, StorageError::EmbeddingModelMismatch { .. }));
+        assert!(err.to_string().contains("reindex"));
+        let err = storage
+            .save_vector_embedding(&embedding(devman_core::EmbeddingModel::Qwen3Embedding0_6B, 512))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, StorageError::EmbeddingModelMismatch { .. }));
+        assert_eq!(storage.list_vector_embeddings().await.unwrap().len(), 1);
+
+        // Once the active model is switched, the new model is accepted
+        let ada = embedding(devman_core::EmbeddingModel::OpenAIAda002, 1536);
+        storage.save_active_embedding_model(&ActiveEmbeddingModel::of(&ada)).await.unwrap();
+        storage.save_vector_embedding(&ada).await.unwrap();
+    }
 }
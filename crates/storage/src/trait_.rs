@@ -4,8 +4,10 @@ use async_trait::async_trait;
 use devman_core::{
     Goal, GoalId, Project, ProjectId, Phase, PhaseId, Task, TaskId, TaskFilter,
     Event, EventId, Knowledge, KnowledgeId, QualityCheck, QualityCheckId,
-    WorkRecord, WorkRecordId, Blocker, BlockerId, KnowledgeEmbedding,
+    WorkRecord, WorkRecordId, WorkRecordFilter, Blocker, BlockerId, KnowledgeEmbedding, ActiveEmbeddingModel, ReindexCheckpoint, QualityProfile, QualityProfileId, QualityRun, TaskTemplate, TaskTemplateId, BlobId, Time,
 };
+use futures::stream::BoxStream;
+use serde::{Deserialize, Serialize};
 
 /// Error type for storage operations.
 pub type Result<T> = std::result::Result<T, StorageError>;
@@ -21,11 +23,78 @@ pub enum StorageError {
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
 
+    /// A required entity does not exist
+    #[error("{entity_type} not found: {id}")]
+    NotFound {
+        /// Kind of entity, e.g. `"task"`
+        entity_type: String,
+        /// ID that was looked up
+        id: String,
+    },
+
+    /// An embedding disagrees with the store's active embedding model
+    #[error("Embedding from {found} does not match the active embedding model {active}; reindex all knowledge to switch models")]
+    EmbeddingModelMismatch {
+        /// Model and dimension of the stored embeddings
+        active: ActiveEmbeddingModel,
+        /// Model and dimension of the rejected embedding
+        found: ActiveEmbeddingModel,
+    },
+
+    /// An entity changed since it was read, so a versioned write was refused
+    #[error("{entity_type} {id} was modified concurrently (expected version {expected})")]
+    Conflict {
+        /// Kind of entity, e.g. `"task"`
+        entity_type: String,
+        /// ID of the entity
+        id: String,
+        /// Version the writer read
+        expected: u64,
+    },
+
+    /// A database path that cannot be used, such as a directory or a file
+    /// the process may not open
+    #[error("Cannot use {path} as a database: {reason}")]
+    InvalidPath {
+        /// The path given
+        path: String,
+        /// Why it cannot be used
+        reason: String,
+    },
+
+    /// The database is locked by another connection; retrying may succeed
+    #[error("Database is busy: {0}; retry shortly")]
+    Busy(String),
+
+    /// The database file is damaged or is not a database at all
+    #[error("Database {path} is corrupt or not a SQLite database ({message}); restore it from a backup or move it aside to start a new one")]
+    Corrupt {
+        /// The database file
+        path: String,
+        /// Error reported by the database
+        message: String,
+    },
+
     /// Other error
     #[error("{0}")]
     Other(String),
 }
 
+impl StorageError {
+    /// Whether retrying the same operation may succeed.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, StorageError::Busy(_))
+    }
+
+    /// Build a [`StorageError::NotFound`] for `entity_type` with `id`.
+    pub fn not_found(entity_type: &str, id: impl std::fmt::Display) -> Self {
+        StorageError::NotFound {
+            entity_type: entity_type.to_string(),
+            id: id.to_string(),
+        }
+    }
+}
+
 #[cfg(feature = "sqlite")]
 impl From<sqlx::Error> for StorageError {
     fn from(e: sqlx::Error) -> Self {
@@ -33,6 +102,95 @@ impl From<sqlx::Error> for StorageError {
     }
 }
 
+/// Outcome of a storage compaction.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactReport {
+    /// Number of orphaned files or rows removed
+    pub removed: usize,
+    /// Bytes reclaimed on disk
+    pub reclaimed_bytes: u64,
+}
+
+/// Outcome of reconciling the `depends_on` and `blocks` sides of task
+/// dependency edges.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepairReport {
+    /// `depends_on` entries added to mirror an existing `blocks` entry
+    pub added_depends_on: usize,
+    /// `blocks` entries added to mirror an existing `depends_on` entry
+    pub added_blocks: usize,
+    /// Entries removed because they pointed at a task that does not exist
+    pub removed_dangling: usize,
+    /// Tasks that were changed and saved
+    pub repaired_tasks: Vec<TaskId>,
+}
+
+impl RepairReport {
+    /// Whether the graph was already consistent.
+    pub fn is_clean(&self) -> bool {
+        self.repaired_tasks.is_empty()
+    }
+}
+
+/// How much event and work-record history to keep when pruning.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    /// Entries newer than this many days are always kept
+    pub keep_days: u32,
+    /// Newest entries kept per task regardless of age, for context
+    pub keep_per_task: usize,
+    /// Report what would be pruned without changing anything
+    pub dry_run: bool,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self { keep_days: 90, keep_per_task: 20, dry_run: false }
+    }
+}
+
+/// Outcome of pruning history under a [`RetentionPolicy`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PruneReport {
+    /// Events beyond the policy, removed unless this was a dry run
+    pub events: Vec<EventId>,
+    /// Work records beyond the policy, removed unless this was a dry run
+    pub work_records: Vec<WorkRecordId>,
+    /// NDJSON blob holding the removed entries, one `{"kind", "data"}`
+    /// object per line; `None` on a dry run or when nothing was pruned
+    pub archive: Option<BlobId>,
+    /// Whether this was a dry run
+    pub dry_run: bool,
+}
+
+impl PruneReport {
+    /// Whether nothing was beyond the policy.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty() && self.work_records.is_empty()
+    }
+}
+
+/// Entity created by a request that carried an idempotency key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdempotencyRecord {
+    /// Client-supplied key, scoped by the caller (e.g. per operation)
+    pub key: String,
+    /// ID of the entity the first request created
+    pub entity_id: String,
+    /// When the entity was created
+    pub created_at: Time,
+}
+
+/// A runtime setting, stored as JSON so any serializable value fits.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigEntry {
+    /// Dotted setting name, e.g. `"quality.check_timeout_secs"`
+    pub key: String,
+    /// Current value
+    pub value: serde_json::Value,
+    /// When the value was last set
+    pub updated_at: Time,
+}
 
 /// Storage abstraction for DevMan data.
 ///
@@ -47,6 +205,13 @@ pub trait Storage: Send + Sync {
     /// Load a goal by ID.
     async fn load_goal(&self, id: GoalId) -> Result<Option<Goal>>;
 
+    /// Load a goal that must exist, failing with [`StorageError::NotFound`] otherwise.
+    async fn require_goal(&self, id: GoalId) -> Result<Goal> {
+        self.load_goal(id)
+            .await?
+            .ok_or_else(|| StorageError::not_found("goal", id))
+    }
+
     /// List all goals.
     async fn list_goals(&self) -> Result<Vec<Goal>>;
 
@@ -58,6 +223,18 @@ pub trait Storage: Send + Sync {
     /// Load a project by ID.
     async fn load_project(&self, id: ProjectId) -> Result<Option<Project>>;
 
+    /// Load a project that must exist, failing with [`StorageError::NotFound`] otherwise.
+    async fn require_project(&self, id: ProjectId) -> Result<Project> {
+        self.load_project(id)
+            .await?
+            .ok_or_else(|| StorageError::not_found("project", id))
+    }
+
+    /// List all projects.
+    async fn list_projects(&self) -> Result<Vec<Project>> {
+        Ok(Vec::new())
+    }
+
     // === Phase operations ===
 
     /// Save a phase.
@@ -66,6 +243,18 @@ pub trait Storage: Send + Sync {
     /// Load a phase by ID.
     async fn load_phase(&self, id: PhaseId) -> Result<Option<Phase>>;
 
+    /// Load a phase that must exist, failing with [`StorageError::NotFound`] otherwise.
+    async fn require_phase(&self, id: PhaseId) -> Result<Phase> {
+        self.load_phase(id)
+            .await?
+            .ok_or_else(|| StorageError::not_found("phase", id))
+    }
+
+    /// List all phases.
+    async fn list_phases(&self) -> Result<Vec<Phase>> {
+        Ok(Vec::new())
+    }
+
     // === Task operations ===
 
     /// Save a task.
@@ -74,12 +263,134 @@ pub trait Storage: Send + Sync {
     /// Load a task by ID.
     async fn load_task(&self, id: TaskId) -> Result<Option<Task>>;
 
+    /// Load a task that must exist, failing with [`StorageError::NotFound`] otherwise.
+    async fn require_task(&self, id: TaskId) -> Result<Task> {
+        self.load_task(id)
+            .await?
+            .ok_or_else(|| StorageError::not_found("task", id))
+    }
+
+    /// Current version of a task, incremented by every save; 0 if it was
+    /// never saved.
+    async fn task_version(&self, _id: TaskId) -> Result<u64> {
+        Err(StorageError::Other("This storage backend does not track task versions".into()))
+    }
+
+    /// Save `task` only if its stored version is still `expected`, returning
+    /// the new version, or [`StorageError::Conflict`] if another writer got
+    /// there first. The check and the write are atomic, also across
+    /// processes sharing the store.
+    async fn save_task_if_version(&mut self, _task: &Task, _expected: u64) -> Result<u64> {
+        Err(StorageError::Other("This storage backend does not track task versions".into()))
+    }
+
     /// List tasks with optional filter.
     async fn list_tasks(&self, filter: &TaskFilter) -> Result<Vec<Task>>;
 
     /// Delete a task.
     async fn delete_task(&mut self, id: TaskId) -> Result<()>;
 
+    /// Record that `dependent` depends on `prerequisite`, adding the edge to
+    /// the dependent's `depends_on` and the prerequisite's `blocks`.
+    async fn link_tasks(&mut self, dependent: TaskId, prerequisite: TaskId) -> Result<()> {
+        if dependent == prerequisite {
+            return Err(StorageError::Other(format!("Task {} cannot depend on itself", dependent)));
+        }
+        let mut dependent_task = self.require_task(dependent).await?;
+        let mut prerequisite_task = self.require_task(prerequisite).await?;
+        let now = chrono::Utc::now();
+        if !dependent_task.depends_on.contains(&prerequisite) {
+            dependent_task.depends_on.push(prerequisite);
+            dependent_task.updated_at = now;
+            self.save_task(&dependent_task).await?;
+        }
+        if !prerequisite_task.blocks.contains(&dependent) {
+            prerequisite_task.blocks.push(dependent);
+            prerequisite_task.updated_at = now;
+            self.save_task(&prerequisite_task).await?;
+        }
+        Ok(())
+    }
+
+    /// Remove the edge `dependent` depends on `prerequisite` from both tasks.
+    ///
+    /// The prerequisite may already be gone, so dangling edges can be dropped.
+    async fn unlink_tasks(&mut self, dependent: TaskId, prerequisite: TaskId) -> Result<()> {
+        let mut dependent_task = self.require_task(dependent).await?;
+        let now = chrono::Utc::now();
+        if dependent_task.depends_on.contains(&prerequisite) {
+            dependent_task.depends_on.retain(|id| *id != prerequisite);
+            dependent_task.updated_at = now;
+            self.save_task(&dependent_task).await?;
+        }
+        if let Some(mut prerequisite_task) = self.load_task(prerequisite).await? {
+            if prerequisite_task.blocks.contains(&dependent) {
+                prerequisite_task.blocks.retain(|id| *id != dependent);
+                prerequisite_task.updated_at = now;
+                self.save_task(&prerequisite_task).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Make every task's `depends_on` and `blocks` agree: an edge recorded on
+    /// either side is added to the other, and edges to missing tasks are
+    /// dropped.
+    async fn repair_dependency_graph(&mut self) -> Result<RepairReport> {
+        let listed = self.list_tasks(&TaskFilter::default()).await?;
+        let order: Vec<TaskId> = listed.iter().map(|t| t.id).collect();
+        let known: std::collections::HashSet<TaskId> = order.iter().copied().collect();
+        let mut tasks: std::collections::HashMap<TaskId, Task> =
+            listed.into_iter().map(|t| (t.id, t)).collect();
+        let mut report = RepairReport::default();
+        let mut changed = std::collections::HashSet::new();
+
+        for task in tasks.values_mut() {
+            let before = task.depends_on.len() + task.blocks.len();
+            task.depends_on.retain(|id| known.contains(id));
+            task.blocks.retain(|id| known.contains(id));
+            let removed = before - task.depends_on.len() - task.blocks.len();
+            if removed > 0 {
+                report.removed_dangling += removed;
+                changed.insert(task.id);
+            }
+        }
+
+        let edges: Vec<(TaskId, TaskId)> = tasks
+            .values()
+            .flat_map(|t| {
+                let depends = t.depends_on.iter().map(move |d| (t.id, *d));
+                depends.chain(t.blocks.iter().map(move |b| (*b, t.id)))
+            })
+            .collect();
+        for (dependent, prerequisite) in edges {
+            if let Some(task) = tasks.get_mut(&dependent) {
+                if !task.depends_on.contains(&prerequisite) {
+                    task.depends_on.push(prerequisite);
+                    report.added_depends_on += 1;
+                    changed.insert(dependent);
+                }
+            }
+            if let Some(task) = tasks.get_mut(&prerequisite) {
+                if !task.blocks.contains(&dependent) {
+                    task.blocks.push(dependent);
+                    report.added_blocks += 1;
+                    changed.insert(prerequisite);
+                }
+            }
+        }
+
+        let now = chrono::Utc::now();
+        for id in order.into_iter().filter(|id| changed.contains(id)) {
+            if let Some(task) = tasks.get_mut(&id) {
+                task.updated_at = now;
+                self.save_task(task).await?;
+            }
+            report.repaired_tasks.push(id);
+        }
+        Ok(report)
+    }
+
     // === Event operations ===
 
     /// Save an event.
@@ -91,6 +402,13 @@ pub trait Storage: Send + Sync {
     /// List all events.
     async fn list_events(&self) -> Result<Vec<Event>>;
 
+    /// Delete an event.
+    ///
+    /// Backends that cannot delete events reject the call.
+    async fn delete_event(&mut self, _id: EventId) -> Result<()> {
+        Err(StorageError::Other("This storage backend does not delete events".to_string()))
+    }
+
     // === Knowledge operations ===
 
     /// Save knowledge.
@@ -99,12 +417,31 @@ pub trait Storage: Send + Sync {
     /// Load knowledge by ID.
     async fn load_knowledge(&self, id: KnowledgeId) -> Result<Option<Knowledge>>;
 
+    /// Load knowledge that must exist, failing with [`StorageError::NotFound`] otherwise.
+    async fn require_knowledge(&self, id: KnowledgeId) -> Result<Knowledge> {
+        self.load_knowledge(id)
+            .await?
+            .ok_or_else(|| StorageError::not_found("knowledge", id))
+    }
+
     /// List all knowledge.
     async fn list_knowledge(&self) -> Result<Vec<Knowledge>>;
 
+    /// List knowledge by `updated_at`, newest first, returning at most `limit` items.
+    async fn list_recent_knowledge(&self, limit: usize) -> Result<Vec<Knowledge>> {
+        let mut items = self.list_knowledge().await?;
+        items.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        items.truncate(limit);
+        Ok(items)
+    }
+
     // === Vector Embedding operations ===
 
     /// Save a knowledge embedding.
+    ///
+    /// The first embedding saved sets the store's active embedding model;
+    /// later embeddings from another model or with another dimension fail
+    /// with [`StorageError::EmbeddingModelMismatch`].
     async fn save_vector_embedding(&mut self, embedding: &KnowledgeEmbedding) -> Result<()>;
 
     /// Load a knowledge embedding by knowledge ID.
@@ -113,6 +450,160 @@ pub trait Storage: Send + Sync {
     /// List all knowledge embeddings.
     async fn list_vector_embeddings(&self) -> Result<Vec<KnowledgeEmbedding>>;
 
+    /// Load the model every stored embedding must match, if one is set.
+    async fn load_active_embedding_model(&self) -> Result<Option<ActiveEmbeddingModel>> {
+        Ok(None)
+    }
+
+    /// Switch the active embedding model.
+    ///
+    /// Existing embeddings are not touched; callers re-embed all knowledge
+    /// under the new model right after.
+    async fn save_active_embedding_model(&mut self, _active: &ActiveEmbeddingModel) -> Result<()> {
+        Ok(())
+    }
+
+    /// Load the checkpoint of an unfinished reindex, if any.
+    async fn load_reindex_checkpoint(&self) -> Result<Option<ReindexCheckpoint>> {
+        Ok(None)
+    }
+
+    /// Record how far a reindex got, replacing any earlier checkpoint.
+    ///
+    /// Backends that do not persist checkpoints ignore it, so reindexing
+    /// restarts from scratch.
+    async fn save_reindex_checkpoint(&mut self, _checkpoint: &ReindexCheckpoint) -> Result<()> {
+        Ok(())
+    }
+
+    /// Drop the reindex checkpoint once a reindex finishes.
+    async fn clear_reindex_checkpoint(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    // === Blocker operations ===
+
+    /// Save a manually recorded blocker.
+    ///
+    /// Backends that do not persist blockers reject the save.
+    async fn save_blocker(&mut self, _blocker: &Blocker) -> Result<()> {
+        Err(StorageError::Other("This storage backend does not persist blockers".to_string()))
+    }
+
+    /// Load a blocker by ID.
+    async fn load_blocker(&self, _id: BlockerId) -> Result<Option<Blocker>> {
+        Ok(None)
+    }
+
+    /// Load a blocker that must exist, failing with [`StorageError::NotFound`] otherwise.
+    async fn require_blocker(&self, id: BlockerId) -> Result<Blocker> {
+        self.load_blocker(id)
+            .await?
+            .ok_or_else(|| StorageError::not_found("blocker", id))
+    }
+
+    /// List all stored blockers, resolved ones included.
+    async fn list_blockers(&self) -> Result<Vec<Blocker>> {
+        Ok(Vec::new())
+    }
+
+    // === Quality Profile operations ===
+
+    /// Save a quality profile.
+    ///
+    /// Backends that do not persist quality profiles reject the save.
+    async fn save_quality_profile(&mut self, _profile: &QualityProfile) -> Result<()> {
+        Err(StorageError::Other("This storage backend does not persist quality profiles".to_string()))
+    }
+
+    /// Load a quality profile by ID.
+    async fn load_quality_profile(&self, _id: QualityProfileId) -> Result<Option<QualityProfile>> {
+        Ok(None)
+    }
+
+    /// Load a quality profile that must exist, failing with [`StorageError::NotFound`] otherwise.
+    async fn require_quality_profile(&self, id: QualityProfileId) -> Result<QualityProfile> {
+        self.load_quality_profile(id)
+            .await?
+            .ok_or_else(|| StorageError::not_found("quality_profile", id))
+    }
+
+    /// List all quality profiles.
+    async fn list_quality_profiles(&self) -> Result<Vec<QualityProfile>> {
+        Ok(Vec::new())
+    }
+
+    // === Task Template operations ===
+
+    /// Save a task template.
+    ///
+    /// Backends that do not persist task templates reject the save.
+    async fn save_task_template(&mut self, _template: &TaskTemplate) -> Result<()> {
+        Err(StorageError::Other("This storage backend does not persist task templates".to_string()))
+    }
+
+    /// Load a task template by ID.
+    async fn load_task_template(&self, _id: TaskTemplateId) -> Result<Option<TaskTemplate>> {
+        Ok(None)
+    }
+
+    /// Load a task template that must exist, failing with [`StorageError::NotFound`] otherwise.
+    async fn require_task_template(&self, id: TaskTemplateId) -> Result<TaskTemplate> {
+        self.load_task_template(id)
+            .await?
+            .ok_or_else(|| StorageError::not_found("task_template", id))
+    }
+
+    /// List all task templates.
+    async fn list_task_templates(&self) -> Result<Vec<TaskTemplate>> {
+        Ok(Vec::new())
+    }
+
+    // === Blob operations ===
+
+    /// Store `content` and return its [`BlobId`].
+    ///
+    /// Blobs are addressed by content, so storing the same bytes twice
+    /// returns the same ID and keeps a single copy. Backends that do not
+    /// persist blobs reject the put.
+    async fn put_blob(&mut self, _content: &[u8]) -> Result<BlobId> {
+        Err(StorageError::Other("This storage backend does not persist blobs".to_string()))
+    }
+
+    /// Load a blob's content by ID.
+    async fn get_blob(&self, _id: &BlobId) -> Result<Option<Vec<u8>>> {
+        Ok(None)
+    }
+
+    /// Keep blob `id` through [`Storage::compact`] even though no work
+    /// record refers to it.
+    ///
+    /// Backends whose compaction never removes blobs ignore the pin.
+    async fn pin_blob(&mut self, _id: &BlobId) -> Result<()> {
+        Ok(())
+    }
+
+    /// Load a blob that must exist, failing with [`StorageError::NotFound`] otherwise.
+    async fn require_blob(&self, id: &BlobId) -> Result<Vec<u8>> {
+        self.get_blob(id)
+            .await?
+            .ok_or_else(|| StorageError::not_found("blob", id))
+    }
+
+    // === Quality Run operations ===
+
+    /// Append a task's quality run to its history.
+    ///
+    /// Backends that do not keep quality history reject the save.
+    async fn save_quality_run(&mut self, _run: &QualityRun) -> Result<()> {
+        Err(StorageError::Other("This storage backend does not persist quality runs".into()))
+    }
+
+    /// List a task's quality runs, oldest first.
+    async fn list_quality_runs(&self, _task_id: TaskId) -> Result<Vec<QualityRun>> {
+        Ok(Vec::new())
+    }
+
     // === Quality Check operations ===
 
     /// Save a quality check.
@@ -135,6 +626,80 @@ pub trait Storage: Send + Sync {
     /// List work records for a task.
     async fn list_work_records(&self, task_id: TaskId) -> Result<Vec<WorkRecord>>;
 
+    /// Stream every work record, reading them one at a time so the whole
+    /// set is never held in memory. The order is unspecified.
+    fn stream_work_records(&self) -> BoxStream<'_, Result<WorkRecord>> {
+        Box::pin(futures::stream::once(async {
+            Err(StorageError::Other(
+                "This storage backend does not support streaming work records".to_string(),
+            ))
+        }))
+    }
+
+    /// List work records for a task matching a filter, newest first.
+    async fn query_work_records(
+        &self,
+        task_id: TaskId,
+        filter: &WorkRecordFilter,
+    ) -> Result<Vec<WorkRecord>> {
+        let mut records = self.list_work_records(task_id).await?;
+        if let Some(since) = filter.since {
+            records.retain(|r| r.started_at >= since);
+        }
+        records.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        if let Some(limit) = filter.limit {
+            records.truncate(limit);
+        }
+        Ok(records)
+    }
+
+    /// Delete a work record.
+    ///
+    /// Backends that cannot delete work records reject the call.
+    async fn delete_work_record(&mut self, _id: WorkRecordId) -> Result<()> {
+        Err(StorageError::Other("This storage backend does not delete work records".to_string()))
+    }
+
+    // === Idempotency keys ===
+
+    /// Remember which entity a keyed request created, replacing any
+    /// previous record for the key.
+    ///
+    /// Backends that do not persist keys ignore the record.
+    async fn save_idempotency_record(&mut self, _record: &IdempotencyRecord) -> Result<()> {
+        Ok(())
+    }
+
+    /// Load the record for an idempotency key.
+    async fn load_idempotency_record(&self, _key: &str) -> Result<Option<IdempotencyRecord>> {
+        Ok(None)
+    }
+
+    /// Drop records created before `cutoff`, returning how many were removed.
+    async fn purge_idempotency_records(&mut self, _cutoff: Time) -> Result<usize> {
+        Ok(0)
+    }
+
+    // === Runtime settings ===
+
+    /// Save a setting, replacing any previous value for its key.
+    ///
+    /// Backends that do not persist settings ignore it, so readers fall
+    /// back to their defaults.
+    async fn save_config_entry(&mut self, _entry: &ConfigEntry) -> Result<()> {
+        Ok(())
+    }
+
+    /// Load the setting stored under `key`.
+    async fn load_config_entry(&self, _key: &str) -> Result<Option<ConfigEntry>> {
+        Ok(None)
+    }
+
+    /// List every stored setting, sorted by key.
+    async fn list_config_entries(&self) -> Result<Vec<ConfigEntry>> {
+        Ok(Vec::new())
+    }
+
     // === Transaction support ===
 
     /// Commit pending changes with a message.
@@ -142,6 +707,119 @@ pub trait Storage: Send + Sync {
 
     /// Rollback pending changes.
     async fn rollback(&mut self) -> Result<()>;
+
+    // === Maintenance ===
+
+    /// Remove orphaned data and reclaim space.
+    ///
+    /// Backends with nothing to reclaim report an empty result.
+    async fn compact(&mut self) -> Result<CompactReport> {
+        Ok(CompactReport::default())
+    }
+
+    /// Archive then remove events and work records beyond `policy`.
+    ///
+    /// An entry is pruned when it is older than `keep_days` as of `clock`
+    /// and is not among the `keep_per_task` newest entries of any task it
+    /// relates to. The pruned entries are written to an NDJSON blob before
+    /// anything is deleted. On a dry run nothing is written or removed.
+    async fn prune_history(&mut self, policy: &RetentionPolicy, clock: &dyn devman_core::Clock) -> Result<PruneReport> {
+        use futures::TryStreamExt;
+        use std::collections::HashMap;
+
+        // A window reaching past the earliest representable time keeps everything
+        let cutoff = chrono::TimeDelta::try_days(policy.keep_days.into())
+            .and_then(|window| clock.now().checked_sub_signed(window))
+            .unwrap_or(chrono::DateTime::<chrono::Utc>::MIN_UTC);
+
+        // Events newest first, so the first `keep_per_task` seen per task are kept
+        let mut events = self.list_events().await?;
+        events.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
+        let mut seen: HashMap<TaskId, usize> = HashMap::new();
+        let mut pruned_events = Vec::new();
+        for event in events {
+            let mut recent = false;
+            for task in &event.related_tasks {
+                let count = seen.entry(*task).or_default();
+                recent |= *count < policy.keep_per_task;
+                *count += 1;
+            }
+            if event.timestamp < cutoff && !recent {
+                pruned_events.push(event);
+            }
+        }
+
+        let mut records: Vec<WorkRecord> = self.stream_work_records().try_collect().await?;
+        records.sort_by_key(|r| std::cmp::Reverse(r.started_at));
+        let mut seen: HashMap<TaskId, usize> = HashMap::new();
+        let mut pruned_records = Vec::new();
+        for record in records {
+            let count = seen.entry(record.task_id).or_default();
+            *count += 1;
+            if record.started_at < cutoff && *count > policy.keep_per_task {
+                pruned_records.push(record);
+            }
+        }
+
+        let mut report = PruneReport {
+            events: pruned_events.iter().map(|e| e.id).collect(),
+            work_records: pruned_records.iter().map(|r| r.id).collect(),
+            archive: None,
+            dry_run: policy.dry_run,
+        };
+        if policy.dry_run || report.is_empty() {
+            return Ok(report);
+        }
+
+        let mut archive = String::new();
+        for event in &pruned_events {
+            archive.push_str(&serde_json::to_string(&serde_json::json!({"kind": "event", "data": event}))?);
+            archive.push('\n');
+        }
+        for record in &pruned_records {
+            archive.push_str(&serde_json::to_string(&serde_json::json!({"kind": "work_record", "data": record}))?);
+            archive.push('\n');
+        }
+        let archive = self.put_blob(archive.as_bytes()).await?;
+        // Keep the archive, and the artifacts its work records point at,
+        // once nothing live refers to them
+        self.pin_blob(&archive).await?;
+        for blob in pruned_records.iter().flat_map(|r| &r.artifacts).filter_map(|a| a.blob.as_ref()) {
+            self.pin_blob(blob).await?;
+        }
+        report.archive = Some(archive);
+
+        for id in &report.events {
+            self.delete_event(*id).await?;
+        }
+        for id in &report.work_records {
+            self.delete_work_record(*id).await?;
+        }
+        let owners: std::collections::HashSet<TaskId> = pruned_records.iter().map(|r| r.task_id).collect();
+        for task_id in owners {
+            if let Some(mut task) = self.load_task(task_id).await? {
+                task.work_records.retain(|id| !report.work_records.contains(id));
+                self.save_task(&task).await?;
+            }
+        }
+        Ok(report)
+    }
+}
+
+/// Check `embedding` against the active embedding model of `storage`,
+/// making its model the active one if none is set yet.
+pub(crate) async fn guard_embedding_model<S: Storage + ?Sized>(
+    storage: &mut S,
+    embedding: &KnowledgeEmbedding,
+) -> Result<()> {
+    match storage.load_active_embedding_model().await? {
+        Some(active) if !active.matches(embedding) => Err(StorageError::EmbeddingModelMismatch {
+            active,
+            found: ActiveEmbeddingModel::of(embedding),
+        }),
+        Some(_) => Ok(()),
+        None => storage.save_active_embedding_model(&ActiveEmbeddingModel::of(embedding)).await,
+    }
 }
 
 /// A transaction for atomic operations.
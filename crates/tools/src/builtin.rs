@@ -3,6 +3,67 @@
 use super::{r#trait::*, ToolSchema};
 use async_trait::async_trait;
 use tokio::process::Command;
+use tokio_util::sync::CancellationToken;
+
+/// Run `cmd` to completion, or until `cancel` fires.
+///
+/// The command runs in its own process group so that cancelling kills it
+/// together with any processes it spawned (e.g. test binaries under
+/// `cargo test`). Returns [`ToolError::Cancelled`] when cancelled.
+pub async fn run_command(
+    mut cmd: Command,
+    input: &ToolInput,
+    cancel: &CancellationToken,
+) -> Result<ToolOutput, anyhow::Error> {
+    let start = std::time::Instant::now();
+
+    for (k, v) in &input.env {
+        cmd.env(k, v);
+    }
+    if let Some(dir) = &input.work_dir {
+        cmd.current_dir(dir);
+    }
+    // TODO: Handle input.stdin
+    cmd.stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true);
+    #[cfg(unix)]
+    cmd.process_group(0);
+
+    let child = cmd.spawn()?;
+    let pid = child.id();
+    let output = child.wait_with_output();
+    tokio::pin!(output);
+
+    tokio::select! {
+        output = &mut output => {
+            let output = output?;
+            Ok(ToolOutput {
+                exit_code: output.status.code().unwrap_or(-1),
+                stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+                stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+                duration: start.elapsed(),
+            })
+        }
+        _ = cancel.cancelled() => {
+            #[cfg(unix)]
+            if let Some(pid) = pid {
+                // SAFETY: killpg only sends a signal; the group id is the
+                // pid of the child we spawned as group leader.
+                unsafe {
+                    libc::killpg(pid as libc::pid_t, libc::SIGKILL);
+                }
+            }
+            #[cfg(not(unix))]
+            let _ = pid;
+            // Reap the killed child; dropping the future would also kill it
+            let _ = output.await;
+            tracing::debug!("Cancelled {:?}", cmd.as_std().get_program());
+            Err(ToolError::Cancelled.into())
+        }
+    }
+}
 
 /// Cargo tool for Rust projects.
 pub struct CargoTool;
@@ -18,27 +79,17 @@ impl Tool for CargoTool {
     }
 
     async fn execute(&self, input: &ToolInput) -> Result<ToolOutput, anyhow::Error> {
-        let start = std::time::Instant::now();
+        self.execute_cancellable(input, &CancellationToken::new()).await
+    }
 
+    async fn execute_cancellable(
+        &self,
+        input: &ToolInput,
+        cancel: &CancellationToken,
+    ) -> Result<ToolOutput, anyhow::Error> {
         let mut cmd = Command::new("cargo");
         cmd.args(&input.args);
-
-        if let Some(stdin) = &input.stdin {
-            // TODO: Handle stdin
-        }
-
-        for (k, v) in &input.env {
-            cmd.env(k, v);
-        }
-
-        let output = cmd.output().await?;
-
-        Ok(ToolOutput {
-            exit_code: output.status.code().unwrap_or(-1),
-            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-            duration: start.elapsed(),
-        })
+        run_command(cmd, input, cancel).await
     }
 
     fn schema(&self) -> ToolSchema {
@@ -80,23 +131,17 @@ impl Tool for NpmTool {
     }
 
     async fn execute(&self, input: &ToolInput) -> Result<ToolOutput, anyhow::Error> {
-        let start = std::time::Instant::now();
+        self.execute_cancellable(input, &CancellationToken::new()).await
+    }
 
+    async fn execute_cancellable(
+        &self,
+        input: &ToolInput,
+        cancel: &CancellationToken,
+    ) -> Result<ToolOutput, anyhow::Error> {
         let mut cmd = Command::new("npm");
         cmd.args(&input.args);
-
-        for (k, v) in &input.env {
-            cmd.env(k, v);
-        }
-
-        let output = cmd.output().await?;
-
-        Ok(ToolOutput {
-            exit_code: output.status.code().unwrap_or(-1),
-            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-            duration: start.elapsed(),
-        })
+        run_command(cmd, input, cancel).await
     }
 
     fn schema(&self) -> ToolSchema {
@@ -112,6 +157,22 @@ impl Tool for NpmTool {
 /// Git tool for version control.
 pub struct GitTool;
 
+impl GitTool {
+    /// Paths listed in `git status --porcelain` output, relative to the
+    /// repository root. Renames report the new path.
+    pub fn parse_status_porcelain(output: &str) -> Vec<String> {
+        output
+            .lines()
+            .filter_map(|line| line.get(3..))
+            .map(|path| {
+                let path = path.rsplit(" -> ").next().unwrap_or(path);
+                path.trim_matches('"').to_string()
+            })
+            .filter(|path| !path.is_empty())
+            .collect()
+    }
+}
+
 #[async_trait]
 impl Tool for GitTool {
     fn name(&self) -> &str {
@@ -123,23 +184,17 @@ impl Tool for GitTool {
     }
 
     async fn execute(&self, input: &ToolInput) -> Result<ToolOutput, anyhow::Error> {
-        let start = std::time::Instant::now();
+        self.execute_cancellable(input, &CancellationToken::new()).await
+    }
 
+    async fn execute_cancellable(
+        &self,
+        input: &ToolInput,
+        cancel: &CancellationToken,
+    ) -> Result<ToolOutput, anyhow::Error> {
         let mut cmd = Command::new("git");
         cmd.args(&input.args);
-
-        for (k, v) in &input.env {
-            cmd.env(k, v);
-        }
-
-        let output = cmd.output().await?;
-
-        Ok(ToolOutput {
-            exit_code: output.status.code().unwrap_or(-1),
-            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-            duration: start.elapsed(),
-        })
+        run_command(cmd, input, cancel).await
     }
 
     fn schema(&self) -> ToolSchema {
@@ -245,3 +300,119 @@ impl Tool for FsTool {
         }
     }
 }
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    /// Fake long-running tool: a shell that starts a background `sleep`
+    /// and records its pid, so tests can see whether the whole process
+    /// group went away.
+    struct SleepTool {
+        pid_file: std::path::PathBuf,
+    }
+
+    #[async_trait]
+    impl Tool for SleepTool {
+        fn name(&self) -> &str {
+            "sleep"
+        }
+
+        fn description(&self) -> &str {
+            "Sleeps in a grandchild process"
+        }
+
+        async fn execute(&self, input: &ToolInput) -> Result<ToolOutput, anyhow::Error> {
+            self.execute_cancellable(input, &CancellationToken::new()).await
+        }
+
+        async fn execute_cancellable(
+            &self,
+            input: &ToolInput,
+            cancel: &CancellationToken,
+        ) -> Result<ToolOutput, anyhow::Error> {
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c")
+                .arg(format!("sleep 30 & echo $! > {}; wait", self.pid_file.display()));
+            run_command(cmd, input, cancel).await
+        }
+
+        fn schema(&self) -> ToolSchema {
+            ToolSchema {
+                name: "sleep".to_string(),
+                description: String::new(),
+                parameters: vec![],
+                examples: vec![],
+            }
+        }
+    }
+
+    /// Whether `pid` is a live (non-zombie) process.
+    fn is_running(pid: i32) -> bool {
+        std::fs::read_to_string(format!("/proc/{}/stat", pid))
+            .map(|stat| {
+                // State follows the parenthesised command name
+                let state = stat.rsplit(')').next().unwrap_or("").trim_start();
+                !state.starts_with('Z') && !state.starts_with('X')
+            })
+            .unwrap_or(false)
+    }
+
+    async fn read_pid(path: &std::path::Path) -> i32 {
+        loop {
+            if let Some(pid) = std::fs::read_to_string(path).ok().and_then(|s| s.trim().parse().ok()) {
+                return pid;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_kills_process_group() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = SleepTool { pid_file: dir.path().join("pid") };
+        let input = ToolInput {
+            args: vec![],
+            env: Default::default(),
+            stdin: None,
+            timeout: None,
+            work_dir: None,
+        };
+
+        let cancel = CancellationToken::new();
+        let canceller = cancel.clone();
+        let pid_file = tool.pid_file.clone();
+        let grandchild = tokio::spawn(async move {
+            let pid = read_pid(&pid_file).await;
+            canceller.cancel();
+            pid
+        });
+
+        let start = Instant::now();
+        let result = tokio::time::timeout(Duration::from_secs(5), tool.execute_cancellable(&input, &cancel))
+            .await
+            .expect("cancelled tool did not return");
+        let err = result.unwrap_err();
+        assert!(ToolError::is_cancelled(&err), "unexpected error: {}", err);
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        // The background sleep shares the process group and dies with it
+        let pid = grandchild.await.unwrap();
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while is_running(pid) && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(!is_running(pid), "grandchild {} still running", pid);
+    }
+
+    #[test]
+    fn test_parse_status_porcelain() {
+        let output = " M crates/a/src/lib.rs\n?? notes.md\nR  old.rs -> crates/b/src/new.rs\n D \"with space.rs\"\n";
+        assert_eq!(
+            GitTool::parse_status_porcelain(output),
+            vec!["crates/a/src/lib.rs", "notes.md", "crates/b/src/new.rs", "with space.rs"]
+        );
+        assert!(GitTool::parse_status_porcelain("").is_empty());
+    }
+}
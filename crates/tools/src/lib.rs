@@ -6,10 +6,13 @@
 
 pub mod r#trait;
 pub mod builtin;
+pub mod registry;
 pub mod workflow;
 
-pub use r#trait::{Tool, ToolExecutor, ToolInput, ToolOutput, ToolSchema};
-pub use builtin::{CargoTool, NpmTool, GitTool, FsTool};
+pub use r#trait::{Tool, ToolExecutor, ToolInput, ToolOutput, ToolSchema, ToolError};
+pub use builtin::{CargoTool, NpmTool, GitTool, FsTool, run_command};
+pub use registry::ToolRegistry;
+pub use tokio_util::sync::CancellationToken;
 pub use workflow::{
     Workflow, WorkflowStep, WorkflowResult, WorkflowExecutor, BasicWorkflowExecutor,
     WorkflowError, FailureStrategy, StepCondition, StepResult, WorkflowStepBuilder,
@@ -0,0 +1,153 @@
+//! Tool registry.
+
+use super::{builtin::*, r#trait::*};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+
+/// Tools available to an executor, keyed by name.
+///
+/// The registry is itself a [`ToolExecutor`], so downstream crates can add
+/// their own tools next to the builtins and hand the registry to anything
+/// that runs tools.
+pub struct ToolRegistry {
+    tools: HashMap<String, Arc<dyn Tool>>,
+}
+
+impl ToolRegistry {
+    /// Create a registry with the builtin tools (cargo, npm, git, fs).
+    pub fn new() -> Self {
+        let mut registry = Self::empty();
+        registry.register(Arc::new(CargoTool));
+        registry.register(Arc::new(NpmTool));
+        registry.register(Arc::new(GitTool));
+        registry.register(Arc::new(FsTool));
+        registry
+    }
+
+    /// Create a registry with no tools.
+    pub fn empty() -> Self {
+        Self {
+            tools: HashMap::new(),
+        }
+    }
+
+    /// Register a tool under its [`Tool::name`], returning any tool it replaces.
+    pub fn register(&mut self, tool: Arc<dyn Tool>) -> Option<Arc<dyn Tool>> {
+        self.tools.insert(tool.name().to_string(), tool)
+    }
+
+    /// Get a tool by name.
+    pub fn get(&self, name: &str) -> Option<Arc<dyn Tool>> {
+        self.tools.get(name).cloned()
+    }
+
+    /// Names of all registered tools, sorted.
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.tools.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+}
+
+impl Default for ToolRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for ToolRegistry {
+    async fn execute_tool(
+        &self,
+        tool: &str,
+        input: ToolInput,
+        cancel: &CancellationToken,
+    ) -> Result<ToolOutput, anyhow::Error> {
+        let tool = self
+            .get(tool)
+            .ok_or_else(|| ToolError::UnknownTool(tool.to_string()))?;
+        tool.execute_cancellable(&input, cancel).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ToolSchema;
+
+    /// Tool that echoes its arguments without running anything.
+    struct EchoTool;
+
+    #[async_trait]
+    impl Tool for EchoTool {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn description(&self) -> &str {
+            "Echoes its arguments"
+        }
+
+        async fn execute(&self, input: &ToolInput) -> Result<ToolOutput, anyhow::Error> {
+            Ok(ToolOutput {
+                exit_code: 0,
+                stdout: input.args.join(" "),
+                stderr: String::new(),
+                duration: std::time::Duration::ZERO,
+            })
+        }
+
+        fn schema(&self) -> ToolSchema {
+            ToolSchema {
+                name: "echo".to_string(),
+                description: String::new(),
+                parameters: vec![],
+                examples: vec![],
+            }
+        }
+    }
+
+    fn input(args: &[&str]) -> ToolInput {
+        ToolInput {
+            args: args.iter().map(|a| a.to_string()).collect(),
+            env: HashMap::new(),
+            stdin: None,
+            timeout: None,
+            work_dir: None,
+        }
+    }
+
+    #[test]
+    fn test_builtins_registered_by_default() {
+        assert_eq!(ToolRegistry::new().names(), ["cargo", "fs", "git", "npm"]);
+        assert!(ToolRegistry::empty().names().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_executes_registered_custom_tool() {
+        let mut registry = ToolRegistry::new();
+        assert!(registry.register(Arc::new(EchoTool)).is_none());
+
+        let output = registry
+            .execute_tool("echo", input(&["hello", "world"]), &CancellationToken::new())
+            .await
+            .unwrap();
+        assert_eq!(output.stdout, "hello world");
+    }
+
+    #[tokio::test]
+    async fn test_unknown_tool_errors() {
+        let registry = ToolRegistry::empty();
+        let error = registry
+            .execute_tool("make", input(&[]), &CancellationToken::new())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<ToolError>(),
+            Some(&ToolError::UnknownTool("make".to_string()))
+        );
+        assert_eq!(error.to_string(), "Unknown tool: make");
+    }
+}
@@ -3,6 +3,7 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use tokio_util::sync::CancellationToken;
 
 /// A tool that can be executed.
 #[async_trait]
@@ -16,6 +17,22 @@ pub trait Tool: Send + Sync {
     /// Execute the tool.
     async fn execute(&self, input: &ToolInput) -> Result<ToolOutput, anyhow::Error>;
 
+    /// Execute the tool, stopping early if `cancel` fires.
+    ///
+    /// Returns [`ToolError::Cancelled`] when cancelled. The default drops
+    /// the in-flight execution; tools that spawn processes override this
+    /// to kill them.
+    async fn execute_cancellable(
+        &self,
+        input: &ToolInput,
+        cancel: &CancellationToken,
+    ) -> Result<ToolOutput, anyhow::Error> {
+        tokio::select! {
+            output = self.execute(input) => output,
+            _ = cancel.cancelled() => Err(ToolError::Cancelled.into()),
+        }
+    }
+
     /// Get tool schema (for AI discovery).
     fn schema(&self) -> ToolSchema;
 }
@@ -24,7 +41,33 @@ pub trait Tool: Send + Sync {
 #[async_trait]
 pub trait ToolExecutor: Send + Sync {
     /// Execute a tool by name.
-    async fn execute_tool(&self, tool: &str, input: ToolInput) -> Result<ToolOutput, anyhow::Error>;
+    ///
+    /// Returns [`ToolError::Cancelled`] if `cancel` fires before the tool
+    /// finishes.
+    async fn execute_tool(
+        &self,
+        tool: &str,
+        input: ToolInput,
+        cancel: &CancellationToken,
+    ) -> Result<ToolOutput, anyhow::Error>;
+}
+
+/// Tool execution errors.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ToolError {
+    /// Execution was cancelled before it finished
+    #[error("Tool execution cancelled")]
+    Cancelled,
+    /// No tool is registered under the requested name
+    #[error("Unknown tool: {0}")]
+    UnknownTool(String),
+}
+
+impl ToolError {
+    /// Whether `error` is a [`ToolError::Cancelled`].
+    pub fn is_cancelled(error: &anyhow::Error) -> bool {
+        error.downcast_ref::<ToolError>() == Some(&ToolError::Cancelled)
+    }
 }
 
 /// Input to a tool.
@@ -41,6 +84,10 @@ pub struct ToolInput {
 
     /// Timeout
     pub timeout: Option<std::time::Duration>,
+
+    /// Working directory; the process CWD when unset
+    #[serde(default)]
+    pub work_dir: Option<std::path::PathBuf>,
 }
 
 /// Output from a tool.
@@ -543,6 +543,7 @@ impl WorkflowStepBuilder {
                 env: HashMap::new(),
                 stdin: None,
                 timeout: None,
+                work_dir: None,
             },
             on_failure: FailureStrategy::default(),
             max_retries: 0,
@@ -624,6 +625,7 @@ mod tests {
                 env: HashMap::new(),
                 stdin: None,
                 timeout: None,
+                work_dir: None,
             },
             on_failure: FailureStrategy::Stop,
             condition: None,
@@ -752,6 +754,7 @@ mod tests {
             },
             stdin: Some("{project} data".to_string()),
             timeout: None,
+            work_dir: None,
         };
 
         let result = executor.substitute_variables(&input, &vars);
@@ -1,6 +1,6 @@
 //! Work context management.
 
-use devman_core::{Goal, GoalId, Phase, PhaseId, Project, ProjectId, Task, TaskId};
+use devman_core::{Goal, GoalId, Phase, PhaseId, Project, ProjectId, ScoredKnowledge, Task, TaskId, WorkEvent};
 
 /// Current work context (for work management).
 #[derive(Debug, Clone)]
@@ -25,6 +25,18 @@ pub struct WorkManagementContext {
 
     /// Environment variables
     pub env: std::collections::HashMap<String, String>,
+
+    /// Knowledge relevant to the active task
+    pub knowledge: Vec<ScoredKnowledge>,
+
+    /// Tasks the active task depends on
+    pub dependencies: Vec<Task>,
+
+    /// Recent work history
+    pub history: Vec<WorkEvent>,
+
+    /// What [`WorkManagementContext::build`] dropped to fit its budget
+    pub omitted: OmittedContext,
 }
 
 impl Default for WorkManagementContext {
@@ -37,6 +49,10 @@ impl Default for WorkManagementContext {
             recent_changes: Vec::new(),
             work_dir: None,
             env: std::collections::HashMap::new(),
+            knowledge: Vec::new(),
+            dependencies: Vec::new(),
+            history: Vec::new(),
+            omitted: OmittedContext::default(),
         }
     }
 }
@@ -76,6 +92,143 @@ impl WorkManagementContext {
         self.recent_changes.push(change);
         self
     }
+
+    /// Set relevant knowledge.
+    pub fn with_knowledge(mut self, knowledge: Vec<ScoredKnowledge>) -> Self {
+        self.knowledge = knowledge;
+        self
+    }
+
+    /// Set dependencies.
+    pub fn with_dependencies(mut self, dependencies: Vec<Task>) -> Self {
+        self.dependencies = dependencies;
+        self
+    }
+
+    /// Set work history.
+    pub fn with_history(mut self, history: Vec<WorkEvent>) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Trim knowledge, dependencies and history to fit `budget`.
+    ///
+    /// Goal, project, phase and active task are always kept and their cost
+    /// comes off the budget first. The rest is ranked within each kind
+    /// (knowledge by relevance score, open dependencies before closed ones
+    /// then most recently updated, history newest first) and taken in turns
+    /// from each kind, so no kind crowds out the others. Items that do not
+    /// fit are counted in [`WorkManagementContext::omitted`].
+    pub fn build(mut self, budget: ContextBudget) -> Self {
+        let fixed: usize = [
+            self.goal.as_ref().map(|g| format!("{} {}", g.title, g.description)),
+            self.project.as_ref().map(|p| format!("{} {}", p.name, p.description)),
+            self.phase.as_ref().map(|p| format!("{} {}", p.name, p.description)),
+            self.active_task.as_ref().map(dependency_text),
+        ]
+        .iter()
+        .flatten()
+        .map(|text| ContextBudget::estimate_tokens(text))
+        .sum();
+        let mut remaining = budget.max_tokens.saturating_sub(fixed);
+
+        self.knowledge.sort_by(|a, b| b.score.total_cmp(&a.score));
+        self.dependencies
+            .sort_by(|a, b| a.is_closed().cmp(&b.is_closed()).then(b.updated_at.cmp(&a.updated_at)));
+        self.history.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
+
+        let knowledge_cost: Vec<usize> = self
+            .knowledge
+            .iter()
+            .map(|k| {
+                let text = format!("{} {} {}", k.knowledge.title, k.knowledge.content.summary, k.knowledge.content.detail);
+                ContextBudget::estimate_tokens(&text)
+            })
+            .collect();
+        let dependency_cost: Vec<usize> = self
+            .dependencies
+            .iter()
+            .map(|t| ContextBudget::estimate_tokens(&dependency_text(t)))
+            .collect();
+        let history_cost: Vec<usize> = self
+            .history
+            .iter()
+            .map(|e| ContextBudget::estimate_tokens(&e.description))
+            .collect();
+
+        let costs = [knowledge_cost, dependency_cost, history_cost];
+        let mut keep = costs.clone().map(|c| vec![false; c.len()]);
+        let mut turns: Vec<(usize, usize)> = costs
+            .iter()
+            .enumerate()
+            .flat_map(|(kind, kind_costs)| (0..kind_costs.len()).map(move |rank| (rank, kind)))
+            .collect();
+        turns.sort_unstable();
+        for (rank, kind) in turns {
+            let cost = costs[kind][rank];
+            if cost <= remaining {
+                remaining -= cost;
+                keep[kind][rank] = true;
+            }
+        }
+
+        let [keep_knowledge, keep_dependencies, keep_history] = keep;
+        self.omitted = OmittedContext {
+            knowledge: keep_knowledge.iter().filter(|k| !**k).count(),
+            dependencies: keep_dependencies.iter().filter(|k| !**k).count(),
+            history: keep_history.iter().filter(|k| !**k).count(),
+        };
+        self.knowledge = retain_kept(self.knowledge, &keep_knowledge);
+        self.dependencies = retain_kept(self.dependencies, &keep_dependencies);
+        self.history = retain_kept(self.history, &keep_history);
+        self
+    }
+}
+
+/// Text of a task as it appears in context.
+fn dependency_text(task: &Task) -> String {
+    format!("{} {}", task.title, task.description)
+}
+
+fn retain_kept<T>(items: Vec<T>, keep: &[bool]) -> Vec<T> {
+    items.into_iter().zip(keep).filter(|(_, k)| **k).map(|(item, _)| item).collect()
+}
+
+/// Token budget for context handed to an AI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextBudget {
+    /// Maximum estimated tokens
+    pub max_tokens: usize,
+}
+
+impl ContextBudget {
+    /// Create a budget of `max_tokens`.
+    pub fn new(max_tokens: usize) -> Self {
+        Self { max_tokens }
+    }
+
+    /// Rough token count of `text`: one token per four characters.
+    pub fn estimate_tokens(text: &str) -> usize {
+        text.chars().count().div_ceil(4)
+    }
+}
+
+/// Number of items of each kind left out of a budgeted context.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OmittedContext {
+    /// Knowledge items left out
+    pub knowledge: usize,
+    /// Dependencies left out
+    pub dependencies: usize,
+    /// History events left out
+    pub history: usize,
+}
+
+impl OmittedContext {
+    /// Whether nothing was left out.
+    pub fn is_empty(&self) -> bool {
+        self.knowledge == 0 && self.dependencies == 0 && self.history == 0
+    }
 }
 
 /// A change in the work context.
@@ -102,3 +255,143 @@ pub enum ChangeType {
     Delete,
     Execute,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use devman_core::{
+        Knowledge, KnowledgeContent, KnowledgeId, KnowledgeMetadata, KnowledgeType, TaskContext,
+        TaskIntent, TaskProgress, TaskStatus, UsageStats, WorkEventType,
+    };
+
+    fn knowledge(title: &str, score: f32) -> ScoredKnowledge {
+        let now = chrono::Utc::now();
+        ScoredKnowledge {
+            knowledge: Knowledge {
+                id: KnowledgeId::new(),
+                title: title.to_string(),
+                knowledge_type: KnowledgeType::LessonLearned {
+                    lesson: String::new(),
+                    context: String::new(),
+                },
+                content: KnowledgeContent {
+                    summary: "x".repeat(40),
+                    detail: String::new(),
+                    examples: vec![],
+                    references: vec![],
+                },
+                metadata: KnowledgeMetadata {
+                    domain: vec![],
+                    tech_stack: vec![],
+                    scenarios: vec![],
+                    quality_score: 0.5,
+                    verified: false,
+                },
+                tags: vec![],
+                related_to: vec![],
+                derived_from: vec![],
+                usage_stats: UsageStats {
+                    times_used: 0,
+                    last_used: None,
+                    success_rate: 0.0,
+                    feedback: vec![],
+                },
+                archived: false,
+                created_at: now,
+                updated_at: now,
+            },
+            score,
+        }
+    }
+
+    fn task(title: &str, status: TaskStatus) -> Task {
+        let now = chrono::Utc::now();
+        Task {
+            id: TaskId::new(),
+            title: title.to_string(),
+            description: "y".repeat(40),
+            intent: TaskIntent {
+                natural_language: String::new(),
+                context: TaskContext {
+                    relevant_knowledge: Vec::new(),
+                    similar_tasks: Vec::new(),
+                    affected_files: Vec::new(),
+                },
+                success_criteria: Vec::new(),
+            },
+            steps: Vec::new(),
+            inputs: Vec::new(),
+            expected_outputs: Vec::new(),
+            quality_gates: Vec::new(),
+            status,
+            progress: TaskProgress::default(),
+            phase_id: PhaseId::new(),
+            depends_on: Vec::new(),
+            blocks: Vec::new(),
+            parent: None,
+            children: Vec::new(),
+            archived: false,
+            priority: 0,
+            work_records: Vec::new(),
+            actuals: None,
+            claim: None,
+            state: None,
+            merged_into: None,
+            base_priority: None,
+            due_at: None,
+            created_knowledge: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn event(description: &str, minutes_ago: i64) -> WorkEvent {
+        WorkEvent {
+            timestamp: chrono::Utc::now() - chrono::Duration::minutes(minutes_ago),
+            event_type: WorkEventType::StepCompleted,
+            description: format!("{} {}", description, "z".repeat(40)),
+            data: serde_json::Value::Null,
+        }
+    }
+
+    fn abundant_context() -> WorkManagementContext {
+        WorkManagementContext::new()
+            .with_knowledge((0..10).map(|i| knowledge(&format!("k{}", i), i as f32 / 10.0)).collect())
+            .with_dependencies(vec![
+                task("done", TaskStatus::Done),
+                task("open", TaskStatus::Active),
+                task("queued", TaskStatus::Queued),
+            ])
+            .with_history((0..10).map(|i| event(&format!("e{}", i), i)).collect())
+    }
+
+    #[test]
+    fn test_build_within_budget_keeps_everything() {
+        let context = abundant_context().build(ContextBudget::new(10_000));
+        assert_eq!(context.knowledge.len(), 10);
+        assert_eq!(context.dependencies.len(), 3);
+        assert_eq!(context.history.len(), 10);
+        assert!(context.omitted.is_empty());
+    }
+
+    #[test]
+    fn test_build_trims_to_budget_and_flags_omissions() {
+        let context = abundant_context()
+            .with_active_task(task("active", TaskStatus::Active))
+            .build(ContextBudget::new(80));
+
+        // Active task (~12 tokens) comes off first, leaving room for two
+        // items of each kind at ~11-12 tokens apiece
+        let titles: Vec<_> = context.knowledge.iter().map(|k| k.knowledge.title.as_str()).collect();
+        assert_eq!(titles, ["k9", "k8"]);
+        let mut titles: Vec<_> = context.dependencies.iter().map(|t| t.title.as_str()).collect();
+        titles.sort_unstable();
+        assert_eq!(titles, ["open", "queued"]);
+        assert!(context.history[0].description.starts_with("e0"));
+        assert!(context.history[1].description.starts_with("e1"));
+        assert_eq!(
+            context.omitted,
+            OmittedContext { knowledge: 8, dependencies: 1, history: 8 }
+        );
+    }
+}
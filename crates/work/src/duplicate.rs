@@ -0,0 +1,167 @@
+//! Duplicate-task detection.
+//!
+//! When several agents add work to the same project, near-identical tasks
+//! pile up. [`find_duplicates`] compares a new [`TaskSpec`] against the open
+//! tasks by token overlap of their title and intent, so a creator can be
+//! warned before adding another copy.
+
+use crate::TaskSpec;
+use devman_core::{Task, TaskId, TaskStatus};
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Similarity at or above which an open task counts as a duplicate.
+pub const DEFAULT_DUPLICATE_THRESHOLD: f32 = 0.8;
+
+/// An existing task that looks like the one being created.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DuplicateCandidate {
+    /// Existing task
+    pub task_id: TaskId,
+    /// Its title
+    pub title: String,
+    /// Token overlap with the new task, from 0.0 to 1.0
+    pub similarity: f32,
+}
+
+/// Open tasks similar enough to a new task to be likely duplicates.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DuplicateWarning {
+    /// Threshold the candidates met
+    pub threshold: f32,
+    /// Candidates, most similar first
+    pub candidates: Vec<DuplicateCandidate>,
+}
+
+impl DuplicateWarning {
+    /// IDs of the candidate tasks.
+    pub fn task_ids(&self) -> Vec<TaskId> {
+        self.candidates.iter().map(|c| c.task_id).collect()
+    }
+}
+
+/// Lowercased alphanumeric words of `text`.
+fn tokens(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Words describing what a task is for.
+fn intent_tokens(title: &str, description: &str, intent: &str) -> HashSet<String> {
+    let mut words = tokens(title);
+    words.extend(tokens(description));
+    words.extend(tokens(intent));
+    words
+}
+
+/// Jaccard similarity of two word sets; 0.0 when both are empty.
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f32 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f32 / union as f32
+}
+
+/// Open tasks in `tasks` whose intent overlaps `spec` by at least `threshold`.
+///
+/// Archived, done and abandoned tasks are ignored, since repeating finished
+/// work is a deliberate choice rather than an accident.
+pub fn find_duplicates(spec: &TaskSpec, tasks: &[Task], threshold: f32) -> Option<DuplicateWarning> {
+    let new = intent_tokens(&spec.title, &spec.description, &spec.intent.natural_language);
+
+    let mut candidates: Vec<DuplicateCandidate> = tasks
+        .iter()
+        .filter(|t| !t.archived && !matches!(t.status, TaskStatus::Done | TaskStatus::Abandoned))
+        .filter_map(|t| {
+            let existing = intent_tokens(&t.title, &t.description, &t.intent.natural_language);
+            let similarity = jaccard(&new, &existing);
+            (similarity >= threshold).then(|| DuplicateCandidate {
+                task_id: t.id,
+                title: t.title.clone(),
+                similarity,
+            })
+        })
+        .collect();
+
+    if candidates.is_empty() {
+        return None;
+    }
+    candidates.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    Some(DuplicateWarning { threshold, candidates })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BasicWorkManager, WorkManager};
+    use devman_core::{PhaseId, TaskContext, TaskIntent};
+
+    fn spec(title: &str, description: &str) -> TaskSpec {
+        TaskSpec {
+            title: title.to_string(),
+            description: description.to_string(),
+            intent: TaskIntent {
+                natural_language: description.to_string(),
+                context: TaskContext {
+                    relevant_knowledge: vec![],
+                    similar_tasks: vec![],
+                    affected_files: vec![],
+                },
+                success_criteria: vec![],
+            },
+            phase_id: PhaseId::new(),
+            quality_gates: vec![],
+            priority: Default::default(),
+            quality_profile: None,
+            due_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_near_identical_task_is_flagged() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let mut manager = BasicWorkManager::new(storage);
+        let existing = manager
+            .create_task(spec("Add login page", "Build the login page with email and password"))
+            .await
+            .unwrap();
+        let tasks = vec![existing.clone()];
+
+        let warning = find_duplicates(
+            &spec("Add the login page", "Build the login page with email and password"),
+            &tasks,
+            DEFAULT_DUPLICATE_THRESHOLD,
+        )
+        .expect("near-identical task should be flagged");
+        assert_eq!(warning.task_ids(), vec![existing.id]);
+        assert!(warning.candidates[0].similarity >= DEFAULT_DUPLICATE_THRESHOLD);
+
+        let distinct = find_duplicates(
+            &spec("Fix flaky CI cache", "Pin the cache key to the lockfile hash"),
+            &tasks,
+            DEFAULT_DUPLICATE_THRESHOLD,
+        );
+        assert_eq!(distinct, None);
+    }
+
+    #[tokio::test]
+    async fn test_finished_tasks_are_not_duplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let mut manager = BasicWorkManager::new(storage);
+        let mut task = manager.create_task(spec("Add login page", "")).await.unwrap();
+        let new = spec("Add login page", "");
+
+        task.status = TaskStatus::Done;
+        assert_eq!(find_duplicates(&new, std::slice::from_ref(&task), 0.5), None);
+        task.status = TaskStatus::Active;
+        task.archived = true;
+        assert_eq!(find_duplicates(&new, std::slice::from_ref(&task), 0.5), None);
+        task.archived = false;
+        assert!(find_duplicates(&new, &[task], 0.5).is_some());
+    }
+}
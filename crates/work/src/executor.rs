@@ -25,6 +25,7 @@ pub trait TaskExecutor: Send + Sync {
 pub struct BasicTaskExecutor<S: Storage> {
     storage: std::sync::Arc<tokio::sync::Mutex<S>>,
     tool_executor: std::sync::Arc<dyn devman_tools::ToolExecutor>,
+    max_output_len: usize,
 }
 
 impl<S: Storage> BasicTaskExecutor<S> {
@@ -36,9 +37,16 @@ impl<S: Storage> BasicTaskExecutor<S> {
         Self {
             storage: std::sync::Arc::new(tokio::sync::Mutex::new(storage)),
             tool_executor,
+            max_output_len: devman_core::DEFAULT_MAX_OUTPUT_LEN,
         }
     }
 
+    /// Cap the stdout and stderr kept for each step at `max_len` bytes.
+    pub fn with_max_output_len(mut self, max_len: usize) -> Self {
+        self.max_output_len = max_len;
+        self
+    }
+
     /// Execute task steps.
     async fn execute_steps(
         &self,
@@ -56,18 +64,19 @@ impl<S: Storage> BasicTaskExecutor<S> {
                 env: Default::default(),
                 stdin: None,
                 timeout: Some(std::time::Duration::from_secs(300)),
+                work_dir: None,
             };
 
             let output = self
                 .tool_executor
-                .execute_tool(&step.tool.tool, input)
+                .execute_tool(&step.tool.tool, input, &devman_tools::CancellationToken::new())
                 .await?;
 
             results.push(StepResult {
                 step_order: step.order,
                 success: output.exit_code == 0,
-                output: output.stdout,
-                error: output.stderr,
+                output: devman_core::truncate_output(&output.stdout, self.max_output_len),
+                error: devman_core::truncate_output(&output.stderr, self.max_output_len),
                 duration: step_start.elapsed(),
             });
 
@@ -124,6 +133,7 @@ impl<S: Storage> TaskExecutor for BasicTaskExecutor<S> {
                     quality_checks_run: 0,
                     quality_checks_passed: 0,
                 },
+                created_knowledge: Vec::new(),
             },
             artifacts: Vec::new(),
             issues: Vec::new(),
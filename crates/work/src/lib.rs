@@ -7,7 +7,9 @@
 pub mod manager;
 pub mod context;
 pub mod executor;
+pub mod duplicate;
 
 pub use manager::{WorkManager, TaskSpec, Executor, BasicWorkManager};
-pub use context::WorkManagementContext;
+pub use context::{WorkManagementContext, ContextBudget, OmittedContext};
 pub use executor::TaskExecutor;
+pub use duplicate::{find_duplicates, DuplicateCandidate, DuplicateWarning, DEFAULT_DUPLICATE_THRESHOLD};
@@ -50,6 +50,13 @@ pub struct TaskSpec {
     pub intent: devman_core::TaskIntent,
     pub phase_id: PhaseId,
     pub quality_gates: Vec<QualityGate>,
+    /// Priority level
+    pub priority: devman_core::Priority,
+    /// Profile to take gates from when `quality_gates` is empty, instead of
+    /// the project's default; a profile without checks opts out of gates
+    pub quality_profile: Option<devman_core::QualityProfileId>,
+    /// Deadline that raises the task's priority as it nears
+    pub due_at: Option<devman_core::Time>,
 }
 
 /// Who/what is executing work.
@@ -64,6 +71,7 @@ pub enum Executor {
 pub struct BasicWorkManager<S: Storage> {
     storage: std::sync::Arc<tokio::sync::Mutex<S>>,
     quality_engine: Option<std::sync::Arc<dyn devman_quality::QualityEngine>>,
+    clock: std::sync::Arc<dyn devman_core::Clock>,
 }
 
 impl<S: Storage> BasicWorkManager<S> {
@@ -72,6 +80,7 @@ impl<S: Storage> BasicWorkManager<S> {
         Self {
             storage: std::sync::Arc::new(tokio::sync::Mutex::new(storage)),
             quality_engine: None,
+            clock: std::sync::Arc::new(devman_core::SystemClock),
         }
     }
 
@@ -83,6 +92,111 @@ impl<S: Storage> BasicWorkManager<S> {
         self.quality_engine = Some(engine);
         self
     }
+
+    /// Set the clock used to timestamp tasks and work records.
+    pub fn with_clock(mut self, clock: std::sync::Arc<dyn devman_core::Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Recover work left running by a crash: mark the still-running work
+    /// record of every active task as interrupted and queue the task again.
+    ///
+    /// Returns the requeued tasks.
+    pub async fn recover_interrupted(&self, reason: &str) -> Result<Vec<TaskId>, anyhow::Error> {
+        let mut storage = self.storage.lock().await;
+        let filter = devman_core::TaskFilter {
+            status: Some(vec![devman_core::TaskStatus::Active]),
+            ..Default::default()
+        };
+        let mut requeued = Vec::new();
+        for mut task in storage.list_tasks(&filter).await? {
+            let Some(record_id) = task.work_records.last().copied() else {
+                continue;
+            };
+            let Some(mut record) = storage.load_work_record(record_id).await? else {
+                continue;
+            };
+            if record.result.status != devman_core::CompletionStatus::Running {
+                continue;
+            }
+
+            let now = self.clock.now();
+            record.completed_at = Some(now);
+            record.duration = Some(now - record.started_at);
+            record.result.status = devman_core::CompletionStatus::Interrupted {
+                at: now,
+                reason: reason.to_string(),
+            };
+            storage.save_work_record(&record).await?;
+
+            task.set_status(devman_core::TaskStatus::Queued);
+            task.updated_at = now;
+            storage.save_task(&task).await?;
+            requeued.push(task.id);
+        }
+        Ok(requeued)
+    }
+
+    /// Derive each open task's effective priority from its base priority,
+    /// the open tasks it unblocks and how close its due date is.
+    ///
+    /// Returns `(task, old, new)` for every task whose priority changes.
+    /// With `write_back` the new priorities are saved, keeping the base in
+    /// `base_priority` so the next pass starts from it rather than
+    /// compounding the boosts; otherwise nothing is modified.
+    pub async fn recompute_priorities(&mut self, write_back: bool) -> Result<Vec<(TaskId, u8, u8)>, anyhow::Error> {
+        let mut storage = self.storage.lock().await;
+        let tasks = storage.list_tasks(&devman_core::TaskFilter::default()).await?;
+        let open: Vec<&Task> = tasks.iter().filter(|t| !t.archived && !t.is_closed()).collect();
+        let now = self.clock.now();
+
+        let mut changes = Vec::new();
+        for task in &open {
+            let unblocked = open
+                .iter()
+                .filter(|t| t.depends_on.contains(&task.id) || task.blocks.contains(&t.id))
+                .count();
+            let base = task.base_priority.unwrap_or(task.priority);
+            let effective = effective_priority(base, unblocked, task.due_at, now);
+            if effective == task.priority {
+                continue;
+            }
+            changes.push((task.id, task.priority, effective));
+
+            if write_back {
+                let mut task = (*task).clone();
+                task.base_priority = Some(base);
+                task.priority = effective;
+                task.updated_at = now;
+                storage.save_task(&task).await?;
+            }
+        }
+        if write_back && !changes.is_empty() {
+            storage.commit(&format!("Recompute priorities of {} task(s)", changes.len())).await?;
+        }
+        Ok(changes)
+    }
+}
+
+/// Boost per open task a task unblocks.
+const UNBLOCK_BOOST: u8 = 16;
+/// Most a task can gain from the tasks it unblocks.
+const MAX_UNBLOCK_BOOST: u8 = 64;
+
+/// `base` raised by [`UNBLOCK_BOOST`] per task unblocked, up to
+/// [`MAX_UNBLOCK_BOOST`], and by a boost growing as `due_at` nears: a
+/// level for a week left, two for three days, and four (enough to reach
+/// critical from medium) for the last day or once overdue.
+fn effective_priority(base: u8, unblocked: usize, due_at: Option<devman_core::Time>, now: devman_core::Time) -> u8 {
+    let unblock = (unblocked.min(usize::from(MAX_UNBLOCK_BOOST / UNBLOCK_BOOST)) as u8) * UNBLOCK_BOOST;
+    let deadline = match due_at.map(|due| due - now) {
+        Some(left) if left <= chrono::Duration::days(1) => 128,
+        Some(left) if left <= chrono::Duration::days(3) => 64,
+        Some(left) if left <= chrono::Duration::days(7) => 32,
+        _ => 0,
+    };
+    base.saturating_add(unblock).saturating_add(deadline)
 }
 
 #[async_trait]
@@ -102,9 +216,20 @@ impl<S: Storage + 'static> WorkManager for BasicWorkManager<S> {
             phase_id: spec.phase_id,
             depends_on: Vec::new(),
             blocks: Vec::new(),
+            parent: None,
+            children: Vec::new(),
+            archived: false,
+            priority: spec.priority.value(),
             work_records: Vec::new(),
-            created_at: chrono::Utc::now(),
-            updated_at: chrono::Utc::now(),
+            actuals: None,
+            claim: None,
+            state: None,
+            merged_into: None,
+            base_priority: None,
+            due_at: spec.due_at,
+            created_knowledge: Vec::new(),
+            created_at: self.clock.now(),
+            updated_at: self.clock.now(),
         };
 
         self.storage.lock().await.save_task(&task).await?;
@@ -124,10 +249,7 @@ impl<S: Storage + 'static> WorkManager for BasicWorkManager<S> {
             .await?
             .ok_or_else(|| anyhow::anyhow!("Task not found"))?;
 
-        task.status = devman_core::TaskStatus::Active;
-        self.storage.lock().await.save_task(&task).await?;
-
-        let started_at = chrono::Utc::now();
+        let started_at = self.clock.now();
         let mut work_record = WorkRecord {
             id: devman_core::WorkRecordId::new(),
             task_id,
@@ -150,13 +272,18 @@ impl<S: Storage + 'static> WorkManager for BasicWorkManager<S> {
                     quality_checks_run: 0,
                     quality_checks_passed: 0,
                 },
+                created_knowledge: Vec::new(),
             },
             artifacts: Vec::new(),
             issues: Vec::new(),
             resolutions: Vec::new(),
         };
 
-        self.storage.lock().await.save_work_record(&work_record).await?;
+        task.set_status(devman_core::TaskStatus::Active);
+        task.work_records.push(work_record.id);
+        let mut storage = self.storage.lock().await;
+        storage.save_task(&task).await?;
+        storage.save_work_record(&work_record).await?;
 
         Ok(work_record)
     }
@@ -207,7 +334,7 @@ impl<S: Storage + 'static> WorkManager for BasicWorkManager<S> {
             .ok_or_else(|| anyhow::anyhow!("Task not found"))?;
 
         task.progress = progress;
-        task.updated_at = chrono::Utc::now();
+        task.updated_at = self.clock.now();
         self.storage.lock().await.save_task(&task).await?;
 
         Ok(())
@@ -226,18 +353,22 @@ impl<S: Storage + 'static> WorkManager for BasicWorkManager<S> {
             .await?
             .ok_or_else(|| anyhow::anyhow!("Task not found"))?;
 
-        task.status = match result.status {
+        // Look the record up first so a task without one is left unchanged
+        let record_id = *task.work_records.last().ok_or_else(|| {
+            anyhow::anyhow!("No active work record for task")
+        })?;
+
+        task.set_status(match result.status {
             devman_core::CompletionStatus::Success => devman_core::TaskStatus::Done,
             devman_core::CompletionStatus::Failed => devman_core::TaskStatus::Review,
+            // Interrupted work never reached an outcome; queue it to run again
+            devman_core::CompletionStatus::Interrupted { .. } => devman_core::TaskStatus::Queued,
             _ => devman_core::TaskStatus::Review,
-        };
-        task.updated_at = chrono::Utc::now();
+        });
+        task.updated_at = self.clock.now();
         self.storage.lock().await.save_task(&task).await?;
 
         // Update work record
-        let record_id = *task.work_records.last().ok_or_else(|| {
-            anyhow::anyhow!("No active work record for task")
-        })?;
 
         let mut record = self
             .storage
@@ -247,7 +378,7 @@ impl<S: Storage + 'static> WorkManager for BasicWorkManager<S> {
             .await?
             .ok_or_else(|| anyhow::anyhow!("Work record not found"))?;
 
-        record.completed_at = Some(chrono::Utc::now());
+        record.completed_at = Some(self.clock.now());
         record.duration = Some(record.completed_at.unwrap() - record.started_at);
         record.result = result;
         self.storage.lock().await.save_work_record(&record).await?;
@@ -255,3 +386,153 @@ impl<S: Storage + 'static> WorkManager for BasicWorkManager<S> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use devman_core::{CompletionStatus, TaskContext, TaskIntent, TaskStatus, WorkMetrics};
+
+    fn spec(title: &str) -> TaskSpec {
+        TaskSpec {
+            title: title.to_string(),
+            description: String::new(),
+            intent: TaskIntent {
+                natural_language: title.to_string(),
+                context: TaskContext {
+                    relevant_knowledge: vec![],
+                    similar_tasks: vec![],
+                    affected_files: vec![],
+                },
+                success_criteria: vec![],
+            },
+            phase_id: PhaseId::new(),
+            quality_gates: vec![],
+            priority: Default::default(),
+            quality_profile: None,
+            due_at: None,
+        }
+    }
+
+    fn result(status: CompletionStatus) -> WorkResult {
+        WorkResult {
+            status,
+            outputs: Vec::new(),
+            metrics: WorkMetrics {
+                token_used: None,
+                time_spent: std::time::Duration::ZERO,
+                tools_invoked: 0,
+                quality_checks_run: 0,
+                quality_checks_passed: 0,
+            },
+            created_knowledge: Vec::new(),
+        }
+    }
+
+    fn executor() -> Executor {
+        Executor::AI { model: "test".to_string() }
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_work_requeues_task() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let mut manager = BasicWorkManager::new(storage);
+        let task = manager.create_task(spec("Migrate schema")).await.unwrap();
+        let record = manager.execute_task(task.id, executor()).await.unwrap();
+
+        let interrupted = CompletionStatus::Interrupted {
+            at: chrono::Utc::now(),
+            reason: "cancelled by user".to_string(),
+        };
+        assert!(interrupted.is_interrupted());
+        manager.complete_task(task.id, result(interrupted.clone())).await.unwrap();
+
+        let storage = manager.storage.lock().await;
+        let task = storage.load_task(task.id).await.unwrap().unwrap();
+        assert_eq!(task.status, TaskStatus::Queued);
+        let record = storage.load_work_record(record.id).await.unwrap().unwrap();
+        assert_eq!(record.result.status, interrupted);
+        assert!(record.completed_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_recompute_priorities_boosts_blockers_and_deadlines() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let mut manager = BasicWorkManager::new(storage);
+        let medium = devman_core::Priority::Medium.value();
+
+        let foundation = manager.create_task(spec("Set up schema")).await.unwrap();
+        let mut dependents = Vec::new();
+        for title in ["Users API", "Orders API", "Reports"] {
+            let mut task = manager.create_task(spec(title)).await.unwrap();
+            task.depends_on.push(foundation.id);
+            dependents.push(task);
+        }
+        let mut urgent = manager.create_task(spec("Renew certificate")).await.unwrap();
+        urgent.due_at = Some(chrono::Utc::now() + chrono::Duration::hours(6));
+        let mut later = manager.create_task(spec("Quarterly review")).await.unwrap();
+        later.due_at = Some(chrono::Utc::now() + chrono::Duration::days(30));
+        {
+            let mut storage = manager.storage.lock().await;
+            for task in dependents.iter().chain([&urgent, &later]) {
+                storage.save_task(task).await.unwrap();
+            }
+        }
+
+        // The store lists tasks in no particular order
+        let by_task = |changes: Vec<(TaskId, u8, u8)>| -> std::collections::HashMap<TaskId, (u8, u8)> {
+            changes.into_iter().map(|(id, old, new)| (id, (old, new))).collect()
+        };
+        let preview = by_task(manager.recompute_priorities(false).await.unwrap());
+        assert_eq!(preview, [(foundation.id, (medium, medium + 48)), (urgent.id, (medium, 255))].into());
+        let stored = manager.storage.lock().await.load_task(foundation.id).await.unwrap().unwrap();
+        assert_eq!(stored.priority, medium);
+
+        let changes = by_task(manager.recompute_priorities(true).await.unwrap());
+        assert_eq!(changes, preview);
+        let stored = manager.storage.lock().await.load_task(foundation.id).await.unwrap().unwrap();
+        assert_eq!((stored.priority, stored.base_priority), (medium + 48, Some(medium)));
+
+        // Passes start from the base, so they do not compound
+        assert!(manager.recompute_priorities(true).await.unwrap().is_empty());
+
+        // Once the dependents are done the boost goes away again
+        {
+            let mut storage = manager.storage.lock().await;
+            for task in &mut dependents {
+                task.status = TaskStatus::Done;
+                storage.save_task(task).await.unwrap();
+            }
+        }
+        let changes = manager.recompute_priorities(true).await.unwrap();
+        assert_eq!(changes, vec![(foundation.id, medium + 48, medium)]);
+    }
+
+    #[tokio::test]
+    async fn test_recover_interrupted_requeues_running_work() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = devman_storage::JsonStorage::new(dir.path()).await.unwrap();
+        let mut manager = BasicWorkManager::new(storage);
+        let crashed = manager.create_task(spec("Crashed mid-way")).await.unwrap();
+        let record = manager.execute_task(crashed.id, executor()).await.unwrap();
+        let failed = manager.create_task(spec("Failed properly")).await.unwrap();
+        manager.execute_task(failed.id, executor()).await.unwrap();
+        manager.complete_task(failed.id, result(CompletionStatus::Failed)).await.unwrap();
+
+        let requeued = manager.recover_interrupted("process restarted").await.unwrap();
+        assert_eq!(requeued, vec![crashed.id]);
+        assert!(manager.recover_interrupted("again").await.unwrap().is_empty());
+
+        let storage = manager.storage.lock().await;
+        let task = storage.load_task(crashed.id).await.unwrap().unwrap();
+        assert_eq!(task.status, TaskStatus::Queued);
+        let record = storage.load_work_record(record.id).await.unwrap().unwrap();
+        assert!(matches!(
+            record.result.status,
+            CompletionStatus::Interrupted { ref reason, .. } if reason == "process restarted"
+        ));
+        let failed = storage.load_task(failed.id).await.unwrap().unwrap();
+        assert_eq!(failed.status, TaskStatus::Review);
+    }
+}